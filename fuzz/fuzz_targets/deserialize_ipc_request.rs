@@ -0,0 +1,13 @@
+//! Requests as the service parses them: any bytes from a client must either
+//! fail to deserialize or produce a request that validates without panicking.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use omnirec_types::ipc::Request;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(request) = serde_json::from_slice::<Request>(data) {
+        let _ = request.validate();
+    }
+});
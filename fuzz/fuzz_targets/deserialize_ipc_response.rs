@@ -0,0 +1,14 @@
+//! Responses as the CLI and picker clients parse them: any bytes from the
+//! service must deserialize or fail without panicking, and a parsed response
+//! must serialize again.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use omnirec_types::ipc::Response;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(response) = serde_json::from_slice::<Response>(data) {
+        serde_json::to_vec(&response).expect("parsed response failed to serialize");
+    }
+});
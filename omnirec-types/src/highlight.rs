@@ -0,0 +1,139 @@
+//! Highlight geometry shared by the platform highlight implementations.
+
+use crate::MonitorInfo;
+use serde::{Deserialize, Serialize};
+
+/// Screen area outlined by a capture highlight.
+///
+/// Uses the same coordinate space as [`MonitorInfo`]: virtual screen position
+/// and monitor-sized width/height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightRegion {
+    /// Virtual screen X position
+    pub x: i32,
+    /// Virtual screen Y position
+    pub y: i32,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+}
+
+impl HighlightRegion {
+    /// Intersection with `monitor`, or `None` if they do not overlap.
+    fn intersect(&self, monitor: &MonitorInfo) -> Option<HighlightRegion> {
+        let left = self.x.max(monitor.x);
+        let top = self.y.max(monitor.y);
+        let right =
+            (self.x as i64 + self.width as i64).min(monitor.x as i64 + monitor.width as i64);
+        let bottom =
+            (self.y as i64 + self.height as i64).min(monitor.y as i64 + monitor.height as i64);
+
+        if right <= left as i64 || bottom <= top as i64 {
+            return None;
+        }
+        Some(HighlightRegion {
+            x: left,
+            y: top,
+            width: (right - left as i64) as u32,
+            height: (bottom - top as i64) as u32,
+        })
+    }
+}
+
+/// Split a highlight region into one sub-region per monitor it intersects.
+///
+/// Native highlight windows cannot span monitors reliably, so each platform
+/// creates one window per returned sub-region. Sub-regions are returned in
+/// monitor order. If the region does not intersect any monitor (or the list
+/// is empty) it is returned unchanged.
+pub fn split_highlight_region(
+    region: &HighlightRegion,
+    monitors: &[MonitorInfo],
+) -> Vec<HighlightRegion> {
+    let parts: Vec<HighlightRegion> = monitors
+        .iter()
+        .filter_map(|monitor| region.intersect(monitor))
+        .collect();
+
+    if parts.is_empty() {
+        vec![*region]
+    } else {
+        parts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(id: &str, x: i32, y: i32, width: u32, height: u32) -> MonitorInfo {
+        MonitorInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            x,
+            y,
+            width,
+            height,
+            is_primary: x == 0 && y == 0,
+            scale_factor: 1.0,
+        }
+    }
+
+    fn region(x: i32, y: i32, width: u32, height: u32) -> HighlightRegion {
+        HighlightRegion {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn side_by_side() -> Vec<MonitorInfo> {
+        vec![
+            monitor("left", 0, 0, 1920, 1080),
+            monitor("right", 1920, 0, 1920, 1080),
+        ]
+    }
+
+    #[test]
+    fn test_split_region_spanning_two_monitors() {
+        let parts = split_highlight_region(&region(0, 0, 3840, 1080), &side_by_side());
+        assert_eq!(
+            parts,
+            vec![region(0, 0, 1920, 1080), region(1920, 0, 1920, 1080)]
+        );
+    }
+
+    #[test]
+    fn test_split_region_partially_on_each_monitor() {
+        let parts = split_highlight_region(&region(1520, 100, 800, 600), &side_by_side());
+        assert_eq!(
+            parts,
+            vec![region(1520, 100, 400, 600), region(1920, 100, 400, 600)]
+        );
+    }
+
+    #[test]
+    fn test_region_on_single_monitor_is_unchanged() {
+        let parts = split_highlight_region(&region(2000, 50, 640, 480), &side_by_side());
+        assert_eq!(parts, vec![region(2000, 50, 640, 480)]);
+    }
+
+    #[test]
+    fn test_region_clipped_to_monitors() {
+        // Overhangs the right edge of the desktop
+        let parts = split_highlight_region(&region(3500, 0, 1000, 1080), &side_by_side());
+        assert_eq!(parts, vec![region(3500, 0, 340, 1080)]);
+    }
+
+    #[test]
+    fn test_region_outside_all_monitors_is_unchanged() {
+        let off_screen = region(-5000, -5000, 100, 100);
+        assert_eq!(
+            split_highlight_region(&off_screen, &side_by_side()),
+            vec![off_screen]
+        );
+        assert_eq!(split_highlight_region(&off_screen, &[]), vec![off_screen]);
+    }
+}
@@ -0,0 +1,163 @@
+//! SHA-256 checksums of saved recordings.
+//!
+//! The checksum of `<dir>/<basename>.<ext>` is kept in `<dir>/<basename>.sha256`
+//! in the format written by `sha256sum` (`<hex digest>  <file name>`), so it
+//! can also be checked with `sha256sum -c`.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Files are hashed in chunks of this size to keep memory use flat.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Outcome of checking a recording against its checksum file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityResult {
+    /// Whether the recording matches its checksum
+    pub ok: bool,
+    /// Digest stored in the checksum file, if it could be read
+    pub expected: Option<String>,
+    /// Digest of the recording as it is now, if it could be read
+    pub actual: Option<String>,
+    /// Why the recording could not be verified
+    pub error: Option<String>,
+}
+
+/// Path of the checksum file for `path`.
+pub fn checksum_path(path: &Path) -> PathBuf {
+    path.with_extension("sha256")
+}
+
+/// Hex-encoded SHA-256 digest of the file at `path`.
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Hash `path` and write its checksum file, replacing any existing one.
+/// Returns the digest.
+pub fn write_checksum(path: &Path) -> io::Result<String> {
+    let digest = sha256_file(path)?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    std::fs::write(checksum_path(path), format!("{}  {}\n", digest, name))?;
+    Ok(digest)
+}
+
+/// Read the digest from the checksum file for `path`.
+fn read_checksum(path: &Path) -> Result<String, String> {
+    let checksum_file = checksum_path(path);
+    let contents = std::fs::read_to_string(&checksum_file)
+        .map_err(|e| format!("Cannot read {}: {}", checksum_file.display(), e))?;
+    let digest = contents.split_whitespace().next().unwrap_or_default();
+    if digest.len() != 64 || !digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!(
+            "{} is not a SHA-256 checksum file",
+            checksum_file.display()
+        ));
+    }
+    Ok(digest.to_ascii_lowercase())
+}
+
+/// Recompute the digest of `path` and compare it with its checksum file.
+pub fn verify(path: &Path) -> IntegrityResult {
+    let expected = read_checksum(path);
+    let actual = sha256_file(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e));
+    let error = match (&expected, &actual) {
+        (Err(e), _) | (_, Err(e)) => Some(e.clone()),
+        _ => None,
+    };
+    let expected = expected.ok();
+    let actual = actual.ok();
+    IntegrityResult {
+        ok: error.is_none() && expected == actual,
+        expected,
+        actual,
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::temp_dir;
+
+    #[test]
+    fn test_sha256_file() {
+        let dir = temp_dir("integrity-hash");
+        let path = dir.join("empty.mp4");
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        // Spans several chunks
+        std::fs::write(&path, vec![b'a'; CHUNK_SIZE * 2 + 7]).unwrap();
+        let mut hasher = Sha256::new();
+        hasher.update(vec![b'a'; CHUNK_SIZE * 2 + 7]);
+        let expected: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        assert_eq!(sha256_file(&path).unwrap(), expected);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify_detects_changes() {
+        let dir = temp_dir("integrity-verify");
+        let path = dir.join("recording_2026.mp4");
+        std::fs::write(&path, b"frames").unwrap();
+
+        let digest = write_checksum(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("recording_2026.sha256")).unwrap(),
+            format!("{}  recording_2026.mp4\n", digest)
+        );
+        let result = verify(&path);
+        assert!(result.ok);
+        assert_eq!(result.expected, Some(digest.clone()));
+        assert_eq!(result.actual, Some(digest));
+
+        std::fs::write(&path, b"frameS").unwrap();
+        let result = verify(&path);
+        assert!(!result.ok);
+        assert!(result.error.is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify_without_checksum_file() {
+        let dir = temp_dir("integrity-missing");
+        let path = dir.join("recording.mp4");
+        std::fs::write(&path, b"frames").unwrap();
+        let result = verify(&path);
+        assert!(!result.ok);
+        assert!(result.expected.is_none());
+        assert!(result.error.is_some());
+
+        std::fs::write(checksum_path(&path), "not a checksum\n").unwrap();
+        assert!(verify(&path).error.is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -3,7 +3,11 @@
 mod protocol;
 mod requests;
 mod responses;
+#[cfg(not(target_arch = "wasm32"))]
+mod socket;
 
 pub use protocol::*;
 pub use requests::*;
 pub use responses::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use socket::*;
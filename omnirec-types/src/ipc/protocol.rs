@@ -1,11 +1,79 @@
 //! IPC message framing and transport protocol.
+//!
+//! A versioned client opens the connection with a protocol header
+//! ([`PROTOCOL_MAGIC`] followed by its protocol version) and then sends
+//! [`Request::Hello`](super::Request::Hello) as its first message. Clients
+//! that start directly with a length-prefixed message (such as the portal
+//! picker) are served without negotiation.
+//!
+//! Requests answered with many results (see [`Request::is_streaming`]) get a
+//! streamed response instead of a single message: JSON Lines split into
+//! length-prefixed chunks of up to [`STREAM_CHUNK_SIZE`] bytes and ended by
+//! a zero-length chunk, so neither side holds the whole payload at once.
+//!
+//! [`Request::is_streaming`]: super::Request::is_streaming
 
-use std::path::PathBuf;
+use crate::ipc::Response;
+#[cfg(feature = "native")]
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 /// Maximum IPC message size (64 KB)
 pub const MAX_MESSAGE_SIZE: usize = 65536;
 
+/// Largest chunk of a streamed response.
+pub const STREAM_CHUNK_SIZE: usize = 65536;
+
+/// Largest single item of a streamed response (16 MB).
+pub const MAX_STREAM_ITEM_SIZE: usize = 16 * 1024 * 1024;
+
+/// Protocol version spoken by this build.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Oldest protocol version the service still accepts.
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// First bytes of a versioned connection. Read as a little-endian length
+/// they exceed [`MAX_MESSAGE_SIZE`], so they never look like a message.
+pub const PROTOCOL_MAGIC: [u8; 4] = *b"ORPC";
+
+/// Optional capabilities announced in [`Response::Welcome`].
+pub mod features {
+    /// `DryRunCapture` requests
+    pub const DRY_RUN: &str = "dry_run";
+    /// `StartWorkspaceCapture` requests (Hyprland)
+    pub const WORKSPACE_CAPTURE: &str = "workspace_capture";
+    /// `GetCountdown` requests
+    pub const COUNTDOWN: &str = "countdown";
+    /// `copied_to_clipboard` in `RecordingStopped`
+    pub const CLIPBOARD: &str = "clipboard";
+    /// `SetFrameRateLimit` requests
+    pub const FRAME_RATE_LIMIT: &str = "frame_rate_limit";
+    /// `GetDiagnostics` requests
+    pub const DIAGNOSTICS: &str = "diagnostics";
+    /// `GetThumbnailBatch` requests, answered with a streamed response
+    pub const THUMBNAIL_BATCH: &str = "thumbnail_batch";
+    /// `GetRecentEvents` requests, answered with a streamed response
+    pub const RECENT_EVENTS: &str = "recent_events";
+    /// `TakeScreenshot` requests
+    pub const SCREENSHOT: &str = "screenshot";
+    /// `StartPreviewStream` and `StopPreviewStream` requests
+    pub const PREVIEW_STREAM: &str = "preview_stream";
+
+    /// Every feature this build supports.
+    pub const ALL: &[&str] = &[
+        DRY_RUN,
+        WORKSPACE_CAPTURE,
+        COUNTDOWN,
+        CLIPBOARD,
+        FRAME_RATE_LIMIT,
+        DIAGNOSTICS,
+        THUMBNAIL_BATCH,
+        RECENT_EVENTS,
+        SCREENSHOT,
+        PREVIEW_STREAM,
+    ];
+}
+
 /// Error type for IPC operations.
 #[derive(Debug)]
 pub enum IpcError {
@@ -44,26 +112,69 @@ impl From<std::io::Error> for IpcError {
     }
 }
 
-/// Get the platform-specific socket path for the IPC connection.
-pub fn get_socket_path() -> PathBuf {
-    #[cfg(target_os = "linux")]
-    {
-        let runtime_dir = std::env::var("XDG_RUNTIME_DIR")
-            .unwrap_or_else(|_| format!("/run/user/{}", unsafe { libc::getuid() }));
-        PathBuf::from(runtime_dir)
-            .join("omnirec")
-            .join("service.sock")
-    }
+/// How a client opened its connection.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ConnectionStart {
+    /// Protocol header with the client's protocol version
+    Versioned(u32),
+    /// No header; carries the payload of the client's first message
+    Unversioned(Vec<u8>),
+}
+
+/// Write the protocol header that opens a versioned connection.
+#[cfg(feature = "native")]
+pub async fn write_protocol_header<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    version: u32,
+) -> Result<(), IpcError> {
+    let mut header = [0u8; 8];
+    header[..4].copy_from_slice(&PROTOCOL_MAGIC);
+    header[4..].copy_from_slice(&version.to_le_bytes());
+    writer.write_all(&header).await?;
+    writer.flush().await?;
+    Ok(())
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        let tmpdir = std::env::var("TMPDIR").unwrap_or_else(|_| "/tmp".to_string());
-        PathBuf::from(tmpdir).join("omnirec").join("service.sock")
+/// Read the start of a connection: either the protocol header or, for
+/// unversioned clients, their first length-prefixed message.
+#[cfg(feature = "native")]
+pub async fn read_connection_start<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> Result<ConnectionStart, IpcError> {
+    read_connection_start_with_limit(reader, MAX_MESSAGE_SIZE).await
+}
+
+/// [`read_connection_start`] with a first message of at most `max` bytes.
+#[cfg(feature = "native")]
+pub async fn read_connection_start_with_limit<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    max: usize,
+) -> Result<ConnectionStart, IpcError> {
+    let mut prefix = [0u8; 4];
+    reader.read_exact(&mut prefix).await?;
+    if prefix == PROTOCOL_MAGIC {
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version).await?;
+        return Ok(ConnectionStart::Versioned(u32::from_le_bytes(version)));
     }
+    read_payload(reader, prefix, max)
+        .await
+        .map(ConnectionStart::Unversioned)
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        PathBuf::from(r"\\.\pipe\omnirec-service")
+/// Answer a client's `Hello`: [`Response::Welcome`] if the client's
+/// protocol range overlaps ours, [`Response::IncompatibleVersion`] otherwise.
+pub fn negotiate(client_version: u32, min_protocol: u32) -> Response {
+    if client_version < MIN_PROTOCOL_VERSION || min_protocol > PROTOCOL_VERSION {
+        Response::IncompatibleVersion {
+            min: MIN_PROTOCOL_VERSION,
+            max: PROTOCOL_VERSION,
+        }
+    } else {
+        Response::Welcome {
+            server_version: PROTOCOL_VERSION,
+            supported_features: features::ALL.iter().map(|f| f.to_string()).collect(),
+        }
     }
 }
 
@@ -76,18 +187,35 @@ pub fn get_socket_path() -> PathBuf {
 /// │ Little-endian    │ Max 65,536 bytes                │
 /// └──────────────────┴─────────────────────────────────┘
 /// ```
+#[cfg(feature = "native")]
 pub async fn read_message<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Vec<u8>, IpcError> {
+    read_message_with_limit(reader, MAX_MESSAGE_SIZE).await
+}
+
+/// [`read_message`] with a payload of at most `max` bytes.
+#[cfg(feature = "native")]
+pub async fn read_message_with_limit<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    max: usize,
+) -> Result<Vec<u8>, IpcError> {
     // Read 4-byte length prefix
     let mut len_buf = [0u8; 4];
     reader.read_exact(&mut len_buf).await?;
+    read_payload(reader, len_buf, max).await
+}
+
+/// Read the payload of a message whose length prefix was already read.
+#[cfg(feature = "native")]
+async fn read_payload<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    len_buf: [u8; 4],
+    max: usize,
+) -> Result<Vec<u8>, IpcError> {
     let len = u32::from_le_bytes(len_buf) as usize;
 
     // Validate size BEFORE allocating
-    if len > MAX_MESSAGE_SIZE {
-        return Err(IpcError::MessageTooLarge {
-            size: len,
-            max: MAX_MESSAGE_SIZE,
-        });
+    if len > max {
+        return Err(IpcError::MessageTooLarge { size: len, max });
     }
 
     // Read payload
@@ -98,6 +226,7 @@ pub async fn read_message<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Vec<u8
 }
 
 /// Write a length-prefixed message.
+#[cfg(feature = "native")]
 pub async fn write_message<W: AsyncWrite + Unpin>(
     writer: &mut W,
     data: &[u8],
@@ -121,14 +250,25 @@ pub async fn write_message<W: AsyncWrite + Unpin>(
 }
 
 /// Read and deserialize a JSON message.
+#[cfg(feature = "native")]
 pub async fn read_json<R: AsyncRead + Unpin, T: serde::de::DeserializeOwned>(
     reader: &mut R,
 ) -> Result<T, IpcError> {
-    let data = read_message(reader).await?;
+    read_json_with_limit(reader, MAX_MESSAGE_SIZE).await
+}
+
+/// [`read_json`] with a message of at most `max` bytes.
+#[cfg(feature = "native")]
+pub async fn read_json_with_limit<R: AsyncRead + Unpin, T: serde::de::DeserializeOwned>(
+    reader: &mut R,
+    max: usize,
+) -> Result<T, IpcError> {
+    let data = read_message_with_limit(reader, max).await?;
     serde_json::from_slice(&data).map_err(|e| IpcError::ParseError(e.to_string()))
 }
 
 /// Serialize and write a JSON message.
+#[cfg(feature = "native")]
 pub async fn write_json<W: AsyncWrite + Unpin, T: serde::Serialize>(
     writer: &mut W,
     value: &T,
@@ -137,6 +277,127 @@ pub async fn write_json<W: AsyncWrite + Unpin, T: serde::Serialize>(
     write_message(writer, &data).await
 }
 
+/// Writer for a streamed response.
+///
+/// Each item is sent and flushed as it is written, split into chunks if it
+/// is larger than one; [`finish`](Self::finish) sends the terminating
+/// zero-length chunk.
+#[cfg(feature = "native")]
+pub struct StreamWriter<'a, W> {
+    writer: &'a mut W,
+    buffer: Vec<u8>,
+}
+
+#[cfg(feature = "native")]
+impl<'a, W: AsyncWrite + Unpin> StreamWriter<'a, W> {
+    /// Start a streamed response on `writer`.
+    pub fn new(writer: &'a mut W) -> Self {
+        Self {
+            writer,
+            buffer: Vec::with_capacity(STREAM_CHUNK_SIZE),
+        }
+    }
+
+    /// Serialize and send one item, so the reader gets it without waiting
+    /// for the items after it.
+    pub async fn write_item<T: serde::Serialize>(&mut self, item: &T) -> Result<(), IpcError> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        serde_json::to_writer(&mut buffer, item)
+            .map_err(|e| IpcError::ParseError(e.to_string()))?;
+        buffer.push(b'\n');
+
+        for chunk in buffer.chunks(STREAM_CHUNK_SIZE) {
+            self.write_chunk(chunk).await?;
+        }
+        self.buffer = buffer;
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    /// End the stream.
+    pub async fn finish(mut self) -> Result<(), IpcError> {
+        self.write_chunk(&[]).await?;
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    async fn write_chunk(&mut self, chunk: &[u8]) -> Result<(), IpcError> {
+        self.writer
+            .write_all(&(chunk.len() as u32).to_le_bytes())
+            .await?;
+        self.writer.write_all(chunk).await?;
+        Ok(())
+    }
+}
+
+/// Reader for a streamed response, yielding items as their chunks arrive.
+#[cfg(feature = "native")]
+pub struct StreamReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+#[cfg(feature = "native")]
+impl<R: AsyncRead + Unpin> StreamReader<R> {
+    /// Read a streamed response from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// The next item, or `None` once the terminating chunk was read.
+    pub async fn next_item<T: serde::de::DeserializeOwned>(
+        &mut self,
+    ) -> Result<Option<T>, IpcError> {
+        loop {
+            if let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=end).collect();
+                return serde_json::from_slice(&line[..end])
+                    .map(Some)
+                    .map_err(|e| IpcError::ParseError(e.to_string()));
+            }
+            if self.finished {
+                return if self.buffer.is_empty() {
+                    Ok(None)
+                } else {
+                    Err(IpcError::ParseError(
+                        "Stream ended inside an item".to_string(),
+                    ))
+                };
+            }
+
+            let mut len_buf = [0u8; 4];
+            self.reader.read_exact(&mut len_buf).await?;
+            let len = u32::from_le_bytes(len_buf) as usize;
+            if len == 0 {
+                self.finished = true;
+                continue;
+            }
+            if len > STREAM_CHUNK_SIZE {
+                return Err(IpcError::MessageTooLarge {
+                    size: len,
+                    max: STREAM_CHUNK_SIZE,
+                });
+            }
+            if self.buffer.len() + len > MAX_STREAM_ITEM_SIZE {
+                return Err(IpcError::MessageTooLarge {
+                    size: self.buffer.len() + len,
+                    max: MAX_STREAM_ITEM_SIZE,
+                });
+            }
+
+            let start = self.buffer.len();
+            self.buffer.resize(start + len, 0);
+            self.reader.read_exact(&mut self.buffer[start..]).await?;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,4 +426,125 @@ mod tests {
         let result = write_message(&mut buf, &oversized).await;
         assert!(matches!(result, Err(IpcError::MessageTooLarge { .. })));
     }
+
+    #[tokio::test]
+    async fn test_read_rejects_message_over_limit() {
+        let mut buf = Vec::new();
+        write_message(&mut buf, &[b'x'; 100]).await.unwrap();
+
+        let result = read_message_with_limit(&mut Cursor::new(&buf), 99).await;
+        assert!(matches!(
+            result,
+            Err(IpcError::MessageTooLarge { size: 100, max: 99 })
+        ));
+        let read = read_message_with_limit(&mut Cursor::new(&buf), 100).await;
+        assert_eq!(read.unwrap().len(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_stream_roundtrip_splits_large_items() {
+        let large = "x".repeat(STREAM_CHUNK_SIZE * 2);
+        let mut buf = Vec::new();
+        let mut writer = StreamWriter::new(&mut buf);
+        writer.write_item(&"small").await.unwrap();
+        writer.write_item(&large).await.unwrap();
+        writer.write_item(&"last").await.unwrap();
+        writer.finish().await.unwrap();
+
+        let mut reader = StreamReader::new(Cursor::new(buf));
+        assert_eq!(
+            reader.next_item::<String>().await.unwrap().unwrap(),
+            "small"
+        );
+        assert_eq!(reader.next_item::<String>().await.unwrap().unwrap(), large);
+        assert_eq!(reader.next_item::<String>().await.unwrap().unwrap(), "last");
+        assert!(reader.next_item::<String>().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_items_are_sent_as_written() {
+        let mut buf = Vec::new();
+        let mut writer = StreamWriter::new(&mut buf);
+        writer.write_item(&"first").await.unwrap();
+        drop(writer);
+
+        // Readable before the stream is finished
+        let mut reader = StreamReader::new(Cursor::new(buf));
+        assert_eq!(
+            reader.next_item::<String>().await.unwrap().unwrap(),
+            "first"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_rejects_oversized_chunk() {
+        let mut buf = ((STREAM_CHUNK_SIZE + 1) as u32).to_le_bytes().to_vec();
+        buf.extend(vec![b'x'; STREAM_CHUNK_SIZE + 1]);
+
+        let mut reader = StreamReader::new(Cursor::new(buf));
+        let result = reader.next_item::<String>().await;
+        assert!(matches!(result, Err(IpcError::MessageTooLarge { .. })));
+    }
+
+    #[test]
+    fn test_magic_is_not_a_valid_length() {
+        assert!(u32::from_le_bytes(PROTOCOL_MAGIC) as usize > MAX_MESSAGE_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_connection_start_versioned() {
+        let mut buf = Vec::new();
+        write_protocol_header(&mut buf, PROTOCOL_VERSION)
+            .await
+            .unwrap();
+        write_message(&mut buf, b"{}").await.unwrap();
+
+        let mut cursor = Cursor::new(buf);
+        assert_eq!(
+            read_connection_start(&mut cursor).await.unwrap(),
+            ConnectionStart::Versioned(PROTOCOL_VERSION)
+        );
+        assert_eq!(read_message(&mut cursor).await.unwrap(), b"{}");
+    }
+
+    #[tokio::test]
+    async fn test_connection_start_unversioned() {
+        let mut buf = Vec::new();
+        write_message(&mut buf, br#"{"type":"ping"}"#)
+            .await
+            .unwrap();
+
+        let mut cursor = Cursor::new(buf);
+        assert_eq!(
+            read_connection_start(&mut cursor).await.unwrap(),
+            ConnectionStart::Unversioned(br#"{"type":"ping"}"#.to_vec())
+        );
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert!(matches!(
+            negotiate(PROTOCOL_VERSION, PROTOCOL_VERSION),
+            Response::Welcome { server_version: PROTOCOL_VERSION, ref supported_features }
+                if supported_features.iter().any(|f| f == features::COUNTDOWN)
+        ));
+        // A newer client that can still speak our version
+        assert!(matches!(
+            negotiate(PROTOCOL_VERSION + 1, PROTOCOL_VERSION),
+            Response::Welcome { .. }
+        ));
+        // A client that requires a newer protocol than ours
+        assert!(matches!(
+            negotiate(PROTOCOL_VERSION + 1, PROTOCOL_VERSION + 1),
+            Response::IncompatibleVersion {
+                min: MIN_PROTOCOL_VERSION,
+                max: PROTOCOL_VERSION
+            }
+        ));
+        // A client older than we support
+        assert!(matches!(
+            negotiate(MIN_PROTOCOL_VERSION - 1, 0),
+            Response::IncompatibleVersion { .. }
+        ));
+    }
 }
@@ -6,15 +6,26 @@ fn default_agc_noise_gate_enabled() -> bool {
     true
 }
 
+use crate::ipc::SelectionGeometry;
 use crate::security::validation::{
-    validate_coordinates, validate_dimensions, validate_monitor_id, validate_source_id,
-    validate_window_handle, ValidationError,
+    validate_coordinates, validate_dimensions, validate_frame_rate, validate_monitor_id,
+    validate_preview_fps, validate_recent_events, validate_selection, validate_source_id,
+    validate_thumbnail_batch, validate_window_handle, ValidationError,
 };
+use crate::types::ScreenshotTarget;
 
 /// IPC request from client to service.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Request {
+    // === Handshake ===
+    /// First message of a versioned connection: the client's protocol
+    /// version and the oldest server protocol it can work with
+    Hello {
+        client_version: u32,
+        min_protocol: u32,
+    },
+
     // === Enumeration ===
     /// List all capturable windows
     ListWindows,
@@ -42,6 +53,11 @@ pub enum Request {
     },
     /// Start portal-based capture (GNOME mode)
     StartPortalCapture,
+    /// Start capture of the monitor showing a Hyprland workspace
+    StartWorkspaceCapture { workspace_id: i32 },
+    /// Pre-flight check: capture a few frames from the target described by
+    /// the inner `Start*Capture` request and encode them to a null sink.
+    DryRunCapture { capture: Box<Request> },
     /// Stop current recording
     StopRecording,
 
@@ -50,13 +66,15 @@ pub enum Request {
     GetRecordingState,
     /// Get elapsed recording time
     GetElapsedTime,
+    /// Get the configured pre-recording countdown
+    GetCountdown,
     /// Subscribe to real-time events
     SubscribeEvents,
 
     // === Configuration ===
-    /// Get current output format
+    /// Get the output format of the next recording
     GetOutputFormat,
-    /// Set output format
+    /// Set the output format of the next recording
     SetOutputFormat { format: String },
     /// Get audio configuration
     GetAudioConfig,
@@ -71,12 +89,22 @@ pub enum Request {
         #[serde(default = "default_agc_noise_gate_enabled")]
         agc_noise_gate_enabled: bool,
     },
+    /// Limit the capture frame rate of the next recording (`None` for no limit)
+    SetFrameRateLimit { max_fps: Option<u32> },
 
     // === Thumbnails ===
     /// Get window thumbnail
     GetWindowThumbnail { window_handle: isize },
     /// Get display thumbnail
     GetDisplayThumbnail { monitor_id: String },
+    /// Get thumbnails of several windows and displays, streamed back as a
+    /// `BatchThumbnail` per source as each is captured
+    GetThumbnailBatch {
+        #[serde(default)]
+        window_handles: Vec<isize>,
+        #[serde(default)]
+        monitor_ids: Vec<String>,
+    },
     /// Get region preview
     GetRegionPreview {
         monitor_id: String,
@@ -85,6 +113,19 @@ pub enum Request {
         width: u32,
         height: u32,
     },
+    /// Broadcast preview frames of the recording at up to `fps` frames per
+    /// second, scaled to fit 240x135, until `StopPreviewStream`
+    StartPreviewStream { fps: u8 },
+    /// Stop the preview stream
+    StopPreviewStream,
+
+    // === Screenshots ===
+    /// Save a full-resolution PNG of `target` (the primary display if
+    /// `None`) to the output directory
+    TakeScreenshot {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        target: Option<ScreenshotTarget>,
+    },
 
     // === Highlights ===
     /// Show display highlight
@@ -106,6 +147,20 @@ pub enum Request {
     ValidateToken { token: String },
     /// Store approval token
     StoreToken { token: String },
+    /// List stored approval tokens
+    ListApprovalTokens,
+    /// Revoke a stored approval token
+    RevokeApprovalToken { token_id: String },
+    /// Pre-select the source for the next portal request (picker protocol)
+    SetSelection {
+        /// "monitor", "window", or "region"
+        source_type: String,
+        /// Monitor name or window address
+        source_id: String,
+        /// Region geometry (required for "region")
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        geometry: Option<SelectionGeometry>,
+    },
 
     // === Transcription ===
     /// Get transcription configuration
@@ -126,6 +181,16 @@ pub enum Request {
     GetTranscriptionSegments { since_index: u32 },
 
     // === Service Control ===
+    /// Report the service's setup for `omnirec doctor`
+    GetDiagnostics,
+    /// Get the service's most recent log messages, oldest first, streamed
+    /// back as a `LogEvent` each. With `level`, only messages at least that
+    /// severe are included.
+    GetRecentEvents {
+        count: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        level: Option<String>,
+    },
     /// Request service shutdown
     Shutdown,
     /// Ping for health check
@@ -133,6 +198,16 @@ pub enum Request {
 }
 
 impl Request {
+    /// Whether the response to this request is streamed (see
+    /// [`StreamReader`](crate::ipc::StreamReader)) rather than sent as one
+    /// message.
+    pub fn is_streaming(&self) -> bool {
+        matches!(
+            self,
+            Request::GetThumbnailBatch { .. } | Request::GetRecentEvents { .. }
+        )
+    }
+
     /// Validate all parameters in this request.
     ///
     /// Returns Ok(()) if all parameters are valid, or an error describing
@@ -161,12 +236,30 @@ impl Request {
                 validate_coordinates(*x, *y)?;
                 validate_dimensions(*width, *height)?;
             }
+            Request::DryRunCapture { capture } => {
+                capture.validate()?;
+            }
             Request::GetWindowThumbnail { window_handle } => {
                 validate_window_handle(*window_handle)?;
             }
             Request::GetDisplayThumbnail { monitor_id } => {
                 validate_monitor_id(monitor_id)?;
             }
+            Request::GetThumbnailBatch {
+                window_handles,
+                monitor_ids,
+            } => {
+                validate_thumbnail_batch(window_handles.len() + monitor_ids.len())?;
+                for handle in window_handles {
+                    validate_window_handle(*handle)?;
+                }
+                for id in monitor_ids {
+                    validate_monitor_id(id)?;
+                }
+            }
+            Request::GetRecentEvents { count, level } => {
+                validate_recent_events(*count, level.as_deref())?;
+            }
             Request::GetRegionPreview {
                 monitor_id,
                 x,
@@ -178,6 +271,16 @@ impl Request {
                 validate_coordinates(*x, *y)?;
                 validate_dimensions(*width, *height)?;
             }
+            Request::TakeScreenshot {
+                target: Some(ScreenshotTarget::Window { window_handle }),
+            } => {
+                validate_window_handle(*window_handle)?;
+            }
+            Request::TakeScreenshot {
+                target: Some(ScreenshotTarget::Display { monitor_id }),
+            } => {
+                validate_monitor_id(monitor_id)?;
+            }
             Request::ShowDisplayHighlight { x, y, .. } => {
                 // Width/height can be negative for highlight bounds
                 validate_coordinates(*x, *y)?;
@@ -185,6 +288,17 @@ impl Request {
             Request::ShowWindowHighlight { window_handle } => {
                 validate_window_handle(*window_handle)?;
             }
+            Request::SetSelection {
+                source_type,
+                source_id,
+                geometry,
+            } => {
+                validate_selection(source_type, source_id, geometry.is_some())?;
+                if let Some(geometry) = geometry {
+                    validate_coordinates(geometry.x, geometry.y)?;
+                    validate_dimensions(geometry.width, geometry.height)?;
+                }
+            }
             Request::SetAudioConfig {
                 source_id,
                 microphone_id,
@@ -197,6 +311,12 @@ impl Request {
                     validate_source_id(id)?;
                 }
             }
+            Request::SetFrameRateLimit { max_fps: Some(fps) } => {
+                validate_frame_rate(*fps)?;
+            }
+            Request::StartPreviewStream { fps } => {
+                validate_preview_fps(*fps)?;
+            }
             // Other requests have no parameters to validate
             _ => {}
         }
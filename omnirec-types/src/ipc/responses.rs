@@ -3,8 +3,8 @@
 use serde::{Deserialize, Serialize};
 
 use crate::types::{
-    AudioConfig, AudioSource, MonitorInfo, RecordingState, TranscriptionConfig,
-    TranscriptionSegment, TranscriptionStatus, WindowInfo,
+    ApprovalTokenInfo, AudioConfig, AudioSource, LogEvent, MonitorInfo, RecordingState,
+    TranscriptionConfig, TranscriptionSegment, TranscriptionStatus, WindowInfo,
 };
 
 /// Geometry for region selection (picker compatibility).
@@ -20,6 +20,16 @@ pub struct SelectionGeometry {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Response {
+    // === Handshake Responses ===
+    /// Handshake accepted
+    Welcome {
+        server_version: u32,
+        /// Optional capabilities the service supports (see `ipc::features`)
+        supported_features: Vec<String>,
+    },
+    /// Handshake rejected; the service speaks protocol versions `min..=max`
+    IncompatibleVersion { min: u32, max: u32 },
+
     // === Success Responses ===
     /// List of windows
     Windows { windows: Vec<WindowInfo> },
@@ -36,11 +46,23 @@ pub enum Response {
     RecordingStopped {
         file_path: String,
         source_path: String,
+        /// Whether `file_path` was copied to the clipboard
+        #[serde(default)]
+        copied_to_clipboard: bool,
     },
     /// Elapsed time in seconds
     ElapsedTime { seconds: u64 },
+    /// Pre-recording countdown in seconds (0 = none)
+    Countdown { seconds: u8 },
+    /// Outcome of a dry-run capture
+    DryRunResult {
+        frames_captured: u32,
+        encoder_ok: bool,
+        audio_ok: bool,
+    },
 
-    /// Current output format
+    /// Output format of the next recording, or `"auto"` if smart format
+    /// will choose it when the recording stops
     OutputFormat { format: String },
     /// Current audio configuration
     AudioConfig(AudioConfig),
@@ -62,6 +84,15 @@ pub enum Response {
         width: u32,
         height: u32,
     },
+    /// One source's thumbnail in a `GetThumbnailBatch` stream: the window
+    /// handle or monitor ID it shows, and the image or why it failed
+    BatchThumbnail {
+        source_id: String,
+        data: Option<String>,
+        width: u32,
+        height: u32,
+        error: Option<String>,
+    },
 
     /// Subscribed to events
     Subscribed,
@@ -69,6 +100,21 @@ pub enum Response {
     Ok,
     /// Pong response to ping
     Pong,
+    /// Service setup for `omnirec doctor`
+    Diagnostics {
+        /// FFmpeg binary the service runs
+        ffmpeg_path: String,
+        /// First line of `ffmpeg -version`; `None` if it could not be run
+        ffmpeg_version: Option<String>,
+        /// Screen recording permission (macOS only)
+        screen_recording_permission: Option<bool>,
+        /// Directory recordings are saved to; `None` if it can't be determined
+        output_directory: Option<String>,
+    },
+    /// One message in a `GetRecentEvents` stream
+    LogEvent(LogEvent),
+    /// Screenshot saved to `path`
+    ScreenshotSaved { path: String },
 
     // === Selection Responses (Picker Compatibility) ===
     /// Current selection info
@@ -87,6 +133,8 @@ pub enum Response {
     TokenInvalid,
     /// Token stored successfully
     TokenStored,
+    /// Stored approval tokens
+    ApprovalTokens { tokens: Vec<ApprovalTokenInfo> },
 
     // === Error Response ===
     /// Error occurred (message is sanitized - no internal details)
@@ -138,3 +186,53 @@ impl Response {
         matches!(self, Response::Error { .. })
     }
 }
+
+/// Format an error followed by each of its `source()` causes, separated by
+/// `": "` (the same layout as anyhow's `{:#}`).
+pub fn format_error_chain(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct Layer(&'static str, Option<Box<Layer>>);
+
+    impl fmt::Display for Layer {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl std::error::Error for Layer {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            self.1.as_deref().map(|e| e as _)
+        }
+    }
+
+    #[test]
+    fn test_format_error_chain() {
+        let err = Layer(
+            "capture failed",
+            Some(Box::new(Layer(
+                "portal request failed",
+                Some(Box::new(Layer("timed out", None))),
+            ))),
+        );
+        assert_eq!(
+            format_error_chain(&err),
+            "capture failed: portal request failed: timed out"
+        );
+        assert_eq!(format_error_chain(&Layer("single", None)), "single");
+    }
+}
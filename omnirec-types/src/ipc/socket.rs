@@ -0,0 +1,153 @@
+//! Location of the service socket (Unix) or named pipe (Windows).
+//!
+//! Each desktop session gets its own service instance, so the default name
+//! includes the [`session_id`]. Not available on wasm32, which has neither.
+
+use std::path::PathBuf;
+
+/// Environment variable (and app flag `--socket`) naming the IPC socket or
+/// named pipe explicitly, instead of the session's default.
+pub const SOCKET_PATH_ENV: &str = "OMNIREC_SOCKET";
+
+/// Identifier of the desktop session this process runs in, so that each
+/// session of a user (e.g. a Hyprland session and a VNC session) gets its
+/// own service instance.
+///
+/// The Wayland display on Linux, the security session on macOS, and the
+/// Terminal Services session on Windows. `None` when not known.
+pub fn session_id() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    let id = std::env::var("WAYLAND_DISPLAY").ok();
+    #[cfg(target_os = "macos")]
+    let id = std::env::var("SECURITYSESSIONID").ok();
+    #[cfg(target_os = "windows")]
+    let id = windows_session_id().map(|id| id.to_string());
+
+    id.as_deref().and_then(sanitize_session_id)
+}
+
+/// Session of the current process, which differs between the console and
+/// Remote Desktop sessions.
+#[cfg(target_os = "windows")]
+fn windows_session_id() -> Option<u32> {
+    use windows::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+    use windows::Win32::System::Threading::GetCurrentProcessId;
+
+    let mut session = 0u32;
+    unsafe { ProcessIdToSessionId(GetCurrentProcessId(), &mut session) }.ok()?;
+    Some(session)
+}
+
+/// Make a session identifier safe for a file or pipe name.
+fn sanitize_session_id(id: &str) -> Option<String> {
+    // WAYLAND_DISPLAY may be an absolute socket path
+    let name = id.rsplit('/').next().unwrap_or(id);
+    if name.is_empty() {
+        return None;
+    }
+    Some(
+        name.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect(),
+    )
+}
+
+/// File or pipe name of the service socket for `session`.
+fn socket_name(session: Option<&str>) -> String {
+    #[cfg(unix)]
+    let (prefix, suffix) = ("service", ".sock");
+    #[cfg(windows)]
+    let (prefix, suffix) = ("omnirec-service", "");
+
+    match session {
+        Some(session) => format!("{}-{}{}", prefix, session, suffix),
+        None => format!("{}{}", prefix, suffix),
+    }
+}
+
+/// Get the platform-specific socket path for the IPC connection.
+///
+/// `OMNIREC_SOCKET` takes precedence; otherwise the name includes the
+/// [`session_id`].
+pub fn get_socket_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(SOCKET_PATH_ENV).filter(|path| !path.is_empty()) {
+        return PathBuf::from(path);
+    }
+    let name = socket_name(session_id().as_deref());
+
+    #[cfg(target_os = "linux")]
+    {
+        let runtime_dir = std::env::var("XDG_RUNTIME_DIR")
+            .unwrap_or_else(|_| format!("/run/user/{}", unsafe { libc::getuid() }));
+        PathBuf::from(runtime_dir).join("omnirec").join(name)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let tmpdir = std::env::var("TMPDIR").unwrap_or_else(|_| "/tmp".to_string());
+        PathBuf::from(tmpdir).join("omnirec").join(name)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        PathBuf::from(format!(r"\\.\pipe\{}", name))
+    }
+}
+
+/// Socket of the CLI connection proxy (`omnirec-proxy`) for this session,
+/// in the user's data directory (`~/.local/share/omnirec` on Linux).
+#[cfg(unix)]
+pub fn get_proxy_socket_path() -> Option<PathBuf> {
+    let dirs = directories::ProjectDirs::from("", "", "omnirec")?;
+    Some(
+        dirs.data_dir()
+            .join(proxy_socket_name(session_id().as_deref())),
+    )
+}
+
+/// File name of the CLI proxy socket for `session`.
+#[cfg(unix)]
+fn proxy_socket_name(session: Option<&str>) -> String {
+    match session {
+        Some(session) => format!("cli-session-{}.sock", session),
+        None => "cli-session.sock".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_id_is_sanitized() {
+        assert_eq!(
+            sanitize_session_id("wayland-1").as_deref(),
+            Some("wayland-1")
+        );
+        assert_eq!(
+            sanitize_session_id("/run/user/1000/wayland-0").as_deref(),
+            Some("wayland-0")
+        );
+        assert_eq!(sanitize_session_id("a b:c").as_deref(), Some("a_b_c"));
+        assert_eq!(sanitize_session_id(""), None);
+        assert_eq!(sanitize_session_id("/tmp/"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_socket_name_includes_session() {
+        assert_eq!(socket_name(Some("wayland-1")), "service-wayland-1.sock");
+        assert_eq!(socket_name(None), "service.sock");
+        assert_eq!(
+            proxy_socket_name(Some("wayland-1")),
+            "cli-session-wayland-1.sock"
+        );
+        assert_eq!(proxy_socket_name(None), "cli-session.sock");
+    }
+}
@@ -1,11 +1,23 @@
 //! OmniRec Types Library
 //!
-//! OmniRec-specific types, IPC protocol, logging, and security utilities
-//! shared between the Tauri backend (src-tauri) and CLI (src-cli).
+//! OmniRec-specific types, IPC protocol, logging, recording checksums, region
+//! geometry and security utilities shared between the Tauri backend
+//! (src-tauri) and CLI (src-cli).
 
+pub mod highlight;
+pub mod integrity;
 pub mod ipc;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod logging;
+pub mod region;
 pub mod security;
+#[cfg(test)]
+mod test_utils;
 pub mod types;
 
+pub use highlight::{split_highlight_region, HighlightRegion};
+pub use region::{
+    constrain_to_aspect, find_snap_target, logical_to_physical_region, physical_to_logical_region,
+    SnapResult, DEFAULT_SNAP_THRESHOLD_PX, PRESET_ASPECT_RATIOS,
+};
 pub use types::*;
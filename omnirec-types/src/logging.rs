@@ -1,6 +1,7 @@
 //! Platform-specific logging directory resolution.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 /// Returns the platform-appropriate directory for log files.
 ///
@@ -15,6 +16,7 @@ pub fn log_dir() -> PathBuf {
         let base = directories::ProjectDirs::from("", "", "omnirec")
             .expect("Failed to determine project directories");
         base.state_dir()
+            .map(|p| p.to_path_buf())
             .unwrap_or_else(|| base.data_local_dir().join("state"))
             .join("logs")
     }
@@ -71,3 +73,138 @@ pub fn ensure_log_dir() -> Result<(), std::io::Error> {
 pub fn app_log_path() -> PathBuf {
     log_dir().join("omnirec-app.log")
 }
+
+/// Returns `true` if `name` looks like a file written by the app's rolling
+/// appender (`omnirec-app.<date>.log` or `omnirec-app.<n>.<date>.log`).
+fn is_app_log_file(name: &str) -> bool {
+    name.starts_with("omnirec-app.") && name.ends_with(".log")
+}
+
+/// Lists the application log files in `dir`, most recently modified first.
+///
+/// Files that cannot be stat'ed are skipped. Returns an empty list if the
+/// directory does not exist.
+pub fn list_log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<(PathBuf, SystemTime)> = std::fs::read_dir(dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| is_app_log_file(&e.file_name().to_string_lossy()))
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            if !meta.is_file() {
+                return None;
+            }
+            Some((e.path(), meta.modified().ok()?))
+        })
+        .collect();
+
+    files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+    files.into_iter().map(|(path, _)| path).collect()
+}
+
+/// Deletes application log files in `dir` that were last modified more than
+/// `max_age` ago, or that exceed the newest `max_files` files.
+///
+/// A `max_files` of 0 disables the count limit. Returns the number of files
+/// removed; individual deletion failures are ignored.
+pub fn prune_logs(dir: &Path, max_age: Duration, max_files: usize) -> usize {
+    let now = SystemTime::now();
+    let mut removed = 0;
+
+    for (index, path) in list_log_files(dir).into_iter().enumerate() {
+        let too_many = max_files > 0 && index >= max_files;
+        let too_old = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > max_age);
+
+        if (too_many || too_old) && std::fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::temp_dir;
+    use std::fs::File;
+
+    fn touch(dir: &Path, name: &str, age: Duration) -> PathBuf {
+        let path = dir.join(name);
+        let file = File::create(&path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_list_log_files_newest_first() {
+        let dir = temp_dir("logging-list");
+        touch(
+            &dir,
+            "omnirec-app.2026-03-01.log",
+            Duration::from_secs(3600),
+        );
+        touch(&dir, "omnirec-app.2026-03-02.log", Duration::from_secs(60));
+        touch(&dir, "unrelated.log", Duration::from_secs(0));
+
+        let files = list_log_files(&dir);
+        let names: Vec<_> = files
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["omnirec-app.2026-03-02.log", "omnirec-app.2026-03-01.log"]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_list_log_files_missing_dir() {
+        let dir = std::env::temp_dir().join("omnirec-logging-test-does-not-exist");
+        assert!(list_log_files(&dir).is_empty());
+    }
+
+    #[test]
+    fn test_prune_logs_by_age() {
+        let dir = temp_dir("logging-age");
+        let old = touch(
+            &dir,
+            "omnirec-app.2026-01-01.log",
+            Duration::from_secs(10 * 86400),
+        );
+        let fresh = touch(&dir, "omnirec-app.2026-03-01.log", Duration::from_secs(60));
+        let other = touch(&dir, "notes.txt", Duration::from_secs(30 * 86400));
+
+        let removed = prune_logs(&dir, Duration::from_secs(7 * 86400), 0);
+        assert_eq!(removed, 1);
+        assert!(!old.exists());
+        assert!(fresh.exists());
+        assert!(other.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_prune_logs_by_count() {
+        let dir = temp_dir("logging-count");
+        let a = touch(&dir, "omnirec-app.2026-03-01.log", Duration::from_secs(300));
+        let b = touch(&dir, "omnirec-app.2026-03-02.log", Duration::from_secs(200));
+        let c = touch(&dir, "omnirec-app.2026-03-03.log", Duration::from_secs(100));
+
+        let removed = prune_logs(&dir, Duration::from_secs(86400), 2);
+        assert_eq!(removed, 1);
+        assert!(!a.exists());
+        assert!(b.exists());
+        assert!(c.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
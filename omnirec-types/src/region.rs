@@ -0,0 +1,330 @@
+//! Capture region geometry helpers.
+
+use crate::{CaptureRegion, MonitorInfo, WindowInfo};
+
+/// Common aspect ratios offered by the region selector.
+pub const PRESET_ASPECT_RATIOS: [(u32, u32); 3] = [(16, 9), (4, 3), (1, 1)];
+
+/// Default distance, in pixels, within which the region selector snaps to
+/// a window's edges.
+pub const DEFAULT_SNAP_THRESHOLD_PX: u32 = 15;
+
+/// A region lined up with a window's edges by [`find_snap_target`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapResult {
+    /// Handle of the window snapped to
+    pub window_handle: isize,
+    /// The region, moved onto the window's edges
+    pub region: CaptureRegion,
+}
+
+/// Find the window with an edge within `threshold` pixels of the same edge
+/// of `region` (left with left, top with top, and so on), preferring the
+/// nearest one.
+///
+/// The region is moved, not resized: on each axis, the nearer of its two
+/// edges within `threshold` is lined up with the window's. An edge only
+/// counts when the region lies alongside it, so a window far above the
+/// region doesn't pull it sideways. `region` and `windows` must share the
+/// same coordinates; windows without a size are skipped.
+pub fn find_snap_target(
+    region: &CaptureRegion,
+    windows: &[WindowInfo],
+    threshold: u32,
+) -> Option<SnapResult> {
+    let threshold = threshold as i64;
+    let (left, top) = (region.x as i64, region.y as i64);
+    let (right, bottom) = (left + region.width as i64, top + region.height as i64);
+
+    // Offset lining up the nearer pair of edges on one axis, if any are close
+    let snap_axis = |near: i64, far: i64, window_near: i64, window_far: i64| {
+        [window_near - near, window_far - far]
+            .into_iter()
+            .filter(|offset| offset.abs() <= threshold)
+            .min_by_key(|offset| offset.abs())
+    };
+    let alongside = |start: i64, end: i64, window_start: i64, window_end: i64| {
+        start < window_end + threshold && end > window_start - threshold
+    };
+
+    windows
+        .iter()
+        .filter(|window| window.width > 0 && window.height > 0)
+        .filter_map(|window| {
+            let (window_left, window_top) = (window.x as i64, window.y as i64);
+            let window_right = window_left + window.width as i64;
+            let window_bottom = window_top + window.height as i64;
+
+            let dx = alongside(top, bottom, window_top, window_bottom)
+                .then(|| snap_axis(left, right, window_left, window_right))
+                .flatten();
+            let dy = alongside(left, right, window_left, window_right)
+                .then(|| snap_axis(top, bottom, window_top, window_bottom))
+                .flatten();
+            let distance = dx.into_iter().chain(dy).map(i64::abs).min()?;
+            Some((distance, window.handle, dx.unwrap_or(0), dy.unwrap_or(0)))
+        })
+        .min_by_key(|&(distance, ..)| distance)
+        .map(|(_, window_handle, dx, dy)| SnapResult {
+            window_handle,
+            region: CaptureRegion {
+                x: (left + dx) as i32,
+                y: (top + dy) as i32,
+                ..region.clone()
+            },
+        })
+}
+
+/// Resize `region` to the `ratio` (width, height).
+///
+/// The larger of width and height is kept and the smaller one is derived
+/// from the ratio. The position is unchanged. A ratio with a zero term
+/// leaves the region as it is.
+pub fn constrain_to_aspect(region: &mut CaptureRegion, ratio: (u32, u32)) {
+    let (ratio_width, ratio_height) = (ratio.0 as u64, ratio.1 as u64);
+    if ratio_width == 0 || ratio_height == 0 {
+        return;
+    }
+    if region.width >= region.height {
+        let height = (region.width as u64 * ratio_height + ratio_width / 2) / ratio_width;
+        region.height = height.clamp(1, u32::MAX as u64) as u32;
+    } else {
+        let width = (region.height as u64 * ratio_width + ratio_height / 2) / ratio_height;
+        region.width = width.clamp(1, u32::MAX as u64) as u32;
+    }
+}
+
+/// Convert `region`, in physical pixels relative to `monitor`, to the
+/// monitor's logical pixels.
+///
+/// Windows with per-monitor DPI awareness reports monitor and window
+/// geometry in physical pixels, while the region selector webview works in
+/// logical pixels scaled by the monitor's `scale_factor`. A scale factor
+/// that isn't positive is treated as 1.0.
+pub fn physical_to_logical_region(region: &CaptureRegion, monitor: &MonitorInfo) -> CaptureRegion {
+    let scale = if monitor.scale_factor > 0.0 {
+        monitor.scale_factor
+    } else {
+        1.0
+    };
+    let logical = |value: f64| (value / scale).round();
+    CaptureRegion {
+        monitor_id: region.monitor_id.clone(),
+        x: logical(region.x as f64) as i32,
+        y: logical(region.y as f64) as i32,
+        width: logical(region.width as f64).max(1.0) as u32,
+        height: logical(region.height as f64).max(1.0) as u32,
+        aspect_ratio: region.aspect_ratio,
+    }
+}
+
+/// Convert `region`, in the logical pixels of `monitor`, to physical pixels
+/// of the monitor's framebuffer, clipped to the monitor.
+///
+/// The monitor's own size is taken as logical too and scaled the same way,
+/// as ScreenCaptureKit captures at the logical size times `scale_factor`.
+/// A negative offset counts as 0, so the result can be cropped from a
+/// captured frame as is; clipping may leave it empty. A scale factor that
+/// isn't positive is treated as 1.0.
+pub fn logical_to_physical_region(region: &CaptureRegion, monitor: &MonitorInfo) -> CaptureRegion {
+    let scale = if monitor.scale_factor > 0.0 {
+        monitor.scale_factor
+    } else {
+        1.0
+    };
+    let physical = |value: f64| (value * scale).round().clamp(0.0, i32::MAX as f64) as u32;
+    let (monitor_width, monitor_height) = (
+        physical(monitor.width as f64),
+        physical(monitor.height as f64),
+    );
+    let x = physical(region.x as f64).min(monitor_width);
+    let y = physical(region.y as f64).min(monitor_height);
+    CaptureRegion {
+        monitor_id: region.monitor_id.clone(),
+        x: x as i32,
+        y: y as i32,
+        width: physical(region.width as f64).min(monitor_width - x),
+        height: physical(region.height as f64).min(monitor_height - y),
+        aspect_ratio: region.aspect_ratio,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(width: u32, height: u32) -> CaptureRegion {
+        CaptureRegion {
+            monitor_id: "DP-1".to_string(),
+            x: 10,
+            y: 20,
+            width,
+            height,
+            aspect_ratio: None,
+        }
+    }
+
+    #[test]
+    fn test_constrain_adjusts_smaller_dimension() {
+        let mut r = region(1280, 600);
+        constrain_to_aspect(&mut r, (16, 9));
+        assert_eq!((r.width, r.height), (1280, 720));
+
+        let mut r = region(800, 900);
+        constrain_to_aspect(&mut r, (4, 3));
+        assert_eq!((r.width, r.height), (1200, 900));
+
+        let mut r = region(640, 480);
+        constrain_to_aspect(&mut r, (1, 1));
+        assert_eq!((r.width, r.height), (640, 640));
+        assert_eq!((r.x, r.y), (10, 20));
+    }
+
+    #[test]
+    fn test_constrain_rounds_to_nearest_pixel() {
+        let mut r = region(1000, 10);
+        constrain_to_aspect(&mut r, (16, 9));
+        assert_eq!(r.height, 563);
+    }
+
+    fn monitor(scale_factor: f64) -> MonitorInfo {
+        MonitorInfo {
+            id: "DP-1".to_string(),
+            name: "DP-1".to_string(),
+            x: 0,
+            y: 0,
+            width: 3840,
+            height: 2160,
+            is_primary: true,
+            scale_factor,
+        }
+    }
+
+    #[test]
+    fn test_physical_to_logical_region() {
+        let physical = CaptureRegion {
+            monitor_id: "DP-1".to_string(),
+            x: 300,
+            y: 150,
+            width: 1920,
+            height: 1080,
+            aspect_ratio: Some((16, 9)),
+        };
+        let cases = [
+            (1.0, (300, 150, 1920, 1080)),
+            (1.25, (240, 120, 1536, 864)),
+            (1.5, (200, 100, 1280, 720)),
+            (2.0, (150, 75, 960, 540)),
+        ];
+        for (scale, expected) in cases {
+            let r = physical_to_logical_region(&physical, &monitor(scale));
+            assert_eq!((r.x, r.y, r.width, r.height), expected, "scale {}", scale);
+            assert_eq!(r.monitor_id, "DP-1");
+            assert_eq!(r.aspect_ratio, Some((16, 9)));
+        }
+    }
+
+    #[test]
+    fn test_physical_to_logical_region_rounds() {
+        let r = physical_to_logical_region(&region(101, 3), &monitor(1.5));
+        assert_eq!((r.x, r.y, r.width, r.height), (7, 13, 67, 2));
+        let r = physical_to_logical_region(&region(1, 1), &monitor(2.0));
+        assert_eq!((r.width, r.height), (1, 1));
+        let r = physical_to_logical_region(&region(640, 480), &monitor(0.0));
+        assert_eq!((r.width, r.height), (640, 480));
+    }
+
+    #[test]
+    fn test_logical_to_physical_region() {
+        let logical = CaptureRegion {
+            monitor_id: "DP-1".to_string(),
+            x: 101,
+            y: 3,
+            width: 640,
+            height: 480,
+            aspect_ratio: Some((4, 3)),
+        };
+        let r = logical_to_physical_region(&logical, &monitor(1.5));
+        assert_eq!((r.x, r.y, r.width, r.height), (152, 5, 960, 720));
+        assert_eq!(r.aspect_ratio, Some((4, 3)));
+
+        // Clipped at the right and bottom edges, 7680x4320 at 2.0
+        let edge = CaptureRegion {
+            x: 3500,
+            y: -20,
+            ..logical
+        };
+        let r = logical_to_physical_region(&edge, &monitor(2.0));
+        assert_eq!((r.x, r.y, r.width, r.height), (7000, 0, 680, 960));
+    }
+
+    fn window(handle: isize, x: i32, y: i32, width: u32, height: u32) -> WindowInfo {
+        WindowInfo {
+            handle,
+            title: format!("Window {}", handle),
+            process_name: "app".to_string(),
+            x,
+            y,
+            width,
+            height,
+            workspace_id: None,
+            workspace_name: None,
+        }
+    }
+
+    fn at(x: i32, y: i32, width: u32, height: u32) -> CaptureRegion {
+        CaptureRegion {
+            monitor_id: String::new(),
+            x,
+            y,
+            width,
+            height,
+            aspect_ratio: None,
+        }
+    }
+
+    #[test]
+    fn test_snap_to_window_corner() {
+        let windows = [window(1, 100, 100, 800, 600)];
+        let snap = find_snap_target(&at(110, 92, 400, 300), &windows, 15).unwrap();
+        assert_eq!(snap.window_handle, 1);
+        assert_eq!((snap.region.x, snap.region.y), (100, 100));
+        assert_eq!((snap.region.width, snap.region.height), (400, 300));
+
+        // Right and bottom edges line up too
+        let snap = find_snap_target(&at(495, 395, 400, 300), &windows, 15).unwrap();
+        assert_eq!((snap.region.x, snap.region.y), (500, 400));
+    }
+
+    #[test]
+    fn test_snap_single_axis() {
+        let windows = [window(1, 100, 100, 800, 600)];
+        let snap = find_snap_target(&at(105, 250, 200, 100), &windows, 15).unwrap();
+        assert_eq!((snap.region.x, snap.region.y), (100, 250));
+    }
+
+    #[test]
+    fn test_snap_prefers_nearest_window() {
+        let windows = [window(1, 100, 100, 800, 600), window(2, 112, 400, 300, 300)];
+        let snap = find_snap_target(&at(110, 420, 200, 100), &windows, 15).unwrap();
+        assert_eq!(snap.window_handle, 2);
+        assert_eq!(snap.region.x, 112);
+    }
+
+    #[test]
+    fn test_no_snap() {
+        let windows = [window(1, 100, 100, 800, 600), window(2, 0, 0, 0, 0)];
+        // Too far from every edge
+        assert!(find_snap_target(&at(300, 300, 200, 100), &windows, 15).is_none());
+        // Left edges line up, but the window is far above the region
+        assert!(find_snap_target(&at(105, 1000, 200, 100), &windows, 15).is_none());
+        assert!(find_snap_target(&at(105, 92, 200, 100), &[], 15).is_none());
+    }
+
+    #[test]
+    fn test_constrain_ignores_zero_ratio() {
+        let mut r = region(640, 480);
+        constrain_to_aspect(&mut r, (0, 9));
+        assert_eq!((r.width, r.height), (640, 480));
+    }
+}
@@ -1,11 +1,16 @@
 //! Security modules for IPC authentication and validation.
 
+#[cfg(not(target_arch = "wasm32"))]
 pub mod path_validation;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod peer_verify;
 pub mod validation;
 
 /// Executable names permitted to connect to the service.
-pub const TRUSTED_EXECUTABLES: &[&str] = &["omnirec", "omnirec-picker"];
+///
+/// `omnirec-proxy` forwards CLI requests to the service; it verifies its own
+/// clients against this list before forwarding anything.
+pub const TRUSTED_EXECUTABLES: &[&str] = &["omnirec", "omnirec-picker", "omnirec-proxy"];
 
 /// Trusted installation directories (Linux).
 #[cfg(target_os = "linux")]
@@ -56,6 +56,12 @@ impl std::fmt::Display for PeerVerifyError {
 impl std::error::Error for PeerVerifyError {}
 
 fn verify_executable(exe_path: &Path) -> Result<(), PeerVerifyError> {
+    // Tests that connect to a server running in their own process
+    #[cfg(feature = "integration-tests")]
+    if std::env::current_exe().is_ok_and(|own| own == exe_path) {
+        return Ok(());
+    }
+
     let exe_name = exe_path
         .file_name()
         .and_then(|n| n.to_str())
@@ -243,3 +249,21 @@ pub fn verify_peer(pipe: windows::Win32::Foundation::HANDLE) -> Result<PeerInfo,
         executable: exe_path,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trusted_executables() {
+        let dir = Path::new(TRUSTED_DIRECTORIES[0]);
+        assert!(verify_executable(&dir.join("omnirec")).is_ok());
+        assert!(verify_executable(&dir.join("omnirec-picker")).is_ok());
+        // The CLI proxy connects to the service on the CLI's behalf
+        assert!(verify_executable(&dir.join("omnirec-proxy")).is_ok());
+        assert!(matches!(
+            verify_executable(&dir.join("nc")),
+            Err(PeerVerifyError::UntrustedExecutable(_))
+        ));
+    }
+}
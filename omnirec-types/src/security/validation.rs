@@ -22,6 +22,21 @@ pub const MAX_COORDINATE: i32 = 65535;
 /// Maximum dimension value (must be positive)
 pub const MAX_DIMENSION: u32 = 16384;
 
+/// Maximum capture frame rate limit
+pub const MAX_FRAME_RATE: u32 = 240;
+
+/// Maximum live preview frame rate
+pub const MAX_PREVIEW_FPS: u8 = 30;
+
+/// Maximum number of sources in one thumbnail batch
+pub const MAX_THUMBNAIL_BATCH: usize = 64;
+
+/// Maximum number of log events returned by one `GetRecentEvents`
+pub const MAX_RECENT_EVENTS: usize = 1000;
+
+/// Log level names accepted by `GetRecentEvents`
+const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
 /// Validation error types.
 #[derive(Debug, Clone)]
 pub enum ValidationError {
@@ -47,6 +62,16 @@ pub enum ValidationError {
     },
     /// Message exceeds maximum size
     MessageTooLarge { size: usize, max: usize },
+    /// Picker selection is malformed (unknown type or missing geometry)
+    InvalidSelection(String),
+    /// List field has too many entries
+    TooManyItems {
+        field: &'static str,
+        len: usize,
+        max: usize,
+    },
+    /// Log level is not one of error, warn, info, debug or trace
+    InvalidLogLevel(String),
 }
 
 impl std::fmt::Display for ValidationError {
@@ -67,6 +92,11 @@ impl std::fmt::Display for ValidationError {
             ValidationError::MessageTooLarge { size, max } => {
                 write!(f, "Message too large: {} bytes (max {})", size, max)
             }
+            ValidationError::InvalidSelection(msg) => write!(f, "Invalid selection: {}", msg),
+            ValidationError::TooManyItems { field, len, max } => {
+                write!(f, "{} has too many entries: {} (max {})", field, len, max)
+            }
+            ValidationError::InvalidLogLevel(level) => write!(f, "Invalid log level: {}", level),
         }
     }
 }
@@ -97,6 +127,35 @@ pub fn validate_window_handle(handle: isize) -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Validate the size of a thumbnail batch.
+pub fn validate_thumbnail_batch(len: usize) -> Result<(), ValidationError> {
+    if len > MAX_THUMBNAIL_BATCH {
+        return Err(ValidationError::TooManyItems {
+            field: "sources",
+            len,
+            max: MAX_THUMBNAIL_BATCH,
+        });
+    }
+    Ok(())
+}
+
+/// Validate a `GetRecentEvents` request: the event count and level name.
+pub fn validate_recent_events(count: u32, level: Option<&str>) -> Result<(), ValidationError> {
+    if count as usize > MAX_RECENT_EVENTS {
+        return Err(ValidationError::TooManyItems {
+            field: "count",
+            len: count as usize,
+            max: MAX_RECENT_EVENTS,
+        });
+    }
+    if let Some(level) = level {
+        if !LOG_LEVELS.contains(&level.to_ascii_lowercase().as_str()) {
+            return Err(ValidationError::InvalidLogLevel(level.to_string()));
+        }
+    }
+    Ok(())
+}
+
 /// Validate dimension values (width, height).
 pub fn validate_dimensions(width: u32, height: u32) -> Result<(), ValidationError> {
     if width == 0 || width > MAX_DIMENSION {
@@ -133,6 +192,58 @@ pub fn validate_coordinates(x: i32, y: i32) -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Validate a capture frame rate limit.
+pub fn validate_frame_rate(fps: u32) -> Result<(), ValidationError> {
+    if fps == 0 || fps > MAX_FRAME_RATE {
+        return Err(ValidationError::DimensionOutOfRange {
+            field: "max_fps",
+            value: fps,
+            max: MAX_FRAME_RATE,
+        });
+    }
+    Ok(())
+}
+
+/// Validate a live preview frame rate.
+pub fn validate_preview_fps(fps: u8) -> Result<(), ValidationError> {
+    if fps == 0 || fps > MAX_PREVIEW_FPS {
+        return Err(ValidationError::DimensionOutOfRange {
+            field: "fps",
+            value: fps as u32,
+            max: MAX_PREVIEW_FPS as u32,
+        });
+    }
+    Ok(())
+}
+
+/// Validate a picker selection.
+///
+/// `source_type` must be "monitor", "window", or "region". Monitor and region
+/// selections take a monitor ID; window selections take a compositor window
+/// address. Region selections require geometry.
+pub fn validate_selection(
+    source_type: &str,
+    source_id: &str,
+    has_geometry: bool,
+) -> Result<(), ValidationError> {
+    match source_type {
+        "monitor" | "region" => validate_monitor_id(source_id)?,
+        "window" => validate_source_id(source_id)?,
+        other => {
+            return Err(ValidationError::InvalidSelection(format!(
+                "unknown source type '{}'",
+                other
+            )))
+        }
+    }
+    if source_type == "region" && !has_geometry {
+        return Err(ValidationError::InvalidSelection(
+            "region selection requires geometry".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,4 +303,49 @@ mod tests {
         assert!(validate_coordinates(MAX_COORDINATE + 1, 0).is_err());
         assert!(validate_coordinates(0, -MAX_COORDINATE - 1).is_err());
     }
+
+    #[test]
+    fn test_frame_rate() {
+        assert!(validate_frame_rate(1).is_ok());
+        assert!(validate_frame_rate(30).is_ok());
+        assert!(validate_frame_rate(MAX_FRAME_RATE).is_ok());
+
+        assert!(validate_frame_rate(0).is_err());
+        assert!(validate_frame_rate(MAX_FRAME_RATE + 1).is_err());
+    }
+
+    #[test]
+    fn test_preview_fps() {
+        assert!(validate_preview_fps(1).is_ok());
+        assert!(validate_preview_fps(MAX_PREVIEW_FPS).is_ok());
+
+        assert!(validate_preview_fps(0).is_err());
+        assert!(validate_preview_fps(MAX_PREVIEW_FPS + 1).is_err());
+    }
+
+    #[test]
+    fn test_thumbnail_batch() {
+        assert!(validate_thumbnail_batch(0).is_ok());
+        assert!(validate_thumbnail_batch(MAX_THUMBNAIL_BATCH).is_ok());
+        assert!(validate_thumbnail_batch(MAX_THUMBNAIL_BATCH + 1).is_err());
+    }
+
+    #[test]
+    fn test_recent_events() {
+        assert!(validate_recent_events(100, None).is_ok());
+        assert!(validate_recent_events(MAX_RECENT_EVENTS as u32, Some("WARN")).is_ok());
+        assert!(validate_recent_events(MAX_RECENT_EVENTS as u32 + 1, None).is_err());
+        assert!(validate_recent_events(100, Some("verbose")).is_err());
+    }
+
+    #[test]
+    fn test_selection() {
+        assert!(validate_selection("monitor", "DP-1", false).is_ok());
+        assert!(validate_selection("window", "0x55d1c2a0b3e0", false).is_ok());
+        assert!(validate_selection("region", "DP-1", true).is_ok());
+
+        assert!(validate_selection("region", "DP-1", false).is_err());
+        assert!(validate_selection("monitor", "DP 1", false).is_err());
+        assert!(validate_selection("screen", "DP-1", false).is_err());
+    }
 }
@@ -0,0 +1,16 @@
+//! Helpers shared by the unit tests.
+
+use std::path::PathBuf;
+
+/// An empty directory under the system temp dir, named after `name` and the
+/// process id so parallel test runs don't share it.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "omnirec-types-test-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
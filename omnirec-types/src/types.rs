@@ -1,9 +1,15 @@
 //! OmniRec-specific shared types for capture, IPC, and recording configuration.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
 
 /// Information about a capturable window.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "wasm-bindgen",
+    wasm_bindgen::prelude::wasm_bindgen(getter_with_clone)
+)]
 pub struct WindowInfo {
     /// Window handle/ID as an integer (platform-specific)
     pub handle: isize,
@@ -23,10 +29,20 @@ pub struct WindowInfo {
     /// Window height in pixels
     #[serde(default)]
     pub height: u32,
+    /// ID of the workspace the window is on (Hyprland only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<i32>,
+    /// Name of the workspace the window is on (Hyprland only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_name: Option<String>,
 }
 
 /// Information about a display monitor.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "wasm-bindgen",
+    wasm_bindgen::prelude::wasm_bindgen(getter_with_clone)
+)]
 pub struct MonitorInfo {
     /// Unique identifier (platform-specific)
     pub id: String,
@@ -52,7 +68,7 @@ fn default_scale_factor() -> f64 {
 }
 
 /// Region specification for capture.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CaptureRegion {
     /// Monitor device ID
     pub monitor_id: String,
@@ -64,10 +80,27 @@ pub struct CaptureRegion {
     pub width: u32,
     /// Region height
     pub height: u32,
+    /// Aspect ratio (width, height) the region is locked to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<(u32, u32)>,
+}
+
+/// Source of a screenshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum ScreenshotTarget {
+    /// A whole display
+    Display { monitor_id: String },
+    /// A single window
+    Window { window_handle: isize },
 }
 
 /// Information about an audio source (input device or system audio).
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "wasm-bindgen",
+    wasm_bindgen::prelude::wasm_bindgen(getter_with_clone)
+)]
 pub struct AudioSource {
     /// Unique identifier (PipeWire node ID, WASAPI endpoint ID, etc.)
     pub id: String,
@@ -80,6 +113,7 @@ pub struct AudioSource {
 /// Type of audio source.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
 pub enum AudioSourceType {
     /// Microphone or other audio input device
     Input,
@@ -87,6 +121,74 @@ pub enum AudioSourceType {
     Output,
 }
 
+/// Prefix of [`AudioConfig::source_id`] values that select the audio of a
+/// single application (`app:<name>`) instead of a whole output device.
+pub const APP_AUDIO_SOURCE_PREFIX: &str = "app:";
+
+/// An application currently playing audio.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioAppInfo {
+    /// Application name (`application.name`, or the binary if unset)
+    pub name: String,
+    /// Executable name (`application.process.binary`), if reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<String>,
+    /// Number of audio streams the application has open
+    pub stream_count: u32,
+}
+
+/// A webcam that can be overlaid on recordings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CameraInfo {
+    /// Camera index or platform device path, as accepted by [`PipConfig::camera_id`]
+    pub id: String,
+    /// Display name for UI
+    pub name: String,
+}
+
+/// Corner of the recording the webcam overlay is placed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// Accepted webcam overlay sizes, as a fraction of the recording's width.
+pub const PIP_SIZE_FRACTION_RANGE: RangeInclusive<f32> = 0.05..=0.5;
+
+/// Picture-in-picture webcam overlay of a recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipConfig {
+    /// Camera to overlay, from [`CameraInfo::id`]
+    pub camera_id: String,
+    #[serde(default)]
+    pub position: PipPosition,
+    /// Overlay width as a fraction of the recording's width
+    pub size_fraction: f32,
+}
+
+impl PipConfig {
+    /// Check that the camera ID is set and the size is accepted.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.camera_id.trim().is_empty() {
+            return Err("PiP camera ID must not be empty".to_string());
+        }
+        if !PIP_SIZE_FRACTION_RANGE.contains(&self.size_fraction) {
+            return Err(format!(
+                "PiP size must be between {} and {} of the recording width, got {}",
+                PIP_SIZE_FRACTION_RANGE.start(),
+                PIP_SIZE_FRACTION_RANGE.end(),
+                self.size_fraction
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// Output format for recordings.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -153,11 +255,14 @@ impl OutputFormat {
 /// Recording state enum.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
 pub enum RecordingState {
     /// Not recording, ready to start
     Idle,
     /// Currently recording
     Recording,
+    /// Recording paused while the system sleeps; resumes on wake
+    Suspended,
     /// Finalizing the recording (encoding remaining frames, writing file)
     Saving,
 }
@@ -182,12 +287,105 @@ pub struct AudioConfig {
     /// When disabled, gate_threshold_db = -100.0 dBFS (gate bypassed).
     #[serde(default = "AudioConfig::default_agc_noise_gate_enabled")]
     pub agc_noise_gate_enabled: bool,
+    /// Level the microphone AGC normalizes to, in dBFS (RMS).
+    #[serde(default = "AudioConfig::default_agc_target_level_dbfs")]
+    pub agc_target_level_dbfs: f32,
+    /// Whether captured audio is played through the default output device
+    /// while recording (macOS only).
+    #[serde(default)]
+    pub audio_monitor_enabled: bool,
+    /// Playback volume of the audio monitor (0.0–1.0).
+    #[serde(default = "AudioConfig::default_monitor_volume")]
+    pub monitor_volume: f32,
+    /// Audio bitrate in kbps, or [`AUDIO_BITRATE_AUTO`] for
+    /// [`DEFAULT_AUDIO_BITRATE_KBPS`].
+    #[serde(default)]
+    pub audio_bitrate_kbps: u32,
 }
 
 impl AudioConfig {
     fn default_agc_noise_gate_enabled() -> bool {
         true
     }
+
+    fn default_monitor_volume() -> f32 {
+        1.0
+    }
+
+    fn default_agc_target_level_dbfs() -> f32 {
+        DEFAULT_AGC_TARGET_LEVEL_DBFS
+    }
+
+    /// Bitrate audio is encoded at: the configured one, or the default when
+    /// automatic.
+    pub fn effective_bitrate_kbps(&self) -> u32 {
+        if self.audio_bitrate_kbps == AUDIO_BITRATE_AUTO {
+            DEFAULT_AUDIO_BITRATE_KBPS
+        } else {
+            self.audio_bitrate_kbps
+        }
+    }
+}
+
+/// Default level the microphone AGC normalizes to, in dBFS.
+pub const DEFAULT_AGC_TARGET_LEVEL_DBFS: f32 = -20.0;
+
+/// Accepted microphone AGC target levels, in dBFS.
+pub const AGC_TARGET_LEVEL_RANGE_DBFS: RangeInclusive<f32> = -40.0..=-6.0;
+
+/// Check that `dbfs` is an accepted microphone AGC target level.
+pub fn validate_agc_target_level(dbfs: f32) -> Result<(), String> {
+    if AGC_TARGET_LEVEL_RANGE_DBFS.contains(&dbfs) {
+        Ok(())
+    } else {
+        Err(format!(
+            "AGC target level must be between {} and {} dBFS, got {}",
+            AGC_TARGET_LEVEL_RANGE_DBFS.start(),
+            AGC_TARGET_LEVEL_RANGE_DBFS.end(),
+            dbfs
+        ))
+    }
+}
+
+/// `audio_bitrate_kbps` value that picks the bitrate automatically.
+pub const AUDIO_BITRATE_AUTO: u32 = 0;
+
+/// Automatic audio bitrate, in kbps.
+pub const DEFAULT_AUDIO_BITRATE_KBPS: u32 = 192;
+
+/// Audio codecs recordings are encoded with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioCodec {
+    /// AAC, muxed into the MP4 while recording
+    Aac,
+    /// Opus, used when transcoding to WebM
+    Opus,
+}
+
+impl AudioCodec {
+    /// Bitrates the codec's FFmpeg encoder accepts, in kbps.
+    pub fn bitrate_range_kbps(&self) -> RangeInclusive<u32> {
+        match self {
+            AudioCodec::Aac => 32..=320,
+            AudioCodec::Opus => 6..=510,
+        }
+    }
+
+    /// Check that `kbps` is [`AUDIO_BITRATE_AUTO`] or within the codec's range.
+    pub fn validate_bitrate(&self, kbps: u32) -> Result<(), String> {
+        let range = self.bitrate_range_kbps();
+        if kbps == AUDIO_BITRATE_AUTO || range.contains(&kbps) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{:?} audio bitrate must be between {} and {} kbps, got {}",
+                self,
+                range.start(),
+                range.end(),
+                kbps
+            ))
+        }
+    }
 }
 
 impl Default for AudioConfig {
@@ -199,10 +397,31 @@ impl Default for AudioConfig {
             echo_cancellation: true,
             agc_enabled: false,
             agc_noise_gate_enabled: true,
+            agc_target_level_dbfs: DEFAULT_AGC_TARGET_LEVEL_DBFS,
+            audio_monitor_enabled: false,
+            monitor_volume: 1.0,
+            audio_bitrate_kbps: AUDIO_BITRATE_AUTO,
         }
     }
 }
 
+/// A stored "Always allow" approval of the screen sharing picker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalTokenInfo {
+    /// Identifier used to revoke the token
+    pub token_id: String,
+    /// Type of source selected when the approval was granted
+    /// ("monitor", "window", "region", or "unknown")
+    pub source_type: String,
+    /// Source selected when the approval was granted
+    pub source_id: String,
+    /// When the approval was granted (RFC 3339)
+    pub created_at: String,
+    /// When the picker last used the approval (RFC 3339)
+    #[serde(default)]
+    pub last_used_at: Option<String>,
+}
+
 /// Transcription configuration for recording.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TranscriptionConfig {
@@ -213,6 +432,10 @@ pub struct TranscriptionConfig {
     /// If not set, the service will use its default path.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model_path: Option<String>,
+    /// Whisper language code of the speech (`auto` to detect it).
+    /// English if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
 }
 
 /// Status of the transcription system.
@@ -245,6 +468,7 @@ pub struct TranscriptionSegment {
 
 impl TranscriptionSegment {
     /// Convert from a vtx-engine TranscriptionSegment.
+    #[cfg(feature = "native")]
     pub fn from_vtx(seg: &vtx_engine::TranscriptionSegment) -> Self {
         Self {
             timestamp_secs: seg.timestamp_offset_ms as f64 / 1000.0,
@@ -252,3 +476,93 @@ impl TranscriptionSegment {
         }
     }
 }
+
+/// A log message recorded by the service.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEvent {
+    /// When the message was logged (RFC 3339, UTC)
+    pub timestamp: String,
+    /// `ERROR`, `WARN`, `INFO`, `DEBUG` or `TRACE`
+    pub level: String,
+    /// Module that logged the message, e.g. `omnirec_lib::state`
+    pub target: String,
+    pub message: String,
+    /// Fields of the message and of the spans it was logged in
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_bitrate() {
+        let mut config = AudioConfig {
+            source_id: Some("speakers".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.effective_bitrate_kbps(), 192);
+
+        config.microphone_id = Some("mic".to_string());
+        assert_eq!(config.effective_bitrate_kbps(), 192);
+
+        config.audio_bitrate_kbps = 96;
+        assert_eq!(config.effective_bitrate_kbps(), 96);
+    }
+
+    #[test]
+    fn test_validate_agc_target_level() {
+        assert!(validate_agc_target_level(DEFAULT_AGC_TARGET_LEVEL_DBFS).is_ok());
+        assert!(validate_agc_target_level(-6.0).is_ok());
+        assert!(validate_agc_target_level(0.0).is_err());
+        assert!(validate_agc_target_level(-60.0).is_err());
+        assert!(validate_agc_target_level(f32::NAN).is_err());
+    }
+
+    #[test]
+    fn test_pip_config() {
+        let pip: PipConfig =
+            serde_json::from_str(r#"{"camera_id":"0","size_fraction":0.25}"#).unwrap();
+        assert_eq!(pip.position, PipPosition::BottomRight);
+        assert!(pip.validate().is_ok());
+
+        let pip: PipConfig =
+            serde_json::from_str(r#"{"camera_id":"0","position":"top_left","size_fraction":0.6}"#)
+                .unwrap();
+        assert_eq!(pip.position, PipPosition::TopLeft);
+        assert!(pip.validate().is_err());
+
+        let pip = PipConfig {
+            camera_id: " ".to_string(),
+            position: PipPosition::TopRight,
+            size_fraction: 0.2,
+        };
+        assert!(pip.validate().is_err());
+    }
+
+    #[test]
+    fn test_screenshot_target_wire_format() {
+        let target: ScreenshotTarget =
+            serde_json::from_str(r#"{"source":"window","window_handle":42}"#).unwrap();
+        assert_eq!(target, ScreenshotTarget::Window { window_handle: 42 });
+        assert_eq!(
+            serde_json::to_string(&ScreenshotTarget::Display {
+                monitor_id: "DP-1".to_string()
+            })
+            .unwrap(),
+            r#"{"source":"display","monitor_id":"DP-1"}"#
+        );
+    }
+
+    #[test]
+    fn test_validate_bitrate() {
+        assert!(AudioCodec::Aac.validate_bitrate(AUDIO_BITRATE_AUTO).is_ok());
+        assert!(AudioCodec::Aac.validate_bitrate(320).is_ok());
+        assert!(AudioCodec::Aac.validate_bitrate(16).is_err());
+        assert!(AudioCodec::Aac.validate_bitrate(384).is_err());
+        assert!(AudioCodec::Opus.validate_bitrate(6).is_ok());
+        assert!(AudioCodec::Opus.validate_bitrate(510).is_ok());
+        assert!(AudioCodec::Opus.validate_bitrate(511).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+//! Property tests of the translation between logical and physical regions.
+//!
+//! CI runs these with `PROPTEST_CASES=1000`.
+
+use omnirec_types::{
+    logical_to_physical_region, physical_to_logical_region, CaptureRegion, MonitorInfo,
+};
+use proptest::prelude::*;
+
+fn monitor() -> impl Strategy<Value = MonitorInfo> {
+    (640u32..=7680, 480u32..=4320, 1.0f64..=4.0).prop_map(|(width, height, scale_factor)| {
+        MonitorInfo {
+            id: "DP-1".to_string(),
+            name: "DP-1".to_string(),
+            x: 0,
+            y: 0,
+            width,
+            height,
+            is_primary: true,
+            scale_factor,
+        }
+    })
+}
+
+/// Any region near `monitor`, including ones that hang off its edges.
+fn region_near(monitor: &MonitorInfo) -> impl Strategy<Value = CaptureRegion> {
+    let (width, height) = (monitor.width as i32, monitor.height as i32);
+    (
+        -width..=2 * width,
+        -height..=2 * height,
+        0..=2 * monitor.width,
+        0..=2 * monitor.height,
+    )
+        .prop_map(|(x, y, width, height)| CaptureRegion {
+            monitor_id: "DP-1".to_string(),
+            x,
+            y,
+            width,
+            height,
+            aspect_ratio: None,
+        })
+}
+
+/// Physical size of `monitor`, as `logical_to_physical_region` clips to it.
+fn physical_size(monitor: &MonitorInfo) -> (u32, u32) {
+    let physical = |value: u32| (value as f64 * monitor.scale_factor).round() as u32;
+    (physical(monitor.width), physical(monitor.height))
+}
+
+/// A region in the physical pixels of `monitor`, inside it and at least
+/// 16 pixels across.
+fn physical_region_in(monitor: &MonitorInfo) -> impl Strategy<Value = CaptureRegion> {
+    let (width, height) = physical_size(monitor);
+    (0..width - 16, 0..height - 16)
+        .prop_flat_map(move |(x, y)| (Just(x), Just(y), 16..=width - x, 16..=height - y))
+        .prop_map(|(x, y, width, height)| CaptureRegion {
+            monitor_id: "DP-1".to_string(),
+            x: x as i32,
+            y: y as i32,
+            width,
+            height,
+            aspect_ratio: None,
+        })
+}
+
+proptest! {
+    #[test]
+    fn translated_region_fits_monitor(
+        (region, monitor) in monitor().prop_flat_map(|m| (region_near(&m), Just(m)))
+    ) {
+        let (width, height) = physical_size(&monitor);
+        let physical = logical_to_physical_region(&region, &monitor);
+        prop_assert!(physical.x >= 0 && physical.y >= 0);
+        prop_assert!(physical.x as u32 + physical.width <= width);
+        prop_assert!(physical.y as u32 + physical.height <= height);
+    }
+
+    /// Rounding to logical pixels moves each value by up to half a logical
+    /// pixel, and rounding back by up to half a physical one: at most one
+    /// physical pixel below a scale of 3, and two at a scale of 4.
+    #[test]
+    fn physical_round_trip_loses_at_most_rounding(
+        (region, monitor) in monitor().prop_flat_map(|m| (physical_region_in(&m), Just(m)))
+    ) {
+        let logical = physical_to_logical_region(&region, &monitor);
+        let back = logical_to_physical_region(&logical, &monitor);
+        let max_error = (monitor.scale_factor / 2.0 + 0.5).floor();
+        for (before, after) in [
+            (region.x as i64, back.x as i64),
+            (region.y as i64, back.y as i64),
+            (region.width as i64, back.width as i64),
+            (region.height as i64, back.height as i64),
+        ] {
+            prop_assert!(
+                (before - after).abs() as f64 <= max_error,
+                "{:?} became {:?} at scale {}",
+                region,
+                back,
+                monitor.scale_factor
+            );
+        }
+    }
+}
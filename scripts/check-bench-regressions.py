@@ -0,0 +1,44 @@
+#!/usr/bin/env python3
+
+"""Fail if a criterion benchmark got slower than its baseline.
+
+Reads the change estimates criterion writes after `cargo bench -- --baseline
+<name>` and exits non-zero if the mean time of any benchmark rose by more
+than the threshold (default 10%).
+"""
+
+import json
+import sys
+from pathlib import Path
+
+
+def main() -> int:
+    if len(sys.argv) not in (2, 3):
+        raise SystemExit(
+            "usage: check-bench-regressions.py <criterion-dir> [max-regression-percent]"
+        )
+
+    criterion_dir = Path(sys.argv[1])
+    threshold = float(sys.argv[2]) / 100 if len(sys.argv) == 3 else 0.10
+
+    changes = sorted(criterion_dir.glob("**/change/estimates.json"))
+    if not changes:
+        print(f"No benchmark changes found in {criterion_dir}; was a baseline loaded?")
+        return 1
+
+    regressions = []
+    for path in changes:
+        name = path.parent.parent.relative_to(criterion_dir).as_posix()
+        change = json.loads(path.read_text())["mean"]["point_estimate"]
+        print(f"{name}: {change:+.1%}")
+        if change > threshold:
+            regressions.append(name)
+
+    if regressions:
+        print(f"Regressed by more than {threshold:.0%}: {', '.join(regressions)}")
+        return 1
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
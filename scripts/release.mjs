@@ -171,6 +171,7 @@ const updateTargets = async () => {
   await updateCargoVersion(path.join(repoRoot, "src-tauri", "Cargo.toml"));
   await updateCargoVersion(path.join(repoRoot, "omnirec-types", "Cargo.toml"));
   await updateCargoVersion(path.join(repoRoot, "src-cli", "Cargo.toml"));
+  await updateCargoVersion(path.join(repoRoot, "src-cli-proxy", "Cargo.toml"));
   await updatePkgbuild(path.join(repoRoot, "packaging", "aur", "PKGBUILD"));
   await updateReadme(path.join(repoRoot, "README.md"));
 };
@@ -198,6 +199,7 @@ const versionedFiles = [
   "src-tauri/Cargo.toml",
   "omnirec-types/Cargo.toml",
   "src-cli/Cargo.toml",
+  "src-cli-proxy/Cargo.toml",
   "packaging/aur/PKGBUILD",
   "README.md",
   "Cargo.lock",
@@ -0,0 +1,74 @@
+//! `omnirec-proxy`: keeps OmniRec CLI connections to the service open
+//! between CLI invocations.
+//!
+//! The proxy listens on the session's CLI proxy socket
+//! (`~/.local/share/omnirec/cli-session.sock` on Linux) and forwards each
+//! request to the service over a pooled connection that has already
+//! completed the handshake. A script that runs several `omnirec` commands
+//! then connects to the service once instead of once per command. When the
+//! proxy is not running, the CLI connects to the service directly.
+//!
+//! Unix only; on Windows the CLI always connects directly.
+
+#[cfg(unix)]
+mod proxy;
+
+use std::process::ExitCode;
+
+#[cfg(unix)]
+#[tokio::main]
+async fn main() -> ExitCode {
+    use omnirec_types::ipc::{get_proxy_socket_path, get_socket_path};
+    use std::sync::Arc;
+
+    let Some(listen_path) = get_proxy_socket_path() else {
+        eprintln!("omnirec-proxy: could not determine the data directory");
+        return ExitCode::FAILURE;
+    };
+    let listener = match proxy::bind(&listen_path).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!(
+                "omnirec-proxy: failed to listen on {}: {}",
+                listen_path.display(),
+                e
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let service_path = get_socket_path();
+    eprintln!(
+        "omnirec-proxy: forwarding {} to {}",
+        listen_path.display(),
+        service_path.display()
+    );
+    let pool = Arc::new(proxy::Pool::new(service_path));
+
+    tokio::select! {
+        _ = proxy::serve(listener, pool) => {}
+        _ = shutdown_signal() => {}
+    }
+
+    let _ = std::fs::remove_file(&listen_path);
+    ExitCode::SUCCESS
+}
+
+/// Wait for SIGINT or SIGTERM.
+#[cfg(unix)]
+async fn shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sigint = signal(SignalKind::interrupt()).expect("Failed to set up SIGINT handler");
+    let mut sigterm = signal(SignalKind::terminate()).expect("Failed to set up SIGTERM handler");
+    tokio::select! {
+        _ = sigint.recv() => {}
+        _ = sigterm.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+fn main() -> ExitCode {
+    eprintln!("omnirec-proxy is only available on Linux and macOS");
+    ExitCode::FAILURE
+}
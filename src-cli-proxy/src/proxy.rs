@@ -0,0 +1,323 @@
+//! Forwarding CLI requests over pooled service connections.
+//!
+//! Each CLI connection is checked with the same peer verification as the
+//! service's, handshaken by the proxy itself and then given a service
+//! connection from the pool for its lifetime. Requests and
+//! responses are relayed one at a time, so the service connection is idle
+//! whenever the CLI disconnects and can be handed to the next one.
+
+use omnirec_types::ipc::{
+    negotiate, read_connection_start, read_json, read_message, write_json, write_message,
+    write_protocol_header, ConnectionStart, IpcError, Request, Response, MIN_PROTOCOL_VERSION,
+    PROTOCOL_VERSION,
+};
+use omnirec_types::security::peer_verify::verify_peer;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::net::{UnixListener, UnixStream};
+
+/// Idle service connections kept open, at most.
+const MAX_IDLE_CONNECTIONS: usize = 4;
+
+/// Socket directory permissions (owner only)
+const DIRECTORY_MODE: u32 = 0o700;
+
+/// Bind the proxy socket, replacing a stale one left by a proxy that
+/// exited. Fails if another proxy is listening on it.
+///
+/// The socket's directory is made private before binding, so the socket is
+/// never reachable by other users.
+pub async fn bind(path: &Path) -> std::io::Result<UnixListener> {
+    if let Some(dir) = path.parent() {
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(DIRECTORY_MODE)
+            .create(dir)?;
+        // An existing directory keeps its mode
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(DIRECTORY_MODE))?;
+    }
+    if path.exists() {
+        if UnixStream::connect(path).await.is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                "another omnirec-proxy is running",
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+
+    UnixListener::bind(path)
+}
+
+/// Service connections that completed the handshake, ready for reuse.
+pub struct Pool {
+    socket_path: PathBuf,
+    idle: Mutex<Vec<UnixStream>>,
+    /// Features the service announced in the last handshake
+    features: Mutex<Vec<String>>,
+}
+
+impl Pool {
+    /// Create an empty pool of connections to the service at `socket_path`.
+    pub fn new(socket_path: PathBuf) -> Self {
+        Self {
+            socket_path,
+            idle: Mutex::new(Vec::new()),
+            features: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Take an idle connection, or open a new one if none is left open.
+    async fn take(&self) -> Result<UnixStream, IpcError> {
+        loop {
+            let stream = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+            match stream {
+                Some(stream) if is_open(&stream) => return Ok(stream),
+                Some(_) => continue,
+                None => return self.open().await,
+            }
+        }
+    }
+
+    /// Connect to the service and perform the handshake.
+    async fn open(&self) -> Result<UnixStream, IpcError> {
+        let mut stream = UnixStream::connect(&self.socket_path).await?;
+        write_protocol_header(&mut stream, PROTOCOL_VERSION).await?;
+        let hello = Request::Hello {
+            client_version: PROTOCOL_VERSION,
+            min_protocol: MIN_PROTOCOL_VERSION,
+        };
+        write_json(&mut stream, &hello).await?;
+        match read_json(&mut stream).await? {
+            Response::Welcome {
+                supported_features, ..
+            } => {
+                *self.features.lock().unwrap_or_else(|e| e.into_inner()) = supported_features;
+                Ok(stream)
+            }
+            other => Err(IpcError::ParseError(format!(
+                "unexpected handshake response: {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Return a connection whose last request was answered.
+    fn put_back(&self, stream: UnixStream) {
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        if idle.len() < MAX_IDLE_CONNECTIONS {
+            idle.push(stream);
+        }
+    }
+
+    fn features(&self) -> Vec<String> {
+        self.features
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+/// Whether the service end of an idle connection is still open. A closed
+/// connection reads as end of file; an open, idle one has nothing to read.
+fn is_open(stream: &UnixStream) -> bool {
+    matches!(
+        stream.try_read(&mut [0u8; 1]),
+        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock
+    )
+}
+
+/// Accept CLI connections until the listener fails.
+pub async fn serve(listener: UnixListener, pool: Arc<Pool>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                eprintln!("omnirec-proxy: accept failed: {}", e);
+                return;
+            }
+        };
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            match handle_client(stream, &pool).await {
+                Ok(()) | Err(IpcError::ConnectionClosed) => {}
+                Err(e) => eprintln!("omnirec-proxy: {}", e),
+            }
+        });
+    }
+}
+
+/// Serve one CLI connection: verify the client, answer its handshake, then
+/// relay each request to the service and its response back.
+///
+/// The connection is closed without a handshake answer when the service
+/// can't be reached, so the CLI falls back to connecting directly.
+async fn handle_client(client: UnixStream, pool: &Pool) -> Result<(), IpcError> {
+    let mut client = verify_client(client)?;
+    let ConnectionStart::Versioned(_) = read_connection_start(&mut client).await? else {
+        return Err(IpcError::ParseError(
+            "client connected without a protocol header".to_string(),
+        ));
+    };
+    let Request::Hello {
+        client_version,
+        min_protocol,
+    } = read_json(&mut client).await?
+    else {
+        let response = Response::error("Expected hello as the first request");
+        return write_json(&mut client, &response).await;
+    };
+    let server_version = match negotiate(client_version, min_protocol) {
+        Response::Welcome { server_version, .. } => server_version,
+        rejected => return write_json(&mut client, &rejected).await,
+    };
+
+    let mut upstream = pool.take().await?;
+    let welcome = Response::Welcome {
+        server_version,
+        supported_features: pool.features(),
+    };
+    write_json(&mut client, &welcome).await?;
+
+    loop {
+        let payload = match read_message(&mut client).await {
+            Ok(payload) => payload,
+            Err(IpcError::ConnectionClosed) => break,
+            Err(e) => return Err(e),
+        };
+
+        // A streamed response would have to be relayed chunk by chunk; the
+        // CLI opens a direct connection for those
+        let streaming = serde_json::from_slice::<Request>(&payload)
+            .map(|request| request.is_streaming())
+            .unwrap_or(false);
+        if streaming {
+            let response = Response::error("Streamed requests are not forwarded by omnirec-proxy");
+            write_json(&mut client, &response).await?;
+            continue;
+        }
+
+        let response = forward(pool, &mut upstream, &payload).await?;
+        write_message(&mut client, &response).await?;
+    }
+
+    pool.put_back(upstream);
+    Ok(())
+}
+
+/// Check that the client is a trusted OmniRec binary run by this user,
+/// before reading anything from it.
+fn verify_client(client: UnixStream) -> Result<UnixStream, IpcError> {
+    let client = client.into_std()?;
+    verify_peer(&client).map_err(|e| {
+        IpcError::Io(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("rejected connection: {}", e),
+        ))
+    })?;
+    Ok(UnixStream::from_std(client)?)
+}
+
+/// Send one request to the service and read its response.
+///
+/// A pooled connection to a service that has since exited fails on write,
+/// in which case the request is sent on a new connection. A request that
+/// was written is never sent twice.
+async fn forward(
+    pool: &Pool,
+    upstream: &mut UnixStream,
+    payload: &[u8],
+) -> Result<Vec<u8>, IpcError> {
+    if write_message(upstream, payload).await.is_err() {
+        *upstream = pool.open().await?;
+        write_message(upstream, payload).await?;
+    }
+    read_message(upstream).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Stand-in service that answers `Ping` and counts its connections.
+    async fn fake_service(listener: UnixListener, connections: Arc<AtomicUsize>) {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            connections.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(async move {
+                read_connection_start(&mut stream).await.unwrap();
+                let _: Request = read_json(&mut stream).await.unwrap();
+                let welcome = Response::Welcome {
+                    server_version: PROTOCOL_VERSION,
+                    supported_features: vec!["dry_run".to_string()],
+                };
+                write_json(&mut stream, &welcome).await.unwrap();
+                while let Ok(request) = read_json::<_, Request>(&mut stream).await {
+                    let response = match request {
+                        Request::Ping => Response::Pong,
+                        _ => Response::error("unexpected request"),
+                    };
+                    write_json(&mut stream, &response).await.unwrap();
+                }
+            });
+        }
+    }
+
+    /// Connect to the proxy as the CLI does and send one `Ping`.
+    async fn ping_through(proxy_path: &Path) -> Vec<String> {
+        let mut stream = UnixStream::connect(proxy_path).await.unwrap();
+        write_protocol_header(&mut stream, PROTOCOL_VERSION)
+            .await
+            .unwrap();
+        let hello = Request::Hello {
+            client_version: PROTOCOL_VERSION,
+            min_protocol: MIN_PROTOCOL_VERSION,
+        };
+        write_json(&mut stream, &hello).await.unwrap();
+        let Response::Welcome {
+            supported_features, ..
+        } = read_json(&mut stream).await.unwrap()
+        else {
+            panic!("proxy did not welcome the client");
+        };
+        write_json(&mut stream, &Request::Ping).await.unwrap();
+        let response: Response = read_json(&mut stream).await.unwrap();
+        assert!(matches!(response, Response::Pong));
+        supported_features
+    }
+
+    #[tokio::test]
+    async fn test_connection_is_reused_between_clients() {
+        let dir = std::env::temp_dir().join(format!("omnirec-proxy-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let service_path = dir.join("service.sock");
+        let proxy_path = dir.join("cli-session.sock");
+
+        let connections = Arc::new(AtomicUsize::new(0));
+        let service = UnixListener::bind(&service_path).unwrap();
+        tokio::spawn(fake_service(service, connections.clone()));
+
+        let pool = Arc::new(Pool::new(service_path));
+        let listener = bind(&proxy_path).await.unwrap();
+        let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, DIRECTORY_MODE);
+        tokio::spawn(serve(listener, pool.clone()));
+
+        for _ in 0..3 {
+            assert_eq!(ping_through(&proxy_path).await, vec!["dry_run"]);
+            // The proxy returns the connection once it sees the client leave
+            for _ in 0..100 {
+                if !pool.idle.lock().unwrap().is_empty() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -2,10 +2,19 @@
 //!
 //! The CLI connects to the Tauri app via IPC socket. If the app is not running,
 //! it spawns the app in headless mode (--headless) which runs tray-only.
-
-use omnirec_types::ipc::{Request, Response, MAX_MESSAGE_SIZE};
+//!
+//! On Linux and macOS, requests go through `omnirec-proxy` when it is
+//! running, which keeps service connections open between CLI invocations.
+
+use futures_util::Stream;
+use omnirec_types::ipc::{
+    read_json, write_json, write_protocol_header, Request, Response, StreamReader,
+    MAX_MESSAGE_SIZE, MIN_PROTOCOL_VERSION, PROTOCOL_MAGIC, PROTOCOL_VERSION,
+};
+use serde::de::DeserializeOwned;
 use std::io::{Read, Write};
 use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::Mutex;
 
 use crate::exit_codes::ExitCode;
@@ -26,6 +35,8 @@ pub enum ServiceError {
     RemoteError(String),
     /// Request timed out
     Timeout,
+    /// Service speaks an IPC protocol version this CLI cannot use
+    Incompatible(String),
 }
 
 impl std::fmt::Display for ServiceError {
@@ -37,6 +48,7 @@ impl std::fmt::Display for ServiceError {
             ServiceError::ReceiveFailed(msg) => write!(f, "Receive failed: {}", msg),
             ServiceError::RemoteError(msg) => write!(f, "Service error: {}", msg),
             ServiceError::Timeout => write!(f, "Request timed out"),
+            ServiceError::Incompatible(msg) => write!(f, "Incompatible service: {}", msg),
         }
     }
 }
@@ -49,7 +61,8 @@ impl ServiceError {
         match self {
             ServiceError::NotConnected
             | ServiceError::ConnectionFailed(_)
-            | ServiceError::Timeout => ExitCode::ServiceConnectionFailed,
+            | ServiceError::Timeout
+            | ServiceError::Incompatible(_) => ExitCode::ServiceConnectionFailed,
             ServiceError::SendFailed(_) | ServiceError::ReceiveFailed(_) => {
                 ExitCode::ServiceConnectionFailed
             }
@@ -79,8 +92,13 @@ enum ConnectionState {
 /// Client for communicating with the OmniRec service.
 pub struct ServiceClient {
     connection: Mutex<ConnectionState>,
-    #[cfg(unix)]
+    /// Features announced by the service during the handshake
+    features: std::sync::Mutex<Vec<String>>,
+    /// Socket (named pipe on Windows) of this session's service
     socket_path: std::path::PathBuf,
+    /// Socket of this session's `omnirec-proxy`, tried before the service
+    #[cfg_attr(windows, allow(dead_code))]
+    proxy_path: Option<std::path::PathBuf>,
 }
 
 impl ServiceClient {
@@ -88,8 +106,9 @@ impl ServiceClient {
     pub fn new() -> Self {
         Self {
             connection: Mutex::new(ConnectionState::Disconnected),
-            #[cfg(unix)]
+            features: std::sync::Mutex::new(Vec::new()),
             socket_path: omnirec_types::ipc::get_socket_path(),
+            proxy_path: proxy_socket_path(),
         }
     }
 
@@ -99,7 +118,7 @@ impl ServiceClient {
         !matches!(*conn, ConnectionState::Disconnected)
     }
 
-    /// Connect to the service.
+    /// Connect to the service and perform the protocol handshake.
     pub async fn connect(&self) -> Result<(), ServiceError> {
         let mut conn = self.connection.lock().await;
 
@@ -112,6 +131,22 @@ impl ServiceClient {
         {
             use std::os::unix::net::UnixStream;
 
+            // A proxy that can't reach the service closes the connection
+            // during the handshake; connect directly then
+            if let Some(mut stream) = self
+                .proxy_path
+                .as_ref()
+                .and_then(|path| UnixStream::connect(path).ok())
+            {
+                stream.set_read_timeout(Some(Duration::from_secs(30))).ok();
+                stream.set_write_timeout(Some(Duration::from_secs(10))).ok();
+                if let Ok(features) = handshake(&mut stream) {
+                    *self.features.lock().unwrap() = features;
+                    *conn = ConnectionState::Connected(stream);
+                    return Ok(());
+                }
+            }
+
             let stream = UnixStream::connect(&self.socket_path).map_err(|e| {
                 ServiceError::ConnectionFailed(format!(
                     "Failed to connect to {}: {}",
@@ -124,6 +159,8 @@ impl ServiceClient {
             stream.set_read_timeout(Some(Duration::from_secs(30))).ok();
             stream.set_write_timeout(Some(Duration::from_secs(10))).ok();
 
+            let mut stream = stream;
+            *self.features.lock().unwrap() = handshake(&mut stream)?;
             *conn = ConnectionState::Connected(stream);
             Ok(())
         }
@@ -132,7 +169,7 @@ impl ServiceClient {
         {
             use std::fs::OpenOptions;
 
-            let pipe_path = r"\\.\pipe\omnirec-service";
+            let pipe_path = &self.socket_path;
 
             let file = OpenOptions::new()
                 .read(true)
@@ -150,11 +187,14 @@ impl ServiceClient {
                     } else {
                         ServiceError::ConnectionFailed(format!(
                             "Failed to connect to {}: {}",
-                            pipe_path, e
+                            pipe_path.display(),
+                            e
                         ))
                     }
                 })?;
 
+            let mut file = file;
+            *self.features.lock().unwrap() = handshake(&mut file)?;
             *conn = ConnectionState::Connected(file);
             Ok(())
         }
@@ -170,8 +210,7 @@ impl ServiceClient {
     /// Check if the service is available (Windows: named pipe exists).
     #[cfg(windows)]
     fn is_service_available(&self) -> bool {
-        let pipe_path = r"\\.\pipe\omnirec-service";
-        std::path::Path::new(pipe_path).exists()
+        self.socket_path.exists()
     }
 
     /// Send a request to the service and wait for a response.
@@ -182,149 +221,115 @@ impl ServiceClient {
         }
 
         let mut conn = self.connection.lock().await;
-
-        #[cfg(unix)]
-        {
-            let stream = match &mut *conn {
-                ConnectionState::Connected(s) => s,
-                ConnectionState::Disconnected => {
-                    return Err(ServiceError::NotConnected);
-                }
-            };
-
-            // Serialize request
-            let request_json = serde_json::to_vec(&request).map_err(|e| {
-                ServiceError::SendFailed(format!("Failed to serialize request: {}", e))
-            })?;
-
-            // Send length-prefixed message
-            let len = request_json.len() as u32;
-            stream
-                .write_all(&len.to_le_bytes())
-                .map_err(|e| ServiceError::SendFailed(format!("Failed to write length: {}", e)))?;
-            stream
-                .write_all(&request_json)
-                .map_err(|e| ServiceError::SendFailed(format!("Failed to write request: {}", e)))?;
-            stream
-                .flush()
-                .map_err(|e| ServiceError::SendFailed(format!("Failed to flush: {}", e)))?;
-
-            // Read response length
-            let mut len_buf = [0u8; 4];
-            stream.read_exact(&mut len_buf).map_err(|e| {
-                ServiceError::ReceiveFailed(format!("Failed to read response length: {}", e))
-            })?;
-            let response_len = u32::from_le_bytes(len_buf) as usize;
-
-            // Validate response length
-            if response_len > MAX_MESSAGE_SIZE {
-                return Err(ServiceError::ReceiveFailed(format!(
-                    "Response too large: {} bytes",
-                    response_len
-                )));
+        let stream = match &mut *conn {
+            ConnectionState::Connected(s) => s,
+            ConnectionState::Disconnected => {
+                return Err(ServiceError::NotConnected);
             }
+        };
 
-            // Read response body
-            let mut response_buf = vec![0u8; response_len];
-            stream.read_exact(&mut response_buf).map_err(|e| {
-                ServiceError::ReceiveFailed(format!("Failed to read response: {}", e))
-            })?;
-
-            // Deserialize response
-            let response: Response = serde_json::from_slice(&response_buf).map_err(|e| {
-                ServiceError::ReceiveFailed(format!("Failed to deserialize response: {}", e))
-            })?;
-
-            // Check for service error
-            if let Response::Error { message } = &response {
-                return Err(ServiceError::RemoteError(message.clone()));
-            }
+        let response = exchange(stream, &request)?;
 
-            Ok(response)
+        // Check for service error
+        if let Response::Error { message } = &response {
+            return Err(ServiceError::RemoteError(message.clone()));
         }
 
-        #[cfg(windows)]
-        {
-            let file = match &mut *conn {
-                ConnectionState::Connected(f) => f,
-                ConnectionState::Disconnected => {
-                    return Err(ServiceError::NotConnected);
-                }
-            };
-
-            // Serialize request
-            let request_json = serde_json::to_vec(&request).map_err(|e| {
-                ServiceError::SendFailed(format!("Failed to serialize request: {}", e))
-            })?;
-
-            // Send length-prefixed message
-            let len = request_json.len() as u32;
-            file.write_all(&len.to_le_bytes())
-                .map_err(|e| ServiceError::SendFailed(format!("Failed to write length: {}", e)))?;
-            file.write_all(&request_json)
-                .map_err(|e| ServiceError::SendFailed(format!("Failed to write request: {}", e)))?;
-            file.flush()
-                .map_err(|e| ServiceError::SendFailed(format!("Failed to flush: {}", e)))?;
-
-            // Read response length
-            let mut len_buf = [0u8; 4];
-            file.read_exact(&mut len_buf).map_err(|e| {
-                ServiceError::ReceiveFailed(format!("Failed to read response length: {}", e))
-            })?;
-            let response_len = u32::from_le_bytes(len_buf) as usize;
-
-            // Validate response length
-            if response_len > MAX_MESSAGE_SIZE {
-                return Err(ServiceError::ReceiveFailed(format!(
-                    "Response too large: {} bytes",
-                    response_len
-                )));
-            }
+        Ok(response)
+    }
 
-            // Read response body
-            let mut response_buf = vec![0u8; response_len];
-            file.read_exact(&mut response_buf).map_err(|e| {
-                ServiceError::ReceiveFailed(format!("Failed to read response: {}", e))
+    /// Send a request answered with a streamed response (see
+    /// [`Request::is_streaming`]) and yield its items as they arrive.
+    ///
+    /// The stream gets a connection of its own, so a large response doesn't
+    /// hold up other requests. Service errors arrive as `Response::Error`
+    /// items.
+    #[allow(dead_code)]
+    pub async fn send_streaming_request<T: DeserializeOwned>(
+        &self,
+        request: &Request,
+    ) -> Result<impl Stream<Item = Result<T, ServiceError>>, ServiceError> {
+        #[cfg(unix)]
+        let mut stream = tokio::net::UnixStream::connect(&self.socket_path)
+            .await
+            .map_err(|e| {
+                ServiceError::ConnectionFailed(format!(
+                    "Failed to connect to {}: {}",
+                    self.socket_path.display(),
+                    e
+                ))
             })?;
-
-            // Deserialize response
-            let response: Response = serde_json::from_slice(&response_buf).map_err(|e| {
-                ServiceError::ReceiveFailed(format!("Failed to deserialize response: {}", e))
+        #[cfg(windows)]
+        let mut stream = tokio::net::windows::named_pipe::ClientOptions::new()
+            .open(&self.socket_path)
+            .map_err(|e| {
+                ServiceError::ConnectionFailed(format!(
+                    "Failed to connect to {}: {}",
+                    self.socket_path.display(),
+                    e
+                ))
             })?;
 
-            // Check for service error
-            if let Response::Error { message } = &response {
-                return Err(ServiceError::RemoteError(message.clone()));
-            }
+        handshake_async(&mut stream).await?;
+        write_json(&mut stream, request)
+            .await
+            .map_err(|e| ServiceError::SendFailed(format!("Failed to write request: {}", e)))?;
+
+        let reader = StreamReader::new(stream);
+        Ok(futures_util::stream::unfold(
+            Some(reader),
+            |reader| async move {
+                let mut reader = reader?;
+                match reader.next_item().await {
+                    Ok(Some(item)) => Some((Ok(item), Some(reader))),
+                    Ok(None) => None,
+                    Err(e) => Some((
+                        Err(ServiceError::ReceiveFailed(format!(
+                            "Failed to read streamed response: {}",
+                            e
+                        ))),
+                        None,
+                    )),
+                }
+            },
+        ))
+    }
 
-            Ok(response)
-        }
+    /// Whether the connected service announced `feature` (see
+    /// [`omnirec_types::ipc::features`]) during the handshake.
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|supported| supported == feature)
     }
 
     /// Wait for the service to become available.
     pub async fn wait_for_service(&self, timeout: Duration) -> Result<(), ServiceError> {
-        let start = std::time::Instant::now();
         let poll_interval = Duration::from_millis(100);
 
-        while start.elapsed() < timeout {
-            #[cfg(unix)]
-            let exists = self.socket_path.exists();
-            #[cfg(windows)]
-            let exists = self.is_service_available();
-
-            if exists {
-                match self.connect().await {
-                    Ok(()) => return Ok(()),
-                    Err(_) => {
-                        tokio::time::sleep(poll_interval).await;
+        let poll = async {
+            loop {
+                #[cfg(unix)]
+                let exists = self.socket_path.exists();
+                #[cfg(windows)]
+                let exists = self.is_service_available();
+
+                if exists {
+                    match self.connect().await {
+                        Ok(()) => return Ok(()),
+                        Err(e @ ServiceError::Incompatible(_)) => return Err(e),
+                        Err(_) => {}
                     }
                 }
-            } else {
                 tokio::time::sleep(poll_interval).await;
             }
-        }
+        };
 
-        Err(ServiceError::Timeout)
+        tokio::time::timeout(timeout, poll)
+            .await
+            .map_err(|_| ServiceError::Timeout)?
     }
 
     /// Ping the service.
@@ -339,29 +344,54 @@ impl ServiceClient {
     }
 
     /// Connect to the service, spawning the Tauri app if necessary.
+    ///
+    /// If the spawned app does not accept connections within the configured
+    /// `service_startup_timeout_secs`, it is killed and an error is returned.
     pub async fn connect_or_spawn(&self) -> Result<(), ServiceError> {
         // First try to just connect
-        if self.connect().await.is_ok() {
-            return Ok(());
+        match self.connect().await {
+            Ok(()) => return Ok(()),
+            // A service is running; spawning another would not help
+            Err(e @ ServiceError::Incompatible(_)) => return Err(e),
+            Err(_) => {}
         }
 
         // Connection failed, try to spawn the app
-        Self::spawn_app().map_err(|e| {
-            ServiceError::ConnectionFailed(format!("Failed to spawn app: {}", e))
-        })?;
+        let mut child = Self::spawn_app(&self.socket_path)
+            .map_err(|e| ServiceError::ConnectionFailed(format!("Failed to spawn app: {}", e)))?;
 
         // Wait for service to be ready
-        self.wait_for_service(Duration::from_secs(10)).await
+        let timeout = startup_timeout();
+        match self.wait_for_service(timeout).await {
+            Err(ServiceError::Timeout) => {
+                if let Some(child) = child.as_mut() {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                }
+                Err(ServiceError::ConnectionFailed(format!(
+                    "Service did not become ready within {}s",
+                    timeout.as_secs()
+                )))
+            }
+            result => result,
+        }
     }
 
-    /// Spawn the OmniRec Tauri app in headless mode.
-    fn spawn_app() -> Result<(), String> {
+    /// Spawn the OmniRec Tauri app in headless mode, serving `socket_path`.
+    ///
+    /// The path is passed with `--socket` because an app launched through
+    /// macOS Launch Services does not inherit the CLI's environment.
+    ///
+    /// Returns the child process when it was spawned directly (it is `None`
+    /// when launched through macOS Launch Services).
+    fn spawn_app(socket_path: &std::path::Path) -> Result<Option<std::process::Child>, String> {
         #[cfg(target_os = "macos")]
         {
             // On macOS, prefer using `open -a OmniRec --args --headless`
             // This uses Launch Services and finds the app bundle correctly
             let result = std::process::Command::new("open")
-                .args(["-a", "OmniRec", "--args", "--headless"])
+                .args(["-a", "OmniRec", "--args", "--headless", "--socket"])
+                .arg(socket_path)
                 .stdin(std::process::Stdio::null())
                 .stdout(std::process::Stdio::null())
                 .stderr(std::process::Stdio::null())
@@ -369,20 +399,22 @@ impl ServiceClient {
 
             if let Ok(status) = result {
                 if status.success() {
-                    return Ok(());
+                    return Ok(None);
                 }
             }
 
             // Fall back to binary search if `open` fails
             if let Some(app_path) = Self::find_app_binary() {
-                std::process::Command::new(&app_path)
+                let child = std::process::Command::new(&app_path)
                     .arg("--headless")
+                    .arg("--socket")
+                    .arg(socket_path)
                     .stdin(std::process::Stdio::null())
                     .stdout(std::process::Stdio::null())
                     .stderr(std::process::Stdio::null())
                     .spawn()
                     .map_err(|e| format!("Failed to spawn app: {}", e))?;
-                return Ok(());
+                return Ok(Some(child));
             }
 
             Err("OmniRec app not found".to_string())
@@ -391,14 +423,16 @@ impl ServiceClient {
         #[cfg(target_os = "linux")]
         {
             if let Some(app_path) = Self::find_app_binary() {
-                std::process::Command::new(&app_path)
+                let child = std::process::Command::new(&app_path)
                     .arg("--headless")
+                    .arg("--socket")
+                    .arg(socket_path)
                     .stdin(std::process::Stdio::null())
                     .stdout(std::process::Stdio::null())
                     .stderr(std::process::Stdio::null())
                     .spawn()
                     .map_err(|e| format!("Failed to spawn app: {}", e))?;
-                return Ok(());
+                return Ok(Some(child));
             }
             Err("omnirec binary not found".to_string())
         }
@@ -406,14 +440,16 @@ impl ServiceClient {
         #[cfg(target_os = "windows")]
         {
             if let Some(app_path) = Self::find_app_binary() {
-                std::process::Command::new(&app_path)
+                let child = std::process::Command::new(&app_path)
                     .arg("--headless")
+                    .arg("--socket")
+                    .arg(socket_path)
                     .stdin(std::process::Stdio::null())
                     .stdout(std::process::Stdio::null())
                     .stderr(std::process::Stdio::null())
                     .spawn()
                     .map_err(|e| format!("Failed to spawn app: {}", e))?;
-                return Ok(());
+                return Ok(Some(child));
             }
             Err("omnirec.exe not found".to_string())
         }
@@ -442,7 +478,11 @@ impl ServiceClient {
                 if let Some(workspace_root) = exe_path.parent().and_then(|p| p.parent()) {
                     // Check src-tauri/target/release/omnirec or src-tauri/target/debug/omnirec
                     for target_dir in ["release", "debug"] {
-                        let path = workspace_root.join("src-tauri").join("target").join(target_dir).join("omnirec");
+                        let path = workspace_root
+                            .join("src-tauri")
+                            .join("target")
+                            .join(target_dir)
+                            .join("omnirec");
                         if path.exists() {
                             return Some(path);
                         }
@@ -470,7 +510,11 @@ impl ServiceClient {
             if let Ok(exe_path) = std::env::current_exe() {
                 if let Some(workspace_root) = exe_path.parent().and_then(|p| p.parent()) {
                     for target_dir in ["release", "debug"] {
-                        let path = workspace_root.join("src-tauri").join("target").join(target_dir).join("omnirec");
+                        let path = workspace_root
+                            .join("src-tauri")
+                            .join("target")
+                            .join(target_dir)
+                            .join("omnirec");
                         if path.exists() {
                             return Some(path);
                         }
@@ -488,7 +532,11 @@ impl ServiceClient {
             }
 
             // 3. Common installation paths
-            let common_paths = ["/usr/bin/omnirec", "/usr/local/bin/omnirec", "/opt/omnirec/bin/omnirec"];
+            let common_paths = [
+                "/usr/bin/omnirec",
+                "/usr/local/bin/omnirec",
+                "/opt/omnirec/bin/omnirec",
+            ];
             for path in &common_paths {
                 let path = std::path::PathBuf::from(path);
                 if path.exists() {
@@ -511,7 +559,11 @@ impl ServiceClient {
             if let Ok(exe_path) = std::env::current_exe() {
                 if let Some(workspace_root) = exe_path.parent().and_then(|p| p.parent()) {
                     for target_dir in ["release", "debug"] {
-                        let path = workspace_root.join("src-tauri").join("target").join(target_dir).join("omnirec.exe");
+                        let path = workspace_root
+                            .join("src-tauri")
+                            .join("target")
+                            .join(target_dir)
+                            .join("omnirec.exe");
                         if path.exists() {
                             return Some(path);
                         }
@@ -550,6 +602,141 @@ impl ServiceClient {
     }
 }
 
+/// Send one length-prefixed request and read the response.
+fn exchange<S: Read + Write>(stream: &mut S, request: &Request) -> Result<Response, ServiceError> {
+    // Serialize request
+    let request_json = serde_json::to_vec(request)
+        .map_err(|e| ServiceError::SendFailed(format!("Failed to serialize request: {}", e)))?;
+
+    // Send length-prefixed message
+    let len = request_json.len() as u32;
+    stream
+        .write_all(&len.to_le_bytes())
+        .map_err(|e| ServiceError::SendFailed(format!("Failed to write length: {}", e)))?;
+    stream
+        .write_all(&request_json)
+        .map_err(|e| ServiceError::SendFailed(format!("Failed to write request: {}", e)))?;
+    stream
+        .flush()
+        .map_err(|e| ServiceError::SendFailed(format!("Failed to flush: {}", e)))?;
+
+    // Read response length
+    let mut len_buf = [0u8; 4];
+    stream.read_exact(&mut len_buf).map_err(|e| {
+        ServiceError::ReceiveFailed(format!("Failed to read response length: {}", e))
+    })?;
+    let response_len = u32::from_le_bytes(len_buf) as usize;
+
+    // Validate response length
+    if response_len > MAX_MESSAGE_SIZE {
+        return Err(ServiceError::ReceiveFailed(format!(
+            "Response too large: {} bytes",
+            response_len
+        )));
+    }
+
+    // Read response body
+    let mut response_buf = vec![0u8; response_len];
+    stream
+        .read_exact(&mut response_buf)
+        .map_err(|e| ServiceError::ReceiveFailed(format!("Failed to read response: {}", e)))?;
+
+    // Deserialize response
+    serde_json::from_slice(&response_buf)
+        .map_err(|e| ServiceError::ReceiveFailed(format!("Failed to deserialize response: {}", e)))
+}
+
+/// Send the protocol header and `Hello`; returns the service's features.
+fn handshake<S: Read + Write>(stream: &mut S) -> Result<Vec<String>, ServiceError> {
+    let mut header = [0u8; 8];
+    header[..4].copy_from_slice(&PROTOCOL_MAGIC);
+    header[4..].copy_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+    stream
+        .write_all(&header)
+        .map_err(|e| ServiceError::SendFailed(format!("Failed to write header: {}", e)))?;
+
+    let hello = Request::Hello {
+        client_version: PROTOCOL_VERSION,
+        min_protocol: MIN_PROTOCOL_VERSION,
+    };
+    welcome_features(exchange(stream, &hello))
+}
+
+/// [`handshake`] over an async stream, for streamed responses.
+async fn handshake_async<S>(stream: &mut S) -> Result<Vec<String>, ServiceError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    write_protocol_header(stream, PROTOCOL_VERSION)
+        .await
+        .map_err(|e| ServiceError::SendFailed(format!("Failed to write header: {}", e)))?;
+
+    let hello = Request::Hello {
+        client_version: PROTOCOL_VERSION,
+        min_protocol: MIN_PROTOCOL_VERSION,
+    };
+    write_json(stream, &hello)
+        .await
+        .map_err(|e| ServiceError::SendFailed(format!("Failed to write request: {}", e)))?;
+    let response = read_json(stream)
+        .await
+        .map_err(|e| ServiceError::ReceiveFailed(format!("Failed to read response: {}", e)));
+    welcome_features(response)
+}
+
+/// The features announced in the service's answer to `Hello`.
+fn welcome_features(response: Result<Response, ServiceError>) -> Result<Vec<String>, ServiceError> {
+    match response {
+        Ok(Response::Welcome {
+            supported_features, ..
+        }) => Ok(supported_features),
+        Ok(Response::IncompatibleVersion { min, max }) => Err(ServiceError::Incompatible(format!(
+            "service speaks IPC protocol {}-{}, this CLI speaks {}",
+            min, max, PROTOCOL_VERSION
+        ))),
+        Ok(Response::Error { message }) => Err(ServiceError::RemoteError(message)),
+        Ok(other) => Err(ServiceError::Incompatible(format!(
+            "unexpected handshake response: {:?}",
+            other
+        ))),
+        // Services without versioning drop the connection on the header
+        Err(ServiceError::ReceiveFailed(_)) => Err(ServiceError::Incompatible(
+            "no handshake response; the service may be older than this CLI".to_string(),
+        )),
+        Err(e) => Err(e),
+    }
+}
+
+/// Socket of this session's `omnirec-proxy`. Not used when `OMNIREC_SOCKET`
+/// names the service socket explicitly, since the proxy may serve another.
+fn proxy_socket_path() -> Option<std::path::PathBuf> {
+    if std::env::var_os(omnirec_types::ipc::SOCKET_PATH_ENV).is_some_and(|path| !path.is_empty()) {
+        return None;
+    }
+    #[cfg(unix)]
+    {
+        omnirec_types::ipc::get_proxy_socket_path()
+    }
+    #[cfg(windows)]
+    {
+        None
+    }
+}
+
+/// Default time to wait for a freshly spawned app to accept connections.
+const DEFAULT_STARTUP_TIMEOUT_SECS: u64 = 15;
+
+/// Read `service_startup_timeout_secs` from the app's config file, falling
+/// back to the default when the file or field is missing.
+fn startup_timeout() -> Duration {
+    directories::ProjectDirs::from("", "", "omnirec")
+        .and_then(|dirs| std::fs::read_to_string(dirs.config_dir().join("config.json")).ok())
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        .and_then(|config| config.get("service_startup_timeout_secs")?.as_u64())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(DEFAULT_STARTUP_TIMEOUT_SECS))
+}
+
 impl Default for ServiceClient {
     fn default() -> Self {
         Self::new()
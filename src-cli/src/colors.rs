@@ -53,6 +53,24 @@ pub fn success(msg: &str) -> String {
     }
 }
 
+/// Style for failed checks printed to stdout.
+pub fn failure(msg: &str) -> String {
+    if is_interactive() {
+        format!("{}", msg.red().bold())
+    } else {
+        msg.to_string()
+    }
+}
+
+/// Style for checks that passed with a warning, printed to stdout.
+pub fn caution(msg: &str) -> String {
+    if is_interactive() {
+        format!("{}", msg.yellow().bold())
+    } else {
+        msg.to_string()
+    }
+}
+
 /// Style for info/status messages.
 pub fn info(msg: &str) -> String {
     if is_interactive() {
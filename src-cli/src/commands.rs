@@ -4,10 +4,12 @@ use crate::client::{ServiceClient, ServiceError};
 use crate::colors;
 use crate::exit_codes::ExitCode;
 use crate::platform;
-use crate::RecordTarget;
-use omnirec_types::ipc::{Request, Response};
+use crate::{RecordTarget, ScreenshotTarget};
+use omnirec_types::integrity;
+use omnirec_types::ipc::{features, Request, Response};
 use omnirec_types::{AudioSourceType, OutputFormat, RecordingState};
 use std::io::Write;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
@@ -447,16 +449,36 @@ pub async fn record(target: RecordTarget, json: bool, quiet: bool, verbose: bool
             }
             (options, Request::StartPortalCapture)
         }
+        RecordTarget::Workspace { id, options } => {
+            if !platform::is_hyprland() {
+                if !quiet {
+                    eprintln!(
+                        "{}",
+                        colors::error("Workspace recording requires the Hyprland compositor.")
+                    );
+                    eprintln!("Use 'omnirec record display' instead.");
+                }
+                return ExitCode::GeneralError;
+            }
+            (
+                options,
+                Request::StartWorkspaceCapture { workspace_id: *id },
+            )
+        }
     };
 
     // Validate output format
-    if OutputFormat::parse(&options.format).is_none() {
+    if let Some(format) = options
+        .format
+        .as_deref()
+        .filter(|format| OutputFormat::parse(format).is_none())
+    {
         if !quiet {
             eprintln!(
                 "{}",
                 colors::error(&format!(
                     "Invalid format '{}'. Valid formats: mp4, webm, mkv, mov, gif, apng, webp",
-                    options.format
+                    format
                 ))
             );
         }
@@ -487,6 +509,31 @@ pub async fn record(target: RecordTarget, json: bool, quiet: bool, verbose: bool
         eprintln!("Connected to service.");
     }
 
+    // Refuse requests the running service does not understand
+    let required = if options.dry_run {
+        Some((features::DRY_RUN, "dry runs"))
+    } else if matches!(request, Request::StartWorkspaceCapture { .. }) {
+        Some((features::WORKSPACE_CAPTURE, "workspace recording"))
+    } else if options.limit_fps.is_some() {
+        Some((features::FRAME_RATE_LIMIT, "--limit-fps"))
+    } else {
+        None
+    };
+    if let Some((feature, name)) = required {
+        if !client.supports(feature) {
+            if !quiet {
+                eprintln!(
+                    "{}",
+                    colors::error(&format!(
+                        "The running OmniRec app does not support {}. Update it and try again.",
+                        name
+                    ))
+                );
+            }
+            return ExitCode::GeneralError;
+        }
+    }
+
     // Configure audio if specified
     if options.audio.is_some() || options.microphone.is_some() {
         let audio_enabled = options.audio.as_deref() != Some("none")
@@ -520,11 +567,16 @@ pub async fn record(target: RecordTarget, json: bool, quiet: bool, verbose: bool
         }
     }
 
-    // Set output format if not default
-    if options.format != "mp4" {
+    if options.dry_run {
+        return dry_run(&client, request, quiet).await;
+    }
+
+    // Set output format if one was given; otherwise the service's own
+    // choice applies
+    if let Some(format) = &options.format {
         if let Err(e) = client
             .request(Request::SetOutputFormat {
-                format: options.format.clone(),
+                format: format.clone(),
             })
             .await
         {
@@ -537,11 +589,40 @@ pub async fn record(target: RecordTarget, json: bool, quiet: bool, verbose: bool
         } else if verbose && !quiet {
             eprintln!(
                 "{}",
-                colors::info(&format!("Output format set to {}.", options.format))
+                colors::info(&format!("Output format set to {}.", format))
+            );
+        }
+    }
+
+    // Limit the capture frame rate for this recording
+    if let Some(max_fps) = options.limit_fps {
+        if let Err(e) = client
+            .request(Request::SetFrameRateLimit {
+                max_fps: Some(max_fps),
+            })
+            .await
+        {
+            if !quiet {
+                eprintln!(
+                    "{}",
+                    colors::warning(&format!("Failed to limit frame rate: {}", e))
+                );
+            }
+        } else if verbose && !quiet {
+            eprintln!(
+                "{}",
+                colors::info(&format!("Capture limited to {} fps.", max_fps))
             );
         }
     }
 
+    // Count down as configured in the app
+    if client.supports(features::COUNTDOWN) {
+        if let Ok(Response::Countdown { seconds }) = client.request(Request::GetCountdown).await {
+            countdown(seconds, quiet).await;
+        }
+    }
+
     // Start recording
     match client.request(request).await {
         Ok(Response::RecordingStarted) => {
@@ -648,18 +729,16 @@ pub async fn record(target: RecordTarget, json: bool, quiet: bool, verbose: bool
         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
         match client.request(Request::GetElapsedTime).await {
-            Ok(Response::ElapsedTime { seconds }) => {
-                if !quiet && !json && seconds != last_elapsed {
-                    last_elapsed = seconds;
-                    let mins = seconds / 60;
-                    let secs = seconds % 60;
-                    print!(
-                        "\r{} {}",
-                        colors::recording("Recording:"),
-                        colors::elapsed_time(mins, secs)
-                    );
-                    std::io::stdout().flush().ok();
-                }
+            Ok(Response::ElapsedTime { seconds }) if !quiet && !json && seconds != last_elapsed => {
+                last_elapsed = seconds;
+                let mins = seconds / 60;
+                let secs = seconds % 60;
+                print!(
+                    "\r{} {}",
+                    colors::recording("Recording:"),
+                    colors::elapsed_time(mins, secs)
+                );
+                std::io::stdout().flush().ok();
             }
             Ok(Response::RecordingState {
                 state: RecordingState::Idle,
@@ -683,10 +762,7 @@ pub async fn record(target: RecordTarget, json: bool, quiet: bool, verbose: bool
 
     // Stop recording
     match client.request(Request::StopRecording).await {
-        Ok(Response::RecordingStopped {
-            file_path,
-            source_path: _,
-        }) => {
+        Ok(Response::RecordingStopped { file_path, .. }) => {
             if json {
                 println!(
                     r#"{{"status": "recording_stopped", "file_path": "{}"}}"#,
@@ -722,6 +798,64 @@ pub async fn record(target: RecordTarget, json: bool, quiet: bool, verbose: bool
     }
 }
 
+/// Wait `seconds`, printing the time left to stderr.
+async fn countdown(seconds: u8, quiet: bool) {
+    for remaining in (1..=seconds).rev() {
+        if !quiet {
+            eprint!(
+                "\r{}",
+                colors::info(&format!("Recording starts in {}...", remaining))
+            );
+            std::io::stderr().flush().ok();
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+    if seconds > 0 && !quiet {
+        eprintln!();
+    }
+}
+
+/// Run a dry-run capture of the record target and print a JSON summary.
+async fn dry_run(client: &ServiceClient, capture: Request, quiet: bool) -> ExitCode {
+    match client
+        .request(Request::DryRunCapture {
+            capture: Box::new(capture),
+        })
+        .await
+    {
+        Ok(Response::DryRunResult {
+            frames_captured,
+            encoder_ok,
+            audio_ok,
+        }) => {
+            if !quiet {
+                let summary = serde_json::json!({
+                    "frames_captured": frames_captured,
+                    "encoder_ok": encoder_ok,
+                    "audio_ok": audio_ok,
+                });
+                println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+            }
+            ExitCode::Success
+        }
+        Ok(other) => {
+            if !quiet {
+                eprintln!(
+                    "{}",
+                    colors::error(&format!("Unexpected response: {:?}", other))
+                );
+            }
+            ExitCode::RecordingFailedToStart
+        }
+        Err(e) => {
+            if !quiet {
+                eprintln!("{}", colors::error(&format!("Dry run failed: {}", e)));
+            }
+            ExitCode::RecordingFailedToStart
+        }
+    }
+}
+
 /// Stop the current recording.
 pub async fn stop(json: bool, quiet: bool) -> ExitCode {
     let client = ServiceClient::new();
@@ -734,10 +868,7 @@ pub async fn stop(json: bool, quiet: bool) -> ExitCode {
     }
 
     match client.request(Request::StopRecording).await {
-        Ok(Response::RecordingStopped {
-            file_path,
-            source_path: _,
-        }) => {
+        Ok(Response::RecordingStopped { file_path, .. }) => {
             if json {
                 println!(
                     r#"{{"status": "stopped", "file_path": "{}"}}"#,
@@ -778,6 +909,72 @@ pub async fn stop(json: bool, quiet: bool) -> ExitCode {
     }
 }
 
+/// Save a screenshot of `target`, or of the primary display.
+pub async fn screenshot(target: Option<ScreenshotTarget>, json: bool, quiet: bool) -> ExitCode {
+    let client = ServiceClient::new();
+
+    if let Err(e) = client.connect_or_spawn().await {
+        if !quiet {
+            eprintln!("{}", colors::error(&e.to_string()));
+        }
+        return e.to_exit_code();
+    }
+
+    if !client.supports(features::SCREENSHOT) {
+        if !quiet {
+            eprintln!(
+                "{}",
+                colors::error(
+                    "The running OmniRec app does not support screenshots. Update it and try again."
+                )
+            );
+        }
+        return ExitCode::GeneralError;
+    }
+
+    let target = target.map(|target| match target {
+        ScreenshotTarget::Window { handle } => omnirec_types::ScreenshotTarget::Window {
+            window_handle: handle,
+        },
+        ScreenshotTarget::Display { id } => {
+            omnirec_types::ScreenshotTarget::Display { monitor_id: id }
+        }
+    });
+
+    match client.request(Request::TakeScreenshot { target }).await {
+        Ok(Response::ScreenshotSaved { path }) => {
+            if json {
+                println!(
+                    r#"{{"status": "saved", "path": "{}"}}"#,
+                    path.replace('\\', "\\\\").replace('"', "\\\"")
+                );
+            } else if !quiet {
+                println!(
+                    "{} {}",
+                    colors::success("Screenshot saved:"),
+                    colors::path(&path)
+                );
+            }
+            ExitCode::Success
+        }
+        Ok(other) => {
+            if !quiet {
+                eprintln!(
+                    "{}",
+                    colors::error(&format!("Unexpected response: {:?}", other))
+                );
+            }
+            ExitCode::GeneralError
+        }
+        Err(e) => {
+            if !quiet {
+                eprintln!("{}", colors::error(&e.to_string()));
+            }
+            e.to_exit_code()
+        }
+    }
+}
+
 /// Show current recording status.
 pub async fn status(json: bool) -> ExitCode {
     let client = ServiceClient::new();
@@ -796,10 +993,11 @@ pub async fn status(json: bool) -> ExitCode {
             let state_str = match state {
                 RecordingState::Idle => "idle",
                 RecordingState::Recording => "recording",
+                RecordingState::Suspended => "suspended",
                 RecordingState::Saving => "saving",
             };
 
-            if state == RecordingState::Recording {
+            if matches!(state, RecordingState::Recording | RecordingState::Suspended) {
                 // Get elapsed time
                 match client.request(Request::GetElapsedTime).await {
                     Ok(Response::ElapsedTime { seconds }) => {
@@ -856,6 +1054,30 @@ pub async fn status(json: bool) -> ExitCode {
     }
 }
 
+/// Check a recording against its checksum file, printing `OK` or `CORRUPTED`.
+pub fn verify(path: &Path, json: bool, quiet: bool) -> ExitCode {
+    let result = integrity::verify(path);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result).unwrap());
+    } else if let Some(error) = &result.error {
+        if !quiet {
+            eprintln!("{}", colors::error(error));
+        }
+    } else if result.ok {
+        println!("{}", colors::success("OK"));
+    } else {
+        println!("{}", colors::failure("CORRUPTED"));
+    }
+
+    if result.error.is_some() {
+        ExitCode::GeneralError
+    } else if result.ok {
+        ExitCode::Success
+    } else {
+        ExitCode::IntegrityCheckFailed
+    }
+}
+
 /// Show version information.
 pub fn version(json: bool) {
     let version = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,249 @@
+//! Shell completion scripts.
+//!
+//! `omnirec completions <shell>` prints a completion script to stdout.
+//! `omnirec completions --install` adds a line to the current shell's rc
+//! file that loads the script on startup, so it stays in sync with the CLI.
+
+use clap::{Command, ValueEnum};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::colors;
+use crate::exit_codes::ExitCode;
+
+/// Shells completion scripts can be generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl CompletionShell {
+    fn generator(self) -> clap_complete::Shell {
+        match self {
+            CompletionShell::Bash => clap_complete::Shell::Bash,
+            CompletionShell::Zsh => clap_complete::Shell::Zsh,
+            CompletionShell::Fish => clap_complete::Shell::Fish,
+            CompletionShell::Powershell => clap_complete::Shell::PowerShell,
+        }
+    }
+
+    /// Detect the shell from the value of `$SHELL`.
+    ///
+    /// Windows has no `$SHELL`, so PowerShell is assumed there.
+    pub fn detect(shell_env: Option<&str>) -> Option<Self> {
+        let Some(shell) = shell_env else {
+            return cfg!(windows).then_some(CompletionShell::Powershell);
+        };
+        let name = Path::new(shell).file_stem()?.to_str()?;
+        match name {
+            "bash" => Some(CompletionShell::Bash),
+            "zsh" => Some(CompletionShell::Zsh),
+            "fish" => Some(CompletionShell::Fish),
+            "pwsh" | "powershell" => Some(CompletionShell::Powershell),
+            _ => None,
+        }
+    }
+
+    /// Startup file the loader line is appended to.
+    fn rc_file(self, home: &Path, documents: Option<&Path>) -> PathBuf {
+        match self {
+            CompletionShell::Bash => home.join(".bashrc"),
+            CompletionShell::Zsh => home.join(".zshrc"),
+            CompletionShell::Fish => home.join(".config").join("fish").join("config.fish"),
+            CompletionShell::Powershell => {
+                let base = if cfg!(windows) {
+                    documents.unwrap_or(home).join("PowerShell")
+                } else {
+                    home.join(".config").join("powershell")
+                };
+                base.join("Microsoft.PowerShell_profile.ps1")
+            }
+        }
+    }
+
+    /// Line that loads the completion script when the shell starts.
+    fn loader_line(self) -> &'static str {
+        match self {
+            CompletionShell::Bash => r#"eval "$(omnirec completions bash)""#,
+            CompletionShell::Zsh => "source <(omnirec completions zsh)",
+            CompletionShell::Fish => "omnirec completions fish | source",
+            CompletionShell::Powershell => {
+                "omnirec completions powershell | Out-String | Invoke-Expression"
+            }
+        }
+    }
+}
+
+/// Write the completion script for `shell` to `out`.
+pub fn generate(shell: CompletionShell, cmd: &mut Command, out: &mut dyn Write) {
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell.generator(), cmd, name, out);
+}
+
+/// Append the loader line for `shell` to its rc file, unless it is already
+/// there. Returns the rc file and whether it was changed.
+fn install(shell: CompletionShell) -> Result<(PathBuf, bool), String> {
+    let home = directories::BaseDirs::new()
+        .map(|dirs| dirs.home_dir().to_path_buf())
+        .ok_or("Could not determine home directory")?;
+    let documents = directories::UserDirs::new()
+        .and_then(|dirs| dirs.document_dir().map(|documents| documents.to_path_buf()));
+    let rc_file = shell.rc_file(&home, documents.as_deref());
+    let line = shell.loader_line();
+
+    let existing = std::fs::read_to_string(&rc_file).unwrap_or_default();
+    if existing.lines().any(|l| l.trim() == line) {
+        return Ok((rc_file, false));
+    }
+
+    if let Some(parent) = rc_file.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&rc_file)
+        .map_err(|e| format!("Failed to open {}: {}", rc_file.display(), e))?;
+    let separator = if existing.is_empty() || existing.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    write!(file, "{}\n# OmniRec completions\n{}\n", separator, line)
+        .map_err(|e| format!("Failed to write {}: {}", rc_file.display(), e))?;
+    Ok((rc_file, true))
+}
+
+/// Run `omnirec completions`.
+pub fn run(
+    cmd: &mut Command,
+    shell: Option<CompletionShell>,
+    install_rc: bool,
+    quiet: bool,
+) -> ExitCode {
+    if !install_rc {
+        let Some(shell) = shell else {
+            return ExitCode::InvalidArguments;
+        };
+        generate(shell, cmd, &mut std::io::stdout());
+        return ExitCode::Success;
+    }
+
+    let shell_env = std::env::var("SHELL").ok();
+    let Some(shell) = shell.or_else(|| CompletionShell::detect(shell_env.as_deref())) else {
+        if !quiet {
+            eprintln!(
+                "{}",
+                colors::error("Could not detect your shell from $SHELL. Pass it explicitly, e.g. 'omnirec completions bash --install'.")
+            );
+        }
+        return ExitCode::InvalidArguments;
+    };
+
+    match install(shell) {
+        Ok((rc_file, changed)) => {
+            if !quiet {
+                let message = if changed {
+                    "Completions installed in"
+                } else {
+                    "Completions already installed in"
+                };
+                println!(
+                    "{} {}",
+                    colors::success(message),
+                    colors::path(&rc_file.display().to_string())
+                );
+                if changed {
+                    println!("Restart your shell to use them.");
+                }
+            }
+            ExitCode::Success
+        }
+        Err(e) => {
+            if !quiet {
+                eprintln!("{}", colors::error(&e));
+            }
+            ExitCode::GeneralError
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cli;
+    use clap::CommandFactory;
+
+    fn script(shell: CompletionShell) -> String {
+        let mut out = Vec::new();
+        generate(shell, &mut Cli::command(), &mut out);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_scripts_contain_all_commands() {
+        let commands = [
+            "record",
+            "list",
+            "stop",
+            "status",
+            "windows",
+            "displays",
+            "audio",
+            "window",
+            "display",
+            "region",
+            "portal",
+            "workspace",
+        ];
+        for shell in CompletionShell::value_variants() {
+            let script = script(*shell);
+            for command in commands {
+                assert!(
+                    script.contains(command),
+                    "{:?} completions are missing '{}'",
+                    shell,
+                    command
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_shell() {
+        assert_eq!(
+            CompletionShell::detect(Some("/bin/bash")),
+            Some(CompletionShell::Bash)
+        );
+        assert_eq!(
+            CompletionShell::detect(Some("/usr/bin/zsh")),
+            Some(CompletionShell::Zsh)
+        );
+        assert_eq!(
+            CompletionShell::detect(Some("/opt/homebrew/bin/fish")),
+            Some(CompletionShell::Fish)
+        );
+        assert_eq!(
+            CompletionShell::detect(Some("/usr/bin/pwsh")),
+            Some(CompletionShell::Powershell)
+        );
+        assert_eq!(CompletionShell::detect(Some("/bin/tcsh")), None);
+    }
+
+    #[test]
+    fn test_rc_file() {
+        let home = Path::new("/home/user");
+        assert_eq!(
+            CompletionShell::Zsh.rc_file(home, None),
+            home.join(".zshrc")
+        );
+        assert_eq!(
+            CompletionShell::Fish.rc_file(home, None),
+            home.join(".config/fish/config.fish")
+        );
+    }
+}
@@ -0,0 +1,432 @@
+//! `omnirec doctor`: checks for common setup problems.
+//!
+//! Each check reports `OK`, `WARN` or `ERROR` with a hint on how to fix
+//! it. Checks that need the app ask it over IPC (it knows which FFmpeg it
+//! runs and where it saves recordings); when the app can't be reached they
+//! fall back to what the CLI can see itself, or are skipped with a warning.
+
+use crate::client::ServiceClient;
+use crate::colors;
+use crate::exit_codes::ExitCode;
+use omnirec_types::ipc::{features, Request, Response};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Error,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "OK",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Error => "ERROR",
+        }
+    }
+}
+
+/// Result of one check.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// How to fix a warning or error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn ok(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Ok,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn error(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Error,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn skipped(name: &'static str) -> Self {
+        Self::warn(
+            name,
+            "Not checked, the app is not reachable or too old",
+            "Fix the service check first, or update OmniRec",
+        )
+    }
+}
+
+/// Setup reported by the app, if it could be asked.
+struct Diagnostics {
+    ffmpeg_path: String,
+    ffmpeg_version: Option<String>,
+    screen_recording_permission: Option<bool>,
+    output_directory: Option<String>,
+}
+
+/// Run all checks and print the report.
+pub async fn run(json: bool) -> ExitCode {
+    let checks = run_checks().await;
+
+    if json {
+        let report = serde_json::json!({
+            "ok": passed(&checks),
+            "checks": checks,
+        });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print_report(&checks);
+    }
+
+    if passed(&checks) {
+        ExitCode::Success
+    } else {
+        ExitCode::GeneralError
+    }
+}
+
+/// Whether no check failed (warnings are fine).
+fn passed(checks: &[CheckResult]) -> bool {
+    checks
+        .iter()
+        .all(|check| check.status != CheckStatus::Error)
+}
+
+async fn run_checks() -> Vec<CheckResult> {
+    let mut checks = Vec::new();
+
+    let client = ServiceClient::new();
+    let connected = match client.connect_or_spawn().await {
+        Ok(()) => {
+            checks.push(CheckResult::ok("Service", "The OmniRec app is running"));
+            true
+        }
+        Err(e) => {
+            checks.push(CheckResult::error(
+                "Service",
+                e.to_string(),
+                "Start OmniRec, or run `omnirec-app --headless` and check its log",
+            ));
+            false
+        }
+    };
+
+    let diagnostics = if connected && client.supports(features::DIAGNOSTICS) {
+        match client.request(Request::GetDiagnostics).await {
+            Ok(Response::Diagnostics {
+                ffmpeg_path,
+                ffmpeg_version,
+                screen_recording_permission,
+                output_directory,
+            }) => Some(Diagnostics {
+                ffmpeg_path,
+                ffmpeg_version,
+                screen_recording_permission,
+                output_directory,
+            }),
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    checks.push(check_ffmpeg(diagnostics.as_ref()));
+    if cfg!(target_os = "macos") {
+        checks.push(check_screen_permission(diagnostics.as_ref()));
+    }
+    if cfg!(target_os = "linux") {
+        checks.push(check_portal());
+    }
+    checks.push(if connected {
+        check_audio(&client).await
+    } else {
+        CheckResult::skipped("Audio devices")
+    });
+    checks.push(check_output_directory(diagnostics.as_ref()));
+
+    checks
+}
+
+fn check_ffmpeg(diagnostics: Option<&Diagnostics>) -> CheckResult {
+    const NAME: &str = "FFmpeg";
+    let (path, version) = match diagnostics {
+        Some(d) => (d.ffmpeg_path.clone(), d.ffmpeg_version.clone()),
+        // The app runs FFmpeg from PATH on Linux; elsewhere it is bundled
+        // next to the app, which the CLI can't check on its own
+        None if cfg!(target_os = "linux") => match which::which("ffmpeg") {
+            Ok(path) => {
+                let version = ffmpeg_version(&path);
+                (path.to_string_lossy().to_string(), version)
+            }
+            Err(_) => ("ffmpeg".to_string(), None),
+        },
+        None => return CheckResult::skipped(NAME),
+    };
+
+    match version {
+        Some(version) => CheckResult::ok(NAME, format!("{} ({})", version, path)),
+        None => CheckResult::error(
+            NAME,
+            format!("Could not run {}", path),
+            if cfg!(target_os = "linux") {
+                "Install FFmpeg with your package manager (e.g. `sudo apt install ffmpeg`)"
+            } else {
+                "Reinstall OmniRec; FFmpeg is bundled with the app"
+            },
+        ),
+    }
+}
+
+/// First line of `ffmpeg -version`.
+fn ffmpeg_version(path: &Path) -> Option<String> {
+    let output = std::process::Command::new(path)
+        .arg("-version")
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+}
+
+fn check_screen_permission(diagnostics: Option<&Diagnostics>) -> CheckResult {
+    const NAME: &str = "Screen recording";
+    match diagnostics.and_then(|d| d.screen_recording_permission) {
+        Some(true) => CheckResult::ok(NAME, "Permission granted"),
+        Some(false) => CheckResult::error(
+            NAME,
+            "Permission not granted",
+            "Allow OmniRec in System Settings > Privacy & Security > Screen Recording, then restart it",
+        ),
+        None => CheckResult::skipped(NAME),
+    }
+}
+
+/// Whether the desktop portal offers screen casting, asked over D-Bus
+/// (which also starts the portal if it isn't running yet).
+fn check_portal() -> CheckResult {
+    const NAME: &str = "Desktop portal";
+    let output = std::process::Command::new("gdbus")
+        .args([
+            "introspect",
+            "--session",
+            "--dest",
+            "org.freedesktop.portal.Desktop",
+            "--object-path",
+            "/org/freedesktop/portal/desktop",
+        ])
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            if portal_has_screencast(&String::from_utf8_lossy(&output.stdout)) {
+                CheckResult::ok(NAME, "xdg-desktop-portal supports screen casting")
+            } else {
+                CheckResult::error(
+                    NAME,
+                    "xdg-desktop-portal is running without screen casting",
+                    portal_hint(),
+                )
+            }
+        }
+        Ok(_) => CheckResult::error(NAME, "xdg-desktop-portal is not available", portal_hint()),
+        Err(_) => CheckResult::warn(
+            NAME,
+            "Could not check, gdbus is not installed",
+            "Install GLib's gdbus tool to check the portal",
+        ),
+    }
+}
+
+fn portal_has_screencast(introspection: &str) -> bool {
+    introspection.contains("interface org.freedesktop.portal.ScreenCast")
+}
+
+fn portal_hint() -> String {
+    let backend = match crate::platform::desktop_name()
+        .unwrap_or_default()
+        .to_uppercase()
+    {
+        d if d.contains("HYPRLAND") => "xdg-desktop-portal-hyprland",
+        d if d.contains("GNOME") => "xdg-desktop-portal-gnome",
+        d if d.contains("KDE") => "xdg-desktop-portal-kde",
+        d if d.contains("COSMIC") => "xdg-desktop-portal-cosmic",
+        _ => "the portal backend for your desktop",
+    };
+    format!(
+        "Install xdg-desktop-portal and {}, then log in again",
+        backend
+    )
+}
+
+async fn check_audio(client: &ServiceClient) -> CheckResult {
+    const NAME: &str = "Audio devices";
+    match client.request(Request::ListAudioSources).await {
+        Ok(Response::AudioSources { sources }) if sources.is_empty() => CheckResult::warn(
+            NAME,
+            "No audio sources found; recordings will be silent",
+            "Check that your sound server is running and a device is connected",
+        ),
+        Ok(Response::AudioSources { sources }) => {
+            CheckResult::ok(NAME, format!("{} audio sources found", sources.len()))
+        }
+        Ok(other) => CheckResult::error(
+            NAME,
+            format!("Unexpected response: {:?}", other),
+            "Update OmniRec and the CLI to the same version",
+        ),
+        Err(e) => CheckResult::error(NAME, e.to_string(), "Check the app's log for audio errors"),
+    }
+}
+
+fn check_output_directory(diagnostics: Option<&Diagnostics>) -> CheckResult {
+    const NAME: &str = "Output directory";
+    let dir = match diagnostics {
+        Some(d) => d.output_directory.clone().map(PathBuf::from),
+        None => default_output_dir(),
+    };
+    let Some(dir) = dir else {
+        return CheckResult::error(
+            NAME,
+            "Could not determine the output directory",
+            "Choose an output directory in OmniRec's settings",
+        );
+    };
+
+    match probe_writable(&dir) {
+        Ok(()) => CheckResult::ok(NAME, format!("{} is writable", dir.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => CheckResult::warn(
+            NAME,
+            format!("{} does not exist", dir.display()),
+            "It is created with the first recording; choose another directory in settings if that fails",
+        ),
+        Err(e) => CheckResult::error(
+            NAME,
+            format!("Cannot write to {}: {}", dir.display(), e),
+            "Fix the directory's permissions or choose another one in OmniRec's settings",
+        ),
+    }
+}
+
+/// The app's default output directory when it can't be asked.
+fn default_output_dir() -> Option<PathBuf> {
+    let dirs = directories::UserDirs::new()?;
+    if cfg!(target_os = "macos") {
+        return Some(dirs.home_dir().join("Documents").join("Recordings"));
+    }
+    Some(
+        dirs.video_dir()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| dirs.home_dir().join("Videos")),
+    )
+}
+
+/// Create and remove a file in `dir`.
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    if !dir.is_dir() {
+        return Err(std::io::ErrorKind::NotFound.into());
+    }
+    let probe = dir.join(format!(".omnirec-doctor-{}", std::process::id()));
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+fn print_report(checks: &[CheckResult]) {
+    let name_width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for check in checks {
+        let label = match check.status {
+            CheckStatus::Ok => colors::pad_left(check.status.label(), 5, colors::success),
+            CheckStatus::Warn => colors::pad_left(check.status.label(), 5, colors::caution),
+            CheckStatus::Error => colors::pad_left(check.status.label(), 5, colors::failure),
+        };
+        println!(
+            "{}  {}  {}",
+            label,
+            colors::pad_left(check.name, name_width, colors::bold),
+            check.message
+        );
+        if let Some(hint) = &check.hint {
+            println!(
+                "{}  {}  {}",
+                " ".repeat(5),
+                " ".repeat(name_width),
+                colors::dim(hint)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warnings_still_pass() {
+        let checks = [
+            CheckResult::ok("Service", "running"),
+            CheckResult::warn("Audio devices", "none", "connect one"),
+        ];
+        assert!(passed(&checks));
+
+        let checks = [
+            CheckResult::ok("Service", "running"),
+            CheckResult::error("FFmpeg", "missing", "install it"),
+        ];
+        assert!(!passed(&checks));
+    }
+
+    #[test]
+    fn test_status_serializes_uppercase() {
+        let json = serde_json::to_value(CheckResult::warn("A", "b", "c")).unwrap();
+        assert_eq!(json["status"], "WARN");
+        assert_eq!(json["hint"], "c");
+        let json = serde_json::to_value(CheckResult::ok("A", "b")).unwrap();
+        assert!(json.get("hint").is_none());
+    }
+
+    #[test]
+    fn test_portal_has_screencast() {
+        let introspection = "node /org/freedesktop/portal/desktop {\n  \
+            interface org.freedesktop.portal.Screenshot {\n  };\n  \
+            interface org.freedesktop.portal.ScreenCast {\n  };\n}";
+        assert!(portal_has_screencast(introspection));
+        assert!(!portal_has_screencast(
+            "interface org.freedesktop.portal.Screenshot {"
+        ));
+    }
+
+    #[test]
+    fn test_missing_output_directory() {
+        let err = probe_writable(Path::new("/nonexistent/omnirec-doctor")).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        assert!(probe_writable(&std::env::temp_dir()).is_ok());
+    }
+}
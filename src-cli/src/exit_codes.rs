@@ -25,6 +25,8 @@ pub enum ExitCode {
     PortalRequired = 7,
     /// User cancelled (portal picker)
     UserCancelled = 8,
+    /// Recording does not match its checksum
+    IntegrityCheckFailed = 9,
 }
 
 impl ExitCode {
@@ -46,6 +48,7 @@ impl std::fmt::Display for ExitCode {
             ExitCode::TranscodingFailed => write!(f, "transcoding failed"),
             ExitCode::PortalRequired => write!(f, "portal required"),
             ExitCode::UserCancelled => write!(f, "user cancelled"),
+            ExitCode::IntegrityCheckFailed => write!(f, "integrity check failed"),
         }
     }
 }
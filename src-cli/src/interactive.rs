@@ -0,0 +1,254 @@
+//! Interactive source selection for `omnirec record` without a target.
+//!
+//! On a terminal the user picks a window or display from a list. With
+//! `--interactive` and piped stdin, the same numbered list is printed to
+//! stderr and the selection is read as a number from stdin. Scripts that
+//! pass neither a target nor `--interactive` record the primary display.
+
+use crate::client::ServiceClient;
+use crate::colors;
+use crate::exit_codes::ExitCode;
+use crate::platform;
+use crate::{RecordOptions, RecordTarget};
+use omnirec_types::ipc::{Request, Response};
+use omnirec_types::{MonitorInfo, WindowInfo};
+use std::io::{BufRead, IsTerminal};
+
+/// A capture source offered in the list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Source {
+    Display(String),
+    Window(isize),
+}
+
+impl Source {
+    fn into_target(self, options: RecordOptions) -> RecordTarget {
+        match self {
+            Source::Display(id) => RecordTarget::Display { id, options },
+            Source::Window(handle) => RecordTarget::Window { handle, options },
+        }
+    }
+}
+
+/// List entries: displays first (primary on top), then windows.
+fn sources(monitors: &[MonitorInfo], windows: &[WindowInfo]) -> Vec<(String, Source)> {
+    let mut monitors: Vec<_> = monitors.iter().collect();
+    monitors.sort_by_key(|m| !m.is_primary);
+
+    let displays = monitors.into_iter().map(|m| {
+        let primary = if m.is_primary { ", primary" } else { "" };
+        (
+            format!("Display: {} ({}x{}{})", m.name, m.width, m.height, primary),
+            Source::Display(m.id.clone()),
+        )
+    });
+    let windows = windows.iter().map(|w| {
+        (
+            format!("Window:  {} [{}]", w.title, w.process_name),
+            Source::Window(w.handle),
+        )
+    });
+    displays.chain(windows).collect()
+}
+
+/// Parse a 1-based selection from `input` for a list of `count` entries.
+fn parse_selection(input: &str, count: usize) -> Option<usize> {
+    let number: usize = input.trim().parse().ok()?;
+    (1..=count).contains(&number).then(|| number - 1)
+}
+
+/// Print `labels` as a numbered list and read a selection from stdin.
+fn read_selection(labels: &[String]) -> Result<Option<usize>, String> {
+    for (i, label) in labels.iter().enumerate() {
+        eprintln!("{:>3}) {}", i + 1, label);
+    }
+    eprint!("Select a source to record [1-{}]: ", labels.len());
+
+    let mut line = String::new();
+    let read = std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read selection: {}", e))?;
+    if read == 0 {
+        return Ok(None);
+    }
+    parse_selection(&line, labels.len())
+        .map(Some)
+        .ok_or_else(|| format!("Invalid selection '{}'", line.trim()))
+}
+
+/// Fetch windows and monitors from the service.
+async fn fetch_sources() -> Result<(Vec<MonitorInfo>, Vec<WindowInfo>), (String, ExitCode)> {
+    let client = ServiceClient::new();
+    client
+        .connect_or_spawn()
+        .await
+        .map_err(|e| (e.to_string(), e.to_exit_code()))?;
+
+    let monitors = match client.request(Request::ListMonitors).await {
+        Ok(Response::Monitors { monitors }) => monitors,
+        Ok(other) => {
+            return Err((
+                format!("Unexpected response: {:?}", other),
+                ExitCode::GeneralError,
+            ))
+        }
+        Err(e) => return Err((e.to_string(), e.to_exit_code())),
+    };
+    let windows = match client.request(Request::ListWindows).await {
+        Ok(Response::Windows { windows }) => windows,
+        Ok(other) => {
+            return Err((
+                format!("Unexpected response: {:?}", other),
+                ExitCode::GeneralError,
+            ))
+        }
+        Err(e) => return Err((e.to_string(), e.to_exit_code())),
+    };
+    Ok((monitors, windows))
+}
+
+/// Choose a recording target when none was given on the command line.
+pub async fn pick_target(
+    options: RecordOptions,
+    interactive: bool,
+    quiet: bool,
+) -> Result<RecordTarget, ExitCode> {
+    let is_tty = std::io::stdin().is_terminal();
+
+    // The portal shows its own picker
+    if platform::is_portal_mode_desktop() && (interactive || is_tty) {
+        return Ok(RecordTarget::Portal { options });
+    }
+
+    let (monitors, windows) = match fetch_sources().await {
+        Ok(sources) => sources,
+        Err((message, code)) => {
+            if !quiet {
+                eprintln!("{}", colors::error(&message));
+            }
+            return Err(code);
+        }
+    };
+
+    if !interactive && !is_tty {
+        let Some(primary) = monitors
+            .iter()
+            .find(|m| m.is_primary)
+            .or_else(|| monitors.first())
+        else {
+            if !quiet {
+                eprintln!(
+                    "{}",
+                    colors::error("No target specified and no displays found.")
+                );
+            }
+            return Err(ExitCode::InvalidArguments);
+        };
+        if !quiet {
+            eprintln!(
+                "{}",
+                colors::warning(&format!(
+                    "No target specified; recording primary display '{}'.",
+                    primary.name
+                ))
+            );
+        }
+        return Ok(Source::Display(primary.id.clone()).into_target(options));
+    }
+
+    let (labels, sources): (Vec<_>, Vec<_>) = sources(&monitors, &windows).into_iter().unzip();
+    if labels.is_empty() {
+        if !quiet {
+            eprintln!("{}", colors::error("No capture sources found."));
+        }
+        return Err(ExitCode::GeneralError);
+    }
+
+    let selection = if is_tty {
+        dialoguer::Select::new()
+            .with_prompt("Select a source to record")
+            .items(&labels)
+            .default(0)
+            .interact_opt()
+            .map_err(|e| e.to_string())
+    } else {
+        read_selection(&labels)
+    };
+
+    match selection {
+        Ok(Some(index)) => Ok(sources[index].clone().into_target(options)),
+        Ok(None) => {
+            if !quiet {
+                eprintln!("{}", colors::dim("No source selected."));
+            }
+            Err(ExitCode::UserCancelled)
+        }
+        Err(e) => {
+            if !quiet {
+                eprintln!("{}", colors::error(&e));
+            }
+            Err(ExitCode::InvalidArguments)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(id: &str, is_primary: bool) -> MonitorInfo {
+        MonitorInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            is_primary,
+            scale_factor: 1.0,
+        }
+    }
+
+    fn window(handle: isize, title: &str) -> WindowInfo {
+        WindowInfo {
+            handle,
+            title: title.to_string(),
+            process_name: "app".to_string(),
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+            workspace_id: None,
+            workspace_name: None,
+        }
+    }
+
+    #[test]
+    fn test_sources_list_primary_display_first() {
+        let sources: Vec<_> = sources(
+            &[monitor("DP-2", false), monitor("DP-1", true)],
+            &[window(42, "Editor")],
+        )
+        .into_iter()
+        .map(|(_, source)| source)
+        .collect();
+        assert_eq!(
+            sources,
+            vec![
+                Source::Display("DP-1".to_string()),
+                Source::Display("DP-2".to_string()),
+                Source::Window(42),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(parse_selection("1\n", 3), Some(0));
+        assert_eq!(parse_selection(" 3 ", 3), Some(2));
+        assert_eq!(parse_selection("0", 3), None);
+        assert_eq!(parse_selection("4", 3), None);
+        assert_eq!(parse_selection("abc", 3), None);
+    }
+}
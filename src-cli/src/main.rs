@@ -6,11 +6,16 @@
 mod client;
 mod colors;
 mod commands;
+mod completions;
+mod doctor;
 mod exit_codes;
+mod interactive;
 mod platform;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use completions::CompletionShell;
 use exit_codes::ExitCode;
+use omnirec_types::security::validation::MAX_FRAME_RATE;
 
 /// OmniRec - Screen Recording CLI
 #[derive(Parser, Debug)]
@@ -41,17 +46,48 @@ enum Commands {
         #[command(subcommand)]
         source: ListSource,
     },
-    /// Start a recording
+    /// Start a recording (pick a source interactively if no target is given)
+    #[command(args_conflicts_with_subcommands = true)]
     Record {
         #[command(subcommand)]
-        target: RecordTarget,
+        target: Option<RecordTarget>,
+
+        /// Pick a source from a numbered list, even when stdin is not a terminal
+        #[arg(long)]
+        interactive: bool,
+
+        #[command(flatten)]
+        options: Box<RecordOptions>,
     },
     /// Stop the current recording
     Stop,
+    /// Save a PNG screenshot of a window or display (the primary display if
+    /// no target is given)
+    Screenshot {
+        #[command(subcommand)]
+        target: Option<ScreenshotTarget>,
+    },
     /// Show current recording status
     Status,
+    /// Check a recording against its SHA-256 checksum file
+    Verify {
+        /// Recording file (its checksum is read from `<basename>.sha256`)
+        path: std::path::PathBuf,
+    },
+    /// Check for common setup problems
+    Doctor,
     /// Show version information
     Version,
+    /// Print a shell completion script
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum, required_unless_present = "install")]
+        shell: Option<CompletionShell>,
+
+        /// Add completions to your shell's startup file (detected from $SHELL)
+        #[arg(long)]
+        install: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -113,6 +149,30 @@ pub enum RecordTarget {
         #[command(flatten)]
         options: RecordOptions,
     },
+    /// Record the monitor showing a Hyprland workspace
+    Workspace {
+        /// Workspace ID (as shown by 'hyprctl workspaces')
+        #[arg(allow_hyphen_values = true)]
+        id: i32,
+
+        #[command(flatten)]
+        options: RecordOptions,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ScreenshotTarget {
+    /// Screenshot a specific window by handle
+    Window {
+        /// Window handle (use 'omnirec list windows' to find)
+        #[arg(allow_hyphen_values = true)]
+        handle: isize,
+    },
+    /// Screenshot a specific display by ID
+    Display {
+        /// Display ID (use 'omnirec list displays' to find)
+        id: String,
+    },
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -121,9 +181,10 @@ pub struct RecordOptions {
     #[arg(short, long)]
     output: Option<String>,
 
-    /// Output format: mp4, webm, mkv, mov, gif, apng, webp
-    #[arg(short, long, default_value = "mp4")]
-    format: String,
+    /// Output format: mp4, webm, mkv, mov, gif, apng, webp (default: mp4,
+    /// or chosen from the recording when smart format is on)
+    #[arg(short, long)]
+    format: Option<String>,
 
     /// Auto-stop after duration (seconds)
     #[arg(short, long)]
@@ -140,6 +201,14 @@ pub struct RecordOptions {
     /// Fail if specific target cannot be selected (don't fall back to portal)
     #[arg(long)]
     strict: bool,
+
+    /// Capture and encode a few test frames without saving a recording
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Capture at most this many frames per second
+    #[arg(long, value_name = "FPS", value_parser = clap::value_parser!(u32).range(1..=MAX_FRAME_RATE as i64))]
+    limit_fps: Option<u32>,
 }
 
 fn main() {
@@ -162,22 +231,38 @@ async fn run(cli: Cli) -> ExitCode {
             ListSource::Displays => commands::list_displays(cli.json, cli.quiet).await,
             ListSource::Audio => commands::list_audio(cli.json, cli.quiet).await,
         },
-        Commands::Record { target } => {
+        Commands::Record {
+            target,
+            interactive,
+            options,
+        } => {
+            let target = match target {
+                Some(target) => target,
+                None => match interactive::pick_target(*options, interactive, cli.quiet).await {
+                    Ok(target) => target,
+                    Err(code) => return code,
+                },
+            };
             commands::record(target, cli.json, cli.quiet, cli.verbose).await
         }
         Commands::Stop => commands::stop(cli.json, cli.quiet).await,
+        Commands::Screenshot { target } => commands::screenshot(target, cli.json, cli.quiet).await,
         Commands::Status => commands::status(cli.json).await,
+        Commands::Verify { path } => commands::verify(&path, cli.json, cli.quiet),
+        Commands::Doctor => doctor::run(cli.json).await,
         Commands::Version => {
             commands::version(cli.json);
             ExitCode::Success
         }
+        Commands::Completions { shell, install } => {
+            completions::run(&mut Cli::command(), shell, install, cli.quiet)
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use clap::CommandFactory;
 
     /// Verify the CLI definition is valid
     #[test]
@@ -246,25 +331,78 @@ mod tests {
         let cli = Cli::try_parse_from(["omnirec", "record", "window", "12345"]).unwrap();
         match cli.command {
             Commands::Record {
-                target: RecordTarget::Window { handle, options },
+                target: Some(RecordTarget::Window { handle, options }),
+                ..
             } => {
                 assert_eq!(handle, 12345);
-                assert_eq!(options.format, "mp4");
+                assert!(options.format.is_none());
                 assert!(options.output.is_none());
                 assert!(options.duration.is_none());
                 assert!(!options.strict);
+                assert!(!options.dry_run);
+                assert!(options.limit_fps.is_none());
             }
             _ => panic!("Expected Record Window command"),
         }
     }
 
+    /// Test parsing 'record display --dry-run'
+    #[test]
+    fn parse_record_dry_run() {
+        let cli =
+            Cli::try_parse_from(["omnirec", "record", "display", "HDMI-1", "--dry-run"]).unwrap();
+        match cli.command {
+            Commands::Record {
+                target: Some(RecordTarget::Display { options, .. }),
+                ..
+            } => {
+                assert!(options.dry_run);
+            }
+            _ => panic!("Expected Record Display command"),
+        }
+    }
+
+    /// Test parsing 'record display --limit-fps'
+    #[test]
+    fn parse_record_limit_fps() {
+        let cli = Cli::try_parse_from([
+            "omnirec",
+            "record",
+            "display",
+            "HDMI-1",
+            "--limit-fps",
+            "15",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Record {
+                target: Some(RecordTarget::Display { options, .. }),
+                ..
+            } => {
+                assert_eq!(options.limit_fps, Some(15));
+            }
+            _ => panic!("Expected Record Display command"),
+        }
+
+        assert!(Cli::try_parse_from([
+            "omnirec",
+            "record",
+            "display",
+            "HDMI-1",
+            "--limit-fps",
+            "0"
+        ])
+        .is_err());
+    }
+
     /// Test parsing 'record display' command
     #[test]
     fn parse_record_display() {
         let cli = Cli::try_parse_from(["omnirec", "record", "display", "HDMI-1"]).unwrap();
         match cli.command {
             Commands::Record {
-                target: RecordTarget::Display { id, .. },
+                target: Some(RecordTarget::Display { id, .. }),
+                ..
             } => {
                 assert_eq!(id, "HDMI-1");
             }
@@ -294,14 +432,15 @@ mod tests {
         match cli.command {
             Commands::Record {
                 target:
-                    RecordTarget::Region {
+                    Some(RecordTarget::Region {
                         display,
                         x,
                         y,
                         width,
                         height,
                         ..
-                    },
+                    }),
+                ..
             } => {
                 assert_eq!(display, "DP-1");
                 assert_eq!(x, 100);
@@ -313,6 +452,21 @@ mod tests {
         }
     }
 
+    /// Test parsing 'record workspace' command
+    #[test]
+    fn parse_record_workspace() {
+        let cli = Cli::try_parse_from(["omnirec", "record", "workspace", "3"]).unwrap();
+        match cli.command {
+            Commands::Record {
+                target: Some(RecordTarget::Workspace { id, .. }),
+                ..
+            } => {
+                assert_eq!(id, 3);
+            }
+            _ => panic!("Expected Record Workspace command"),
+        }
+    }
+
     /// Test parsing 'record portal' command
     #[test]
     fn parse_record_portal() {
@@ -320,11 +474,33 @@ mod tests {
         assert!(matches!(
             cli.command,
             Commands::Record {
-                target: RecordTarget::Portal { .. }
+                target: Some(RecordTarget::Portal { .. }),
+                ..
             }
         ));
     }
 
+    /// Test parsing 'record' without a target
+    #[test]
+    fn parse_record_without_target() {
+        let cli =
+            Cli::try_parse_from(["omnirec", "record", "--interactive", "-f", "webm"]).unwrap();
+        match cli.command {
+            Commands::Record {
+                target: None,
+                interactive,
+                options,
+            } => {
+                assert!(interactive);
+                assert_eq!(options.format.as_deref(), Some("webm"));
+            }
+            _ => panic!("Expected Record command without target"),
+        }
+
+        // Options belong to the target once one is given
+        assert!(Cli::try_parse_from(["omnirec", "record", "--interactive", "portal"]).is_err());
+    }
+
     /// Test parsing record command with output options
     #[test]
     fn parse_record_with_options() {
@@ -343,10 +519,11 @@ mod tests {
         .unwrap();
         match cli.command {
             Commands::Record {
-                target: RecordTarget::Display { options, .. },
+                target: Some(RecordTarget::Display { options, .. }),
+                ..
             } => {
                 assert_eq!(options.output, Some("/tmp/recording.webm".to_string()));
-                assert_eq!(options.format, "webm");
+                assert_eq!(options.format.as_deref(), Some("webm"));
                 assert_eq!(options.duration, Some(60));
             }
             _ => panic!("Expected Record Display command"),
@@ -369,7 +546,8 @@ mod tests {
         .unwrap();
         match cli.command {
             Commands::Record {
-                target: RecordTarget::Display { options, .. },
+                target: Some(RecordTarget::Display { options, .. }),
+                ..
             } => {
                 assert_eq!(options.audio, Some("default".to_string()));
                 assert_eq!(options.microphone, Some("none".to_string()));
@@ -384,7 +562,8 @@ mod tests {
         let cli = Cli::try_parse_from(["omnirec", "record", "window", "123", "--strict"]).unwrap();
         match cli.command {
             Commands::Record {
-                target: RecordTarget::Window { options, .. },
+                target: Some(RecordTarget::Window { options, .. }),
+                ..
             } => {
                 assert!(options.strict);
             }
@@ -413,6 +592,38 @@ mod tests {
         assert!(matches!(cli.command, Commands::Version));
     }
 
+    /// Test parsing 'doctor' command
+    #[test]
+    fn parse_doctor() {
+        let cli = Cli::try_parse_from(["omnirec", "doctor", "--json"]).unwrap();
+        assert!(matches!(cli.command, Commands::Doctor));
+        assert!(cli.json);
+    }
+
+    /// Test parsing 'completions' command
+    #[test]
+    fn parse_completions() {
+        let cli = Cli::try_parse_from(["omnirec", "completions", "zsh"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Completions {
+                shell: Some(CompletionShell::Zsh),
+                install: false
+            }
+        ));
+
+        let cli = Cli::try_parse_from(["omnirec", "completions", "--install"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Completions {
+                shell: None,
+                install: true
+            }
+        ));
+
+        assert!(Cli::try_parse_from(["omnirec", "completions"]).is_err());
+    }
+
     /// Test that global flags work after subcommand
     #[test]
     fn parse_global_flags_after_subcommand() {
@@ -428,6 +639,43 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Test parsing 'screenshot' command with and without a target
+    #[test]
+    fn parse_screenshot() {
+        let cli = Cli::try_parse_from(["omnirec", "screenshot"]).unwrap();
+        assert!(matches!(cli.command, Commands::Screenshot { target: None }));
+
+        let cli = Cli::try_parse_from(["omnirec", "screenshot", "window", "-42"]).unwrap();
+        match cli.command {
+            Commands::Screenshot {
+                target: Some(ScreenshotTarget::Window { handle }),
+            } => assert_eq!(handle, -42),
+            _ => panic!("Expected Screenshot window command"),
+        }
+
+        let cli = Cli::try_parse_from(["omnirec", "screenshot", "display", "DP-1"]).unwrap();
+        match cli.command {
+            Commands::Screenshot {
+                target: Some(ScreenshotTarget::Display { id }),
+            } => assert_eq!(id, "DP-1"),
+            _ => panic!("Expected Screenshot display command"),
+        }
+        assert!(Cli::try_parse_from(["omnirec", "screenshot", "window"]).is_err());
+    }
+
+    /// Test parsing 'verify' command
+    #[test]
+    fn parse_verify() {
+        let cli = Cli::try_parse_from(["omnirec", "verify", "recording.mp4"]).unwrap();
+        match cli.command {
+            Commands::Verify { path } => {
+                assert_eq!(path, std::path::PathBuf::from("recording.mp4"));
+            }
+            _ => panic!("Expected Verify command"),
+        }
+        assert!(Cli::try_parse_from(["omnirec", "verify"]).is_err());
+    }
+
     /// Test missing required argument returns error
     #[test]
     fn parse_missing_window_handle() {
@@ -448,7 +696,8 @@ mod tests {
         let cli = Cli::try_parse_from(["omnirec", "record", "window", "-1"]).unwrap();
         match cli.command {
             Commands::Record {
-                target: RecordTarget::Window { handle, .. },
+                target: Some(RecordTarget::Window { handle, .. }),
+                ..
             } => {
                 assert_eq!(handle, -1);
             }
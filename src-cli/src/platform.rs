@@ -44,6 +44,19 @@ pub fn is_portal_supported() -> bool {
     }
 }
 
+/// Check if we're running under the Hyprland compositor.
+pub fn is_hyprland() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        std::env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
 /// Get the name of the current desktop environment (for messages).
 pub fn desktop_name() -> Option<String> {
     std::env::var("XDG_CURRENT_DESKTOP").ok()
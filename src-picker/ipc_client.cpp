@@ -6,7 +6,9 @@
  */
 
 #include "ipc_client.h"
+#include "picker_logic.h"
 
+#include <QDeadlineTimer>
 #include <QLocalSocket>
 #include <QJsonDocument>
 #include <QJsonObject>
@@ -14,18 +16,63 @@
 #include <QDir>
 #include <QDataStream>
 #include <QTextStream>
+#include <QThread>
 #include <cstdlib>
 #include <iostream>
 #include <unistd.h>
 
+// Timeout of a selection query when OMNIREC_PICKER_TIMEOUT_MS is not set
+static const int DEFAULT_QUERY_TIMEOUT_MS = 2000;
+
+// Pause before the second selection query attempt
+static const int QUERY_RETRY_DELAY_MS = 500;
+
+// Timeout of the other requests
+static const int REQUEST_TIMEOUT_MS = 5000;
+
+int queryTimeoutMs()
+{
+    QString value = qEnvironmentVariable("OMNIREC_PICKER_TIMEOUT_MS");
+    if (value.isEmpty()) {
+        return DEFAULT_QUERY_TIMEOUT_MS;
+    }
+    bool ok = false;
+    int timeoutMs = value.trimmed().toInt(&ok);
+    if (!ok || timeoutMs <= 0) {
+        pickerLog(QString("[omnirec-picker] WARN: Ignoring OMNIREC_PICKER_TIMEOUT_MS=%1, using %2 ms")
+            .arg(value)
+            .arg(DEFAULT_QUERY_TIMEOUT_MS));
+        return DEFAULT_QUERY_TIMEOUT_MS;
+    }
+    return timeoutMs;
+}
+
 QString getSocketPath()
 {
-    // Use the unified service socket path
+    // An explicit path, e.g. inherited from the app, wins
+    QString explicitPath = qEnvironmentVariable("OMNIREC_SOCKET");
+    if (!explicitPath.isEmpty()) {
+        return explicitPath;
+    }
+
+    // Otherwise the service of this Wayland session, named like
+    // omnirec_types::ipc::get_socket_path()
     QString runtimeDir = qEnvironmentVariable("XDG_RUNTIME_DIR");
     if (runtimeDir.isEmpty()) {
         runtimeDir = QString("/run/user/%1").arg(getuid());
     }
-    return QDir(runtimeDir).filePath("omnirec/service.sock");
+
+    QString session = qEnvironmentVariable("WAYLAND_DISPLAY").section('/', -1);
+    if (session.isEmpty()) {
+        return QDir(runtimeDir).filePath("omnirec/service.sock");
+    }
+    for (QChar& c : session) {
+        bool safe = (c.unicode() < 128 && c.isLetterOrNumber()) || c == '-' || c == '_' || c == '.';
+        if (!safe) {
+            c = '_';
+        }
+    }
+    return QDir(runtimeDir).filePath(QString("omnirec/service-%1.sock").arg(session));
 }
 
 /**
@@ -66,7 +113,7 @@ static bool sendLengthPrefixedMessage(QLocalSocket& socket, const QJsonObject& m
 /**
  * Read a length-prefixed JSON message.
  */
-static QByteArray readLengthPrefixedMessage(QLocalSocket& socket, QString* errorOut)
+static QByteArray readLengthPrefixedMessage(QLocalSocket& socket, const QDeadlineTimer& deadline, QString* errorOut)
 {
     // Read length prefix (4 bytes)
     char lenBytes[4];
@@ -74,7 +121,7 @@ static QByteArray readLengthPrefixedMessage(QLocalSocket& socket, QString* error
     while (bytesRead < 4) {
         // Only wait if no data is available - data might already be buffered
         if (socket.bytesAvailable() == 0) {
-            if (!socket.waitForReadyRead(5000)) {
+            if (!socket.waitForReadyRead(deadline.remainingTime())) {
                 if (errorOut) *errorOut = QString("Timeout waiting for response length: %1").arg(socket.errorString());
                 return QByteArray();
             }
@@ -106,7 +153,7 @@ static QByteArray readLengthPrefixedMessage(QLocalSocket& socket, QString* error
     while (bytesRead < static_cast<qint64>(len)) {
         // Only wait if no data is available - data might already be buffered
         if (socket.bytesAvailable() == 0) {
-            if (!socket.waitForReadyRead(5000)) {
+            if (!socket.waitForReadyRead(deadline.remainingTime())) {
                 if (errorOut) *errorOut = QString("Timeout waiting for response body: %1").arg(socket.errorString());
                 return QByteArray();
             }
@@ -165,8 +212,10 @@ static IpcResponse parseResponse(const QByteArray& data, QString* errorOut)
         response.type = ResponseType::TokenValid;
     } else if (type == "token_invalid") {
         response.type = ResponseType::TokenInvalid;
-    } else if (type == "token_stored" || type == "ok") {
+    } else if (type == "token_stored") {
         response.type = ResponseType::TokenStored;
+    } else if (type == "ok") {
+        response.type = ResponseType::Ok;
     } else {
         response.type = ResponseType::Error;
         response.errorMessage = QString("Unknown response type: %1").arg(type);
@@ -176,21 +225,25 @@ static IpcResponse parseResponse(const QByteArray& data, QString* errorOut)
     return response;
 }
 
-IpcResponse querySelection(QString* errorOut)
+/**
+ * One query_selection round trip, connecting and reading the response
+ * within timeoutMs. Returns an empty array on failure, with the error in
+ * errorOut and timedOut set if the deadline passed.
+ */
+static QByteArray querySelectionOnce(const QString& socketPath, int timeoutMs, bool* timedOut, QString* errorOut)
 {
-    IpcResponse response;
-    QString socketPath = getSocketPath();
+    QDeadlineTimer deadline(timeoutMs);
+    *timedOut = false;
     
     QLocalSocket socket;
     socket.connectToServer(socketPath);
     
-    if (!socket.waitForConnected(3000)) {
-        response.type = ResponseType::Error;
-        response.errorMessage = QString("Failed to connect to service (is it running?): %1 (path: %2)")
+    if (!socket.waitForConnected(timeoutMs)) {
+        *timedOut = socket.error() == QLocalSocket::SocketTimeoutError;
+        if (errorOut) *errorOut = QString("Failed to connect to service (is it running?): %1 (path: %2)")
             .arg(socket.errorString())
             .arg(socketPath);
-        if (errorOut) *errorOut = response.errorMessage;
-        return response;
+        return QByteArray();
     }
     
     // Send query_selection request using unified protocol
@@ -198,20 +251,53 @@ IpcResponse querySelection(QString* errorOut)
     request["type"] = "query_selection";
     
     if (!sendLengthPrefixedMessage(socket, request, errorOut)) {
-        response.type = ResponseType::Error;
-        response.errorMessage = errorOut ? *errorOut : "Failed to send request";
-        return response;
+        return QByteArray();
     }
     
-    QByteArray data = readLengthPrefixedMessage(socket, errorOut);
+    QByteArray data = readLengthPrefixedMessage(socket, deadline, errorOut);
     if (data.isEmpty()) {
-        response.type = ResponseType::Error;
-        response.errorMessage = errorOut ? *errorOut : "Empty response";
-        return response;
+        *timedOut = deadline.hasExpired();
+        return QByteArray();
     }
     
     socket.disconnectFromServer();
-    return parseResponse(data, errorOut);
+    return data;
+}
+
+IpcResponse querySelection(QString* errorOut)
+{
+    IpcResponse response;
+    QString socketPath = getSocketPath();
+    int timeoutMs = queryTimeoutMs();
+    
+    // The portal waits for the picker, so a service that is not running or
+    // not answering must not hold it up: try twice, then give up
+    QString error;
+    for (int attempt = 1; attempt <= 2; attempt++) {
+        bool timedOut = false;
+        QByteArray data = querySelectionOnce(socketPath, timeoutMs, &timedOut, &error);
+        if (!data.isEmpty()) {
+            return parseResponse(data, errorOut);
+        }
+        
+        if (timedOut) {
+            pickerLog(QString("[omnirec-picker] WARN: Selection query timed out after %1 ms (attempt %2 of 2)")
+                .arg(timeoutMs)
+                .arg(attempt));
+        } else {
+            pickerLog(QString("[omnirec-picker] WARN: Selection query failed (attempt %1 of 2): %2")
+                .arg(attempt)
+                .arg(error));
+        }
+        if (attempt == 1) {
+            QThread::msleep(QUERY_RETRY_DELAY_MS);
+        }
+    }
+    
+    response.type = ResponseType::Error;
+    response.errorMessage = error.isEmpty() ? "Empty response" : error;
+    if (errorOut) *errorOut = response.errorMessage;
+    return response;
 }
 
 bool storeToken(const QString& token, QString* errorOut)
@@ -237,7 +323,49 @@ bool storeToken(const QString& token, QString* errorOut)
         return false;
     }
     
-    QByteArray data = readLengthPrefixedMessage(socket, errorOut);
+    QByteArray data = readLengthPrefixedMessage(socket, QDeadlineTimer(REQUEST_TIMEOUT_MS), errorOut);
+    if (data.isEmpty()) {
+        return false;
+    }
+    
+    IpcResponse response = parseResponse(data, errorOut);
+    socket.disconnectFromServer();
+    
+    if (response.type == ResponseType::TokenStored || response.type == ResponseType::Ok) {
+        return true;
+    } else if (response.type == ResponseType::Error) {
+        if (errorOut) *errorOut = response.errorMessage;
+        return false;
+    } else {
+        if (errorOut) *errorOut = "Unexpected response type";
+        return false;
+    }
+}
+
+bool setSelection(const QString& sourceType, const QString& sourceId, QString* errorOut)
+{
+    QString socketPath = getSocketPath();
+    
+    QLocalSocket socket;
+    socket.connectToServer(socketPath);
+    
+    if (!socket.waitForConnected(3000)) {
+        if (errorOut) *errorOut = QString("Failed to connect to service: %1 (path: %2)")
+            .arg(socket.errorString())
+            .arg(socketPath);
+        return false;
+    }
+    
+    QJsonObject request;
+    request["type"] = "set_selection";
+    request["source_type"] = sourceType;
+    request["source_id"] = sourceId;
+    
+    if (!sendLengthPrefixedMessage(socket, request, errorOut)) {
+        return false;
+    }
+    
+    QByteArray data = readLengthPrefixedMessage(socket, QDeadlineTimer(REQUEST_TIMEOUT_MS), errorOut);
     if (data.isEmpty()) {
         return false;
     }
@@ -245,7 +373,7 @@ bool storeToken(const QString& token, QString* errorOut)
     IpcResponse response = parseResponse(data, errorOut);
     socket.disconnectFromServer();
     
-    if (response.type == ResponseType::TokenStored) {
+    if (response.type == ResponseType::Ok) {
         return true;
     } else if (response.type == ResponseType::Error) {
         if (errorOut) *errorOut = response.errorMessage;
@@ -255,3 +383,26 @@ bool storeToken(const QString& token, QString* errorOut)
         return false;
     }
 }
+
+QString selectionToJson(const IpcResponse& response)
+{
+    QJsonObject obj;
+    obj["selected"] = response.type == ResponseType::Selection;
+    
+    if (response.type == ResponseType::Selection) {
+        obj["source_type"] = response.sourceType;
+        obj["source_id"] = response.sourceId;
+        obj["has_approval_token"] = response.hasApprovalToken;
+        if (response.geometry.has_value()) {
+            const Geometry& geom = response.geometry.value();
+            QJsonObject geomObj;
+            geomObj["x"] = geom.x;
+            geomObj["y"] = geom.y;
+            geomObj["width"] = static_cast<qint64>(geom.width);
+            geomObj["height"] = static_cast<qint64>(geom.height);
+            obj["geometry"] = geomObj;
+        }
+    }
+    
+    return QString::fromUtf8(QJsonDocument(obj).toJson(QJsonDocument::Indented));
+}
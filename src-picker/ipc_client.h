@@ -30,7 +30,8 @@ enum class ResponseType {
     Error,
     TokenValid,
     TokenInvalid,
-    TokenStored
+    TokenStored,
+    Ok
 };
 
 /**
@@ -50,13 +51,23 @@ struct IpcResponse {
 };
 
 /**
- * Get the IPC socket path.
+ * Get the IPC socket path: $OMNIREC_SOCKET, or the service socket of the
+ * current Wayland session.
  */
 QString getSocketPath();
 
+/**
+ * Timeout of one selection query in milliseconds: $OMNIREC_PICKER_TIMEOUT_MS,
+ * or 2000.
+ */
+int queryTimeoutMs();
+
 /**
  * Query the main app for the current capture selection.
- * Returns the response, or an error response if connection failed.
+ *
+ * Each attempt must connect and get a response within queryTimeoutMs().
+ * A failed attempt is logged and retried once after 500 ms.
+ * Returns the response, or an error response if both attempts failed.
  */
 IpcResponse querySelection(QString* errorOut = nullptr);
 
@@ -66,4 +77,19 @@ IpcResponse querySelection(QString* errorOut = nullptr);
  */
 bool storeToken(const QString& token, QString* errorOut = nullptr);
 
+/**
+ * Pre-select the source the next portal request will use.
+ * sourceType is "monitor" or "window"; sourceId is the monitor name or
+ * window address. Returns true on success, false on failure with error
+ * message in errorOut.
+ */
+bool setSelection(const QString& sourceType, const QString& sourceId, QString* errorOut = nullptr);
+
+/**
+ * Format a query_selection response as a JSON object for --list.
+ * Selection responses include source_type, source_id, has_approval_token
+ * and geometry (regions only); no_selection yields {"selected": false}.
+ */
+QString selectionToJson(const IpcResponse& response);
+
 #endif // IPC_CLIENT_H
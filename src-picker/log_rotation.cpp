@@ -0,0 +1,29 @@
+/**
+ * Size-based log rotation implementation.
+ */
+
+#include "log_rotation.h"
+
+#include <filesystem>
+#include <system_error>
+
+namespace fs = std::filesystem;
+
+std::string archivedLogPath(const std::string& logPath)
+{
+    return logPath + ".1";
+}
+
+bool rotateLogIfNeeded(const std::string& logPath, std::uintmax_t maxBytes)
+{
+    std::error_code ec;
+    std::uintmax_t size = fs::file_size(logPath, ec);
+    if (ec || size <= maxBytes) {
+        return false;
+    }
+
+    std::string archive = archivedLogPath(logPath);
+    fs::remove(archive, ec);
+    fs::rename(logPath, archive, ec);
+    return !ec;
+}
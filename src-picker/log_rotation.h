@@ -0,0 +1,31 @@
+/**
+ * Size-based log rotation for the picker log file.
+ *
+ * Kept free of Qt so the logic can be unit tested without a display.
+ */
+
+#ifndef LOG_ROTATION_H
+#define LOG_ROTATION_H
+
+#include <cstdint>
+#include <string>
+
+/**
+ * Default size limit for the picker log (1 MB).
+ */
+constexpr std::uintmax_t kPickerLogMaxBytes = 1024 * 1024;
+
+/**
+ * Path of the single archived log for a log file ("<path>.1").
+ */
+std::string archivedLogPath(const std::string& logPath);
+
+/**
+ * Rotate the log if it is larger than maxBytes.
+ *
+ * The current log is renamed to "<path>.1", replacing any previous archive,
+ * so at most one archived file is kept. Returns true if the log was rotated.
+ */
+bool rotateLogIfNeeded(const std::string& logPath, std::uintmax_t maxBytes = kPickerLogMaxBytes);
+
+#endif // LOG_ROTATION_H
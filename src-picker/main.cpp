@@ -11,6 +11,17 @@
  *
  *   Dry-run mode (for testing the dialog):
  *     omnirec-picker --dry-run [--source-type monitor|window|region] [--source-id ID]
+ *
+ *   Inspect or pre-set the selection (for scripts):
+ *     omnirec-picker --list
+ *     omnirec-picker --set-selection --type monitor|window --id ID
+ *
+ * The log file defaults to /tmp/omnirec-picker.log and can be moved with
+ * --log-path or the OMNIREC_PICKER_LOG environment variable.
+ *
+ * If the main app doesn't answer within OMNIREC_PICKER_TIMEOUT_MS (default
+ * 2000) on either of two attempts, the fallback picker runs so the portal
+ * request still completes.
  */
 
 #include "ipc_client.h"
@@ -19,6 +30,7 @@
 
 #include <QApplication>
 #include <QCommandLineParser>
+#include <QCoreApplication>
 #include <iostream>
 #include <cstdlib>
 #include <unistd.h>
@@ -34,6 +46,11 @@ static void printHelp()
               << "  --dry-run              Test the dialog without IPC\n"
               << "  --source-type TYPE     Source type: monitor, window, region (default: monitor)\n"
               << "  --source-id ID         Source identifier (default: DP-1)\n"
+              << "  --list                 Print the current capture selection as JSON and exit\n"
+              << "  --set-selection        Pre-select the source for the next portal request\n"
+              << "    --type TYPE          Selection type: monitor, window\n"
+              << "    --id ID              Monitor name or window address\n"
+              << "  --log-path PATH        Log file (default: $OMNIREC_PICKER_LOG or /tmp/omnirec-picker.log)\n"
               << "  --help, -h             Show this help\n";
 }
 
@@ -44,6 +61,11 @@ struct Args {
     bool dryRun = false;
     QString sourceType = "monitor";
     QString sourceId = "DP-1";
+    QString logPath;
+    bool list = false;
+    bool setSelection = false;
+    QString selectionType;
+    QString selectionId;
     bool showHelp = false;
 };
 
@@ -60,6 +82,16 @@ static Args parseArgs(int argc, char* argv[])
             args.sourceType = QString::fromUtf8(argv[++i]);
         } else if (arg == "--source-id" && i + 1 < argc) {
             args.sourceId = QString::fromUtf8(argv[++i]);
+        } else if (arg == "--list") {
+            args.list = true;
+        } else if (arg == "--set-selection") {
+            args.setSelection = true;
+        } else if (arg == "--type" && i + 1 < argc) {
+            args.selectionType = QString::fromUtf8(argv[++i]);
+        } else if (arg == "--id" && i + 1 < argc) {
+            args.selectionId = QString::fromUtf8(argv[++i]);
+        } else if (arg == "--log-path" && i + 1 < argc) {
+            args.logPath = QString::fromUtf8(argv[++i]);
         } else if (arg == "--help" || arg == "-h") {
             args.showHelp = true;
         }
@@ -97,6 +129,50 @@ static int runDryRun(const QString& sourceType, const QString& sourceId)
     }
 }
 
+/**
+ * Print the current capture selection as JSON.
+ */
+static int runList()
+{
+    QString error;
+    IpcResponse response = querySelection(&error);
+    
+    if (response.type == ResponseType::Error) {
+        std::cerr << "[omnirec-picker] Failed to query selection: "
+                  << response.errorMessage.toStdString() << std::endl;
+        return 1;
+    }
+    
+    std::cout << selectionToJson(response).toStdString();
+    std::cout.flush();
+    return 0;
+}
+
+/**
+ * Pre-select a source for the next portal request.
+ */
+static int runSetSelection(const QString& type, const QString& id)
+{
+    if (type != "monitor" && type != "window") {
+        std::cerr << "[omnirec-picker] --set-selection requires --type monitor or --type window"
+                  << std::endl;
+        return 1;
+    }
+    if (id.isEmpty()) {
+        std::cerr << "[omnirec-picker] --set-selection requires --id" << std::endl;
+        return 1;
+    }
+    
+    QString error;
+    if (!setSelection(type, id, &error)) {
+        std::cerr << "[omnirec-picker] Failed to set selection: " << error.toStdString() << std::endl;
+        return 1;
+    }
+    
+    pickerLog(QString("[omnirec-picker] Selection set: type=%1, id=%2").arg(type).arg(id));
+    return 0;
+}
+
 /**
  * Main picker logic.
  */
@@ -225,6 +301,17 @@ int main(int argc, char* argv[])
         return 0;
     }
     
+    if (!args.logPath.isEmpty()) {
+        setPickerLogPath(args.logPath);
+    }
+    
+    // Scripting modes only talk to the service, so they don't need a display
+    if (args.list || args.setSelection) {
+        QCoreApplication app(argc, argv);
+        app.setApplicationName("omnirec-picker");
+        return args.list ? runList() : runSetSelection(args.selectionType, args.selectionId);
+    }
+    
     // Set up Qt for Wayland
     qputenv("QT_WAYLAND_FORCE_DPI", "96");
     
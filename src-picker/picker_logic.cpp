@@ -3,6 +3,7 @@
  */
 
 #include "picker_logic.h"
+#include "log_rotation.h"
 
 #include <QProcess>
 #include <QFile>
@@ -11,13 +12,33 @@
 #include <iostream>
 #include <cstdlib>
 
+static const char* const kDefaultLogPath = "/tmp/omnirec-picker.log";
+
+static QString logPathOverride;
+
+void setPickerLogPath(const QString& path)
+{
+    logPathOverride = path;
+}
+
+QString pickerLogPath()
+{
+    if (!logPathOverride.isEmpty()) {
+        return logPathOverride;
+    }
+    QString envPath = qEnvironmentVariable("OMNIREC_PICKER_LOG");
+    return envPath.isEmpty() ? QString(kDefaultLogPath) : envPath;
+}
+
 void pickerLog(const QString& msg)
 {
     // Print to stderr for manual testing
     std::cerr << msg.toStdString() << std::endl;
     
-    // Write to log file
-    QFile file("/tmp/omnirec-picker.log");
+    // Write to log file, starting a fresh one if it has grown too large
+    QString path = pickerLogPath();
+    rotateLogIfNeeded(QFile::encodeName(path).toStdString());
+    QFile file(path);
     if (file.open(QIODevice::Append | QIODevice::Text)) {
         QTextStream out(&file);
         qint64 timestamp = QDateTime::currentSecsSinceEpoch();
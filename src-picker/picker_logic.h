@@ -52,7 +52,20 @@ QString formatRegionOutput(const QString& sourceId, int x, int y, unsigned int w
 int runFallbackPicker();
 
 /**
- * Log a message to stderr and to /tmp/omnirec-picker.log.
+ * Override the log file path (e.g. from --log-path).
+ */
+void setPickerLogPath(const QString& path);
+
+/**
+ * Current log file path: the --log-path override, then $OMNIREC_PICKER_LOG,
+ * then /tmp/omnirec-picker.log.
+ */
+QString pickerLogPath();
+
+/**
+ * Log a message to stderr and to the picker log file.
+ *
+ * The log is rotated to "<path>.1" once it exceeds 1 MB.
  */
 void pickerLog(const QString& msg);
 
@@ -0,0 +1,119 @@
+/**
+ * Unit tests for picker log rotation.
+ */
+
+#include "../log_rotation.h"
+
+#include <cstdlib>
+#include <filesystem>
+#include <fstream>
+#include <iostream>
+#include <string>
+#include <unistd.h>
+
+namespace fs = std::filesystem;
+
+static int failures = 0;
+
+#define CHECK(cond)                                                              \
+    do {                                                                         \
+        if (!(cond)) {                                                           \
+            std::cerr << __FILE__ << ":" << __LINE__ << ": CHECK failed: " #cond \
+                      << std::endl;                                              \
+            ++failures;                                                          \
+        }                                                                        \
+    } while (0)
+
+static fs::path makeTempDir(const std::string& name)
+{
+    fs::path dir = fs::temp_directory_path() /
+                   ("omnirec-picker-test-" + name + "-" + std::to_string(getpid()));
+    fs::remove_all(dir);
+    fs::create_directories(dir);
+    return dir;
+}
+
+static void writeBytes(const fs::path& path, std::size_t count, char fill = 'x')
+{
+    std::ofstream out(path, std::ios::binary | std::ios::trunc);
+    out << std::string(count, fill);
+}
+
+static void testSmallLogIsKept()
+{
+    fs::path dir = makeTempDir("small");
+    fs::path log = dir / "picker.log";
+    writeBytes(log, 100);
+
+    CHECK(!rotateLogIfNeeded(log.string(), 1024));
+    CHECK(fs::file_size(log) == 100);
+    CHECK(!fs::exists(archivedLogPath(log.string())));
+
+    fs::remove_all(dir);
+}
+
+static void testMissingLogIsNotRotated()
+{
+    fs::path dir = makeTempDir("missing");
+    fs::path log = dir / "picker.log";
+
+    CHECK(!rotateLogIfNeeded(log.string(), 1024));
+    CHECK(!fs::exists(archivedLogPath(log.string())));
+
+    fs::remove_all(dir);
+}
+
+static void testLargeLogIsArchived()
+{
+    fs::path dir = makeTempDir("large");
+    fs::path log = dir / "picker.log";
+    writeBytes(log, 2048);
+
+    CHECK(rotateLogIfNeeded(log.string(), 1024));
+    CHECK(!fs::exists(log));
+    CHECK(fs::file_size(archivedLogPath(log.string())) == 2048);
+
+    fs::remove_all(dir);
+}
+
+static void testOnlyOneArchiveIsKept()
+{
+    fs::path dir = makeTempDir("single");
+    fs::path log = dir / "picker.log";
+    std::string archive = archivedLogPath(log.string());
+    writeBytes(archive, 4096, 'o');
+    writeBytes(log, 2048, 'n');
+
+    CHECK(rotateLogIfNeeded(log.string(), 1024));
+    CHECK(fs::file_size(archive) == 2048);
+    CHECK(!fs::exists(log.string() + ".2"));
+
+    std::size_t files = 0;
+    for ([[maybe_unused]] const auto& entry : fs::directory_iterator(dir)) {
+        ++files;
+    }
+    CHECK(files == 1);
+
+    fs::remove_all(dir);
+}
+
+static void testDefaultLimitIsOneMegabyte()
+{
+    CHECK(kPickerLogMaxBytes == 1024 * 1024);
+}
+
+int main()
+{
+    testSmallLogIsKept();
+    testMissingLogIsNotRotated();
+    testLargeLogIsArchived();
+    testOnlyOneArchiveIsKept();
+    testDefaultLimitIsOneMegabyte();
+
+    if (failures > 0) {
+        std::cerr << failures << " check(s) failed" << std::endl;
+        return EXIT_FAILURE;
+    }
+    std::cout << "All log rotation tests passed" << std::endl;
+    return EXIT_SUCCESS;
+}
@@ -0,0 +1,108 @@
+//! Throughput of the per-frame work of a recording: encoding, thumbnails and
+//! cropping.
+//!
+//! The encoder benchmarks need FFmpeg on PATH. Run with:
+//!
+//! ```text
+//! cargo bench -p omnirec --bench encoder_throughput
+//! ```
+//!
+//! Reports are written to `target/criterion/`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use omnirec_lib::bench::{
+    bgra_to_jpeg_thumbnail, timestamp_us, CapturedFrame, PixelFormat, VideoEncoder,
+    DEFAULT_JPEG_QUALITY, THUMBNAIL_MAX_HEIGHT, THUMBNAIL_MAX_WIDTH,
+};
+use std::hint::black_box;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Frame sizes of the encoder and thumbnail benchmarks.
+const RESOLUTIONS: [(&str, u32, u32); 3] = [
+    ("720p", 1280, 720),
+    ("1080p", 1920, 1080),
+    ("4K", 3840, 2160),
+];
+
+/// Region sizes cropped from a 4K frame.
+const CROP_SIZES: [(u32, u32); 4] = [(320, 240), (1280, 720), (1920, 1080), (3200, 1800)];
+
+/// A BGRA frame with a gradient, so the encoder has detail to work on.
+fn frame(width: u32, height: u32) -> CapturedFrame {
+    let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height {
+        for x in 0..width {
+            data.extend_from_slice(&[x as u8, y as u8, (x + y) as u8, 255]);
+        }
+    }
+    CapturedFrame {
+        width,
+        height,
+        data: data.into(),
+        pixel_format: PixelFormat::Bgra,
+        captured_at: SystemTime::now(),
+        timestamp_us: timestamp_us(Instant::now()),
+    }
+}
+
+/// Frames per second written to FFmpeg, output discarded.
+fn write_frame(c: &mut Criterion) {
+    let mut group = c.benchmark_group("write_frame");
+    group
+        .throughput(Throughput::Elements(1))
+        .sample_size(20)
+        .measurement_time(Duration::from_secs(10));
+    for (name, width, height) in RESOLUTIONS {
+        let frame = frame(width, height);
+        let mut encoder = VideoEncoder::new_discarding(width, height, PixelFormat::Bgra).unwrap();
+        encoder.start(None).unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| encoder.write_frame(black_box(&frame)).unwrap())
+        });
+        encoder.finish().unwrap();
+    }
+    group.finish();
+}
+
+/// Frames per second turned into preview thumbnails.
+fn jpeg_thumbnail(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bgra_to_jpeg_thumbnail");
+    group.throughput(Throughput::Elements(1));
+    for (name, width, height) in RESOLUTIONS {
+        let frame = frame(width, height);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                bgra_to_jpeg_thumbnail(
+                    black_box(&frame.data),
+                    width,
+                    height,
+                    THUMBNAIL_MAX_WIDTH,
+                    THUMBNAIL_MAX_HEIGHT,
+                    DEFAULT_JPEG_QUALITY,
+                )
+                .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Bytes per second copied out of a 4K frame for region captures.
+fn crop(c: &mut Criterion) {
+    let source = frame(3840, 2160);
+    let mut group = c.benchmark_group("crop_frame");
+    for (width, height) in CROP_SIZES {
+        group.throughput(Throughput::Bytes(width as u64 * height as u64 * 4));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}x{}", width, height)),
+            &(width, height),
+            |b, &(width, height)| {
+                b.iter(|| black_box(&source).crop(100, 100, width, height).unwrap())
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, write_frame, jpeg_thumbnail, crop);
+criterion_main!(benches);
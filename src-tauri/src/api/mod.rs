@@ -0,0 +1,155 @@
+//! Optional HTTP REST API for headless servers.
+//!
+//! Enabled with `--listen <addr>`. Each endpoint maps to an IPC request and
+//! is answered by the same handlers as the CLI socket. Mutating endpoints
+//! and the `GET /ws` event stream require a bearer token from `--api-token`
+//! or `OMNIREC_API_TOKEN`; the API is not started without one. See
+//! `docs/api/openapi.yaml`.
+
+mod routes;
+mod ws;
+
+pub(crate) use routes::require_token;
+
+use std::net::SocketAddr;
+use tracing::{info, warn};
+
+/// Environment variable holding the API bearer token.
+pub const API_TOKEN_ENV: &str = "OMNIREC_API_TOKEN";
+
+/// REST API settings parsed from the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiOptions {
+    /// Address the HTTP server binds to
+    pub listen: SocketAddr,
+    /// Bearer token required by mutating endpoints
+    pub token: String,
+}
+
+impl ApiOptions {
+    /// Parse `--listen` and `--api-token` from `args` (without the program
+    /// name), falling back to `env_token` for the token.
+    ///
+    /// Returns `Ok(None)` when `--listen` is absent.
+    pub fn from_args<I>(args: I, env_token: Option<String>) -> Result<Option<Self>, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut listen = None;
+        let mut token = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let slot = match name.as_str() {
+                "--listen" => &mut listen,
+                "--api-token" => &mut token,
+                _ => continue,
+            };
+            let value = inline
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} requires a value", name))?;
+            *slot = Some(value);
+        }
+
+        let Some(listen) = listen else {
+            return Ok(None);
+        };
+        let listen = listen
+            .parse::<SocketAddr>()
+            .map_err(|e| format!("Invalid --listen address '{}': {}", listen, e))?;
+        let token = token
+            .or(env_token)
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| {
+                format!(
+                    "--listen requires an API token (--api-token or {})",
+                    API_TOKEN_ENV
+                )
+            })?;
+
+        Ok(Some(Self { listen, token }))
+    }
+}
+
+/// Run the REST API server until it fails.
+pub async fn run_server(options: ApiOptions) -> Result<(), Box<dyn std::error::Error>> {
+    if !options.listen.ip().is_loopback() {
+        warn!(
+            "[API] Listening on non-loopback address {}; traffic is not encrypted",
+            options.listen
+        );
+    }
+
+    let listener = tokio::net::TcpListener::bind(options.listen).await?;
+    info!("[API] REST API listening on http://{}", options.listen);
+    axum::serve(listener, routes::router(options.token)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_args_without_listen() {
+        assert_eq!(ApiOptions::from_args(args(&["--headless"]), None), Ok(None));
+    }
+
+    #[test]
+    fn test_from_args_parses_listen_and_token() {
+        let expected = ApiOptions {
+            listen: "127.0.0.1:7890".parse().unwrap(),
+            token: "secret".to_string(),
+        };
+        assert_eq!(
+            ApiOptions::from_args(
+                args(&[
+                    "--headless",
+                    "--listen",
+                    "127.0.0.1:7890",
+                    "--api-token",
+                    "secret"
+                ]),
+                None
+            ),
+            Ok(Some(expected.clone()))
+        );
+        assert_eq!(
+            ApiOptions::from_args(
+                args(&["--listen=127.0.0.1:7890"]),
+                Some("secret".to_string())
+            ),
+            Ok(Some(expected))
+        );
+    }
+
+    #[test]
+    fn test_from_args_flag_token_overrides_env() {
+        let options = ApiOptions::from_args(
+            args(&["--listen", "127.0.0.1:7890", "--api-token=flag"]),
+            Some("env".to_string()),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(options.token, "flag");
+    }
+
+    #[test]
+    fn test_from_args_rejects_invalid_options() {
+        assert!(ApiOptions::from_args(args(&["--listen"]), Some("t".to_string())).is_err());
+        assert!(ApiOptions::from_args(args(&["--listen", "7890"]), Some("t".to_string())).is_err());
+        assert!(ApiOptions::from_args(args(&["--listen", "127.0.0.1:7890"]), None).is_err());
+        assert!(
+            ApiOptions::from_args(args(&["--listen", "127.0.0.1:7890"]), Some(String::new()))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! REST API routes and their mapping onto IPC requests.
+
+use crate::ipc::handlers::{handle_request, handle_start_request};
+use axum::extract::{Request as HttpRequest, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response as HttpResponse};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use omnirec_types::ipc::{Request, Response};
+use omnirec_types::{PipConfig, RecordingState};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Build the API router; `token` guards the mutating endpoints and the
+/// event stream.
+pub fn router(token: String) -> Router {
+    let token = Arc::new(token);
+
+    let protected = Router::new()
+        .route("/record/start", post(start_recording))
+        .route("/record/stop", post(stop_recording))
+        .merge(super::ws::router())
+        .route_layer(middleware::from_fn_with_state(token, require_token));
+
+    Router::new()
+        .route("/status", get(status))
+        .route("/sources/windows", get(list_windows))
+        .route("/sources/displays", get(list_displays))
+        .route("/sources/audio", get(list_audio))
+        .merge(crate::metrics::router())
+        .merge(protected)
+}
+
+/// Reject requests without a matching `Authorization: Bearer` header.
+pub(crate) async fn require_token(
+    State(token): State<Arc<String>>,
+    request: HttpRequest,
+    next: Next,
+) -> HttpResponse {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided {
+        Some(provided) if token_matches(provided, &token) => next.run(request).await,
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(Response::error("Missing or invalid API token")),
+        )
+            .into_response(),
+    }
+}
+
+/// Compare tokens without returning early on the first mismatching byte.
+fn token_matches(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Body of `GET /status`.
+#[derive(Debug, Serialize)]
+struct StatusBody {
+    state: RecordingState,
+    elapsed_seconds: u64,
+}
+
+async fn status() -> HttpResponse {
+    let state = match handle_request(Request::GetRecordingState).await {
+        Response::RecordingState { state } => state,
+        other => return into_http(other),
+    };
+    let elapsed_seconds = match handle_request(Request::GetElapsedTime).await {
+        Response::ElapsedTime { seconds } => seconds,
+        other => return into_http(other),
+    };
+    Json(StatusBody {
+        state,
+        elapsed_seconds,
+    })
+    .into_response()
+}
+
+/// Body of `POST /record/start`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct StartRecordingRequest {
+    #[serde(flatten)]
+    target: StartTarget,
+    /// Webcam to overlay on this recording, in place of the configured one
+    #[serde(default)]
+    pip: Option<PipConfig>,
+}
+
+/// Capture target of a [`StartRecordingRequest`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "target", rename_all = "snake_case")]
+enum StartTarget {
+    Window {
+        window_handle: isize,
+    },
+    Display {
+        monitor_id: String,
+        width: u32,
+        height: u32,
+    },
+    Region {
+        monitor_id: String,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+    Portal,
+    Workspace {
+        workspace_id: i32,
+    },
+}
+
+impl From<StartTarget> for Request {
+    fn from(target: StartTarget) -> Self {
+        match target {
+            StartTarget::Window { window_handle } => Request::StartWindowCapture { window_handle },
+            StartTarget::Display {
+                monitor_id,
+                width,
+                height,
+            } => Request::StartDisplayCapture {
+                monitor_id,
+                width,
+                height,
+            },
+            StartTarget::Region {
+                monitor_id,
+                x,
+                y,
+                width,
+                height,
+            } => Request::StartRegionCapture {
+                monitor_id,
+                x,
+                y,
+                width,
+                height,
+            },
+            StartTarget::Portal => Request::StartPortalCapture,
+            StartTarget::Workspace { workspace_id } => {
+                Request::StartWorkspaceCapture { workspace_id }
+            }
+        }
+    }
+}
+
+async fn start_recording(Json(body): Json<StartRecordingRequest>) -> HttpResponse {
+    let request = Request::from(body.target);
+    let valid = request
+        .validate()
+        .map_err(|e| e.to_string())
+        .and_then(|_| body.pip.as_ref().map_or(Ok(()), PipConfig::validate));
+    if let Err(e) = valid {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(Response::error(format!("Invalid request: {}", e))),
+        )
+            .into_response();
+    }
+    into_http(handle_start_request(request, body.pip).await)
+}
+
+async fn stop_recording() -> HttpResponse {
+    into_http(handle_request(Request::StopRecording).await)
+}
+
+async fn list_windows() -> HttpResponse {
+    into_http(handle_request(Request::ListWindows).await)
+}
+
+async fn list_displays() -> HttpResponse {
+    into_http(handle_request(Request::ListMonitors).await)
+}
+
+async fn list_audio() -> HttpResponse {
+    into_http(handle_request(Request::ListAudioSources).await)
+}
+
+/// Serialize an IPC response; errors become `409 Conflict`, since they
+/// describe a request the service could not carry out in its current state.
+fn into_http(response: Response) -> HttpResponse {
+    let status = if response.is_error() {
+        StatusCode::CONFLICT
+    } else {
+        StatusCode::OK
+    };
+    (status, Json(response)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_matches() {
+        assert!(token_matches("secret", "secret"));
+        assert!(!token_matches("secreT", "secret"));
+        assert!(!token_matches("secret2", "secret"));
+        assert!(!token_matches("", "secret"));
+    }
+
+    #[test]
+    fn test_start_target_maps_to_request() {
+        let target: StartTarget = serde_json::from_str(
+            r#"{"target":"display","monitor_id":"DP-1","width":1920,"height":1080}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            Request::from(target),
+            Request::StartDisplayCapture { ref monitor_id, width: 1920, height: 1080 }
+                if monitor_id == "DP-1"
+        ));
+
+        let target: StartTarget = serde_json::from_str(r#"{"target":"portal"}"#).unwrap();
+        assert!(matches!(Request::from(target), Request::StartPortalCapture));
+
+        assert!(serde_json::from_str::<StartTarget>(r#"{"target":"tab"}"#).is_err());
+    }
+
+    #[test]
+    fn test_start_request_with_pip() {
+        let body: StartRecordingRequest = serde_json::from_str(
+            r#"{"target":"window","window_handle":42,
+                "pip":{"camera_id":"0","position":"top_right","size_fraction":0.2}}"#,
+        )
+        .unwrap();
+        assert_eq!(body.target, StartTarget::Window { window_handle: 42 });
+        let pip = body.pip.unwrap();
+        assert_eq!(pip.camera_id, "0");
+        assert_eq!(pip.position, omnirec_types::PipPosition::TopRight);
+
+        let body: StartRecordingRequest = serde_json::from_str(r#"{"target":"portal"}"#).unwrap();
+        assert_eq!(body.pip, None);
+    }
+}
@@ -0,0 +1,180 @@
+//! WebSocket event stream (`GET /ws`).
+//!
+//! Forwards the recording manager's [`ServiceEvent`] broadcast to network
+//! clients as [`ServerEvent`] JSON text messages.
+
+use crate::state::{get_recording_manager, ServiceEvent};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response as HttpResponse};
+use axum::routing::get;
+use axum::{Json, Router};
+use omnirec_types::ipc::Response;
+use omnirec_types::RecordingState;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, warn};
+
+/// Maximum number of concurrent event stream connections.
+pub const MAX_WS_CONNECTIONS: usize = 8;
+
+/// Event pushed to WebSocket clients.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerEvent {
+    /// A recording started
+    RecordingStarted,
+    /// The recording was stopped and saved
+    RecordingStopped,
+    /// The recording was paused because the system is going to sleep
+    RecordingSuspended,
+    /// A suspended recording continued after `gap_seconds` of sleep
+    RecordingResumed { gap_seconds: f64 },
+    /// Periodic update while recording
+    StatsUpdate { elapsed_seconds: u64 },
+    /// Something went wrong during or after a recording
+    Error { message: String },
+}
+
+impl ServerEvent {
+    /// The client-facing event for a service event, if any.
+    fn from_service_event(event: ServiceEvent) -> Option<Self> {
+        match event {
+            ServiceEvent::StateChanged(RecordingState::Recording) => Some(Self::RecordingStarted),
+            ServiceEvent::StateChanged(RecordingState::Idle) => Some(Self::RecordingStopped),
+            ServiceEvent::StateChanged(RecordingState::Suspended) => Some(Self::RecordingSuspended),
+            ServiceEvent::RecordingResumed { gap_secs } => Some(Self::RecordingResumed {
+                gap_seconds: gap_secs,
+            }),
+            ServiceEvent::ElapsedTime(elapsed_seconds) => {
+                Some(Self::StatsUpdate { elapsed_seconds })
+            }
+            ServiceEvent::TranscodingComplete { success: false, .. } => Some(Self::Error {
+                message: "Transcoding failed".to_string(),
+            }),
+            ServiceEvent::CaptureDisconnected { reason, .. } => Some(Self::Error {
+                message: format!("Capture disconnected: {}", reason),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Router for `GET /ws`, limited to [`MAX_WS_CONNECTIONS`] clients.
+pub fn router() -> Router {
+    Router::new()
+        .route("/ws", get(upgrade))
+        .with_state(Arc::new(Semaphore::new(MAX_WS_CONNECTIONS)))
+}
+
+async fn upgrade(State(slots): State<Arc<Semaphore>>, ws: WebSocketUpgrade) -> HttpResponse {
+    let Ok(permit) = slots.try_acquire_owned() else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(Response::error("Too many event stream connections")),
+        )
+            .into_response();
+    };
+    // Subscribe before the upgrade completes so no event is missed
+    let events = get_recording_manager().subscribe();
+    ws.on_upgrade(move |socket| stream_events(socket, events, permit))
+}
+
+/// Send events to `socket` until either side closes.
+async fn stream_events(
+    mut socket: WebSocket,
+    mut events: broadcast::Receiver<ServiceEvent>,
+    _permit: OwnedSemaphorePermit,
+) {
+    debug!("[API] Event stream client connected");
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(ServiceEvent::Shutdown) | Err(RecvError::Closed) => {
+                    let _ = socket.send(Message::Close(None)).await;
+                    break;
+                }
+                Ok(event) => {
+                    let Some(event) = ServerEvent::from_service_event(event) else {
+                        continue;
+                    };
+                    let Ok(text) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(n)) => {
+                    warn!("[API] Event stream lagged: {} events skipped", n);
+                }
+            },
+            incoming = socket.recv() => match incoming {
+                // Pings are answered by axum; other client messages are ignored
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    debug!("[API] Event stream client disconnected");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_service_event() {
+        assert_eq!(
+            ServerEvent::from_service_event(ServiceEvent::StateChanged(RecordingState::Recording)),
+            Some(ServerEvent::RecordingStarted)
+        );
+        assert_eq!(
+            ServerEvent::from_service_event(ServiceEvent::StateChanged(RecordingState::Saving)),
+            None
+        );
+        assert_eq!(
+            ServerEvent::from_service_event(ServiceEvent::StateChanged(RecordingState::Idle)),
+            Some(ServerEvent::RecordingStopped)
+        );
+        assert_eq!(
+            ServerEvent::from_service_event(ServiceEvent::StateChanged(RecordingState::Suspended)),
+            Some(ServerEvent::RecordingSuspended)
+        );
+        assert_eq!(
+            ServerEvent::from_service_event(ServiceEvent::RecordingResumed { gap_secs: 30.5 }),
+            Some(ServerEvent::RecordingResumed { gap_seconds: 30.5 })
+        );
+        assert_eq!(
+            ServerEvent::from_service_event(ServiceEvent::ElapsedTime(12)),
+            Some(ServerEvent::StatsUpdate {
+                elapsed_seconds: 12
+            })
+        );
+        assert!(matches!(
+            ServerEvent::from_service_event(ServiceEvent::CaptureDisconnected {
+                reason: "window closed".to_string(),
+                path: None,
+            }),
+            Some(ServerEvent::Error { .. })
+        ));
+        assert_eq!(
+            ServerEvent::from_service_event(ServiceEvent::TranscodingComplete {
+                success: true,
+                path: None,
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_server_event_json() {
+        assert_eq!(
+            serde_json::to_string(&ServerEvent::StatsUpdate { elapsed_seconds: 3 }).unwrap(),
+            r#"{"type":"stats_update","elapsed_seconds":3}"#
+        );
+    }
+}
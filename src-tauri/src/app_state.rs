@@ -0,0 +1,131 @@
+//! Shared application state managed by Tauri.
+//!
+//! Each piece of state is its own managed type, so a command extracts only
+//! what it uses (`State<'_, AppConfigState>`) and can be tested with just
+//! that piece. [`AppStateBuilder`] creates all of them, for the app and for
+//! tests.
+//!
+//! The RecordingManager is a `'static` singleton accessed via
+//! `state::get_recording_manager()` — it is not managed by Tauri.
+
+use crate::config::AppConfig;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{Manager, Runtime};
+use tokio::sync::Mutex;
+
+/// Application configuration (UI preferences, output directory, etc.).
+pub struct AppConfigState {
+    pub app_config: Arc<Mutex<AppConfig>>,
+}
+
+/// Whether the recording subsystem is initialized and ready.
+pub struct ServiceReadyState {
+    pub service_ready: Arc<AtomicBool>,
+}
+
+impl ServiceReadyState {
+    /// Check if the recording subsystem is ready.
+    pub fn is_service_ready(&self) -> bool {
+        self.service_ready.load(Ordering::SeqCst)
+    }
+}
+
+/// Whether the app was launched in headless mode (--headless).
+pub struct HeadlessState {
+    pub headless: bool,
+}
+
+/// Builder for the managed application state.
+#[derive(Default)]
+pub struct AppStateBuilder {
+    config: AppConfig,
+    service_ready: bool,
+    headless: bool,
+}
+
+/// The managed application state, as built by [`AppStateBuilder`].
+pub struct AppStates {
+    pub config: AppConfigState,
+    pub service_ready: ServiceReadyState,
+    pub headless: HeadlessState,
+}
+
+impl AppStateBuilder {
+    /// Start from the default configuration, not ready and not headless.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `config` as the application configuration.
+    pub fn config(mut self, config: AppConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Mark the recording subsystem as ready.
+    pub fn service_ready(mut self, ready: bool) -> Self {
+        self.service_ready = ready;
+        self
+    }
+
+    /// Mark the app as launched in headless mode.
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
+    }
+
+    /// Create the state.
+    pub fn build(self) -> AppStates {
+        AppStates {
+            config: AppConfigState {
+                app_config: Arc::new(Mutex::new(self.config)),
+            },
+            service_ready: ServiceReadyState {
+                service_ready: Arc::new(AtomicBool::new(self.service_ready)),
+            },
+            headless: HeadlessState {
+                headless: self.headless,
+            },
+        }
+    }
+}
+
+impl AppStates {
+    /// Hand each piece of state to Tauri.
+    pub fn manage<R: Runtime, M: Manager<R>>(self, manager: &M) {
+        manager.manage(self.config);
+        manager.manage(self.service_ready);
+        manager.manage(self.headless);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_sets_each_state() {
+        let config = AppConfig {
+            always_on_top: true,
+            ..AppConfig::default()
+        };
+        let states = AppStateBuilder::new()
+            .config(config)
+            .service_ready(true)
+            .headless(true)
+            .build();
+
+        assert!(states.config.app_config.blocking_lock().always_on_top);
+        assert!(states.service_ready.is_service_ready());
+        assert!(states.headless.headless);
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let states = AppStateBuilder::new().build();
+
+        assert!(!states.service_ready.is_service_ready());
+        assert!(!states.headless.headless);
+    }
+}
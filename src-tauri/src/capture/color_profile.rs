@@ -0,0 +1,193 @@
+//! Display color profiles.
+//!
+//! Frames are captured in the color space of the display they come from. A
+//! recording of a Display P3 screen that is not tagged as P3 is shown as
+//! sRGB by players and looks washed out. The display's ICC profile is read
+//! when a recording starts and the output is tagged with the matching
+//! primaries, transfer function and range.
+//!
+//! Profiles are read on macOS and Windows; Linux compositors do not expose
+//! them, so recordings there are tagged as sRGB.
+
+/// Color primaries of a display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorPrimaries {
+    /// sRGB / BT.709
+    Srgb,
+    /// Display P3
+    DisplayP3,
+    /// BT.2020
+    Bt2020,
+}
+
+impl ColorPrimaries {
+    /// Every supported gamut with the CIE x chromaticity of its red
+    /// colorant, as stored in a D50-adapted ICC profile.
+    const RED_X: [(ColorPrimaries, f64); 3] = [
+        (ColorPrimaries::Srgb, 0.648),
+        (ColorPrimaries::DisplayP3, 0.684),
+        (ColorPrimaries::Bt2020, 0.708),
+    ];
+
+    /// Name FFmpeg uses for these primaries (`-color_primaries`).
+    pub fn ffmpeg_name(self) -> &'static str {
+        match self {
+            ColorPrimaries::Srgb => "bt709",
+            ColorPrimaries::DisplayP3 => "smpte432",
+            ColorPrimaries::Bt2020 => "bt2020",
+        }
+    }
+
+    /// Gamut whose red colorant is nearest to chromaticity `red_x`.
+    fn nearest(red_x: f64) -> Self {
+        Self::RED_X
+            .iter()
+            .min_by(|a, b| (a.1 - red_x).abs().total_cmp(&(b.1 - red_x).abs()))
+            .map(|(primaries, _)| *primaries)
+            .unwrap_or(ColorPrimaries::Srgb)
+    }
+}
+
+/// Color profile of a display, as written to the output file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorProfile {
+    /// Gamut of the display.
+    pub primaries: ColorPrimaries,
+}
+
+impl Default for ColorProfile {
+    fn default() -> Self {
+        Self {
+            primaries: ColorPrimaries::Srgb,
+        }
+    }
+}
+
+impl ColorProfile {
+    /// Read the profile from ICC profile data.
+    ///
+    /// Only the red colorant (`rXYZ` tag) is used to pick the gamut; the
+    /// transfer function of SDR desktop profiles is always sRGB. Returns
+    /// `None` for data that isn't a matrix-based ICC profile.
+    pub fn from_icc(data: &[u8]) -> Option<Self> {
+        let [x, y, z] = icc_xyz_tag(data, b"rXYZ")?;
+        let sum = x + y + z;
+        if sum <= 0.0 {
+            return None;
+        }
+        Some(Self {
+            primaries: ColorPrimaries::nearest(x / sum),
+        })
+    }
+
+    /// FFmpeg output arguments that tag the video with this profile.
+    ///
+    /// The range is limited ("tv"), matching FFmpeg's RGB to YUV
+    /// conversion.
+    pub fn ffmpeg_args(&self) -> [&'static str; 6] {
+        [
+            "-color_primaries",
+            self.primaries.ffmpeg_name(),
+            "-color_trc",
+            "iec61966-2-1",
+            "-color_range",
+            "tv",
+        ]
+    }
+}
+
+/// Color profile of a monitor, or `None` if it can't be read.
+pub fn get_display_color_profile(monitor_id: &str) -> Option<ColorProfile> {
+    #[cfg(target_os = "macos")]
+    {
+        super::macos::monitor_list::get_display_color_profile(monitor_id)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        super::windows::get_display_color_profile(monitor_id)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = monitor_id;
+        None
+    }
+}
+
+/// Size of the ICC profile header, after which the tag table starts.
+const ICC_HEADER_LEN: usize = 128;
+
+/// Read an `XYZType` tag from ICC profile data.
+fn icc_xyz_tag(data: &[u8], signature: &[u8; 4]) -> Option<[f64; 3]> {
+    if data.get(36..40)? != b"acsp" {
+        return None;
+    }
+    let count = read_u32(data, ICC_HEADER_LEN)? as usize;
+    let entry = (0..count)
+        .map(|i| ICC_HEADER_LEN + 4 + i * 12)
+        .find(|&entry| data.get(entry..entry + 4) == Some(signature.as_slice()))?;
+    let offset = read_u32(data, entry + 4)? as usize;
+    let size = read_u32(data, entry + 8)? as usize;
+    let tag = data.get(offset..offset.checked_add(size)?)?;
+    if tag.get(0..4)? != b"XYZ " || tag.len() < 20 {
+        return None;
+    }
+    let fixed = |at: usize| read_u32(tag, at).map(|v| v as i32 as f64 / 65536.0);
+    Some([fixed(8)?, fixed(12)?, fixed(16)?])
+}
+
+fn read_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal ICC profile with only an `rXYZ` tag.
+    fn icc_with_red(xyz: [f64; 3]) -> Vec<u8> {
+        let mut data = vec![0u8; ICC_HEADER_LEN];
+        data[36..40].copy_from_slice(b"acsp");
+        let tag_offset = (ICC_HEADER_LEN + 4 + 12) as u32;
+        data.extend_from_slice(&1u32.to_be_bytes());
+        data.extend_from_slice(b"rXYZ");
+        data.extend_from_slice(&tag_offset.to_be_bytes());
+        data.extend_from_slice(&20u32.to_be_bytes());
+        data.extend_from_slice(b"XYZ \0\0\0\0");
+        for v in xyz {
+            data.extend_from_slice(&((v * 65536.0).round() as i32).to_be_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn icc_red_colorant_picks_gamut() {
+        let srgb = icc_with_red([0.4361, 0.2225, 0.0139]);
+        let p3 = icc_with_red([0.5151, 0.2412, -0.0011]);
+        let bt2020 = icc_with_red([0.6734, 0.2790, -0.0019]);
+        assert_eq!(
+            ColorProfile::from_icc(&srgb).unwrap().primaries,
+            ColorPrimaries::Srgb
+        );
+        assert_eq!(
+            ColorProfile::from_icc(&p3).unwrap().primaries,
+            ColorPrimaries::DisplayP3
+        );
+        assert_eq!(
+            ColorProfile::from_icc(&bt2020).unwrap().primaries,
+            ColorPrimaries::Bt2020
+        );
+    }
+
+    #[test]
+    fn icc_rejects_invalid_data() {
+        assert_eq!(ColorProfile::from_icc(&[]), None);
+        let mut missing_signature = icc_with_red([0.4361, 0.2225, 0.0139]);
+        missing_signature[36..40].copy_from_slice(b"xxxx");
+        assert_eq!(ColorProfile::from_icc(&missing_signature), None);
+        let mut truncated = icc_with_red([0.4361, 0.2225, 0.0139]);
+        truncated.truncate(truncated.len() - 4);
+        assert_eq!(ColorProfile::from_icc(&truncated), None);
+    }
+}
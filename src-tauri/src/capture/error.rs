@@ -21,6 +21,12 @@ pub enum CaptureError {
     Cancelled,
     /// Audio-specific error
     AudioError(String),
+    /// xdg-desktop-portal screencast request failed (Linux)
+    Portal(PortalError),
+    /// PipeWire stream setup or streaming failed (Linux)
+    PipeWire(PipeWireError),
+    /// FFmpeg could not be located or run
+    Ffmpeg(FfmpegError),
 }
 
 impl fmt::Display for CaptureError {
@@ -34,15 +40,162 @@ impl fmt::Display for CaptureError {
             CaptureError::NotImplemented(msg) => write!(f, "Not implemented: {}", msg),
             CaptureError::Cancelled => write!(f, "Capture cancelled"),
             CaptureError::AudioError(msg) => write!(f, "Audio error: {}", msg),
+            // Sub-errors are reported through `source()`, so the chain
+            // formatter doesn't print the inner message twice.
+            CaptureError::Portal(_) => write!(f, "Screencast portal request failed"),
+            CaptureError::PipeWire(_) => write!(f, "PipeWire capture failed"),
+            CaptureError::Ffmpeg(_) => write!(f, "FFmpeg error"),
         }
     }
 }
 
-impl std::error::Error for CaptureError {}
+impl std::error::Error for CaptureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CaptureError::Portal(e) => Some(e),
+            CaptureError::PipeWire(e) => Some(e),
+            CaptureError::Ffmpeg(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl From<CaptureError> for String {
     fn from(err: CaptureError) -> Self {
-        err.to_string()
+        omnirec_types::ipc::format_error_chain(&err)
+    }
+}
+
+impl From<PortalError> for CaptureError {
+    fn from(err: PortalError) -> Self {
+        CaptureError::Portal(err)
+    }
+}
+
+impl From<PipeWireError> for CaptureError {
+    fn from(err: PipeWireError) -> Self {
+        CaptureError::PipeWire(err)
+    }
+}
+
+impl From<FfmpegError> for CaptureError {
+    fn from(err: FfmpegError) -> Self {
+        CaptureError::Ffmpeg(err)
+    }
+}
+
+/// Error from the xdg-desktop-portal screencast flow.
+#[derive(Debug)]
+pub enum PortalError {
+    /// Could not connect to the screencast portal over D-Bus
+    Connection(String),
+    /// A portal call (session, source selection, start) failed
+    RequestFailed(String),
+    /// The user or picker dismissed the request
+    Cancelled,
+    /// The portal responded without any streams
+    NoStreams,
+    /// The portal reported a transient failure (compositor busy)
+    Busy(String),
+    /// The request kept failing after all retry attempts
+    Timeout,
+}
+
+impl fmt::Display for PortalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortalError::Connection(msg) => {
+                write!(f, "Failed to connect to screencast portal: {}", msg)
+            }
+            PortalError::RequestFailed(msg) => write!(f, "{}", msg),
+            PortalError::Cancelled => write!(f, "Screencast request was cancelled"),
+            PortalError::NoStreams => write!(f, "No streams returned from portal"),
+            PortalError::Busy(msg) => write!(f, "Compositor busy: {}", msg),
+            PortalError::Timeout => write!(f, "Compositor busy, try again"),
+        }
+    }
+}
+
+impl std::error::Error for PortalError {}
+
+impl PortalError {
+    /// Whether the request may succeed if retried.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, PortalError::Busy(_))
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl From<ashpd::Error> for PortalError {
+    fn from(err: ashpd::Error) -> Self {
+        match err {
+            ashpd::Error::Response(ashpd::desktop::ResponseError::Cancelled) => {
+                PortalError::Cancelled
+            }
+            // org.freedesktop.portal.Error.Failed: usually the compositor is busy
+            ashpd::Error::Portal(ashpd::PortalError::Failed(msg)) => PortalError::Busy(msg),
+            ashpd::Error::PortalNotFound(_) | ashpd::Error::Zbus(_) => {
+                PortalError::Connection(err.to_string())
+            }
+            other => PortalError::RequestFailed(other.to_string()),
+        }
+    }
+}
+
+/// Error from a PipeWire capture stream.
+#[derive(Debug)]
+pub enum PipeWireError {
+    /// The PipeWire main loop, context, or core connection could not be created
+    Connection(String),
+    /// The video stream could not be created, connected, or failed while running
+    Stream(String),
+}
+
+impl fmt::Display for PipeWireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipeWireError::Connection(msg) => write!(f, "PipeWire connection failed: {}", msg),
+            PipeWireError::Stream(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PipeWireError {}
+
+#[cfg(target_os = "linux")]
+impl From<pipewire::Error> for PipeWireError {
+    fn from(err: pipewire::Error) -> Self {
+        PipeWireError::Connection(err.to_string())
+    }
+}
+
+/// Error from locating or running the FFmpeg binary.
+#[derive(Debug)]
+pub enum FfmpegError {
+    /// The FFmpeg binary could not be found or executed
+    NotFound(String),
+    /// FFmpeg exited with a non-zero status
+    ExitStatus(Option<i32>),
+    /// Downloading FFmpeg as a fallback failed
+    DownloadFailed(String),
+}
+
+impl fmt::Display for FfmpegError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FfmpegError::NotFound(msg) => write!(f, "FFmpeg not found: {}", msg),
+            FfmpegError::ExitStatus(Some(code)) => write!(f, "FFmpeg exited with code {}", code),
+            FfmpegError::ExitStatus(None) => write!(f, "FFmpeg was terminated by a signal"),
+            FfmpegError::DownloadFailed(msg) => write!(f, "FFmpeg download failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FfmpegError {}
+
+impl From<std::io::Error> for FfmpegError {
+    fn from(err: std::io::Error) -> Self {
+        FfmpegError::NotFound(err.to_string())
     }
 }
 
@@ -71,3 +224,24 @@ impl From<EnumerationError> for String {
         err.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sub_error_is_reported_as_source() {
+        let err = CaptureError::from(PortalError::NoStreams);
+        let source = std::error::Error::source(&err).expect("portal error should be the source");
+        assert_eq!(source.to_string(), "No streams returned from portal");
+    }
+
+    #[test]
+    fn test_string_conversion_includes_chain() {
+        let err: String = CaptureError::Ffmpeg(FfmpegError::ExitStatus(Some(1))).into();
+        assert_eq!(err, "FFmpeg error: FFmpeg exited with code 1");
+
+        let err: String = CaptureError::InvalidRegion("too small".to_string()).into();
+        assert_eq!(err, "Invalid region: too small");
+    }
+}
@@ -0,0 +1,134 @@
+//! Capture frame rate limit.
+//!
+//! Recordings started with a limit (`omnirec record --limit-fps`) drop frames
+//! in the capture loops before they are copied to the encoder, so a busy
+//! display doesn't cost more than the limit allows. Frames are dropped rather
+//! than delayed because the capture callbacks must not block.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Instant;
+
+/// Frame rate limit of the current recording; 0 means no limit.
+static MAX_FPS: AtomicU32 = AtomicU32::new(0);
+
+/// Set the frame rate limit for captures started from now on. Set at the
+/// start of each recording and cleared when it stops.
+pub fn set_max_fps(max_fps: Option<u32>) {
+    MAX_FPS.store(max_fps.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Frame rate limit for captures started now, if any.
+pub fn max_fps() -> Option<u32> {
+    match MAX_FPS.load(Ordering::Relaxed) {
+        0 => None,
+        fps => Some(fps),
+    }
+}
+
+/// Token bucket admitting at most `rate` frames per second.
+///
+/// Credit left over after a frame is kept up to one frame interval, so a
+/// source that isn't a multiple of the rate still gets the full rate, but
+/// frames arriving in a burst after an idle period are not all admitted.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    rate: u32,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket admitting `rate` frames per second.
+    pub fn new(rate: u32) -> Self {
+        Self {
+            rate: rate.max(1),
+            tokens: 1.0,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Bucket for the current limit, or `None` when captures are unlimited.
+    pub fn for_current_limit() -> Option<Self> {
+        max_fps().map(Self::new)
+    }
+
+    /// Frames per second the bucket admits.
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    /// Take a token for a frame arriving now; `false` means drop the frame.
+    pub fn try_acquire(&mut self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate as f64).min(2.0);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Whether a frame passes `bucket`; always true without a limit.
+pub fn admit(bucket: &mut Option<TokenBucket>) -> bool {
+    bucket.as_mut().is_none_or(TokenBucket::try_acquire)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Count frames admitted out of `frames` arriving at `source_fps`.
+    fn admitted(rate: u32, source_fps: u32, frames: u32) -> u32 {
+        let mut bucket = TokenBucket::new(rate);
+        let start = bucket.last_refill;
+        let interval = Duration::from_secs_f64(1.0 / source_fps as f64);
+        (0..frames)
+            .filter(|&i| bucket.try_acquire_at(start + interval * i))
+            .count() as u32
+    }
+
+    #[test]
+    fn test_token_bucket_caps_rate() {
+        // One second of 60 fps capture limited to 30 and 24 fps
+        assert_eq!(admitted(30, 60, 60), 30);
+        assert_eq!(admitted(24, 60, 60), 24);
+    }
+
+    #[test]
+    fn test_token_bucket_keeps_leftover_credit() {
+        // 60 fps is not a multiple of 45 fps
+        assert_eq!(admitted(45, 60, 60), 45);
+        assert_eq!(admitted(40, 60, 60), 40);
+    }
+
+    #[test]
+    fn test_token_bucket_passes_slower_sources() {
+        assert_eq!(admitted(60, 30, 30), 30);
+    }
+
+    #[test]
+    fn test_token_bucket_does_not_burst_after_idle() {
+        let mut bucket = TokenBucket::new(10);
+        let start = bucket.last_refill;
+        assert!(bucket.try_acquire_at(start));
+        // One frame interval of credit carries over, no more
+        let later = start + Duration::from_secs(5);
+        assert!(bucket.try_acquire_at(later));
+        assert!(bucket.try_acquire_at(later + Duration::from_millis(1)));
+        assert!(!bucket.try_acquire_at(later + Duration::from_millis(2)));
+    }
+
+    #[test]
+    fn test_admit_without_limit() {
+        assert!(admit(&mut None));
+    }
+}
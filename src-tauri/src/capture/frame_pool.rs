@@ -0,0 +1,212 @@
+//! Reusable pixel buffers for captured frames.
+//!
+//! Copying every captured frame into a fresh `Vec<u8>` allocates and frees a
+//! frame-sized buffer per frame: about 250 MB/s at 1080p30 and 1 GB/s at
+//! 4K30. Capture loops instead copy into buffers from a [`FramePool`]. A
+//! buffer goes back to its pool when the last [`FrameData`] referring to it
+//! is dropped (after the encoder has written it), so once the pool is warm a
+//! recording allocates no frame buffers at all.
+//!
+//! [`FrameData`] is reference counted, so the encoder repeating a frame to
+//! fill its constant frame rate clones a pointer instead of the pixels.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, Weak};
+
+/// Idle buffers kept per pool: enough for the capture channel, the frame
+/// the encoder holds, and the one being filled.
+const MAX_IDLE_BUFFERS: usize = 6;
+
+/// Pool of same-size pixel buffers shared by a capture loop and the encoder.
+pub struct FramePool {
+    buffers: Mutex<Vec<Box<[u8]>>>,
+}
+
+impl FramePool {
+    /// Create a pool, preallocating buffers of `frame_size` bytes.
+    ///
+    /// Pass 0 when the frame size is not known yet; buffers are then
+    /// allocated on first use.
+    pub fn new(frame_size: usize) -> Arc<Self> {
+        let buffers = if frame_size > 0 {
+            (0..MAX_IDLE_BUFFERS)
+                .map(|_| vec![0u8; frame_size].into_boxed_slice())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Arc::new(Self {
+            buffers: Mutex::new(buffers),
+        })
+    }
+
+    /// Take a buffer of `len` bytes, allocating one if none is idle.
+    ///
+    /// The contents are left over from earlier frames; callers overwrite
+    /// the whole buffer.
+    pub fn acquire(self: &Arc<Self>, len: usize) -> PooledBuffer {
+        let reused = {
+            let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+            // Buffers of another size are left over from before a resize
+            buffers.retain(|buffer| buffer.len() == len);
+            buffers.pop()
+        };
+        PooledBuffer {
+            data: reused.unwrap_or_else(|| vec![0u8; len].into_boxed_slice()),
+            pool: Arc::downgrade(self),
+        }
+    }
+
+    /// Return a buffer to the pool. Dropping a [`PooledBuffer`] does the same.
+    pub fn release(&self, mut buffer: PooledBuffer) {
+        self.put(std::mem::take(&mut buffer.data));
+    }
+
+    fn put(&self, data: Box<[u8]>) {
+        if data.is_empty() {
+            return;
+        }
+        let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        if buffers.len() < MAX_IDLE_BUFFERS {
+            buffers.push(data);
+        }
+    }
+
+    /// Number of idle buffers.
+    pub fn idle(&self) -> usize {
+        self.buffers.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+/// A pixel buffer that returns to its [`FramePool`] when dropped.
+pub struct PooledBuffer {
+    data: Box<[u8]>,
+    /// Pool to return to; empty for buffers made from a `Vec`
+    pool: Weak<FramePool>,
+}
+
+impl Deref for PooledBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+}
+
+impl From<Vec<u8>> for PooledBuffer {
+    fn from(data: Vec<u8>) -> Self {
+        Self {
+            data: data.into_boxed_slice(),
+            pool: Weak::new(),
+        }
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if let Some(pool) = self.pool.upgrade() {
+            pool.put(std::mem::take(&mut self.data));
+        }
+    }
+}
+
+/// Shared, immutable pixel data of a [`CapturedFrame`](super::CapturedFrame).
+///
+/// Cloning is cheap; the buffer returns to its pool once every clone is gone.
+#[derive(Clone)]
+pub struct FrameData(Arc<PooledBuffer>);
+
+impl Deref for FrameData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<PooledBuffer> for FrameData {
+    fn from(buffer: PooledBuffer) -> Self {
+        Self(Arc::new(buffer))
+    }
+}
+
+impl From<Vec<u8>> for FrameData {
+    fn from(data: Vec<u8>) -> Self {
+        PooledBuffer::from(data).into()
+    }
+}
+
+impl PartialEq for FrameData {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl std::fmt::Debug for FrameData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FrameData({} bytes)", self.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffers_are_reused() {
+        let pool = FramePool::new(0);
+        let buffer = pool.acquire(16);
+        let ptr = buffer.as_ptr();
+        drop(buffer);
+        assert_eq!(pool.idle(), 1);
+
+        let reused = pool.acquire(16);
+        assert_eq!(reused.as_ptr(), ptr);
+        assert_eq!(pool.idle(), 0);
+    }
+
+    #[test]
+    fn test_buffer_returns_after_last_frame_data_clone() {
+        let pool = FramePool::new(8);
+        assert_eq!(pool.idle(), MAX_IDLE_BUFFERS);
+
+        let data = FrameData::from(pool.acquire(8));
+        let repeated = data.clone();
+        assert_eq!(pool.idle(), MAX_IDLE_BUFFERS - 1);
+        drop(data);
+        assert_eq!(pool.idle(), MAX_IDLE_BUFFERS - 1);
+        drop(repeated);
+        assert_eq!(pool.idle(), MAX_IDLE_BUFFERS);
+    }
+
+    #[test]
+    fn test_resize_discards_old_buffers() {
+        let pool = FramePool::new(8);
+        let buffer = pool.acquire(12);
+        assert_eq!(buffer.len(), 12);
+        assert_eq!(pool.idle(), 0);
+        pool.release(buffer);
+        assert_eq!(pool.idle(), 1);
+    }
+
+    #[test]
+    fn test_idle_buffers_are_capped() {
+        let pool = FramePool::new(0);
+        let buffers: Vec<_> = (0..MAX_IDLE_BUFFERS + 2).map(|_| pool.acquire(4)).collect();
+        drop(buffers);
+        assert_eq!(pool.idle(), MAX_IDLE_BUFFERS);
+    }
+
+    #[test]
+    fn test_unpooled_data() {
+        let data = FrameData::from(vec![1, 2, 3]);
+        assert_eq!(&*data, &[1, 2, 3]);
+        assert_eq!(data, FrameData::from(vec![1, 2, 3]));
+    }
+}
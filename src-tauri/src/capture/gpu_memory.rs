@@ -0,0 +1,140 @@
+//! GPU memory pressure during capture.
+//!
+//! High-resolution captures on integrated graphics can run out of video
+//! memory, after which the capture fails with `DXGI_ERROR_ACCESS_LOST`.
+//! Capture loops sample the adapter's memory budget before each frame. When
+//! the memory left drops below the configured threshold, a
+//! [`ServiceEvent::GpuMemoryWarning`](crate::state::ServiceEvent) is sent and
+//! the capture is throttled to [`THROTTLED_FPS`] until at least twice the
+//! threshold is free again.
+//!
+//! Implemented on Windows; other platforms report no GPU memory info.
+
+use super::frame_limit::TokenBucket;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Default free video memory below which captures are throttled, in MB.
+pub const DEFAULT_LOW_THRESHOLD_MB: u64 = 50;
+
+/// Frame rate captures are throttled to while video memory is low.
+pub const THROTTLED_FPS: u32 = 15;
+
+static LOW_THRESHOLD_MB: AtomicU64 = AtomicU64::new(DEFAULT_LOW_THRESHOLD_MB);
+
+/// Set the free video memory below which captures are throttled; 0 turns
+/// throttling off.
+pub fn set_low_threshold_mb(threshold_mb: u64) {
+    LOW_THRESHOLD_MB.store(threshold_mb, Ordering::Relaxed);
+}
+
+/// Free video memory below which captures are throttled, in MB.
+pub fn low_threshold_mb() -> u64 {
+    LOW_THRESHOLD_MB.load(Ordering::Relaxed)
+}
+
+/// Video memory of the capture adapter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct GpuMemoryInfo {
+    /// Video memory the OS lets this process use, in bytes
+    pub budget_bytes: u64,
+    /// Video memory this process is using, in bytes
+    pub usage_bytes: u64,
+}
+
+impl GpuMemoryInfo {
+    /// Budget left, in MB.
+    pub fn available_mb(&self) -> u64 {
+        self.budget_bytes.saturating_sub(self.usage_bytes) / (1024 * 1024)
+    }
+}
+
+/// Change in memory pressure reported by [`MemoryPressure::update`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureChange {
+    /// Free memory dropped below the threshold
+    Low,
+    /// Free memory is back to twice the threshold
+    Eased,
+}
+
+/// Memory pressure of one capture, and the throttle applied while it lasts.
+#[derive(Debug, Default)]
+pub struct MemoryPressure {
+    throttle: Option<TokenBucket>,
+}
+
+impl MemoryPressure {
+    /// Whether the capture is being throttled.
+    pub fn is_low(&self) -> bool {
+        self.throttle.is_some()
+    }
+
+    /// Record a memory sample, returning the change in pressure if any.
+    pub fn update(&mut self, info: &GpuMemoryInfo, threshold_mb: u64) -> Option<PressureChange> {
+        let available_mb = info.available_mb();
+        if !self.is_low() && available_mb < threshold_mb {
+            self.throttle = Some(TokenBucket::new(THROTTLED_FPS));
+            Some(PressureChange::Low)
+        } else if self.is_low() && available_mb >= threshold_mb.saturating_mul(2) {
+            self.throttle = None;
+            Some(PressureChange::Eased)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a frame arriving now passes the throttle.
+    pub fn admit(&mut self) -> bool {
+        super::frame_limit::admit(&mut self.throttle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(available_mb: u64) -> GpuMemoryInfo {
+        GpuMemoryInfo {
+            budget_bytes: 1024 * 1024 * 1024,
+            usage_bytes: (1024 - available_mb) * 1024 * 1024,
+        }
+    }
+
+    #[test]
+    fn test_available_mb() {
+        assert_eq!(info(300).available_mb(), 300);
+        let over_budget = GpuMemoryInfo {
+            budget_bytes: 100,
+            usage_bytes: 200,
+        };
+        assert_eq!(over_budget.available_mb(), 0);
+    }
+
+    #[test]
+    fn test_pressure_eases_at_twice_the_threshold() {
+        let mut pressure = MemoryPressure::default();
+        assert_eq!(pressure.update(&info(200), 50), None);
+        assert_eq!(pressure.update(&info(40), 50), Some(PressureChange::Low));
+        assert!(pressure.is_low());
+        assert_eq!(pressure.update(&info(30), 50), None);
+        assert_eq!(pressure.update(&info(80), 50), None);
+        assert_eq!(pressure.update(&info(100), 50), Some(PressureChange::Eased));
+        assert!(!pressure.is_low());
+    }
+
+    #[test]
+    fn test_zero_threshold_never_throttles() {
+        let mut pressure = MemoryPressure::default();
+        assert_eq!(pressure.update(&info(0), 0), None);
+        assert!(pressure.admit());
+    }
+
+    #[test]
+    fn test_throttle_drops_burst_while_low() {
+        let mut pressure = MemoryPressure::default();
+        pressure.update(&info(10), 50);
+        assert!(pressure.admit());
+        assert!(!pressure.admit());
+    }
+}
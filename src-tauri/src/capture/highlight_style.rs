@@ -0,0 +1,251 @@
+//! Appearance of the capture highlight border.
+//!
+//! Shared by the platform `show_highlight` implementations so the border
+//! looks the same everywhere. The pure helpers here (dash pattern, pulse
+//! curve) are used by the platforms that draw pixels themselves.
+
+use serde::{Deserialize, Serialize};
+
+/// Minimum highlight border width in pixels.
+pub const HIGHLIGHT_BORDER_WIDTH_MIN: u32 = 1;
+
+/// Maximum highlight border width in pixels.
+pub const HIGHLIGHT_BORDER_WIDTH_MAX: u32 = 64;
+
+/// Length of one dash (and one gap) for [`HighlightAnimation::Dash`], in pixels.
+pub const DASH_LENGTH: u32 = 16;
+
+/// Dash offset speed for [`HighlightAnimation::Dash`], in pixels per second.
+pub const DASH_SPEED: f32 = 60.0;
+
+/// Duration of one pulse cycle for [`HighlightAnimation::Pulse`], in milliseconds.
+pub const PULSE_PERIOD_MS: u64 = 400;
+
+/// Opacity at the low point of a pulse.
+const PULSE_MIN_OPACITY: f32 = 0.35;
+
+/// Animation applied to the highlight border.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightAnimation {
+    /// Solid, static border
+    #[default]
+    None,
+    /// Border opacity pulses
+    Pulse,
+    /// Dashed border whose dashes move around the edge
+    Dash,
+}
+
+/// Highlight border style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HighlightStyle {
+    /// Border color as RGBA (non-premultiplied)
+    pub color_rgba: [u8; 4],
+    /// Border width in pixels
+    pub border_width: u32,
+    /// Border animation
+    pub animation: HighlightAnimation,
+}
+
+impl Default for HighlightStyle {
+    fn default() -> Self {
+        Self {
+            // #2196F3
+            color_rgba: [0x21, 0x96, 0xF3, 0xFF],
+            border_width: 8,
+            animation: HighlightAnimation::None,
+        }
+    }
+}
+
+impl HighlightStyle {
+    /// Validate the style.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(HIGHLIGHT_BORDER_WIDTH_MIN..=HIGHLIGHT_BORDER_WIDTH_MAX).contains(&self.border_width) {
+            return Err(format!(
+                "Highlight border width must be between {} and {} (got {})",
+                HIGHLIGHT_BORDER_WIDTH_MIN, HIGHLIGHT_BORDER_WIDTH_MAX, self.border_width
+            ));
+        }
+        Ok(())
+    }
+
+    /// Border color as premultiplied BGRA bytes (the layout of Windows DIBs
+    /// and Wayland ARGB8888 buffers).
+    pub fn premultiplied_bgra(&self) -> [u8; 4] {
+        let [r, g, b, a] = self.color_rgba;
+        let premultiply = |c: u8| ((c as u16 * a as u16 + 127) / 255) as u8;
+        [premultiply(b), premultiply(g), premultiply(r), a]
+    }
+
+    /// Whether the border pixel at (`x`, `y`) is drawn, given the dash offset.
+    ///
+    /// Always true unless the animation is [`HighlightAnimation::Dash`], in which
+    /// case dashes follow the perimeter clockwise and `offset` shifts them along it.
+    pub fn is_dash_on(&self, x: i32, y: i32, width: i32, height: i32, offset: u32) -> bool {
+        if self.animation != HighlightAnimation::Dash {
+            return true;
+        }
+        let border = self.border_width as i32;
+        // Distance along the perimeter, measured clockwise from the top-left corner
+        let position = if y < border {
+            x
+        } else if x >= width - border {
+            width + y
+        } else if y >= height - border {
+            width + height + (width - 1 - x)
+        } else {
+            2 * width + height + (height - 1 - y)
+        };
+        let position = position.max(0) as u32 + DASH_LENGTH * 2 - offset % (DASH_LENGTH * 2);
+        (position / DASH_LENGTH).is_multiple_of(2)
+    }
+
+    /// Dash offset after `elapsed_ms` milliseconds of animation.
+    pub fn dash_offset(elapsed_ms: u64) -> u32 {
+        (elapsed_ms as f32 / 1000.0 * DASH_SPEED) as u32
+    }
+
+    /// Border opacity multiplier (0.0-1.0) after `elapsed_ms` milliseconds.
+    pub fn opacity_at(&self, elapsed_ms: u64) -> f32 {
+        if self.animation != HighlightAnimation::Pulse {
+            return 1.0;
+        }
+        let phase = (elapsed_ms % PULSE_PERIOD_MS) as f32 / PULSE_PERIOD_MS as f32;
+        // Cosine wave: 1.0 at the start of each cycle, PULSE_MIN_OPACITY halfway
+        let wave = (1.0 + (phase * std::f32::consts::TAU).cos()) / 2.0;
+        PULSE_MIN_OPACITY + (1.0 - PULSE_MIN_OPACITY) * wave
+    }
+}
+
+/// Coordinates of every pixel in the border band of a `width`x`height` area.
+///
+/// Interior pixels are skipped entirely, so redrawing an animated border
+/// costs time proportional to the border, not the whole area.
+pub fn border_pixels(
+    width: i32,
+    height: i32,
+    border_width: u32,
+) -> impl Iterator<Item = (i32, i32)> {
+    let border = border_width as i32;
+    (0..height.max(0)).flat_map(move |y| {
+        let full_row = y < border || y >= height - border;
+        let (left_end, right_start) = if full_row {
+            (width, width)
+        } else {
+            (border.min(width), (width - border).max(border))
+        };
+        (0..left_end).chain(right_start..width).map(move |x| (x, y))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_previous_hardcoded_style() {
+        let style = HighlightStyle::default();
+        assert_eq!(style.color_rgba, [0x21, 0x96, 0xF3, 0xFF]);
+        assert_eq!(style.border_width, 8);
+        assert_eq!(style.animation, HighlightAnimation::None);
+        assert!(style.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_border_width() {
+        let style = |border_width| HighlightStyle {
+            border_width,
+            ..Default::default()
+        };
+        assert!(style(HIGHLIGHT_BORDER_WIDTH_MIN).validate().is_ok());
+        assert!(style(HIGHLIGHT_BORDER_WIDTH_MAX).validate().is_ok());
+        assert!(style(0).validate().is_err());
+        assert!(style(HIGHLIGHT_BORDER_WIDTH_MAX + 1).validate().is_err());
+    }
+
+    #[test]
+    fn test_premultiplied_bgra() {
+        let opaque = HighlightStyle::default();
+        assert_eq!(opaque.premultiplied_bgra(), [0xF3, 0x96, 0x21, 0xFF]);
+
+        let half = HighlightStyle {
+            color_rgba: [255, 0, 100, 128],
+            ..Default::default()
+        };
+        assert_eq!(half.premultiplied_bgra(), [50, 0, 128, 128]);
+    }
+
+    #[test]
+    fn test_dash_pattern_moves_with_offset() {
+        let style = HighlightStyle {
+            animation: HighlightAnimation::Dash,
+            ..Default::default()
+        };
+        // Along the top edge, dashes alternate every DASH_LENGTH pixels
+        assert!(style.is_dash_on(0, 0, 200, 100, 0));
+        assert!(!style.is_dash_on(DASH_LENGTH as i32, 0, 200, 100, 0));
+        // Shifting by one dash length swaps on and off
+        assert!(!style.is_dash_on(0, 0, 200, 100, DASH_LENGTH));
+        assert!(style.is_dash_on(DASH_LENGTH as i32, 0, 200, 100, DASH_LENGTH));
+        // A full period brings the pattern back
+        assert!(style.is_dash_on(0, 0, 200, 100, DASH_LENGTH * 2));
+
+        // Without the dash animation every border pixel is drawn
+        let solid = HighlightStyle::default();
+        assert!(solid.is_dash_on(DASH_LENGTH as i32, 0, 200, 100, 0));
+    }
+
+    #[test]
+    fn test_border_pixels_cover_only_the_border() {
+        let pixels: Vec<(i32, i32)> = border_pixels(10, 6, 2).collect();
+        // 2 full rows top + 2 bottom, 2 px on each side of the 2 middle rows
+        assert_eq!(pixels.len(), 4 * 10 + 2 * 4);
+        assert!(pixels.contains(&(5, 0)));
+        assert!(pixels.contains(&(1, 2)));
+        assert!(pixels.contains(&(8, 3)));
+        assert!(!pixels.contains(&(2, 2)));
+        assert!(!pixels.contains(&(7, 3)));
+
+        // A border wider than half the area covers every pixel exactly once
+        assert_eq!(border_pixels(4, 4, 3).count(), 16);
+    }
+
+    #[test]
+    fn test_pulse_opacity() {
+        let pulse = HighlightStyle {
+            animation: HighlightAnimation::Pulse,
+            ..Default::default()
+        };
+        assert!((pulse.opacity_at(0) - 1.0).abs() < 1e-6);
+        assert!((pulse.opacity_at(PULSE_PERIOD_MS / 2) - PULSE_MIN_OPACITY).abs() < 1e-6);
+        assert!((pulse.opacity_at(PULSE_PERIOD_MS) - 1.0).abs() < 1e-6);
+
+        assert_eq!(
+            HighlightStyle::default().opacity_at(PULSE_PERIOD_MS / 2),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let style = HighlightStyle {
+            color_rgba: [255, 0, 0, 200],
+            border_width: 4,
+            animation: HighlightAnimation::Dash,
+        };
+        let json = serde_json::to_string(&style).unwrap();
+        assert!(json.contains(r#""animation":"dash""#));
+        assert_eq!(
+            serde_json::from_str::<HighlightStyle>(&json).unwrap(),
+            style
+        );
+
+        // Missing fields fall back to defaults
+        let partial: HighlightStyle = serde_json::from_str(r#"{"border_width": 3}"#).unwrap();
+        assert_eq!(partial.border_width, 3);
+        assert_eq!(partial.color_rgba, HighlightStyle::default().color_rgba);
+    }
+}
@@ -1,20 +1,50 @@
 //! Approval token storage for the picker consent system.
 //!
-//! This module handles reading and writing the approval token that allows
-//! the picker to bypass the consent dialog when "Always allow" is selected.
+//! When the user picks "Always allow" in the consent dialog, the picker
+//! stores an approval token that lets it skip the dialog afterwards. Tokens
+//! are kept in a JSON file with what was being shared when they were granted
+//! and when they were last used, so they can be listed and revoked from the
+//! configuration panel.
+//!
+//! The file is read once and the tokens are kept in memory. It is written
+//! only when a token is added or revoked, so the last use of a token is
+//! saved with the next change.
 
+use omnirec_types::ApprovalTokenInfo;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
-/// Get the path to the approval token file.
+/// A stored token with its metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StoredToken {
+    #[serde(flatten)]
+    info: ApprovalTokenInfo,
+    /// The secret the picker presents
+    token: String,
+}
+
+/// The stored tokens, loaded on first use.
+static TOKENS: OnceLock<Mutex<Vec<StoredToken>>> = OnceLock::new();
+
+/// Lock the stored tokens, loading them if this is the first use.
+fn tokens() -> MutexGuard<'static, Vec<StoredToken>> {
+    TOKENS
+        .get_or_init(|| Mutex::new(load()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Directory of the token store.
 ///
 /// Uses XDG_STATE_HOME if set, otherwise falls back to ~/.local/state/omnirec/
-pub fn get_token_path() -> PathBuf {
-    let state_dir = std::env::var("XDG_STATE_HOME")
+fn state_dir() -> PathBuf {
+    std::env::var("XDG_STATE_HOME")
         .map(PathBuf::from)
         .unwrap_or_else(|_| {
             std::env::var("HOME")
@@ -22,94 +52,159 @@ pub fn get_token_path() -> PathBuf {
                 .unwrap_or_else(|_| PathBuf::from("/tmp"))
                 .join(".local")
                 .join("state")
-        });
+        })
+        .join("omnirec")
+}
+
+/// Get the path to the approval token store.
+pub fn get_token_path() -> PathBuf {
+    state_dir().join("approval-tokens.json")
+}
 
-    state_dir.join("omnirec").join("approval-token")
+/// Single token file written by earlier versions.
+fn legacy_token_path() -> PathBuf {
+    state_dir().join("approval-token")
 }
 
 /// Check if an approval token exists.
 pub fn has_token() -> bool {
-    get_token_path().exists()
+    !tokens().is_empty()
 }
 
-/// Read the approval token from disk.
+/// Validate a token against the stored tokens, recording its use.
 ///
-/// Returns None if the token file doesn't exist or can't be read.
-pub fn read_token() -> Option<String> {
+/// Returns true if the token matches, false otherwise.
+pub fn validate_token(token: &str) -> bool {
+    let mut tokens = tokens();
+    let Some(stored) = tokens
+        .iter_mut()
+        .find(|t| constant_time_eq(&t.token, token))
+    else {
+        return false;
+    };
+    stored.info.last_used_at = Some(now());
+    true
+}
+
+/// Store an approval token granted while `source_type`/`source_id` was selected.
+///
+/// Creates the parent directory if needed and sets file permissions to 0600.
+pub fn write_token(token: &str, source_type: &str, source_id: &str) -> io::Result<()> {
     let path = get_token_path();
-    match fs::read_to_string(&path) {
-        Ok(content) => {
-            let token = content.trim().to_string();
-            if token.is_empty() {
-                None
-            } else {
-                Some(token)
-            }
+    let mut tokens = tokens();
+    let mut updated = tokens.clone();
+    add_token(&mut updated, token, source_type, source_id);
+    save_to(&path, &updated)?;
+    *tokens = updated;
+    tracing::debug!("[ApprovalToken] Token stored at {:?}", path);
+    Ok(())
+}
+
+/// List the stored tokens, oldest first. Secrets are not included.
+pub fn list_tokens() -> Vec<ApprovalTokenInfo> {
+    tokens().iter().map(|t| t.info.clone()).collect()
+}
+
+/// Revoke the token with `token_id`.
+///
+/// Returns false if there is no such token.
+pub fn revoke_token(token_id: &str) -> io::Result<bool> {
+    let mut tokens = tokens();
+    let mut updated = tokens.clone();
+    updated.retain(|t| t.info.token_id != token_id);
+    if updated.len() == tokens.len() {
+        return Ok(false);
+    }
+    save_to(&get_token_path(), &updated)?;
+    *tokens = updated;
+    tracing::info!("[ApprovalToken] Token {} revoked", token_id);
+    Ok(true)
+}
+
+/// Load the stored tokens, migrating a token from the legacy single-token file.
+fn load() -> Vec<StoredToken> {
+    let path = get_token_path();
+    let mut tokens = load_from(&path);
+
+    let legacy = legacy_token_path();
+    if let Ok(content) = fs::read_to_string(&legacy) {
+        let token = content.trim();
+        if !token.is_empty() {
+            add_token(&mut tokens, token, "unknown", "unknown");
+        }
+        if save_to(&path, &tokens).is_ok() {
+            let _ = fs::remove_file(&legacy);
+            tracing::debug!("[ApprovalToken] Migrated legacy token file");
         }
+    }
+    tokens
+}
+
+fn load_from(path: &Path) -> Vec<StoredToken> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("[ApprovalToken] Ignoring unreadable token store: {}", e);
+            Vec::new()
+        }),
         Err(e) => {
             if e.kind() != io::ErrorKind::NotFound {
-                tracing::debug!("[ApprovalToken] Failed to read token: {}", e);
+                tracing::debug!("[ApprovalToken] Failed to read tokens: {}", e);
             }
-            None
+            Vec::new()
         }
     }
 }
 
-/// Write an approval token to disk.
-///
-/// Creates the parent directory if needed and sets file permissions to 0600.
-pub fn write_token(token: &str) -> io::Result<()> {
-    let path = get_token_path();
-
-    // Create parent directory if needed
+fn save_to(path: &Path, tokens: &[StoredToken]) -> io::Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
-
-    // Write the token
-    fs::write(&path, token)?;
+    let json = serde_json::to_string_pretty(tokens).map_err(io::Error::other)?;
+    fs::write(path, json)?;
 
     // Set restrictive permissions (owner read/write only)
     #[cfg(unix)]
     {
-        let mut perms = fs::metadata(&path)?.permissions();
+        let mut perms = fs::metadata(path)?.permissions();
         perms.set_mode(0o600);
-        fs::set_permissions(&path, perms)?;
+        fs::set_permissions(path, perms)?;
     }
-
-    tracing::debug!("[ApprovalToken] Token stored at {:?}", path);
     Ok(())
 }
 
-/// Validate a token against the stored token.
-///
-/// Returns true if the token matches, false otherwise.
-pub fn validate_token(token: &str) -> bool {
-    match read_token() {
-        Some(stored) => {
-            // Constant-time comparison to prevent timing attacks
-            if stored.len() != token.len() {
-                return false;
-            }
-            stored
-                .bytes()
-                .zip(token.bytes())
-                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
-                == 0
-        }
-        None => false,
+fn add_token(tokens: &mut Vec<StoredToken>, token: &str, source_type: &str, source_id: &str) {
+    let created_at = chrono::Utc::now();
+    // Timestamp-based, so the id reveals nothing about the secret
+    let mut token_id = format!("{:x}", created_at.timestamp_micros());
+    while tokens.iter().any(|t| t.info.token_id == token_id) {
+        token_id.push('0');
     }
+    tokens.push(StoredToken {
+        info: ApprovalTokenInfo {
+            token_id,
+            source_type: source_type.to_string(),
+            source_id: source_id.to_string(),
+            created_at: created_at.to_rfc3339(),
+            last_used_at: None,
+        },
+        token: token.to_string(),
+    });
 }
 
-/// Delete the approval token.
-#[allow(dead_code)]
-pub fn delete_token() -> io::Result<()> {
-    let path = get_token_path();
-    if path.exists() {
-        fs::remove_file(&path)?;
-        tracing::debug!("[ApprovalToken] Token deleted");
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// Constant-time comparison to prevent timing attacks.
+fn constant_time_eq(stored: &str, token: &str) -> bool {
+    if stored.len() != token.len() {
+        return false;
     }
-    Ok(())
+    stored
+        .bytes()
+        .zip(token.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
 }
 
 #[cfg(test)]
@@ -122,23 +217,39 @@ mod tests {
         // We don't modify env vars since that causes race conditions in parallel tests
         let path = get_token_path();
         // Just verify it ends with the expected suffix
-        assert!(path.ends_with("omnirec/approval-token"));
+        assert!(path.ends_with("omnirec/approval-tokens.json"));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("abc", "abc"));
+        assert!(!constant_time_eq("abc", "abd"));
+        assert!(!constant_time_eq("abc", "abcd"));
     }
 
     #[test]
-    fn test_validate_token_mismatch() {
-        // Test the constant-time comparison logic
-        // If stored token is "abc", "abd" should fail
-        // We can't easily test with real files due to env var races,
-        // but we can test the comparison logic
-        assert!(!validate_token("any-token")); // No token stored = fails
+    fn test_store_round_trip_hides_secret() {
+        let dir = std::env::temp_dir().join(format!("omnirec-tokens-{}", std::process::id()));
+        let path = dir.join("approval-tokens.json");
+
+        let mut tokens = Vec::new();
+        add_token(&mut tokens, "secret-1", "monitor", "DP-1");
+        add_token(&mut tokens, "secret-2", "window", "0x55d1");
+        assert_ne!(tokens[0].info.token_id, tokens[1].info.token_id);
+        save_to(&path, &tokens).unwrap();
+
+        let loaded = load_from(&path);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(loaded, tokens);
+        assert_eq!(loaded[1].info.source_id, "0x55d1");
+        assert!(loaded[1].info.last_used_at.is_none());
+
+        let listed = serde_json::to_string(&loaded[0].info).unwrap();
+        assert!(!listed.contains("secret-1"));
     }
 
     #[test]
-    fn test_token_format() {
-        // Test that read_token trims whitespace
-        // This tests the logic without needing real file operations
-        let token = "  test-token  ".trim().to_string();
-        assert_eq!(token, "test-token");
+    fn test_missing_store_is_empty() {
+        assert!(load_from(Path::new("/nonexistent/omnirec/approval-tokens.json")).is_empty());
     }
 }
@@ -1,18 +1,33 @@
 //! PipeWire-based audio device enumeration for Linux.
 //!
 //! This module provides audio device discovery using the PipeWire registry.
-//! Input devices (microphones) and output devices (sink monitors) are
-//! enumerated and kept up-to-date via registry listener callbacks.
+//! Input devices (microphones), output devices (sink monitors) and
+//! application playback streams are enumerated and kept up-to-date via
+//! registry listener callbacks.
+//!
+//! Per-application capture creates a private virtual sink and links the
+//! application's playback streams to it, so recording the sink's monitor
+//! captures that application only.
 
 use pipewire::{context::Context, main_loop::MainLoop, types::ObjectType};
+use pipewire::{link::Link, node::Node, spa::utils::dict::DictRef};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
 use crate::capture::error::EnumerationError;
 use crate::capture::{AudioSource, AudioSourceType};
+use omnirec_types::AudioAppInfo;
+
+/// Media class of application playback streams.
+const APP_STREAM_MEDIA_CLASS: &str = "Stream/Output/Audio";
+
+/// Maximum time to wait for the per-application sink to appear.
+const APP_SINK_TIMEOUT: Duration = Duration::from_secs(3);
 
 /// Handle to the PipeWire audio backend.
 ///
@@ -22,6 +37,8 @@ pub struct PipeWireAudioBackend {
     input_devices: Arc<Mutex<Vec<AudioSource>>>,
     /// Cached output devices (sink monitors for system audio)
     output_devices: Arc<Mutex<Vec<AudioSource>>>,
+    /// Application playback streams by node ID
+    app_streams: Arc<Mutex<HashMap<u32, AppStream>>>,
     /// Thread handle
     _thread_handle: JoinHandle<()>,
 }
@@ -31,12 +48,18 @@ impl PipeWireAudioBackend {
     pub fn new() -> Result<Self, String> {
         let input_devices = Arc::new(Mutex::new(Vec::new()));
         let output_devices = Arc::new(Mutex::new(Vec::new()));
+        let app_streams = Arc::new(Mutex::new(HashMap::new()));
 
         let input_devices_clone = Arc::clone(&input_devices);
         let output_devices_clone = Arc::clone(&output_devices);
+        let app_streams_clone = Arc::clone(&app_streams);
 
         let thread_handle = thread::spawn(move || {
-            if let Err(e) = run_pipewire_audio_thread(input_devices_clone, output_devices_clone) {
+            if let Err(e) = run_pipewire_audio_thread(
+                input_devices_clone,
+                output_devices_clone,
+                app_streams_clone,
+            ) {
                 tracing::error!("[Audio] PipeWire thread error: {}", e);
             }
         });
@@ -47,6 +70,7 @@ impl PipeWireAudioBackend {
         Ok(Self {
             input_devices,
             output_devices,
+            app_streams,
             _thread_handle: thread_handle,
         })
     }
@@ -67,12 +91,75 @@ impl PipeWireAudioBackend {
 
         sources
     }
+
+    /// List applications that currently have audio playback streams.
+    pub fn list_running_audio_apps(&self) -> Vec<AudioAppInfo> {
+        match self.app_streams.lock() {
+            Ok(streams) => group_audio_apps(streams.values()),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+/// An application playback stream (`Stream/Output/Audio` node).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct AppStream {
+    /// `application.name` property
+    name: Option<String>,
+    /// `application.process.binary` property
+    binary: Option<String>,
+}
+
+impl AppStream {
+    fn from_props(props: &DictRef) -> Self {
+        Self {
+            name: props.get("application.name").map(str::to_string),
+            binary: props.get("application.process.binary").map(str::to_string),
+        }
+    }
+
+    /// Name the application is listed and selected by.
+    fn display_name(&self) -> Option<&str> {
+        self.name.as_deref().or(self.binary.as_deref())
+    }
+
+    /// Whether the stream belongs to `app_name`, matched against either the
+    /// application name or the process binary.
+    fn matches(&self, app_name: &str) -> bool {
+        self.name.as_deref() == Some(app_name) || self.binary.as_deref() == Some(app_name)
+    }
+}
+
+/// Group playback streams by application, sorted by name.
+fn group_audio_apps<'a>(streams: impl IntoIterator<Item = &'a AppStream>) -> Vec<AudioAppInfo> {
+    let mut apps: Vec<AudioAppInfo> = Vec::new();
+    for stream in streams {
+        let Some(name) = stream.display_name() else {
+            continue;
+        };
+        match apps.iter_mut().find(|app| app.name == name) {
+            Some(app) => {
+                app.stream_count += 1;
+                if app.binary.is_none() {
+                    app.binary = stream.binary.clone();
+                }
+            }
+            None => apps.push(AudioAppInfo {
+                name: name.to_string(),
+                binary: stream.binary.clone(),
+                stream_count: 1,
+            }),
+        }
+    }
+    apps.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    apps
 }
 
 /// Run the PipeWire main loop thread for audio device enumeration.
 fn run_pipewire_audio_thread(
     input_devices: Arc<Mutex<Vec<AudioSource>>>,
     output_devices: Arc<Mutex<Vec<AudioSource>>>,
+    app_streams: Arc<Mutex<HashMap<u32, AppStream>>>,
 ) -> Result<(), String> {
     // Initialize PipeWire
     pipewire::init();
@@ -96,6 +183,7 @@ fn run_pipewire_audio_thread(
     let output_map_clone = Rc::clone(&output_map);
     let input_devices_clone = Arc::clone(&input_devices);
     let output_devices_clone = Arc::clone(&output_devices);
+    let app_streams_clone = Arc::clone(&app_streams);
 
     let _registry_listener = registry
         .add_listener_local()
@@ -138,6 +226,15 @@ fn run_pipewire_audio_thread(
                         // Update shared list
                         let devices: Vec<_> = output_map_clone.borrow().values().cloned().collect();
                         *output_devices_clone.lock().unwrap() = devices;
+                    } else if media_class == APP_STREAM_MEDIA_CLASS {
+                        // Application playback stream - selectable for per-app capture
+                        let stream = AppStream::from_props(props);
+                        tracing::debug!(
+                            "[Audio] Found app stream: {:?} (ID: {})",
+                            stream.display_name(),
+                            global.id
+                        );
+                        app_streams_clone.lock().unwrap().insert(global.id, stream);
                     }
                 }
             }
@@ -147,6 +244,7 @@ fn run_pipewire_audio_thread(
             let output_map = Rc::clone(&output_map);
             let input_devices = Arc::clone(&input_devices);
             let output_devices = Arc::clone(&output_devices);
+            let app_streams = Arc::clone(&app_streams);
             move |id| {
                 if input_map.borrow_mut().remove(&id).is_some() {
                     tracing::debug!("[Audio] Input device removed: {}", id);
@@ -158,6 +256,9 @@ fn run_pipewire_audio_thread(
                     let devices: Vec<_> = output_map.borrow().values().cloned().collect();
                     *output_devices.lock().unwrap() = devices;
                 }
+                if app_streams.lock().unwrap().remove(&id).is_some() {
+                    tracing::debug!("[Audio] App stream removed: {}", id);
+                }
             }
         })
         .register();
@@ -201,6 +302,286 @@ pub fn list_audio_sources() -> Result<Vec<AudioSource>, EnumerationError> {
     Ok(backend.list_audio_sources())
 }
 
+/// List applications that currently have audio playback streams.
+pub fn list_running_audio_apps() -> Result<Vec<AudioAppInfo>, EnumerationError> {
+    let backend = get_audio_backend().ok_or_else(|| {
+        EnumerationError::PlatformError("Audio backend not initialized".to_string())
+    })?;
+    Ok(backend.list_running_audio_apps())
+}
+
+/// Running per-application audio capture.
+///
+/// Dropping the handle removes the virtual sink and its links.
+pub struct AppAudioCapture {
+    node_id: u32,
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl AppAudioCapture {
+    /// PipeWire node ID of the virtual sink. Capture its monitor like any
+    /// other output device.
+    pub fn node_id(&self) -> u32 {
+        self.node_id
+    }
+}
+
+impl Drop for AppAudioCapture {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Start capturing the audio of a single application.
+///
+/// Creates a virtual sink and links every playback stream whose
+/// `application.name` or `application.process.binary` equals `app_name` to
+/// it, including streams the application opens later. Other applications
+/// are not routed to the sink, so its monitor carries only this
+/// application's audio.
+pub fn start_app_audio_capture(app_name: &str) -> Result<AppAudioCapture, String> {
+    let (id_tx, id_rx) = std::sync::mpsc::channel();
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let stop_flag_clone = Arc::clone(&stop_flag);
+    let app_name = app_name.to_string();
+
+    thread::spawn(move || {
+        if let Err(e) = run_app_audio_thread(&app_name, id_tx.clone(), stop_flag_clone) {
+            tracing::error!("[Audio] App capture error for {}: {}", app_name, e);
+            let _ = id_tx.send(Err(e));
+        }
+        tracing::debug!("[Audio] App capture thread for {} exited", app_name);
+    });
+
+    match id_rx.recv_timeout(APP_SINK_TIMEOUT) {
+        Ok(Ok(node_id)) => Ok(AppAudioCapture { node_id, stop_flag }),
+        Ok(Err(e)) => Err(e),
+        Err(_) => {
+            stop_flag.store(true, Ordering::Relaxed);
+            Err("Timed out waiting for the application audio sink".to_string())
+        }
+    }
+}
+
+/// Registry state of a per-application capture.
+#[derive(Default)]
+struct AppLinkState {
+    /// Global ID of our virtual sink, once announced
+    sink_id: Option<u32>,
+    /// Playback stream nodes of the captured application
+    app_nodes: HashSet<u32>,
+    /// Ports by global ID
+    ports: HashMap<u32, PortInfo>,
+    /// Port pairs already linked
+    linked: HashSet<(u32, u32)>,
+    /// Link proxies; dropping them destroys the links
+    links: Vec<Link>,
+}
+
+/// A node port as announced by the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PortInfo {
+    node_id: u32,
+    /// Whether this is an output port
+    output: bool,
+    /// `audio.channel` property (e.g. `FL`, `MONO`)
+    channel: String,
+}
+
+impl PortInfo {
+    fn from_props(props: &DictRef) -> Option<Self> {
+        Some(Self {
+            node_id: props.get("node.id")?.parse().ok()?,
+            output: props.get("port.direction")? == "out",
+            channel: props.get("audio.channel").unwrap_or("").to_string(),
+        })
+    }
+}
+
+/// Pair application output ports with sink input ports.
+///
+/// Ports are paired by channel; an application port whose channel the sink
+/// does not have (e.g. `MONO`) is linked to every sink input.
+fn port_pairs(app_ports: &[(u32, &PortInfo)], sink_ports: &[(u32, &PortInfo)]) -> Vec<(u32, u32)> {
+    let mut pairs = Vec::new();
+    for (out_id, out_port) in app_ports {
+        let same_channel: Vec<u32> = sink_ports
+            .iter()
+            .filter(|(_, in_port)| in_port.channel == out_port.channel)
+            .map(|(in_id, _)| *in_id)
+            .collect();
+        let targets = if same_channel.is_empty() {
+            sink_ports.iter().map(|(in_id, _)| *in_id).collect()
+        } else {
+            same_channel
+        };
+        pairs.extend(targets.into_iter().map(|in_id| (*out_id, in_id)));
+    }
+    pairs
+}
+
+/// Sanitize an application name for use in a PipeWire node name.
+fn sink_node_name(app_name: &str) -> String {
+    let name: String = app_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("omnirec-app-{}", name)
+}
+
+/// Run the PipeWire main loop for a per-application capture.
+fn run_app_audio_thread(
+    app_name: &str,
+    id_tx: std::sync::mpsc::Sender<Result<u32, String>>,
+    stop_flag: Arc<AtomicBool>,
+) -> Result<(), String> {
+    pipewire::init();
+
+    let mainloop = MainLoop::new(None).map_err(|e| format!("Failed to create main loop: {}", e))?;
+    let context =
+        Context::new(&mainloop).map_err(|e| format!("Failed to create context: {}", e))?;
+    let core = context
+        .connect(None)
+        .map_err(|e| format!("Failed to connect to PipeWire: {}", e))?;
+    let registry = core
+        .get_registry()
+        .map_err(|e| format!("Failed to get registry: {}", e))?;
+
+    let sink_name = sink_node_name(app_name);
+    let _sink: Node = core
+        .create_object(
+            "adapter",
+            &pipewire::properties::properties! {
+                "factory.name" => "support.null-audio-sink",
+                "node.name" => sink_name.as_str(),
+                "node.description" => format!("OmniRec: {}", app_name),
+                "media.class" => "Audio/Sink",
+                "audio.position" => "FL,FR",
+                "node.virtual" => "true",
+                "object.linger" => "false",
+            },
+        )
+        .map_err(|e| format!("Failed to create application sink: {}", e))?;
+
+    let state = Rc::new(RefCell::new(AppLinkState::default()));
+    let app_name = app_name.to_string();
+
+    let _registry_listener = registry
+        .add_listener_local()
+        .global({
+            let state = Rc::clone(&state);
+            let core = core.clone();
+            move |global| {
+                let Some(props) = &global.props else {
+                    return;
+                };
+                let mut state = state.borrow_mut();
+                match global.type_ {
+                    ObjectType::Node => {
+                        if props.get("node.name") == Some(sink_name.as_str()) {
+                            state.sink_id = Some(global.id);
+                            let _ = id_tx.send(Ok(global.id));
+                        } else if props.get("media.class") == Some(APP_STREAM_MEDIA_CLASS)
+                            && AppStream::from_props(props).matches(&app_name)
+                        {
+                            tracing::debug!(
+                                "[Audio] Routing stream {} of {} to app sink",
+                                global.id,
+                                app_name
+                            );
+                            state.app_nodes.insert(global.id);
+                        }
+                    }
+                    ObjectType::Port => {
+                        if let Some(port) = PortInfo::from_props(props) {
+                            state.ports.insert(global.id, port);
+                        }
+                    }
+                    _ => return,
+                }
+                link_app_ports(&core, &mut state);
+            }
+        })
+        .global_remove({
+            let state = Rc::clone(&state);
+            move |id| {
+                let mut state = state.borrow_mut();
+                state.app_nodes.remove(&id);
+                state.ports.remove(&id);
+                state
+                    .linked
+                    .retain(|(out_id, in_id)| *out_id != id && *in_id != id);
+            }
+        })
+        .register();
+
+    // Quit when the capture handle is dropped
+    let mainloop_clone = mainloop.clone();
+    let timer = mainloop.loop_().add_timer(move |_timer_expired_count| {
+        if stop_flag.load(Ordering::Relaxed) {
+            mainloop_clone.quit();
+        }
+    });
+    timer.update_timer(
+        Some(Duration::from_millis(100)),
+        Some(Duration::from_millis(100)),
+    );
+
+    mainloop.run();
+
+    Ok(())
+}
+
+/// Link any not yet linked application output ports to the sink inputs.
+fn link_app_ports(core: &pipewire::core::Core, state: &mut AppLinkState) {
+    let Some(sink_id) = state.sink_id else {
+        return;
+    };
+    let app_ports: Vec<(u32, &PortInfo)> = state
+        .ports
+        .iter()
+        .filter(|(_, port)| port.output && state.app_nodes.contains(&port.node_id))
+        .map(|(id, port)| (*id, port))
+        .collect();
+    let sink_ports: Vec<(u32, &PortInfo)> = state
+        .ports
+        .iter()
+        .filter(|(_, port)| !port.output && port.node_id == sink_id)
+        .map(|(id, port)| (*id, port))
+        .collect();
+
+    let mut new_links = Vec::new();
+    for (out_id, in_id) in port_pairs(&app_ports, &sink_ports) {
+        if state.linked.contains(&(out_id, in_id)) {
+            continue;
+        }
+        let out_node = state.ports[&out_id].node_id;
+        match core.create_object::<Link>(
+            "link-factory",
+            &pipewire::properties::properties! {
+                "link.output.node" => out_node.to_string(),
+                "link.output.port" => out_id.to_string(),
+                "link.input.node" => sink_id.to_string(),
+                "link.input.port" => in_id.to_string(),
+                "object.linger" => "false",
+            },
+        ) {
+            Ok(link) => new_links.push(((out_id, in_id), link)),
+            Err(e) => tracing::warn!("[Audio] Failed to link port {} to app sink: {}", out_id, e),
+        }
+    }
+    for (pair, link) in new_links {
+        state.linked.insert(pair);
+        state.links.push(link);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,4 +594,65 @@ mod tests {
         assert_eq!(serde_json::to_string(&input).unwrap(), "\"input\"");
         assert_eq!(serde_json::to_string(&output).unwrap(), "\"output\"");
     }
+
+    fn stream(name: Option<&str>, binary: Option<&str>) -> AppStream {
+        AppStream {
+            name: name.map(String::from),
+            binary: binary.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_app_stream_matches_name_or_binary() {
+        let firefox = stream(Some("Firefox"), Some("firefox"));
+        assert!(firefox.matches("Firefox"));
+        assert!(firefox.matches("firefox"));
+        assert!(!firefox.matches("mpv"));
+    }
+
+    #[test]
+    fn test_group_audio_apps() {
+        let streams = [
+            stream(Some("mpv"), Some("mpv")),
+            stream(Some("Firefox"), Some("firefox")),
+            stream(Some("Firefox"), None),
+            stream(None, Some("aplay")),
+            stream(None, None),
+        ];
+        let apps = group_audio_apps(&streams);
+        let summary: Vec<(&str, u32)> = apps
+            .iter()
+            .map(|app| (app.name.as_str(), app.stream_count))
+            .collect();
+        assert_eq!(summary, [("aplay", 1), ("Firefox", 2), ("mpv", 1)]);
+        assert_eq!(apps[1].binary.as_deref(), Some("firefox"));
+    }
+
+    #[test]
+    fn test_port_pairs_by_channel() {
+        let port = |node_id, output, channel: &str| PortInfo {
+            node_id,
+            output,
+            channel: channel.to_string(),
+        };
+        let (app_fl, app_fr, app_mono) = (
+            port(1, true, "FL"),
+            port(1, true, "FR"),
+            port(2, true, "MONO"),
+        );
+        let (sink_fl, sink_fr) = (port(9, false, "FL"), port(9, false, "FR"));
+        let sink = [(90, &sink_fl), (91, &sink_fr)];
+
+        assert_eq!(
+            port_pairs(&[(10, &app_fl), (11, &app_fr)], &sink),
+            [(10, 90), (11, 91)]
+        );
+        // Mono streams feed both sink channels
+        assert_eq!(port_pairs(&[(20, &app_mono)], &sink), [(20, 90), (20, 91)]);
+    }
+
+    #[test]
+    fn test_sink_node_name() {
+        assert_eq!(sink_node_name("Google Chrome"), "omnirec-app-google-chrome");
+    }
 }
@@ -1,36 +1,37 @@
 //! Linux display highlight using wlr-layer-shell protocol.
 //!
 //! Creates a transparent overlay surface with a colored border to highlight a monitor or window.
-//! Uses the wlr-layer-shell Wayland protocol for broad compositor support.
+//! Uses the wlr-layer-shell Wayland protocol for broad compositor support. The border is
+//! drawn into a shared-memory buffer using the configured [`HighlightStyle`]; animated
+//! styles redraw the buffer while the highlight is visible.
 
 use std::os::unix::io::{AsFd, OwnedFd};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::time::{Duration, Instant};
 
+use crate::capture::highlight_style::border_pixels;
+use crate::capture::{HighlightAnimation, HighlightStyle};
 use hyprland::data::Monitors;
 use hyprland::shared::HyprData;
+use omnirec_types::{split_highlight_region, HighlightRegion};
 use wayland_client::protocol::{
     wl_buffer, wl_compositor, wl_output, wl_registry, wl_shm, wl_shm_pool, wl_surface,
 };
 use wayland_client::{delegate_noop, Connection, Dispatch, Proxy, QueueHandle};
 use wayland_protocols_wlr::layer_shell::v1::client::{zwlr_layer_shell_v1, zwlr_layer_surface_v1};
 
-const BORDER_WIDTH: i32 = 8;
 const HIGHLIGHT_DURATION_MS: u64 = 800;
 
-// Color: #2196F3 (blue) - same as Windows/macOS
-const BORDER_R: u8 = 0x21;
-const BORDER_G: u8 = 0x96;
-const BORDER_B: u8 = 0xF3;
-const BORDER_A: u8 = 0xFF;
-
 /// Global flag to signal cancellation of a running highlight
 static CANCEL_FLAG: AtomicBool = AtomicBool::new(false);
 
 /// Show a highlight border around the specified area.
 /// This function spawns a thread and returns immediately.
-pub fn show_highlight(x: i32, y: i32, width: i32, height: i32) {
+///
+/// Layer surfaces are bound to a single output, so a region spanning several
+/// monitors gets one surface per monitor.
+pub fn show_highlight(x: i32, y: i32, width: i32, height: i32, style: HighlightStyle) {
     // Signal any existing highlight to cancel
     CANCEL_FLAG.store(true, Ordering::SeqCst);
 
@@ -40,11 +41,21 @@ pub fn show_highlight(x: i32, y: i32, width: i32, height: i32) {
     // Reset cancel flag for new highlight
     CANCEL_FLAG.store(false, Ordering::SeqCst);
 
-    thread::spawn(move || {
-        if let Err(e) = run_highlight(x, y, width, height) {
-            tracing::debug!("[Linux Highlight] Error: {}", e);
-        }
-    });
+    let region = HighlightRegion {
+        x,
+        y,
+        width: width.max(0) as u32,
+        height: height.max(0) as u32,
+    };
+    for part in split_highlight_region(&region, &crate::capture::list_monitors()) {
+        thread::spawn(move || {
+            if let Err(e) =
+                run_highlight(part.x, part.y, part.width as i32, part.height as i32, style)
+            {
+                tracing::debug!("[Linux Highlight] Error: {}", e);
+            }
+        });
+    }
 }
 
 /// Information about a Wayland output
@@ -147,7 +158,13 @@ impl HighlightState {
     }
 }
 
-fn run_highlight(x: i32, y: i32, width: i32, height: i32) -> Result<(), String> {
+fn run_highlight(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    style: HighlightStyle,
+) -> Result<(), String> {
     // Connect to Wayland display
     let conn = Connection::connect_to_env()
         .map_err(|e| format!("Failed to connect to Wayland display: {}", e))?;
@@ -270,7 +287,8 @@ fn run_highlight(x: i32, y: i32, width: i32, height: i32) -> Result<(), String>
 
     // Create and attach the buffer with border graphic
     // Buffer should be in logical size to match the layer surface
-    let buffer = create_border_buffer(&shm, &qh, logical_width, logical_height)?;
+    let (buffer, canvas) = create_border_buffer(&shm, &qh, logical_width, logical_height)?;
+    canvas.draw(&style, 0, 1.0);
     surface.attach(Some(&buffer), 0, 0);
 
     // Mark the entire surface as damaged
@@ -314,6 +332,19 @@ fn run_highlight(x: i32, y: i32, width: i32, height: i32) -> Result<(), String>
             break;
         }
 
+        // Redraw animated styles in place and recommit the buffer
+        if style.animation != HighlightAnimation::None {
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+            canvas.draw(
+                &style,
+                HighlightStyle::dash_offset(elapsed_ms),
+                style.opacity_at(elapsed_ms),
+            );
+            surface.attach(Some(&buffer), 0, 0);
+            surface.damage(0, 0, i32::MAX, i32::MAX);
+            surface.commit();
+        }
+
         thread::sleep(Duration::from_millis(16));
     }
 
@@ -331,42 +362,77 @@ fn run_highlight(x: i32, y: i32, width: i32, height: i32) -> Result<(), String>
     Ok(())
 }
 
-/// Create an SHM buffer with the border graphic
+/// Memory-mapped pixels of the highlight SHM buffer.
+struct BorderCanvas {
+    ptr: *mut u8,
+    size: usize,
+    width: i32,
+    height: i32,
+    stride: i32,
+}
+
+impl BorderCanvas {
+    /// Draw the border graphic with the given dash offset and opacity (0.0-1.0).
+    fn draw(&self, style: &HighlightStyle, dash_offset: u32, opacity: f32) {
+        unsafe {
+            draw_border(
+                self.ptr,
+                self.width,
+                self.height,
+                self.stride,
+                style,
+                dash_offset,
+                opacity,
+            )
+        }
+    }
+}
+
+impl Drop for BorderCanvas {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr as *mut libc::c_void, self.size);
+        }
+    }
+}
+
+/// Create an SHM buffer for the border graphic, returning it with its mapped pixels.
 fn create_border_buffer(
     shm: &wl_shm::WlShm,
     qh: &QueueHandle<HighlightState>,
     width: i32,
     height: i32,
-) -> Result<wl_buffer::WlBuffer, String> {
+) -> Result<(wl_buffer::WlBuffer, BorderCanvas), String> {
     let stride = width * 4; // 4 bytes per pixel (ARGB8888)
     let size = (stride * height) as usize;
 
     // Create a temporary file for the shared memory
     let fd = create_shm_fd(size)?;
 
-    // Memory map the file and draw the border
-    unsafe {
-        let ptr = libc::mmap(
+    // Memory map the file; it stays mapped so animated styles can redraw
+    let ptr = unsafe {
+        libc::mmap(
             std::ptr::null_mut(),
             size,
             libc::PROT_READ | libc::PROT_WRITE,
             libc::MAP_SHARED,
             fd.as_fd().as_raw_fd(),
             0,
-        );
-
-        if ptr == libc::MAP_FAILED {
-            return Err("mmap failed".to_string());
-        }
+        )
+    };
 
-        // Draw the border
-        let pixels = ptr as *mut u8;
-        draw_border(pixels, width, height, stride);
-
-        // Unmap - the fd keeps the data
-        libc::munmap(ptr, size);
+    if ptr == libc::MAP_FAILED {
+        return Err("mmap failed".to_string());
     }
 
+    let canvas = BorderCanvas {
+        ptr: ptr as *mut u8,
+        size,
+        width,
+        height,
+        stride,
+    };
+
     // Create SHM pool and buffer
     let pool = shm.create_pool(fd.as_fd(), size as i32, qh, ());
     let buffer = pool.create_buffer(0, width, height, stride, wl_shm::Format::Argb8888, qh, ());
@@ -374,7 +440,7 @@ fn create_border_buffer(
     // We can destroy the pool immediately - buffer keeps reference
     pool.destroy();
 
-    Ok(buffer)
+    Ok((buffer, canvas))
 }
 
 /// Create a file descriptor for shared memory
@@ -400,31 +466,30 @@ fn create_shm_fd(size: usize) -> Result<OwnedFd, String> {
 }
 
 /// Draw the border graphic to the buffer
-unsafe fn draw_border(pixels: *mut u8, width: i32, height: i32, stride: i32) {
-    // ARGB8888 format: bytes are [B, G, R, A] in memory (little-endian)
-    for py in 0..height {
-        for px in 0..width {
-            let idx = (py * stride + px * 4) as usize;
-
-            let is_border = py < BORDER_WIDTH
-                || py >= height - BORDER_WIDTH
-                || px < BORDER_WIDTH
-                || px >= width - BORDER_WIDTH;
-
-            if is_border {
-                // Blue border with full alpha
-                *pixels.add(idx) = BORDER_B; // Blue
-                *pixels.add(idx + 1) = BORDER_G; // Green
-                *pixels.add(idx + 2) = BORDER_R; // Red
-                *pixels.add(idx + 3) = BORDER_A; // Alpha
-            } else {
-                // Transparent interior (alpha = 0)
-                *pixels.add(idx) = 0;
-                *pixels.add(idx + 1) = 0;
-                *pixels.add(idx + 2) = 0;
-                *pixels.add(idx + 3) = 0;
-            }
-        }
+unsafe fn draw_border(
+    pixels: *mut u8,
+    width: i32,
+    height: i32,
+    stride: i32,
+    style: &HighlightStyle,
+    dash_offset: u32,
+    opacity: f32,
+) {
+    // ARGB8888 format: bytes are [B, G, R, A] in memory (little-endian), premultiplied
+    let color = style
+        .premultiplied_bgra()
+        .map(|c| (c as f32 * opacity).round() as u8);
+
+    // The memfd starts zeroed, so the interior is already transparent
+    for (px, py) in border_pixels(width, height, style.border_width) {
+        let idx = (py * stride + px * 4) as usize;
+        // Dash gaps are transparent (alpha = 0)
+        let bgra = if style.is_dash_on(px, py, width, height, dash_offset) {
+            color
+        } else {
+            [0; 4]
+        };
+        std::ptr::copy_nonoverlapping(bgra.as_ptr(), pixels.add(idx), 4);
     }
 }
 
@@ -580,7 +645,7 @@ mod tests {
     fn test_show_highlight_does_not_panic() {
         // Just verify it doesn't crash (actual display requires Wayland)
         // This will fail gracefully if not running on Wayland
-        show_highlight(100, 100, 800, 600);
+        show_highlight(100, 100, 800, 600, HighlightStyle::default());
         thread::sleep(Duration::from_millis(100));
     }
 }
@@ -0,0 +1,140 @@
+//! Hyprland dispatchers sent over the compositor's IPC socket.
+//!
+//! A window on another workspace is not rendered, so a window capture
+//! started on it begins with a black frame. With `focus_before_capture`
+//! enabled, window captures first dispatch `focuswindow` (which switches to
+//! the window's workspace) and give the compositor a moment to draw it.
+
+use std::fmt;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// How long a focused window is given to render before capture starts.
+pub const FOCUS_SETTLE_DELAY: Duration = Duration::from_millis(200);
+
+/// Bound on waiting for Hyprland to answer a dispatch.
+const DISPATCH_TIMEOUT: Duration = Duration::from_secs(2);
+
+static FOCUS_BEFORE_CAPTURE: AtomicBool = AtomicBool::new(true);
+
+/// Set whether window captures focus the window first.
+pub fn set_focus_before_capture(enabled: bool) {
+    FOCUS_BEFORE_CAPTURE.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether window captures focus the window first.
+pub fn focus_before_capture() -> bool {
+    FOCUS_BEFORE_CAPTURE.load(Ordering::Relaxed)
+}
+
+/// Error from sending a dispatcher to Hyprland.
+#[derive(Debug)]
+pub enum HyprlandError {
+    /// `HYPRLAND_INSTANCE_SIGNATURE` is not set
+    NotRunning,
+    /// The IPC socket could not be reached or read
+    Io(std::io::Error),
+    /// Hyprland answered with something other than `ok`
+    Rejected(String),
+}
+
+impl fmt::Display for HyprlandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HyprlandError::NotRunning => write!(f, "Hyprland is not running"),
+            HyprlandError::Io(e) => write!(f, "Hyprland IPC failed: {}", e),
+            HyprlandError::Rejected(reply) => write!(f, "Hyprland rejected dispatch: {}", reply),
+        }
+    }
+}
+
+impl std::error::Error for HyprlandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HyprlandError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for HyprlandError {
+    fn from(err: std::io::Error) -> Self {
+        HyprlandError::Io(err)
+    }
+}
+
+/// Run a dispatcher, like `hyprctl dispatch <cmd>`.
+pub fn dispatch_hyprland(cmd: &str) -> Result<(), HyprlandError> {
+    let signature =
+        std::env::var("HYPRLAND_INSTANCE_SIGNATURE").map_err(|_| HyprlandError::NotRunning)?;
+    let path = socket_path(std::env::var("XDG_RUNTIME_DIR").ok(), &signature);
+
+    let mut stream = UnixStream::connect(&path)?;
+    stream.set_read_timeout(Some(DISPATCH_TIMEOUT))?;
+    stream.set_write_timeout(Some(DISPATCH_TIMEOUT))?;
+    stream.write_all(format!("dispatch {}", cmd).as_bytes())?;
+
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    check_reply(&reply)
+}
+
+/// Focus the window at `address` (e.g. `0x5638d0a12345`), switching to its
+/// workspace.
+pub fn focus_window(address: &str) -> Result<(), HyprlandError> {
+    dispatch_hyprland(&format!("focuswindow address:{}", address))
+}
+
+/// Hyprland's command socket: under `$XDG_RUNTIME_DIR/hypr` since 0.40, in
+/// `/tmp/hypr` before.
+fn socket_path(runtime_dir: Option<String>, signature: &str) -> PathBuf {
+    let current = runtime_dir.map(|dir| {
+        PathBuf::from(dir)
+            .join("hypr")
+            .join(signature)
+            .join(".socket.sock")
+    });
+    match current {
+        Some(path) if path.exists() => path,
+        _ => PathBuf::from("/tmp/hypr")
+            .join(signature)
+            .join(".socket.sock"),
+    }
+}
+
+fn check_reply(reply: &str) -> Result<(), HyprlandError> {
+    match reply.trim() {
+        "ok" => Ok(()),
+        other => Err(HyprlandError::Rejected(other.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_reply() {
+        assert!(check_reply("ok").is_ok());
+        assert!(check_reply("ok\n").is_ok());
+        match check_reply("No such window found") {
+            Err(HyprlandError::Rejected(reply)) => assert_eq!(reply, "No such window found"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_socket_path_falls_back_to_tmp() {
+        assert_eq!(
+            socket_path(None, "abc_123"),
+            PathBuf::from("/tmp/hypr/abc_123/.socket.sock")
+        );
+        assert_eq!(
+            socket_path(Some("/nonexistent/run".to_string()), "abc_123"),
+            PathBuf::from("/tmp/hypr/abc_123/.socket.sock")
+        );
+    }
+}
@@ -33,6 +33,14 @@ pub struct CaptureSelection {
     pub geometry: Option<Geometry>,
 }
 
+/// Type and id of `selection`, or "unknown" for both without one.
+pub fn selection_source(selection: Option<&CaptureSelection>) -> (String, String) {
+    match selection {
+        Some(sel) => (sel.source_type.clone(), sel.source_id.clone()),
+        None => ("unknown".to_string(), "unknown".to_string()),
+    }
+}
+
 /// IPC message sent from picker to main app.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -78,12 +86,14 @@ pub struct IpcServerState {
     pub selection: Option<CaptureSelection>,
 }
 
-/// Get the IPC socket path.
+/// Get the IPC socket path, one per Wayland session like the service socket.
 pub fn get_socket_path() -> PathBuf {
     let runtime_dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_else(|_| "/tmp".to_string());
-    PathBuf::from(runtime_dir)
-        .join("omnirec")
-        .join("picker.sock")
+    let name = match omnirec_types::ipc::session_id() {
+        Some(session) => format!("picker-{}.sock", session),
+        None => "picker.sock".to_string(),
+    };
+    PathBuf::from(runtime_dir).join("omnirec").join(name)
 }
 
 /// Handle a single client connection.
@@ -153,18 +163,21 @@ async fn handle_client(
                 IpcResponse::TokenInvalid
             }
         }
-        IpcRequest::StoreToken { token } => match approval_token::write_token(&token) {
-            Ok(()) => {
-                tracing::debug!("[IPC] Token stored successfully");
-                IpcResponse::TokenStored
-            }
-            Err(e) => {
-                tracing::debug!("[IPC] Failed to store token: {}", e);
-                IpcResponse::Error {
-                    message: format!("Failed to store token: {}", e),
+        IpcRequest::StoreToken { token } => {
+            let (source_type, source_id) = selection_source(state.read().await.selection.as_ref());
+            match approval_token::write_token(&token, &source_type, &source_id) {
+                Ok(()) => {
+                    tracing::debug!("[IPC] Token stored successfully");
+                    IpcResponse::TokenStored
+                }
+                Err(e) => {
+                    tracing::debug!("[IPC] Failed to store token: {}", e);
+                    IpcResponse::Error {
+                        message: format!("Failed to store token: {}", e),
+                    }
                 }
             }
-        },
+        }
     };
 
     // Send response
@@ -220,11 +233,12 @@ pub async fn start_ipc_server(
 }
 
 /// Update the current capture selection.
-#[allow(dead_code)]
 pub async fn set_selection(state: &Arc<RwLock<IpcServerState>>, selection: CaptureSelection) {
     tracing::debug!(
         "[IPC] Setting selection: type={}, id={}, geometry={:?}",
-        selection.source_type, selection.source_id, selection.geometry
+        selection.source_type,
+        selection.source_id,
+        selection.geometry
     );
     let mut state = state.write().await;
     state.selection = Some(selection);
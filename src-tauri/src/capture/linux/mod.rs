@@ -11,18 +11,23 @@
 pub mod approval_token;
 pub mod audio;
 pub mod highlight;
+pub mod hyprland_dispatch;
 pub mod ipc_server;
+pub mod monitor_watch;
 pub mod pipewire_capture;
 pub mod portal_client;
+pub mod power_watch;
+pub mod probe;
+pub mod reconnect;
 pub mod screencopy;
 pub mod thumbnail;
 
-use crate::capture::error::{CaptureError, EnumerationError};
+use crate::capture::error::{CaptureError, EnumerationError, PipeWireError};
 use crate::capture::types::{FrameReceiver, StopHandle};
 use crate::capture::{
-    AudioEnumerator, AudioSource, CaptureBackend, CaptureRegion,
-    HighlightProvider, MonitorEnumerator, MonitorInfo, ThumbnailCapture, ThumbnailResult,
-    WindowEnumerator, WindowInfo,
+    AudioEnumerator, AudioSource, CaptureBackend, CaptureRegion, HighlightProvider, HighlightStyle,
+    MonitorEnumerator, MonitorInfo, Screenshot, ThumbnailCapture, ThumbnailOptions,
+    ThumbnailResult, WindowEnumerator, WindowInfo,
 };
 
 use hyprland::data::Monitors;
@@ -61,6 +66,16 @@ pub fn get_ipc_state() -> Option<Arc<RwLock<IpcServerState>>> {
     IPC_STATE.get().cloned()
 }
 
+/// Type and id of the current picker selection, recorded with approval
+/// tokens; "unknown" when nothing is selected.
+pub async fn current_selection_source() -> (String, String) {
+    let Some(state) = get_ipc_state() else {
+        return ("unknown".to_string(), "unknown".to_string());
+    };
+    let guard = state.read().await;
+    ipc_server::selection_source(guard.selection.as_ref())
+}
+
 /// Pre-initialize the screencopy subsystem for faster first thumbnail.
 ///
 /// Call this at app startup (after IPC init) to avoid latency on first thumbnail.
@@ -104,6 +119,8 @@ impl Default for LinuxBackend {
 #[derive(serde::Deserialize, Debug)]
 struct RawWorkspaceBasic {
     id: i32,
+    #[serde(default)]
+    name: String,
 }
 
 /// Raw client data from Hyprland JSON (includes fields missing from hyprland crate)
@@ -212,6 +229,8 @@ impl WindowEnumerator for LinuxBackend {
                 y: logical_y,
                 width,
                 height,
+                workspace_id: Some(client.workspace.id),
+                workspace_name: Some(client.workspace.name.clone()),
             });
         }
 
@@ -308,7 +327,8 @@ impl CaptureBackend for LinuxBackend {
 
         tracing::debug!(
             "[Linux] Starting window capture for {} ({})",
-            window.title, window_address
+            window.title,
+            window_address
         );
 
         // Get IPC state
@@ -319,9 +339,23 @@ impl CaptureBackend for LinuxBackend {
         let stream = tokio::task::block_in_place(|| {
             let rt = tokio::runtime::Handle::current();
             let portal_client = portal_client::PortalClient::new(ipc_state);
-            rt.block_on(portal_client.request_window_capture(&window_address))
+            rt.block_on(async {
+                // Bring the window onto a visible workspace so the first
+                // frame isn't black
+                if Self::is_hyprland() && hyprland_dispatch::focus_before_capture() {
+                    match hyprland_dispatch::focus_window(&window_address) {
+                        Ok(()) => tokio::time::sleep(hyprland_dispatch::FOCUS_SETTLE_DELAY).await,
+                        Err(e) => tracing::warn!(
+                            "[Linux] Failed to focus {} before capture: {}",
+                            window_address,
+                            e
+                        ),
+                    }
+                }
+                portal_client.request_window_capture(&window_address).await
+            })
         })
-        .map_err(CaptureError::PlatformError)?;
+        .map_err(CaptureError::Portal)?;
 
         tracing::debug!(
             "[Linux] Portal returned node ID {} for window capture",
@@ -336,7 +370,7 @@ impl CaptureBackend for LinuxBackend {
 
         // Start PipeWire capture
         pipewire_capture::start_pipewire_capture(stream.node_id, width, height)
-            .map_err(CaptureError::PlatformError)
+            .map_err(|e| CaptureError::PipeWire(PipeWireError::Stream(e)))
     }
 
     fn start_region_capture(
@@ -345,7 +379,11 @@ impl CaptureBackend for LinuxBackend {
     ) -> Result<(FrameReceiver, StopHandle), CaptureError> {
         tracing::debug!(
             "[Linux] Starting region capture for {} ({}x{} at {},{})",
-            region.monitor_id, region.width, region.height, region.x, region.y
+            region.monitor_id,
+            region.width,
+            region.height,
+            region.x,
+            region.y
         );
 
         // Validate region bounds
@@ -415,7 +453,7 @@ impl CaptureBackend for LinuxBackend {
                 region_height,
             ))
         })
-        .map_err(CaptureError::PlatformError)?;
+        .map_err(CaptureError::Portal)?;
 
         tracing::debug!(
             "[Linux] Portal returned node ID {} for region capture",
@@ -430,15 +468,20 @@ impl CaptureBackend for LinuxBackend {
 
         tracing::debug!(
             "[Linux] Capture stream size: {}x{}",
-            capture_width, capture_height
+            capture_width,
+            capture_height
         );
         tracing::debug!(
             "[Linux] Monitor reported size: {}x{}",
-            monitor.width, monitor.height
+            monitor.width,
+            monitor.height
         );
         tracing::debug!(
             "[Linux] Region from UI: {}x{} at {},{}",
-            region.width, region.height, region.x, region.y
+            region.width,
+            region.height,
+            region.x,
+            region.y
         );
 
         // Check if the portal already cropped the stream to the region
@@ -452,7 +495,7 @@ impl CaptureBackend for LinuxBackend {
 
             // The stream is already the region - just capture it directly
             pipewire_capture::start_pipewire_capture(stream.node_id, capture_width, capture_height)
-                .map_err(CaptureError::PlatformError)
+                .map_err(|e| CaptureError::PipeWire(PipeWireError::Stream(e)))
         } else {
             tracing::debug!("[Linux] Portal provided full monitor stream - will crop in app");
 
@@ -468,7 +511,10 @@ impl CaptureBackend for LinuxBackend {
 
             tracing::debug!(
                 "[Linux] App-level crop region: {}x{} at {},{}",
-                scaled_width, scaled_height, scaled_x, scaled_y
+                scaled_width,
+                scaled_height,
+                scaled_x,
+                scaled_y
             );
 
             let crop_region = pipewire_capture::CropRegion {
@@ -484,7 +530,7 @@ impl CaptureBackend for LinuxBackend {
                 capture_height,
                 Some(crop_region),
             )
-            .map_err(CaptureError::PlatformError)
+            .map_err(|e| CaptureError::PipeWire(PipeWireError::Stream(e)))
         }
     }
 
@@ -496,7 +542,9 @@ impl CaptureBackend for LinuxBackend {
     ) -> Result<(FrameReceiver, StopHandle), CaptureError> {
         tracing::debug!(
             "[Linux] Starting display capture for {} ({}x{})",
-            monitor_id, width, height
+            monitor_id,
+            width,
+            height
         );
 
         // Get IPC state
@@ -504,13 +552,12 @@ impl CaptureBackend for LinuxBackend {
             .ok_or_else(|| CaptureError::PlatformError("IPC server not initialized".to_string()))?;
 
         // Use block_in_place to run async code from sync context within tokio runtime
-        let monitor_id_clone = monitor_id.clone();
+        let runtime = tokio::runtime::Handle::current();
         let stream = tokio::task::block_in_place(|| {
-            let rt = tokio::runtime::Handle::current();
-            let portal_client = portal_client::PortalClient::new(ipc_state);
-            rt.block_on(portal_client.request_monitor_capture(&monitor_id_clone))
+            let portal_client = portal_client::PortalClient::new(ipc_state.clone());
+            runtime.block_on(portal_client.request_monitor_capture(&monitor_id))
         })
-        .map_err(CaptureError::PlatformError)?;
+        .map_err(CaptureError::Portal)?;
 
         tracing::debug!(
             "[Linux] Portal returned node ID {} for display capture",
@@ -523,15 +570,28 @@ impl CaptureBackend for LinuxBackend {
             .map(|(w, h)| (w as u32, h as u32))
             .unwrap_or((width, height));
 
-        // Start PipeWire capture
-        pipewire_capture::start_pipewire_capture(stream.node_id, capture_width, capture_height)
-            .map_err(CaptureError::PlatformError)
+        // Start PipeWire capture, reconnecting if the compositor replaces the node
+        let capture =
+            pipewire_capture::start_pipewire_capture(stream.node_id, capture_width, capture_height)
+                .map_err(|e| CaptureError::PipeWire(PipeWireError::Stream(e)))?;
+        Ok(reconnect::supervise_display_capture(
+            monitor_id,
+            capture,
+            (capture_width, capture_height),
+            ipc_state,
+            runtime,
+        ))
+    }
+
+    /// Available when the startup probe found no missing dependencies.
+    fn is_available(&self) -> bool {
+        crate::capture::probe::get_probe_result().ok
     }
 }
 
 impl HighlightProvider for LinuxBackend {
-    fn show_highlight(&self, x: i32, y: i32, width: i32, height: i32) {
-        highlight::show_highlight(x, y, width, height);
+    fn show_highlight(&self, x: i32, y: i32, width: i32, height: i32, style: &HighlightStyle) {
+        highlight::show_highlight(x, y, width, height, *style);
     }
 }
 
@@ -539,14 +599,19 @@ impl ThumbnailCapture for LinuxBackend {
     fn capture_window_thumbnail(
         &self,
         window_handle: isize,
+        options: ThumbnailOptions,
     ) -> Result<ThumbnailResult, CaptureError> {
         let thumb_capture = thumbnail::LinuxThumbnailCapture::new();
-        thumb_capture.capture_window_thumbnail(window_handle)
+        thumb_capture.capture_window_thumbnail(window_handle, options)
     }
 
-    fn capture_display_thumbnail(&self, monitor_id: &str) -> Result<ThumbnailResult, CaptureError> {
+    fn capture_display_thumbnail(
+        &self,
+        monitor_id: &str,
+        options: ThumbnailOptions,
+    ) -> Result<ThumbnailResult, CaptureError> {
         let thumb_capture = thumbnail::LinuxThumbnailCapture::new();
-        thumb_capture.capture_display_thumbnail(monitor_id)
+        thumb_capture.capture_display_thumbnail(monitor_id, options)
     }
 
     fn capture_region_preview(
@@ -556,9 +621,20 @@ impl ThumbnailCapture for LinuxBackend {
         y: i32,
         width: u32,
         height: u32,
+        quality: u8,
     ) -> Result<ThumbnailResult, CaptureError> {
         let thumb_capture = thumbnail::LinuxThumbnailCapture::new();
-        thumb_capture.capture_region_preview(monitor_id, x, y, width, height)
+        thumb_capture.capture_region_preview(monitor_id, x, y, width, height, quality)
+    }
+
+    fn capture_window_screenshot(&self, window_handle: isize) -> Result<Screenshot, CaptureError> {
+        let thumb_capture = thumbnail::LinuxThumbnailCapture::new();
+        thumb_capture.capture_window_screenshot(window_handle)
+    }
+
+    fn capture_display_screenshot(&self, monitor_id: &str) -> Result<Screenshot, CaptureError> {
+        let thumb_capture = thumbnail::LinuxThumbnailCapture::new();
+        thumb_capture.capture_display_screenshot(monitor_id)
     }
 }
 
@@ -570,6 +646,47 @@ impl AudioEnumerator for LinuxBackend {
 
 // Extension methods for Linux-specific capture features
 impl LinuxBackend {
+    /// Start capturing the monitor that currently shows a Hyprland workspace.
+    ///
+    /// Returns a frame receiver, stop handle and the workspace name.
+    pub fn start_workspace_capture(
+        &self,
+        workspace_id: i32,
+    ) -> Result<(FrameReceiver, StopHandle, String), CaptureError> {
+        if !Self::is_hyprland() {
+            return Err(CaptureError::NotImplemented(
+                "Workspace capture requires Hyprland compositor".to_string(),
+            ));
+        }
+
+        let monitors = Monitors::get().map_err(|e| {
+            CaptureError::PlatformError(format!("Failed to get Hyprland monitors: {}", e))
+        })?;
+        let monitor = monitors
+            .iter()
+            .find(|m| m.active_workspace.id == workspace_id)
+            .ok_or_else(|| {
+                CaptureError::TargetNotFound(format!(
+                    "Workspace {} is not shown on any monitor",
+                    workspace_id
+                ))
+            })?;
+
+        tracing::debug!(
+            "[Linux] Workspace {} ({}) is on monitor {}",
+            workspace_id,
+            monitor.active_workspace.name,
+            monitor.name
+        );
+
+        let (frame_rx, stop_flag) = self.start_display_capture(
+            monitor.name.clone(),
+            monitor.width as u32,
+            monitor.height as u32,
+        )?;
+        Ok((frame_rx, stop_flag, monitor.active_workspace.name.clone()))
+    }
+
     /// Start portal-based capture with native picker (for GNOME).
     ///
     /// This method invokes the xdg-desktop-portal screencast flow without
@@ -591,7 +708,7 @@ impl LinuxBackend {
             // Request generic screencast - portal will show picker
             rt.block_on(portal_client.request_screencast_with_picker())
         })
-        .map_err(CaptureError::PlatformError)?;
+        .map_err(CaptureError::Portal)?;
 
         tracing::debug!(
             "[Linux] Portal returned node ID {} from native picker",
@@ -606,14 +723,16 @@ impl LinuxBackend {
 
         tracing::debug!(
             "[Linux] Content dimensions: {}x{}",
-            content_width, content_height
+            content_width,
+            content_height
         );
 
         // Check if we need to crop (window capture provides position)
         if let Some((x, y)) = stream.position {
             tracing::debug!(
                 "[Linux] Window position: ({}, {}), will crop to content",
-                x, y
+                x,
+                y
             );
 
             // For window captures, the stream is the full display but we need to crop
@@ -637,20 +756,21 @@ impl LinuxBackend {
                 stream_height,
                 Some(crop),
             )
-            .map_err(CaptureError::PlatformError)
+            .map_err(|e| CaptureError::PipeWire(PipeWireError::Stream(e)))
         } else {
             // No position info - use auto-crop detection for window captures
             // This handles GNOME's portal which doesn't provide window position
             tracing::debug!(
                 "[Linux] No position info, using auto-crop detection, dimensions: {}x{}",
-                content_width, content_height
+                content_width,
+                content_height
             );
             pipewire_capture::start_pipewire_capture_with_auto_crop(
                 stream.node_id,
                 content_width,
                 content_height,
             )
-            .map_err(CaptureError::PlatformError)
+            .map_err(|e| CaptureError::PipeWire(PipeWireError::Stream(e)))
         }
     }
 }
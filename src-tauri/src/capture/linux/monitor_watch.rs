@@ -0,0 +1,103 @@
+//! Monitor hot-plug watcher for Hyprland.
+//!
+//! Hyprland publishes compositor events as `name>>data` lines on its event
+//! socket (`.socket2.sock`). Monitor connects and disconnects are reported;
+//! other compositors have no equivalent and are not watched.
+
+use super::LinuxBackend;
+use std::io::{BufRead, BufReader};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+/// Start watching for monitor changes, calling `on_change` for each one.
+///
+/// Does nothing outside Hyprland.
+pub fn start(on_change: fn()) {
+    if !LinuxBackend::is_hyprland() {
+        tracing::debug!("[MonitorWatch] Not running under Hyprland, monitor watch disabled");
+        return;
+    }
+    let spawned = thread::Builder::new()
+        .name("monitor-watch".to_string())
+        .spawn(move || {
+            if let Err(e) = watch_event_socket(on_change) {
+                tracing::warn!("[MonitorWatch] Hyprland event socket: {}", e);
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::warn!("[MonitorWatch] Failed to start monitor watcher: {}", e);
+    }
+}
+
+fn watch_event_socket(on_change: fn()) -> Result<(), String> {
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE")
+        .map_err(|_| "HYPRLAND_INSTANCE_SIGNATURE is not set".to_string())?;
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from);
+
+    let stream = event_socket_paths(&signature, runtime_dir.as_deref())
+        .into_iter()
+        .find_map(|path| UnixStream::connect(path).ok())
+        .ok_or_else(|| "could not connect".to_string())?;
+    tracing::debug!("[MonitorWatch] Connected to Hyprland event socket");
+
+    for line in BufReader::new(stream).lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if is_monitor_event(&line) {
+            tracing::debug!("[MonitorWatch] {}", line);
+            on_change();
+        }
+    }
+    Err("connection closed".to_string())
+}
+
+/// Candidate locations of the event socket. Hyprland 0.40+ uses
+/// `$XDG_RUNTIME_DIR/hypr`, older releases `/tmp/hypr`.
+fn event_socket_paths(signature: &str, runtime_dir: Option<&Path>) -> Vec<PathBuf> {
+    runtime_dir
+        .map(|dir| dir.join("hypr"))
+        .into_iter()
+        .chain(std::iter::once(PathBuf::from("/tmp/hypr")))
+        .map(|dir| dir.join(signature).join(".socket2.sock"))
+        .collect()
+}
+
+/// Whether an event socket line reports a monitor being connected or disconnected.
+fn is_monitor_event(line: &str) -> bool {
+    let name = line.split_once(">>").map_or(line, |(name, _)| name);
+    matches!(
+        name,
+        "monitoradded" | "monitoraddedv2" | "monitorremoved" | "monitorremovedv2"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_monitor_event() {
+        assert!(is_monitor_event("monitoradded>>DP-2"));
+        assert!(is_monitor_event("monitoraddedv2>>2,DP-2,Dell U2720Q"));
+        assert!(is_monitor_event("monitorremoved>>HDMI-A-1"));
+        assert!(!is_monitor_event("focusedmon>>DP-1,2"));
+        assert!(!is_monitor_event("workspace>>3"));
+        assert!(!is_monitor_event(""));
+    }
+
+    #[test]
+    fn test_event_socket_paths() {
+        let paths = event_socket_paths("abc", Some(Path::new("/run/user/1000")));
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/run/user/1000/hypr/abc/.socket2.sock"),
+                PathBuf::from("/tmp/hypr/abc/.socket2.sock"),
+            ]
+        );
+        assert_eq!(
+            event_socket_paths("abc", None),
+            vec![PathBuf::from("/tmp/hypr/abc/.socket2.sock")]
+        );
+    }
+}
@@ -12,7 +12,11 @@
 //! When the captured window is closed, the PipeWire stream transitions
 //! to an error state. This triggers the stop flag and cleanly exits capture.
 
-use crate::capture::types::{CapturedFrame, FrameReceiver, StopHandle};
+use crate::capture::frame_limit::{self, TokenBucket};
+use crate::capture::frame_pool::{FramePool, PooledBuffer};
+use crate::capture::types::{
+    CapturedFrame, FrameReceiver, PixelFormat, PresentationClock, StopHandle,
+};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -21,6 +25,12 @@ use pipewire as pw;
 use pw::spa;
 use spa::pod::Pod;
 
+/// Frame rate requested from the compositor by default.
+const DEFAULT_FRAMERATE: u32 = 30;
+
+/// Highest frame rate accepted from the compositor.
+const MAX_FRAMERATE: u32 = 120;
+
 /// Region specification for cropping frames.
 #[derive(Debug, Clone, Copy)]
 pub struct CropRegion {
@@ -144,6 +154,12 @@ struct StreamData {
     auto_crop: Option<CropRegion>,
     /// Whether we should try to auto-detect content bounds
     enable_auto_crop: bool,
+    /// Frame rate limit, for compositors that send faster than negotiated
+    frame_limit: Option<TokenBucket>,
+    /// Buffers frames are copied into
+    frame_pool: Arc<FramePool>,
+    /// Maps buffer presentation times onto the recording epoch
+    clock: PresentationClock,
 }
 
 /// Run the PipeWire main loop and capture frames.
@@ -196,6 +212,9 @@ fn run_pipewire_capture(
         crop_region,
         auto_crop: None,
         enable_auto_crop,
+        frame_limit: TokenBucket::for_current_limit(),
+        frame_pool: FramePool::new(width as usize * height as usize * 4),
+        clock: PresentationClock::new(),
     };
 
     // Clone mainloop for stop check
@@ -249,7 +268,7 @@ fn run_pipewire_capture(
                 _ => {}
             }
         })
-        .param_changed(|_, user_data, id, param| {
+        .param_changed(|stream, user_data, id, param| {
             let Some(param) = param else { return };
             if id != pw::spa::param::ParamType::Format.as_raw() {
                 return;
@@ -297,6 +316,19 @@ fn run_pipewire_capture(
                 user_data.format.framerate().num,
                 user_data.format.framerate().denom
             );
+
+            // Ask for header metadata, which carries each buffer's
+            // presentation time
+            match header_meta_param() {
+                Ok(values) => {
+                    if let Some(pod) = Pod::from_bytes(&values) {
+                        if let Err(e) = stream.update_params(&mut [pod]) {
+                            tracing::debug!("[PipeWire] Failed to request header metadata: {}", e);
+                        }
+                    }
+                }
+                Err(e) => tracing::debug!("[PipeWire] {}", e),
+            }
         })
         .process(|stream, user_data| {
             if user_data.stop_flag.load(Ordering::Relaxed) {
@@ -310,26 +342,43 @@ fn run_pipewire_capture(
                 return;
             }
 
-            match stream.dequeue_buffer() {
-                Some(mut buffer) => {
-                    user_data.frames_received += 1;
-                    // Log periodically instead of every frame
-                    if user_data.frames_received == 1 || user_data.frames_received % 100 == 0 {
-                        tracing::debug!(
-                            "[PipeWire] Processing frame #{}",
-                            user_data.frames_received
-                        );
-                    }
-                    process_buffer(&mut buffer, user_data);
-                }
-                None => {
-                    // This is normal when the producer hasn't provided a new buffer yet
+            // Dequeue the raw buffer, since the `Buffer` wrapper doesn't
+            // expose the header metadata
+            let buffer = unsafe { stream.dequeue_raw_buffer() };
+            if buffer.is_null() {
+                // This is normal when the producer hasn't provided a new buffer yet
+                return;
+            }
+
+            user_data.frames_received += 1;
+            // Log periodically instead of every frame
+            if user_data.frames_received == 1 || user_data.frames_received % 100 == 0 {
+                tracing::debug!("[PipeWire] Processing frame #{}", user_data.frames_received);
+            }
+            // Drop frames above the frame rate limit
+            if frame_limit::admit(&mut user_data.frame_limit) {
+                unsafe {
+                    let spa_buffer = &*(*buffer).buffer;
+                    let pts_us = header_pts_us(spa_buffer);
+                    let datas = if spa_buffer.datas.is_null() {
+                        &mut []
+                    } else {
+                        std::slice::from_raw_parts_mut(
+                            spa_buffer.datas as *mut spa::buffer::Data,
+                            spa_buffer.n_datas as usize,
+                        )
+                    };
+                    process_buffer(datas, pts_us, user_data);
                 }
             }
+            unsafe { stream.queue_raw_buffer(buffer) };
         })
         .register()
         .map_err(|e| format!("Failed to register stream listener: {}", e))?;
 
+    // Ask for no more frames than the frame rate limit
+    let max_fps = frame_limit::max_fps().map_or(MAX_FRAMERATE, |fps| fps.min(MAX_FRAMERATE));
+
     // Request video format with preference for formats we can handle
     // This helps negotiate a linear buffer format instead of tiled DMA-BUF
     let obj = pw::spa::pod::object!(
@@ -380,9 +429,15 @@ fn run_pipewire_capture(
             Choice,
             Range,
             Fraction,
-            pw::spa::utils::Fraction { num: 30, denom: 1 },
+            pw::spa::utils::Fraction {
+                num: DEFAULT_FRAMERATE.min(max_fps),
+                denom: 1
+            },
             pw::spa::utils::Fraction { num: 0, denom: 1 },
-            pw::spa::utils::Fraction { num: 120, denom: 1 }
+            pw::spa::utils::Fraction {
+                num: max_fps,
+                denom: 1
+            }
         ),
     );
 
@@ -449,12 +504,67 @@ fn run_pipewire_capture(
     Ok(())
 }
 
-/// Process a buffer from the PipeWire stream.
-fn process_buffer(buffer: &mut pw::buffer::Buffer, user_data: &mut StreamData) {
-    let datas = buffer.datas_mut();
+/// Serialized `SPA_PARAM_Meta` asking for `spa_meta_header` on each buffer.
+fn header_meta_param() -> Result<Vec<u8>, String> {
+    let obj = pw::spa::pod::Object {
+        type_: libspa_sys::SPA_TYPE_OBJECT_ParamMeta,
+        id: libspa_sys::SPA_PARAM_Meta,
+        properties: vec![
+            pw::spa::pod::Property {
+                key: libspa_sys::SPA_PARAM_META_type,
+                flags: pw::spa::pod::PropertyFlags::empty(),
+                value: pw::spa::pod::Value::Id(pw::spa::utils::Id(libspa_sys::SPA_META_Header)),
+            },
+            pw::spa::pod::Property {
+                key: libspa_sys::SPA_PARAM_META_size,
+                flags: pw::spa::pod::PropertyFlags::empty(),
+                value: pw::spa::pod::Value::Int(
+                    std::mem::size_of::<libspa_sys::spa_meta_header>() as i32
+                ),
+            },
+        ],
+    };
+    Ok(pw::spa::pod::serialize::PodSerializer::serialize(
+        std::io::Cursor::new(Vec::new()),
+        &pw::spa::pod::Value::Object(obj),
+    )
+    .map_err(|e| format!("Failed to serialize meta params: {:?}", e))?
+    .0
+    .into_inner())
+}
+
+/// Presentation time from a buffer's header metadata, in microseconds.
+///
+/// # Safety
+///
+/// `buffer` must be a buffer dequeued from the stream and not yet queued
+/// back.
+unsafe fn header_pts_us(buffer: &libspa_sys::spa_buffer) -> Option<i64> {
+    if buffer.metas.is_null() {
+        return None;
+    }
+    let metas = std::slice::from_raw_parts(buffer.metas, buffer.n_metas as usize);
+    let meta = metas.iter().find(|meta| {
+        meta.type_ == libspa_sys::SPA_META_Header
+            && !meta.data.is_null()
+            && meta.size as usize >= std::mem::size_of::<libspa_sys::spa_meta_header>()
+    })?;
+    let header = &*(meta.data as *const libspa_sys::spa_meta_header);
+    // Nanoseconds on the compositor's monotonic clock; negative if unknown
+    (header.pts > 0).then_some(header.pts / 1_000)
+}
+
+/// Process the data planes of a buffer from the PipeWire stream, presented
+/// at `pts_us` if known.
+fn process_buffer(
+    datas: &mut [spa::buffer::Data],
+    pts_us: Option<i64>,
+    user_data: &mut StreamData,
+) {
     if datas.is_empty() {
         return;
     }
+    let timestamp_us = user_data.clock.timestamp_us(pts_us);
 
     let data = &mut datas[0];
 
@@ -538,8 +648,14 @@ fn process_buffer(buffer: &mut pw::buffer::Buffer, user_data: &mut StreamData) {
                     );
 
                     // Process the frame
-                    let frame_data =
-                        extract_frame_data(mapped_slice, width, height, stride, bytes_per_pixel);
+                    let frame_data = extract_frame_data(
+                        mapped_slice,
+                        width,
+                        height,
+                        stride,
+                        bytes_per_pixel,
+                        &user_data.frame_pool,
+                    );
 
                     // Unmap
                     libc::munmap(ptr, map_size);
@@ -547,13 +663,23 @@ fn process_buffer(buffer: &mut pw::buffer::Buffer, user_data: &mut StreamData) {
                     if let Some(frame_data) = frame_data {
                         // Apply cropping if specified
                         if let Some(crop) = user_data.crop_region {
-                            if let Some(cropped_data) =
-                                crop_frame_data(&frame_data, width, height, crop)
-                            {
-                                send_frame(user_data, crop.width, crop.height, cropped_data);
+                            if let Some(cropped_data) = crop_frame_data(
+                                &frame_data,
+                                width,
+                                height,
+                                crop,
+                                &user_data.frame_pool,
+                            ) {
+                                send_frame(
+                                    user_data,
+                                    crop.width,
+                                    crop.height,
+                                    cropped_data,
+                                    timestamp_us,
+                                );
                             }
                         } else {
-                            send_frame(user_data, width, height, frame_data);
+                            send_frame(user_data, width, height, frame_data, timestamp_us);
                         }
                     }
                     return;
@@ -565,14 +691,29 @@ fn process_buffer(buffer: &mut pw::buffer::Buffer, user_data: &mut StreamData) {
         }
     };
 
-    if let Some(frame_data) = extract_frame_data(slice, width, height, stride, bytes_per_pixel) {
+    let frame_data = extract_frame_data(
+        slice,
+        width,
+        height,
+        stride,
+        bytes_per_pixel,
+        &user_data.frame_pool,
+    );
+    if let Some(frame_data) = frame_data {
         // Apply cropping if specified
         if let Some(crop) = user_data.crop_region {
-            if let Some(cropped_data) = crop_frame_data(&frame_data, width, height, crop) {
-                send_frame(user_data, crop.width, crop.height, cropped_data);
+            let cropped = crop_frame_data(&frame_data, width, height, crop, &user_data.frame_pool);
+            if let Some(cropped_data) = cropped {
+                send_frame(
+                    user_data,
+                    crop.width,
+                    crop.height,
+                    cropped_data,
+                    timestamp_us,
+                );
             }
         } else {
-            send_frame(user_data, width, height, frame_data);
+            send_frame(user_data, width, height, frame_data, timestamp_us);
         }
     }
 }
@@ -584,7 +725,8 @@ fn extract_frame_data(
     height: u32,
     stride: usize,
     bytes_per_pixel: usize,
-) -> Option<Vec<u8>> {
+    pool: &Arc<FramePool>,
+) -> Option<PooledBuffer> {
     let row_bytes = width as usize * bytes_per_pixel;
 
     // Log stride info once
@@ -608,22 +750,21 @@ fn extract_frame_data(
         }
     }
 
+    let total_bytes = (height as usize) * row_bytes;
+    let mut frame_data = pool.acquire(total_bytes);
+
     // If stride matches row_bytes exactly, we can do a direct copy
-    if stride == row_bytes {
-        let total_bytes = (height as usize) * row_bytes;
-        if slice.len() >= total_bytes {
-            return Some(slice[..total_bytes].to_vec());
-        }
+    if stride == row_bytes && slice.len() >= total_bytes {
+        frame_data.copy_from_slice(&slice[..total_bytes]);
+        return Some(frame_data);
     }
 
     // Otherwise, copy row by row to handle stride padding
-    let mut frame_data = Vec::with_capacity((width * height * 4) as usize);
-
-    for y in 0..height as usize {
+    for (y, row) in frame_data.chunks_exact_mut(row_bytes.max(1)).enumerate() {
         let row_start = y * stride;
         let row_end = row_start + row_bytes;
         if row_end <= slice.len() {
-            frame_data.extend_from_slice(&slice[row_start..row_end]);
+            row.copy_from_slice(&slice[row_start..row_end]);
         } else {
             // Log only once per session
             static LOGGED_TOO_SMALL: AtomicBool = AtomicBool::new(false);
@@ -648,7 +789,8 @@ fn crop_frame_data(
     full_width: u32,
     full_height: u32,
     crop: CropRegion,
-) -> Option<Vec<u8>> {
+    pool: &Arc<FramePool>,
+) -> Option<PooledBuffer> {
     let bytes_per_pixel = 4; // BGRA
 
     // Validate crop region
@@ -690,16 +832,16 @@ fn crop_frame_data(
         );
     }
 
-    let mut cropped =
-        Vec::with_capacity((actual_crop_width * actual_crop_height * bytes_per_pixel) as usize);
+    let row_bytes = (actual_crop_width as usize) * (bytes_per_pixel as usize);
+    let mut cropped = pool.acquire(row_bytes * actual_crop_height as usize);
 
     // Copy row by row
-    for y in crop_y..crop_y_end {
+    for (y, row) in (crop_y..crop_y_end).zip(cropped.chunks_exact_mut(row_bytes.max(1))) {
         let row_start = ((y * full_width + crop_x) as usize) * (bytes_per_pixel as usize);
-        let row_end = row_start + ((actual_crop_width as usize) * (bytes_per_pixel as usize));
+        let row_end = row_start + row_bytes;
 
         if row_end <= frame_data.len() {
-            cropped.extend_from_slice(&frame_data[row_start..row_end]);
+            row.copy_from_slice(&frame_data[row_start..row_end]);
         } else {
             tracing::debug!(
                 "[PipeWire] Crop overflow at row {}: need {} but have {}",
@@ -714,72 +856,52 @@ fn crop_frame_data(
     Some(cropped)
 }
 
-/// Convert frame data to BGRA format based on the source format.
-fn convert_to_bgra(frame_data: Vec<u8>, format: spa::param::video::VideoFormat) -> Vec<u8> {
+/// Convert frame data to BGRA format in place, based on the source format.
+fn convert_to_bgra(frame_data: &mut [u8], format: spa::param::video::VideoFormat) {
     use spa::param::video::VideoFormat;
 
     match format {
         // Already BGRA or BGRx - no conversion needed
-        VideoFormat::BGRA | VideoFormat::BGRx => frame_data,
+        VideoFormat::BGRA | VideoFormat::BGRx => {}
 
         // RGBA/RGBx -> BGRA: swap R and B
         VideoFormat::RGBA | VideoFormat::RGBx => {
-            let mut converted = frame_data;
-            for chunk in converted.chunks_exact_mut(4) {
+            for chunk in frame_data.chunks_exact_mut(4) {
                 chunk.swap(0, 2); // Swap R and B
             }
-            converted
         }
 
-        // ARGB -> BGRA: shift ARGB to BGRA (A,R,G,B -> B,G,R,A)
+        // ARGB -> BGRA: reverse ARGB to BGRA (A,R,G,B -> B,G,R,A)
         VideoFormat::ARGB => {
-            let mut converted = Vec::with_capacity(frame_data.len());
-            for chunk in frame_data.chunks_exact(4) {
-                converted.push(chunk[3]); // B
-                converted.push(chunk[2]); // G
-                converted.push(chunk[1]); // R
-                converted.push(chunk[0]); // A
+            for chunk in frame_data.chunks_exact_mut(4) {
+                chunk.reverse();
             }
-            converted
         }
 
-        // xRGB -> BGRA: shift xRGB to BGRA (x,R,G,B -> B,G,R,255)
+        // xRGB -> BGRA: reverse xRGB to BGRA (x,R,G,B -> B,G,R,255)
         VideoFormat::xRGB => {
-            let mut converted = Vec::with_capacity(frame_data.len());
-            for chunk in frame_data.chunks_exact(4) {
-                converted.push(chunk[3]); // B
-                converted.push(chunk[2]); // G
-                converted.push(chunk[1]); // R
-                converted.push(255); // A (fully opaque)
+            for chunk in frame_data.chunks_exact_mut(4) {
+                chunk.reverse();
+                chunk[3] = 255; // A (fully opaque)
             }
-            converted
         }
 
         // ABGR -> BGRA: A,B,G,R -> B,G,R,A
         VideoFormat::ABGR => {
-            let mut converted = Vec::with_capacity(frame_data.len());
-            for chunk in frame_data.chunks_exact(4) {
-                converted.push(chunk[1]); // B
-                converted.push(chunk[2]); // G
-                converted.push(chunk[3]); // R
-                converted.push(chunk[0]); // A
+            for chunk in frame_data.chunks_exact_mut(4) {
+                chunk.rotate_left(1);
             }
-            converted
         }
 
         // xBGR -> BGRA: x,B,G,R -> B,G,R,255
         VideoFormat::xBGR => {
-            let mut converted = Vec::with_capacity(frame_data.len());
-            for chunk in frame_data.chunks_exact(4) {
-                converted.push(chunk[1]); // B
-                converted.push(chunk[2]); // G
-                converted.push(chunk[3]); // R
-                converted.push(255); // A (fully opaque)
+            for chunk in frame_data.chunks_exact_mut(4) {
+                chunk.rotate_left(1);
+                chunk[3] = 255; // A (fully opaque)
             }
-            converted
         }
 
-        // Unknown format - log warning and return as-is
+        // Unknown format - log warning and leave as-is
         _ => {
             static LOGGED_UNKNOWN: AtomicBool = AtomicBool::new(false);
             if !LOGGED_UNKNOWN.swap(true, Ordering::Relaxed) {
@@ -788,7 +910,6 @@ fn convert_to_bgra(frame_data: Vec<u8>, format: spa::param::video::VideoFormat)
                     format
                 );
             }
-            frame_data
         }
     }
 }
@@ -858,10 +979,17 @@ fn detect_content_bounds(frame_data: &[u8], width: u32, height: u32) -> Option<C
 }
 
 /// Send a frame to the encoder channel.
-fn send_frame(user_data: &mut StreamData, width: u32, height: u32, frame_data: Vec<u8>) {
+fn send_frame(
+    user_data: &mut StreamData,
+    width: u32,
+    height: u32,
+    frame_data: PooledBuffer,
+    timestamp_us: u64,
+) {
     // Convert to BGRA format for consistent downstream processing
     let format = user_data.format.format();
-    let bgra_data = convert_to_bgra(frame_data, format);
+    let mut bgra_data = frame_data;
+    convert_to_bgra(&mut bgra_data, format);
 
     // Auto-crop detection on first frame
     if user_data.enable_auto_crop && user_data.auto_crop.is_none() && user_data.frames_received == 1
@@ -879,7 +1007,9 @@ fn send_frame(user_data: &mut StreamData, width: u32, height: u32, frame_data: V
 
     // Apply auto-crop if detected
     let (final_data, final_width, final_height) = if let Some(crop) = user_data.auto_crop {
-        if let Some(cropped) = crop_frame_data(&bgra_data, width, height, crop) {
+        if let Some(cropped) =
+            crop_frame_data(&bgra_data, width, height, crop, &user_data.frame_pool)
+        {
             (cropped, crop.width, crop.height)
         } else {
             (bgra_data, width, height)
@@ -891,8 +1021,10 @@ fn send_frame(user_data: &mut StreamData, width: u32, height: u32, frame_data: V
     let frame = CapturedFrame {
         width: final_width,
         height: final_height,
-        data: final_data,
+        data: final_data.into(),
+        pixel_format: PixelFormat::Bgra,
         captured_at: std::time::SystemTime::now(),
+        timestamp_us,
     };
 
     // Non-blocking send - drop frame if channel is full
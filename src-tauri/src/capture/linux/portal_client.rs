@@ -8,10 +8,29 @@ use ashpd::desktop::screencast::{CursorMode, Screencast, SourceType};
 use ashpd::desktop::PersistMode;
 use ashpd::enumflags2::BitFlags;
 use ashpd::WindowIdentifier;
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 use super::ipc_server::{CaptureSelection, Geometry, IpcServerState};
+use crate::capture::error::PortalError;
+
+/// Maximum number of attempts for a single screencast request.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay between screencast request attempts.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Number of retries the most recent screencast request needed.
+static LAST_REQUEST_RETRIES: AtomicU32 = AtomicU32::new(0);
+
+/// Number of retries the most recent screencast request needed before it
+/// succeeded or gave up.
+pub fn last_request_retries() -> u32 {
+    LAST_REQUEST_RETRIES.load(Ordering::Relaxed)
+}
 
 /// Source type for capture selection.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -64,7 +83,7 @@ impl PortalClient {
     pub async fn request_monitor_capture(
         &self,
         monitor_id: &str,
-    ) -> Result<ScreencastStream, String> {
+    ) -> Result<ScreencastStream, PortalError> {
         tracing::debug!("[PortalClient] request_monitor_capture: {}", monitor_id);
 
         // Set selection for picker to query
@@ -90,7 +109,7 @@ impl PortalClient {
     pub async fn request_window_capture(
         &self,
         window_address: &str,
-    ) -> Result<ScreencastStream, String> {
+    ) -> Result<ScreencastStream, PortalError> {
         tracing::debug!("[PortalClient] request_window_capture: {}", window_address);
 
         let selection = CaptureSelection {
@@ -120,7 +139,7 @@ impl PortalClient {
         y: i32,
         width: u32,
         height: u32,
-    ) -> Result<ScreencastStream, String> {
+    ) -> Result<ScreencastStream, PortalError> {
         let selection = CaptureSelection {
             source_type: CaptureSourceType::Region.as_str().to_string(),
             source_id: monitor_id.to_string(),
@@ -146,7 +165,7 @@ impl PortalClient {
     /// This method does NOT set any IPC selection, allowing the portal's
     /// native picker (e.g., GNOME's dialog) to handle source selection.
     /// Use this for GNOME mode where we want the standard portal UX.
-    pub async fn request_screencast_with_picker(&self) -> Result<ScreencastStream, String> {
+    pub async fn request_screencast_with_picker(&self) -> Result<ScreencastStream, PortalError> {
         tracing::debug!("[PortalClient] request_screencast_with_picker: using native picker");
 
         // Clear any existing IPC selection so our custom picker doesn't interfere
@@ -165,21 +184,75 @@ impl PortalClient {
     async fn request_screencast(
         &self,
         source_type: SourceType,
-    ) -> Result<ScreencastStream, String> {
+    ) -> Result<ScreencastStream, PortalError> {
         self.request_screencast_multi(source_type.into()).await
     }
 
     /// Internal method to execute the portal screencast flow with multiple source types.
+    ///
+    /// Transient portal failures are retried; see [`request_with_retry`].
     async fn request_screencast_multi(
         &self,
         source_types: BitFlags<SourceType>,
-    ) -> Result<ScreencastStream, String> {
+    ) -> Result<ScreencastStream, PortalError> {
+        request_with_retry(self, source_types, RETRY_DELAY).await
+    }
+}
+
+/// A single screencast request attempt.
+///
+/// Abstracted so the retry policy can be exercised without a D-Bus session.
+trait ScreencastRequester {
+    fn request_once(
+        &self,
+        source_types: BitFlags<SourceType>,
+    ) -> impl Future<Output = Result<ScreencastStream, PortalError>>;
+}
+
+/// Run a screencast request, retrying transient failures up to
+/// `MAX_ATTEMPTS` times with `delay` between attempts.
+///
+/// Returns `PortalError::Timeout` if every attempt failed transiently.
+async fn request_with_retry<R: ScreencastRequester>(
+    requester: &R,
+    source_types: BitFlags<SourceType>,
+    delay: Duration,
+) -> Result<ScreencastStream, PortalError> {
+    let mut attempt = 1;
+    loop {
+        tracing::debug!(
+            "[Portal] Screencast request attempt {}/{}",
+            attempt,
+            MAX_ATTEMPTS
+        );
+        LAST_REQUEST_RETRIES.store(attempt - 1, Ordering::Relaxed);
+
+        match requester.request_once(source_types).await {
+            Err(e) if e.is_transient() => {
+                tracing::debug!("[Portal] Attempt {} failed: {}", attempt, e);
+                if attempt >= MAX_ATTEMPTS {
+                    return Err(PortalError::Timeout);
+                }
+                attempt += 1;
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+impl ScreencastRequester for PortalClient {
+    /// Execute one pass of the portal screencast flow.
+    async fn request_once(
+        &self,
+        source_types: BitFlags<SourceType>,
+    ) -> Result<ScreencastStream, PortalError> {
         tracing::debug!("[Portal] request_screencast_multi: connecting to portal...");
 
         // Get the screencast portal proxy
         let screencast = Screencast::new()
             .await
-            .map_err(|e| format!("Failed to connect to screencast portal: {}", e))?;
+            .map_err(|e| PortalError::Connection(e.to_string()))?;
 
         tracing::debug!("[Portal] Connected to screencast portal, creating session...");
 
@@ -187,7 +260,7 @@ impl PortalClient {
         let session = screencast
             .create_session()
             .await
-            .map_err(|e| format!("Failed to create portal session: {}", e))?;
+            .map_err(|e| portal_request_error("Failed to create portal session", e))?;
 
         tracing::debug!(
             "[Portal] Session created, selecting sources (types: {:?})...",
@@ -205,9 +278,11 @@ impl PortalClient {
                 PersistMode::DoNot, // don't persist for now
             )
             .await
-            .map_err(|e| format!("Failed to select sources: {}", e))?;
+            .map_err(|e| portal_request_error("Failed to select sources", e))?;
 
-        tracing::debug!("[Portal] Sources selected, starting screencast (picker should appear now)...");
+        tracing::debug!(
+            "[Portal] Sources selected, starting screencast (picker should appear now)..."
+        );
         tracing::debug!("[Portal] NOTE: On KDE, check if the dialog appeared behind other windows or in the system tray");
 
         // Start the screencast - picker will handle source selection
@@ -216,22 +291,20 @@ impl PortalClient {
         let response = screencast
             .start(&session, &WindowIdentifier::None)
             .await
-            .map_err(|e| format!("Failed to start screencast: {}", e))?;
+            .map_err(|e| portal_request_error("Failed to start screencast", e))?;
 
         tracing::debug!("[Portal] Screencast start returned, waiting for response...");
 
         // Wait for the response
         let streams = response
             .response()
-            .map_err(|e| format!("Portal request failed: {}", e))?;
+            .map_err(|e| portal_request_error("Portal request failed", e))?;
 
         // Get the first stream
         let all_streams = streams.streams();
         tracing::debug!("[Portal] Got {} streams from portal", all_streams.len());
 
-        let stream = all_streams
-            .first()
-            .ok_or_else(|| "No streams returned from portal".to_string())?;
+        let stream = all_streams.first().ok_or(PortalError::NoStreams)?;
 
         let node_id = stream.pipe_wire_node_id();
         let source_type = stream.source_type();
@@ -240,7 +313,10 @@ impl PortalClient {
 
         tracing::debug!(
             "[Portal] Stream info: node_id={}, source_type={:?}, size={:?}, position={:?}",
-            node_id, source_type, size, position
+            node_id,
+            source_type,
+            size,
+            position
         );
 
         Ok(ScreencastStream {
@@ -252,10 +328,92 @@ impl PortalClient {
     }
 }
 
+/// Convert an ashpd error from the given portal call into a `PortalError`,
+/// keeping the call description for non-cancellation failures.
+fn portal_request_error(context: &str, err: ashpd::Error) -> PortalError {
+    match PortalError::from(err) {
+        PortalError::RequestFailed(msg) => {
+            PortalError::RequestFailed(format!("{}: {}", context, msg))
+        }
+        PortalError::Busy(msg) => PortalError::Busy(format!("{}: {}", context, msg)),
+        other => other,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Portal stand-in that fails transiently a fixed number of times.
+    struct MockPortal {
+        failures: u32,
+        calls: AtomicU32,
+    }
+
+    impl MockPortal {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures,
+                calls: AtomicU32::new(0),
+            }
+        }
+    }
+
+    impl ScreencastRequester for MockPortal {
+        async fn request_once(
+            &self,
+            _source_types: BitFlags<SourceType>,
+        ) -> Result<ScreencastStream, PortalError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures {
+                return Err(PortalError::Busy("compositor busy".to_string()));
+            }
+            Ok(ScreencastStream {
+                node_id: 42,
+                source_type: Some(SourceType::Monitor),
+                size: Some((1920, 1080)),
+                position: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_succeeds_after_transient_failures() {
+        let portal = MockPortal::new(2);
+        let stream = request_with_retry(&portal, SourceType::Monitor.into(), Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(stream.node_id, 42);
+        assert_eq!(portal.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_with_timeout() {
+        let portal = MockPortal::new(u32::MAX);
+        let result = request_with_retry(&portal, SourceType::Monitor.into(), Duration::ZERO).await;
+        assert!(matches!(result, Err(PortalError::Timeout)));
+        assert_eq!(portal.calls.load(Ordering::SeqCst), MAX_ATTEMPTS);
+    }
+
+    #[tokio::test]
+    async fn test_non_transient_error_is_not_retried() {
+        struct Cancelling(AtomicU32);
+        impl ScreencastRequester for Cancelling {
+            async fn request_once(
+                &self,
+                _source_types: BitFlags<SourceType>,
+            ) -> Result<ScreencastStream, PortalError> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Err(PortalError::Cancelled)
+            }
+        }
+
+        let portal = Cancelling(AtomicU32::new(0));
+        let result = request_with_retry(&portal, SourceType::Monitor.into(), Duration::ZERO).await;
+        assert!(matches!(result, Err(PortalError::Cancelled)));
+        assert_eq!(portal.0.load(Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn test_source_type_as_str() {
         assert_eq!(CaptureSourceType::Monitor.as_str(), "monitor");
@@ -0,0 +1,94 @@
+//! Sleep and wake watcher using logind.
+//!
+//! systemd-logind emits `PrepareForSleep(true)` on the system bus before
+//! suspending or hibernating and `PrepareForSleep(false)` after resuming.
+//! The signal is read from `gdbus monitor`, which ships with GLib on every
+//! desktop that runs the portal.
+
+use crate::capture::power_watch::PowerEvent;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Start watching for sleep and wake, calling `on_event` for each one.
+pub fn start(on_event: fn(PowerEvent)) {
+    let spawned = thread::Builder::new()
+        .name("power-watch".to_string())
+        .spawn(move || {
+            if let Err(e) = watch_logind(on_event) {
+                tracing::warn!("[PowerWatch] logind monitor: {}", e);
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::warn!("[PowerWatch] Failed to start power watcher: {}", e);
+    }
+}
+
+fn watch_logind(on_event: fn(PowerEvent)) -> Result<(), String> {
+    let mut child = Command::new("gdbus")
+        .args([
+            "monitor",
+            "--system",
+            "--dest",
+            "org.freedesktop.login1",
+            "--object-path",
+            "/org/freedesktop/login1",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("could not start gdbus: {}", e))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "no gdbus output".to_string())?;
+    tracing::debug!("[PowerWatch] Watching logind for PrepareForSleep");
+
+    for line in BufReader::new(stdout).lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if let Some(event) = parse_prepare_for_sleep(&line) {
+            on_event(event);
+        }
+    }
+    let _ = child.wait();
+    Err("gdbus exited".to_string())
+}
+
+/// Parse a `gdbus monitor` line carrying logind's `PrepareForSleep` signal.
+fn parse_prepare_for_sleep(line: &str) -> Option<PowerEvent> {
+    let (_, args) = line.split_once("org.freedesktop.login1.Manager.PrepareForSleep")?;
+    match args.trim() {
+        "(true,)" => Some(PowerEvent::Sleep),
+        "(false,)" => Some(PowerEvent::Wake),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_prepare_for_sleep() {
+        assert_eq!(
+            parse_prepare_for_sleep(
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)"
+            ),
+            Some(PowerEvent::Sleep)
+        );
+        assert_eq!(
+            parse_prepare_for_sleep(
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (false,)"
+            ),
+            Some(PowerEvent::Wake)
+        );
+        assert_eq!(
+            parse_prepare_for_sleep(
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.SessionNew ('3', objectpath '/org/freedesktop/login1/session/_33')"
+            ),
+            None
+        );
+        assert_eq!(parse_prepare_for_sleep(""), None);
+    }
+}
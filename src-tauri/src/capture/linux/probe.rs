@@ -0,0 +1,80 @@
+//! Linux capture dependency probe.
+
+use super::{screencopy, LinuxBackend};
+use crate::capture::probe::BackendProbeResult;
+use std::ffi::CStr;
+use std::path::PathBuf;
+
+/// PipeWire client library loaded by the capture backend.
+const PIPEWIRE_LIBRARY: &CStr = c"libpipewire-0.3.so.0";
+
+/// Check PipeWire, Wayland and compositor protocol availability.
+pub fn probe_capture_backend() -> BackendProbeResult {
+    let mut result = BackendProbeResult::default();
+
+    if !library_available(PIPEWIRE_LIBRARY) {
+        result.error(format!(
+            "{} not found. Install PipeWire (pipewire package).",
+            PIPEWIRE_LIBRARY.to_string_lossy()
+        ));
+    }
+
+    match pipewire_socket() {
+        Some(socket) if socket.exists() => {}
+        Some(socket) => result.error(format!(
+            "PipeWire is not running (no socket at {}). Start the pipewire user service.",
+            socket.display()
+        )),
+        None => result.error("XDG_RUNTIME_DIR is not set; cannot locate the PipeWire socket"),
+    }
+
+    if std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        result.error("No Wayland session detected (WAYLAND_DISPLAY is not set)");
+    }
+
+    // Hyprland thumbnails and previews use wlr-screencopy; recording itself
+    // goes through the portal, so a missing protocol is only a warning.
+    if LinuxBackend::is_hyprland() {
+        if let Err(e) = screencopy::init() {
+            result.warn(format!("Window and display thumbnails unavailable: {}", e));
+        }
+    }
+
+    result
+}
+
+/// Whether a shared library can be loaded.
+fn library_available(name: &CStr) -> bool {
+    // SAFETY: `name` is a valid NUL-terminated string and the handle is
+    // closed immediately without resolving any symbols.
+    unsafe {
+        let handle = libc::dlopen(name.as_ptr(), libc::RTLD_LAZY | libc::RTLD_LOCAL);
+        if handle.is_null() {
+            return false;
+        }
+        libc::dlclose(handle);
+    }
+    true
+}
+
+/// Default PipeWire socket path for the current user.
+fn pipewire_socket() -> Option<PathBuf> {
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")?;
+    let name = std::env::var_os("PIPEWIRE_REMOTE").unwrap_or_else(|| "pipewire-0".into());
+    Some(PathBuf::from(runtime_dir).join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_library_is_reported_unavailable() {
+        assert!(!library_available(c"libomnirec-does-not-exist.so.0"));
+    }
+
+    #[test]
+    fn test_libc_is_available() {
+        assert!(library_available(c"libc.so.6"));
+    }
+}
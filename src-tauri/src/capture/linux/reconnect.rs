@@ -0,0 +1,239 @@
+//! PipeWire stream reconnection for display capture.
+//!
+//! Compositors may replace the PipeWire node behind a monitor screencast
+//! (e.g. on a mode change or output hot-plug), which ends the stream. The
+//! supervisor here sits between the PipeWire capture thread and the encoder:
+//! when the stream ends unexpectedly it feeds blank frames to the encoder,
+//! requests a new portal stream for the same monitor and resumes forwarding.
+//! After [`MAX_RECONNECT_ATTEMPTS`] consecutive failures the recording is
+//! stopped and a capture-disconnected event is broadcast.
+
+use super::ipc_server::IpcServerState;
+use super::{pipewire_capture, portal_client};
+use crate::capture::types::{self, CapturedFrame, FrameReceiver, PixelFormat, StopHandle};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc::{self, error::TryRecvError, error::TrySendError};
+use tokio::sync::RwLock;
+
+/// Delay before each reconnection attempt.
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Consecutive reconnection attempts before the recording is stopped.
+pub const MAX_RECONNECT_ATTEMPTS: u32 = 3;
+
+/// Poll interval while waiting for frames or for the reconnect delay.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Wrap a running display capture so that it reconnects when the stream ends.
+///
+/// Returns the frame receiver and stop handle the encoder should use in place
+/// of `capture`. `size` is the capture size used for blank frames until the
+/// first real frame arrives.
+pub fn supervise_display_capture(
+    monitor_id: String,
+    capture: (FrameReceiver, StopHandle),
+    size: (u32, u32),
+    ipc_state: Arc<RwLock<IpcServerState>>,
+    runtime: tokio::runtime::Handle,
+) -> (FrameReceiver, StopHandle) {
+    let (frame_tx, frame_rx) = mpsc::channel::<CapturedFrame>(2);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let stop_flag_clone = stop_flag.clone();
+
+    std::thread::spawn(move || {
+        let supervisor = Supervisor {
+            monitor_id,
+            size,
+            ipc_state,
+            runtime,
+            frame_tx,
+            stop_flag: stop_flag_clone,
+        };
+        supervisor.run(capture);
+        tracing::debug!("[Reconnect] Supervisor thread exited");
+    });
+
+    (frame_rx, stop_flag)
+}
+
+/// A black BGRA frame of the given size.
+pub fn blank_frame(width: u32, height: u32) -> CapturedFrame {
+    CapturedFrame {
+        width,
+        height,
+        data: [0, 0, 0, 255]
+            .repeat(width as usize * height as usize)
+            .into(),
+        pixel_format: PixelFormat::Bgra,
+        captured_at: SystemTime::now(),
+        timestamp_us: types::timestamp_us(Instant::now()),
+    }
+}
+
+struct Supervisor {
+    monitor_id: String,
+    /// Size of the most recent frame
+    size: (u32, u32),
+    ipc_state: Arc<RwLock<IpcServerState>>,
+    runtime: tokio::runtime::Handle,
+    frame_tx: mpsc::Sender<CapturedFrame>,
+    /// Set by the recording manager when the recording stops
+    stop_flag: StopHandle,
+}
+
+/// Why forwarding from the current stream ended.
+enum StreamEnd {
+    /// The recording was stopped or the encoder went away
+    Finished,
+    /// The PipeWire stream closed while the recording was still running
+    Lost,
+}
+
+impl Supervisor {
+    fn run(mut self, mut capture: (FrameReceiver, StopHandle)) {
+        loop {
+            match self.forward(&mut capture) {
+                StreamEnd::Finished => return,
+                StreamEnd::Lost => {}
+            }
+
+            tracing::warn!(
+                "[Reconnect] PipeWire stream for monitor {} ended, reconnecting",
+                self.monitor_id
+            );
+
+            // The encoder repeats the last frame it received, so a single
+            // blank frame covers the whole gap.
+            if !self.send(blank_frame(self.size.0, self.size.1)) {
+                return;
+            }
+
+            match self.reconnect() {
+                Some(new_capture) => capture = new_capture,
+                None => {
+                    if !self.stop_flag.load(Ordering::Relaxed) {
+                        self.give_up();
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Forward frames from `capture` until it ends or the recording stops.
+    fn forward(&mut self, capture: &mut (FrameReceiver, StopHandle)) -> StreamEnd {
+        let (frame_rx, capture_stop) = capture;
+        loop {
+            if self.stop_flag.load(Ordering::Relaxed) {
+                capture_stop.store(true, Ordering::Relaxed);
+                return StreamEnd::Finished;
+            }
+            match frame_rx.try_recv() {
+                Ok(frame) => {
+                    self.size = (frame.width, frame.height);
+                    if !self.send(frame) {
+                        capture_stop.store(true, Ordering::Relaxed);
+                        return StreamEnd::Finished;
+                    }
+                }
+                Err(TryRecvError::Empty) => std::thread::sleep(POLL_INTERVAL),
+                Err(TryRecvError::Disconnected) => {
+                    return if self.stop_flag.load(Ordering::Relaxed) {
+                        StreamEnd::Finished
+                    } else {
+                        StreamEnd::Lost
+                    };
+                }
+            }
+        }
+    }
+
+    /// Send a frame to the encoder, dropping it if the encoder is busy.
+    /// Returns false once the encoder has gone away.
+    fn send(&self, frame: CapturedFrame) -> bool {
+        !matches!(self.frame_tx.try_send(frame), Err(TrySendError::Closed(_)))
+    }
+
+    /// Try to open a new stream for the monitor, up to
+    /// [`MAX_RECONNECT_ATTEMPTS`] times.
+    fn reconnect(&self) -> Option<(FrameReceiver, StopHandle)> {
+        for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+            if !self.wait(RECONNECT_DELAY) {
+                return None;
+            }
+            match self.open_stream() {
+                Ok(capture) => {
+                    tracing::info!(
+                        "[Reconnect] Reconnected to monitor {} (attempt {})",
+                        self.monitor_id,
+                        attempt
+                    );
+                    return Some(capture);
+                }
+                Err(e) => tracing::warn!(
+                    "[Reconnect] Attempt {}/{} for monitor {} failed: {}",
+                    attempt,
+                    MAX_RECONNECT_ATTEMPTS,
+                    self.monitor_id,
+                    e
+                ),
+            }
+        }
+        None
+    }
+
+    /// Sleep for `delay`. Returns false if the recording stopped meanwhile.
+    fn wait(&self, delay: Duration) -> bool {
+        let deadline = Instant::now() + delay;
+        while Instant::now() < deadline {
+            if self.stop_flag.load(Ordering::Relaxed) || self.frame_tx.is_closed() {
+                return false;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        true
+    }
+
+    fn open_stream(&self) -> Result<(FrameReceiver, StopHandle), String> {
+        let portal_client = portal_client::PortalClient::new(self.ipc_state.clone());
+        let stream = self
+            .runtime
+            .block_on(portal_client.request_monitor_capture(&self.monitor_id))
+            .map_err(|e| e.to_string())?;
+
+        let (width, height) = stream
+            .size
+            .map(|(w, h)| (w as u32, h as u32))
+            .unwrap_or(self.size);
+        pipewire_capture::start_pipewire_capture(stream.node_id, width, height)
+    }
+
+    /// Stop the recording after the last reconnection attempt failed.
+    fn give_up(&self) {
+        let reason = format!(
+            "Lost capture of monitor {} and could not reconnect after {} attempts",
+            self.monitor_id, MAX_RECONNECT_ATTEMPTS
+        );
+        tracing::error!("[Reconnect] {}", reason);
+        self.runtime.spawn(async move {
+            crate::state::get_recording_manager()
+                .handle_capture_disconnected(reason)
+                .await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blank_frame_is_opaque_black() {
+        let frame = blank_frame(4, 2);
+        assert_eq!((frame.width, frame.height), (4, 2));
+        assert_eq!(frame.data.len(), 4 * 2 * 4);
+        assert!(frame.data.chunks(4).all(|px| px == [0, 0, 0, 255]));
+    }
+}
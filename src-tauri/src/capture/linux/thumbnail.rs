@@ -6,15 +6,19 @@
 
 use crate::capture::error::CaptureError;
 use crate::capture::thumbnail::{
-    bgra_to_jpeg_thumbnail, PREVIEW_MAX_HEIGHT, PREVIEW_MAX_WIDTH, THUMBNAIL_MAX_HEIGHT,
-    THUMBNAIL_MAX_WIDTH,
+    bgra_to_jpeg_thumbnail, decode_png_icon, icon_to_jpeg_thumbnail, ThumbnailOptions,
+    PREVIEW_MAX_HEIGHT, PREVIEW_MAX_WIDTH,
 };
-use crate::capture::{ThumbnailCapture, ThumbnailResult};
+use crate::capture::{Screenshot, ThumbnailCapture, ThumbnailResult};
 
 use super::screencopy;
 
 use hyprland::data::{Clients, Monitors};
 use hyprland::shared::HyprData;
+use std::path::{Path, PathBuf};
+
+/// `hicolor` icon theme sizes searched for application icons, best first.
+const ICON_THEME_SIZES: &[&str] = &["128x128", "256x256", "96x96", "64x64", "48x48", "512x512"];
 
 /// Crop a BGRA frame to a specified region.
 fn crop_frame(
@@ -85,6 +89,127 @@ fn get_monitor_info(monitor_name: &str) -> Option<(i32, i32, u32, u32, f64)> {
         .map(|m| (m.x, m.y, m.width as u32, m.height as u32, m.scale as f64))
 }
 
+/// Application icon for a window class, shown when the window can't be captured.
+///
+/// The icon name comes from the application's `.desktop` entry (matched by
+/// file name or `StartupWMClass`) and is resolved in the `hicolor` fallback
+/// theme and `pixmaps`, per the freedesktop icon theme specification. Only
+/// PNG icons are supported.
+pub fn get_fallback_icon(process_name: &str) -> Option<ThumbnailResult> {
+    let data_dirs = xdg_data_dirs();
+    let icon_name = find_desktop_entry_icon(process_name, &data_dirs)
+        .unwrap_or_else(|| process_name.to_lowercase());
+    let path = resolve_icon_path(&icon_name, &data_dirs)?;
+
+    let bytes = std::fs::read(&path).ok()?;
+    let (data, width, height) = decode_png_icon(&bytes)
+        .map_err(|e| tracing::debug!("Icon {}: {}", path.display(), e))
+        .ok()?;
+    let (data, width, height) = icon_to_jpeg_thumbnail(&data, width, height).ok()?;
+    Some(ThumbnailResult {
+        data,
+        width,
+        height,
+    })
+}
+
+/// `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`, with the spec defaults.
+fn xdg_data_dirs() -> Vec<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    data_home
+        .into_iter()
+        .chain(data_dirs.split(':').map(PathBuf::from))
+        .collect()
+}
+
+/// Icon name from the first `.desktop` entry belonging to `class`.
+fn find_desktop_entry_icon(class: &str, data_dirs: &[PathBuf]) -> Option<String> {
+    data_dirs
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir.join("applications")).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "desktop"))
+        .find_map(|path| {
+            let contents = std::fs::read_to_string(&path).ok()?;
+            let entry = parse_desktop_entry(&contents);
+            let stem = path.file_stem()?.to_string_lossy();
+            if desktop_file_matches(&stem, entry.startup_wm_class.as_deref(), class) {
+                entry.icon
+            } else {
+                None
+            }
+        })
+}
+
+/// Keys of a `.desktop` file's `[Desktop Entry]` group used for icon lookup.
+#[derive(Debug, Default, PartialEq)]
+struct DesktopEntry {
+    icon: Option<String>,
+    startup_wm_class: Option<String>,
+}
+
+fn parse_desktop_entry(contents: &str) -> DesktopEntry {
+    let mut entry = DesktopEntry::default();
+    let mut in_main_group = false;
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_main_group = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_main_group {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            match key.trim() {
+                "Icon" => entry.icon = Some(value.trim().to_string()),
+                "StartupWMClass" => entry.startup_wm_class = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+    entry
+}
+
+/// Whether a `.desktop` file describes the application with window `class`.
+///
+/// Reverse-DNS file names such as `org.mozilla.firefox` match on their last
+/// component.
+fn desktop_file_matches(stem: &str, startup_wm_class: Option<&str>, class: &str) -> bool {
+    let last_component = stem.rsplit('.').next().unwrap_or(stem);
+    stem.eq_ignore_ascii_case(class)
+        || last_component.eq_ignore_ascii_case(class)
+        || startup_wm_class.is_some_and(|wm_class| wm_class.eq_ignore_ascii_case(class))
+}
+
+/// Path of a PNG icon named `icon` (or an absolute icon path).
+fn resolve_icon_path(icon: &str, data_dirs: &[PathBuf]) -> Option<PathBuf> {
+    let is_png = |path: &Path| path.extension().is_some_and(|ext| ext == "png");
+    let direct = Path::new(icon);
+    if direct.is_absolute() {
+        return (is_png(direct) && direct.is_file()).then(|| direct.to_path_buf());
+    }
+
+    let file_name = format!("{}.png", icon);
+    data_dirs
+        .iter()
+        .flat_map(|dir| {
+            ICON_THEME_SIZES
+                .iter()
+                .map(move |size| dir.join("icons/hicolor").join(size).join("apps"))
+                .chain(std::iter::once(dir.join("pixmaps")))
+        })
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.is_file())
+}
+
 /// Linux thumbnail capture implementation using wlr-screencopy.
 pub struct LinuxThumbnailCapture;
 
@@ -93,19 +218,35 @@ impl LinuxThumbnailCapture {
     pub fn new() -> Self {
         Self
     }
-}
-
-impl Default for LinuxThumbnailCapture {
-    fn default() -> Self {
-        Self::new()
-    }
-}
 
-impl ThumbnailCapture for LinuxThumbnailCapture {
-    fn capture_window_thumbnail(
+    /// Capture a window and scale it to a thumbnail.
+    fn capture_window_image(
         &self,
         window_handle: isize,
+        options: ThumbnailOptions,
     ) -> Result<ThumbnailResult, CaptureError> {
+        let image = self.capture_window_pixels(window_handle)?;
+
+        // Convert to thumbnail
+        let (base64_data, thumb_width, thumb_height) = bgra_to_jpeg_thumbnail(
+            &image.data,
+            image.width,
+            image.height,
+            options.max_width,
+            options.max_height,
+            options.quality,
+        )
+        .map_err(CaptureError::PlatformError)?;
+
+        Ok(ThumbnailResult {
+            data: base64_data,
+            width: thumb_width,
+            height: thumb_height,
+        })
+    }
+
+    /// Capture a window at full resolution by cropping its monitor's output.
+    fn capture_window_pixels(&self, window_handle: isize) -> Result<Screenshot, CaptureError> {
         // Get window info from Hyprland
         let clients = Clients::get().map_err(|e| {
             CaptureError::PlatformError(format!("Failed to get Hyprland clients: {}", e))
@@ -174,24 +315,46 @@ impl ThumbnailCapture for LinuxThumbnailCapture {
             ));
         }
 
-        // Convert to thumbnail
-        let (base64_data, thumb_width, thumb_height) = bgra_to_jpeg_thumbnail(
-            &cropped,
-            crop_width,
-            crop_height,
-            THUMBNAIL_MAX_WIDTH,
-            THUMBNAIL_MAX_HEIGHT,
-        )
-        .map_err(CaptureError::PlatformError)?;
-
-        Ok(ThumbnailResult {
-            data: base64_data,
-            width: thumb_width,
-            height: thumb_height,
+        Ok(Screenshot {
+            data: cropped,
+            width: crop_width,
+            height: crop_height,
         })
     }
+}
+
+impl Default for LinuxThumbnailCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    fn capture_display_thumbnail(&self, monitor_id: &str) -> Result<ThumbnailResult, CaptureError> {
+impl ThumbnailCapture for LinuxThumbnailCapture {
+    fn capture_window_thumbnail(
+        &self,
+        window_handle: isize,
+        options: ThumbnailOptions,
+    ) -> Result<ThumbnailResult, CaptureError> {
+        match self.capture_window_image(window_handle, options) {
+            Ok(result) => Ok(result),
+            Err(e) => crate::capture::window_process_name(window_handle)
+                .and_then(|process_name| get_fallback_icon(&process_name))
+                .inspect(|_| {
+                    tracing::debug!(
+                        "Window {} thumbnail failed ({}), using application icon",
+                        window_handle,
+                        e
+                    )
+                })
+                .ok_or(e),
+        }
+    }
+
+    fn capture_display_thumbnail(
+        &self,
+        monitor_id: &str,
+        options: ThumbnailOptions,
+    ) -> Result<ThumbnailResult, CaptureError> {
         // Capture the output directly via screencopy
         let frame = screencopy::capture_output(monitor_id).map_err(CaptureError::PlatformError)?;
 
@@ -200,8 +363,9 @@ impl ThumbnailCapture for LinuxThumbnailCapture {
             &frame.data,
             frame.width,
             frame.height,
-            THUMBNAIL_MAX_WIDTH,
-            THUMBNAIL_MAX_HEIGHT,
+            options.max_width,
+            options.max_height,
+            options.quality,
         )
         .map_err(CaptureError::PlatformError)?;
 
@@ -219,6 +383,7 @@ impl ThumbnailCapture for LinuxThumbnailCapture {
         y: i32,
         width: u32,
         height: u32,
+        quality: u8,
     ) -> Result<ThumbnailResult, CaptureError> {
         // Validate region
         if width < 100 || height < 100 {
@@ -268,6 +433,7 @@ impl ThumbnailCapture for LinuxThumbnailCapture {
             crop_height,
             PREVIEW_MAX_WIDTH,
             PREVIEW_MAX_HEIGHT,
+            quality,
         )
         .map_err(CaptureError::PlatformError)?;
 
@@ -277,4 +443,70 @@ impl ThumbnailCapture for LinuxThumbnailCapture {
             height: preview_height,
         })
     }
+
+    fn capture_window_screenshot(&self, window_handle: isize) -> Result<Screenshot, CaptureError> {
+        self.capture_window_pixels(window_handle)
+    }
+
+    fn capture_display_screenshot(&self, monitor_id: &str) -> Result<Screenshot, CaptureError> {
+        let frame = screencopy::capture_output(monitor_id).map_err(CaptureError::PlatformError)?;
+        Ok(Screenshot {
+            data: frame.data,
+            width: frame.width,
+            height: frame.height,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_desktop_entry() {
+        let contents = "[Desktop Entry]\nName=Firefox\nIcon=firefox\nStartupWMClass=firefox\n\n[Desktop Action new-window]\nIcon=other\n";
+        assert_eq!(
+            parse_desktop_entry(contents),
+            DesktopEntry {
+                icon: Some("firefox".to_string()),
+                startup_wm_class: Some("firefox".to_string()),
+            }
+        );
+        assert_eq!(parse_desktop_entry("Icon=orphan"), DesktopEntry::default());
+    }
+
+    #[test]
+    fn test_desktop_file_matches() {
+        assert!(desktop_file_matches("kitty", None, "kitty"));
+        assert!(desktop_file_matches("org.mozilla.firefox", None, "firefox"));
+        assert!(desktop_file_matches("code", Some("Code"), "code"));
+        assert!(desktop_file_matches(
+            "visual-studio-code",
+            Some("Code"),
+            "code"
+        ));
+        assert!(!desktop_file_matches("kitty", None, "alacritty"));
+    }
+
+    #[test]
+    fn test_resolve_icon_path() {
+        let dir = std::env::temp_dir().join(format!("omnirec-icons-{}", std::process::id()));
+        let apps = dir.join("icons/hicolor/256x256/apps");
+        std::fs::create_dir_all(&apps).unwrap();
+        std::fs::write(apps.join("editor.png"), b"png").unwrap();
+        std::fs::write(apps.join("vector.svg"), b"svg").unwrap();
+
+        let data_dirs = vec![dir.clone()];
+        assert_eq!(
+            resolve_icon_path("editor", &data_dirs),
+            Some(apps.join("editor.png"))
+        );
+        assert_eq!(resolve_icon_path("vector", &data_dirs), None);
+        assert_eq!(
+            resolve_icon_path(apps.join("editor.png").to_str().unwrap(), &data_dirs),
+            Some(apps.join("editor.png"))
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,12 +1,17 @@
-//! macOS audio device enumeration.
+//! macOS audio device enumeration and monitoring.
 //!
-//! This module provides audio device enumeration using Core Audio APIs.
+//! This module provides audio device enumeration using Core Audio APIs, and
+//! an audio monitor that plays captured audio back while recording.
 //!
 //! ## Architecture
 //!
 //! Audio enumeration uses Core Audio's AudioHardware APIs to list all audio
 //! devices on the system, filtering by input (microphones) and output (speakers)
 //! capabilities.
+//!
+//! The monitor is a passthrough tap on the vtx-engine event stream: captured
+//! samples are queued and pulled by the render callback of an `AUGraph`
+//! wrapping the default output unit.
 
 use crate::capture::error::EnumerationError;
 use crate::capture::{AudioSource, AudioSourceType};
@@ -15,12 +20,26 @@ use core_foundation::base::TCFType;
 use core_foundation::string::CFString;
 use coreaudio_sys::{
     kAudioDevicePropertyDeviceNameCFString, kAudioDevicePropertyScopeInput,
-    kAudioDevicePropertyScopeOutput, kAudioDevicePropertyStreams, kAudioHardwarePropertyDevices,
+    kAudioDevicePropertyScopeOutput, kAudioDevicePropertyStreams, kAudioFormatFlagIsFloat,
+    kAudioFormatFlagIsPacked, kAudioFormatLinearPCM, kAudioHardwarePropertyDevices,
     kAudioObjectPropertyElementMain, kAudioObjectPropertyScopeGlobal, kAudioObjectSystemObject,
-    AudioDeviceID, AudioObjectGetPropertyData, AudioObjectGetPropertyDataSize,
-    AudioObjectPropertyAddress, OSStatus,
+    kAudioUnitManufacturer_Apple, kAudioUnitProperty_StreamFormat, kAudioUnitScope_Input,
+    kAudioUnitSubType_DefaultOutput, kAudioUnitType_Output, AUGraph, AUGraphAddNode, AUGraphClose,
+    AUGraphInitialize, AUGraphNodeInfo, AUGraphOpen, AUGraphSetNodeInputCallback, AUGraphStart,
+    AUGraphStop, AUGraphUninitialize, AUNode, AURenderCallbackStruct, AudioBufferList,
+    AudioComponentDescription, AudioDeviceID, AudioObjectGetPropertyData,
+    AudioObjectGetPropertyDataSize, AudioObjectPropertyAddress, AudioStreamBasicDescription,
+    AudioTimeStamp, AudioUnit, AudioUnitRenderActionFlags, AudioUnitSetProperty, DisposeAUGraph,
+    NewAUGraph, OSStatus,
 };
+use std::collections::VecDeque;
 use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::TryRecvError};
+use vtx_engine::EngineEvent;
 
 /// Check if running on macOS 13 (Ventura) or later.
 ///
@@ -264,6 +283,280 @@ pub fn is_system_audio_available() -> bool {
     is_macos_13_or_later()
 }
 
+// =============================================================================
+// Audio Monitoring
+// =============================================================================
+
+/// Maximum audio queued for playback, in seconds. Older samples are dropped
+/// so the monitor never drifts behind the capture.
+const MONITOR_MAX_LATENCY_SECS: u32 = 1;
+
+/// Output channels; the mono capture is duplicated to both.
+const MONITOR_CHANNELS: u32 = 2;
+
+/// How often the tap thread checks for audio and the stop flag.
+const MONITOR_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Clamp a monitor volume to the 0.0–1.0 range.
+pub fn clamp_monitor_volume(volume: f32) -> f32 {
+    if volume.is_nan() {
+        0.0
+    } else {
+        volume.clamp(0.0, 1.0)
+    }
+}
+
+/// Plays captured audio through the default output device while recording.
+///
+/// The tap runs on its own thread and creates the output graph when the first
+/// samples arrive (the sample rate is only known then). Dropping the monitor
+/// stops playback before returning.
+pub struct AudioMonitor {
+    stop_flag: Arc<AtomicBool>,
+    /// Volume as `f32` bits, shared with the render callback
+    volume: Arc<AtomicU32>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AudioMonitor {
+    /// Start monitoring the `RawAudioData` events from `audio_rx`.
+    pub fn start(audio_rx: broadcast::Receiver<EngineEvent>, volume: f32) -> Self {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let volume = Arc::new(AtomicU32::new(clamp_monitor_volume(volume).to_bits()));
+
+        let thread = {
+            let stop_flag = stop_flag.clone();
+            let volume = volume.clone();
+            std::thread::spawn(move || run_monitor_tap(audio_rx, stop_flag, volume))
+        };
+
+        tracing::info!("[Audio] Monitor started");
+        Self {
+            stop_flag,
+            volume,
+            thread: Some(thread),
+        }
+    }
+
+    /// Change the playback volume (0.0–1.0) without restarting playback.
+    pub fn set_volume(&self, volume: f32) {
+        self.volume
+            .store(clamp_monitor_volume(volume).to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl Drop for AudioMonitor {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        tracing::info!("[Audio] Monitor stopped");
+    }
+}
+
+fn run_monitor_tap(
+    mut audio_rx: broadcast::Receiver<EngineEvent>,
+    stop_flag: Arc<AtomicBool>,
+    volume: Arc<AtomicU32>,
+) {
+    let mut graph: Option<MonitorGraph> = None;
+
+    while !stop_flag.load(Ordering::Relaxed) {
+        match audio_rx.try_recv() {
+            Ok(EngineEvent::RawAudioData(data)) => {
+                if graph.as_ref().map(|g| g.sample_rate) != Some(data.sample_rate) {
+                    // Stop the old graph before opening one at the new rate
+                    graph = None;
+                    match MonitorGraph::start(data.sample_rate, volume.clone()) {
+                        Ok(started) => graph = Some(started),
+                        Err(e) => {
+                            tracing::warn!("[Audio] Failed to start monitor playback: {}", e);
+                            return;
+                        }
+                    }
+                }
+                if let Some(graph) = &graph {
+                    graph.push(&data.samples);
+                }
+            }
+            Ok(_) => {}
+            Err(TryRecvError::Empty) => std::thread::sleep(MONITOR_POLL_INTERVAL),
+            Err(TryRecvError::Lagged(skipped)) => {
+                tracing::debug!("[Audio] Monitor skipped {} audio events", skipped);
+            }
+            Err(TryRecvError::Closed) => break,
+        }
+    }
+}
+
+/// Samples waiting for playback, shared with the render callback.
+struct MonitorQueue {
+    samples: Mutex<VecDeque<f32>>,
+    max_len: usize,
+    volume: Arc<AtomicU32>,
+}
+
+/// An `AUGraph` with a single default output node fed by [`render_monitor`].
+struct MonitorGraph {
+    graph: AUGraph,
+    sample_rate: u32,
+    /// Referenced by the render callback; must outlive the graph
+    queue: Arc<MonitorQueue>,
+}
+
+impl MonitorGraph {
+    fn start(sample_rate: u32, volume: Arc<AtomicU32>) -> Result<Self, String> {
+        let queue = Arc::new(MonitorQueue {
+            samples: Mutex::new(VecDeque::new()),
+            max_len: (sample_rate * MONITOR_MAX_LATENCY_SECS) as usize,
+            volume,
+        });
+
+        let mut graph: AUGraph = std::ptr::null_mut();
+        check(unsafe { NewAUGraph(&mut graph) }, "NewAUGraph")?;
+        // From here on Drop disposes the graph on any error
+        let monitor = Self {
+            graph,
+            sample_rate,
+            queue,
+        };
+
+        let description = AudioComponentDescription {
+            componentType: kAudioUnitType_Output,
+            componentSubType: kAudioUnitSubType_DefaultOutput,
+            componentManufacturer: kAudioUnitManufacturer_Apple,
+            componentFlags: 0,
+            componentFlagsMask: 0,
+        };
+        let mut node: AUNode = 0;
+        check(
+            unsafe { AUGraphAddNode(graph, &description, &mut node) },
+            "AUGraphAddNode",
+        )?;
+        check(unsafe { AUGraphOpen(graph) }, "AUGraphOpen")?;
+
+        let mut unit: AudioUnit = std::ptr::null_mut();
+        check(
+            unsafe { AUGraphNodeInfo(graph, node, std::ptr::null_mut(), &mut unit) },
+            "AUGraphNodeInfo",
+        )?;
+
+        let bytes_per_frame = MONITOR_CHANNELS * std::mem::size_of::<f32>() as u32;
+        let format = AudioStreamBasicDescription {
+            mSampleRate: sample_rate as f64,
+            mFormatID: kAudioFormatLinearPCM,
+            mFormatFlags: kAudioFormatFlagIsFloat | kAudioFormatFlagIsPacked,
+            mBytesPerPacket: bytes_per_frame,
+            mFramesPerPacket: 1,
+            mBytesPerFrame: bytes_per_frame,
+            mChannelsPerFrame: MONITOR_CHANNELS,
+            mBitsPerChannel: 32,
+            mReserved: 0,
+        };
+        check(
+            unsafe {
+                AudioUnitSetProperty(
+                    unit,
+                    kAudioUnitProperty_StreamFormat,
+                    kAudioUnitScope_Input,
+                    0,
+                    &format as *const _ as *const c_void,
+                    std::mem::size_of::<AudioStreamBasicDescription>() as u32,
+                )
+            },
+            "AudioUnitSetProperty(StreamFormat)",
+        )?;
+
+        let callback = AURenderCallbackStruct {
+            inputProc: Some(render_monitor),
+            inputProcRefCon: Arc::as_ptr(&monitor.queue) as *mut c_void,
+        };
+        check(
+            unsafe { AUGraphSetNodeInputCallback(graph, node, 0, &callback) },
+            "AUGraphSetNodeInputCallback",
+        )?;
+
+        check(unsafe { AUGraphInitialize(graph) }, "AUGraphInitialize")?;
+        check(unsafe { AUGraphStart(graph) }, "AUGraphStart")?;
+
+        tracing::debug!("[Audio] Monitor playback started at {} Hz", sample_rate);
+        Ok(monitor)
+    }
+
+    fn push(&self, samples: &[f32]) {
+        if let Ok(mut queue) = self.queue.samples.lock() {
+            queue.extend(samples);
+            let excess = queue.len().saturating_sub(self.queue.max_len);
+            queue.drain(..excess);
+        }
+    }
+}
+
+impl Drop for MonitorGraph {
+    fn drop(&mut self) {
+        // AUGraphStop blocks until the render callback has returned, so the
+        // queue can be released afterwards.
+        unsafe {
+            AUGraphStop(self.graph);
+            AUGraphUninitialize(self.graph);
+            AUGraphClose(self.graph);
+            DisposeAUGraph(self.graph);
+        }
+    }
+}
+
+fn check(status: OSStatus, call: &str) -> Result<(), String> {
+    if status == 0 {
+        Ok(())
+    } else {
+        Err(format!("{} failed: OSStatus {}", call, status))
+    }
+}
+
+/// Render callback of the monitor's output unit.
+extern "C" fn render_monitor(
+    in_ref_con: *mut c_void,
+    _io_action_flags: *mut AudioUnitRenderActionFlags,
+    _in_time_stamp: *const AudioTimeStamp,
+    _in_bus_number: u32,
+    _in_number_frames: u32,
+    io_data: *mut AudioBufferList,
+) -> OSStatus {
+    // SAFETY: the ref-con is the graph's `MonitorQueue`, which outlives the
+    // graph, and `io_data` holds one interleaved f32 buffer as configured.
+    unsafe {
+        let queue = &*(in_ref_con as *const MonitorQueue);
+        let buffer = &mut (*io_data).mBuffers[0];
+        if buffer.mData.is_null() {
+            return 0;
+        }
+        let out = std::slice::from_raw_parts_mut(
+            buffer.mData as *mut f32,
+            buffer.mDataByteSize as usize / std::mem::size_of::<f32>(),
+        );
+
+        // Never block the real-time thread; play silence if the tap holds the lock
+        match queue.samples.try_lock() {
+            Ok(mut samples) => {
+                let volume = f32::from_bits(queue.volume.load(Ordering::Relaxed));
+                fill_interleaved(&mut samples, out, MONITOR_CHANNELS as usize, volume);
+            }
+            Err(_) => out.fill(0.0),
+        }
+    }
+    0
+}
+
+/// Fill interleaved `out` from mono `samples` at `volume`, padding with
+/// silence when the queue runs dry.
+fn fill_interleaved(samples: &mut VecDeque<f32>, out: &mut [f32], channels: usize, volume: f32) {
+    for frame in out.chunks_mut(channels) {
+        let sample = samples.pop_front().unwrap_or(0.0) * volume;
+        frame.fill(sample);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -296,6 +589,23 @@ mod tests {
         let _ = is_macos_13_or_later();
     }
 
+    #[test]
+    fn test_clamp_monitor_volume() {
+        assert_eq!(clamp_monitor_volume(0.5), 0.5);
+        assert_eq!(clamp_monitor_volume(-1.0), 0.0);
+        assert_eq!(clamp_monitor_volume(2.0), 1.0);
+        assert_eq!(clamp_monitor_volume(f32::NAN), 0.0);
+    }
+
+    #[test]
+    fn test_fill_interleaved_duplicates_and_pads() {
+        let mut samples: VecDeque<f32> = [0.5, -1.0].into_iter().collect();
+        let mut out = [1.0; 6];
+        fill_interleaved(&mut samples, &mut out, 2, 0.5);
+        assert_eq!(out, [0.25, 0.25, -0.5, -0.5, 0.0, 0.0]);
+        assert!(samples.is_empty());
+    }
+
     #[test]
     fn test_system_audio_available_check() {
         // Should return a boolean without crashing
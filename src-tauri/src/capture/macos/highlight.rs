@@ -1,43 +1,59 @@
 //! macOS visual highlight for capture target preview.
 //!
 //! Shows a temporary border around the selected capture target using NSWindow.
+//! The border is drawn by the content view's CALayer in the configured
+//! [`HighlightStyle`]; animations use `CABasicAnimation`.
 
+use super::monitor_list::list_monitors;
+use crate::capture::highlight_style::{DASH_LENGTH, DASH_SPEED, PULSE_PERIOD_MS};
+use crate::capture::{HighlightAnimation, HighlightStyle};
 use core_foundation::base::TCFType;
 use core_graphics::base::CGFloat;
+use core_graphics::path::CGPath;
+use foreign_types::ForeignType;
 use objc2::msg_send;
 use objc2::rc::Retained;
-use objc2::runtime::AnyObject;
+use objc2::runtime::{AnyClass, AnyObject};
 use objc2::MainThreadMarker;
 use objc2::MainThreadOnly;
 use objc2_app_kit::{NSBackingStoreType, NSColor, NSScreen, NSView, NSWindow, NSWindowStyleMask};
-use objc2_foundation::{NSArray, NSPoint, NSRect, NSSize};
+use objc2_foundation::{NSArray, NSNumber, NSPoint, NSRect, NSSize, NSString};
+use omnirec_types::{split_highlight_region, HighlightRegion};
 use std::ffi::c_void;
 use std::sync::Mutex;
 use std::thread;
 use std::time::Duration;
 
-const BORDER_WIDTH: CGFloat = 6.0;
 const HIGHLIGHT_DURATION_MS: u64 = 800;
 
-// Store the current highlight window pointer to manage its lifecycle safely
+// Store the current highlight window pointers to manage their lifecycle safely
 // We store as usize to avoid Send/Sync issues with raw pointers
-// The window is only ever accessed from the main thread
-static CURRENT_HIGHLIGHT: Mutex<Option<usize>> = Mutex::new(None);
+// The windows are only ever accessed from the main thread
+static CURRENT_HIGHLIGHTS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
 
 /// Show a highlight border around the specified area.
 ///
 /// The highlight is non-interactive and auto-dismisses after a short duration.
+/// A region spanning several displays gets one window per display.
 /// This function dispatches to the main thread and returns immediately.
-pub fn show_highlight(x: i32, y: i32, width: i32, height: i32) {
+pub fn show_highlight(x: i32, y: i32, width: i32, height: i32, style: HighlightStyle) {
+    let region = HighlightRegion {
+        x,
+        y,
+        width: width.max(0) as u32,
+        height: height.max(0) as u32,
+    };
+    let parts = split_highlight_region(&region, &list_monitors());
+
     // Dispatch directly to main thread
     let queue = dispatch::Queue::main();
     queue.exec_async(move || {
-        create_highlight_window(x, y, width, height);
+        show_highlight_windows(&parts, style);
     });
 }
 
-/// Create and show the highlight window on the main thread
-fn create_highlight_window(x: i32, y: i32, width: i32, height: i32) {
+/// Create and show one highlight window per region on the main thread
+fn show_highlight_windows(parts: &[HighlightRegion], style: HighlightStyle) {
     // Get main thread marker - we should be on main thread now
     let mtm = match MainThreadMarker::new() {
         Some(m) => m,
@@ -47,19 +63,48 @@ fn create_highlight_window(x: i32, y: i32, width: i32, height: i32) {
         }
     };
 
-    // Close any existing highlight window first
-    {
-        let mut current = CURRENT_HIGHLIGHT.lock().unwrap();
-        if let Some(old_ptr) = current.take() {
-            // Reconstruct the Retained to properly close and release the old window
-            let old_window: Option<Retained<NSWindow>> =
-                unsafe { Retained::from_raw(old_ptr as *mut NSWindow) };
-            if let Some(w) = old_window {
-                w.orderOut(None);
-                // w is dropped here, releasing the window
-            }
+    // Close any existing highlight windows first
+    close_highlight_windows();
+
+    for part in parts {
+        let window = create_highlight_window(mtm, part, &style);
+
+        // Store the window pointer in our static so it stays alive
+        // Convert to usize to make it Send-safe
+        let window_ptr = Retained::into_raw(window) as usize;
+        CURRENT_HIGHLIGHTS.lock().unwrap().push(window_ptr);
+    }
+
+    // Schedule windows to close after duration
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(HIGHLIGHT_DURATION_MS));
+        let queue = dispatch::Queue::main();
+        queue.exec_async(close_highlight_windows);
+    });
+}
+
+/// Close and release all highlight windows (main thread only)
+fn close_highlight_windows() {
+    let mut current = CURRENT_HIGHLIGHTS.lock().unwrap();
+    for ptr in current.drain(..) {
+        // Reconstruct the Retained to properly close and release the window
+        let window: Option<Retained<NSWindow>> =
+            unsafe { Retained::from_raw(ptr as *mut NSWindow) };
+        if let Some(w) = window {
+            w.orderOut(None);
+            // w is dropped here, releasing the window
         }
     }
+}
+
+/// Create and show a single highlight window
+fn create_highlight_window(
+    mtm: MainThreadMarker,
+    region: &HighlightRegion,
+    style: &HighlightStyle,
+) -> Retained<NSWindow> {
+    let (x, y) = (region.x, region.y);
+    let (width, height) = (region.width as f64, region.height as f64);
 
     // macOS coordinate system has origin at bottom-left, but we receive top-left coords
     // We need to flip the y coordinate using main screen height
@@ -74,21 +119,21 @@ fn create_highlight_window(x: i32, y: i32, width: i32, height: i32) {
     };
 
     // Flip Y coordinate (convert from top-left to bottom-left origin)
-    let flipped_y = screen_height - (y as f64) - (height as f64);
+    let flipped_y = screen_height - (y as f64) - height;
 
     let frame = NSRect::new(
         NSPoint::new(x as f64, flipped_y),
-        NSSize::new(width as f64, height as f64),
+        NSSize::new(width, height),
     );
 
     // Create a borderless, transparent window
-    let style = NSWindowStyleMask::Borderless;
+    let window_style = NSWindowStyleMask::Borderless;
 
     let window = unsafe {
         NSWindow::initWithContentRect_styleMask_backing_defer(
             NSWindow::alloc(mtm),
             frame,
-            style,
+            window_style,
             NSBackingStoreType::Buffered,
             false,
         )
@@ -106,42 +151,22 @@ fn create_highlight_window(x: i32, y: i32, width: i32, height: i32) {
     window.setIgnoresMouseEvents(true);
 
     // Create a custom view that draws the border
-    let content_view = create_border_view(mtm, width as CGFloat, height as CGFloat);
+    let content_view = create_border_view(mtm, width as CGFloat, height as CGFloat, style);
     window.setContentView(Some(&content_view));
 
     // Show the window
     window.orderFrontRegardless();
 
-    // Store the window pointer in our static so it stays alive
-    // Convert to usize to make it Send-safe
-    let window_ptr = Retained::into_raw(window) as usize;
-    {
-        let mut current = CURRENT_HIGHLIGHT.lock().unwrap();
-        *current = Some(window_ptr);
-    }
-
-    // Schedule window to close after duration
-    thread::spawn(move || {
-        thread::sleep(Duration::from_millis(HIGHLIGHT_DURATION_MS));
-        let queue = dispatch::Queue::main();
-        queue.exec_async(move || {
-            // Close the window on main thread
-            let mut current = CURRENT_HIGHLIGHT.lock().unwrap();
-            if let Some(ptr) = current.take() {
-                // Reconstruct the Retained to properly close and release
-                let window: Option<Retained<NSWindow>> =
-                    unsafe { Retained::from_raw(ptr as *mut NSWindow) };
-                if let Some(w) = window {
-                    w.orderOut(None);
-                    // Window is dropped here, properly releasing it
-                }
-            }
-        });
-    });
+    window
 }
 
 /// Create a view that draws a border using layer-backed drawing
-fn create_border_view(mtm: MainThreadMarker, width: CGFloat, height: CGFloat) -> Retained<NSView> {
+fn create_border_view(
+    mtm: MainThreadMarker,
+    width: CGFloat,
+    height: CGFloat,
+    style: &HighlightStyle,
+) -> Retained<NSView> {
     unsafe {
         let frame = NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new(width, height));
 
@@ -151,13 +176,14 @@ fn create_border_view(mtm: MainThreadMarker, width: CGFloat, height: CGFloat) ->
         view.setWantsLayer(true);
 
         if let Some(layer) = view.layer() {
-            // Set border color (nice blue: #2196F3)
+            let [r, g, b, a] = style.color_rgba;
             let cg_color = core_graphics::color::CGColor::rgb(
-                0x21 as CGFloat / 255.0,
-                0x96 as CGFloat / 255.0,
-                0xF3 as CGFloat / 255.0,
-                1.0,
+                r as CGFloat / 255.0,
+                g as CGFloat / 255.0,
+                b as CGFloat / 255.0,
+                a as CGFloat / 255.0,
             );
+            let border_width = style.border_width as CGFloat;
 
             // Get the raw CGColorRef pointer for passing to CALayer
             let cg_color_ref: *const c_void = cg_color.as_concrete_TypeRef() as *const c_void;
@@ -168,15 +194,97 @@ fn create_border_view(mtm: MainThreadMarker, width: CGFloat, height: CGFloat) ->
             let layer_ptr: *const AnyObject = &*layer as *const _ as *const AnyObject;
             let layer_ref: &AnyObject = &*layer_ptr;
 
-            let _: () = msg_send![layer_ref, setBorderColor: cg_color_ref];
-            let _: () = msg_send![layer_ref, setBorderWidth: BORDER_WIDTH];
-            let _: () = msg_send![layer_ref, setCornerRadius: 4.0f64];
+            if style.animation == HighlightAnimation::Dash {
+                // CALayer borders can't be dashed; stroke a CAShapeLayer instead
+                add_dashed_border(layer_ref, frame, cg_color_ref, border_width);
+            } else {
+                let _: () = msg_send![layer_ref, setBorderColor: cg_color_ref];
+                let _: () = msg_send![layer_ref, setBorderWidth: border_width];
+                let _: () = msg_send![layer_ref, setCornerRadius: 4.0f64];
+            }
+
+            if style.animation == HighlightAnimation::Pulse {
+                let pulse = basic_animation("opacity", 1.0, 0.35, PULSE_PERIOD_MS as f64 / 2000.0);
+                if let Some(pulse) = pulse {
+                    let _: () = msg_send![&*pulse, setAutoreverses: true];
+                    add_animation(layer_ref, &pulse, "omnirec-pulse");
+                }
+            }
         }
 
         view
     }
 }
 
+/// Add a CAShapeLayer stroking a dashed rectangle, with a marching-dash animation.
+unsafe fn add_dashed_border(
+    layer: &AnyObject,
+    frame: NSRect,
+    cg_color_ref: *const c_void,
+    border_width: CGFloat,
+) {
+    let Some(shape_class) = AnyClass::get(c"CAShapeLayer") else {
+        tracing::debug!("[macOS] Highlight: CAShapeLayer unavailable");
+        return;
+    };
+    let shape: Retained<AnyObject> = msg_send![shape_class, layer];
+
+    // Stroke is centred on the path, so inset by half the width to keep it inside
+    let inset = border_width / 2.0;
+    let path_rect = core_graphics::geometry::CGRect::new(
+        &core_graphics::geometry::CGPoint::new(inset, inset),
+        &core_graphics::geometry::CGSize::new(
+            (frame.size.width - border_width).max(0.0),
+            (frame.size.height - border_width).max(0.0),
+        ),
+    );
+    let path = CGPath::from_rect(path_rect, None);
+    let path_ref: *const c_void = path.as_ptr() as *const c_void;
+    let dash = NSNumber::numberWithDouble(DASH_LENGTH as f64);
+    let dash_pattern = NSArray::from_retained_slice(&[dash.clone(), dash]);
+    let no_fill: *const c_void = std::ptr::null();
+
+    let _: () = msg_send![&*shape, setFrame: frame];
+    let _: () = msg_send![&*shape, setPath: path_ref];
+    let _: () = msg_send![&*shape, setFillColor: no_fill];
+    let _: () = msg_send![&*shape, setStrokeColor: cg_color_ref];
+    let _: () = msg_send![&*shape, setLineWidth: border_width];
+    let _: () = msg_send![&*shape, setLineDashPattern: &*dash_pattern];
+    let _: () = msg_send![layer, addSublayer: &*shape];
+
+    // Shift the dashes by one period per cycle, repeating forever
+    let period = (DASH_LENGTH * 2) as f64;
+    if let Some(march) = basic_animation("lineDashPhase", 0.0, -period, period / DASH_SPEED as f64)
+    {
+        add_animation(&shape, &march, "omnirec-dash");
+    }
+}
+
+/// Create a repeating linear `CABasicAnimation` for `key_path`.
+unsafe fn basic_animation(
+    key_path: &str,
+    from: f64,
+    to: f64,
+    duration_secs: f64,
+) -> Option<Retained<AnyObject>> {
+    let class = AnyClass::get(c"CABasicAnimation")?;
+    let key_path = NSString::from_str(key_path);
+    let animation: Retained<AnyObject> = msg_send![class, animationWithKeyPath: &*key_path];
+    let from = NSNumber::numberWithDouble(from);
+    let to = NSNumber::numberWithDouble(to);
+
+    let _: () = msg_send![&*animation, setFromValue: &*from];
+    let _: () = msg_send![&*animation, setToValue: &*to];
+    let _: () = msg_send![&*animation, setDuration: duration_secs];
+    let _: () = msg_send![&*animation, setRepeatCount: f32::INFINITY];
+    Some(animation)
+}
+
+unsafe fn add_animation(layer: &AnyObject, animation: &AnyObject, key: &str) {
+    let key = NSString::from_str(key);
+    let _: () = msg_send![layer, addAnimation: animation, forKey: &*key];
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,7 +292,7 @@ mod tests {
     #[test]
     fn test_show_highlight_does_not_panic() {
         // Just verify it doesn't crash (actual display requires main thread)
-        show_highlight(100, 100, 800, 600);
+        show_highlight(100, 100, 800, 600, HighlightStyle::default());
         thread::sleep(Duration::from_millis(100));
     }
 }
@@ -8,16 +8,23 @@
 pub mod audio;
 pub mod highlight;
 pub mod monitor_list;
+pub mod monitor_watch;
+pub mod permission;
+pub mod power_watch;
+pub mod probe;
 pub mod recorder;
 pub mod thumbnail;
+pub mod window_follow;
 pub mod window_list;
 
 use crate::capture::error::{CaptureError, EnumerationError};
 use crate::capture::types::{CapturedFrame, FrameReceiver, StopHandle};
 use crate::capture::{
-    AudioEnumerator, AudioSource, CaptureBackend, CaptureRegion, HighlightProvider,
-    MonitorEnumerator, MonitorInfo, ThumbnailCapture, ThumbnailResult, WindowEnumerator, WindowInfo,
+    AudioEnumerator, AudioSource, CaptureBackend, CaptureRegion, HighlightProvider, HighlightStyle,
+    MonitorEnumerator, MonitorInfo, Screenshot, ThumbnailCapture, ThumbnailOptions,
+    ThumbnailResult, WindowEnumerator, WindowInfo,
 };
+use omnirec_types::logical_to_physical_region;
 use std::sync::atomic::Ordering;
 use tokio::sync::mpsc;
 
@@ -39,9 +46,9 @@ impl MacOSBackend {
         true
     }
 
-    /// Check if screen recording permission is granted.
+    /// Check if screen recording permission is granted (cached, see [`permission`]).
     pub fn has_screen_recording_permission() -> bool {
-        unsafe { CGPreflightScreenCaptureAccess() }
+        permission::has_screen_recording_permission()
     }
 
     /// Request screen recording permission from the user.
@@ -103,6 +110,9 @@ impl CaptureBackend for MacOSBackend {
         // Convert handle to CGWindowID (u32)
         let window_id = window_handle as u32;
 
+        if crate::capture::window_follow::is_enabled() {
+            return window_follow::start_capture(window_id).map_err(CaptureError::PlatformError);
+        }
         recorder::start_window_capture(window_id).map_err(CaptureError::PlatformError)
     }
 
@@ -158,32 +168,32 @@ impl CaptureBackend for MacOSBackend {
         tracing::debug!("[macOS] Input region from frontend (logical coords):");
         tracing::debug!(
             "[macOS]   x={}, y={}, width={}, height={}",
-            region.x, region.y, region.width, region.height
+            region.x,
+            region.y,
+            region.width,
+            region.height
         );
         tracing::debug!("[macOS] Monitor info:");
         tracing::debug!(
             "[macOS]   id={}, logical_origin=({}, {}), logical_size={}x{}, scale={}",
-            monitor.id, monitor.x, monitor.y, monitor.width, monitor.height, scale
+            monitor.id,
+            monitor.x,
+            monitor.y,
+            monitor.width,
+            monitor.height,
+            scale
         );
         tracing::debug!(
             "[macOS]   physical_size={}x{}",
-            physical_monitor_width, physical_monitor_height
+            physical_monitor_width,
+            physical_monitor_height
         );
 
         // Region from frontend is in logical coordinates (matching monitor coordinate system)
         // Need to convert to physical pixels for cropping captured frames
-        let region_x_physical = ((region.x.max(0) as f64) * scale).round() as u32;
-        let region_y_physical = ((region.y.max(0) as f64) * scale).round() as u32;
-        let region_width_physical = ((region.width as f64) * scale).round() as u32;
-        let region_height_physical = ((region.height as f64) * scale).round() as u32;
-
-        // Clamp to physical monitor bounds
-        let max_width = physical_monitor_width.saturating_sub(region_x_physical);
-        let max_height = physical_monitor_height.saturating_sub(region_y_physical);
-        let region_x = region_x_physical;
-        let region_y = region_y_physical;
-        let region_width = region_width_physical.min(max_width);
-        let region_height = region_height_physical.min(max_height);
+        let physical = logical_to_physical_region(&region, monitor);
+        let (region_x, region_y) = (physical.x as u32, physical.y as u32);
+        let (region_width, region_height) = (physical.width, physical.height);
 
         // Validate we still have a valid region (in physical pixels)
         let min_size_physical = (100.0_f64 * scale).round() as u32;
@@ -197,7 +207,10 @@ impl CaptureBackend for MacOSBackend {
         tracing::debug!("[macOS] Final crop region (physical pixels):");
         tracing::debug!(
             "[macOS]   x={}, y={}, width={}, height={}",
-            region_x, region_y, region_width, region_height
+            region_x,
+            region_y,
+            region_width,
+            region_height
         );
         tracing::debug!("[macOS] =============================");
 
@@ -223,11 +236,15 @@ impl CaptureBackend for MacOSBackend {
                         if frame_count == 0 {
                             tracing::debug!(
                                 "[macOS] First frame received: {}x{}",
-                                frame.width, frame.height
+                                frame.width,
+                                frame.height
                             );
                             tracing::debug!(
                                 "[macOS] Cropping to: {}x{} at ({},{})",
-                                region_width, region_height, region_x, region_y
+                                region_width,
+                                region_height,
+                                region_x,
+                                region_y
                             );
                         }
                         frame_count += 1;
@@ -275,11 +292,16 @@ impl CaptureBackend for MacOSBackend {
         recorder::start_display_capture(display_id, width, height)
             .map_err(CaptureError::PlatformError)
     }
+
+    /// Available when the startup probe found no missing dependencies.
+    fn is_available(&self) -> bool {
+        crate::capture::probe::get_probe_result().ok
+    }
 }
 
 impl HighlightProvider for MacOSBackend {
-    fn show_highlight(&self, x: i32, y: i32, width: i32, height: i32) {
-        highlight::show_highlight(x, y, width, height);
+    fn show_highlight(&self, x: i32, y: i32, width: i32, height: i32, style: &HighlightStyle) {
+        highlight::show_highlight(x, y, width, height, *style);
     }
 }
 
@@ -287,12 +309,17 @@ impl ThumbnailCapture for MacOSBackend {
     fn capture_window_thumbnail(
         &self,
         window_handle: isize,
+        options: ThumbnailOptions,
     ) -> Result<ThumbnailResult, CaptureError> {
-        thumbnail::MacOSThumbnailCapture::new().capture_window_thumbnail(window_handle)
+        thumbnail::MacOSThumbnailCapture::new().capture_window_thumbnail(window_handle, options)
     }
 
-    fn capture_display_thumbnail(&self, monitor_id: &str) -> Result<ThumbnailResult, CaptureError> {
-        thumbnail::MacOSThumbnailCapture::new().capture_display_thumbnail(monitor_id)
+    fn capture_display_thumbnail(
+        &self,
+        monitor_id: &str,
+        options: ThumbnailOptions,
+    ) -> Result<ThumbnailResult, CaptureError> {
+        thumbnail::MacOSThumbnailCapture::new().capture_display_thumbnail(monitor_id, options)
     }
 
     fn capture_region_preview(
@@ -302,9 +329,18 @@ impl ThumbnailCapture for MacOSBackend {
         y: i32,
         width: u32,
         height: u32,
+        quality: u8,
     ) -> Result<ThumbnailResult, CaptureError> {
         thumbnail::MacOSThumbnailCapture::new()
-            .capture_region_preview(monitor_id, x, y, width, height)
+            .capture_region_preview(monitor_id, x, y, width, height, quality)
+    }
+
+    fn capture_window_screenshot(&self, window_handle: isize) -> Result<Screenshot, CaptureError> {
+        thumbnail::MacOSThumbnailCapture::new().capture_window_screenshot(window_handle)
+    }
+
+    fn capture_display_screenshot(&self, monitor_id: &str) -> Result<Screenshot, CaptureError> {
+        thumbnail::MacOSThumbnailCapture::new().capture_display_screenshot(monitor_id)
     }
 }
 
@@ -321,6 +357,9 @@ pub fn init_audio() -> Result<(), String> {
 }
 
 /// Crop a captured frame to a specified region.
+///
+/// Handles packed BGRA and planar NV12/YUV420 frames; see
+/// [`CapturedFrame::crop`].
 fn crop_frame(
     frame: &CapturedFrame,
     x: u32,
@@ -332,44 +371,38 @@ fn crop_frame(
     if x + width > frame.width || y + height > frame.height {
         tracing::debug!(
             "[macOS] Crop region {}x{} at ({},{}) exceeds frame size {}x{}",
-            width, height, x, y, frame.width, frame.height
+            width,
+            height,
+            x,
+            y,
+            frame.width,
+            frame.height
         );
         return None;
     }
 
-    // Create cropped buffer (4 bytes per pixel for BGRA)
-    let mut cropped_data = Vec::with_capacity((width * height * 4) as usize);
-
-    let src_stride = (frame.width * 4) as usize;
-    let dst_stride = (width * 4) as usize;
-
-    for row in 0..height {
-        let src_y = (y + row) as usize;
-        let src_start = src_y * src_stride + (x * 4) as usize;
-        let src_end = src_start + dst_stride;
-
-        if src_end <= frame.data.len() {
-            cropped_data.extend_from_slice(&frame.data[src_start..src_end]);
-        }
+    let cropped = frame.crop(x, y, width, height);
+    if cropped.is_none() {
+        tracing::debug!(
+            "[macOS] Frame data too short for {}x{} {:?}",
+            frame.width,
+            frame.height,
+            frame.pixel_format
+        );
     }
-
-    Some(CapturedFrame {
-        width,
-        height,
-        data: cropped_data,
-        captured_at: frame.captured_at,
-    })
+    cropped
 }
 
 // External declarations for Core Graphics permission functions
 extern "C" {
-    fn CGPreflightScreenCaptureAccess() -> bool;
     fn CGRequestScreenCaptureAccess() -> bool;
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::capture::types::PixelFormat;
+    use crate::test_utils::{assert_frame_equals, generate_test_frame};
 
     #[test]
     fn test_backend_creation() {
@@ -384,13 +417,8 @@ mod tests {
 
     #[test]
     fn test_crop_frame() {
-        // Create a test frame 10x10 pixels (400 bytes for BGRA)
-        let frame = CapturedFrame {
-            width: 10,
-            height: 10,
-            data: vec![0u8; 400],
-            captured_at: std::time::SystemTime::now(),
-        };
+        // 10x10 BGRA test frame (400 bytes)
+        let frame = generate_test_frame(10, 10, 0);
 
         // Crop to 5x5 at (2,2)
         let cropped = crop_frame(&frame, 2, 2, 5, 5);
@@ -400,17 +428,30 @@ mod tests {
         assert_eq!(cropped.width, 5);
         assert_eq!(cropped.height, 5);
         assert_eq!(cropped.data.len(), 100); // 5*5*4
+        assert_frame_equals(&cropped, &frame.crop(2, 2, 5, 5).unwrap(), 0);
     }
 
     #[test]
-    fn test_crop_frame_invalid() {
+    fn test_crop_frame_nv12() {
+        // 10x10 NV12: 100 bytes of Y, then 5 rows of 10 bytes of UV
         let frame = CapturedFrame {
             width: 10,
             height: 10,
-            data: vec![0u8; 400],
+            data: vec![0u8; 150].into(),
+            pixel_format: PixelFormat::Nv12,
             captured_at: std::time::SystemTime::now(),
+            timestamp_us: 0,
         };
 
+        let cropped = crop_frame(&frame, 2, 2, 6, 4).unwrap();
+        assert_eq!(cropped.pixel_format, PixelFormat::Nv12);
+        assert_eq!(cropped.data.len(), 36); // 6*4 Y + 3*2 UV pairs
+    }
+
+    #[test]
+    fn test_crop_frame_invalid() {
+        let frame = generate_test_frame(10, 10, 0);
+
         // Try to crop beyond bounds
         let cropped = crop_frame(&frame, 8, 8, 5, 5);
         assert!(cropped.is_none());
@@ -1,7 +1,20 @@
 //! macOS monitor/display enumeration using Core Graphics.
 
+use crate::capture::color_profile::ColorProfile;
 use crate::capture::MonitorInfo;
+use core_foundation::base::TCFType;
+use core_foundation::data::{CFData, CFDataRef};
 use core_graphics::display::{CGDirectDisplayID, CGDisplay, CGMainDisplayID};
+use std::ffi::c_void;
+
+type CGColorSpaceRef = *mut c_void;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGDisplayCopyColorSpace(display: CGDirectDisplayID) -> CGColorSpaceRef;
+    fn CGColorSpaceCopyICCData(space: CGColorSpaceRef) -> CFDataRef;
+    fn CGColorSpaceRelease(space: CGColorSpaceRef);
+}
 
 /// List all connected monitors on macOS.
 ///
@@ -89,6 +102,30 @@ pub fn list_monitors() -> Vec<MonitorInfo> {
     monitors
 }
 
+/// Get the color profile of a display from its ICC profile.
+///
+/// `monitor_id` is the `CGDirectDisplayID` returned by [`list_monitors`].
+pub fn get_display_color_profile(monitor_id: &str) -> Option<ColorProfile> {
+    let display_id: CGDirectDisplayID = monitor_id.parse().ok()?;
+
+    let data = unsafe {
+        let space = CGDisplayCopyColorSpace(display_id);
+        if space.is_null() {
+            tracing::debug!("[ColorProfile] No color space for display {}", display_id);
+            return None;
+        }
+        let icc = CGColorSpaceCopyICCData(space);
+        CGColorSpaceRelease(space);
+        if icc.is_null() {
+            tracing::debug!("[ColorProfile] No ICC data for display {}", display_id);
+            return None;
+        }
+        CFData::wrap_under_create_rule(icc)
+    };
+
+    ColorProfile::from_icc(data.bytes())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
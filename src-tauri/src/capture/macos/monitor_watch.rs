@@ -0,0 +1,60 @@
+//! Display configuration watcher using Core Graphics.
+//!
+//! `CGDisplayRegisterReconfigurationCallback` reports every display that is
+//! added, removed or changes mode. Core Graphics calls the callback once
+//! before and once after each change; only the completed change is reported.
+
+use std::ffi::c_void;
+use std::sync::OnceLock;
+
+type CGDirectDisplayID = u32;
+type CGDisplayChangeSummaryFlags = u32;
+type CGError = i32;
+
+/// Set in the callback issued before the configuration changes.
+const K_CG_DISPLAY_BEGIN_CONFIGURATION_FLAG: CGDisplayChangeSummaryFlags = 1 << 0;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGDisplayRegisterReconfigurationCallback(
+        callback: extern "C" fn(CGDirectDisplayID, CGDisplayChangeSummaryFlags, *mut c_void),
+        user_info: *mut c_void,
+    ) -> CGError;
+}
+
+/// Callback invoked for each completed display change.
+static ON_CHANGE: OnceLock<fn()> = OnceLock::new();
+
+/// Start watching for display changes, calling `on_change` for each one.
+///
+/// The callback is delivered on the main run loop. Only the first call
+/// registers a watcher.
+pub fn start(on_change: fn()) {
+    if ON_CHANGE.set(on_change).is_err() {
+        return;
+    }
+    let err = unsafe {
+        CGDisplayRegisterReconfigurationCallback(display_reconfigured, std::ptr::null_mut())
+    };
+    if err != 0 {
+        tracing::warn!(
+            "[MonitorWatch] CGDisplayRegisterReconfigurationCallback failed: {}",
+            err
+        );
+    } else {
+        tracing::debug!("[MonitorWatch] Watching for display reconfiguration");
+    }
+}
+
+extern "C" fn display_reconfigured(
+    _display: CGDirectDisplayID,
+    flags: CGDisplayChangeSummaryFlags,
+    _user_info: *mut c_void,
+) {
+    if flags & K_CG_DISPLAY_BEGIN_CONFIGURATION_FLAG != 0 {
+        return;
+    }
+    if let Some(on_change) = ON_CHANGE.get() {
+        on_change();
+    }
+}
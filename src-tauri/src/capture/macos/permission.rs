@@ -0,0 +1,110 @@
+//! Cached screen recording permission checks.
+//!
+//! `CGPreflightScreenCaptureAccess` is comparatively slow and was being called
+//! before every recording and thumbnail. A granted permission is remembered
+//! for a configurable TTL; a denied one is always re-probed so a fresh grant is
+//! picked up on the next attempt.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a granted permission is trusted before it is probed again.
+pub const DEFAULT_PERMISSION_CACHE_TTL: Duration = Duration::from_secs(30);
+
+extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+}
+
+fn preflight() -> bool {
+    unsafe { CGPreflightScreenCaptureAccess() }
+}
+
+/// When screen recording permission was last seen granted.
+#[derive(Debug, Clone, Copy)]
+pub struct PermissionCache {
+    /// Time of the last successful probe, if still cached
+    granted_at: Option<Instant>,
+    /// How long a successful probe stays valid
+    ttl: Duration,
+}
+
+impl PermissionCache {
+    /// Create an empty cache.
+    pub const fn new(ttl: Duration) -> Self {
+        Self {
+            granted_at: None,
+            ttl,
+        }
+    }
+
+    /// Whether a grant recorded before `now` is still valid.
+    fn is_fresh(&self, now: Instant) -> bool {
+        self.granted_at
+            .is_some_and(|granted_at| now.saturating_duration_since(granted_at) < self.ttl)
+    }
+
+    /// Return the cached grant, or call `probe` and remember a grant.
+    fn check(&mut self, now: Instant, probe: impl FnOnce() -> bool) -> bool {
+        if self.is_fresh(now) {
+            return true;
+        }
+        let granted = probe();
+        self.granted_at = granted.then_some(now);
+        granted
+    }
+}
+
+static CACHE: Mutex<PermissionCache> =
+    Mutex::new(PermissionCache::new(DEFAULT_PERMISSION_CACHE_TTL));
+
+/// Check if screen recording permission is granted, using the cache.
+pub fn has_screen_recording_permission() -> bool {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.check(Instant::now(), preflight)
+}
+
+/// Forget the cached result so the next check probes again.
+pub fn invalidate_permission_cache() {
+    CACHE.lock().unwrap_or_else(|e| e.into_inner()).granted_at = None;
+}
+
+/// Set how long a granted permission is cached.
+pub fn set_permission_cache_ttl(ttl: Duration) {
+    CACHE.lock().unwrap_or_else(|e| e.into_inner()).ttl = ttl;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grant_is_cached_until_ttl() {
+        let mut cache = PermissionCache::new(Duration::from_secs(30));
+        let start = Instant::now();
+        assert!(cache.check(start, || true));
+        assert!(cache.check(start + Duration::from_secs(29), || panic!("probed")));
+
+        let mut probes = 0;
+        assert!(!cache.check(start + Duration::from_secs(30), || {
+            probes += 1;
+            false
+        }));
+        assert_eq!(probes, 1);
+    }
+
+    #[test]
+    fn test_denial_is_not_cached() {
+        let mut cache = PermissionCache::new(Duration::from_secs(30));
+        let now = Instant::now();
+        assert!(!cache.check(now, || false));
+        assert!(cache.check(now, || true));
+    }
+
+    #[test]
+    fn test_zero_ttl_always_probes() {
+        let mut cache = PermissionCache::new(Duration::ZERO);
+        let now = Instant::now();
+        assert!(cache.check(now, || true));
+        assert!(!cache.check(now, || false));
+    }
+}
@@ -0,0 +1,61 @@
+//! Sleep and wake watcher using `NSWorkspace` notifications.
+//!
+//! The shared workspace's notification center posts
+//! `NSWorkspaceWillSleepNotification` before the system sleeps and
+//! `NSWorkspaceDidWakeNotification` after it wakes. Notifications are
+//! delivered on the main run loop.
+
+use crate::capture::power_watch::PowerEvent;
+use block2::RcBlock;
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::NSString;
+use std::ptr::NonNull;
+use std::sync::OnceLock;
+
+/// Callback invoked for each sleep and wake.
+static ON_EVENT: OnceLock<fn(PowerEvent)> = OnceLock::new();
+
+/// Start watching for sleep and wake, calling `on_event` for each one.
+///
+/// Only the first call registers observers.
+pub fn start(on_event: fn(PowerEvent)) {
+    if ON_EVENT.set(on_event).is_err() {
+        return;
+    }
+    let registered = unsafe {
+        observe("NSWorkspaceWillSleepNotification", PowerEvent::Sleep)
+            && observe("NSWorkspaceDidWakeNotification", PowerEvent::Wake)
+    };
+    if registered {
+        tracing::debug!("[PowerWatch] Watching for workspace sleep and wake");
+    } else {
+        tracing::warn!("[PowerWatch] Failed to observe workspace sleep notifications");
+    }
+}
+
+/// Observe the workspace notification `name`, reporting it as `event`.
+unsafe fn observe(name: &str, event: PowerEvent) -> bool {
+    let Some(workspace_class) = AnyClass::get(c"NSWorkspace") else {
+        return false;
+    };
+    let workspace: Retained<AnyObject> = msg_send![workspace_class, sharedWorkspace];
+    let center: Retained<AnyObject> = msg_send![&*workspace, notificationCenter];
+
+    let block = RcBlock::new(move |_notification: NonNull<AnyObject>| {
+        if let Some(on_event) = ON_EVENT.get() {
+            on_event(event);
+        }
+    });
+    let name = NSString::from_str(name);
+    let observer: Option<Retained<AnyObject>> = msg_send![
+        &*center,
+        addObserverForName: &*name,
+        object: Option::<&AnyObject>::None,
+        queue: Option::<&AnyObject>::None,
+        usingBlock: &*block
+    ];
+    // The observer stays registered for the life of the app
+    observer.map(std::mem::forget).is_some()
+}
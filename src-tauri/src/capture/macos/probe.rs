@@ -0,0 +1,60 @@
+//! macOS capture dependency probe.
+
+use super::MacOSBackend;
+use crate::capture::probe::BackendProbeResult;
+use std::process::Command;
+
+/// Minimum macOS version with ScreenCaptureKit support.
+const MIN_MACOS_VERSION: (u32, u32) = (12, 3);
+
+/// Check the macOS version and screen recording permission.
+pub fn probe_capture_backend() -> BackendProbeResult {
+    let mut result = BackendProbeResult::default();
+
+    match product_version() {
+        Some(version) if version < MIN_MACOS_VERSION => result.error(format!(
+            "ScreenCaptureKit requires macOS {}.{} or later (found {}.{})",
+            MIN_MACOS_VERSION.0, MIN_MACOS_VERSION.1, version.0, version.1
+        )),
+        Some(_) => {}
+        None => result.warn("Could not determine macOS version"),
+    }
+
+    if !MacOSBackend::has_screen_recording_permission() {
+        result.warn(
+            "Screen recording permission not granted. Enable OmniRec in System Settings > Privacy & Security > Screen Recording.",
+        );
+    }
+
+    result
+}
+
+/// Read the (major, minor) macOS version via `sw_vers`.
+fn product_version() -> Option<(u32, u32)> {
+    let output = Command::new("sw_vers")
+        .arg("-productVersion")
+        .output()
+        .ok()?;
+    parse_version(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("14.4.1"), Some((14, 4)));
+        assert_eq!(parse_version("12.3"), Some((12, 3)));
+        assert_eq!(parse_version("15"), Some((15, 0)));
+        assert_eq!(parse_version(""), None);
+        assert!(parse_version("12.2").unwrap() < MIN_MACOS_VERSION);
+    }
+}
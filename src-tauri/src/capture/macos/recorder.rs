@@ -2,7 +2,9 @@
 //!
 //! Provides high-performance GPU-accelerated capture for displays and windows.
 
-use crate::capture::types::{CapturedFrame, FrameReceiver, StopHandle};
+use crate::capture::frame_limit::{self, TokenBucket};
+use crate::capture::frame_pool::FramePool;
+use crate::capture::types::{self, CapturedFrame, FrameReceiver, PresentationClock, StopHandle};
 use screencapturekit::{
     cm_sample_buffer::CMSampleBuffer,
     sc_content_filter::{InitParams, SCContentFilter},
@@ -25,12 +27,47 @@ extern "C" {
     fn CVPixelBufferGetBytesPerRow(pixelBuffer: *const c_void) -> usize;
 }
 
+/// CoreMedia's `CMTime`: `value / timescale` seconds.
+#[repr(C)]
+struct CMTime {
+    value: i64,
+    timescale: i32,
+    flags: u32,
+    epoch: i64,
+}
+
+/// `kCMTimeFlags_Valid`
+const CM_TIME_FLAGS_VALID: u32 = 1;
+
+#[link(name = "CoreMedia", kind = "framework")]
+extern "C" {
+    fn CMSampleBufferGetPresentationTimeStamp(sbuf: *const c_void) -> CMTime;
+}
+
+/// Presentation time of a sample buffer in microseconds, if it has one.
+fn presentation_time_us(sample: &CMSampleBuffer) -> Option<i64> {
+    // Same toll-free pointer trick as for the image buffer below
+    let sample_ptr = &*sample.sys_ref as *const _ as *const c_void;
+    let time = unsafe { CMSampleBufferGetPresentationTimeStamp(sample_ptr) };
+    if time.flags & CM_TIME_FLAGS_VALID == 0 || time.timescale <= 0 {
+        return None;
+    }
+    Some((time.value as i128 * 1_000_000 / time.timescale as i128) as i64)
+}
+
 /// Frame rate for capture (frames per second).
 const CAPTURE_FPS: f64 = 30.0;
 
 /// Channel buffer size for frames.
 const FRAME_BUFFER_SIZE: usize = 3;
 
+/// Frames per second to request from ScreenCaptureKit, lowered by the
+/// capture frame rate limit.
+fn stream_fps() -> i32 {
+    let fps = CAPTURE_FPS as i32;
+    frame_limit::max_fps().map_or(fps, |limit| fps.min(limit as i32))
+}
+
 /// Error handler for SCStream.
 struct CaptureErrorHandler;
 
@@ -46,6 +83,12 @@ struct FrameOutputHandler {
     stop_flag: Arc<AtomicBool>,
     width: u32,
     height: u32,
+    /// Frame rate limit; the callback only gets `&self`
+    frame_limit: std::sync::Mutex<Option<TokenBucket>>,
+    /// Buffers frames are copied into
+    frame_pool: Arc<FramePool>,
+    /// Maps presentation times onto the recording epoch
+    clock: std::sync::Mutex<PresentationClock>,
 }
 
 impl StreamOutput for FrameOutputHandler {
@@ -60,6 +103,19 @@ impl StreamOutput for FrameOutputHandler {
             return;
         }
 
+        // Drop frames above the frame rate limit before copying them
+        let admitted =
+            frame_limit::admit(&mut self.frame_limit.lock().unwrap_or_else(|e| e.into_inner()));
+        if !admitted {
+            return;
+        }
+
+        let timestamp_us = self
+            .clock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .timestamp_us(presentation_time_us(&sample));
+
         // Get pixel buffer - skip frames without valid pixel data
         let pixel_buffer = match &sample.pixel_buffer {
             Some(pb) => pb,
@@ -131,31 +187,32 @@ impl StreamOutput for FrameOutputHandler {
             );
         }
 
-        // If stride matches expected, we can copy directly
-        let data = if bytes_per_row == expected_bytes_per_row || bytes_per_row == 0 {
-            let data_size = (width * height * 4) as usize;
+        // Copy into a pooled buffer; if stride matches expected, we can copy directly
+        let data_size = (width * height * 4) as usize;
+        let mut data = self.frame_pool.acquire(data_size);
+        if bytes_per_row == expected_bytes_per_row || bytes_per_row == 0 {
             let src = unsafe { std::slice::from_raw_parts(base_address as *const u8, data_size) };
-            src.to_vec()
+            data.copy_from_slice(src);
         } else {
             // Stride doesn't match - need to copy row by row, removing padding
-            let mut data = Vec::with_capacity((width * height * 4) as usize);
             let src_ptr = base_address as *const u8;
 
-            for row in 0..height {
-                let row_start = (row as usize) * bytes_per_row;
+            for (row, output) in data.chunks_exact_mut(expected_bytes_per_row).enumerate() {
+                let row_start = row * bytes_per_row;
                 let row_data = unsafe {
                     std::slice::from_raw_parts(src_ptr.add(row_start), expected_bytes_per_row)
                 };
-                data.extend_from_slice(row_data);
+                output.copy_from_slice(row_data);
             }
-            data
-        };
+        }
 
         let frame = CapturedFrame {
             width,
             height,
-            data,
+            data: data.into(),
+            pixel_format: types::PixelFormat::Bgra,
             captured_at: std::time::SystemTime::now(),
+            timestamp_us,
         };
 
         // Unlock the buffer
@@ -208,7 +265,7 @@ pub fn start_display_capture(
         pixel_format: PixelFormat::ARGB8888, // BGRA in memory
         minimum_frame_interval: screencapturekit::sc_types::base::CMTime {
             value: 1,
-            timescale: CAPTURE_FPS as i32,
+            timescale: stream_fps(),
             flags: 1,
             epoch: 0,
         },
@@ -232,6 +289,9 @@ pub fn start_display_capture(
         stop_flag: stop_flag.clone(),
         width,
         height,
+        frame_limit: std::sync::Mutex::new(TokenBucket::for_current_limit()),
+        frame_pool: FramePool::new((width * height * 4) as usize),
+        clock: std::sync::Mutex::new(PresentationClock::new()),
     };
     stream.add_output(handler, SCStreamOutputType::Screen);
 
@@ -297,7 +357,7 @@ pub fn start_window_capture(window_id: u32) -> Result<(FrameReceiver, StopHandle
         pixel_format: PixelFormat::ARGB8888,
         minimum_frame_interval: screencapturekit::sc_types::base::CMTime {
             value: 1,
-            timescale: CAPTURE_FPS as i32,
+            timescale: stream_fps(),
             flags: 1,
             epoch: 0,
         },
@@ -321,6 +381,9 @@ pub fn start_window_capture(window_id: u32) -> Result<(FrameReceiver, StopHandle
         stop_flag: stop_flag.clone(),
         width,
         height,
+        frame_limit: std::sync::Mutex::new(TokenBucket::for_current_limit()),
+        frame_pool: FramePool::new((width * height * 4) as usize),
+        clock: std::sync::Mutex::new(PresentationClock::new()),
     };
     stream.add_output(handler, SCStreamOutputType::Screen);
 
@@ -351,4 +414,16 @@ mod tests {
     fn test_capture_fps() {
         assert_eq!(CAPTURE_FPS, 30.0);
     }
+
+    #[test]
+    fn test_stream_fps_respects_limit() {
+        let previous = frame_limit::max_fps();
+        frame_limit::set_max_fps(Some(15));
+        assert_eq!(stream_fps(), 15);
+        frame_limit::set_max_fps(Some(60));
+        assert_eq!(stream_fps(), 30);
+        frame_limit::set_max_fps(None);
+        assert_eq!(stream_fps(), 30);
+        frame_limit::set_max_fps(previous);
+    }
 }
@@ -6,10 +6,10 @@
 
 use crate::capture::error::CaptureError;
 use crate::capture::thumbnail::{
-    bgra_to_jpeg_thumbnail, PREVIEW_MAX_HEIGHT, PREVIEW_MAX_WIDTH, THUMBNAIL_MAX_HEIGHT,
-    THUMBNAIL_MAX_WIDTH,
+    bgra_to_jpeg_thumbnail, decode_png_icon, icon_to_jpeg_thumbnail, ThumbnailOptions,
+    ICON_FALLBACK_SIZE, PREVIEW_MAX_HEIGHT, PREVIEW_MAX_WIDTH,
 };
-use crate::capture::{ThumbnailCapture, ThumbnailResult};
+use crate::capture::{Screenshot, ThumbnailCapture, ThumbnailResult};
 
 use super::monitor_list;
 
@@ -19,20 +19,18 @@ use core_graphics::display::{
 };
 use core_graphics::image::CGImage;
 use foreign_types::ForeignType;
+use objc2::msg_send;
+use objc2::rc::{autoreleasepool, Retained};
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::NSString;
+use std::ffi::c_void;
 
-// External declaration for permission check
-extern "C" {
-    fn CGPreflightScreenCaptureAccess() -> bool;
-}
-
-/// Check if screen recording permission is granted.
-fn has_permission() -> bool {
-    unsafe { CGPreflightScreenCaptureAccess() }
-}
+/// `NSBitmapImageFileTypePNG`
+const NS_BITMAP_IMAGE_FILE_TYPE_PNG: usize = 4;
 
 /// Ensure screen recording permission is granted.
 fn ensure_permission() -> Result<(), CaptureError> {
-    if !has_permission() {
+    if !super::permission::has_screen_recording_permission() {
         return Err(CaptureError::PermissionDenied(
             "Screen recording permission required for thumbnail capture. Please grant permission in System Settings > Privacy & Security > Screen Recording, then restart the app.".to_string()
         ));
@@ -82,6 +80,76 @@ fn cgimage_to_bgra(image: &CGImage) -> Result<(Vec<u8>, u32, u32), String> {
     Ok((bgra_data, width, height))
 }
 
+/// Application icon for a process, shown when the window can't be captured.
+///
+/// The application is located by name through `NSWorkspace`; the icon
+/// representation closest to [`ICON_FALLBACK_SIZE`] is used.
+pub fn get_fallback_icon(process_name: &str) -> Option<ThumbnailResult> {
+    let png = autoreleasepool(|_| unsafe { application_icon_png(process_name) })?;
+    let (data, width, height) = decode_png_icon(&png).ok()?;
+    let (data, width, height) = icon_to_jpeg_thumbnail(&data, width, height).ok()?;
+    Some(ThumbnailResult {
+        data,
+        width,
+        height,
+    })
+}
+
+/// PNG data of the icon of the application named `process_name`.
+unsafe fn application_icon_png(process_name: &str) -> Option<Vec<u8>> {
+    let workspace_class = AnyClass::get(c"NSWorkspace")?;
+    let bitmap_class = AnyClass::get(c"NSBitmapImageRep")?;
+    let dictionary_class = AnyClass::get(c"NSDictionary")?;
+
+    let workspace: Retained<AnyObject> = msg_send![workspace_class, sharedWorkspace];
+    let name = NSString::from_str(process_name);
+    let path: Option<Retained<AnyObject>> = msg_send![&*workspace, fullPathForApplication: &*name];
+    let icon: Option<Retained<AnyObject>> = msg_send![&*workspace, iconForFile: &*path?];
+    let tiff: Option<Retained<AnyObject>> = msg_send![&*icon?, TIFFRepresentation];
+    let reps: Option<Retained<AnyObject>> = msg_send![bitmap_class, imageRepsWithData: &*tiff?];
+    let reps = reps?;
+
+    let count: usize = msg_send![&*reps, count];
+    let mut best: Option<(isize, Retained<AnyObject>)> = None;
+    for index in 0..count {
+        let rep: Retained<AnyObject> = msg_send![&*reps, objectAtIndex: index];
+        let width: isize = msg_send![&*rep, pixelsWide];
+        if best
+            .as_ref()
+            .is_none_or(|(best_width, _)| is_better_icon_width(width, *best_width))
+        {
+            best = Some((width, rep));
+        }
+    }
+    let (_, rep) = best?;
+
+    let properties: Retained<AnyObject> = msg_send![dictionary_class, dictionary];
+    let png: Option<Retained<AnyObject>> = msg_send![
+        &*rep,
+        representationUsingType: NS_BITMAP_IMAGE_FILE_TYPE_PNG,
+        properties: &*properties
+    ];
+    let png = png?;
+    let length: usize = msg_send![&*png, length];
+    let bytes: *const c_void = msg_send![&*png, bytes];
+    if bytes.is_null() {
+        return None;
+    }
+    Some(std::slice::from_raw_parts(bytes.cast::<u8>(), length).to_vec())
+}
+
+/// Whether an icon representation `candidate` pixels wide is a better fit than
+/// `current`: the smallest one covering [`ICON_FALLBACK_SIZE`], else the largest.
+fn is_better_icon_width(candidate: isize, current: isize) -> bool {
+    let target = ICON_FALLBACK_SIZE as isize;
+    match (candidate >= target, current >= target) {
+        (true, true) => candidate < current,
+        (true, false) => true,
+        (false, true) => false,
+        (false, false) => candidate > current,
+    }
+}
+
 /// Capture a display to CGImage.
 fn capture_display(display_id: CGDirectDisplayID) -> Result<CGImage, CaptureError> {
     let display = CGDisplay::new(display_id);
@@ -183,18 +251,12 @@ impl MacOSThumbnailCapture {
     pub fn new() -> Self {
         Self
     }
-}
-
-impl Default for MacOSThumbnailCapture {
-    fn default() -> Self {
-        Self::new()
-    }
-}
 
-impl ThumbnailCapture for MacOSThumbnailCapture {
-    fn capture_window_thumbnail(
+    /// Capture a window and scale it to a thumbnail.
+    fn capture_window_image(
         &self,
         window_handle: isize,
+        options: ThumbnailOptions,
     ) -> Result<ThumbnailResult, CaptureError> {
         // Check permission first
         ensure_permission()?;
@@ -214,8 +276,9 @@ impl ThumbnailCapture for MacOSThumbnailCapture {
             &bgra_data,
             width,
             height,
-            THUMBNAIL_MAX_WIDTH,
-            THUMBNAIL_MAX_HEIGHT,
+            options.max_width,
+            options.max_height,
+            options.quality,
         )
         .map_err(CaptureError::PlatformError)?;
 
@@ -225,8 +288,40 @@ impl ThumbnailCapture for MacOSThumbnailCapture {
             height: thumb_height,
         })
     }
+}
 
-    fn capture_display_thumbnail(&self, monitor_id: &str) -> Result<ThumbnailResult, CaptureError> {
+impl Default for MacOSThumbnailCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThumbnailCapture for MacOSThumbnailCapture {
+    fn capture_window_thumbnail(
+        &self,
+        window_handle: isize,
+        options: ThumbnailOptions,
+    ) -> Result<ThumbnailResult, CaptureError> {
+        match self.capture_window_image(window_handle, options) {
+            Ok(result) => Ok(result),
+            Err(e) => crate::capture::window_process_name(window_handle)
+                .and_then(|process_name| get_fallback_icon(&process_name))
+                .inspect(|_| {
+                    tracing::debug!(
+                        "Window {} thumbnail failed ({}), using application icon",
+                        window_handle,
+                        e
+                    )
+                })
+                .ok_or(e),
+        }
+    }
+
+    fn capture_display_thumbnail(
+        &self,
+        monitor_id: &str,
+        options: ThumbnailOptions,
+    ) -> Result<ThumbnailResult, CaptureError> {
         // Check permission first
         ensure_permission()?;
 
@@ -247,8 +342,9 @@ impl ThumbnailCapture for MacOSThumbnailCapture {
             &bgra_data,
             width,
             height,
-            THUMBNAIL_MAX_WIDTH,
-            THUMBNAIL_MAX_HEIGHT,
+            options.max_width,
+            options.max_height,
+            options.quality,
         )
         .map_err(CaptureError::PlatformError)?;
 
@@ -266,6 +362,7 @@ impl ThumbnailCapture for MacOSThumbnailCapture {
         y: i32,
         width: u32,
         height: u32,
+        quality: u8,
     ) -> Result<ThumbnailResult, CaptureError> {
         // Check permission first
         ensure_permission()?;
@@ -324,6 +421,7 @@ impl ThumbnailCapture for MacOSThumbnailCapture {
             crop_height,
             PREVIEW_MAX_WIDTH,
             PREVIEW_MAX_HEIGHT,
+            quality,
         )
         .map_err(CaptureError::PlatformError)?;
 
@@ -333,11 +431,48 @@ impl ThumbnailCapture for MacOSThumbnailCapture {
             height: preview_height,
         })
     }
+
+    fn capture_window_screenshot(&self, window_handle: isize) -> Result<Screenshot, CaptureError> {
+        ensure_permission()?;
+        let image = capture_window(window_handle as CGWindowID)?;
+        let (data, width, height) = cgimage_to_bgra(&image).map_err(CaptureError::PlatformError)?;
+        Ok(Screenshot {
+            data,
+            width,
+            height,
+        })
+    }
+
+    fn capture_display_screenshot(&self, monitor_id: &str) -> Result<Screenshot, CaptureError> {
+        ensure_permission()?;
+        let display_id: CGDirectDisplayID = monitor_id.parse().map_err(|_| {
+            CaptureError::InvalidParameters(format!("Invalid monitor ID: {}", monitor_id))
+        })?;
+        let image = capture_display(display_id)?;
+        let (data, width, height) = cgimage_to_bgra(&image).map_err(CaptureError::PlatformError)?;
+        Ok(Screenshot {
+            data,
+            width,
+            height,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::capture::DEFAULT_JPEG_QUALITY;
+
+    #[test]
+    fn test_is_better_icon_width() {
+        // Prefer the smallest representation covering the fallback size
+        assert!(is_better_icon_width(128, 256));
+        assert!(!is_better_icon_width(512, 256));
+        assert!(is_better_icon_width(256, 64));
+        // Otherwise the largest available
+        assert!(is_better_icon_width(64, 32));
+        assert!(!is_better_icon_width(16, 32));
+    }
 
     #[test]
     fn test_crop_frame_basic() {
@@ -400,7 +535,8 @@ mod tests {
             return;
         }
 
-        let result = capture.capture_display_thumbnail(&monitors[0].id);
+        let result =
+            capture.capture_display_thumbnail(&monitors[0].id, ThumbnailOptions::default());
 
         // Result depends on permission state
         match result {
@@ -409,11 +545,11 @@ mod tests {
                 assert!(thumb.width > 0, "Thumbnail width should be positive");
                 assert!(thumb.height > 0, "Thumbnail height should be positive");
                 assert!(
-                    thumb.width <= THUMBNAIL_MAX_WIDTH,
+                    thumb.width <= ThumbnailOptions::default().max_width,
                     "Thumbnail width should be <= max"
                 );
                 assert!(
-                    thumb.height <= THUMBNAIL_MAX_HEIGHT,
+                    thumb.height <= ThumbnailOptions::default().max_height,
                     "Thumbnail height should be <= max"
                 );
                 println!(
@@ -450,7 +586,8 @@ mod tests {
         let x = 100;
         let y = 100;
 
-        let result = capture.capture_region_preview(&monitor.id, x, y, 200, 200);
+        let result =
+            capture.capture_region_preview(&monitor.id, x, y, 200, 200, DEFAULT_JPEG_QUALITY);
 
         match result {
             Ok(preview) => {
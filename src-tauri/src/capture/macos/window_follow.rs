@@ -0,0 +1,150 @@
+//! Window-follow capture by polling the window's frame.
+//!
+//! The display the window is on is captured with ScreenCaptureKit and each
+//! frame is cropped to the window's area. A polling thread reads the window's
+//! current frame with `CGWindowListCopyWindowInfo` and moves the crop origin
+//! to match.
+
+use super::window_list::{get_window_bounds, WindowBounds};
+use super::{crop_frame, monitor_list, recorder};
+use crate::capture::types::{FrameReceiver, StopHandle};
+use crate::capture::window_follow::follow_origin;
+use crate::capture::MonitorInfo;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// How often the window's frame is read.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Maps window bounds (logical, global) to a crop origin on the display
+/// (physical pixels).
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    monitor_x: i32,
+    monitor_y: i32,
+    scale: f64,
+    /// Crop size, in physical pixels
+    width: u32,
+    height: u32,
+    /// Display size, in physical pixels
+    display_width: u32,
+    display_height: u32,
+}
+
+impl Placement {
+    fn origin(&self, bounds: &WindowBounds) -> (u32, u32) {
+        follow_origin(
+            ((bounds.x - self.monitor_x) as f64 * self.scale).round() as i32,
+            ((bounds.y - self.monitor_y) as f64 * self.scale).round() as i32,
+            self.width,
+            self.height,
+            self.display_width,
+            self.display_height,
+        )
+    }
+}
+
+/// The monitor containing the centre of `bounds`, or the primary monitor.
+fn monitor_for_bounds(monitors: &[MonitorInfo], bounds: &WindowBounds) -> Option<MonitorInfo> {
+    let center_x = bounds.x + (bounds.width / 2) as i32;
+    let center_y = bounds.y + (bounds.height / 2) as i32;
+    monitors
+        .iter()
+        .find(|m| {
+            center_x >= m.x
+                && center_x < m.x + m.width as i32
+                && center_y >= m.y
+                && center_y < m.y + m.height as i32
+        })
+        .or_else(|| monitors.iter().find(|m| m.is_primary))
+        .cloned()
+}
+
+/// Start capturing the screen area of a window, following it as it moves.
+///
+/// Returns a frame receiver and stop handle.
+pub fn start_capture(window_id: u32) -> Result<(FrameReceiver, StopHandle), String> {
+    let bounds =
+        get_window_bounds(window_id).ok_or_else(|| format!("Window {} not found", window_id))?;
+    let monitor = monitor_for_bounds(&monitor_list::list_monitors(), &bounds)
+        .ok_or_else(|| format!("No display found for window {}", window_id))?;
+    let display_id: u32 = monitor
+        .id
+        .parse()
+        .map_err(|_| format!("Invalid monitor ID: {}", monitor.id))?;
+
+    let scale = monitor.scale_factor;
+    let display_width = ((monitor.width as f64) * scale).round() as u32;
+    let display_height = ((monitor.height as f64) * scale).round() as u32;
+    let placement = Placement {
+        monitor_x: monitor.x,
+        monitor_y: monitor.y,
+        scale,
+        width: (((bounds.width as f64) * scale).round() as u32).min(display_width),
+        height: (((bounds.height as f64) * scale).round() as u32).min(display_height),
+        display_width,
+        display_height,
+    };
+    let origin = Arc::new(Mutex::new(placement.origin(&bounds)));
+
+    tracing::debug!(
+        "[macOS] Following window {} on display {}: {}x{} at {:?}",
+        window_id,
+        display_id,
+        placement.width,
+        placement.height,
+        origin.lock().unwrap_or_else(|e| e.into_inner())
+    );
+
+    let (mut display_rx, stop_handle) =
+        recorder::start_display_capture(display_id, display_width, display_height)?;
+
+    // Track the window's position
+    {
+        let origin = origin.clone();
+        let stop_flag = stop_handle.clone();
+        std::thread::spawn(move || {
+            while !stop_flag.load(Ordering::Relaxed) {
+                std::thread::sleep(POLL_INTERVAL);
+                match get_window_bounds(window_id) {
+                    Some(bounds) => {
+                        *origin.lock().unwrap_or_else(|e| e.into_inner()) =
+                            placement.origin(&bounds);
+                    }
+                    None => {
+                        tracing::debug!("[macOS] Followed window closed, stopping capture");
+                        stop_flag.store(true, Ordering::Relaxed);
+                    }
+                }
+            }
+        });
+    }
+
+    // Crop display frames to the window's current area
+    let (tx, rx) = mpsc::channel(3);
+    let stop_flag = stop_handle.clone();
+    tokio::spawn(async move {
+        while !stop_flag.load(Ordering::Relaxed) {
+            // Use timeout to periodically check stop flag
+            match tokio::time::timeout(Duration::from_millis(100), display_rx.recv()).await {
+                Ok(Some(frame)) => {
+                    let (x, y) = *origin.lock().unwrap_or_else(|e| e.into_inner());
+                    if let Some(cropped) =
+                        crop_frame(&frame, x, y, placement.width, placement.height)
+                    {
+                        if tx.send(cropped).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => continue,
+            }
+        }
+        tracing::debug!("[macOS] Window follow cropping task finished");
+    });
+
+    Ok((rx, stop_handle))
+}
@@ -24,18 +24,18 @@ extern "C" {
 
 /// Window bounds from Core Graphics
 #[derive(Debug, Clone, Copy, Default)]
-struct WindowBounds {
-    x: i32,
-    y: i32,
-    width: u32,
-    height: u32,
+pub(super) struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
 }
 
 /// Get window bounds for a specific window ID using Core Graphics.
 ///
 /// This uses CGWindowListCopyWindowInfo to get the kCGWindowBounds dictionary
 /// which contains X, Y, Width, Height of the window frame.
-fn get_window_bounds(window_id: CGWindowID) -> Option<WindowBounds> {
+pub(super) fn get_window_bounds(window_id: CGWindowID) -> Option<WindowBounds> {
     // Get window info for this specific window
     let info_array =
         CGDisplay::window_list_info(kCGWindowListOptionIncludingWindow, Some(window_id))?;
@@ -209,6 +209,8 @@ pub fn list_windows() -> Vec<WindowInfo> {
             y: bounds.y,
             width: bounds.width,
             height: bounds.height,
+            workspace_id: None,
+            workspace_name: None,
         });
     }
 
@@ -0,0 +1,184 @@
+//! Automatic gain control for microphone input.
+//!
+//! Brings quiet speakers up to a target level before the audio is encoded.
+//! The level is the RMS over a sliding one-second window, in dBFS relative
+//! to a full-scale (±1.0) signal. Gain rises slowly towards the target so
+//! pauses and breaths don't pump the noise floor, and falls quickly when the
+//! level jumps so loud speech doesn't clip.
+//!
+//! The encoder runs a recording's audio through this stage when the
+//! microphone is its only source and AGC is on, whichever backend captured
+//! it; vtx-engine's own AGC is turned off for that recording so the gain is
+//! applied once. With system audio also recorded the mix is left alone and
+//! vtx-engine's AGC levels the microphone before mixing.
+
+/// Length of the sliding RMS window, in seconds.
+const WINDOW_SECS: f32 = 1.0;
+
+/// Most the signal is boosted, in dB.
+pub const MAX_GAIN_DB: f32 = 20.0;
+
+/// Most the signal is attenuated, in dB.
+const MIN_GAIN_DB: f32 = -20.0;
+
+/// Time constant of gain increases, in seconds.
+const ATTACK_SECS: f32 = 3.0;
+
+/// Time constant of gain decreases, in seconds.
+const RELEASE_SECS: f32 = 0.05;
+
+/// Below this level the input is treated as silence and the gain is held.
+const SILENCE_FLOOR_DBFS: f32 = -60.0;
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Per-sample smoothing coefficient for time constant `secs`.
+fn smoothing_coeff(secs: f32, sample_rate: u32) -> f32 {
+    (-1.0 / (secs * sample_rate as f32)).exp()
+}
+
+/// Gain stage normalizing mono microphone audio to a target level.
+pub struct MicAgc {
+    target_rms: f32,
+    /// Squared samples of the RMS window, used as a ring buffer
+    squares: Vec<f32>,
+    pos: usize,
+    filled: usize,
+    sum_squares: f64,
+    gain: f32,
+    attack_coeff: f32,
+    release_coeff: f32,
+}
+
+impl MicAgc {
+    /// Create a stage for audio at `sample_rate` aiming for
+    /// `target_level_dbfs`.
+    pub fn new(sample_rate: u32, target_level_dbfs: f32) -> Self {
+        let window_len = ((sample_rate as f32 * WINDOW_SECS) as usize).max(1);
+        Self {
+            target_rms: db_to_linear(target_level_dbfs),
+            squares: vec![0.0; window_len],
+            pos: 0,
+            filled: 0,
+            sum_squares: 0.0,
+            gain: 1.0,
+            attack_coeff: smoothing_coeff(ATTACK_SECS, sample_rate),
+            release_coeff: smoothing_coeff(RELEASE_SECS, sample_rate),
+        }
+    }
+
+    /// Current gain, in dB.
+    pub fn gain_db(&self) -> f32 {
+        20.0 * self.gain.log10()
+    }
+
+    /// Apply the gain to `samples` in place.
+    pub fn process(&mut self, samples: &mut [f32]) {
+        let min_gain = db_to_linear(MIN_GAIN_DB);
+        let max_gain = db_to_linear(MAX_GAIN_DB);
+        let silence_floor = db_to_linear(SILENCE_FLOOR_DBFS);
+
+        for sample in samples.iter_mut() {
+            let square = *sample * *sample;
+            self.sum_squares += square as f64 - self.squares[self.pos] as f64;
+            self.squares[self.pos] = square;
+            self.pos = (self.pos + 1) % self.squares.len();
+            self.filled = (self.filled + 1).min(self.squares.len());
+
+            let rms = (self.sum_squares.max(0.0) / self.filled as f64).sqrt() as f32;
+            if rms > silence_floor {
+                let desired = (self.target_rms / rms).clamp(min_gain, max_gain);
+                let coeff = if desired > self.gain {
+                    self.attack_coeff
+                } else {
+                    self.release_coeff
+                };
+                self.gain = desired + coeff * (self.gain - desired);
+            }
+
+            *sample = (*sample * self.gain).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48_000;
+
+    /// A 440 Hz sine whose RMS level ramps linearly (in dB) from `from_dbfs`
+    /// to `to_dbfs` over `secs` seconds.
+    fn ramp(from_dbfs: f32, to_dbfs: f32, secs: f32) -> Vec<f32> {
+        let len = (SAMPLE_RATE as f32 * secs) as usize;
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let level = from_dbfs + (to_dbfs - from_dbfs) * t / secs;
+                let peak = db_to_linear(level) * std::f32::consts::SQRT_2;
+                peak * (2.0 * std::f32::consts::PI * 440.0 * t).sin()
+            })
+            .collect()
+    }
+
+    fn rms_dbfs(samples: &[f32]) -> f32 {
+        let mean_square =
+            samples.iter().map(|s| (*s as f64).powi(2)).sum::<f64>() / samples.len() as f64;
+        10.0 * mean_square.log10() as f32
+    }
+
+    /// Process `samples` in 10 ms chunks, checking the gain stays in range.
+    fn run(agc: &mut MicAgc, samples: &mut [f32]) {
+        for chunk in samples.chunks_mut(SAMPLE_RATE as usize / 100) {
+            agc.process(chunk);
+            assert!(agc.gain_db() <= MAX_GAIN_DB + 0.01);
+            assert!(agc.gain_db() >= MIN_GAIN_DB - 0.01);
+        }
+    }
+
+    #[test]
+    fn test_quiet_ramp_normalized_to_target() {
+        let mut agc = MicAgc::new(SAMPLE_RATE, -20.0);
+        let mut samples = ramp(-50.0, -32.0, 4.0);
+        samples.extend(ramp(-32.0, -32.0, 12.0));
+        run(&mut agc, &mut samples);
+
+        let last_second = &samples[samples.len() - SAMPLE_RATE as usize..];
+        let level = rms_dbfs(last_second);
+        assert!((level + 20.0).abs() < 1.0, "output level {} dBFS", level);
+    }
+
+    #[test]
+    fn test_gain_capped() {
+        let mut agc = MicAgc::new(SAMPLE_RATE, -20.0);
+        let mut samples = ramp(-50.0, -50.0, 20.0);
+        run(&mut agc, &mut samples);
+
+        assert!((agc.gain_db() - MAX_GAIN_DB).abs() < 0.5);
+        let last_second = &samples[samples.len() - SAMPLE_RATE as usize..];
+        assert!((rms_dbfs(last_second) + 30.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_fast_release_on_loud_input() {
+        let mut agc = MicAgc::new(SAMPLE_RATE, -20.0);
+        run(&mut agc, &mut ramp(-30.0, -30.0, 10.0));
+        assert!(agc.gain_db() > 8.0);
+
+        let mut loud = ramp(-10.0, -10.0, 0.3);
+        run(&mut agc, &mut loud);
+        assert!(agc.gain_db() < 0.0, "gain {} dB", agc.gain_db());
+        assert!(loud.iter().all(|s| s.abs() <= 1.0));
+    }
+
+    #[test]
+    fn test_silence_holds_gain() {
+        let mut agc = MicAgc::new(SAMPLE_RATE, -20.0);
+        let mut silence = vec![0.0; SAMPLE_RATE as usize * 2];
+        run(&mut agc, &mut silence);
+        assert_eq!(agc.gain_db(), 0.0);
+        assert!(silence.iter().all(|s| *s == 0.0));
+    }
+}
@@ -7,9 +7,24 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+pub mod color_profile;
 pub mod error;
+pub mod frame_limit;
+pub mod frame_pool;
+pub mod gpu_memory;
+pub mod highlight_style;
+pub mod mic_agc;
+pub mod monitor_watch;
+pub mod power_watch;
+pub mod probe;
+pub mod registry;
+#[cfg(feature = "integration-tests")]
+pub mod synthetic;
 pub mod thumbnail;
+pub mod thumbnail_pool;
 pub mod types;
+pub mod webcam;
+pub mod window_follow;
 
 // Platform-specific modules
 #[cfg(target_os = "linux")]
@@ -26,7 +41,16 @@ pub use omnirec_types::{
 };
 
 // Re-export local error types
-pub use error::{CaptureError, EnumerationError};
+pub use error::{CaptureError, EnumerationError, FfmpegError, PipeWireError, PortalError};
+
+// Re-export highlight style types
+pub use highlight_style::{HighlightAnimation, HighlightStyle};
+
+// Re-export backend probe types
+pub use probe::BackendProbeResult;
+
+// Re-export backend registry types
+pub use registry::{get_capture_backend, list_available_backends, BackendId, BackendInfo};
 
 // Re-export runtime types (service-internal, not for IPC)
 pub use types::{CapturedFrame, FrameReceiver, StopHandle};
@@ -34,9 +58,11 @@ pub use types::{CapturedFrame, FrameReceiver, StopHandle};
 // Re-export thumbnail utilities (used by platform implementations)
 #[allow(unused_imports)]
 pub use thumbnail::{
-    bgra_to_jpeg_thumbnail, PREVIEW_MAX_HEIGHT, PREVIEW_MAX_WIDTH, THUMBNAIL_MAX_HEIGHT,
-    THUMBNAIL_MAX_WIDTH,
+    bgra_to_jpeg_thumbnail, save_bgra_png, ThumbnailOptions, DEFAULT_JPEG_QUALITY,
+    PREVIEW_MAX_HEIGHT, PREVIEW_MAX_WIDTH, THUMBNAIL_DIMENSION_MAX, THUMBNAIL_DIMENSION_MIN,
+    THUMBNAIL_MAX_HEIGHT, THUMBNAIL_MAX_WIDTH,
 };
+pub use thumbnail_pool::{ThumbnailPool, DEFAULT_THUMBNAIL_POOL_SIZE, MAX_THUMBNAIL_POOL_SIZE};
 
 // Platform-specific backend aliases
 #[cfg(target_os = "linux")]
@@ -86,12 +112,20 @@ pub trait CaptureBackend: Send + Sync {
         width: u32,
         height: u32,
     ) -> Result<(FrameReceiver, StopHandle), CaptureError>;
+
+    /// Whether this backend can capture on the current system.
+    ///
+    /// Used by the backend registry to skip backends whose runtime
+    /// dependencies are missing.
+    fn is_available(&self) -> bool {
+        true
+    }
 }
 
 /// Trait for visual highlight rendering.
 pub trait HighlightProvider: Send + Sync {
     /// Show a highlight border around the specified area.
-    fn show_highlight(&self, x: i32, y: i32, width: i32, height: i32);
+    fn show_highlight(&self, x: i32, y: i32, width: i32, height: i32, style: &HighlightStyle);
 }
 
 /// Trait for audio device enumeration operations.
@@ -111,24 +145,40 @@ pub struct ThumbnailResult {
     pub height: u32,
 }
 
+/// A full-resolution still image of a window or display.
+#[derive(Debug, Clone)]
+pub struct Screenshot {
+    /// BGRA pixel data
+    pub data: Vec<u8>,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+}
+
 /// Trait for thumbnail capture operations.
 pub trait ThumbnailCapture: Send + Sync {
     /// Capture a thumbnail of a window.
     ///
-    /// Returns a base64-encoded JPEG image scaled to fit within max dimensions.
+    /// Returns a base64-encoded JPEG image scaled to fit within `options`.
     fn capture_window_thumbnail(
         &self,
         window_handle: isize,
+        options: ThumbnailOptions,
     ) -> Result<ThumbnailResult, CaptureError>;
 
     /// Capture a thumbnail of a display.
     ///
-    /// Returns a base64-encoded JPEG image scaled to fit within max dimensions.
-    fn capture_display_thumbnail(&self, monitor_id: &str) -> Result<ThumbnailResult, CaptureError>;
+    /// Returns a base64-encoded JPEG image scaled to fit within `options`.
+    fn capture_display_thumbnail(
+        &self,
+        monitor_id: &str,
+        options: ThumbnailOptions,
+    ) -> Result<ThumbnailResult, CaptureError>;
 
     /// Capture a preview of a screen region.
     ///
-    /// Returns a base64-encoded JPEG image of the specified region.
+    /// Returns a base64-encoded JPEG image of the specified region, encoded at `quality`.
     fn capture_region_preview(
         &self,
         monitor_id: &str,
@@ -136,10 +186,20 @@ pub trait ThumbnailCapture: Send + Sync {
         y: i32,
         width: u32,
         height: u32,
+        quality: u8,
     ) -> Result<ThumbnailResult, CaptureError>;
+
+    /// Capture a window at full resolution, for a screenshot.
+    fn capture_window_screenshot(&self, window_handle: isize) -> Result<Screenshot, CaptureError>;
+
+    /// Capture a display at full resolution, for a screenshot.
+    fn capture_display_screenshot(&self, monitor_id: &str) -> Result<Screenshot, CaptureError>;
 }
 
-/// Get the platform-specific capture backend.
+/// Get the platform-specific backend.
+///
+/// Use [`get_capture_backend`] to start recordings so that backend
+/// availability and the user's preference are respected.
 pub fn get_backend() -> PlatformBackend {
     PlatformBackend::new()
 }
@@ -158,10 +218,18 @@ pub fn list_monitors() -> Vec<MonitorInfo> {
     backend.list_monitors().unwrap_or_default()
 }
 
+/// Process name of a capturable window, used to look up its application icon.
+pub fn window_process_name(window_handle: isize) -> Option<String> {
+    list_windows()
+        .into_iter()
+        .find(|w| w.handle == window_handle)
+        .map(|w| w.process_name)
+}
+
 /// Show a highlight border around the specified area.
-pub fn show_highlight(x: i32, y: i32, width: i32, height: i32) {
+pub fn show_highlight(x: i32, y: i32, width: i32, height: i32, style: &HighlightStyle) {
     let backend = get_backend();
-    backend.show_highlight(x, y, width, height);
+    backend.show_highlight(x, y, width, height, style);
 }
 
 /// List all available audio sources.
@@ -0,0 +1,57 @@
+//! Monitor configuration change notifications.
+//!
+//! Each platform reports resolution changes and monitor hot-plug through its
+//! own mechanism (see the `monitor_watch` module of each backend). Every
+//! change bumps a version counter and is broadcast as
+//! [`ServiceEvent::MonitorConfigChanged`](crate::state::ServiceEvent), which
+//! the app forwards to the frontend so it can refresh display thumbnails.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Number of monitor configuration changes seen since startup.
+static MONITOR_CONFIG_VERSION: AtomicU64 = AtomicU64::new(0);
+
+/// Current monitor configuration version. Changes whenever monitors are
+/// added, removed or change resolution, for clients that poll.
+pub fn monitor_config_version() -> u64 {
+    MONITOR_CONFIG_VERSION.load(Ordering::SeqCst)
+}
+
+/// Start the platform monitor watcher.
+///
+/// Must be called from the main thread on macOS, whose display callbacks
+/// are delivered on the main run loop.
+pub fn start() {
+    #[cfg(target_os = "linux")]
+    super::linux::monitor_watch::start(monitor_config_changed);
+    #[cfg(target_os = "macos")]
+    super::macos::monitor_watch::start(monitor_config_changed);
+    #[cfg(target_os = "windows")]
+    super::windows::monitor_watch::start(monitor_config_changed);
+}
+
+fn monitor_config_changed() {
+    let version = bump_version();
+    tracing::info!(
+        "[MonitorWatch] Monitor configuration changed (version {})",
+        version
+    );
+    crate::state::get_recording_manager().notify_monitor_config_changed(version);
+}
+
+fn bump_version() -> u64 {
+    MONITOR_CONFIG_VERSION.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bump_version_increments() {
+        let before = monitor_config_version();
+        let bumped = bump_version();
+        assert!(bumped > before);
+        assert!(monitor_config_version() >= bumped);
+    }
+}
@@ -0,0 +1,39 @@
+//! System sleep and wake notifications.
+//!
+//! Each platform reports power transitions through its own mechanism (see
+//! the `power_watch` module of each backend). A recording in progress is
+//! suspended when the system is about to sleep and resumed when it wakes,
+//! instead of being cut off or left with a frozen frame for the whole sleep.
+
+/// A system power transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerEvent {
+    /// The system is about to sleep or hibernate
+    Sleep,
+    /// The system has woken up
+    Wake,
+}
+
+/// Start the platform power watcher.
+///
+/// Must be called from the main thread on macOS, whose workspace
+/// notifications are delivered on the main run loop.
+pub fn start() {
+    #[cfg(target_os = "linux")]
+    super::linux::power_watch::start(power_event);
+    #[cfg(target_os = "macos")]
+    super::macos::power_watch::start(power_event);
+    #[cfg(target_os = "windows")]
+    super::windows::power_watch::start(power_event);
+}
+
+fn power_event(event: PowerEvent) {
+    tracing::info!("[PowerWatch] {:?}", event);
+    tauri::async_runtime::spawn(async move {
+        let manager = crate::state::get_recording_manager();
+        match event {
+            PowerEvent::Sleep => manager.suspend_recording().await,
+            PowerEvent::Wake => manager.resume_recording().await,
+        }
+    });
+}
@@ -0,0 +1,101 @@
+//! Startup probe for capture backend dependencies.
+//!
+//! Each platform checks for the system pieces its capture backend relies on
+//! (shared libraries, daemons, compositor protocols, permissions) so missing
+//! dependencies are reported up front instead of failing at capture time.
+
+use serde::Serialize;
+use std::sync::RwLock;
+
+/// Outcome of probing the platform capture backend.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BackendProbeResult {
+    /// True when no errors were found (warnings are allowed)
+    pub ok: bool,
+    /// Problems that degrade functionality but don't prevent recording
+    pub warnings: Vec<String>,
+    /// Problems that prevent recording
+    pub errors: Vec<String>,
+}
+
+impl BackendProbeResult {
+    /// Record a non-fatal problem.
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.warnings.push(message.into());
+    }
+
+    /// Record a fatal problem.
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.errors.push(message.into());
+    }
+
+    /// Finalize the result, deriving `ok` from the collected errors.
+    pub fn finish(mut self) -> Self {
+        self.ok = self.errors.is_empty();
+        self
+    }
+}
+
+/// Result of the most recent startup probe.
+static PROBE_RESULT: RwLock<Option<BackendProbeResult>> = RwLock::new(None);
+
+/// Probe the capture backend for the current platform.
+pub fn probe_capture_backend() -> BackendProbeResult {
+    #[cfg(target_os = "linux")]
+    let result = super::linux::probe::probe_capture_backend();
+    #[cfg(target_os = "macos")]
+    let result = super::macos::probe::probe_capture_backend();
+    #[cfg(target_os = "windows")]
+    let result = super::windows::probe::probe_capture_backend();
+
+    result.finish()
+}
+
+/// Run the probe, log its findings and keep the result for later queries.
+pub fn run_startup_probe() -> BackendProbeResult {
+    let result = probe_capture_backend();
+
+    for warning in &result.warnings {
+        tracing::warn!("[Probe] {}", warning);
+    }
+    for error in &result.errors {
+        tracing::error!("[Probe] {}", error);
+    }
+    if result.ok {
+        tracing::info!(
+            "[Probe] Capture backend OK ({} warning(s))",
+            result.warnings.len()
+        );
+    }
+
+    *PROBE_RESULT.write().unwrap() = Some(result.clone());
+    result
+}
+
+/// Get the startup probe result, probing now if startup hasn't done so yet.
+pub fn get_probe_result() -> BackendProbeResult {
+    if let Some(result) = PROBE_RESULT.read().unwrap().clone() {
+        return result;
+    }
+    run_startup_probe()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finish_derives_ok_from_errors() {
+        let mut result = BackendProbeResult::default();
+        result.warn("thumbnails unavailable");
+        let result = result.finish();
+        assert!(result.ok);
+        assert_eq!(result.warnings.len(), 1);
+
+        let mut result = BackendProbeResult::default();
+        result.error("PipeWire is not running");
+        let result = result.finish();
+        assert!(!result.ok);
+        assert_eq!(result.errors, vec!["PipeWire is not running".to_string()]);
+    }
+}
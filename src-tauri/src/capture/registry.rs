@@ -0,0 +1,314 @@
+//! Capture backend registry.
+//!
+//! Holds every capture backend compiled into this build, ordered by priority.
+//! Recording selects the highest-priority backend that reports itself as
+//! available, unless the user has pinned a preferred backend in the config.
+
+use super::{CaptureBackend, CaptureError};
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+
+/// Identifier for a capture backend implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendId {
+    /// Linux: xdg-desktop-portal screencast + PipeWire.
+    Portal,
+    /// macOS: ScreenCaptureKit.
+    ScreenCaptureKit,
+    /// Windows: Windows.Graphics.Capture.
+    WindowsGraphicsCapture,
+    /// Generated frames for the integration tests.
+    #[cfg(feature = "integration-tests")]
+    Synthetic,
+}
+
+impl BackendId {
+    /// Human-readable backend name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            BackendId::Portal => "XDG Desktop Portal (PipeWire)",
+            BackendId::ScreenCaptureKit => "ScreenCaptureKit",
+            BackendId::WindowsGraphicsCapture => "Windows Graphics Capture",
+            #[cfg(feature = "integration-tests")]
+            BackendId::Synthetic => "Synthetic (integration tests)",
+        }
+    }
+}
+
+/// Backend description returned to the frontend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackendInfo {
+    /// Backend identifier
+    pub id: BackendId,
+    /// Human-readable name
+    pub name: String,
+    /// Selection priority (higher wins)
+    pub priority: u32,
+    /// Whether the backend can be used on this system right now
+    pub available: bool,
+}
+
+struct RegisteredBackend {
+    id: BackendId,
+    priority: u32,
+    backend: Box<dyn CaptureBackend>,
+}
+
+/// Priority-ordered collection of capture backends.
+#[derive(Default)]
+pub struct BackendRegistry {
+    entries: Vec<RegisteredBackend>,
+}
+
+impl BackendRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry containing the backends built for this platform.
+    pub fn with_platform_backends() -> Self {
+        let mut registry = Self::new();
+
+        #[cfg(target_os = "linux")]
+        registry.register(
+            BackendId::Portal,
+            100,
+            Box::new(super::linux::LinuxBackend::new()),
+        );
+        #[cfg(target_os = "macos")]
+        registry.register(
+            BackendId::ScreenCaptureKit,
+            100,
+            Box::new(super::macos::MacOSBackend::new()),
+        );
+        #[cfg(target_os = "windows")]
+        registry.register(
+            BackendId::WindowsGraphicsCapture,
+            100,
+            Box::new(super::windows::WindowsBackend::new()),
+        );
+        #[cfg(feature = "integration-tests")]
+        registry.register(
+            BackendId::Synthetic,
+            u32::MAX,
+            Box::new(super::synthetic::SyntheticBackend::new()),
+        );
+
+        registry
+    }
+
+    /// Register a backend. Replaces any existing entry with the same ID.
+    pub fn register(&mut self, id: BackendId, priority: u32, backend: Box<dyn CaptureBackend>) {
+        self.entries.retain(|entry| entry.id != id);
+        self.entries.push(RegisteredBackend {
+            id,
+            priority,
+            backend,
+        });
+        // Stable sort keeps registration order for equal priorities.
+        self.entries
+            .sort_by_key(|entry| std::cmp::Reverse(entry.priority));
+    }
+
+    /// Get the backend to record with.
+    ///
+    /// The preferred backend is used if it is registered and available;
+    /// otherwise the highest-priority available backend is returned.
+    pub fn get_backend(
+        &self,
+        preferred: Option<BackendId>,
+    ) -> Result<(BackendId, &dyn CaptureBackend), CaptureError> {
+        if let Some(id) = preferred {
+            match self.entries.iter().find(|entry| entry.id == id) {
+                Some(entry) if entry.backend.is_available() => {
+                    return Ok((entry.id, entry.backend.as_ref()));
+                }
+                _ => tracing::warn!(
+                    "[Capture] Preferred backend {:?} is not available, falling back",
+                    id
+                ),
+            }
+        }
+
+        self.entries
+            .iter()
+            .find(|entry| entry.backend.is_available())
+            .map(|entry| (entry.id, entry.backend.as_ref()))
+            .ok_or_else(|| CaptureError::PlatformError("No capture backend available".to_string()))
+    }
+
+    /// Describe all registered backends, highest priority first.
+    pub fn list(&self) -> Vec<BackendInfo> {
+        self.entries
+            .iter()
+            .map(|entry| BackendInfo {
+                id: entry.id,
+                name: entry.id.display_name().to_string(),
+                priority: entry.priority,
+                available: entry.backend.is_available(),
+            })
+            .collect()
+    }
+}
+
+// =============================================================================
+// Global registry
+// =============================================================================
+
+static REGISTRY: OnceLock<BackendRegistry> = OnceLock::new();
+static PREFERRED_BACKEND: RwLock<Option<BackendId>> = RwLock::new(None);
+
+/// Get the process-wide backend registry.
+pub fn registry() -> &'static BackendRegistry {
+    REGISTRY.get_or_init(BackendRegistry::with_platform_backends)
+}
+
+/// Set the user's preferred backend (`None` selects automatically).
+pub fn set_preferred_backend(id: Option<BackendId>) {
+    *PREFERRED_BACKEND.write().unwrap() = id;
+}
+
+/// Get the user's preferred backend.
+pub fn preferred_backend() -> Option<BackendId> {
+    *PREFERRED_BACKEND.read().unwrap()
+}
+
+/// Get the capture backend to record with, honouring the preference.
+pub fn get_capture_backend() -> Result<&'static dyn CaptureBackend, CaptureError> {
+    let (id, backend) = registry().get_backend(preferred_backend())?;
+    tracing::debug!("[Capture] Using backend {:?}", id);
+    Ok(backend)
+}
+
+/// List all registered backends for this platform.
+pub fn list_available_backends() -> Vec<BackendInfo> {
+    registry().list()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::{CaptureRegion, FrameReceiver, StopHandle};
+
+    struct MockBackend {
+        available: bool,
+    }
+
+    impl CaptureBackend for MockBackend {
+        fn start_window_capture(
+            &self,
+            _window_handle: isize,
+        ) -> Result<(FrameReceiver, StopHandle), CaptureError> {
+            Err(CaptureError::NotImplemented("mock".to_string()))
+        }
+
+        fn start_region_capture(
+            &self,
+            _region: CaptureRegion,
+        ) -> Result<(FrameReceiver, StopHandle), CaptureError> {
+            Err(CaptureError::NotImplemented("mock".to_string()))
+        }
+
+        fn start_display_capture(
+            &self,
+            _monitor_id: String,
+            _width: u32,
+            _height: u32,
+        ) -> Result<(FrameReceiver, StopHandle), CaptureError> {
+            Err(CaptureError::NotImplemented("mock".to_string()))
+        }
+
+        fn is_available(&self) -> bool {
+            self.available
+        }
+    }
+
+    fn mock(available: bool) -> Box<dyn CaptureBackend> {
+        Box::new(MockBackend { available })
+    }
+
+    #[test]
+    fn test_highest_priority_available_backend_wins() {
+        let mut registry = BackendRegistry::new();
+        registry.register(BackendId::Portal, 10, mock(true));
+        registry.register(BackendId::WindowsGraphicsCapture, 50, mock(true));
+        registry.register(BackendId::ScreenCaptureKit, 30, mock(true));
+
+        let (id, _) = registry.get_backend(None).unwrap();
+        assert_eq!(id, BackendId::WindowsGraphicsCapture);
+
+        let order: Vec<BackendId> = registry.list().iter().map(|b| b.id).collect();
+        assert_eq!(
+            order,
+            vec![
+                BackendId::WindowsGraphicsCapture,
+                BackendId::ScreenCaptureKit,
+                BackendId::Portal
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unavailable_backend_is_skipped() {
+        let mut registry = BackendRegistry::new();
+        registry.register(BackendId::Portal, 100, mock(false));
+        registry.register(BackendId::ScreenCaptureKit, 10, mock(true));
+
+        let (id, _) = registry.get_backend(None).unwrap();
+        assert_eq!(id, BackendId::ScreenCaptureKit);
+        assert!(!registry.list()[0].available);
+    }
+
+    #[test]
+    fn test_preferred_backend_overrides_priority() {
+        let mut registry = BackendRegistry::new();
+        registry.register(BackendId::Portal, 100, mock(true));
+        registry.register(BackendId::ScreenCaptureKit, 10, mock(true));
+
+        let (id, _) = registry
+            .get_backend(Some(BackendId::ScreenCaptureKit))
+            .unwrap();
+        assert_eq!(id, BackendId::ScreenCaptureKit);
+    }
+
+    #[test]
+    fn test_unavailable_preferred_backend_falls_back() {
+        let mut registry = BackendRegistry::new();
+        registry.register(BackendId::Portal, 100, mock(true));
+        registry.register(BackendId::ScreenCaptureKit, 10, mock(false));
+
+        let (id, _) = registry
+            .get_backend(Some(BackendId::ScreenCaptureKit))
+            .unwrap();
+        assert_eq!(id, BackendId::Portal);
+
+        // Unregistered preference also falls back.
+        let (id, _) = registry
+            .get_backend(Some(BackendId::WindowsGraphicsCapture))
+            .unwrap();
+        assert_eq!(id, BackendId::Portal);
+    }
+
+    #[test]
+    fn test_no_available_backend_is_error() {
+        let mut registry = BackendRegistry::new();
+        registry.register(BackendId::Portal, 100, mock(false));
+
+        assert!(registry.get_backend(None).is_err());
+        assert!(BackendRegistry::new().get_backend(None).is_err());
+    }
+
+    #[test]
+    fn test_register_replaces_existing_id() {
+        let mut registry = BackendRegistry::new();
+        registry.register(BackendId::Portal, 100, mock(false));
+        registry.register(BackendId::Portal, 5, mock(true));
+
+        let list = registry.list();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].priority, 5);
+        assert!(list[0].available);
+    }
+}
@@ -0,0 +1,111 @@
+//! Synthetic capture backend for the end-to-end integration tests.
+//!
+//! Built only with the `integration-tests` feature, which registers it above
+//! the platform backends. Every capture produces [`SYNTHETIC_FRAME_COUNT`]
+//! BGRA frames of [`SYNTHETIC_WIDTH`]x[`SYNTHETIC_HEIGHT`] at
+//! [`SYNTHETIC_FPS`], whatever the target, then waits to be stopped.
+
+use super::types::{timestamp_us, PixelFormat};
+use super::{
+    CaptureBackend, CaptureError, CaptureRegion, CapturedFrame, FrameReceiver, StopHandle,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+
+/// Frames produced by each capture.
+pub const SYNTHETIC_FRAME_COUNT: u32 = 30;
+
+/// Width of the synthetic frames in pixels.
+pub const SYNTHETIC_WIDTH: u32 = 1280;
+
+/// Height of the synthetic frames in pixels.
+pub const SYNTHETIC_HEIGHT: u32 = 720;
+
+/// Frame rate of the synthetic frames.
+pub const SYNTHETIC_FPS: u32 = 30;
+
+/// Capture backend that produces generated frames instead of the screen.
+#[derive(Debug, Default)]
+pub struct SyntheticBackend;
+
+impl SyntheticBackend {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Start a thread sending the synthetic frames.
+    fn start(&self) -> Result<(FrameReceiver, StopHandle), CaptureError> {
+        let (tx, rx) = mpsc::channel(SYNTHETIC_FRAME_COUNT as usize);
+        let stop: StopHandle = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+
+        std::thread::Builder::new()
+            .name("synthetic-capture".to_string())
+            .spawn(move || {
+                let interval = Duration::from_secs(1) / SYNTHETIC_FPS;
+                let start = Instant::now();
+                for index in 0..SYNTHETIC_FRAME_COUNT {
+                    if thread_stop.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    if tx.blocking_send(frame(index)).is_err() {
+                        return;
+                    }
+                    std::thread::sleep(
+                        (start + interval * (index + 1)).saturating_duration_since(Instant::now()),
+                    );
+                }
+                // Keep the channel open until the recording is stopped
+                while !thread_stop.load(Ordering::Relaxed) && !tx.is_closed() {
+                    std::thread::sleep(Duration::from_millis(20));
+                }
+            })
+            .map_err(|e| CaptureError::PlatformError(e.to_string()))?;
+
+        Ok((rx, stop))
+    }
+}
+
+/// Frame `index`: a gray level that changes from frame to frame.
+fn frame(index: u32) -> CapturedFrame {
+    let level = (index * 255 / SYNTHETIC_FRAME_COUNT) as u8;
+    let mut data = PixelFormat::Bgra.black_frame(SYNTHETIC_WIDTH, SYNTHETIC_HEIGHT);
+    for pixel in data.chunks_exact_mut(4) {
+        pixel[..3].fill(level);
+    }
+    CapturedFrame {
+        width: SYNTHETIC_WIDTH,
+        height: SYNTHETIC_HEIGHT,
+        data: data.into(),
+        pixel_format: PixelFormat::Bgra,
+        captured_at: SystemTime::now(),
+        timestamp_us: timestamp_us(Instant::now()),
+    }
+}
+
+impl CaptureBackend for SyntheticBackend {
+    fn start_window_capture(
+        &self,
+        _window_handle: isize,
+    ) -> Result<(FrameReceiver, StopHandle), CaptureError> {
+        self.start()
+    }
+
+    fn start_region_capture(
+        &self,
+        _region: CaptureRegion,
+    ) -> Result<(FrameReceiver, StopHandle), CaptureError> {
+        self.start()
+    }
+
+    fn start_display_capture(
+        &self,
+        _monitor_id: String,
+        _width: u32,
+        _height: u32,
+    ) -> Result<(FrameReceiver, StopHandle), CaptureError> {
+        self.start()
+    }
+}
@@ -1,25 +1,67 @@
 //! Thumbnail generation utilities.
 //!
 //! This module provides functions for scaling captured frames and encoding
-//! them as JPEG images for use as thumbnails.
+//! them as JPEG images for use as thumbnails, and for saving full-resolution
+//! frames as PNG screenshots.
 
 use base64::{engine::general_purpose::STANDARD, Engine};
-use image::{ImageBuffer, Rgb};
+use image::{ImageBuffer, ImageFormat, Rgb};
+use std::path::Path;
 
-/// Maximum thumbnail width in pixels.
+/// Default maximum thumbnail width in pixels.
 pub const THUMBNAIL_MAX_WIDTH: u32 = 320;
 
-/// Maximum thumbnail height in pixels.
+/// Default maximum thumbnail height in pixels.
 pub const THUMBNAIL_MAX_HEIGHT: u32 = 180;
 
+/// Smallest configurable thumbnail dimension in pixels.
+pub const THUMBNAIL_DIMENSION_MIN: u32 = 64;
+
+/// Largest configurable thumbnail dimension in pixels.
+pub const THUMBNAIL_DIMENSION_MAX: u32 = 1920;
+
 /// Maximum region preview width in pixels.
 pub const PREVIEW_MAX_WIDTH: u32 = 400;
 
 /// Maximum region preview height in pixels.
 pub const PREVIEW_MAX_HEIGHT: u32 = 300;
 
-/// JPEG quality for thumbnails (0-100).
-const JPEG_QUALITY: u8 = 75;
+/// Default JPEG quality for thumbnails (0-100).
+pub const DEFAULT_JPEG_QUALITY: u8 = 75;
+
+/// Maximum edge length of an application icon shown in place of a window thumbnail.
+pub const ICON_FALLBACK_SIZE: u32 = 128;
+
+/// Color transparent icon pixels are flattened onto, as BGR (JPEG has no alpha).
+const ICON_BACKGROUND_BGR: [u8; 3] = [0x30, 0x30, 0x30];
+
+/// Output bounds and encoding quality for window and display thumbnails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThumbnailOptions {
+    /// Maximum thumbnail width in pixels
+    pub max_width: u32,
+    /// Maximum thumbnail height in pixels
+    pub max_height: u32,
+    /// JPEG quality (0-100)
+    pub quality: u8,
+}
+
+impl ThumbnailOptions {
+    /// Copy of these options with a different JPEG quality.
+    pub fn with_quality(self, quality: u8) -> Self {
+        Self { quality, ..self }
+    }
+}
+
+impl Default for ThumbnailOptions {
+    fn default() -> Self {
+        Self {
+            max_width: THUMBNAIL_MAX_WIDTH,
+            max_height: THUMBNAIL_MAX_HEIGHT,
+            quality: DEFAULT_JPEG_QUALITY,
+        }
+    }
+}
 
 /// Convert BGRA frame data to a scaled JPEG thumbnail as base64.
 ///
@@ -33,6 +75,7 @@ const JPEG_QUALITY: u8 = 75;
 /// * `height` - Frame height in pixels
 /// * `max_width` - Maximum output width
 /// * `max_height` - Maximum output height
+/// * `quality` - JPEG quality (0-100)
 ///
 /// # Returns
 /// A tuple of (base64_string, scaled_width, scaled_height)
@@ -42,6 +85,7 @@ pub fn bgra_to_jpeg_thumbnail(
     height: u32,
     max_width: u32,
     max_height: u32,
+    quality: u8,
 ) -> Result<(String, u32, u32), String> {
     if data.len() < (width * height * 4) as usize {
         return Err(format!(
@@ -65,8 +109,7 @@ pub fn bgra_to_jpeg_thumbnail(
 
     // Encode as JPEG
     let mut jpeg_bytes: Vec<u8> = Vec::new();
-    let mut encoder =
-        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg_bytes, JPEG_QUALITY);
+    let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg_bytes, quality);
     encoder
         .encode_image(&img)
         .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
@@ -77,6 +120,81 @@ pub fn bgra_to_jpeg_thumbnail(
     Ok((base64_str, scaled_width, scaled_height))
 }
 
+/// Save BGRA frame data to `path` as a full-resolution PNG.
+///
+/// Uses the same BGRA→RGB conversion as [`bgra_to_jpeg_thumbnail`], without
+/// scaling.
+pub fn save_bgra_png(data: &[u8], width: u32, height: u32, path: &Path) -> Result<(), String> {
+    if width == 0 || height == 0 || data.len() < (width * height * 4) as usize {
+        return Err(format!(
+            "Buffer too small: expected {} bytes for {}x{}, got {}",
+            width * height * 4,
+            width,
+            height,
+            data.len()
+        ));
+    }
+
+    let rgb_data = fast_downsample_bgra_to_rgb(data, width, height, width, height);
+    let img: ImageBuffer<Rgb<u8>, Vec<u8>> = ImageBuffer::from_raw(width, height, rgb_data)
+        .ok_or_else(|| "Failed to create image buffer".to_string())?;
+
+    img.save_with_format(path, ImageFormat::Png)
+        .map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
+
+/// Encode an application icon as a JPEG thumbnail.
+///
+/// `data` is BGRA with straight (non-premultiplied) alpha. Transparent areas
+/// are flattened onto a dark background and the icon is scaled down to
+/// [`ICON_FALLBACK_SIZE`].
+pub fn icon_to_jpeg_thumbnail(
+    data: &[u8],
+    width: u32,
+    height: u32,
+) -> Result<(String, u32, u32), String> {
+    let flattened = flatten_icon_alpha(data);
+    bgra_to_jpeg_thumbnail(
+        &flattened,
+        width,
+        height,
+        ICON_FALLBACK_SIZE,
+        ICON_FALLBACK_SIZE,
+        DEFAULT_JPEG_QUALITY,
+    )
+}
+
+/// Decode a PNG icon into BGRA pixels with straight alpha.
+///
+/// # Returns
+/// A tuple of (bgra_data, width, height)
+#[cfg_attr(windows, allow(dead_code))]
+pub fn decode_png_icon(bytes: &[u8]) -> Result<(Vec<u8>, u32, u32), String> {
+    let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to decode icon: {}", e))?
+        .to_rgba8();
+    let (width, height) = image.dimensions();
+    let mut data = image.into_raw();
+    for pixel in data.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    Ok((data, width, height))
+}
+
+/// Blend BGRA pixels onto [`ICON_BACKGROUND_BGR`], producing opaque pixels.
+fn flatten_icon_alpha(data: &[u8]) -> Vec<u8> {
+    let mut flattened = Vec::with_capacity(data.len());
+    for pixel in data.chunks_exact(4) {
+        let alpha = pixel[3] as u32;
+        for (channel, background) in pixel[..3].iter().zip(ICON_BACKGROUND_BGR) {
+            let blended = (*channel as u32 * alpha + background as u32 * (255 - alpha)) / 255;
+            flattened.push(blended as u8);
+        }
+        flattened.push(255);
+    }
+    flattened
+}
+
 /// Fast downsample BGRA to RGB using nearest-neighbor sampling.
 ///
 /// This is much faster than creating a full-size image and then resizing,
@@ -113,7 +231,7 @@ fn fast_downsample_bgra_to_rgb(
 }
 
 /// Calculate scaled dimensions that fit within max bounds while preserving aspect ratio.
-fn calculate_scaled_dimensions(
+pub fn calculate_scaled_dimensions(
     width: u32,
     height: u32,
     max_width: u32,
@@ -137,6 +255,7 @@ fn calculate_scaled_dimensions(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::generate_test_frame;
 
     #[test]
     fn test_calculate_scaled_dimensions_landscape() {
@@ -164,15 +283,11 @@ mod tests {
 
     #[test]
     fn test_bgra_to_jpeg_thumbnail() {
-        // Create a small test image (10x10 solid blue in BGRA)
-        let width = 10u32;
-        let height = 10u32;
-        let mut data = Vec::with_capacity((width * height * 4) as usize);
-        for _ in 0..(width * height) {
-            data.extend_from_slice(&[255, 0, 0, 255]); // BGRA blue
-        }
+        let (width, height) = (10u32, 10u32);
+        let frame = generate_test_frame(width, height, 0);
 
-        let result = bgra_to_jpeg_thumbnail(&data, width, height, 320, 180);
+        let result =
+            bgra_to_jpeg_thumbnail(&frame.data, width, height, 320, 180, DEFAULT_JPEG_QUALITY);
         assert!(result.is_ok());
 
         let (base64_str, scaled_w, scaled_h) = result.unwrap();
@@ -181,6 +296,58 @@ mod tests {
         assert_eq!(scaled_h, 10);
     }
 
+    #[test]
+    fn test_save_bgra_png_full_resolution() {
+        let (width, height) = (3u32, 2u32);
+        let mut data = Vec::new();
+        for i in 0..(width * height) as u8 {
+            data.extend_from_slice(&[i * 10, 100, 200, 255]); // BGRA
+        }
+        let path = std::env::temp_dir().join(format!("omnirec-shot-{}.png", std::process::id()));
+
+        save_bgra_png(&data, width, height, &path).unwrap();
+        let saved = image::open(&path).unwrap().to_rgb8();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(saved.dimensions(), (width, height));
+        assert_eq!(saved.get_pixel(0, 0).0, [200, 100, 0]);
+        assert_eq!(saved.get_pixel(2, 1).0, [200, 100, 50]);
+        assert!(save_bgra_png(&data, 4, 2, &path).is_err());
+    }
+
+    #[test]
+    fn test_flatten_icon_alpha() {
+        let data = [
+            10, 20, 30, 255, // Opaque
+            10, 20, 30, 0, // Transparent
+            250, 250, 250, 128, // Half transparent
+        ];
+        let flattened = flatten_icon_alpha(&data);
+        assert_eq!(&flattened[0..4], &[10, 20, 30, 255]);
+        assert_eq!(&flattened[4..8], &[0x30, 0x30, 0x30, 255]);
+        assert_eq!(&flattened[8..12], &[149, 149, 149, 255]);
+    }
+
+    #[test]
+    fn test_icon_to_jpeg_thumbnail_scales_to_fallback_size() {
+        let data = [0, 0, 255, 128].repeat(256 * 256);
+        let (base64_str, w, h) = icon_to_jpeg_thumbnail(&data, 256, 256).unwrap();
+        assert!(!base64_str.is_empty());
+        assert_eq!((w, h), (ICON_FALLBACK_SIZE, ICON_FALLBACK_SIZE));
+    }
+
+    #[test]
+    fn test_decode_png_icon_returns_bgra() {
+        let mut png = Vec::new();
+        image::RgbaImage::from_pixel(2, 1, image::Rgba([1, 2, 3, 4]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let (data, w, h) = decode_png_icon(&png).unwrap();
+        assert_eq!((w, h), (2, 1));
+        assert_eq!(data, vec![3, 2, 1, 4, 3, 2, 1, 4]);
+        assert!(decode_png_icon(b"not a png").is_err());
+    }
+
     #[test]
     fn test_fast_downsample() {
         // 4x4 BGRA image with known pattern
@@ -220,4 +387,35 @@ mod tests {
         // Bottom-right: White -> RGB (255,255,255)
         assert_eq!(&rgb[9..12], &[255, 255, 255]);
     }
+
+    #[test]
+    fn test_lower_quality_produces_smaller_thumbnail() {
+        // Gradient with per-pixel noise so quantization has something to discard
+        let width = 320u32;
+        let height = 180u32;
+        let mut data = Vec::with_capacity((width * height * 4) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let noise = ((x * 31 + y * 17) ^ (x * y)) as u8;
+                data.extend_from_slice(&[
+                    (x as u8).wrapping_add(noise),
+                    (y as u8).wrapping_mul(3),
+                    noise,
+                    255,
+                ]);
+            }
+        }
+
+        let (low, _, _) = bgra_to_jpeg_thumbnail(&data, width, height, 320, 180, 50).unwrap();
+        let (high, _, _) = bgra_to_jpeg_thumbnail(&data, width, height, 320, 180, 90).unwrap();
+        let low_bytes = STANDARD.decode(low).unwrap();
+        let high_bytes = STANDARD.decode(high).unwrap();
+
+        assert!(
+            low_bytes.len() < high_bytes.len(),
+            "quality 50 ({} bytes) should be smaller than quality 90 ({} bytes)",
+            low_bytes.len(),
+            high_bytes.len()
+        );
+    }
 }
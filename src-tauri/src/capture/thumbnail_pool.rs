@@ -0,0 +1,110 @@
+//! Bounded pool for thumbnail captures.
+//!
+//! Platform thumbnail captures block while a frame is grabbed and encoded.
+//! The picker requests many thumbnails at once, so captures run on the
+//! blocking thread pool, with a semaphore limiting how many run concurrently.
+
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Default number of thumbnails captured concurrently.
+pub const DEFAULT_THUMBNAIL_POOL_SIZE: usize = 4;
+
+/// Largest configurable number of concurrent thumbnail captures.
+pub const MAX_THUMBNAIL_POOL_SIZE: usize = 16;
+
+/// Runs blocking thumbnail captures with bounded concurrency.
+///
+/// Cloning is cheap; clones share the same permits.
+#[derive(Debug, Clone)]
+pub struct ThumbnailPool {
+    semaphore: Arc<Semaphore>,
+    max_concurrent: usize,
+}
+
+impl ThumbnailPool {
+    /// Create a pool allowing `max_concurrent` captures at once (at least one).
+    pub fn new(max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            max_concurrent,
+        }
+    }
+
+    /// Number of captures allowed to run at once.
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Run `capture` on a blocking thread once a permit is available.
+    pub async fn run<F, T>(&self, capture: F) -> Result<T, String>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| format!("Thumbnail pool closed: {}", e))?;
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            capture()
+        })
+        .await
+        .map_err(|e| format!("Thumbnail task failed: {}", e))
+    }
+}
+
+impl Default for ThumbnailPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_THUMBNAIL_POOL_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_pool_size_is_at_least_one() {
+        assert_eq!(ThumbnailPool::new(0).max_concurrent(), 1);
+        assert_eq!(
+            ThumbnailPool::default().max_concurrent(),
+            DEFAULT_THUMBNAIL_POOL_SIZE
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_run_limits_concurrency() {
+        let pool = ThumbnailPool::new(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let pool = pool.clone();
+                let running = running.clone();
+                let peak = peak.clone();
+                tokio::spawn(async move {
+                    pool.run(move || {
+                        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(20));
+                        running.fetch_sub(1, Ordering::SeqCst);
+                    })
+                    .await
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+}
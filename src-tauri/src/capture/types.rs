@@ -3,18 +3,82 @@
 //! These types are used for actual capture operations and are not
 //! serializable for IPC. For IPC-compatible types, see omnirec-common.
 
+use super::frame_pool::FrameData;
 use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
-use std::time::SystemTime;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::mpsc;
 
+/// Layout of a frame's pixel data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelFormat {
+    /// Packed 8-bit BGRA, 4 bytes per pixel
+    #[default]
+    Bgra,
+    /// Full-size Y plane followed by a half-size interleaved UV plane.
+    /// Preferred input of hardware encoders (NVENC, VAAPI).
+    Nv12,
+    /// Full-size Y plane followed by half-size U and V planes (I420)
+    Yuv420,
+}
+
+impl PixelFormat {
+    /// Name of the format in FFmpeg's `-pix_fmt`.
+    pub fn ffmpeg_name(self) -> &'static str {
+        match self {
+            PixelFormat::Bgra => "bgra",
+            PixelFormat::Nv12 => "nv12",
+            PixelFormat::Yuv420 => "yuv420p",
+        }
+    }
+
+    /// Planes in storage order, as (bytes per sample, subsampling factor).
+    fn planes(self) -> &'static [(usize, u32)] {
+        match self {
+            PixelFormat::Bgra => &[(4, 1)],
+            PixelFormat::Nv12 => &[(1, 1), (2, 2)],
+            PixelFormat::Yuv420 => &[(1, 1), (1, 2), (1, 2)],
+        }
+    }
+
+    /// Whether chroma is stored at half resolution, so crops must start on
+    /// even coordinates.
+    fn is_subsampled(self) -> bool {
+        self != PixelFormat::Bgra
+    }
+
+    /// Number of bytes in a `width`x`height` frame.
+    pub fn frame_size(self, width: u32, height: u32) -> usize {
+        self.planes()
+            .iter()
+            .map(|&(bytes, sub)| {
+                width.div_ceil(sub) as usize * bytes * height.div_ceil(sub) as usize
+            })
+            .sum()
+    }
+
+    /// A black `width`x`height` frame (limited-range black for YUV formats).
+    pub fn black_frame(self, width: u32, height: u32) -> Vec<u8> {
+        match self {
+            PixelFormat::Bgra => [0, 0, 0, 255].repeat(width as usize * height as usize),
+            PixelFormat::Nv12 | PixelFormat::Yuv420 => {
+                let mut frame = vec![128u8; self.frame_size(width, height)];
+                frame[..width as usize * height as usize].fill(16);
+                frame
+            }
+        }
+    }
+}
+
 /// A captured frame with its dimensions and pixel data.
 #[derive(Clone)]
 pub struct CapturedFrame {
     pub width: u32,
     pub height: u32,
-    /// BGRA pixel data
-    pub data: Vec<u8>,
+    /// Pixel data, laid out as `pixel_format`; shared between clones
+    pub data: FrameData,
+    /// Layout of `data`
+    pub pixel_format: PixelFormat,
     /// Wall-clock time when this frame was captured by the OS.
     ///
     /// This is stamped with `SystemTime::now()` in the platform capture
@@ -22,6 +86,112 @@ pub struct CapturedFrame {
     /// moment as possible.  The encoder uses this to align audio and video
     /// on a common absolute timeline.
     pub captured_at: SystemTime,
+    /// When the OS presented this frame, in microseconds since the recording
+    /// epoch (see [`timestamp_us`]).
+    ///
+    /// Taken from the platform's own presentation timestamp where there is
+    /// one, so it is not shifted by delays in the capture callback or the
+    /// frame channel. The encoder places frames in the output by this time.
+    pub timestamp_us: u64,
+}
+
+impl CapturedFrame {
+    /// Copy out the `width`x`height` area at (`x`, `y`).
+    ///
+    /// For subsampled formats the origin is rounded down to even coordinates
+    /// so chroma stays aligned with luma. Returns None if the area does not
+    /// fit in the frame or the data is too short for the frame size.
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Option<CapturedFrame> {
+        let format = self.pixel_format;
+        let (x, y) = if format.is_subsampled() {
+            (x & !1, y & !1)
+        } else {
+            (x, y)
+        };
+        if x + width > self.width
+            || y + height > self.height
+            || self.data.len() < format.frame_size(self.width, self.height)
+        {
+            return None;
+        }
+
+        let mut data = Vec::with_capacity(format.frame_size(width, height));
+        let mut plane_start = 0;
+        for &(bytes, sub) in format.planes() {
+            let src_stride = self.width.div_ceil(sub) as usize * bytes;
+            let dst_stride = width.div_ceil(sub) as usize * bytes;
+            let left = (x / sub) as usize * bytes;
+            for row in 0..height.div_ceil(sub) as usize {
+                let src_start = plane_start + ((y / sub) as usize + row) * src_stride + left;
+                data.extend_from_slice(&self.data[src_start..src_start + dst_stride]);
+            }
+            plane_start += src_stride * self.height.div_ceil(sub) as usize;
+        }
+
+        Some(CapturedFrame {
+            width,
+            height,
+            data: data.into(),
+            pixel_format: format,
+            captured_at: self.captured_at,
+            timestamp_us: self.timestamp_us,
+        })
+    }
+}
+
+/// Start of the timeline frame timestamps are measured on, shared by every
+/// capture source in the process.
+static RECORDING_EPOCH: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+/// Microseconds from the recording epoch to `instant`, or 0 if it is earlier.
+pub fn timestamp_us(instant: Instant) -> u64 {
+    instant
+        .saturating_duration_since(*RECORDING_EPOCH)
+        .as_micros() as u64
+}
+
+/// The `Instant` of a frame timestamp.
+pub fn timestamp_instant(timestamp_us: u64) -> Instant {
+    *RECORDING_EPOCH + Duration::from_micros(timestamp_us)
+}
+
+/// Maps a capture source's presentation timestamps onto the recording epoch.
+///
+/// The source's clock is anchored to the times its frames arrive. Delivery
+/// can only add latency, so the smallest arrival-minus-presentation
+/// difference seen so far is taken as the offset between the two clocks.
+/// Timestamps never go backwards.
+#[derive(Debug, Default)]
+pub struct PresentationClock {
+    offset_us: Option<i64>,
+    last_us: u64,
+}
+
+impl PresentationClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Timestamp of a frame arriving now that the source presented at
+    /// `pts_us` on its own clock. Without a presentation time the arrival
+    /// time is used.
+    pub fn timestamp_us(&mut self, pts_us: Option<i64>) -> u64 {
+        self.stamp(pts_us, timestamp_us(Instant::now()))
+    }
+
+    fn stamp(&mut self, pts_us: Option<i64>, arrived_us: u64) -> u64 {
+        let timestamp = match pts_us {
+            Some(pts_us) => {
+                let offset = arrived_us as i64 - pts_us;
+                let offset = self.offset_us.map_or(offset, |current| current.min(offset));
+                self.offset_us = Some(offset);
+                (pts_us + offset).max(0) as u64
+            }
+            None => arrived_us,
+        };
+        self.last_us = self.last_us.max(timestamp);
+        self.last_us
+    }
 }
 
 /// Handle to stop an ongoing capture.
@@ -29,3 +199,168 @@ pub type StopHandle = Arc<AtomicBool>;
 
 /// Receiver for captured frames.
 pub type FrameReceiver = mpsc::Receiver<CapturedFrame>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{assert_frame_equals, generate_test_frame};
+    use omnirec_types::{logical_to_physical_region, CaptureRegion, MonitorInfo};
+
+    fn frame(format: PixelFormat, width: u32, height: u32) -> CapturedFrame {
+        let size = format.frame_size(width, height);
+        CapturedFrame {
+            width,
+            height,
+            data: (0..size).map(|i| i as u8).collect::<Vec<u8>>().into(),
+            pixel_format: format,
+            captured_at: SystemTime::now(),
+            timestamp_us: timestamp_us(Instant::now()),
+        }
+    }
+
+    #[test]
+    fn test_frame_size_per_format() {
+        assert_eq!(PixelFormat::Bgra.frame_size(1920, 1080), 1920 * 1080 * 4);
+        assert_eq!(
+            PixelFormat::Nv12.frame_size(1920, 1080),
+            1920 * 1080 * 3 / 2
+        );
+        assert_eq!(
+            PixelFormat::Yuv420.frame_size(1920, 1080),
+            1920 * 1080 * 3 / 2
+        );
+        // Odd sizes round chroma up
+        assert_eq!(PixelFormat::Nv12.frame_size(5, 3), 15 + 3 * 2 * 2);
+        assert_eq!(PixelFormat::Yuv420.frame_size(5, 3), 15 + 2 * (3 * 2));
+    }
+
+    #[test]
+    fn test_black_frame() {
+        let bgra = PixelFormat::Bgra.black_frame(2, 1);
+        assert_eq!(bgra, [0, 0, 0, 255, 0, 0, 0, 255]);
+        for format in [PixelFormat::Nv12, PixelFormat::Yuv420] {
+            let frame = format.black_frame(4, 2);
+            assert_eq!(frame.len(), format.frame_size(4, 2));
+            assert!(frame[..8].iter().all(|&y| y == 16));
+            assert!(frame[8..].iter().all(|&c| c == 128));
+        }
+    }
+
+    #[test]
+    fn test_crop_byte_counts() {
+        for format in [PixelFormat::Bgra, PixelFormat::Nv12, PixelFormat::Yuv420] {
+            let cropped = frame(format, 16, 8).crop(2, 2, 6, 4).unwrap();
+            assert_eq!((cropped.width, cropped.height), (6, 4));
+            assert_eq!(cropped.data.len(), format.frame_size(6, 4));
+        }
+    }
+
+    #[test]
+    fn test_crop_nv12_planes() {
+        // 4x4: Y rows of 4 bytes, then 2 UV rows of 4 bytes
+        let source = frame(PixelFormat::Nv12, 4, 4);
+        let cropped = source.crop(2, 2, 2, 2).unwrap();
+        // Y rows 2 and 3, columns 2..4
+        assert_eq!(&cropped.data[..4], &[10, 11, 14, 15]);
+        // UV row 1, chroma column 1 (bytes 2..4)
+        assert_eq!(&cropped.data[4..], &[16 + 4 + 2, 16 + 4 + 3]);
+    }
+
+    #[test]
+    fn test_crop_subsampled_rounds_origin_to_even() {
+        let source = frame(PixelFormat::Yuv420, 8, 8);
+        let odd = source.crop(3, 1, 4, 4).unwrap();
+        let even = source.crop(2, 0, 4, 4).unwrap();
+        assert_frame_equals(&odd, &even, 0);
+    }
+
+    #[test]
+    fn test_crop_bgra_keeps_pixels() {
+        let source = generate_test_frame(16, 8, 0);
+        assert_frame_equals(&source.crop(0, 0, 16, 8).unwrap(), &source, 0);
+
+        // Cropping in two steps gives the same pixels as in one
+        let nested = source.crop(2, 1, 12, 6).unwrap().crop(3, 2, 5, 3).unwrap();
+        assert_frame_equals(&nested, &source.crop(5, 3, 5, 3).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_crop_out_of_bounds() {
+        let source = generate_test_frame(10, 10, 0);
+        assert!(source.crop(8, 8, 5, 5).is_none());
+    }
+
+    #[test]
+    fn test_presentation_clock_anchors_to_lowest_latency() {
+        let mut clock = PresentationClock::new();
+        // Presented at 1000us on the source clock, arrived 5ms late
+        assert_eq!(clock.stamp(Some(1_000), 106_000), 106_000);
+        // Arrived only 1ms late: the offset tightens
+        assert_eq!(clock.stamp(Some(34_000), 135_000), 135_000);
+        // A late arrival keeps its presentation spacing
+        assert_eq!(clock.stamp(Some(67_000), 190_000), 168_000);
+    }
+
+    #[test]
+    fn test_presentation_clock_is_monotonic() {
+        let mut clock = PresentationClock::new();
+        assert_eq!(clock.stamp(None, 50_000), 50_000);
+        assert_eq!(clock.stamp(Some(0), 40_000), 50_000);
+        assert_eq!(clock.stamp(Some(20_000), 70_000), 60_000);
+    }
+
+    proptest::proptest! {
+        /// A region translated to physical pixels can always be cropped from a
+        /// frame of the monitor. Monitors are kept small so the frames are too.
+        #[test]
+        fn test_crop_translated_region(
+            monitor_width in 64u32..=640,
+            monitor_height in 48u32..=480,
+            scale_factor in 1.0f64..=4.0,
+            x in -640i32..=1280,
+            y in -480i32..=960,
+            width in 0u32..=1280,
+            height in 0u32..=960,
+        ) {
+            let monitor = MonitorInfo {
+                id: "DP-1".to_string(),
+                name: "DP-1".to_string(),
+                x: 0,
+                y: 0,
+                width: monitor_width,
+                height: monitor_height,
+                is_primary: true,
+                scale_factor,
+            };
+            let region = CaptureRegion {
+                monitor_id: "DP-1".to_string(),
+                x,
+                y,
+                width,
+                height,
+                aspect_ratio: None,
+            };
+            let physical = logical_to_physical_region(&region, &monitor);
+
+            let (frame_width, frame_height) = (
+                (monitor_width as f64 * scale_factor).round() as u32,
+                (monitor_height as f64 * scale_factor).round() as u32,
+            );
+            let source = CapturedFrame {
+                width: frame_width,
+                height: frame_height,
+                data: PixelFormat::Bgra.black_frame(frame_width, frame_height).into(),
+                pixel_format: PixelFormat::Bgra,
+                captured_at: SystemTime::now(),
+                timestamp_us: timestamp_us(Instant::now()),
+            };
+            let cropped = source
+                .crop(physical.x as u32, physical.y as u32, physical.width, physical.height)
+                .unwrap();
+            proptest::prop_assert_eq!(
+                cropped.data.len(),
+                (physical.width * physical.height * 4) as usize
+            );
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Webcam capture for the picture-in-picture overlay.
+//!
+//! The camera is opened with `nokhwa` on its own thread, which decodes each
+//! frame to RGB and scales it to the overlay size. The encoder composites the
+//! most recent frame onto every screen frame it sends, so a webcam running
+//! slower than the recording repeats frames instead of stalling it.
+//!
+//! On macOS the system asks for camera permission when a camera is first
+//! opened, showing the `NSCameraUsageDescription` from `Info.plist`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use image::imageops::{self, FilterType};
+use image::RgbImage;
+use nokhwa::pixel_format::RgbFormat;
+use nokhwa::utils::{ApiBackend, CameraIndex, RequestedFormat, RequestedFormatType};
+use nokhwa::Camera;
+use omnirec_types::CameraInfo;
+
+/// How long to wait for the camera to open and deliver its first frame.
+const OPEN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pause after a failed frame read before trying again.
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// List the cameras that can be overlaid on recordings.
+pub fn list_cameras() -> Result<Vec<CameraInfo>, String> {
+    let cameras =
+        nokhwa::query(ApiBackend::Auto).map_err(|e| format!("Failed to list cameras: {}", e))?;
+    Ok(cameras
+        .into_iter()
+        .map(|camera| CameraInfo {
+            id: camera.index().as_string(),
+            name: camera.human_name(),
+        })
+        .collect())
+}
+
+/// Camera index for a [`CameraInfo::id`]: a number, or a platform device
+/// path or identifier.
+fn camera_index(camera_id: &str) -> CameraIndex {
+    match camera_id.parse() {
+        Ok(index) => CameraIndex::Index(index),
+        Err(_) => CameraIndex::String(camera_id.to_string()),
+    }
+}
+
+/// Overlay height for a camera frame of `width`x`height` scaled to
+/// `overlay_width`, keeping the aspect ratio. Rounded to an even number so
+/// the overlay lines up with subsampled chroma.
+pub fn overlay_height(width: u32, height: u32, overlay_width: u32) -> u32 {
+    let scaled = (overlay_width as u64 * height as u64 / width.max(1) as u64) as u32;
+    (scaled & !1).max(2)
+}
+
+/// A running webcam capture. Stops when dropped.
+pub struct WebcamCapture {
+    latest: Arc<Mutex<Option<Arc<RgbImage>>>>,
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl WebcamCapture {
+    /// Open `camera_id` and capture frames scaled to `overlay_width` pixels
+    /// wide.
+    pub fn start(camera_id: &str, overlay_width: u32) -> Result<Self, String> {
+        let latest = Arc::new(Mutex::new(None));
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+
+        let index = camera_index(camera_id);
+        let thread_latest = Arc::clone(&latest);
+        let thread_stop = Arc::clone(&stop_flag);
+        thread::Builder::new()
+            .name("webcam-capture".to_string())
+            .spawn(move || {
+                if let Err(e) = run_capture(
+                    index,
+                    overlay_width,
+                    &thread_latest,
+                    &thread_stop,
+                    &ready_tx,
+                ) {
+                    tracing::error!("[Webcam] Capture error: {}", e);
+                    let _ = ready_tx.send(Err(e));
+                }
+                tracing::debug!("[Webcam] Capture thread exited");
+            })
+            .map_err(|e| format!("Failed to start webcam thread: {}", e))?;
+
+        match ready_rx.recv_timeout(OPEN_TIMEOUT) {
+            Ok(Ok(())) => Ok(Self { latest, stop_flag }),
+            Ok(Err(e)) => Err(e),
+            Err(_) => {
+                stop_flag.store(true, Ordering::Relaxed);
+                Err("Timed out opening the camera".to_string())
+            }
+        }
+    }
+
+    /// The most recent frame, scaled to the overlay size.
+    pub fn latest_frame(&self) -> Option<Arc<RgbImage>> {
+        self.latest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl Drop for WebcamCapture {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+}
+
+fn run_capture(
+    index: CameraIndex,
+    overlay_width: u32,
+    latest: &Mutex<Option<Arc<RgbImage>>>,
+    stop_flag: &AtomicBool,
+    ready_tx: &std::sync::mpsc::Sender<Result<(), String>>,
+) -> Result<(), String> {
+    let format = RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestFrameRate);
+    let mut camera = Camera::new(index.clone(), format)
+        .map_err(|e| format!("Failed to open camera {}: {}", index, e))?;
+    camera
+        .open_stream()
+        .map_err(|e| format!("Failed to start camera {}: {}", index, e))?;
+    tracing::info!(
+        "[Webcam] Opened {} at {}",
+        camera.info().human_name(),
+        camera.camera_format()
+    );
+
+    let mut ready = false;
+    while !stop_flag.load(Ordering::Relaxed) {
+        let frame = match camera.frame() {
+            Ok(frame) => frame,
+            Err(e) if ready => {
+                tracing::warn!("[Webcam] Dropped frame: {}", e);
+                thread::sleep(RETRY_DELAY);
+                continue;
+            }
+            Err(e) => return Err(format!("Failed to read from camera {}: {}", index, e)),
+        };
+        let decoded = frame
+            .decode_image::<RgbFormat>()
+            .map_err(|e| format!("Failed to decode camera frame: {}", e))?;
+
+        // nokhwa may build against a different `image` version, so go
+        // through the raw pixels
+        let (width, height) = (decoded.width(), decoded.height());
+        let Some(rgb) = RgbImage::from_raw(width, height, decoded.into_raw()) else {
+            continue;
+        };
+        let scaled = imageops::resize(
+            &rgb,
+            overlay_width,
+            overlay_height(width, height, overlay_width),
+            FilterType::Triangle,
+        );
+        *latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(scaled));
+
+        if !ready {
+            ready = true;
+            let _ = ready_tx.send(Ok(()));
+        }
+    }
+
+    if let Err(e) = camera.stop_stream() {
+        tracing::warn!("[Webcam] Failed to stop camera: {}", e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_height_keeps_aspect() {
+        assert_eq!(overlay_height(1280, 720, 480), 270);
+        assert_eq!(overlay_height(640, 480, 320), 240);
+        assert_eq!(overlay_height(1280, 720, 250), 140);
+        assert_eq!(overlay_height(1920, 1080, 2), 2);
+    }
+
+    #[test]
+    fn test_camera_index() {
+        assert_eq!(camera_index("0"), CameraIndex::Index(0));
+        assert_eq!(
+            camera_index("/dev/video2"),
+            CameraIndex::String("/dev/video2".to_string())
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! Window-follow mode for window captures.
+//!
+//! With window follow on, a window capture records the area of the screen the
+//! window covers rather than the window's own surface, and that area moves
+//! with the window. Menus, tooltips and other popups drawn over the window are
+//! then included. The area keeps the size the window had when recording
+//! started and stays on the monitor the window started on.
+//!
+//! Implemented on Windows and macOS; other platforms ignore the setting.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Set whether window captures follow the window's screen area.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether window captures follow the window's screen area.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Top-left corner of a `width`x`height` capture area over a window at
+/// (`x`, `y`), moved as little as needed to stay inside a
+/// `bounds_width`x`bounds_height` monitor. All values are monitor-relative.
+pub fn follow_origin(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    bounds_width: u32,
+    bounds_height: u32,
+) -> (u32, u32) {
+    let max_x = bounds_width.saturating_sub(width) as i64;
+    let max_y = bounds_height.saturating_sub(height) as i64;
+    (
+        (x as i64).clamp(0, max_x) as u32,
+        (y as i64).clamp(0, max_y) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follow_origin_inside_monitor() {
+        assert_eq!(follow_origin(100, 50, 800, 600, 1920, 1080), (100, 50));
+    }
+
+    #[test]
+    fn test_follow_origin_clamps_to_monitor() {
+        assert_eq!(follow_origin(-40, -10, 800, 600, 1920, 1080), (0, 0));
+        assert_eq!(follow_origin(1500, 900, 800, 600, 1920, 1080), (1120, 480));
+        // Area larger than the monitor sticks to the top-left corner
+        assert_eq!(follow_origin(300, 300, 2560, 1440, 1920, 1080), (0, 0));
+    }
+}
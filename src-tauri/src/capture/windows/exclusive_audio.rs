@@ -0,0 +1,589 @@
+//! WASAPI exclusive-mode microphone capture.
+//!
+//! vtx-engine captures in shared mode (`AUDCLNT_SHAREMODE_SHARED`), where the
+//! Windows audio engine mixes and resamples every stream. With exclusive mode
+//! enabled, a microphone recorded on its own is captured here instead: the
+//! device is opened with `AUDCLNT_SHAREMODE_EXCLUSIVE` in its native format,
+//! bypassing the audio engine. Samples are delivered as vtx-engine
+//! `RawAudioData` events so the encoder consumes them unchanged.
+//!
+//! While an exclusive stream is open no other application can use the device.
+//! vtx-engine's AGC doesn't see these samples; the encoder's gain stage
+//! still does.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+
+use vtx_engine::{EngineEvent, StreamingAudioData};
+use windows::core::{GUID, HSTRING};
+use windows::Win32::Foundation::{CloseHandle, PROPERTYKEY, S_OK, WAIT_OBJECT_0};
+use windows::Win32::Media::Audio::{
+    IAudioCaptureClient, IAudioClient, IMMDevice, IMMDeviceEnumerator, MMDeviceEnumerator,
+    AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_E_BUFFER_SIZE_NOT_ALIGNED, AUDCLNT_SHAREMODE_EXCLUSIVE,
+    AUDCLNT_SHAREMODE_SHARED, AUDCLNT_STREAMFLAGS_AUTOCONVERTPCM,
+    AUDCLNT_STREAMFLAGS_EVENTCALLBACK, AUDCLNT_STREAMFLAGS_SRC_DEFAULT_QUALITY, WAVEFORMATEX,
+    WAVEFORMATEXTENSIBLE, WAVEFORMATEXTENSIBLE_0,
+};
+use windows::Win32::System::Com::StructuredStorage::PROPVARIANT;
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED, STGM_READ,
+};
+use windows::Win32::System::Threading::{CreateEventW, WaitForSingleObject};
+use windows::Win32::System::Variant::VT_BLOB;
+
+/// Sample rate the encoder's audio input expects.
+pub const EXCLUSIVE_SAMPLE_RATE: u32 = 48_000;
+
+/// PKEY_AudioEngine_DeviceFormat - the device's native format
+/// {f19f064d-082c-4e27-bc73-6882a1bb8e4c}, 0
+const PKEY_AUDIO_ENGINE_DEVICE_FORMAT: PROPERTYKEY = PROPERTYKEY {
+    fmtid: GUID::from_u128(0xf19f064d_082c_4e27_bc73_6882a1bb8e4c),
+    pid: 0,
+};
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+const KSDATAFORMAT_SUBTYPE_PCM: GUID = GUID::from_u128(0x00000001_0000_0010_8000_00aa00389b71);
+const KSDATAFORMAT_SUBTYPE_IEEE_FLOAT: GUID =
+    GUID::from_u128(0x00000003_0000_0010_8000_00aa00389b71);
+
+/// Reference time units (100 ns) per second.
+const REFTIMES_PER_SEC: f64 = 10_000_000.0;
+
+/// How long the capture loop waits for a buffer event before re-checking
+/// the stop flag.
+const EVENT_TIMEOUT_MS: u32 = 100;
+
+/// Capacity of the audio event channel.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Maximum time to wait for the device to open.
+const OPEN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// PCM layout of a capture stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureFormat {
+    pub channels: u16,
+    pub sample_rate: u32,
+    /// Container size of one sample in bits
+    pub bits_per_sample: u16,
+    /// Bits actually used within the container (e.g. 24 in a 32-bit container)
+    pub valid_bits_per_sample: u16,
+    /// IEEE float samples (otherwise signed integer)
+    pub float: bool,
+}
+
+impl CaptureFormat {
+    /// 32-bit float samples.
+    pub fn float32(channels: u16, sample_rate: u32) -> Self {
+        Self {
+            channels,
+            sample_rate,
+            bits_per_sample: 32,
+            valid_bits_per_sample: 32,
+            float: true,
+        }
+    }
+
+    /// 16-bit integer samples.
+    pub fn int16(channels: u16, sample_rate: u32) -> Self {
+        Self {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            valid_bits_per_sample: 16,
+            float: false,
+        }
+    }
+
+    /// Bytes per frame (one sample for every channel).
+    fn block_align(&self) -> u16 {
+        self.channels * self.bits_per_sample / 8
+    }
+
+    /// Read a format from a `WAVEFORMATEX` (or `WAVEFORMATEXTENSIBLE`).
+    ///
+    /// # Safety
+    /// `format` must point to a valid `WAVEFORMATEX` whose `cbSize` covers any
+    /// extension.
+    unsafe fn from_wave_format(format: *const WAVEFORMATEX) -> Option<Self> {
+        let base = format.read_unaligned();
+        let (float, valid_bits) = match base.wFormatTag {
+            WAVE_FORMAT_PCM => (false, base.wBitsPerSample),
+            WAVE_FORMAT_IEEE_FLOAT => (true, base.wBitsPerSample),
+            WAVE_FORMAT_EXTENSIBLE if base.cbSize >= 22 => {
+                let ext = (format as *const WAVEFORMATEXTENSIBLE).read_unaligned();
+                let sub_format = ext.SubFormat;
+                let valid_bits = ext.Samples.wValidBitsPerSample;
+                if sub_format == KSDATAFORMAT_SUBTYPE_IEEE_FLOAT {
+                    (true, valid_bits)
+                } else if sub_format == KSDATAFORMAT_SUBTYPE_PCM {
+                    (false, valid_bits)
+                } else {
+                    return None;
+                }
+            }
+            _ => return None,
+        };
+        Some(Self {
+            channels: base.nChannels,
+            sample_rate: base.nSamplesPerSec,
+            bits_per_sample: base.wBitsPerSample,
+            valid_bits_per_sample: valid_bits,
+            float,
+        })
+    }
+
+    /// Build the `WAVEFORMATEXTENSIBLE` describing this format.
+    fn to_wave_format(self) -> WAVEFORMATEXTENSIBLE {
+        let block_align = self.block_align();
+        WAVEFORMATEXTENSIBLE {
+            Format: WAVEFORMATEX {
+                wFormatTag: WAVE_FORMAT_EXTENSIBLE,
+                nChannels: self.channels,
+                nSamplesPerSec: self.sample_rate,
+                nAvgBytesPerSec: self.sample_rate * block_align as u32,
+                nBlockAlign: block_align,
+                wBitsPerSample: self.bits_per_sample,
+                cbSize: 22,
+            },
+            Samples: WAVEFORMATEXTENSIBLE_0 {
+                wValidBitsPerSample: self.valid_bits_per_sample,
+            },
+            dwChannelMask: match self.channels {
+                1 => 0x4, // SPEAKER_FRONT_CENTER
+                2 => 0x3, // SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT
+                _ => 0,
+            },
+            SubFormat: if self.float {
+                KSDATAFORMAT_SUBTYPE_IEEE_FLOAT
+            } else {
+                KSDATAFORMAT_SUBTYPE_PCM
+            },
+        }
+    }
+}
+
+/// An initialized (not yet started) audio client.
+pub struct NegotiatedClient {
+    pub client: IAudioClient,
+    pub format: CaptureFormat,
+    /// Whether the client was opened in exclusive mode
+    pub exclusive: bool,
+}
+
+/// Open `device` in exclusive mode, falling back to shared mode.
+///
+/// Exclusive mode is tried with the device's native format first (when its
+/// sample rate matches `format`), then with `format` and its 16-bit variant.
+/// If the device accepts none of them, the client is opened in shared mode
+/// with `format` as 32-bit float, letting the audio engine convert.
+pub fn try_exclusive_then_shared(
+    device: &IMMDevice,
+    format: &CaptureFormat,
+) -> Result<NegotiatedClient, String> {
+    let mut candidates = Vec::new();
+    match device_native_format(device) {
+        Some(native) if native.sample_rate == format.sample_rate => candidates.push(native),
+        Some(native) => tracing::debug!(
+            "[Audio] Native format {:?} does not match {} Hz, skipping",
+            native,
+            format.sample_rate
+        ),
+        None => tracing::debug!("[Audio] Device did not report a native format"),
+    }
+    candidates.push(*format);
+    candidates.push(CaptureFormat::int16(format.channels, format.sample_rate));
+    candidates.dedup();
+
+    for candidate in candidates {
+        match initialize_exclusive(device, &candidate) {
+            Ok(client) => {
+                return Ok(NegotiatedClient {
+                    client,
+                    format: candidate,
+                    exclusive: true,
+                })
+            }
+            Err(e) => tracing::debug!("[Audio] Exclusive mode rejected {:?}: {}", candidate, e),
+        }
+    }
+
+    tracing::warn!("[Audio] Exclusive mode unavailable, falling back to shared mode");
+    let shared = CaptureFormat::float32(format.channels, format.sample_rate);
+    let client = initialize_shared(device, &shared)?;
+    Ok(NegotiatedClient {
+        client,
+        format: shared,
+        exclusive: false,
+    })
+}
+
+/// Read the device's native format (PKEY_AudioEngine_DeviceFormat).
+fn device_native_format(device: &IMMDevice) -> Option<CaptureFormat> {
+    unsafe {
+        let store = device.OpenPropertyStore(STGM_READ).ok()?;
+        let value: PROPVARIANT = store.GetValue(&PKEY_AUDIO_ENGINE_DEVICE_FORMAT).ok()?;
+        if value.vt() != VT_BLOB {
+            return None;
+        }
+        let blob = value.Anonymous.Anonymous.Anonymous.blob;
+        if blob.pBlobData.is_null() || (blob.cbSize as usize) < size_of::<WAVEFORMATEX>() {
+            return None;
+        }
+        CaptureFormat::from_wave_format(blob.pBlobData as *const WAVEFORMATEX)
+    }
+}
+
+/// Buffer period (in 100 ns units) for `frames` frames, as required after
+/// `AUDCLNT_E_BUFFER_SIZE_NOT_ALIGNED`.
+fn aligned_period(frames: u32, sample_rate: u32) -> i64 {
+    (REFTIMES_PER_SEC * frames as f64 / sample_rate as f64).round() as i64
+}
+
+fn initialize_exclusive(
+    device: &IMMDevice,
+    format: &CaptureFormat,
+) -> Result<IAudioClient, String> {
+    let wave_format = format.to_wave_format();
+    let format_ptr = &wave_format as *const WAVEFORMATEXTENSIBLE as *const WAVEFORMATEX;
+
+    unsafe {
+        let client: IAudioClient = device
+            .Activate(CLSCTX_ALL, None)
+            .map_err(|e| e.to_string())?;
+        let supported = client.IsFormatSupported(AUDCLNT_SHAREMODE_EXCLUSIVE, format_ptr, None);
+        if supported != S_OK {
+            return Err(format!("format not supported ({})", supported));
+        }
+
+        let mut period = 0i64;
+        client
+            .GetDevicePeriod(Some(&mut period), None)
+            .map_err(|e| e.to_string())?;
+
+        match client.Initialize(
+            AUDCLNT_SHAREMODE_EXCLUSIVE,
+            AUDCLNT_STREAMFLAGS_EVENTCALLBACK,
+            period,
+            period,
+            format_ptr,
+            None,
+        ) {
+            Ok(()) => Ok(client),
+            Err(e) if e.code() == AUDCLNT_E_BUFFER_SIZE_NOT_ALIGNED => {
+                // Retry on a fresh client with a period matching the aligned buffer size
+                let frames = client.GetBufferSize().map_err(|e| e.to_string())?;
+                let period = aligned_period(frames, format.sample_rate);
+                let client: IAudioClient = device
+                    .Activate(CLSCTX_ALL, None)
+                    .map_err(|e| e.to_string())?;
+                client
+                    .Initialize(
+                        AUDCLNT_SHAREMODE_EXCLUSIVE,
+                        AUDCLNT_STREAMFLAGS_EVENTCALLBACK,
+                        period,
+                        period,
+                        format_ptr,
+                        None,
+                    )
+                    .map_err(|e| e.to_string())?;
+                Ok(client)
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+fn initialize_shared(device: &IMMDevice, format: &CaptureFormat) -> Result<IAudioClient, String> {
+    let wave_format = format.to_wave_format();
+    let format_ptr = &wave_format as *const WAVEFORMATEXTENSIBLE as *const WAVEFORMATEX;
+
+    unsafe {
+        let client: IAudioClient = device
+            .Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to activate audio client: {}", e))?;
+        client
+            .Initialize(
+                AUDCLNT_SHAREMODE_SHARED,
+                AUDCLNT_STREAMFLAGS_EVENTCALLBACK
+                    | AUDCLNT_STREAMFLAGS_AUTOCONVERTPCM
+                    | AUDCLNT_STREAMFLAGS_SRC_DEFAULT_QUALITY,
+                0,
+                0,
+                format_ptr,
+                None,
+            )
+            .map_err(|e| format!("Failed to initialize shared-mode client: {}", e))?;
+        Ok(client)
+    }
+}
+
+/// Downmix interleaved samples to mono f32 in [-1.0, 1.0].
+fn to_mono_f32(data: &[u8], format: &CaptureFormat) -> Vec<f32> {
+    let channels = format.channels.max(1) as usize;
+    let sample_bytes = (format.bits_per_sample / 8) as usize;
+    if sample_bytes == 0 {
+        return Vec::new();
+    }
+    data.chunks_exact(sample_bytes * channels)
+        .map(|frame| {
+            let sum: f32 = frame
+                .chunks_exact(sample_bytes)
+                .map(|sample| sample_to_f32(sample, format.float))
+                .sum();
+            sum / channels as f32
+        })
+        .collect()
+}
+
+fn sample_to_f32(bytes: &[u8], float: bool) -> f32 {
+    match (float, bytes.len()) {
+        (true, 4) => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        (false, 2) => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32_768.0,
+        (false, 3) => {
+            (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f32 / 8_388_608.0
+        }
+        // Integer samples are left-justified in a 32-bit container
+        (false, 4) => {
+            i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32 / 2_147_483_648.0
+        }
+        _ => 0.0,
+    }
+}
+
+/// Running microphone capture. Dropping the handle stops it and releases the
+/// device.
+pub struct ExclusiveCapture {
+    sender: broadcast::Sender<EngineEvent>,
+    stop_flag: Arc<AtomicBool>,
+    exclusive: bool,
+}
+
+impl ExclusiveCapture {
+    /// Subscribe to the captured audio (`RawAudioData` events).
+    pub fn subscribe(&self) -> broadcast::Receiver<EngineEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Whether the device was opened in exclusive mode (false after falling
+    /// back to shared mode).
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+}
+
+impl Drop for ExclusiveCapture {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Start capturing the microphone `device_id`, trying exclusive mode first.
+///
+/// Audio is delivered mono at [`EXCLUSIVE_SAMPLE_RATE`].
+pub fn start_exclusive_capture(device_id: &str) -> Result<ExclusiveCapture, String> {
+    let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    let stop_flag = Arc::new(AtomicBool::new(false));
+
+    let device_id = device_id.to_string();
+    let thread_sender = sender.clone();
+    let thread_stop = Arc::clone(&stop_flag);
+    thread::spawn(move || {
+        let com_initialized = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED).is_ok() };
+        if let Err(e) = run_capture(&device_id, thread_sender, thread_stop, &ready_tx) {
+            tracing::error!("[Audio] Exclusive capture error: {}", e);
+            let _ = ready_tx.send(Err(e));
+        }
+        if com_initialized {
+            unsafe { CoUninitialize() };
+        }
+        tracing::debug!("[Audio] Exclusive capture thread exited");
+    });
+
+    match ready_rx.recv_timeout(OPEN_TIMEOUT) {
+        Ok(Ok(exclusive)) => Ok(ExclusiveCapture {
+            sender,
+            stop_flag,
+            exclusive,
+        }),
+        Ok(Err(e)) => Err(e),
+        Err(_) => {
+            stop_flag.store(true, Ordering::Relaxed);
+            Err("Timed out opening the audio device".to_string())
+        }
+    }
+}
+
+fn run_capture(
+    device_id: &str,
+    sender: broadcast::Sender<EngineEvent>,
+    stop_flag: Arc<AtomicBool>,
+    ready_tx: &std::sync::mpsc::Sender<Result<bool, String>>,
+) -> Result<(), String> {
+    unsafe {
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
+        let device = enumerator
+            .GetDevice(&HSTRING::from(device_id))
+            .map_err(|e| format!("Audio device {} not found: {}", device_id, e))?;
+
+        let channels = device_native_format(&device)
+            .map(|native| native.channels)
+            .unwrap_or(2);
+        let negotiated = try_exclusive_then_shared(
+            &device,
+            &CaptureFormat::float32(channels, EXCLUSIVE_SAMPLE_RATE),
+        )?;
+        let client = negotiated.client;
+        let format = negotiated.format;
+
+        let event = CreateEventW(None, false, false, None)
+            .map_err(|e| format!("Failed to create capture event: {}", e))?;
+        let result = (|| {
+            client
+                .SetEventHandle(event)
+                .map_err(|e| format!("Failed to set event handle: {}", e))?;
+            let capture_client: IAudioCaptureClient = client
+                .GetService()
+                .map_err(|e| format!("Failed to get capture client: {}", e))?;
+            client
+                .Start()
+                .map_err(|e| format!("Failed to start audio client: {}", e))?;
+
+            tracing::info!(
+                "[Audio] Capturing {} in {} mode ({:?})",
+                device_id,
+                if negotiated.exclusive {
+                    "exclusive"
+                } else {
+                    "shared"
+                },
+                format
+            );
+            let _ = ready_tx.send(Ok(negotiated.exclusive));
+
+            let result = capture_loop(&capture_client, event, &format, &sender, &stop_flag);
+            let _ = client.Stop();
+            result
+        })();
+        let _ = CloseHandle(event);
+        result
+    }
+}
+
+/// Read buffers until the stop flag is set, publishing them as
+/// `RawAudioData` events.
+unsafe fn capture_loop(
+    capture_client: &IAudioCaptureClient,
+    event: windows::Win32::Foundation::HANDLE,
+    format: &CaptureFormat,
+    sender: &broadcast::Sender<EngineEvent>,
+    stop_flag: &AtomicBool,
+) -> Result<(), String> {
+    let block_align = format.block_align() as usize;
+    let mut sample_offset = 0u64;
+
+    while !stop_flag.load(Ordering::Relaxed) {
+        if WaitForSingleObject(event, EVENT_TIMEOUT_MS) != WAIT_OBJECT_0 {
+            continue;
+        }
+        loop {
+            let packet_frames = capture_client
+                .GetNextPacketSize()
+                .map_err(|e| format!("GetNextPacketSize failed: {}", e))?;
+            if packet_frames == 0 {
+                break;
+            }
+
+            let mut data = std::ptr::null_mut();
+            let mut frames = 0u32;
+            let mut flags = 0u32;
+            capture_client
+                .GetBuffer(&mut data, &mut frames, &mut flags, None, None)
+                .map_err(|e| format!("GetBuffer failed: {}", e))?;
+
+            let silent = flags & AUDCLNT_BUFFERFLAGS_SILENT.0 as u32 != 0 || data.is_null();
+            let samples = if silent {
+                vec![0.0; frames as usize]
+            } else {
+                let bytes = std::slice::from_raw_parts(data, frames as usize * block_align);
+                to_mono_f32(bytes, format)
+            };
+            capture_client
+                .ReleaseBuffer(frames)
+                .map_err(|e| format!("ReleaseBuffer failed: {}", e))?;
+
+            let _ = sender.send(EngineEvent::RawAudioData(StreamingAudioData {
+                samples,
+                sample_rate: format.sample_rate,
+                sample_offset,
+            }));
+            sample_offset += frames as u64;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wave_format_roundtrip() {
+        let formats = [
+            CaptureFormat::float32(2, 48_000),
+            CaptureFormat::int16(1, 44_100),
+            CaptureFormat {
+                channels: 2,
+                sample_rate: 96_000,
+                bits_per_sample: 32,
+                valid_bits_per_sample: 24,
+                float: false,
+            },
+        ];
+        for format in formats {
+            let wave_format = format.to_wave_format();
+            let parsed = unsafe {
+                CaptureFormat::from_wave_format(
+                    &wave_format as *const WAVEFORMATEXTENSIBLE as *const WAVEFORMATEX,
+                )
+            };
+            assert_eq!(parsed, Some(format));
+        }
+    }
+
+    #[test]
+    fn test_aligned_period() {
+        // 480 frames at 48 kHz is 10 ms
+        assert_eq!(aligned_period(480, 48_000), 100_000);
+        assert_eq!(aligned_period(441, 44_100), 100_000);
+    }
+
+    #[test]
+    fn test_to_mono_f32_downmixes() {
+        let format = CaptureFormat::int16(2, 48_000);
+        let data: Vec<u8> = [i16::MAX, 0, -16_384, -16_384]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let mono = to_mono_f32(&data, &format);
+        assert_eq!(mono.len(), 2);
+        assert!((mono[0] - 0.5).abs() < 1e-3);
+        assert!((mono[1] + 0.5).abs() < 1e-6);
+
+        let float = CaptureFormat::float32(1, 48_000);
+        let data: Vec<u8> = [0.25f32, -1.0]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        assert_eq!(to_mono_f32(&data, &float), [0.25, -1.0]);
+    }
+}
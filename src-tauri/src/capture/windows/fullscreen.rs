@@ -0,0 +1,79 @@
+//! Detection of Direct3D exclusive fullscreen windows.
+//!
+//! Monitor capture, which window-follow mode uses, records a black or frozen
+//! image while a game owns the display in exclusive fullscreen. Window capture
+//! (`GraphicsCaptureItem.CreateFromWindowId`) still receives the game's
+//! frames, so window captures switch to it for such windows.
+//!
+//! A game's swap chain (`IDXGISwapChain::GetFullscreenState`) cannot be
+//! queried from another process. Instead, the shell reports
+//! `QUNS_RUNNING_D3D_FULL_SCREEN` while an exclusive fullscreen app is active,
+//! and the window must cover its whole monitor.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::UI::Shell::{SHQueryUserNotificationState, QUNS_RUNNING_D3D_FULL_SCREEN};
+
+use super::window_follow::{window_monitor, window_rect};
+
+static FORCE_WGC_FOR_GAMES: AtomicBool = AtomicBool::new(false);
+
+/// Set whether window captures always use window capture, skipping the
+/// exclusive fullscreen check (and window-follow mode).
+pub fn set_force_wgc_for_games(enabled: bool) {
+    FORCE_WGC_FOR_GAMES.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether window captures always use window capture.
+pub fn force_wgc_for_games() -> bool {
+    FORCE_WGC_FOR_GAMES.load(Ordering::Relaxed)
+}
+
+/// Whether `window` covers all of `monitor`.
+fn covers(window: &RECT, monitor: &RECT) -> bool {
+    window.left <= monitor.left
+        && window.top <= monitor.top
+        && window.right >= monitor.right
+        && window.bottom >= monitor.bottom
+}
+
+/// Whether a window is a Direct3D app in exclusive fullscreen.
+pub fn is_exclusive_fullscreen(window_handle: isize) -> bool {
+    let d3d_fullscreen = matches!(
+        unsafe { SHQueryUserNotificationState() },
+        Ok(QUNS_RUNNING_D3D_FULL_SCREEN)
+    );
+    if !d3d_fullscreen {
+        return false;
+    }
+
+    let hwnd = HWND(window_handle as *mut std::ffi::c_void);
+    match (window_rect(hwnd), window_monitor(hwnd)) {
+        (Some(rect), Some((_, monitor))) => covers(&rect, &monitor),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> RECT {
+        RECT {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    #[test]
+    fn test_covers_monitor() {
+        let monitor = rect(1920, 0, 3840, 1080);
+        assert!(covers(&rect(1920, 0, 3840, 1080), &monitor));
+        // Borderless windows may overhang the monitor slightly
+        assert!(covers(&rect(1912, -8, 3848, 1088), &monitor));
+        assert!(!covers(&rect(1920, 0, 3840, 1040), &monitor));
+        assert!(!covers(&rect(0, 0, 1920, 1080), &monitor));
+    }
+}
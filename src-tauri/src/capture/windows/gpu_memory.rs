@@ -0,0 +1,102 @@
+//! Video memory probe for the DXGI adapter captures run on.
+//!
+//! `IDXGIAdapter3::QueryVideoMemoryInfo` (DXGI 1.4, Windows 10) reports the
+//! process's local memory budget and usage. See `capture::gpu_memory` for
+//! what happens when it runs low.
+
+use crate::capture::gpu_memory::{self, GpuMemoryInfo, MemoryPressure, PressureChange};
+use std::time::{Duration, Instant};
+use windows::core::Interface;
+use windows::Win32::Graphics::Dxgi::{
+    CreateDXGIFactory1, IDXGIAdapter3, IDXGIFactory1, DXGI_MEMORY_SEGMENT_GROUP_LOCAL,
+    DXGI_QUERY_VIDEO_MEMORY_INFO,
+};
+
+/// How often video memory use is logged while capturing.
+const LOG_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The primary adapter, which composes the desktop.
+fn primary_adapter() -> windows::core::Result<IDXGIAdapter3> {
+    unsafe {
+        let factory: IDXGIFactory1 = CreateDXGIFactory1()?;
+        factory.EnumAdapters1(0)?.cast()
+    }
+}
+
+fn query_adapter(adapter: &IDXGIAdapter3) -> windows::core::Result<GpuMemoryInfo> {
+    let mut info = DXGI_QUERY_VIDEO_MEMORY_INFO::default();
+    unsafe { adapter.QueryVideoMemoryInfo(0, DXGI_MEMORY_SEGMENT_GROUP_LOCAL, &mut info)? };
+    Ok(GpuMemoryInfo {
+        budget_bytes: info.Budget,
+        usage_bytes: info.CurrentUsage,
+    })
+}
+
+/// Current video memory budget and usage of the primary adapter.
+pub fn query_gpu_memory() -> Result<GpuMemoryInfo, String> {
+    primary_adapter()
+        .and_then(|adapter| query_adapter(&adapter))
+        .map_err(|e| format!("Failed to query GPU memory: {}", e))
+}
+
+/// Per-capture probe sampled before each frame.
+pub struct GpuMemoryProbe {
+    adapter: IDXGIAdapter3,
+    pressure: MemoryPressure,
+    last_log: Option<Instant>,
+}
+
+impl GpuMemoryProbe {
+    /// Probe for the primary adapter, or `None` if DXGI 1.4 is unavailable.
+    pub fn new() -> Option<Self> {
+        match primary_adapter() {
+            Ok(adapter) => Some(Self {
+                adapter,
+                pressure: MemoryPressure::default(),
+                last_log: None,
+            }),
+            Err(e) => {
+                tracing::debug!("[GpuMemory] Video memory probe unavailable: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Sample video memory before a frame; `false` means drop the frame.
+    pub fn admit_frame(&mut self) -> bool {
+        let Ok(info) = query_adapter(&self.adapter) else {
+            return true;
+        };
+
+        if self.last_log.is_none_or(|at| at.elapsed() >= LOG_INTERVAL) {
+            self.last_log = Some(Instant::now());
+            tracing::debug!(
+                "[GpuMemory] Using {} of {} MB ({} MB free)",
+                info.usage_bytes / (1024 * 1024),
+                info.budget_bytes / (1024 * 1024),
+                info.available_mb()
+            );
+        }
+
+        let threshold_mb = gpu_memory::low_threshold_mb();
+        match self.pressure.update(&info, threshold_mb) {
+            Some(PressureChange::Low) => {
+                tracing::warn!(
+                    "[GpuMemory] {} MB of video memory left, throttling capture to {} fps",
+                    info.available_mb(),
+                    gpu_memory::THROTTLED_FPS
+                );
+                crate::state::get_recording_manager()
+                    .notify_gpu_memory_low(info.available_mb(), threshold_mb);
+            }
+            Some(PressureChange::Eased) => {
+                tracing::info!(
+                    "[GpuMemory] {} MB of video memory free, capture no longer throttled",
+                    info.available_mb()
+                );
+            }
+            None => {}
+        }
+        self.pressure.admit()
+    }
+}
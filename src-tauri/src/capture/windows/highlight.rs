@@ -1,11 +1,16 @@
 //! Native display highlight using Windows APIs.
 //!
 //! Creates a transparent layered window with a colored border to highlight a monitor.
-//! Uses UpdateLayeredWindow for flicker-free alpha animation.
+//! Uses UpdateLayeredWindow for flicker-free alpha animation; the border color,
+//! width and animation come from the configured [`HighlightStyle`].
 
 use std::thread;
 use std::time::Instant;
 
+use super::monitor_list::list_monitors;
+use crate::capture::highlight_style::border_pixels;
+use crate::capture::{HighlightAnimation, HighlightStyle};
+use omnirec_types::{split_highlight_region, HighlightRegion};
 use windows::core::PCWSTR;
 use windows::Win32::Foundation::{COLORREF, HINSTANCE, HWND, LPARAM, LRESULT, POINT, SIZE, WPARAM};
 use windows::Win32::Graphics::Gdi::{
@@ -20,17 +25,26 @@ use windows::Win32::UI::WindowsAndMessaging::{
     WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP,
 };
 
-const BORDER_WIDTH: i32 = 8;
 const ANIMATION_DURATION_MS: u64 = 800;
 const TIMER_ID: usize = 1;
 const TIMER_INTERVAL_MS: u32 = 16; // ~60fps
 
 /// Show a highlight border around the specified monitor area.
 /// This function spawns a thread and returns immediately.
-pub fn show_highlight(x: i32, y: i32, width: i32, height: i32) {
-    thread::spawn(move || unsafe {
-        run_highlight_window(x, y, width, height);
-    });
+///
+/// A region spanning several monitors gets one highlight window per monitor.
+pub fn show_highlight(x: i32, y: i32, width: i32, height: i32, style: HighlightStyle) {
+    let region = HighlightRegion {
+        x,
+        y,
+        width: width.max(0) as u32,
+        height: height.max(0) as u32,
+    };
+    for part in split_highlight_region(&region, &list_monitors()) {
+        thread::spawn(move || unsafe {
+            run_highlight_window(part.x, part.y, part.width as i32, part.height as i32, style);
+        });
+    }
 }
 
 /// Render state for UpdateLayeredWindow
@@ -43,13 +57,18 @@ struct RenderState {
     size: SIZE,
     pt_src: POINT,
     start_time: Instant,
+    /// DIB section pixels (premultiplied BGRA, top-down)
+    pixels: *mut u8,
+    width: i32,
+    height: i32,
+    style: HighlightStyle,
 }
 
 thread_local! {
     static RENDER_STATE: std::cell::RefCell<Option<RenderState>> = const { std::cell::RefCell::new(None) };
 }
 
-unsafe fn run_highlight_window(x: i32, y: i32, width: i32, height: i32) {
+unsafe fn run_highlight_window(x: i32, y: i32, width: i32, height: i32, style: HighlightStyle) {
     let class_name: Vec<u16> = "ScreenRecorderHighlight\0".encode_utf16().collect();
     let hmodule = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
     let hinstance = HINSTANCE(hmodule.0);
@@ -120,43 +139,10 @@ unsafe fn run_highlight_window(x: i32, y: i32, width: i32, height: i32) {
 
     let old_bitmap = SelectObject(mem_dc, bitmap.into());
 
-    // Draw directly to the pixel buffer
-    // Windows DIB is BGRA in memory, and for AC_SRC_ALPHA we need premultiplied alpha
-    // On little-endian, u32 0xAARRGGBB becomes bytes [BB, GG, RR, AA] in memory
-    // But Windows DIB expects [BB, GG, RR, AA] which is u32 as 0xAARRGGBB...
-    // Actually, let's write bytes directly to be safe
+    // Start fully transparent, then draw the border band
     let pixels = bits as *mut u8;
-    let stride = (width * 4) as usize; // 4 bytes per pixel
-
-    // #2196F3 = R:0x21, G:0x96, B:0xF3
-    let r: u8 = 0x21;
-    let g: u8 = 0x96;
-    let b: u8 = 0xF3;
-    let a: u8 = 0xFF;
-
-    for py in 0..height {
-        for px in 0..width {
-            let idx = (py as usize) * stride + (px as usize) * 4;
-            let is_border = py < BORDER_WIDTH
-                || py >= height - BORDER_WIDTH
-                || px < BORDER_WIDTH
-                || px >= width - BORDER_WIDTH;
-
-            if is_border {
-                // BGRA order, premultiplied (since alpha=255, RGB values stay the same)
-                *pixels.add(idx) = b; // Blue
-                *pixels.add(idx + 1) = g; // Green
-                *pixels.add(idx + 2) = r; // Red
-                *pixels.add(idx + 3) = a; // Alpha
-            } else {
-                // Fully transparent
-                *pixels.add(idx) = 0;
-                *pixels.add(idx + 1) = 0;
-                *pixels.add(idx + 2) = 0;
-                *pixels.add(idx + 3) = 0;
-            }
-        }
-    }
+    std::ptr::write_bytes(pixels, 0, (width * 4 * height) as usize);
+    draw_border(pixels, width, height, &style, 0);
 
     // Store render state
     let pt_dst = POINT { x, y };
@@ -176,6 +162,10 @@ unsafe fn run_highlight_window(x: i32, y: i32, width: i32, height: i32) {
             size,
             pt_src,
             start_time: Instant::now(),
+            pixels,
+            width,
+            height,
+            style,
         });
     });
 
@@ -205,6 +195,32 @@ unsafe fn run_highlight_window(x: i32, y: i32, width: i32, height: i32) {
     });
 }
 
+/// Draw the border band into the DIB pixels.
+///
+/// Only border pixels are written, so the (transparent) interior is left
+/// untouched and redrawing for the dash animation stays cheap.
+unsafe fn draw_border(
+    pixels: *mut u8,
+    width: i32,
+    height: i32,
+    style: &HighlightStyle,
+    dash_offset: u32,
+) {
+    let stride = (width * 4) as usize;
+    // Windows DIB is BGRA in memory; AC_SRC_ALPHA needs premultiplied alpha
+    let color = style.premultiplied_bgra();
+
+    for (px, py) in border_pixels(width, height, style.border_width) {
+        let idx = (py as usize) * stride + (px as usize) * 4;
+        let bgra = if style.is_dash_on(px, py, width, height, dash_offset) {
+            color
+        } else {
+            [0; 4]
+        };
+        std::ptr::copy_nonoverlapping(bgra.as_ptr(), pixels.add(idx), 4);
+    }
+}
+
 /// Update the window with a new alpha value (0-255)
 unsafe fn update_window_alpha(hwnd: HWND, alpha: u8) {
     RENDER_STATE.with(|state| {
@@ -264,7 +280,24 @@ unsafe extern "system" fn highlight_wnd_proc(
                         ((1.0 - (progress - 0.70) / 0.30) * 255.0) as u8
                     };
 
-                    update_window_alpha(hwnd, alpha);
+                    // Apply the style's animation on top of the fade
+                    let style = RENDER_STATE.with(|state| {
+                        let state = state.borrow();
+                        let rs = state.as_ref()?;
+                        if rs.style.animation == HighlightAnimation::Dash {
+                            draw_border(
+                                rs.pixels,
+                                rs.width,
+                                rs.height,
+                                &rs.style,
+                                HighlightStyle::dash_offset(elapsed_ms),
+                            );
+                        }
+                        Some(rs.style)
+                    });
+                    let opacity = style.map_or(1.0, |s| s.opacity_at(elapsed_ms));
+
+                    update_window_alpha(hwnd, (alpha as f32 * opacity) as u8);
                 }
             }
             LRESULT(0)
@@ -1,19 +1,28 @@
 //! Windows platform capture implementation.
 
 pub mod audio;
+pub mod exclusive_audio;
+pub mod fullscreen;
+pub mod gpu_memory;
 mod highlight;
 mod monitor_list;
+pub mod monitor_watch;
+pub mod power_watch;
+pub mod probe;
 mod recorder;
 mod region;
 pub mod thumbnail;
+mod window_follow;
 mod window_list;
 
+pub use monitor_list::get_display_color_profile;
+
 use crate::capture::error::{CaptureError, EnumerationError};
 use crate::capture::types::{FrameReceiver, StopHandle};
 use crate::capture::{
-    AudioEnumerator, AudioSource, CaptureBackend, CaptureRegion,
-    HighlightProvider, MonitorEnumerator, MonitorInfo, ThumbnailCapture, ThumbnailResult,
-    WindowEnumerator, WindowInfo,
+    AudioEnumerator, AudioSource, CaptureBackend, CaptureRegion, HighlightProvider, HighlightStyle,
+    MonitorEnumerator, MonitorInfo, Screenshot, ThumbnailCapture, ThumbnailOptions,
+    ThumbnailResult, WindowEnumerator, WindowInfo,
 };
 
 /// Windows platform capture backend.
@@ -49,6 +58,10 @@ impl CaptureBackend for WindowsBackend {
         &self,
         window_handle: isize,
     ) -> Result<(FrameReceiver, StopHandle), CaptureError> {
+        if use_window_follow(window_handle) {
+            return window_follow::start_capture(window_handle)
+                .map_err(CaptureError::PlatformError);
+        }
         recorder::start_capture(window_handle).map_err(CaptureError::PlatformError)
     }
 
@@ -72,14 +85,37 @@ impl CaptureBackend for WindowsBackend {
             y: 0,
             width,
             height,
+            aspect_ratio: None,
         };
         region::start_region_capture(region).map_err(CaptureError::PlatformError)
     }
+
+    /// Available when the startup probe found no missing dependencies.
+    fn is_available(&self) -> bool {
+        crate::capture::probe::get_probe_result().ok
+    }
+}
+
+/// Whether to capture a window through its monitor (window-follow mode).
+///
+/// Games in exclusive fullscreen can only be captured as a window.
+fn use_window_follow(window_handle: isize) -> bool {
+    if !crate::capture::window_follow::is_enabled() || fullscreen::force_wgc_for_games() {
+        return false;
+    }
+    if fullscreen::is_exclusive_fullscreen(window_handle) {
+        tracing::warn!(
+            "[Windows] Window {} is in exclusive fullscreen, switching from monitor capture to window capture",
+            window_handle
+        );
+        return false;
+    }
+    true
 }
 
 impl HighlightProvider for WindowsBackend {
-    fn show_highlight(&self, x: i32, y: i32, width: i32, height: i32) {
-        highlight::show_highlight(x, y, width, height);
+    fn show_highlight(&self, x: i32, y: i32, width: i32, height: i32, style: &HighlightStyle) {
+        highlight::show_highlight(x, y, width, height, *style);
     }
 }
 
@@ -87,12 +123,17 @@ impl ThumbnailCapture for WindowsBackend {
     fn capture_window_thumbnail(
         &self,
         window_handle: isize,
+        options: ThumbnailOptions,
     ) -> Result<ThumbnailResult, CaptureError> {
-        thumbnail::WindowsThumbnailCapture::new().capture_window_thumbnail(window_handle)
+        thumbnail::WindowsThumbnailCapture::new().capture_window_thumbnail(window_handle, options)
     }
 
-    fn capture_display_thumbnail(&self, monitor_id: &str) -> Result<ThumbnailResult, CaptureError> {
-        thumbnail::WindowsThumbnailCapture::new().capture_display_thumbnail(monitor_id)
+    fn capture_display_thumbnail(
+        &self,
+        monitor_id: &str,
+        options: ThumbnailOptions,
+    ) -> Result<ThumbnailResult, CaptureError> {
+        thumbnail::WindowsThumbnailCapture::new().capture_display_thumbnail(monitor_id, options)
     }
 
     fn capture_region_preview(
@@ -102,9 +143,18 @@ impl ThumbnailCapture for WindowsBackend {
         y: i32,
         width: u32,
         height: u32,
+        quality: u8,
     ) -> Result<ThumbnailResult, CaptureError> {
         thumbnail::WindowsThumbnailCapture::new()
-            .capture_region_preview(monitor_id, x, y, width, height)
+            .capture_region_preview(monitor_id, x, y, width, height, quality)
+    }
+
+    fn capture_window_screenshot(&self, window_handle: isize) -> Result<Screenshot, CaptureError> {
+        thumbnail::WindowsThumbnailCapture::new().capture_window_screenshot(window_handle)
+    }
+
+    fn capture_display_screenshot(&self, monitor_id: &str) -> Result<Screenshot, CaptureError> {
+        thumbnail::WindowsThumbnailCapture::new().capture_display_screenshot(monitor_id)
     }
 }
 
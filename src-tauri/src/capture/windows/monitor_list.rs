@@ -1,15 +1,18 @@
 //! Monitor enumeration using Windows API.
 
+use crate::capture::color_profile::ColorProfile;
 use crate::capture::MonitorInfo;
 use std::ffi::OsString;
-use std::os::windows::ffi::OsStringExt;
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
 use windows::Win32::Foundation::{LPARAM, RECT};
 use windows::Win32::Graphics::Gdi::{
-    EnumDisplayDevicesW, EnumDisplayMonitors, GetMonitorInfoW, DISPLAY_DEVICEW,
-    DISPLAY_DEVICE_ACTIVE, DISPLAY_DEVICE_STATE_FLAGS, HDC, HMONITOR, MONITORINFOEXW,
+    CreateDCW, DeleteDC, EnumDisplayDevicesW, EnumDisplayMonitors, GetMonitorInfoW,
+    DISPLAY_DEVICEW, DISPLAY_DEVICE_ACTIVE, DISPLAY_DEVICE_STATE_FLAGS, HDC, HMONITOR,
+    MONITORINFOEXW,
 };
+use windows::Win32::UI::ColorSystem::GetICMProfileW;
 use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
-use windows_core::BOOL;
+use windows_core::{BOOL, PCWSTR, PWSTR};
 
 /// List all connected monitors.
 pub fn list_monitors() -> Vec<MonitorInfo> {
@@ -25,7 +28,7 @@ pub fn list_monitors() -> Vec<MonitorInfo> {
     }
 
     // Sort so primary is first
-    monitors.sort_by(|a, b| b.is_primary.cmp(&a.is_primary));
+    monitors.sort_by_key(|m| std::cmp::Reverse(m.is_primary));
 
     monitors
 }
@@ -151,6 +154,45 @@ fn get_display_friendly_name(device_name: &str) -> Option<String> {
     None
 }
 
+/// Get the color profile of a monitor from its ICM profile.
+///
+/// `monitor_id` is the GDI device name returned by [`list_monitors`].
+pub fn get_display_color_profile(monitor_id: &str) -> Option<ColorProfile> {
+    let device: Vec<u16> = std::ffi::OsStr::new(monitor_id)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let path = unsafe {
+        let device = PCWSTR(device.as_ptr());
+        let hdc = CreateDCW(device, device, PCWSTR::null(), None);
+        if hdc.is_invalid() {
+            tracing::debug!("[ColorProfile] CreateDCW failed for {}", monitor_id);
+            return None;
+        }
+
+        let mut buffer = [0u16; 260];
+        let mut len = buffer.len() as u32;
+        let ok = GetICMProfileW(hdc, &mut len, Some(PWSTR(buffer.as_mut_ptr())));
+        let _ = DeleteDC(hdc);
+        if !ok.as_bool() {
+            tracing::debug!("[ColorProfile] No ICM profile for {}", monitor_id);
+            return None;
+        }
+
+        let path_len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        OsString::from_wide(&buffer[..path_len])
+    };
+
+    match std::fs::read(&path) {
+        Ok(data) => ColorProfile::from_icc(&data),
+        Err(e) => {
+            tracing::debug!("[ColorProfile] Failed to read {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
 /// Format a basic monitor name.
 fn format_monitor_name(device_name: &str, is_primary: bool) -> String {
     let suffix = if is_primary { " (Primary)" } else { "" };
@@ -0,0 +1,92 @@
+//! Display configuration watcher using `WM_DISPLAYCHANGE`.
+//!
+//! Windows broadcasts `WM_DISPLAYCHANGE` to top-level windows when the
+//! resolution changes or a monitor is connected or disconnected, so a hidden
+//! (never shown) top-level window is created on a dedicated thread to receive it.
+
+use std::sync::OnceLock;
+use std::thread;
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW, MSG,
+    WM_DISPLAYCHANGE, WNDCLASSW, WS_EX_TOOLWINDOW, WS_POPUP,
+};
+
+/// Callback invoked from the window procedure.
+static ON_CHANGE: OnceLock<fn()> = OnceLock::new();
+
+/// Start watching for display changes, calling `on_change` for each one.
+///
+/// Only the first call starts a watcher.
+pub fn start(on_change: fn()) {
+    if ON_CHANGE.set(on_change).is_err() {
+        return;
+    }
+    let spawned = thread::Builder::new()
+        .name("display-watch".to_string())
+        .spawn(|| unsafe { run_display_watch_window() });
+    if let Err(e) = spawned {
+        tracing::warn!("[MonitorWatch] Failed to start display watcher: {}", e);
+    }
+}
+
+unsafe fn run_display_watch_window() {
+    let class_name: Vec<u16> = "OmniRecDisplayWatch\0".encode_utf16().collect();
+    let hmodule = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+    let hinstance = HINSTANCE(hmodule.0);
+
+    let wc = WNDCLASSW {
+        lpfnWndProc: Some(display_watch_wnd_proc),
+        hInstance: hinstance,
+        lpszClassName: PCWSTR(class_name.as_ptr()),
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+
+    // Message-only windows do not receive broadcasts, so this is a regular
+    // top-level window that is never shown.
+    let hwnd = CreateWindowExW(
+        WS_EX_TOOLWINDOW,
+        PCWSTR(class_name.as_ptr()),
+        PCWSTR::null(),
+        WS_POPUP,
+        0,
+        0,
+        0,
+        0,
+        Some(HWND::default()),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap_or_default();
+
+    if hwnd.0.is_null() {
+        tracing::warn!("[MonitorWatch] Failed to create display watch window");
+        return;
+    }
+
+    tracing::debug!("[MonitorWatch] Watching for WM_DISPLAYCHANGE");
+    let mut msg = MSG::default();
+    while GetMessageW(&mut msg, Some(HWND::default()), 0, 0).as_bool() {
+        DispatchMessageW(&msg);
+    }
+}
+
+unsafe extern "system" fn display_watch_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_DISPLAYCHANGE {
+        if let Some(on_change) = ON_CHANGE.get() {
+            on_change();
+        }
+        return LRESULT(0);
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
@@ -0,0 +1,102 @@
+//! Sleep and wake watcher using `WM_POWERBROADCAST`.
+//!
+//! Windows sends `PBT_APMSUSPEND` to top-level windows before the system
+//! sleeps or hibernates, and `PBT_APMRESUMEAUTOMATIC` when it wakes (with or
+//! without user input), so a hidden top-level window is created on a
+//! dedicated thread to receive them.
+
+use std::sync::OnceLock;
+use std::thread;
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW, MSG,
+    PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND, WM_POWERBROADCAST, WNDCLASSW, WS_EX_TOOLWINDOW,
+    WS_POPUP,
+};
+
+use crate::capture::power_watch::PowerEvent;
+
+/// Callback invoked from the window procedure.
+static ON_EVENT: OnceLock<fn(PowerEvent)> = OnceLock::new();
+
+/// Start watching for sleep and wake, calling `on_event` for each one.
+///
+/// Only the first call starts a watcher.
+pub fn start(on_event: fn(PowerEvent)) {
+    if ON_EVENT.set(on_event).is_err() {
+        return;
+    }
+    let spawned = thread::Builder::new()
+        .name("power-watch".to_string())
+        .spawn(|| unsafe { run_power_watch_window() });
+    if let Err(e) = spawned {
+        tracing::warn!("[PowerWatch] Failed to start power watcher: {}", e);
+    }
+}
+
+unsafe fn run_power_watch_window() {
+    let class_name: Vec<u16> = "OmniRecPowerWatch\0".encode_utf16().collect();
+    let hmodule = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+    let hinstance = HINSTANCE(hmodule.0);
+
+    let wc = WNDCLASSW {
+        lpfnWndProc: Some(power_watch_wnd_proc),
+        hInstance: hinstance,
+        lpszClassName: PCWSTR(class_name.as_ptr()),
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+
+    // Message-only windows do not receive broadcasts, so this is a regular
+    // top-level window that is never shown.
+    let hwnd = CreateWindowExW(
+        WS_EX_TOOLWINDOW,
+        PCWSTR(class_name.as_ptr()),
+        PCWSTR::null(),
+        WS_POPUP,
+        0,
+        0,
+        0,
+        0,
+        Some(HWND::default()),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap_or_default();
+
+    if hwnd.0.is_null() {
+        tracing::warn!("[PowerWatch] Failed to create power watch window");
+        return;
+    }
+
+    tracing::debug!("[PowerWatch] Watching for WM_POWERBROADCAST");
+    let mut msg = MSG::default();
+    while GetMessageW(&mut msg, Some(HWND::default()), 0, 0).as_bool() {
+        DispatchMessageW(&msg);
+    }
+}
+
+unsafe extern "system" fn power_watch_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_POWERBROADCAST {
+        let event = match wparam.0 as u32 {
+            PBT_APMSUSPEND => Some(PowerEvent::Sleep),
+            PBT_APMRESUMEAUTOMATIC => Some(PowerEvent::Wake),
+            _ => None,
+        };
+        if let (Some(event), Some(on_event)) = (event, ON_EVENT.get()) {
+            on_event(event);
+        }
+        // TRUE grants the request; ignored for these notifications
+        return LRESULT(1);
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
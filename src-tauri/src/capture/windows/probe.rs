@@ -0,0 +1,27 @@
+//! Windows capture dependency probe.
+
+use crate::capture::probe::BackendProbeResult;
+use windows_capture::graphics_capture_api::GraphicsCaptureApi;
+
+/// Check that Windows.Graphics.Capture is usable on this system.
+pub fn probe_capture_backend() -> BackendProbeResult {
+    let mut result = BackendProbeResult::default();
+
+    match GraphicsCaptureApi::is_supported() {
+        Ok(true) => {}
+        Ok(false) => result.error(
+            "Windows Graphics Capture is not supported. Windows 10 version 1903 or later is required.",
+        ),
+        Err(e) => result.error(format!(
+            "Failed to query Windows Graphics Capture support: {}",
+            e
+        )),
+    }
+
+    // Without the border toggle, Windows draws a yellow frame around captured content.
+    if !GraphicsCaptureApi::is_border_settings_supported().unwrap_or(false) {
+        result.warn("Capture border cannot be hidden on this Windows version");
+    }
+
+    result
+}
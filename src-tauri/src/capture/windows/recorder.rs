@@ -1,6 +1,9 @@
 //! Window recording using Windows.Graphics.Capture API via windows-capture crate.
 
-use crate::capture::types::CapturedFrame;
+use crate::capture::frame_limit::{self, TokenBucket};
+use crate::capture::frame_pool::FramePool;
+use crate::capture::types::{CapturedFrame, PixelFormat, PresentationClock};
+use crate::capture::windows::gpu_memory::GpuMemoryProbe;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -25,6 +28,10 @@ pub struct CaptureFlags {
 struct CaptureHandler {
     frame_tx: mpsc::Sender<CapturedFrame>,
     stop_flag: Arc<AtomicBool>,
+    frame_limit: Option<TokenBucket>,
+    frame_pool: Arc<FramePool>,
+    gpu_memory: Option<GpuMemoryProbe>,
+    clock: PresentationClock,
 }
 
 impl GraphicsCaptureApiHandler for CaptureHandler {
@@ -35,6 +42,10 @@ impl GraphicsCaptureApiHandler for CaptureHandler {
         Ok(Self {
             frame_tx: ctx.flags.frame_tx,
             stop_flag: ctx.flags.stop_flag,
+            frame_limit: TokenBucket::for_current_limit(),
+            frame_pool: FramePool::new(0),
+            gpu_memory: GpuMemoryProbe::new(),
+            clock: PresentationClock::new(),
         })
     }
 
@@ -49,6 +60,23 @@ impl GraphicsCaptureApiHandler for CaptureHandler {
             return Ok(());
         }
 
+        // Drop frames above the frame rate limit before copying them
+        if !frame_limit::admit(&mut self.frame_limit) {
+            return Ok(());
+        }
+
+        // Throttle while video memory is nearly exhausted
+        if let Some(probe) = &mut self.gpu_memory {
+            if !probe.admit_frame() {
+                return Ok(());
+            }
+        }
+
+        // SystemRelativeTime of the frame, in 100ns units
+        let timestamp_us = self
+            .clock
+            .timestamp_us(Some(frame.timestamp().Duration / 10));
+
         // Get frame buffer
         let mut buffer = frame.buffer()?;
         let width = buffer.width();
@@ -59,28 +87,30 @@ impl GraphicsCaptureApiHandler for CaptureHandler {
         let buffer_stride = raw_data.len() / height as usize;
         let expected_stride = (width as usize) * 4; // BGRA = 4 bytes per pixel
 
-        // Copy pixel data, handling stride padding if present
-        let data = if buffer_stride == expected_stride {
+        // Copy pixel data into a pooled buffer, handling stride padding if present
+        let frame_size = expected_stride * height as usize;
+        let mut data = self.frame_pool.acquire(frame_size);
+        if buffer_stride == expected_stride {
             // No padding, copy directly
-            raw_data.to_vec()
+            data.copy_from_slice(&raw_data[..frame_size]);
         } else {
             // Buffer has stride padding - extract only the actual pixel data row by row
-            let mut output = Vec::with_capacity(expected_stride * height as usize);
-            for row in 0..height as usize {
+            for (row, output) in data.chunks_exact_mut(expected_stride.max(1)).enumerate() {
                 let src_start = row * buffer_stride;
                 let src_end = src_start + expected_stride;
                 if src_end <= raw_data.len() {
-                    output.extend_from_slice(&raw_data[src_start..src_end]);
+                    output.copy_from_slice(&raw_data[src_start..src_end]);
                 }
             }
-            output
-        };
+        }
 
         let captured_frame = CapturedFrame {
             width,
             height,
-            data,
+            data: data.into(),
+            pixel_format: PixelFormat::Bgra,
             captured_at: std::time::SystemTime::now(),
+            timestamp_us,
         };
 
         // Try to send frame, don't block if channel is full (drop frame instead)
@@ -1,10 +1,13 @@
 //! Region recording using Windows.Graphics.Capture API for monitor capture with cropping.
 
-use crate::capture::types::CapturedFrame;
+use crate::capture::frame_limit::{self, TokenBucket};
+use crate::capture::frame_pool::FramePool;
+use crate::capture::types::{CapturedFrame, PixelFormat, PresentationClock};
+use crate::capture::windows::gpu_memory::GpuMemoryProbe;
 use crate::capture::windows::monitor_list;
 use crate::capture::CaptureRegion;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 use windows_capture::{
     capture::{Context, GraphicsCaptureApiHandler},
@@ -21,14 +24,19 @@ use windows_capture::{
 pub struct RegionCaptureFlags {
     pub frame_tx: mpsc::Sender<CapturedFrame>,
     pub stop_flag: Arc<AtomicBool>,
-    pub region: CaptureRegion,
+    /// Region to crop to; may be moved while capturing (see `window_follow`)
+    pub region: Arc<Mutex<CaptureRegion>>,
 }
 
 /// Frame capture handler for monitor-based region capture.
 struct RegionCaptureHandler {
     frame_tx: mpsc::Sender<CapturedFrame>,
     stop_flag: Arc<AtomicBool>,
-    region: CaptureRegion,
+    region: Arc<Mutex<CaptureRegion>>,
+    frame_limit: Option<TokenBucket>,
+    frame_pool: Arc<FramePool>,
+    gpu_memory: Option<GpuMemoryProbe>,
+    clock: PresentationClock,
     #[allow(dead_code)]
     frame_count: u64,
     #[allow(dead_code)]
@@ -44,6 +52,10 @@ impl GraphicsCaptureApiHandler for RegionCaptureHandler {
             frame_tx: ctx.flags.frame_tx,
             stop_flag: ctx.flags.stop_flag,
             region: ctx.flags.region,
+            frame_limit: TokenBucket::for_current_limit(),
+            frame_pool: FramePool::new(0),
+            gpu_memory: GpuMemoryProbe::new(),
+            clock: PresentationClock::new(),
             frame_count: 0,
             dropped_count: 0,
         })
@@ -60,6 +72,23 @@ impl GraphicsCaptureApiHandler for RegionCaptureHandler {
             return Ok(());
         }
 
+        // Drop frames above the frame rate limit before copying them
+        if !frame_limit::admit(&mut self.frame_limit) {
+            return Ok(());
+        }
+
+        // Throttle while video memory is nearly exhausted
+        if let Some(probe) = &mut self.gpu_memory {
+            if !probe.admit_frame() {
+                return Ok(());
+            }
+        }
+
+        // SystemRelativeTime of the frame, in 100ns units
+        let timestamp_us = self
+            .clock
+            .timestamp_us(Some(frame.timestamp().Duration / 10));
+
         // Get frame buffer
         let mut buffer = frame.buffer()?;
         let full_width = buffer.width();
@@ -71,10 +100,15 @@ impl GraphicsCaptureApiHandler for RegionCaptureHandler {
 
         // Region coordinates from the frontend are already in physical pixels
         // (matching the frame buffer coordinates). No conversion needed.
-        let region_x = self.region.x.max(0) as u32;
-        let region_y = self.region.y.max(0) as u32;
-        let region_width = self.region.width;
-        let region_height = self.region.height;
+        let (region_x, region_y, region_width, region_height) = {
+            let region = self.region.lock().unwrap_or_else(|e| e.into_inner());
+            (
+                region.x.max(0) as u32,
+                region.y.max(0) as u32,
+                region.width,
+                region.height,
+            )
+        };
 
         // Debug: log frame dimensions on first frame
         if self.frame_count == 0 {
@@ -105,22 +139,26 @@ impl GraphicsCaptureApiHandler for RegionCaptureHandler {
             return Ok(());
         }
 
-        // Crop the frame to the region
-        let cropped_data = crop_frame(
+        // Crop the frame to the region, into a pooled buffer
+        let mut cropped_data = self
+            .frame_pool
+            .acquire(region_width as usize * region_height as usize * 4);
+        crop_frame(
             raw_data,
-            full_width,
             buffer_stride,
             region_x,
             region_y,
             region_width,
-            region_height,
+            &mut cropped_data,
         );
 
         let captured_frame = CapturedFrame {
             width: region_width,
             height: region_height,
-            data: cropped_data,
+            data: cropped_data.into(),
+            pixel_format: PixelFormat::Bgra,
             captured_at: std::time::SystemTime::now(),
+            timestamp_us,
         };
 
         // Try to send frame
@@ -152,39 +190,28 @@ impl GraphicsCaptureApiHandler for RegionCaptureHandler {
 ///
 /// # Arguments
 /// * `data` - Source BGRA pixel data
-/// * `full_width` - Full frame width in pixels
 /// * `buffer_stride` - Bytes per row in the source buffer (may include padding)
 /// * `x`, `y` - Region top-left position
-/// * `width`, `height` - Region dimensions
-fn crop_frame(
-    data: &[u8],
-    _full_width: u32,
-    buffer_stride: usize,
-    x: u32,
-    y: u32,
-    width: u32,
-    height: u32,
-) -> Vec<u8> {
+/// * `width` - Region width; the height follows from `output`'s length
+/// * `output` - Destination for the region's rows
+fn crop_frame(data: &[u8], buffer_stride: usize, x: u32, y: u32, width: u32, output: &mut [u8]) {
     let pixel_stride = 4usize; // BGRA
     let output_row_bytes = (width as usize) * pixel_stride;
-    let mut output = Vec::with_capacity(output_row_bytes * height as usize);
 
-    for row in 0..height {
-        let src_y = (y + row) as usize;
+    for (row, output_row) in output.chunks_exact_mut(output_row_bytes.max(1)).enumerate() {
+        let src_y = y as usize + row;
         let src_row_start = src_y * buffer_stride;
         let src_x_offset = (x as usize) * pixel_stride;
         let src_start = src_row_start + src_x_offset;
         let src_end = src_start + output_row_bytes;
 
         if src_end <= data.len() {
-            output.extend_from_slice(&data[src_start..src_end]);
+            output_row.copy_from_slice(&data[src_start..src_end]);
         } else {
             // Fill with black if out of bounds
-            output.extend(std::iter::repeat_n(0u8, output_row_bytes));
+            output_row.fill(0);
         }
     }
-
-    output
 }
 
 /// Find a monitor by its device ID.
@@ -268,8 +295,22 @@ pub fn start_region_capture(
     }
     tracing::debug!("[Windows] =============================");
 
+    start_shared_region_capture(Arc::new(Mutex::new(region)))
+}
+
+/// Start capturing a region that may be moved while capturing.
+///
+/// The region must stay on the monitor it starts on.
+pub(super) fn start_shared_region_capture(
+    region: Arc<Mutex<CaptureRegion>>,
+) -> Result<(mpsc::Receiver<CapturedFrame>, Arc<AtomicBool>), String> {
     // Find the monitor for capture
-    let monitor = find_monitor_by_id(&region.monitor_id)?;
+    let monitor_id = region
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .monitor_id
+        .clone();
+    let monitor = find_monitor_by_id(&monitor_id)?;
 
     // Create channel for frames (larger buffer for region capture which may have bursty delivery)
     let (frame_tx, frame_rx) = mpsc::channel::<CapturedFrame>(120);
@@ -358,7 +399,8 @@ mod tests {
         }
 
         // Crop a 2x2 region starting at (1, 1)
-        let cropped = crop_frame(&data, 4, 16, 1, 1, 2, 2);
+        let mut cropped = vec![0u8; 2 * 2 * 4];
+        crop_frame(&data, 16, 1, 1, 2, &mut cropped);
 
         // Expected: pixels 5,6 and 9,10
         assert_eq!(cropped.len(), 2 * 2 * 4); // 2x2 pixels, 4 bytes each
@@ -6,11 +6,11 @@
 
 use crate::capture::error::CaptureError;
 use crate::capture::thumbnail::{
-    bgra_to_jpeg_thumbnail, PREVIEW_MAX_HEIGHT, PREVIEW_MAX_WIDTH, THUMBNAIL_MAX_HEIGHT,
-    THUMBNAIL_MAX_WIDTH,
+    bgra_to_jpeg_thumbnail, icon_to_jpeg_thumbnail, ThumbnailOptions, PREVIEW_MAX_HEIGHT,
+    PREVIEW_MAX_WIDTH,
 };
-use crate::capture::types::CapturedFrame;
-use crate::capture::{ThumbnailCapture, ThumbnailResult};
+use crate::capture::types::{self, CapturedFrame, PixelFormat};
+use crate::capture::{Screenshot, ThumbnailCapture, ThumbnailResult};
 
 use super::monitor_list;
 
@@ -19,6 +19,21 @@ use std::sync::mpsc as std_mpsc;
 use std::sync::Arc;
 use std::time::Duration;
 
+use windows::core::{PCWSTR, PWSTR};
+use windows::Win32::Foundation::{CloseHandle, HWND};
+use windows::Win32::Graphics::Gdi::{
+    DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO, BITMAPINFOHEADER,
+    BI_RGB, DIB_RGB_COLORS, HBITMAP,
+};
+use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
+use windows::Win32::System::ProcessStatus::EnumProcesses;
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::Shell::{SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON};
+use windows::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO};
+
 use windows_capture::{
     capture::{Context, GraphicsCaptureApiHandler},
     frame::Frame,
@@ -98,8 +113,10 @@ impl GraphicsCaptureApiHandler for SingleFrameHandler {
         let captured_frame = CapturedFrame {
             width,
             height,
-            data,
+            data: data.into(),
+            pixel_format: PixelFormat::Bgra,
             captured_at: std::time::SystemTime::now(),
+            timestamp_us: types::timestamp_us(std::time::Instant::now()),
         };
 
         // Send the frame (ignore errors - receiver may have timed out)
@@ -246,6 +263,144 @@ fn crop_frame(
     cropped
 }
 
+/// Application icon for a process, shown when the window can't be captured.
+///
+/// The executable is located among the running processes by name and its
+/// icon is extracted with `SHGetFileInfoW`.
+pub fn get_fallback_icon(process_name: &str) -> Option<ThumbnailResult> {
+    let exe_path = find_process_image_path(process_name)?;
+
+    // SHGetFileInfoW requires COM on the calling thread
+    let com_initialized = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok() };
+    let pixels = unsafe { extract_file_icon(&exe_path) };
+    if com_initialized {
+        unsafe { CoUninitialize() };
+    }
+
+    let (data, width, height) = pixels?;
+    let (data, width, height) = icon_to_jpeg_thumbnail(&data, width, height).ok()?;
+    Some(ThumbnailResult {
+        data,
+        width,
+        height,
+    })
+}
+
+/// Null-terminated path of the first running executable named `process_name`.
+fn find_process_image_path(process_name: &str) -> Option<Vec<u16>> {
+    let mut pids = vec![0u32; 4096];
+    let mut bytes_returned = 0u32;
+    unsafe {
+        EnumProcesses(
+            pids.as_mut_ptr(),
+            (pids.len() * std::mem::size_of::<u32>()) as u32,
+            &mut bytes_returned,
+        )
+    }
+    .ok()?;
+    pids.truncate(bytes_returned as usize / std::mem::size_of::<u32>());
+
+    pids.into_iter()
+        .filter(|pid| *pid != 0)
+        .find_map(|pid| unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut path = vec![0u16; 1024];
+            let mut len = path.len() as u32;
+            let queried = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(path.as_mut_ptr()),
+                &mut len,
+            );
+            let _ = CloseHandle(process);
+            queried.ok()?;
+
+            path.truncate(len as usize);
+            let file_name = String::from_utf16_lossy(&path);
+            let file_name = file_name.rsplit('\\').next()?;
+            if !file_name.eq_ignore_ascii_case(process_name) {
+                return None;
+            }
+            path.push(0);
+            Some(path)
+        })
+}
+
+/// Large shell icon of a file as BGRA pixels with straight alpha.
+unsafe fn extract_file_icon(path: &[u16]) -> Option<(Vec<u8>, u32, u32)> {
+    let mut info = SHFILEINFOW::default();
+    let found = SHGetFileInfoW(
+        PCWSTR(path.as_ptr()),
+        FILE_FLAGS_AND_ATTRIBUTES(0),
+        Some(&mut info),
+        std::mem::size_of::<SHFILEINFOW>() as u32,
+        SHGFI_ICON | SHGFI_LARGEICON,
+    );
+    if found == 0 || info.hIcon.is_invalid() {
+        return None;
+    }
+    let pixels = icon_to_bgra(info.hIcon);
+    let _ = DestroyIcon(info.hIcon);
+    pixels
+}
+
+unsafe fn icon_to_bgra(icon: HICON) -> Option<(Vec<u8>, u32, u32)> {
+    let mut icon_info = ICONINFO::default();
+    GetIconInfo(icon, &mut icon_info).ok()?;
+    let pixels = bitmap_to_bgra(icon_info.hbmColor);
+    let _ = DeleteObject(icon_info.hbmColor.into());
+    let _ = DeleteObject(icon_info.hbmMask.into());
+    pixels
+}
+
+unsafe fn bitmap_to_bgra(bitmap: HBITMAP) -> Option<(Vec<u8>, u32, u32)> {
+    // Monochrome icons have no color bitmap
+    if bitmap.is_invalid() {
+        return None;
+    }
+    let mut header = BITMAP::default();
+    let size = std::mem::size_of::<BITMAP>() as i32;
+    if GetObjectW(bitmap.into(), size, Some(&mut header as *mut _ as *mut _)) == 0 {
+        return None;
+    }
+    let width = header.bmWidth.max(0) as u32;
+    let height = header.bmHeight.max(0) as u32;
+
+    let mut bmi = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width as i32,
+            biHeight: -(height as i32), // Top-down DIB
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut data = vec![0u8; width as usize * height as usize * 4];
+    let screen_dc = GetDC(Some(HWND::default()));
+    let lines = GetDIBits(
+        screen_dc,
+        bitmap,
+        0,
+        height,
+        Some(data.as_mut_ptr().cast()),
+        &mut bmi,
+        DIB_RGB_COLORS,
+    );
+    let _ = ReleaseDC(Some(HWND::default()), screen_dc);
+    if lines == 0 {
+        return None;
+    }
+
+    // Icons without an alpha channel leave it zeroed; treat them as opaque
+    if data.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+        data.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 255);
+    }
+    Some((data, width, height))
+}
+
 /// Windows thumbnail capture implementation.
 pub struct WindowsThumbnailCapture;
 
@@ -253,18 +408,12 @@ impl WindowsThumbnailCapture {
     pub fn new() -> Self {
         Self
     }
-}
-
-impl Default for WindowsThumbnailCapture {
-    fn default() -> Self {
-        Self::new()
-    }
-}
 
-impl ThumbnailCapture for WindowsThumbnailCapture {
-    fn capture_window_thumbnail(
+    /// Capture a window and scale it to a thumbnail.
+    fn capture_window_image(
         &self,
         window_handle: isize,
+        options: ThumbnailOptions,
     ) -> Result<ThumbnailResult, CaptureError> {
         // Capture single frame from window
         let frame = capture_window_frame(window_handle)?;
@@ -274,8 +423,9 @@ impl ThumbnailCapture for WindowsThumbnailCapture {
             &frame.data,
             frame.width,
             frame.height,
-            THUMBNAIL_MAX_WIDTH,
-            THUMBNAIL_MAX_HEIGHT,
+            options.max_width,
+            options.max_height,
+            options.quality,
         )
         .map_err(CaptureError::PlatformError)?;
 
@@ -285,8 +435,40 @@ impl ThumbnailCapture for WindowsThumbnailCapture {
             height: thumb_height,
         })
     }
+}
+
+impl Default for WindowsThumbnailCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThumbnailCapture for WindowsThumbnailCapture {
+    fn capture_window_thumbnail(
+        &self,
+        window_handle: isize,
+        options: ThumbnailOptions,
+    ) -> Result<ThumbnailResult, CaptureError> {
+        match self.capture_window_image(window_handle, options) {
+            Ok(result) => Ok(result),
+            Err(e) => crate::capture::window_process_name(window_handle)
+                .and_then(|process_name| get_fallback_icon(&process_name))
+                .inspect(|_| {
+                    tracing::debug!(
+                        "Window {} thumbnail failed ({}), using application icon",
+                        window_handle,
+                        e
+                    )
+                })
+                .ok_or(e),
+        }
+    }
 
-    fn capture_display_thumbnail(&self, monitor_id: &str) -> Result<ThumbnailResult, CaptureError> {
+    fn capture_display_thumbnail(
+        &self,
+        monitor_id: &str,
+        options: ThumbnailOptions,
+    ) -> Result<ThumbnailResult, CaptureError> {
         // Capture single frame from monitor
         let frame = capture_monitor_frame(monitor_id)?;
 
@@ -295,8 +477,9 @@ impl ThumbnailCapture for WindowsThumbnailCapture {
             &frame.data,
             frame.width,
             frame.height,
-            THUMBNAIL_MAX_WIDTH,
-            THUMBNAIL_MAX_HEIGHT,
+            options.max_width,
+            options.max_height,
+            options.quality,
         )
         .map_err(CaptureError::PlatformError)?;
 
@@ -314,6 +497,7 @@ impl ThumbnailCapture for WindowsThumbnailCapture {
         y: i32,
         width: u32,
         height: u32,
+        quality: u8,
     ) -> Result<ThumbnailResult, CaptureError> {
         // Validate region
         if width < 10 || height < 10 {
@@ -343,6 +527,7 @@ impl ThumbnailCapture for WindowsThumbnailCapture {
             height,
             PREVIEW_MAX_WIDTH,
             PREVIEW_MAX_HEIGHT,
+            quality,
         )
         .map_err(CaptureError::PlatformError)?;
 
@@ -352,11 +537,30 @@ impl ThumbnailCapture for WindowsThumbnailCapture {
             height: preview_height,
         })
     }
+
+    fn capture_window_screenshot(&self, window_handle: isize) -> Result<Screenshot, CaptureError> {
+        let frame = capture_window_frame(window_handle)?;
+        Ok(Screenshot {
+            data: frame.data.to_vec(),
+            width: frame.width,
+            height: frame.height,
+        })
+    }
+
+    fn capture_display_screenshot(&self, monitor_id: &str) -> Result<Screenshot, CaptureError> {
+        let frame = capture_monitor_frame(monitor_id)?;
+        Ok(Screenshot {
+            data: frame.data.to_vec(),
+            width: frame.width,
+            height: frame.height,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::capture::DEFAULT_JPEG_QUALITY;
 
     #[test]
     fn test_crop_frame_basic() {
@@ -410,7 +614,8 @@ mod tests {
             return;
         }
 
-        let result = capture.capture_display_thumbnail(&monitors[0].id);
+        let result =
+            capture.capture_display_thumbnail(&monitors[0].id, ThumbnailOptions::default());
 
         // Should succeed on a real system
         match result {
@@ -419,11 +624,11 @@ mod tests {
                 assert!(thumb.width > 0, "Thumbnail width should be positive");
                 assert!(thumb.height > 0, "Thumbnail height should be positive");
                 assert!(
-                    thumb.width <= THUMBNAIL_MAX_WIDTH,
+                    thumb.width <= ThumbnailOptions::default().max_width,
                     "Thumbnail width should be <= max"
                 );
                 assert!(
-                    thumb.height <= THUMBNAIL_MAX_HEIGHT,
+                    thumb.height <= ThumbnailOptions::default().max_height,
                     "Thumbnail height should be <= max"
                 );
                 println!(
@@ -458,7 +663,8 @@ mod tests {
         let x = (monitor.width / 4) as i32;
         let y = (monitor.height / 4) as i32;
 
-        let result = capture.capture_region_preview(&monitor.id, x, y, 200, 200);
+        let result =
+            capture.capture_region_preview(&monitor.id, x, y, 200, 200, DEFAULT_JPEG_QUALITY);
 
         match result {
             Ok(preview) => {
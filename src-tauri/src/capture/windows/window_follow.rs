@@ -0,0 +1,221 @@
+//! Window-follow capture using `EVENT_OBJECT_LOCATIONCHANGE`.
+//!
+//! The monitor the window is on is captured and cropped to the window's
+//! area (see `region`). A WinEvent hook on a dedicated thread moves the crop
+//! region whenever the window moves; out-of-context hooks are delivered
+//! through that thread's message queue, so it pumps messages until the
+//! capture stops.
+
+use crate::capture::types::CapturedFrame;
+use crate::capture::window_follow::follow_origin;
+use crate::capture::CaptureRegion;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, MonitorFromWindow, MONITORINFOEXW, MONITOR_DEFAULTTONEAREST,
+};
+use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
+use windows::Win32::UI::WindowsAndMessaging::{
+    DispatchMessageW, GetWindowRect, GetWindowThreadProcessId, IsIconic, PeekMessageW,
+    TranslateMessage, EVENT_OBJECT_LOCATIONCHANGE, MSG, OBJID_WINDOW, PM_REMOVE,
+    WINEVENT_OUTOFCONTEXT,
+};
+
+use super::region;
+
+/// How often the tracking thread pumps messages and checks for stop.
+const PUMP_INTERVAL: Duration = Duration::from_millis(15);
+
+/// The window being followed, owned by the tracking thread.
+struct FollowTarget {
+    hwnd: HWND,
+    /// Bounds of the monitor being captured (virtual screen coordinates)
+    monitor: RECT,
+    region: Arc<Mutex<CaptureRegion>>,
+}
+
+impl FollowTarget {
+    /// Move the crop region over the window's current position.
+    fn update(&self) {
+        // Minimized windows report an off-screen position
+        if unsafe { IsIconic(self.hwnd) }.as_bool() {
+            return;
+        }
+        let Some(rect) = window_rect(self.hwnd) else {
+            return;
+        };
+        let mut region = self.region.lock().unwrap_or_else(|e| e.into_inner());
+        let (x, y) = follow_origin(
+            rect.left - self.monitor.left,
+            rect.top - self.monitor.top,
+            region.width,
+            region.height,
+            (self.monitor.right - self.monitor.left) as u32,
+            (self.monitor.bottom - self.monitor.top) as u32,
+        );
+        region.x = x as i32;
+        region.y = y as i32;
+    }
+}
+
+thread_local! {
+    static TARGET: RefCell<Option<FollowTarget>> = const { RefCell::new(None) };
+}
+
+/// Current bounds of a window, or None if it no longer exists.
+pub(super) fn window_rect(hwnd: HWND) -> Option<RECT> {
+    let mut rect = RECT::default();
+    unsafe { GetWindowRect(hwnd, &mut rect) }.ok()?;
+    Some(rect)
+}
+
+/// Device name and bounds of the monitor a window is (mostly) on.
+pub(super) fn window_monitor(hwnd: HWND) -> Option<(String, RECT)> {
+    let mut monitor_info = MONITORINFOEXW::default();
+    monitor_info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+    unsafe {
+        let hmonitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        if !GetMonitorInfoW(hmonitor, &mut monitor_info as *mut _ as *mut _).as_bool() {
+            return None;
+        }
+    }
+    let device_name_raw = &monitor_info.szDevice;
+    let device_name_len = device_name_raw
+        .iter()
+        .position(|&c| c == 0)
+        .unwrap_or(device_name_raw.len());
+    Some((
+        String::from_utf16_lossy(&device_name_raw[..device_name_len]),
+        monitor_info.monitorInfo.rcMonitor,
+    ))
+}
+
+/// Start capturing the screen area of a window, following it as it moves.
+///
+/// Returns a tuple of (frame_receiver, stop_flag).
+/// Set stop_flag to true to stop capture.
+pub fn start_capture(
+    window_handle: isize,
+) -> Result<(mpsc::Receiver<CapturedFrame>, Arc<AtomicBool>), String> {
+    let hwnd = HWND(window_handle as *mut std::ffi::c_void);
+    let rect = window_rect(hwnd).ok_or_else(|| format!("Window not found: {}", window_handle))?;
+    let (monitor_id, monitor) = window_monitor(hwnd)
+        .ok_or_else(|| format!("No monitor found for window {}", window_handle))?;
+
+    let monitor_width = (monitor.right - monitor.left) as u32;
+    let monitor_height = (monitor.bottom - monitor.top) as u32;
+    let width = ((rect.right - rect.left).max(0) as u32).min(monitor_width);
+    let height = ((rect.bottom - rect.top).max(0) as u32).min(monitor_height);
+    let (x, y) = follow_origin(
+        rect.left - monitor.left,
+        rect.top - monitor.top,
+        width,
+        height,
+        monitor_width,
+        monitor_height,
+    );
+
+    tracing::debug!(
+        "[Windows] Following window {} on {}: {}x{} at ({}, {})",
+        window_handle,
+        monitor_id,
+        width,
+        height,
+        x,
+        y
+    );
+
+    let region = Arc::new(Mutex::new(CaptureRegion {
+        monitor_id,
+        x: x as i32,
+        y: y as i32,
+        width,
+        height,
+        aspect_ratio: None,
+    }));
+    let (frame_rx, stop_flag) = region::start_shared_region_capture(region.clone())?;
+
+    // HWND is not Send; the handle crosses threads as an integer
+    let tracking_stop_flag = stop_flag.clone();
+    let spawned = std::thread::Builder::new()
+        .name("window-follow".to_string())
+        .spawn(move || {
+            let target = FollowTarget {
+                hwnd: HWND(window_handle as *mut std::ffi::c_void),
+                monitor,
+                region,
+            };
+            unsafe { track_window(target, tracking_stop_flag) }
+        });
+    if let Err(e) = spawned {
+        stop_flag.store(true, Ordering::Relaxed);
+        return Err(format!("Failed to start window tracking: {}", e));
+    }
+
+    Ok((frame_rx, stop_flag))
+}
+
+/// Follow the window until the capture stops or the window is closed.
+unsafe fn track_window(target: FollowTarget, stop_flag: Arc<AtomicBool>) {
+    let hwnd = target.hwnd;
+    let mut process_id = 0u32;
+    let thread_id = GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+    TARGET.with(|t| *t.borrow_mut() = Some(target));
+
+    let hook = SetWinEventHook(
+        EVENT_OBJECT_LOCATIONCHANGE,
+        EVENT_OBJECT_LOCATIONCHANGE,
+        None,
+        Some(on_location_change),
+        process_id,
+        thread_id,
+        WINEVENT_OUTOFCONTEXT,
+    );
+    if hook.is_invalid() {
+        tracing::warn!("[Windows] Failed to hook window location changes; capture area is fixed");
+        return;
+    }
+
+    let mut msg = MSG::default();
+    while !stop_flag.load(Ordering::Relaxed) {
+        while PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+        if window_rect(hwnd).is_none() {
+            tracing::debug!("[Windows] Followed window closed, stopping capture");
+            stop_flag.store(true, Ordering::Relaxed);
+            break;
+        }
+        std::thread::sleep(PUMP_INTERVAL);
+    }
+
+    let _ = UnhookWinEvent(hook);
+    TARGET.with(|t| *t.borrow_mut() = None);
+}
+
+unsafe extern "system" fn on_location_change(
+    _hook: HWINEVENTHOOK,
+    _event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    _id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+) {
+    // Location changes of the window itself, not its carets or controls
+    if id_object != OBJID_WINDOW.0 {
+        return;
+    }
+    TARGET.with(|target| {
+        if let Some(target) = target.borrow().as_ref() {
+            if target.hwnd == hwnd {
+                target.update();
+            }
+        }
+    });
+}
@@ -134,6 +134,8 @@ unsafe extern "system" fn enum_window_callback(hwnd: HWND, lparam: LPARAM) -> BO
         y,
         width,
         height,
+        workspace_id: None,
+        workspace_name: None,
     });
 
     BOOL(1) // Continue enumeration
@@ -0,0 +1,160 @@
+//! Upload of finished recordings to S3-compatible cloud storage.
+//!
+//! Files are sent as multipart uploads in [`UPLOAD_PART_SIZE`] parts, so
+//! progress can be reported per part and an upload can be cancelled between
+//! parts without leaving a partial object behind.
+
+use crate::config::{CloudProvider, CloudUploadConfig};
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutPayload};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::io::AsyncReadExt;
+
+/// Size of each multipart upload part (S3 requires at least 5 MiB).
+pub const UPLOAD_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Build an S3 client for `config`.
+fn build_store(config: &CloudUploadConfig) -> Result<AmazonS3, String> {
+    config.validate()?;
+    let CloudProvider::S3Compatible { endpoint_url } = &config.provider;
+    AmazonS3Builder::new()
+        .with_endpoint(endpoint_url)
+        .with_allow_http(endpoint_url.starts_with("http://"))
+        .with_bucket_name(&config.bucket)
+        .with_region(&config.credentials.region)
+        .with_access_key_id(&config.credentials.access_key_id)
+        .with_secret_access_key(&config.credentials.secret_access_key)
+        .build()
+        .map_err(|e| format!("Invalid cloud storage settings: {}", e))
+}
+
+/// Object key for `file_name` under `prefix`.
+pub fn object_key(prefix: &str, file_name: &str) -> String {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        file_name.to_string()
+    } else {
+        format!("{}/{}", prefix, file_name)
+    }
+}
+
+/// Percentage of `total` bytes uploaded; an empty file is complete.
+pub fn upload_percent(uploaded: u64, total: u64) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        uploaded as f64 / total as f64 * 100.0
+    }
+}
+
+/// Check that the bucket can be listed with the configured credentials.
+pub async fn test_connection(config: &CloudUploadConfig) -> Result<(), String> {
+    let store = build_store(config)?;
+    let prefix = config.prefix.trim_matches('/');
+    let prefix = (!prefix.is_empty()).then(|| ObjectPath::from(prefix));
+    store
+        .list_with_delimiter(prefix.as_ref())
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Cloud storage connection failed: {}", e))
+}
+
+/// Upload `file` under the configured prefix and return its object key.
+///
+/// `progress` is called with the bytes uploaded so far and the file size
+/// after each part. Setting `cancel` aborts the upload before the next part.
+pub async fn upload_file(
+    config: &CloudUploadConfig,
+    file: &Path,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(u64, u64),
+) -> Result<String, String> {
+    let store = build_store(config)?;
+    let file_name = file
+        .file_name()
+        .ok_or_else(|| format!("Not a file: {}", file.display()))?
+        .to_string_lossy();
+    let key = object_key(&config.prefix, &file_name);
+
+    let mut reader = tokio::fs::File::open(file)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", file.display(), e))?;
+    let total = reader
+        .metadata()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?
+        .len();
+
+    let mut upload = store
+        .put_multipart(&ObjectPath::from(key.as_str()))
+        .await
+        .map_err(|e| format!("Failed to start upload: {}", e))?;
+
+    let mut uploaded = 0u64;
+    let result = async {
+        loop {
+            if cancel.load(Ordering::SeqCst) {
+                return Err("Upload cancelled".to_string());
+            }
+            let mut part = Vec::with_capacity(UPLOAD_PART_SIZE);
+            (&mut reader)
+                .take(UPLOAD_PART_SIZE as u64)
+                .read_to_end(&mut part)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            // An empty file still needs one (empty) part
+            if part.is_empty() && uploaded > 0 {
+                break;
+            }
+            let len = part.len() as u64;
+            upload
+                .put_part(PutPayload::from(part))
+                .await
+                .map_err(|e| format!("Upload failed: {}", e))?;
+            uploaded += len;
+            progress(uploaded, total);
+            if len < UPLOAD_PART_SIZE as u64 {
+                break;
+            }
+        }
+        upload
+            .complete()
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to finish upload: {}", e))
+    }
+    .await;
+
+    if let Err(e) = result {
+        if let Err(abort_error) = upload.abort().await {
+            tracing::warn!("[Cloud] Failed to abort upload: {}", abort_error);
+        }
+        return Err(e);
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_key() {
+        assert_eq!(object_key("", "a.mp4"), "a.mp4");
+        assert_eq!(object_key("recordings", "a.mp4"), "recordings/a.mp4");
+        assert_eq!(
+            object_key("/recordings/2026/", "a.mp4"),
+            "recordings/2026/a.mp4"
+        );
+    }
+
+    #[test]
+    fn test_upload_percent() {
+        assert_eq!(upload_percent(0, 0), 100.0);
+        assert_eq!(upload_percent(0, 200), 0.0);
+        assert_eq!(upload_percent(50, 200), 25.0);
+        assert_eq!(upload_percent(200, 200), 100.0);
+    }
+}
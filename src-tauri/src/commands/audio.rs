@@ -6,16 +6,42 @@
 use crate::capture;
 use crate::config::{save_config as save_config_to_disk, AudioConfig};
 use crate::state::get_recording_manager;
-use crate::AppState;
-use omnirec_types::AudioSource;
+use crate::AppConfigState;
+use omnirec_types::{AudioAppInfo, AudioCodec, AudioSource};
 use tauri::State;
 
 /// Get list of available audio sources.
 #[tauri::command]
-pub async fn get_audio_sources(_state: State<'_, AppState>) -> Result<Vec<AudioSource>, String> {
+pub async fn get_audio_sources() -> Result<Vec<AudioSource>, String> {
     Ok(capture::list_audio_sources())
 }
 
+/// List applications currently playing audio, for per-application capture.
+/// Only supported on Linux (PipeWire); returns an empty list elsewhere.
+#[tauri::command]
+pub fn list_running_audio_apps() -> Result<Vec<AudioAppInfo>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        capture::linux::audio::list_running_audio_apps().map_err(|e| e.to_string())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Ok(Vec::new())
+    }
+}
+
+/// Capture `duration_ms` (max 3000) of audio from a source and return its
+/// levels, so the settings panel can show a waveform before recording.
+#[tauri::command]
+pub async fn get_audio_waveform(
+    source_id: String,
+    duration_ms: u64,
+) -> Result<crate::waveform::WaveformResult, String> {
+    get_recording_manager()
+        .capture_audio_waveform(&source_id, duration_ms)
+        .await
+}
+
 /// Check if macOS system audio capture is available (requires macOS 13+).
 /// Returns true on macOS 13+ with ScreenCaptureKit audio support, false otherwise.
 /// On non-macOS platforms, returns false.
@@ -34,7 +60,7 @@ pub fn is_system_audio_available() -> bool {
 /// Get current audio configuration.
 /// This returns the local config stored in the Tauri client.
 #[tauri::command]
-pub async fn get_audio_config(state: State<'_, AppState>) -> Result<AudioConfig, String> {
+pub async fn get_audio_config(state: State<'_, AppConfigState>) -> Result<AudioConfig, String> {
     let config = state.app_config.lock().await;
     Ok(config.audio.clone())
 }
@@ -49,14 +75,30 @@ pub async fn save_audio_config(
     echo_cancellation: bool,
     agc_enabled: Option<bool>,
     agc_noise_gate_enabled: Option<bool>,
-    state: State<'_, AppState>,
+    agc_target_level_dbfs: Option<f32>,
+    state: State<'_, AppConfigState>,
 ) -> Result<(), String> {
+    if let Some(dbfs) = agc_target_level_dbfs {
+        omnirec_types::validate_agc_target_level(dbfs)?;
+    }
+
     // Resolve AGC fields, preserving existing values when not provided.
-    let (resolved_agc_enabled, resolved_agc_noise_gate) = {
+    let (
+        resolved_agc_enabled,
+        resolved_agc_noise_gate,
+        resolved_agc_target,
+        monitor_enabled,
+        monitor_volume,
+        audio_bitrate_kbps,
+    ) = {
         let config = state.app_config.lock().await;
         (
             agc_enabled.unwrap_or(config.audio.agc_enabled),
             agc_noise_gate_enabled.unwrap_or(config.audio.agc_noise_gate_enabled),
+            agc_target_level_dbfs.unwrap_or(config.audio.agc_target_level_dbfs),
+            config.audio.audio_monitor_enabled,
+            config.audio.monitor_volume,
+            config.audio.audio_bitrate_kbps,
         )
     };
 
@@ -70,6 +112,7 @@ pub async fn save_audio_config(
         config.audio.echo_cancellation = echo_cancellation;
         config.audio.agc_enabled = resolved_agc_enabled;
         config.audio.agc_noise_gate_enabled = resolved_agc_noise_gate;
+        config.audio.agc_target_level_dbfs = resolved_agc_target;
 
         // Save to disk
         save_config_to_disk(&config)?;
@@ -85,13 +128,75 @@ pub async fn save_audio_config(
             echo_cancellation,
             agc_enabled: resolved_agc_enabled,
             agc_noise_gate_enabled: resolved_agc_noise_gate,
+            agc_target_level_dbfs: resolved_agc_target,
+            audio_monitor_enabled: monitor_enabled,
+            monitor_volume,
+            audio_bitrate_kbps,
         })
         .await;
 
     tracing::info!(
-        "Saved audio config: enabled={}, aec={}, agc={}, agc_gate={}",
-        enabled, echo_cancellation, resolved_agc_enabled, resolved_agc_noise_gate
+        "Saved audio config: enabled={}, aec={}, agc={}, agc_gate={}, agc_target={} dBFS",
+        enabled,
+        echo_cancellation,
+        resolved_agc_enabled,
+        resolved_agc_noise_gate,
+        resolved_agc_target
     );
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Enable or disable audio monitoring (listen-through) and set its volume.
+/// Takes effect immediately, including during a recording. Monitoring is
+/// only implemented on macOS; elsewhere the setting is saved but unused.
+#[tauri::command]
+pub async fn set_audio_monitor(
+    enabled: bool,
+    volume: f32,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&volume) {
+        return Err(format!(
+            "Monitor volume must be between 0.0 and 1.0, got {}",
+            volume
+        ));
+    }
+
+    {
+        let mut config = state.app_config.lock().await;
+        config.audio.audio_monitor_enabled = enabled;
+        config.audio.monitor_volume = volume;
+        save_config_to_disk(&config)?;
+    }
+
+    get_recording_manager()
+        .set_audio_monitor(enabled, volume)
+        .await;
+
+    Ok(())
+}
+
+/// Set the audio bitrate in kbps, or 0 to pick it automatically.
+/// Takes effect from the next recording.
+#[tauri::command]
+pub async fn set_audio_bitrate(
+    bitrate_kbps: u32,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    // Recordings are muxed with AAC; its range also fits Opus for WebM
+    AudioCodec::Aac.validate_bitrate(bitrate_kbps)?;
+
+    {
+        let mut config = state.app_config.lock().await;
+        config.audio.audio_bitrate_kbps = bitrate_kbps;
+        save_config_to_disk(&config)?;
+    }
+
+    get_recording_manager()
+        .set_audio_bitrate(bitrate_kbps)
+        .await;
+
+    tracing::debug!("[set_audio_bitrate] Saved bitrate_kbps={}", bitrate_kbps);
+    Ok(())
+}
@@ -4,9 +4,8 @@
 //! These commands interact directly with the capture backends.
 
 use crate::capture::{self, ThumbnailCapture};
-use crate::AppState;
-use omnirec_types::{MonitorInfo, WindowInfo};
-use tauri::State;
+use crate::state::get_recording_manager;
+use omnirec_types::{CameraInfo, MonitorInfo, ScreenshotTarget, WindowInfo};
 
 /// Thumbnail result for JSON serialization.
 #[derive(serde::Serialize)]
@@ -19,60 +18,112 @@ pub struct ThumbnailResponse {
     pub height: u32,
 }
 
+/// List the capture backends registered for this platform.
+#[tauri::command]
+pub async fn list_available_backends() -> Result<Vec<capture::BackendInfo>, String> {
+    Ok(capture::list_available_backends())
+}
+
+/// Get the capture dependency probe result from startup.
+///
+/// The UI uses this to guide the user through installing missing dependencies.
+#[tauri::command]
+pub async fn get_backend_probe_result() -> Result<capture::BackendProbeResult, String> {
+    tokio::task::spawn_blocking(capture::probe::get_probe_result)
+        .await
+        .map_err(|e| format!("Probe task failed: {}", e))
+}
+
 /// Get list of capturable windows.
 #[tauri::command]
-pub async fn get_windows(_state: State<'_, AppState>) -> Result<Vec<WindowInfo>, String> {
+pub async fn get_windows() -> Result<Vec<WindowInfo>, String> {
     Ok(capture::list_windows())
 }
 
 /// Get list of available monitors.
 #[tauri::command]
-pub async fn get_monitors(_state: State<'_, AppState>) -> Result<Vec<MonitorInfo>, String> {
+pub async fn get_monitors() -> Result<Vec<MonitorInfo>, String> {
     Ok(capture::list_monitors())
 }
 
+/// List the webcams that can be overlaid on recordings.
+#[tauri::command]
+pub async fn list_cameras() -> Result<Vec<CameraInfo>, String> {
+    tokio::task::spawn_blocking(capture::webcam::list_cameras)
+        .await
+        .map_err(|e| format!("Camera listing task failed: {}", e))?
+}
+
+/// Get the monitor configuration version.
+///
+/// The version changes whenever monitors are added, removed or change
+/// resolution; an alternative to the `monitor-config-changed` event.
+#[tauri::command]
+pub async fn get_monitor_config_version() -> Result<u64, String> {
+    Ok(capture::monitor_watch::monitor_config_version())
+}
+
 /// Show a highlight border on the specified monitor.
 #[tauri::command]
-pub async fn show_display_highlight(
-    monitor_id: String,
-    _state: State<'_, AppState>,
-) -> Result<(), String> {
+pub async fn show_display_highlight(monitor_id: String) -> Result<(), String> {
     let monitors = capture::list_monitors();
     let monitor = monitors
         .iter()
         .find(|m| m.id == monitor_id)
         .ok_or_else(|| format!("Monitor not found: {}", monitor_id))?;
 
-    capture::show_highlight(monitor.x, monitor.y, monitor.width as i32, monitor.height as i32);
+    let style = crate::state::get_recording_manager()
+        .get_highlight_style()
+        .await;
+    capture::show_highlight(
+        monitor.x,
+        monitor.y,
+        monitor.width as i32,
+        monitor.height as i32,
+        &style,
+    );
     Ok(())
 }
 
 /// Show a highlight border on the specified window.
 #[tauri::command]
-pub async fn show_window_highlight(
-    window_handle: isize,
-    _state: State<'_, AppState>,
-) -> Result<(), String> {
+pub async fn show_window_highlight(window_handle: isize) -> Result<(), String> {
     let windows = capture::list_windows();
     let window = windows
         .iter()
         .find(|w| w.handle == window_handle)
         .ok_or_else(|| format!("Window not found: {}", window_handle))?;
 
-    capture::show_highlight(window.x, window.y, window.width as i32, window.height as i32);
+    let style = crate::state::get_recording_manager()
+        .get_highlight_style()
+        .await;
+    capture::show_highlight(
+        window.x,
+        window.y,
+        window.width as i32,
+        window.height as i32,
+        &style,
+    );
     Ok(())
 }
 
 /// Capture a thumbnail of a window.
 ///
+/// Runs on the thumbnail pool, so concurrent requests are bounded.
 /// Returns a base64-encoded JPEG image or null if capture fails.
 #[tauri::command]
 pub async fn get_window_thumbnail(
     window_handle: isize,
-    _state: State<'_, AppState>,
 ) -> Result<Option<ThumbnailResponse>, String> {
-    let backend = capture::get_backend();
-    match backend.capture_window_thumbnail(window_handle) {
+    let manager = get_recording_manager();
+    let quality = manager.get_thumbnail_quality().await.window_quality;
+    let options = manager.get_thumbnail_options().await.with_quality(quality);
+    let result = manager
+        .get_thumbnail_pool()
+        .await
+        .run(move || capture::get_backend().capture_window_thumbnail(window_handle, options))
+        .await?;
+    match result {
         Ok(result) => Ok(Some(ThumbnailResponse {
             data: result.data,
             width: result.width,
@@ -87,14 +138,21 @@ pub async fn get_window_thumbnail(
 
 /// Capture a thumbnail of a display.
 ///
+/// Runs on the thumbnail pool, so concurrent requests are bounded.
 /// Returns a base64-encoded JPEG image or null if capture fails.
 #[tauri::command]
 pub async fn get_display_thumbnail(
     monitor_id: String,
-    _state: State<'_, AppState>,
 ) -> Result<Option<ThumbnailResponse>, String> {
-    let backend = capture::get_backend();
-    match backend.capture_display_thumbnail(&monitor_id) {
+    let manager = get_recording_manager();
+    let quality = manager.get_thumbnail_quality().await.display_quality;
+    let options = manager.get_thumbnail_options().await.with_quality(quality);
+    let result = manager
+        .get_thumbnail_pool()
+        .await
+        .run(move || capture::get_backend().capture_display_thumbnail(&monitor_id, options))
+        .await?;
+    match result {
         Ok(result) => Ok(Some(ThumbnailResponse {
             data: result.data,
             width: result.width,
@@ -117,10 +175,13 @@ pub async fn get_region_preview(
     y: i32,
     width: u32,
     height: u32,
-    _state: State<'_, AppState>,
 ) -> Result<Option<ThumbnailResponse>, String> {
     let backend = capture::get_backend();
-    match backend.capture_region_preview(&monitor_id, x, y, width, height) {
+    let quality = get_recording_manager()
+        .get_thumbnail_quality()
+        .await
+        .region_quality;
+    match backend.capture_region_preview(&monitor_id, x, y, width, height, quality) {
         Ok(result) => Ok(Some(ThumbnailResponse {
             data: result.data,
             width: result.width,
@@ -133,6 +194,14 @@ pub async fn get_region_preview(
     }
 }
 
+/// Save a full-resolution PNG screenshot of `target` (the primary display if
+/// null) to the output directory. Returns the saved path.
+#[tauri::command]
+pub async fn take_screenshot(target: Option<ScreenshotTarget>) -> Result<String, String> {
+    let path = get_recording_manager().take_screenshot(target).await?;
+    Ok(path.display().to_string())
+}
+
 /// Check screen recording permission status (macOS only).
 /// Returns: "granted", "denied", or "unknown" (non-macOS platforms).
 ///
@@ -167,6 +236,23 @@ pub fn check_screen_recording_permission() -> String {
     }
 }
 
+/// Check the entitlements the app is signed with (macOS only).
+///
+/// Other platforms and unsigned builds report `signed: false`.
+#[tauri::command]
+pub async fn get_entitlements_status() -> Result<crate::platform::EntitlementsStatus, String> {
+    #[cfg(target_os = "macos")]
+    {
+        tokio::task::spawn_blocking(|| crate::platform::macos::EntitlementsValidator::new().check())
+            .await
+            .map_err(|e| format!("Entitlements check failed: {}", e))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        Ok(crate::platform::EntitlementsStatus::default())
+    }
+}
+
 /// Open System Settings to the Screen Recording privacy pane (macOS only).
 #[tauri::command]
 pub fn open_screen_recording_settings() {
@@ -182,5 +268,8 @@ pub fn open_screen_recording_settings() {
         let _ = std::process::Command::new("open")
             .arg("x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture")
             .spawn();
+
+        // The user may be about to grant permission; don't trust a cached result
+        macos::invalidate_permission_cache();
     }
-}
\ No newline at end of file
+}
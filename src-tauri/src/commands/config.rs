@@ -2,11 +2,19 @@
 //!
 //! Commands for managing output directory, theme, and other settings.
 
+use crate::capture::{self, registry, BackendId, CaptureRegion, HighlightStyle, ThumbnailOptions};
 use crate::config::{
-    get_default_output_dir, save_config as save_config_to_disk, validate_directory, AppConfig,
-    ThemeMode,
+    export_config as export_config_json, get_default_output_dir,
+    import_config as import_config_json, resolve_last_region, save_config as save_config_to_disk,
+    validate_keyframe_interval, validate_output_directory as validate_output_dir,
+    validate_temp_directory, validate_thumbnail_dimensions, validate_thumbnail_pool_size,
+    AppConfig, CloudUploadConfig, HotkeyConfig, ThemeMode, ThumbnailQualityConfig,
+    MAX_COUNTDOWN_SECS, SILENCE_THRESHOLD_DB_RANGE,
 };
-use crate::AppState;
+use crate::encoder::validate_video_filter as validate_video_filter_chain;
+use crate::encoder::EncoderThreads;
+use crate::AppConfigState;
+use omnirec_types::PipConfig;
 use tauri::{AppHandle, Emitter, State};
 use tauri_plugin_dialog::DialogExt;
 
@@ -20,12 +28,38 @@ pub struct ConfigResponse {
     pub output: OutputConfigResponse,
     pub audio: AudioConfigResponse,
     pub appearance: AppearanceConfigResponse,
+    pub thumbnail: ThumbnailConfigResponse,
+    pub highlight_style: HighlightStyle,
     pub log_level: String,
+    pub audio_exclusive_mode: bool,
+    pub video_filter_chain: Option<String>,
+    pub encoder_threads: EncoderThreads,
+    pub keyframe_interval_secs: f32,
+    pub trim_silence: bool,
+    pub silence_threshold_db: f32,
+    pub temp_recording_dir: Option<String>,
+    pub copy_recording_path_to_clipboard: bool,
+    pub open_output_dir_after_recording: bool,
+    pub smart_format: bool,
+    pub countdown_secs: u8,
+    pub show_completion_notification: bool,
+    pub notification_include_path: bool,
+    pub show_recording_preview: bool,
+    pub verify_on_finish: bool,
+    pub window_follow: bool,
+    pub force_wgc_for_games: bool,
+    pub focus_before_capture: bool,
+    pub snap_to_window: bool,
+    pub hotkeys: HotkeyConfig,
+    /// Cloud upload settings; the secret access key is never sent back.
+    pub cloud_upload: Option<CloudUploadConfig>,
+    pub pip: Option<PipConfig>,
 }
 
 #[derive(serde::Serialize)]
 pub struct OutputConfigResponse {
     pub directory: Option<String>,
+    pub create_if_missing: bool,
 }
 
 #[derive(serde::Serialize)]
@@ -36,6 +70,15 @@ pub struct AudioConfigResponse {
     pub echo_cancellation: bool,
     pub agc_enabled: bool,
     pub agc_noise_gate_enabled: bool,
+    pub agc_target_level_dbfs: f32,
+}
+
+/// Result of checking a video filter chain with FFmpeg.
+#[derive(serde::Serialize)]
+pub struct FilterValidationResult {
+    pub valid: bool,
+    /// FFmpeg's error output when the chain is invalid.
+    pub error: Option<String>,
 }
 
 #[derive(serde::Serialize)]
@@ -43,11 +86,20 @@ pub struct AppearanceConfigResponse {
     pub theme: String,
 }
 
+#[derive(serde::Serialize)]
+pub struct ThumbnailConfigResponse {
+    pub max_width: u32,
+    pub max_height: u32,
+    pub quality: ThumbnailQualityConfig,
+    pub thread_pool_size: usize,
+}
+
 impl From<&AppConfig> for ConfigResponse {
     fn from(config: &AppConfig) -> Self {
         Self {
             output: OutputConfigResponse {
                 directory: config.output.directory.clone(),
+                create_if_missing: config.output.create_if_missing,
             },
             audio: AudioConfigResponse {
                 enabled: config.audio.enabled,
@@ -56,11 +108,47 @@ impl From<&AppConfig> for ConfigResponse {
                 echo_cancellation: config.audio.echo_cancellation,
                 agc_enabled: config.audio.agc_enabled,
                 agc_noise_gate_enabled: config.audio.agc_noise_gate_enabled,
+                agc_target_level_dbfs: config.audio.agc_target_level_dbfs,
             },
             appearance: AppearanceConfigResponse {
                 theme: config.appearance.theme.as_str().to_string(),
             },
+            thumbnail: ThumbnailConfigResponse {
+                max_width: config.thumbnail_max_width,
+                max_height: config.thumbnail_max_height,
+                quality: config.thumbnail_quality,
+                thread_pool_size: config.thumbnail_thread_pool_size,
+            },
+            highlight_style: config.highlight_style,
             log_level: config.log_level.as_filter_str().to_string(),
+            audio_exclusive_mode: config.audio_exclusive_mode,
+            video_filter_chain: config.video_filter_chain.clone(),
+            encoder_threads: config.encoder_threads,
+            keyframe_interval_secs: config.keyframe_interval_secs,
+            trim_silence: config.trim_silence,
+            silence_threshold_db: config.silence_threshold_db,
+            temp_recording_dir: config
+                .temp_recording_dir
+                .as_ref()
+                .map(|dir| dir.to_string_lossy().to_string()),
+            copy_recording_path_to_clipboard: config.copy_recording_path_to_clipboard,
+            open_output_dir_after_recording: config.open_output_dir_after_recording,
+            smart_format: config.smart_format,
+            countdown_secs: config.countdown_secs,
+            show_completion_notification: config.show_completion_notification,
+            notification_include_path: config.notification_include_path,
+            show_recording_preview: config.show_recording_preview,
+            verify_on_finish: config.verify_on_finish,
+            window_follow: config.window_follow,
+            force_wgc_for_games: config.force_wgc_for_games,
+            focus_before_capture: config.focus_before_capture,
+            snap_to_window: config.region_selector.snap_to_window,
+            hotkeys: config.hotkeys.clone(),
+            cloud_upload: config.cloud_upload.clone().map(|mut cloud| {
+                cloud.credentials.secret_access_key.clear();
+                cloud
+            }),
+            pip: config.pip.clone(),
         }
     }
 }
@@ -71,7 +159,7 @@ impl From<&AppConfig> for ConfigResponse {
 
 /// Get the current application configuration.
 #[tauri::command]
-pub async fn get_config(state: State<'_, AppState>) -> Result<ConfigResponse, String> {
+pub async fn get_config(state: State<'_, AppConfigState>) -> Result<ConfigResponse, String> {
     let config = state.app_config.lock().await;
     Ok(ConfigResponse::from(&*config))
 }
@@ -80,23 +168,32 @@ pub async fn get_config(state: State<'_, AppState>) -> Result<ConfigResponse, St
 #[tauri::command]
 pub async fn save_output_directory(
     directory: Option<String>,
-    state: State<'_, AppState>,
+    state: State<'_, AppConfigState>,
 ) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+
     // Validate directory if provided
     if let Some(ref dir) = directory {
         if !dir.is_empty() {
-            validate_directory(dir)?;
+            validate_output_dir(dir, config.output.create_if_missing)?;
         }
     }
 
-    let mut config = state.app_config.lock().await;
-
     // Update the config
     config.output.directory = directory.filter(|d| !d.is_empty());
 
     // Save to disk
     save_config_to_disk(&config)?;
 
+    let output_dir = config
+        .output
+        .directory
+        .clone()
+        .map(std::path::PathBuf::from);
+    crate::state::get_recording_manager()
+        .set_output_dir(output_dir, config.output.create_if_missing)
+        .await;
+
     tracing::debug!(
         "[save_output_directory] Saved output directory: {:?}",
         config.output.directory
@@ -132,10 +229,538 @@ pub async fn pick_output_directory(app: tauri::AppHandle) -> Result<Option<Strin
     Ok(result)
 }
 
-/// Validate a directory path.
+/// Save the temporary recording directory.
+/// An empty or missing directory records straight to the output directory.
+#[tauri::command]
+pub async fn save_temp_recording_dir(
+    directory: Option<String>,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let directory = directory.filter(|d| !d.is_empty());
+    if let Some(ref dir) = directory {
+        validate_temp_directory(dir)?;
+    }
+    let directory = directory.map(std::path::PathBuf::from);
+
+    let mut config = state.app_config.lock().await;
+    config.temp_recording_dir = directory.clone();
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_temp_recording_dir(directory)
+        .await;
+
+    tracing::debug!(
+        "[save_temp_recording_dir] Saved temporary recording directory: {:?}",
+        config.temp_recording_dir
+    );
+    Ok(())
+}
+
+/// Save whether the saved recording's path is copied to the clipboard.
+#[tauri::command]
+pub async fn save_copy_recording_path_to_clipboard(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.copy_recording_path_to_clipboard = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_copy_path_to_clipboard(enabled)
+        .await;
+
+    tracing::debug!(
+        "[save_copy_recording_path_to_clipboard] Saved enabled={}",
+        enabled
+    );
+    Ok(())
+}
+
+/// Save whether a missing output directory is created when recording.
+#[tauri::command]
+pub async fn save_create_output_dir_if_missing(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.output.create_if_missing = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    let output_dir = config
+        .output
+        .directory
+        .clone()
+        .map(std::path::PathBuf::from);
+    crate::state::get_recording_manager()
+        .set_output_dir(output_dir, enabled)
+        .await;
+
+    tracing::debug!(
+        "[save_create_output_dir_if_missing] Saved enabled={}",
+        enabled
+    );
+    Ok(())
+}
+
+/// Save whether the output directory is opened when a recording stops.
+#[tauri::command]
+pub async fn save_open_output_dir_after_recording(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.open_output_dir_after_recording = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_open_output_dir(enabled)
+        .await;
+
+    tracing::debug!(
+        "[save_open_output_dir_after_recording] Saved enabled={}",
+        enabled
+    );
+    Ok(())
+}
+
+/// Save whether the output format is chosen from the recording.
+#[tauri::command]
+pub async fn save_smart_format(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.smart_format = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_smart_format(enabled)
+        .await;
+
+    tracing::debug!("[save_smart_format] Saved enabled={}", enabled);
+    Ok(())
+}
+
+/// Save whether a notification is shown when a recording is saved.
+#[tauri::command]
+pub async fn save_show_completion_notification(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.show_completion_notification = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    tracing::debug!(
+        "[save_show_completion_notification] Saved enabled={}",
+        enabled
+    );
+    Ok(())
+}
+
+/// Save whether the completion notification shows the full file path.
+#[tauri::command]
+pub async fn save_notification_include_path(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.notification_include_path = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    tracing::debug!("[save_notification_include_path] Saved enabled={}", enabled);
+    Ok(())
+}
+
+/// Save whether the live preview window is shown while recording.
+#[tauri::command]
+pub async fn save_show_recording_preview(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.show_recording_preview = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    tracing::debug!("[save_show_recording_preview] Saved enabled={}", enabled);
+    Ok(())
+}
+
+/// Save whether a checksum file is written for each saved recording.
+#[tauri::command]
+pub async fn save_verify_on_finish(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.verify_on_finish = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_verify_on_finish(enabled)
+        .await;
+
+    tracing::debug!("[save_verify_on_finish] Saved enabled={}", enabled);
+    Ok(())
+}
+
+/// Save whether window captures follow the window's screen area.
+#[tauri::command]
+pub async fn save_window_follow(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.window_follow = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::capture::window_follow::set_enabled(enabled);
+
+    tracing::debug!("[save_window_follow] Saved enabled={}", enabled);
+    Ok(())
+}
+
+/// Save whether window captures always use Windows Graphics Capture.
+#[tauri::command]
+pub async fn save_force_wgc_for_games(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.force_wgc_for_games = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    #[cfg(target_os = "windows")]
+    crate::capture::windows::fullscreen::set_force_wgc_for_games(enabled);
+
+    tracing::debug!("[save_force_wgc_for_games] Saved enabled={}", enabled);
+    Ok(())
+}
+
+/// Save whether window captures focus the window first (Hyprland).
+#[tauri::command]
+pub async fn save_focus_before_capture(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.focus_before_capture = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    #[cfg(target_os = "linux")]
+    crate::capture::linux::hyprland_dispatch::set_focus_before_capture(enabled);
+
+    tracing::debug!("[save_focus_before_capture] Saved enabled={}", enabled);
+    Ok(())
+}
+
+/// Save whether the region selector snaps to window edges (Hyprland).
+#[tauri::command]
+pub async fn save_snap_to_window(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.region_selector.snap_to_window = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    tracing::debug!("[save_snap_to_window] Saved enabled={}", enabled);
+    Ok(())
+}
+
+/// Save and register the global hotkeys. If a shortcut can't be registered
+/// (e.g. another application owns it), the previous hotkeys are restored.
+#[tauri::command]
+pub async fn save_hotkey_config(
+    hotkeys: HotkeyConfig,
+    app: tauri::AppHandle,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    hotkeys.validate()?;
+    let mut config = state.app_config.lock().await;
+    if let Err(e) = crate::hotkeys::apply_config(&app, &hotkeys) {
+        if let Err(restore_error) = crate::hotkeys::apply_config(&app, &config.hotkeys) {
+            tracing::warn!(
+                "[save_hotkey_config] Failed to restore hotkeys: {}",
+                restore_error
+            );
+        }
+        return Err(e);
+    }
+    config.hotkeys = hotkeys;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    tracing::debug!("[save_hotkey_config] Saved {:?}", config.hotkeys);
+    Ok(())
+}
+
+/// Save the countdown before a recording starts.
+#[tauri::command]
+pub async fn save_countdown_secs(secs: u8, state: State<'_, AppConfigState>) -> Result<(), String> {
+    if secs > MAX_COUNTDOWN_SECS {
+        return Err(format!(
+            "Countdown must be between 0 and {} seconds",
+            MAX_COUNTDOWN_SECS
+        ));
+    }
+
+    let mut config = state.app_config.lock().await;
+    config.countdown_secs = secs;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_countdown_secs(secs)
+        .await;
+
+    tracing::debug!("[save_countdown_secs] Saved countdown={}s", secs);
+    Ok(())
+}
+
+/// Fill in the stored secret access key when the frontend leaves it blank,
+/// since [`ConfigResponse`] never includes it.
+fn with_stored_secret(mut cloud: CloudUploadConfig, config: &AppConfig) -> CloudUploadConfig {
+    if cloud.credentials.secret_access_key.is_empty() {
+        if let Some(stored) = &config.cloud_upload {
+            cloud.credentials.secret_access_key = stored.credentials.secret_access_key.clone();
+        }
+    }
+    cloud
+}
+
+/// Save the cloud upload settings.
+///
+/// A blank secret access key keeps the stored one.
+#[tauri::command]
+pub async fn save_cloud_config(
+    cloud: CloudUploadConfig,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    let cloud = with_stored_secret(cloud, &config);
+    if cloud.enabled {
+        cloud.validate()?;
+    }
+    config.cloud_upload = Some(cloud);
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_cloud_upload(config.cloud_upload.clone())
+        .await;
+
+    tracing::debug!(
+        "[save_cloud_config] Saved cloud upload settings: {:?}",
+        config.cloud_upload
+    );
+    Ok(())
+}
+
+/// Save the webcam overlaid on recordings; `None` turns the overlay off.
+#[tauri::command]
+pub async fn save_pip_config(
+    pip: Option<PipConfig>,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    if let Some(pip) = &pip {
+        pip.validate()?;
+    }
+
+    let mut config = state.app_config.lock().await;
+    config.pip = pip;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_pip(config.pip.clone())
+        .await;
+
+    tracing::debug!("[save_pip_config] Saved {:?}", config.pip);
+    Ok(())
+}
+
+/// Check that the given cloud settings can reach the bucket.
+#[tauri::command]
+pub async fn test_cloud_connection(
+    cloud: CloudUploadConfig,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let cloud = with_stored_secret(cloud, &*state.app_config.lock().await);
+    crate::cloud::test_connection(&cloud).await
+}
+
+/// Cancel the cloud upload in progress. Returns whether one was running.
+#[tauri::command]
+pub fn cancel_cloud_upload() -> bool {
+    crate::state::get_recording_manager().cancel_cloud_upload()
+}
+
+/// Open a folder picker dialog for the temporary recording directory.
+#[tauri::command]
+pub async fn pick_temp_directory(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    use std::sync::mpsc;
+
+    let (tx, rx) = mpsc::channel();
+
+    app.dialog()
+        .file()
+        .set_title("Select Temporary Recording Directory")
+        .pick_folder(move |folder_path| {
+            let result = folder_path.map(|p| p.to_string());
+            let _ = tx.send(result);
+        });
+
+    // Wait for dialog result
+    let result = rx.recv().map_err(|e| format!("Dialog error: {}", e))?;
+
+    Ok(result)
+}
+
+/// Export the current configuration as pretty JSON.
+///
+/// The cloud secret access key is left out.
+#[tauri::command]
+pub async fn export_config(state: State<'_, AppConfigState>) -> Result<String, String> {
+    let config = state.app_config.lock().await;
+    export_config_json(&config)
+}
+
+/// Import configuration JSON exported by [`export_config`].
+///
+/// Fields missing from `json` keep their current values. Most settings are
+/// read at startup, so the import takes full effect after a restart.
+#[tauri::command]
+pub async fn import_config(
+    json: String,
+    app: AppHandle,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    let imported = import_config_json(&config, &json)?;
+
+    // Save to disk
+    save_config_to_disk(&imported)?;
+    *config = imported;
+    crate::config_sync::apply_config(&app, &config).await;
+
+    tracing::debug!("[import_config] Imported config");
+
+    // Notify all windows so they can apply the imported theme immediately.
+    let _ = app.emit("theme-changed", config.appearance.theme.as_str());
+
+    Ok(())
+}
+
+/// Open a save dialog and write the exported configuration to the chosen
+/// file. Returns the path, or `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_config_to_file(
+    app: AppHandle,
+    state: State<'_, AppConfigState>,
+) -> Result<Option<String>, String> {
+    use std::sync::mpsc;
+
+    let json = {
+        let config = state.app_config.lock().await;
+        export_config_json(&config)?
+    };
+
+    let (tx, rx) = mpsc::channel();
+
+    app.dialog()
+        .file()
+        .set_title("Export Config")
+        .set_file_name("omnirec-config.json")
+        .add_filter("JSON", &["json"])
+        .save_file(move |file_path| {
+            let result = file_path.map(|p| p.to_string());
+            let _ = tx.send(result);
+        });
+
+    // Wait for dialog result
+    let Some(path) = rx.recv().map_err(|e| format!("Dialog error: {}", e))? else {
+        return Ok(None);
+    };
+
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    tracing::debug!("[export_config_to_file] Exported config to {}", path);
+    Ok(Some(path))
+}
+
+/// Open a file picker and import the chosen exported configuration.
+/// Returns `false` if the dialog was cancelled.
 #[tauri::command]
-pub async fn validate_output_directory(directory: String) -> Result<(), String> {
-    validate_directory(&directory)
+pub async fn import_config_from_file(
+    app: AppHandle,
+    state: State<'_, AppConfigState>,
+) -> Result<bool, String> {
+    use std::sync::mpsc;
+
+    let (tx, rx) = mpsc::channel();
+
+    app.dialog()
+        .file()
+        .set_title("Import Config")
+        .add_filter("JSON", &["json"])
+        .pick_file(move |file_path| {
+            let result = file_path.map(|p| p.to_string());
+            let _ = tx.send(result);
+        });
+
+    // Wait for dialog result
+    let Some(path) = rx.recv().map_err(|e| format!("Dialog error: {}", e))? else {
+        return Ok(false);
+    };
+
+    let json =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    import_config(json, app, state).await?;
+    Ok(true)
+}
+
+/// Validate an output directory path. A missing directory passes if it
+/// will be created when recording.
+#[tauri::command]
+pub async fn validate_output_directory(
+    directory: String,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let create_if_missing = state.app_config.lock().await.output.create_if_missing;
+    validate_output_dir(&directory, create_if_missing)
 }
 
 /// Save the theme mode setting.
@@ -143,7 +768,7 @@ pub async fn validate_output_directory(directory: String) -> Result<(), String>
 pub async fn save_theme(
     theme: String,
     app: AppHandle,
-    state: State<'_, AppState>,
+    state: State<'_, AppConfigState>,
 ) -> Result<(), String> {
     let theme_mode =
         ThemeMode::from_str(&theme).ok_or_else(|| format!("Invalid theme mode: {}", theme))?;
@@ -164,3 +789,294 @@ pub async fn save_theme(
 
     Ok(())
 }
+
+/// Save the maximum thumbnail dimensions.
+#[tauri::command]
+pub async fn save_thumbnail_config(
+    max_width: u32,
+    max_height: u32,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    validate_thumbnail_dimensions(max_width, max_height)?;
+
+    let mut config = state.app_config.lock().await;
+    config.thumbnail_max_width = max_width;
+    config.thumbnail_max_height = max_height;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    let manager = crate::state::get_recording_manager();
+    let options = manager.get_thumbnail_options().await;
+    manager
+        .set_thumbnail_options(ThumbnailOptions {
+            max_width,
+            max_height,
+            ..options
+        })
+        .await;
+
+    tracing::debug!(
+        "[save_thumbnail_config] Saved thumbnail size: {}x{}",
+        max_width,
+        max_height
+    );
+    Ok(())
+}
+
+/// Save how many window/display thumbnails are captured concurrently.
+#[tauri::command]
+pub async fn save_thumbnail_pool_size(
+    size: usize,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    validate_thumbnail_pool_size(size)?;
+
+    let mut config = state.app_config.lock().await;
+    config.thumbnail_thread_pool_size = size;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_thumbnail_pool_size(size)
+        .await;
+
+    tracing::debug!("[save_thumbnail_pool_size] Saved pool size: {}", size);
+    Ok(())
+}
+
+/// Save the JPEG quality used for window thumbnails, display thumbnails and region previews.
+#[tauri::command]
+pub async fn save_thumbnail_quality_config(
+    window_quality: u8,
+    display_quality: u8,
+    region_quality: u8,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let quality = ThumbnailQualityConfig {
+        window_quality,
+        display_quality,
+        region_quality,
+    };
+    quality.validate()?;
+
+    let mut config = state.app_config.lock().await;
+    config.thumbnail_quality = quality;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_thumbnail_quality(quality)
+        .await;
+
+    tracing::debug!("[save_thumbnail_quality_config] Saved {:?}", quality);
+    Ok(())
+}
+
+/// Save the capture highlight border style.
+#[tauri::command]
+pub async fn save_highlight_style(
+    style: HighlightStyle,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    style.validate()?;
+
+    let mut config = state.app_config.lock().await;
+    config.highlight_style = style;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_highlight_style(style)
+        .await;
+
+    tracing::debug!("[save_highlight_style] Saved {:?}", style);
+    Ok(())
+}
+
+/// Save whether the microphone is captured in WASAPI exclusive mode (Windows).
+#[tauri::command]
+pub async fn save_audio_exclusive_mode(
+    enabled: bool,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.audio_exclusive_mode = enabled;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_audio_exclusive_mode(enabled)
+        .await;
+
+    tracing::debug!("[save_audio_exclusive_mode] Saved {}", enabled);
+    Ok(())
+}
+
+/// Check an FFmpeg video filter chain without saving it.
+#[tauri::command]
+pub async fn validate_video_filter(filter: String) -> Result<FilterValidationResult, String> {
+    let result = tokio::task::spawn_blocking(move || validate_video_filter_chain(&filter))
+        .await
+        .map_err(|e| format!("Filter validation task failed: {}", e))?;
+    Ok(match result {
+        Ok(()) => FilterValidationResult {
+            valid: true,
+            error: None,
+        },
+        Err(e) => FilterValidationResult {
+            valid: false,
+            error: Some(e),
+        },
+    })
+}
+
+/// Save the FFmpeg video filter chain applied to recordings.
+/// An empty or missing chain disables filtering; anything else must pass
+/// validation first.
+#[tauri::command]
+pub async fn save_video_filter_chain(
+    filter: Option<String>,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let filter = filter
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty());
+
+    if let Some(chain) = filter.clone() {
+        tokio::task::spawn_blocking(move || validate_video_filter_chain(&chain))
+            .await
+            .map_err(|e| format!("Filter validation task failed: {}", e))??;
+    }
+
+    let mut config = state.app_config.lock().await;
+    config.video_filter_chain = filter.clone();
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_video_filter_chain(filter)
+        .await;
+
+    tracing::debug!(
+        "[save_video_filter_chain] Saved {:?}",
+        config.video_filter_chain
+    );
+    Ok(())
+}
+
+/// Save the encoder settings: the number of threads FFmpeg's video encoders
+/// use.
+#[tauri::command]
+pub async fn save_encoder_config(
+    encoder_threads: EncoderThreads,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    encoder_threads.validate()?;
+
+    let mut config = state.app_config.lock().await;
+    config.encoder_threads = encoder_threads;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_encoder_threads(encoder_threads)
+        .await;
+
+    tracing::debug!("[save_encoder_config] Saved threads={:?}", encoder_threads);
+    Ok(())
+}
+
+/// Save the time between video keyframes, in seconds.
+#[tauri::command]
+pub async fn save_keyframe_interval(
+    secs: f32,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    validate_keyframe_interval(secs)?;
+
+    let mut config = state.app_config.lock().await;
+    config.keyframe_interval_secs = secs;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_keyframe_interval(secs)
+        .await;
+
+    tracing::debug!("[save_keyframe_interval] Saved {} s", secs);
+    Ok(())
+}
+
+/// Save whether silence is trimmed from the start and end of recordings.
+#[tauri::command]
+pub async fn save_silence_trim(
+    enabled: bool,
+    threshold_db: f32,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    if !SILENCE_THRESHOLD_DB_RANGE.contains(&threshold_db) {
+        return Err(format!(
+            "Silence threshold must be between {} and {} dB",
+            SILENCE_THRESHOLD_DB_RANGE.start(),
+            SILENCE_THRESHOLD_DB_RANGE.end()
+        ));
+    }
+
+    let mut config = state.app_config.lock().await;
+    config.trim_silence = enabled;
+    config.silence_threshold_db = threshold_db;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    crate::state::get_recording_manager()
+        .set_silence_trim(enabled, threshold_db)
+        .await;
+
+    tracing::debug!(
+        "[save_silence_trim] Saved enabled={}, threshold={} dB",
+        enabled,
+        threshold_db
+    );
+    Ok(())
+}
+
+/// Get the region of the last successful region recording.
+///
+/// If its monitor is no longer connected, the region is moved to the center of
+/// the primary monitor. Returns `None` if no region has been recorded yet.
+#[tauri::command]
+pub async fn get_last_region(
+    state: State<'_, AppConfigState>,
+) -> Result<Option<CaptureRegion>, String> {
+    let Some(region) = state.app_config.lock().await.last_region.clone() else {
+        return Ok(None);
+    };
+    Ok(resolve_last_region(&region, &capture::list_monitors()))
+}
+
+/// Save the preferred capture backend (`None` selects automatically).
+#[tauri::command]
+pub async fn set_preferred_backend(
+    id: Option<BackendId>,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    let mut config = state.app_config.lock().await;
+    config.preferred_backend = id;
+
+    // Save to disk
+    save_config_to_disk(&config)?;
+
+    registry::set_preferred_backend(id);
+
+    tracing::debug!("[set_preferred_backend] Saved preferred backend: {:?}", id);
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! Recording history commands.
+//!
+//! Commands for browsing, searching, and pruning the log of saved recordings,
+//! and for the short list of recent recordings shown in the tray.
+
+use crate::config::save_config;
+use crate::history::{self, RecentFile, RecordingHistoryEntry};
+use crate::AppConfigState;
+use tauri::State;
+
+/// Get a zero-based page of the recording history, newest first.
+#[tauri::command]
+pub async fn get_recording_history(
+    page: u32,
+    per_page: u32,
+) -> Result<Vec<RecordingHistoryEntry>, String> {
+    tokio::task::spawn_blocking(move || {
+        Ok(history::page_entries(
+            history::load_entries()?,
+            page,
+            per_page,
+        ))
+    })
+    .await
+    .map_err(|e| format!("History task failed: {}", e))?
+}
+
+/// Fuzzy-search the recording history by window/monitor name and file name.
+#[tauri::command]
+pub async fn search_history(query: String) -> Result<Vec<RecordingHistoryEntry>, String> {
+    tokio::task::spawn_blocking(move || {
+        Ok(history::search_entries(history::load_entries()?, &query))
+    })
+    .await
+    .map_err(|e| format!("History task failed: {}", e))?
+}
+
+/// Remove a recording from the history, optionally deleting the file too.
+#[tauri::command]
+pub async fn delete_history_entry(path: String, delete_file: bool) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let entry = history::remove_entry(&path)?
+            .ok_or_else(|| format!("No history entry for {}", path))?;
+        if delete_file {
+            match std::fs::remove_file(&entry.path) {
+                Ok(()) => tracing::info!("[History] Deleted {}", entry.path),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(format!("Failed to delete {}: {}", entry.path, e)),
+            }
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("History task failed: {}", e))?
+}
+
+/// Get the recently saved recordings, newest first.
+#[tauri::command]
+pub async fn get_recent_recordings(
+    state: State<'_, AppConfigState>,
+) -> Result<Vec<RecentFile>, String> {
+    let paths = state.app_config.lock().await.recent_recordings.clone();
+    Ok(paths.into_iter().map(RecentFile::new).collect())
+}
+
+/// Forget the recent recordings and empty the tray's Recent Recordings menu.
+#[tauri::command]
+pub async fn clear_recent_recordings(
+    app: tauri::AppHandle,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    {
+        let mut config = state.app_config.lock().await;
+        config.recent_recordings.clear();
+        save_config(&config)?;
+    }
+    crate::tray::refresh_recent_recordings(&app);
+    Ok(())
+}
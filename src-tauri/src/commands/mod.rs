@@ -6,6 +6,7 @@
 mod audio;
 mod capture;
 mod config;
+mod history;
 mod platform;
 mod recording;
 mod transcription;
@@ -14,6 +15,7 @@ mod windows;
 pub use audio::*;
 pub use capture::*;
 pub use config::*;
+pub use history::*;
 pub use platform::*;
 pub use recording::*;
 pub use transcription::*;
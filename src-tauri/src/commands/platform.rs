@@ -3,7 +3,13 @@
 //! Commands for detecting the current platform, desktop environment,
 //! and managing Hyprland-specific window operations.
 
-use crate::AppState;
+use crate::{AppConfigState, ServiceReadyState};
+#[cfg(target_os = "windows")]
+use omnirec_types::physical_to_logical_region;
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use omnirec_types::CaptureRegion;
+#[cfg(target_os = "linux")]
+use omnirec_types::{constrain_to_aspect, find_snap_target};
 use tauri::State;
 
 /// Get the current platform name.
@@ -189,7 +195,8 @@ pub async fn configure_region_selector_window(window_label: String) -> Result<()
                     let err = String::from_utf8_lossy(&result.stderr);
                     tracing::debug!(
                         "[configure_region_selector] Failed to apply rule: {} - {}",
-                        rule, err
+                        rule,
+                        err
                     );
                 }
             }
@@ -205,8 +212,92 @@ pub async fn configure_region_selector_window(window_label: String) -> Result<()
     Ok(())
 }
 
-/// Stub for non-Linux platforms.
-#[cfg(not(target_os = "linux"))]
+/// Geometry of the region selector window in the logical coordinates of the
+/// monitor it is on, as (x, y, width, height).
+///
+/// With per-monitor DPI awareness, Tauri reports the window's position and
+/// size in physical pixels, and a monitor's logical origin is its physical
+/// origin divided by its own scale factor (as in `main.ts`). Dividing by the
+/// window's scale factor instead misplaces the selector on monitors whose
+/// scale differs from the one it was created on.
+#[cfg(target_os = "windows")]
+fn region_selector_logical_geometry(
+    window: &tauri::WebviewWindow,
+) -> Result<(i32, i32, i32, i32), String> {
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to get selector position: {}", e))?;
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to get selector size: {}", e))?;
+
+    let center_x = position.x + size.width as i32 / 2;
+    let center_y = position.y + size.height as i32 / 2;
+    let monitor = crate::capture::list_monitors()
+        .into_iter()
+        .find(|m| {
+            center_x >= m.x
+                && center_x < m.x + m.width as i32
+                && center_y >= m.y
+                && center_y < m.y + m.height as i32
+        })
+        .ok_or("Region selector is not on any monitor")?;
+
+    let physical = CaptureRegion {
+        monitor_id: monitor.id.clone(),
+        x: position.x - monitor.x,
+        y: position.y - monitor.y,
+        width: size.width,
+        height: size.height,
+        aspect_ratio: None,
+    };
+    let logical = physical_to_logical_region(&physical, &monitor);
+    let origin_x = (monitor.x as f64 / monitor.scale_factor).round() as i32;
+    let origin_y = (monitor.y as f64 / monitor.scale_factor).round() as i32;
+    Ok((
+        origin_x + logical.x,
+        origin_y + logical.y,
+        logical.width as i32,
+        logical.height as i32,
+    ))
+}
+
+/// Check the region selector window's logical geometry against the monitor
+/// it was placed on (Windows per-monitor DPI).
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn configure_region_selector_window(
+    app: tauri::AppHandle,
+    window_label: String,
+) -> Result<(), String> {
+    use tauri::Manager;
+
+    let window = app
+        .get_webview_window(&window_label)
+        .ok_or_else(|| format!("Window not found: {}", window_label))?;
+
+    match region_selector_logical_geometry(&window) {
+        Ok((x, y, width, height)) => tracing::debug!(
+            "[configure_region_selector] Selector at ({}, {}) size {}x{} (logical)",
+            x,
+            y,
+            width,
+            height
+        ),
+        Err(e) => {
+            // The logical position was converted with another monitor's scale
+            // factor and landed off-screen; bring it back to the primary monitor
+            tracing::debug!("[configure_region_selector] {}, centering", e);
+            window
+                .center()
+                .map_err(|e| format!("Failed to center selector: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Stub for macOS.
+#[cfg(target_os = "macos")]
 #[tauri::command]
 pub async fn configure_region_selector_window(_window_label: String) -> Result<(), String> {
     Ok(())
@@ -227,7 +318,10 @@ pub async fn get_region_selector_position() -> Result<(i32, i32, i32, i32), Stri
         if client.title == "Region Selection" {
             tracing::debug!(
                 "[get_region_selector_position] Found window at ({}, {}) size {}x{}",
-                client.at.0, client.at.1, client.size.0, client.size.1
+                client.at.0,
+                client.at.1,
+                client.size.0,
+                client.size.1
             );
             return Ok((
                 client.at.0 as i32,
@@ -241,22 +335,148 @@ pub async fn get_region_selector_position() -> Result<(i32, i32, i32, i32), Stri
     Err("Region selector window not found".to_string())
 }
 
-/// Stub for non-Linux platforms.
-#[cfg(not(target_os = "linux"))]
+/// Get the region selector window's geometry in the logical coordinates of
+/// the monitor it is on (see [`region_selector_logical_geometry`]).
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn get_region_selector_position(
+    app: tauri::AppHandle,
+) -> Result<(i32, i32, i32, i32), String> {
+    use tauri::Manager;
+
+    let window = app
+        .get_webview_window("region-selector")
+        .ok_or("Region selector window not found")?;
+    region_selector_logical_geometry(&window)
+}
+
+/// Stub for macOS.
+#[cfg(target_os = "macos")]
 #[tauri::command]
 pub async fn get_region_selector_position() -> Result<(i32, i32, i32, i32), String> {
-    Err("Only available on Linux".to_string())
+    Err("Only available on Linux and Windows".to_string())
+}
+
+/// Pixels between the region selector window's edge and the recorded area on
+/// Hyprland (`BORDER_WIDTH + 2` in selection-overlay.ts).
+#[cfg(target_os = "linux")]
+const REGION_SELECTOR_INSET: i32 = 5;
+
+/// Window size at which the area recorded by a `width`x`height` region
+/// selector has the aspect ratio `ratio` (see [`constrain_to_aspect`]).
+#[cfg(target_os = "linux")]
+fn constrain_selector_size(width: i32, height: i32, ratio: [u32; 2]) -> (i32, i32) {
+    let mut region = CaptureRegion {
+        monitor_id: String::new(),
+        x: 0,
+        y: 0,
+        width: (width - REGION_SELECTOR_INSET * 2).max(1) as u32,
+        height: (height - REGION_SELECTOR_INSET * 2).max(1) as u32,
+        aspect_ratio: None,
+    };
+    constrain_to_aspect(&mut region, (ratio[0], ratio[1]));
+    (
+        region.width as i32 + REGION_SELECTOR_INSET * 2,
+        region.height as i32 + REGION_SELECTOR_INSET * 2,
+    )
+}
+
+/// Payload of the `region-snapped` event.
+#[cfg(target_os = "linux")]
+#[derive(Clone, serde::Serialize)]
+struct RegionSnapped {
+    /// Handle of the window the selector was lined up with
+    snapped_to: isize,
+}
+
+/// Position of a `width`x`height` region selector at (`x`, `y`) after
+/// lining up its recorded area with the edges of a nearby window, and the
+/// window's handle.
+#[cfg(target_os = "linux")]
+fn snap_selector(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    threshold: u32,
+) -> Option<(i32, i32, isize)> {
+    let area = CaptureRegion {
+        monitor_id: String::new(),
+        x: x + REGION_SELECTOR_INSET,
+        y: y + REGION_SELECTOR_INSET,
+        width: (width - REGION_SELECTOR_INSET * 2).max(1) as u32,
+        height: (height - REGION_SELECTOR_INSET * 2).max(1) as u32,
+        aspect_ratio: None,
+    };
+    let windows: Vec<_> = crate::capture::list_windows()
+        .into_iter()
+        .filter(|window| window.title != "Region Selection")
+        .collect();
+    let snap = find_snap_target(&area, &windows, threshold)?;
+    Some((
+        snap.region.x - REGION_SELECTOR_INSET,
+        snap.region.y - REGION_SELECTOR_INSET,
+        snap.window_handle,
+    ))
 }
 
 /// Move the region selector window to a specific position (Hyprland only).
 /// Wayland doesn't allow apps to position windows, so we use Hyprland IPC.
+///
+/// With `lock_aspect_ratio`, the size is adjusted so the recorded area has
+/// the ratio `aspect_ratio` (width, height). With snapping turned on, a
+/// selector close to a window's edges is lined up with them and
+/// `region-snapped` is emitted.
 #[cfg(target_os = "linux")]
 #[tauri::command]
-pub async fn move_region_selector(x: i32, y: i32, width: i32, height: i32) -> Result<(), String> {
+#[allow(clippy::too_many_arguments)]
+pub async fn move_region_selector(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    lock_aspect_ratio: bool,
+    aspect_ratio: [u32; 2],
+    app: tauri::AppHandle,
+    state: State<'_, AppConfigState>,
+) -> Result<(), String> {
+    use tauri::Emitter;
+
     if std::env::var("HYPRLAND_INSTANCE_SIGNATURE").is_err() {
         return Ok(()); // Not on Hyprland, silently ignore
     }
 
+    let (width, height) = if lock_aspect_ratio {
+        constrain_selector_size(width, height, aspect_ratio)
+    } else {
+        (width, height)
+    };
+
+    let snap_config = state.app_config.lock().await.region_selector;
+    let snap = if snap_config.snap_to_window {
+        snap_selector(x, y, width, height, snap_config.snap_threshold_px)
+    } else {
+        None
+    };
+    let (x, y) = match snap {
+        Some((snapped_x, snapped_y, window_handle)) => {
+            tracing::debug!(
+                "[move_region_selector] Snapped to window {} at ({}, {})",
+                window_handle,
+                snapped_x,
+                snapped_y
+            );
+            let _ = app.emit(
+                "region-snapped",
+                RegionSnapped {
+                    snapped_to: window_handle,
+                },
+            );
+            (snapped_x, snapped_y)
+        }
+        None => (x, y),
+    };
+
     // First, find the window address
     use hyprland::data::Clients;
     use hyprland::shared::HyprData;
@@ -300,7 +520,10 @@ pub async fn move_region_selector(x: i32, y: i32, width: i32, height: i32) -> Re
 
     tracing::debug!(
         "[move_region_selector] Moved window to ({}, {}) size {}x{}",
-        x, y, width, height
+        x,
+        y,
+        width,
+        height
     );
 
     Ok(())
@@ -314,6 +537,8 @@ pub async fn move_region_selector(
     _y: i32,
     _width: i32,
     _height: i32,
+    _lock_aspect_ratio: bool,
+    _aspect_ratio: [u32; 2],
 ) -> Result<(), String> {
     Ok(())
 }
@@ -324,6 +549,118 @@ pub async fn move_region_selector(
 
 /// Check if the background service is connected and ready.
 #[tauri::command]
-pub fn is_service_ready(state: State<'_, AppState>) -> bool {
+pub fn is_service_ready(state: State<'_, ServiceReadyState>) -> bool {
     state.is_service_ready()
 }
+
+/// Result of a service health probe.
+#[derive(serde::Serialize)]
+pub struct ServiceHealth {
+    /// Whether the recording subsystem is initialized and answered the ping.
+    pub ready: bool,
+    /// Round-trip time of the ping in milliseconds.
+    pub latency_ms: u64,
+    /// Process ID of the service, if it is running.
+    pub pid: Option<u32>,
+}
+
+/// Perform a lightweight `Ping` against the recording service.
+///
+/// The service runs in-process, so the ping goes straight through the IPC
+/// request handler rather than over the socket.
+#[tauri::command]
+pub async fn check_service_health(
+    state: State<'_, ServiceReadyState>,
+) -> Result<ServiceHealth, String> {
+    let started = std::time::Instant::now();
+    let response = crate::ipc::handlers::handle_request(omnirec_types::ipc::Request::Ping).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    Ok(ServiceHealth {
+        ready: state.is_service_ready() && matches!(response, omnirec_types::ipc::Response::Pong),
+        latency_ms,
+        pid: Some(std::process::id()),
+    })
+}
+
+/// Whether OmniRec starts in the tray at login.
+#[tauri::command]
+pub fn get_autostart_enabled() -> bool {
+    crate::platform::autostart::is_autostart_enabled()
+}
+
+/// Start OmniRec in the tray at login.
+#[tauri::command]
+pub fn enable_autostart() -> Result<(), String> {
+    crate::platform::autostart::enable_autostart()
+}
+
+/// Stop starting OmniRec at login.
+#[tauri::command]
+pub fn disable_autostart() -> Result<(), String> {
+    crate::platform::autostart::disable_autostart()
+}
+
+/// List the picker's stored "Always allow" approvals, oldest first.
+/// Approvals are only used on Linux.
+#[tauri::command]
+pub fn get_approval_tokens() -> Vec<omnirec_types::ApprovalTokenInfo> {
+    #[cfg(target_os = "linux")]
+    return crate::capture::linux::approval_token::list_tokens();
+    #[cfg(not(target_os = "linux"))]
+    Vec::new()
+}
+
+/// Revoke a stored approval; the picker asks again next time.
+#[tauri::command]
+pub fn revoke_approval_token(token_id: String) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        match crate::capture::linux::approval_token::revoke_token(&token_id) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!("No approval token {}", token_id)),
+            Err(e) => Err(format!("Failed to revoke approval token: {}", e)),
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    Err(format!("No approval token {}", token_id))
+}
+
+/// List crash dumps the service wrote in previous runs, newest first.
+/// Dumps are only written on Windows.
+#[tauri::command]
+pub fn get_crash_reports() -> Vec<crate::crash::CrashReport> {
+    crate::crash::list_crash_reports(&crate::crash::crash_dir())
+}
+
+/// Video memory budget and usage of the GPU captures run on (Windows only).
+#[tauri::command]
+pub fn get_gpu_memory_info() -> Result<crate::capture::gpu_memory::GpuMemoryInfo, String> {
+    #[cfg(target_os = "windows")]
+    return crate::capture::windows::gpu_memory::query_gpu_memory();
+    #[cfg(not(target_os = "windows"))]
+    Err("GPU memory info is only available on Windows".to_string())
+}
+
+/// Open the crash dump directory in the file manager.
+#[tauri::command]
+pub fn open_crash_report_directory() -> Result<(), String> {
+    let dir = crate::crash::crash_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    #[cfg(target_os = "linux")]
+    let program = "xdg-open";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(target_os = "windows")]
+    let program = "explorer.exe";
+
+    let mut child = std::process::Command::new(program)
+        .arg(&dir)
+        .spawn()
+        .map_err(|e| format!("Failed to open crash report directory: {}", e))?;
+    // Reap the launcher once it exits
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
@@ -4,9 +4,14 @@
 //! These commands interact directly with the RecordingManager singleton.
 
 use crate::capture;
+use crate::config::save_config;
+use crate::encoder::benchmark::{run_benchmark, BenchmarkResult};
+use crate::encoder::concat::{self, SegmentInfo};
+use crate::encoder::ffmpeg_update::{self, FfmpegUpdateStatus};
 use crate::state::get_recording_manager;
 use crate::tray::set_tray_visible;
-use crate::AppState;
+use crate::AppConfigState;
+use omnirec_types::integrity::{self, IntegrityResult};
 use omnirec_types::{CaptureRegion, RecordingState};
 use serde::{Deserialize, Serialize};
 use tauri::{Emitter, State};
@@ -18,26 +23,32 @@ pub struct RecordingResult {
     pub file_path: Option<String>,
     pub source_path: Option<String>,
     pub error: Option<String>,
+    /// Whether `file_path` was copied to the clipboard
+    #[serde(default)]
+    pub copied_to_clipboard: bool,
 }
 
 /// Get current recording state.
 #[tauri::command]
-pub async fn get_recording_state(_state: State<'_, AppState>) -> Result<RecordingState, String> {
+pub async fn get_recording_state() -> Result<RecordingState, String> {
     let manager = get_recording_manager();
     Ok(manager.get_state().await)
 }
 
 /// Start recording the specified window.
+///
+/// Like the other `start_*` commands, waits for the configured countdown first.
 #[tauri::command]
-pub async fn start_recording(
-    window_handle: isize,
-    _state: State<'_, AppState>,
-) -> Result<(), String> {
+pub async fn start_recording(window_handle: isize) -> Result<(), String> {
     let manager = get_recording_manager();
-    manager.start_window_capture(window_handle).await.map_err(|e| {
-        tracing::error!("start_recording error: {}", e);
-        e.to_string()
-    })
+    manager.run_countdown().await?;
+    manager
+        .start_window_capture(window_handle, None)
+        .await
+        .map_err(|e| {
+            tracing::error!("start_recording error: {}", e);
+            e.to_string()
+        })
 }
 
 /// Start recording a screen region.
@@ -48,7 +59,8 @@ pub async fn start_region_recording(
     y: i32,
     width: u32,
     height: u32,
-    _state: State<'_, AppState>,
+    aspect_ratio: Option<[u32; 2]>,
+    state: State<'_, AppConfigState>,
 ) -> Result<(), String> {
     let manager = get_recording_manager();
     let region = CaptureRegion {
@@ -57,19 +69,29 @@ pub async fn start_region_recording(
         y,
         width,
         height,
+        aspect_ratio: aspect_ratio.map(|[w, h]| (w, h)),
     };
-    manager.start_region_capture(region).await.map_err(|e| {
-        tracing::error!("start_region_recording error: {}", e);
-        e.to_string()
-    })
+    manager.run_countdown().await?;
+    manager
+        .start_region_capture(region.clone(), None)
+        .await
+        .map_err(|e| {
+            tracing::error!("start_region_recording error: {}", e);
+            e.to_string()
+        })?;
+
+    // Remember the region so the selector can restore it next session
+    let mut config = state.app_config.lock().await;
+    config.last_region = Some(region);
+    if let Err(e) = save_config(&config) {
+        tracing::warn!("Failed to save last region: {}", e);
+    }
+    Ok(())
 }
 
 /// Start recording an entire display.
 #[tauri::command]
-pub async fn start_display_recording(
-    monitor_id: String,
-    _state: State<'_, AppState>,
-) -> Result<(), String> {
+pub async fn start_display_recording(monitor_id: String) -> Result<(), String> {
     let monitors = capture::list_monitors();
 
     let monitor = monitors
@@ -82,8 +104,9 @@ pub async fn start_display_recording(
         })?;
 
     let manager = get_recording_manager();
+    manager.run_countdown().await?;
     manager
-        .start_display_capture(monitor.id.clone(), monitor.width, monitor.height)
+        .start_display_capture(monitor.id.clone(), monitor.width, monitor.height, None)
         .await
         .map_err(|e| {
             tracing::error!("start_display_recording error: {}", e);
@@ -91,17 +114,29 @@ pub async fn start_display_recording(
         })
 }
 
+/// Start recording the monitor that shows a Hyprland workspace.
+#[tauri::command]
+pub async fn start_workspace_recording(workspace_id: i32) -> Result<(), String> {
+    let manager = get_recording_manager();
+    manager.run_countdown().await?;
+    manager
+        .start_workspace_capture(workspace_id, None)
+        .await
+        .map_err(|e| {
+            tracing::error!("start_workspace_recording error: {}", e);
+            e.to_string()
+        })
+}
+
 /// Start recording on GNOME using the standard portal picker.
 /// This invokes the xdg-desktop-portal screencast flow with GNOME's native picker.
 #[tauri::command]
-pub async fn start_gnome_recording(
-    app: tauri::AppHandle,
-    _state: State<'_, AppState>,
-) -> Result<(), String> {
+pub async fn start_gnome_recording(app: tauri::AppHandle) -> Result<(), String> {
     tracing::info!("Starting GNOME portal recording...");
 
     let manager = get_recording_manager();
-    manager.start_portal_capture().await.map_err(|e| {
+    manager.run_countdown().await?;
+    manager.start_portal_capture(None).await.map_err(|e| {
         tracing::error!("start_gnome_recording error: {}", e);
         e.to_string()
     })?;
@@ -126,7 +161,7 @@ pub async fn start_gnome_recording(
             tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 
             let state = manager.get_state().await;
-            if state != RecordingState::Recording {
+            if !matches!(state, RecordingState::Recording | RecordingState::Suspended) {
                 tracing::info!("[GNOME] Recording state changed to {:?}", state);
                 // Restore tray icon visibility
                 set_tray_visible(&app_clone, true);
@@ -189,13 +224,16 @@ pub async fn set_tray_recording_state(
     Ok(())
 }
 
+/// Cancel the countdown of a pending recording. Returns whether one was running.
+#[tauri::command]
+pub fn cancel_countdown() -> bool {
+    get_recording_manager().cancel_countdown()
+}
+
 /// Stop the current recording and save the file.
 /// Transcoding (if needed) is handled by the encoder.
 #[tauri::command]
-pub async fn stop_recording(
-    _app: tauri::AppHandle,
-    _state: State<'_, AppState>,
-) -> Result<RecordingResult, String> {
+pub async fn stop_recording(_app: tauri::AppHandle) -> Result<RecordingResult, String> {
     let manager = get_recording_manager();
     let result = manager.stop_recording().await;
 
@@ -205,6 +243,7 @@ pub async fn stop_recording(
             file_path: Some(recording_result.file_path.to_string_lossy().to_string()),
             source_path: Some(recording_result.source_path.to_string_lossy().to_string()),
             error: None,
+            copied_to_clipboard: recording_result.copied_to_clipboard,
         }),
         Err(e) => {
             tracing::error!("stop_recording error: {}", e);
@@ -213,14 +252,171 @@ pub async fn stop_recording(
                 file_path: None,
                 source_path: None,
                 error: Some(e.to_string()),
+                copied_to_clipboard: false,
             })
         }
     }
 }
 
+/// Open a saved recording with the system's default handler. On Windows the
+/// file is selected in Explorer instead.
+///
+/// Only files in the output directory or the recording history are opened.
+#[tauri::command]
+pub fn open_recording_file(path: String) -> Result<(), String> {
+    let path = std::path::Path::new(&path);
+    if !path.is_file() {
+        return Err(format!("Recording not found: {}", path.display()));
+    }
+    let output_dir = match crate::config::load_config().output.directory {
+        Some(dir) => Some(std::path::PathBuf::from(dir)),
+        None => crate::config::get_default_output_dir().ok(),
+    };
+    let history = crate::history::load_entries().unwrap_or_default();
+    if !crate::history::is_recording_path(path, output_dir.as_deref(), &history) {
+        return Err(format!("Not a saved recording: {}", path.display()));
+    }
+
+    #[cfg(target_os = "linux")]
+    let mut command = {
+        let mut command = std::process::Command::new("xdg-open");
+        command.arg(path);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = std::process::Command::new("open");
+        command.arg(path);
+        command
+    };
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        use std::os::windows::process::CommandExt;
+        // Explorer parses its own command line; quote only the path
+        let mut command = std::process::Command::new("explorer.exe");
+        command.raw_arg(format!("/select,\"{}\"", path.display()));
+        command
+    };
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to open recording: {}", e))?;
+    // Reap the launcher once it exits
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Copy text (e.g. a saved recording's path) to the clipboard.
+#[tauri::command]
+pub fn copy_to_clipboard(app: tauri::AppHandle, text: String) -> Result<(), String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+    app.clipboard().write_text(text).map_err(|e| e.to_string())
+}
+
 /// Get elapsed recording time in seconds.
 #[tauri::command]
-pub async fn get_elapsed_time(_state: State<'_, AppState>) -> Result<u64, String> {
+pub async fn get_elapsed_time() -> Result<u64, String> {
     let manager = get_recording_manager();
     Ok(manager.get_elapsed_seconds().await)
-}
\ No newline at end of file
+}
+
+/// Recompute a recording's SHA-256 and compare it with its `.sha256` file.
+#[tauri::command]
+pub async fn verify_recording_integrity(path: String) -> Result<IntegrityResult, String> {
+    tokio::task::spawn_blocking(move || integrity::verify(std::path::Path::new(&path)))
+        .await
+        .map_err(|e| format!("Verification task failed: {}", e))
+}
+
+/// Benchmark the encoder with synthetic frames to check whether this machine
+/// can sustain recording at the given size and frame rate.
+#[tauri::command]
+pub async fn benchmark_encoder(
+    width: u32,
+    height: u32,
+    fps: u32,
+    duration_secs: u8,
+) -> Result<BenchmarkResult, String> {
+    let manager = get_recording_manager();
+    if manager.get_state().await != RecordingState::Idle {
+        return Err("Cannot run a benchmark while recording".to_string());
+    }
+    let threads = manager.get_encoder_threads().await;
+    tokio::task::spawn_blocking(move || run_benchmark(width, height, fps, duration_secs, threads))
+        .await
+        .map_err(|e| format!("Benchmark task failed: {}", e))?
+}
+
+/// Check whether a newer FFmpeg than the installed one is available.
+#[tauri::command]
+pub async fn check_ffmpeg_update() -> Result<FfmpegUpdateStatus, String> {
+    tokio::task::spawn_blocking(ffmpeg_update::check_ffmpeg_update)
+        .await
+        .map_err(|e| format!("Update check task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+/// Download the pinned FFmpeg release and use it instead of the bundled
+/// binary. Returns the version now installed.
+#[tauri::command]
+pub async fn download_ffmpeg_update() -> Result<String, String> {
+    if get_recording_manager().get_state().await != RecordingState::Idle {
+        return Err("Cannot update FFmpeg while recording".to_string());
+    }
+    tokio::task::spawn_blocking(ffmpeg_update::download_ffmpeg_update)
+        .await
+        .map_err(|e| format!("Update task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+/// Join recordings, in order, into one file with a chapter marker wherever
+/// the recorded display or window changes.
+///
+/// Chapters are named after the source stored in the recording history,
+/// falling back to the file name. Returns the path of the joined file, which
+/// defaults to `<first>_joined.<ext>` next to the first recording.
+#[tauri::command]
+pub async fn concatenate_recordings(
+    paths: Vec<String>,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let history = crate::history::load_entries().unwrap_or_default();
+        let segments = paths
+            .iter()
+            .map(|path| {
+                let path = std::path::PathBuf::from(path);
+                let source = history
+                    .iter()
+                    .find(|entry| std::path::Path::new(&entry.path) == path)
+                    .and_then(|entry| entry.source_name.clone())
+                    .or_else(|| {
+                        path.file_stem()
+                            .map(|stem| stem.to_string_lossy().to_string())
+                    })
+                    .unwrap_or_default();
+                let duration_secs = concat::probe_duration(&path)?;
+                Ok(SegmentInfo {
+                    path,
+                    source,
+                    duration_secs,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let output_path = match output_path {
+            Some(path) => std::path::PathBuf::from(path),
+            None => {
+                let first = &segments.first().ok_or("No recordings to join")?.path;
+                let stem = first.file_stem().unwrap_or_default().to_string_lossy();
+                let extension = first.extension().unwrap_or_default().to_string_lossy();
+                first.with_file_name(format!("{}_joined.{}", stem, extension))
+            }
+        };
+
+        concat::concatenate(&segments, &output_path)?;
+        Ok(output_path.display().to_string())
+    })
+    .await
+    .map_err(|e| format!("Join task failed: {}", e))?
+}
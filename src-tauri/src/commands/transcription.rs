@@ -3,9 +3,11 @@
 //! Commands for managing voice transcription settings.
 //! Model management (path resolution, download) is delegated to vtx-engine's ModelManager.
 
-use crate::config::{save_config as save_config_to_disk, TranscriptionConfig, WhisperModel};
+use crate::config::{
+    save_config as save_config_to_disk, TranscriptionConfig, WhisperLanguage, WhisperModel,
+};
 use crate::state::get_recording_manager;
-use crate::AppState;
+use crate::AppConfigState;
 use omnirec_types::TranscriptionStatus;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -41,6 +43,13 @@ pub struct ModelInfo {
     pub downloaded: bool,
 }
 
+/// Language information for listing supported languages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageInfo {
+    pub code: String,
+    pub display_name: String,
+}
+
 /// Download progress event payload
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadProgress {
@@ -55,7 +64,7 @@ pub struct DownloadProgress {
 /// Get current transcription configuration.
 #[tauri::command]
 pub async fn get_transcription_config(
-    state: State<'_, AppState>,
+    state: State<'_, AppConfigState>,
 ) -> Result<TranscriptionConfig, String> {
     let config = state.app_config.lock().await;
     Ok(config.transcription.clone())
@@ -66,11 +75,12 @@ pub async fn get_transcription_config(
 pub async fn save_transcription_config(
     enabled: bool,
     model: Option<String>,
+    language: Option<String>,
     show_transcript_window: Option<bool>,
-    state: State<'_, AppState>,
+    state: State<'_, AppConfigState>,
 ) -> Result<(), String> {
     // Update local config and get model path
-    let model_path = {
+    let (model_path, language_code) = {
         let mut config = state.app_config.lock().await;
         config.transcription.enabled = enabled;
 
@@ -82,13 +92,21 @@ pub async fn save_transcription_config(
             }
         }
 
+        if let Some(code) = &language {
+            config.transcription.language = WhisperLanguage::from_code(code)
+                .ok_or_else(|| format!("Invalid language: {}", code))?;
+        }
+
         if let Some(show) = show_transcript_window {
             config.transcription.show_transcript_window = show;
         }
 
         save_config_to_disk(&config)?;
 
-        config.transcription.model.model_path()
+        (
+            config.transcription.effective_model().model_path(),
+            config.transcription.language.code(),
+        )
     };
 
     // Sync to RecordingManager
@@ -97,13 +115,15 @@ pub async fn save_transcription_config(
         .set_transcription_config(omnirec_types::TranscriptionConfig {
             enabled,
             model_path: Some(model_path.to_string_lossy().to_string()),
+            language: Some(language_code.to_string()),
         })
         .await;
 
     tracing::info!(
-        "Saved transcription config: enabled={}, model={:?}, show_transcript_window={:?}",
+        "Saved transcription config: enabled={}, model={:?}, language={:?}, show_transcript_window={:?}",
         enabled,
         model,
+        language,
         show_transcript_window
     );
 
@@ -112,25 +132,24 @@ pub async fn save_transcription_config(
 
 /// Get current transcription status from the RecordingManager (backed by vtx-engine state).
 #[tauri::command]
-pub async fn get_transcription_status(
-    _state: State<'_, AppState>,
-) -> Result<TranscriptionStatus, String> {
+pub async fn get_transcription_status() -> Result<TranscriptionStatus, String> {
     let manager = get_recording_manager();
     Ok(manager.get_transcription_status().await)
 }
 
-/// Get status of a specific model (or the currently configured model if not specified).
+/// Get status of a specific model (or the model loaded for the configured
+/// language if not specified).
 /// Uses vtx-engine's ModelManager for path resolution.
 #[tauri::command]
 pub async fn get_model_status(
     model: Option<String>,
-    state: State<'_, AppState>,
+    state: State<'_, AppConfigState>,
 ) -> Result<ModelStatus, String> {
     let whisper_model = if let Some(model_str) = model {
         WhisperModel::from_str(&model_str).ok_or_else(|| format!("Invalid model: {}", model_str))?
     } else {
         let config = state.app_config.lock().await;
-        config.transcription.model
+        config.transcription.effective_model()
     };
 
     // Use vtx-engine's ModelManager for canonical path resolution.
@@ -181,6 +200,18 @@ pub async fn list_available_models() -> Result<Vec<ModelInfo>, String> {
     Ok(models)
 }
 
+/// List the languages transcription can be set to, "auto" first.
+#[tauri::command]
+pub async fn list_supported_languages() -> Result<Vec<LanguageInfo>, String> {
+    Ok(WhisperLanguage::all()
+        .iter()
+        .map(|l| LanguageInfo {
+            code: l.code().to_string(),
+            display_name: l.display_name().to_string(),
+        })
+        .collect())
+}
+
 /// Download a whisper model with progress events.
 /// Uses vtx-engine's ModelManager for the download.
 #[tauri::command]
@@ -311,7 +342,6 @@ pub async fn is_download_in_progress() -> Result<bool, String> {
 #[tauri::command]
 pub async fn get_transcription_segments(
     since_index: u32,
-    _state: State<'_, AppState>,
 ) -> Result<TranscriptionSegmentsResponse, String> {
     let manager = get_recording_manager();
     let (segments, total_count) = manager.get_transcription_segments(since_index);
@@ -341,7 +371,10 @@ fn sync_recording_state_to_transcript(app: &tauri::AppHandle) {
         tauri::async_runtime::spawn(async move {
             let state = get_recording_manager().get_state().await;
             let _ = window.emit("recording-state-changed", state);
-            tracing::debug!("[Transcript] Synced recording state {:?} to transcript window", state);
+            tracing::debug!(
+                "[Transcript] Synced recording state {:?} to transcript window",
+                state
+            );
             drop(app); // keep AppHandle alive until emit completes
         });
     }
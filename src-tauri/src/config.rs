@@ -5,14 +5,23 @@
 //! - macOS: `~/Library/Application Support/omnirec/config.json`
 //! - Windows: `%APPDATA%\omnirec\config.json`
 
+use crate::capture::{
+    BackendId, CaptureRegion, HighlightStyle, MonitorInfo, DEFAULT_THUMBNAIL_POOL_SIZE,
+    MAX_THUMBNAIL_POOL_SIZE, THUMBNAIL_DIMENSION_MAX, THUMBNAIL_DIMENSION_MIN,
+    THUMBNAIL_MAX_HEIGHT, THUMBNAIL_MAX_WIDTH,
+};
+use crate::encoder::scaling::DynamicResolutionScaling;
+use crate::encoder::EncoderThreads;
 use directories::BaseDirs;
 use directories::ProjectDirs;
 use directories::UserDirs;
+use omnirec_types::PipConfig;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fs;
-use std::path::PathBuf;
-use tracing::{debug, warn};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
 
 /// Minimum log level for the tracing subscriber.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -90,11 +99,28 @@ impl Default for AppearanceConfig {
 }
 
 /// Output-related configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OutputConfig {
     /// Custom output directory. If None, uses system default (Videos folder).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub directory: Option<String>,
+    /// Create the output directory (and its parents) when it doesn't exist
+    /// instead of failing the recording.
+    #[serde(default = "default_create_output_dir_if_missing")]
+    pub create_if_missing: bool,
+}
+
+fn default_create_output_dir_if_missing() -> bool {
+    true
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            create_if_missing: true,
+        }
+    }
 }
 
 /// Audio-related configuration.
@@ -119,6 +145,18 @@ pub struct AudioConfig {
     /// (engine default). When disabled, gate_threshold_db = -100.0 dBFS (gate bypassed).
     #[serde(default = "default_agc_noise_gate_enabled")]
     pub agc_noise_gate_enabled: bool,
+    /// Level the microphone AGC normalizes to, in dBFS (RMS).
+    #[serde(default = "default_agc_target_level_dbfs")]
+    pub agc_target_level_dbfs: f32,
+    /// Whether captured audio is played back while recording (macOS only).
+    #[serde(default)]
+    pub audio_monitor_enabled: bool,
+    /// Playback volume of the audio monitor (0.0–1.0).
+    #[serde(default = "default_monitor_volume")]
+    pub monitor_volume: f32,
+    /// Audio bitrate in kbps; 0 picks 192 kbps.
+    #[serde(default)]
+    pub audio_bitrate_kbps: u32,
 }
 
 fn default_audio_enabled() -> bool {
@@ -133,6 +171,14 @@ fn default_agc_noise_gate_enabled() -> bool {
     true
 }
 
+fn default_agc_target_level_dbfs() -> f32 {
+    omnirec_types::DEFAULT_AGC_TARGET_LEVEL_DBFS
+}
+
+fn default_monitor_volume() -> f32 {
+    1.0
+}
+
 impl Default for AudioConfig {
     fn default() -> Self {
         Self {
@@ -142,6 +188,10 @@ impl Default for AudioConfig {
             echo_cancellation: true, // AEC enabled by default when mic is used
             agc_enabled: false,
             agc_noise_gate_enabled: true,
+            agc_target_level_dbfs: omnirec_types::DEFAULT_AGC_TARGET_LEVEL_DBFS,
+            audio_monitor_enabled: false,
+            monitor_volume: 1.0,
+            audio_bitrate_kbps: 0,
         }
     }
 }
@@ -313,6 +363,28 @@ impl WhisperModel {
         }
     }
 
+    /// The multilingual model of the same size; multilingual models map to
+    /// themselves.
+    pub fn multilingual(self) -> Self {
+        match self {
+            Self::TinyEn => Self::Tiny,
+            Self::BaseEn => Self::Base,
+            Self::SmallEn => Self::Small,
+            Self::MediumEn => Self::Medium,
+            other => other,
+        }
+    }
+
+    /// The model to transcribe `language` with: English-only models are
+    /// swapped for their multilingual counterpart unless the language is English.
+    pub fn for_language(self, language: WhisperLanguage) -> Self {
+        if language == WhisperLanguage::En {
+            self
+        } else {
+            self.multilingual()
+        }
+    }
+
     /// Parse from string (display name format)
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
@@ -337,6 +409,122 @@ impl fmt::Display for WhisperModel {
     }
 }
 
+/// Spoken language passed to Whisper.
+///
+/// `Auto` lets Whisper detect the language of each segment. Any language
+/// other than English needs a multilingual model (see
+/// [`WhisperModel::for_language`]).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WhisperLanguage {
+    /// Detect the language per segment
+    Auto,
+    #[default]
+    En,
+    De,
+    Es,
+    Fr,
+    It,
+    Pt,
+    Nl,
+    Pl,
+    Sv,
+    Tr,
+    Ru,
+    Uk,
+    Ar,
+    Hi,
+    Zh,
+    Ja,
+    Ko,
+}
+
+impl WhisperLanguage {
+    /// Whisper language code (`"auto"` for detection)
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::En => "en",
+            Self::De => "de",
+            Self::Es => "es",
+            Self::Fr => "fr",
+            Self::It => "it",
+            Self::Pt => "pt",
+            Self::Nl => "nl",
+            Self::Pl => "pl",
+            Self::Sv => "sv",
+            Self::Tr => "tr",
+            Self::Ru => "ru",
+            Self::Uk => "uk",
+            Self::Ar => "ar",
+            Self::Hi => "hi",
+            Self::Zh => "zh",
+            Self::Ja => "ja",
+            Self::Ko => "ko",
+        }
+    }
+
+    /// Get a human-readable display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Auto => "Detect automatically",
+            Self::En => "English",
+            Self::De => "German",
+            Self::Es => "Spanish",
+            Self::Fr => "French",
+            Self::It => "Italian",
+            Self::Pt => "Portuguese",
+            Self::Nl => "Dutch",
+            Self::Pl => "Polish",
+            Self::Sv => "Swedish",
+            Self::Tr => "Turkish",
+            Self::Ru => "Russian",
+            Self::Uk => "Ukrainian",
+            Self::Ar => "Arabic",
+            Self::Hi => "Hindi",
+            Self::Zh => "Chinese",
+            Self::Ja => "Japanese",
+            Self::Ko => "Korean",
+        }
+    }
+
+    /// Get all supported languages, `Auto` first
+    pub fn all() -> &'static [WhisperLanguage] {
+        &[
+            Self::Auto,
+            Self::En,
+            Self::De,
+            Self::Es,
+            Self::Fr,
+            Self::It,
+            Self::Pt,
+            Self::Nl,
+            Self::Pl,
+            Self::Sv,
+            Self::Tr,
+            Self::Ru,
+            Self::Uk,
+            Self::Ar,
+            Self::Hi,
+            Self::Zh,
+            Self::Ja,
+            Self::Ko,
+        ]
+    }
+
+    /// Parse from a language code
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim().to_lowercase();
+        Self::all().iter().copied().find(|l| l.code() == code)
+    }
+}
+
+impl fmt::Display for WhisperLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
 /// Get the whisper model cache directory for the current platform
 pub fn get_whisper_cache_dir() -> PathBuf {
     if cfg!(target_os = "macos") {
@@ -364,11 +552,22 @@ pub struct TranscriptionConfig {
     /// The whisper model to use for transcription.
     #[serde(default)]
     pub model: WhisperModel,
+    /// Spoken language of recordings.
+    #[serde(default)]
+    pub language: WhisperLanguage,
     /// Whether to show the transcript window when recording starts.
     #[serde(default = "default_show_transcript_window")]
     pub show_transcript_window: bool,
 }
 
+impl TranscriptionConfig {
+    /// The model actually loaded: `model`, or its multilingual counterpart
+    /// when transcribing a language other than English.
+    pub fn effective_model(&self) -> WhisperModel {
+        self.model.for_language(self.language)
+    }
+}
+
 fn default_show_transcript_window() -> bool {
     true
 }
@@ -378,14 +577,260 @@ impl Default for TranscriptionConfig {
         Self {
             enabled: false,
             model: WhisperModel::default(),
+            language: WhisperLanguage::default(),
             show_transcript_window: true,
         }
     }
 }
 
+/// Log file rotation and retention settings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LoggingConfig {
+    /// Size in megabytes at which the current log file is rolled over.
+    #[serde(default = "default_max_file_size_mb")]
+    pub max_file_size_mb: u64,
+    /// Maximum number of log files kept in the log directory.
+    #[serde(default = "default_max_files")]
+    pub max_files: u32,
+    /// Log files older than this many days are deleted at startup.
+    #[serde(default = "default_max_age_days")]
+    pub max_age_days: u32,
+}
+
+fn default_max_file_size_mb() -> u64 {
+    10
+}
+
+fn default_max_files() -> u32 {
+    5
+}
+
+fn default_max_age_days() -> u32 {
+    14
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size_mb: default_max_file_size_mb(),
+            max_files: default_max_files(),
+            max_age_days: default_max_age_days(),
+        }
+    }
+}
+
+/// Region selector behavior.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RegionSelectorConfig {
+    /// Line the selector up with a window's edges when placed close to them
+    /// (Hyprland only).
+    #[serde(default = "default_snap_to_window")]
+    pub snap_to_window: bool,
+    /// How close, in pixels, an edge must be to snap.
+    #[serde(default = "default_snap_threshold_px")]
+    pub snap_threshold_px: u32,
+}
+
+fn default_snap_to_window() -> bool {
+    true
+}
+
+fn default_snap_threshold_px() -> u32 {
+    omnirec_types::DEFAULT_SNAP_THRESHOLD_PX
+}
+
+/// Largest accepted snapping distance, in pixels.
+pub const MAX_SNAP_THRESHOLD_PX: u32 = 100;
+
+impl Default for RegionSelectorConfig {
+    fn default() -> Self {
+        Self {
+            snap_to_window: default_snap_to_window(),
+            snap_threshold_px: default_snap_threshold_px(),
+        }
+    }
+}
+
+impl RegionSelectorConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.snap_threshold_px > MAX_SNAP_THRESHOLD_PX {
+            return Err(format!(
+                "Snap distance must be between 0 and {} pixels",
+                MAX_SNAP_THRESHOLD_PX
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Global keyboard shortcuts, as accelerators such as
+/// `CommandOrControl+Shift+S`. Unset shortcuts are not registered.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HotkeyConfig {
+    /// Save a screenshot of the selected source without recording.
+    #[serde(default)]
+    pub screenshot: Option<String>,
+}
+
+impl HotkeyConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(accelerator) = &self.screenshot {
+            crate::hotkeys::parse_shortcut(accelerator)?;
+        }
+        Ok(())
+    }
+}
+
+/// Smallest accepted thumbnail JPEG quality.
+pub const THUMBNAIL_QUALITY_MIN: u8 = 50;
+
+/// Largest accepted thumbnail JPEG quality.
+pub const THUMBNAIL_QUALITY_MAX: u8 = 100;
+
+/// JPEG quality per thumbnail type.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThumbnailQualityConfig {
+    /// Quality of window thumbnails.
+    #[serde(default = "default_window_quality")]
+    pub window_quality: u8,
+    /// Quality of display thumbnails.
+    #[serde(default = "default_display_quality")]
+    pub display_quality: u8,
+    /// Quality of region previews.
+    #[serde(default = "default_region_quality")]
+    pub region_quality: u8,
+}
+
+fn default_window_quality() -> u8 {
+    85
+}
+
+fn default_display_quality() -> u8 {
+    80
+}
+
+fn default_region_quality() -> u8 {
+    75
+}
+
+impl Default for ThumbnailQualityConfig {
+    fn default() -> Self {
+        Self {
+            window_quality: default_window_quality(),
+            display_quality: default_display_quality(),
+            region_quality: default_region_quality(),
+        }
+    }
+}
+
+impl ThumbnailQualityConfig {
+    /// Check that every quality value is within 50–100.
+    pub fn validate(&self) -> Result<(), String> {
+        let range = THUMBNAIL_QUALITY_MIN..=THUMBNAIL_QUALITY_MAX;
+        for (name, value) in [
+            ("Window", self.window_quality),
+            ("Display", self.display_quality),
+            ("Region", self.region_quality),
+        ] {
+            if !range.contains(&value) {
+                return Err(format!(
+                    "{} thumbnail quality must be between {} and {}",
+                    name, THUMBNAIL_QUALITY_MIN, THUMBNAIL_QUALITY_MAX
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// These settings with every quality lowered to at most `max`.
+    pub fn capped(self, max: u8) -> Self {
+        Self {
+            window_quality: self.window_quality.min(max),
+            display_quality: self.display_quality.min(max),
+            region_quality: self.region_quality.min(max),
+        }
+    }
+}
+
+/// Cloud storage service recordings are uploaded to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CloudProvider {
+    /// Amazon S3 or any service speaking the S3 API (MinIO, R2, B2, ...)
+    S3Compatible { endpoint_url: String },
+}
+
+/// Credentials for the cloud storage service.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CloudCredentials {
+    pub access_key_id: String,
+    /// Left out of exported configs; an import without it keeps the current
+    /// key. The config files are written readable by the user only because
+    /// of it.
+    #[serde(default)]
+    pub secret_access_key: String,
+    /// Signing region; many S3-compatible services accept any value.
+    #[serde(default = "default_cloud_region")]
+    pub region: String,
+}
+
+impl std::fmt::Debug for CloudCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CloudCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"<redacted>")
+            .field("region", &self.region)
+            .finish()
+    }
+}
+
+fn default_cloud_region() -> String {
+    "us-east-1".to_string()
+}
+
+/// Automatic upload of finished recordings to cloud storage.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CloudUploadConfig {
+    /// Upload each recording after it is saved.
+    #[serde(default)]
+    pub enabled: bool,
+    pub provider: CloudProvider,
+    pub bucket: String,
+    /// Key prefix ("folder") recordings are uploaded under.
+    #[serde(default)]
+    pub prefix: String,
+    pub credentials: CloudCredentials,
+}
+
+impl CloudUploadConfig {
+    /// Check that the settings needed to connect are present.
+    pub fn validate(&self) -> Result<(), String> {
+        let CloudProvider::S3Compatible { endpoint_url } = &self.provider;
+        if !(endpoint_url.starts_with("https://") || endpoint_url.starts_with("http://")) {
+            return Err("Endpoint URL must start with https:// or http://".to_string());
+        }
+        if self.bucket.trim().is_empty() {
+            return Err("Bucket name is required".to_string());
+        }
+        if self.credentials.access_key_id.is_empty()
+            || self.credentials.secret_access_key.is_empty()
+        {
+            return Err("Access key ID and secret access key are required".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Schema version written by this build. Bump it and add a `migrate_config`
+/// arm whenever a field is renamed, removed or needs a non-default value.
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
 /// Application configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// Schema version of this config (0 for files written before versioning).
+    #[serde(default)]
+    pub config_version: u32,
     /// Output settings group.
     #[serde(default)]
     pub output: OutputConfig,
@@ -404,112 +849,634 @@ pub struct AppConfig {
     /// Minimum log level persisted to disk (can be changed at runtime).
     #[serde(default)]
     pub log_level: LogLevel,
+    /// Log rotation and retention settings.
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    /// How long to wait for the recording subsystem to become ready at startup.
+    #[serde(default = "default_service_startup_timeout_secs")]
+    pub service_startup_timeout_secs: u64,
+    /// Capture backend to use instead of automatic selection.
+    #[serde(default)]
+    pub preferred_backend: Option<BackendId>,
+    /// How long a granted screen recording permission is cached before it is
+    /// checked again (macOS).
+    #[serde(default = "default_permission_cache_ttl_secs")]
+    pub permission_cache_ttl_secs: u64,
+    /// Check GitHub for a newer release at startup.
+    #[serde(default = "default_check_update_on_startup")]
+    pub check_update_on_startup: bool,
+    /// Maximum width of window/display thumbnails in the picker.
+    #[serde(default = "default_thumbnail_max_width")]
+    pub thumbnail_max_width: u32,
+    /// Maximum height of window/display thumbnails in the picker.
+    #[serde(default = "default_thumbnail_max_height")]
+    pub thumbnail_max_height: u32,
+    /// JPEG quality of picker thumbnails and previews.
+    #[serde(default)]
+    pub thumbnail_quality: ThumbnailQualityConfig,
+    /// Number of window/display thumbnails captured concurrently.
+    #[serde(default = "default_thumbnail_thread_pool_size")]
+    pub thumbnail_thread_pool_size: usize,
+    /// Appearance of the capture target highlight border.
+    #[serde(default)]
+    pub highlight_style: HighlightStyle,
+    /// Region of the last successful region recording, restored by the region selector.
+    #[serde(default)]
+    pub last_region: Option<CaptureRegion>,
+    /// Capture the microphone in WASAPI exclusive mode (Windows). Other
+    /// applications cannot use the device while recording.
+    #[serde(default)]
+    pub audio_exclusive_mode: bool,
+    /// FFmpeg video filter chain (`-vf`) applied to recordings, e.g. `scale=1280:720`.
+    #[serde(default)]
+    pub video_filter_chain: Option<String>,
+    /// Threads FFmpeg's video encoders (H.264 while recording, VP9 when
+    /// transcoding to WebM) use.
+    #[serde(default)]
+    pub encoder_threads: EncoderThreads,
+    /// Seconds between video keyframes. Shorter intervals make seeking more
+    /// precise at the cost of larger files.
+    #[serde(default = "default_keyframe_interval_secs")]
+    pub keyframe_interval_secs: f32,
+    /// Lower the resolution of later recordings while frames are being
+    /// dropped, and raise it again once they aren't.
+    #[serde(default)]
+    pub dynamic_resolution_scaling: DynamicResolutionScaling,
+    /// Drop every other captured frame while encoding is more than 5
+    /// seconds behind real time, until it catches up.
+    #[serde(default = "default_enable_load_shedding")]
+    pub enable_load_shedding: bool,
+    /// Trim silence from the start and end of recordings with audio.
+    #[serde(default)]
+    pub trim_silence: bool,
+    /// Audio below this level (dBFS) counts as silence when trimming.
+    #[serde(default = "default_silence_threshold_db")]
+    pub silence_threshold_db: f32,
+    /// Local directory recordings are written to while capturing, before being
+    /// moved to the output directory. None writes to the output directory directly.
+    #[serde(default)]
+    pub temp_recording_dir: Option<PathBuf>,
+    /// Copy the saved recording's path to the clipboard when a recording stops.
+    #[serde(default)]
+    pub copy_recording_path_to_clipboard: bool,
+    /// Open the output directory in the file manager when a recording stops.
+    #[serde(default)]
+    pub open_output_dir_after_recording: bool,
+    /// Choose the output format from the recording (GIF for short, small
+    /// clips) when no format was given.
+    #[serde(default)]
+    pub smart_format: bool,
+    /// Seconds to count down before a recording starts (0 = start at once).
+    #[serde(default)]
+    pub countdown_secs: u8,
+    /// Show a desktop notification when a recording has been saved.
+    #[serde(default = "default_show_completion_notification")]
+    pub show_completion_notification: bool,
+    /// Include the full file path, not just the file name, in that notification.
+    #[serde(default)]
+    pub notification_include_path: bool,
+    /// Show a small always-on-top window with a live preview of the
+    /// recording while it runs.
+    #[serde(default)]
+    pub show_recording_preview: bool,
+    /// Write a SHA-256 checksum file (`<basename>.sha256`) next to each saved
+    /// recording so it can be verified later.
+    #[serde(default = "default_verify_on_finish")]
+    pub verify_on_finish: bool,
+    /// Paths of the most recently saved recordings, newest first.
+    #[serde(default)]
+    pub recent_recordings: Vec<String>,
+    /// Window captures record the screen area under the window and move with
+    /// it, instead of the window's own contents (Windows and macOS).
+    #[serde(default)]
+    pub window_follow: bool,
+    /// Always capture windows through their own surface (Windows
+    /// Graphics Capture), skipping the exclusive fullscreen check that
+    /// window-follow mode runs before each window capture (Windows only).
+    #[serde(default)]
+    pub force_wgc_for_games: bool,
+    /// Free video memory, in MB, below which captures are throttled to 15 fps
+    /// (Windows only; 0 turns throttling off).
+    #[serde(default = "default_gpu_memory_low_threshold_mb")]
+    pub gpu_memory_low_threshold_mb: u64,
+    /// Focus a window, switching to its workspace, before capturing it so
+    /// the recording doesn't start with a black frame (Hyprland only).
+    #[serde(default = "default_focus_before_capture")]
+    pub focus_before_capture: bool,
+    /// Upload recordings to cloud storage. None until configured.
+    #[serde(default)]
+    pub cloud_upload: Option<CloudUploadConfig>,
+    /// Webcam overlaid in a corner of recordings. None records the screen
+    /// alone.
+    #[serde(default)]
+    pub pip: Option<PipConfig>,
+    /// Region selector behavior.
+    #[serde(default)]
+    pub region_selector: RegionSelectorConfig,
+    /// Global keyboard shortcuts.
+    #[serde(default)]
+    pub hotkeys: HotkeyConfig,
 }
 
-impl AppConfig {
-    /// Create a new config with default values.
-    pub fn new() -> Self {
-        Self::default()
-    }
+fn default_service_startup_timeout_secs() -> u64 {
+    15
 }
 
-/// Get the path to the config file.
-fn get_config_path() -> Result<PathBuf, String> {
-    let proj_dirs =
-        ProjectDirs::from("", "", "omnirec").ok_or("Could not determine config directory")?;
+fn default_permission_cache_ttl_secs() -> u64 {
+    30
+}
 
-    let config_dir = proj_dirs.config_dir();
-    Ok(config_dir.join("config.json"))
+fn default_check_update_on_startup() -> bool {
+    true
 }
 
-/// Load configuration from disk.
-/// Returns default config if file doesn't exist or is invalid.
-pub fn load_config() -> AppConfig {
-    let config_path = match get_config_path() {
-        Ok(path) => path,
-        Err(e) => {
-            warn!("[Config] Failed to get config path: {}", e);
-            return AppConfig::default();
-        }
-    };
+fn default_show_completion_notification() -> bool {
+    true
+}
 
-    if !config_path.exists() {
-        debug!("[Config] No config file found, using defaults");
-        return AppConfig::default();
-    }
+fn default_verify_on_finish() -> bool {
+    true
+}
 
-    match fs::read_to_string(&config_path) {
-        Ok(contents) => match serde_json::from_str::<AppConfig>(&contents) {
-            Ok(config) => {
-                debug!("[Config] Loaded config from {:?}", config_path);
-                config
-            }
-            Err(e) => {
-                warn!(
-                    "[Config] Failed to parse config file: {}. Using defaults.",
-                    e
-                );
-                AppConfig::default()
-            }
-        },
-        Err(e) => {
-            warn!(
-                "[Config] Failed to read config file: {}. Using defaults.",
-                e
-            );
-            AppConfig::default()
-        }
-    }
+fn default_focus_before_capture() -> bool {
+    true
 }
 
-/// Save configuration to disk.
-/// Creates the config directory if it doesn't exist.
-pub fn save_config(config: &AppConfig) -> Result<(), String> {
-    let config_path = get_config_path()?;
+fn default_enable_load_shedding() -> bool {
+    true
+}
 
-    // Ensure config directory exists
-    if let Some(parent) = config_path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
+fn default_gpu_memory_low_threshold_mb() -> u64 {
+    crate::capture::gpu_memory::DEFAULT_LOW_THRESHOLD_MB
+}
 
-    let json = serde_json::to_string_pretty(config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+/// Default noise floor for silence trimming, in dBFS.
+pub const DEFAULT_SILENCE_THRESHOLD_DB: f32 = -40.0;
 
-    fs::write(&config_path, json).map_err(|e| format!("Failed to write config file: {}", e))?;
+/// Accepted range for the silence trimming noise floor, in dBFS.
+pub const SILENCE_THRESHOLD_DB_RANGE: std::ops::RangeInclusive<f32> = -90.0..=-10.0;
 
-    debug!("[Config] Saved config to {:?}", config_path);
-    Ok(())
+/// Default time between video keyframes, in seconds.
+pub const DEFAULT_KEYFRAME_INTERVAL_SECS: f32 = 2.0;
+
+/// Accepted range for the time between video keyframes, in seconds.
+pub const KEYFRAME_INTERVAL_SECS_RANGE: std::ops::RangeInclusive<f32> = 0.1..=10.0;
+
+/// Number of recent recordings remembered in the config.
+pub const MAX_RECENT_RECORDINGS: usize = 10;
+
+/// Longest accepted pre-recording countdown, in seconds.
+pub const MAX_COUNTDOWN_SECS: u8 = 10;
+
+fn default_silence_threshold_db() -> f32 {
+    DEFAULT_SILENCE_THRESHOLD_DB
 }
 
-/// Get the default output directory (system Videos folder).
-pub fn get_default_output_dir() -> Result<PathBuf, String> {
-    let user_dirs = UserDirs::new().ok_or("Could not determine user directories")?;
+fn default_keyframe_interval_secs() -> f32 {
+    DEFAULT_KEYFRAME_INTERVAL_SECS
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        let home = user_dirs.home_dir().to_path_buf();
-        let recordings = home.join("Documents").join("Recordings");
+/// Check that a keyframe interval is within [`KEYFRAME_INTERVAL_SECS_RANGE`].
+pub fn validate_keyframe_interval(secs: f32) -> Result<(), String> {
+    if KEYFRAME_INTERVAL_SECS_RANGE.contains(&secs) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Keyframe interval must be between {} and {} seconds",
+            KEYFRAME_INTERVAL_SECS_RANGE.start(),
+            KEYFRAME_INTERVAL_SECS_RANGE.end()
+        ))
+    }
+}
 
-        if recordings.exists() || fs::create_dir_all(&recordings).is_ok() {
-            return Ok(recordings);
-        }
+fn default_thumbnail_max_width() -> u32 {
+    THUMBNAIL_MAX_WIDTH
+}
 
-        let documents = user_dirs
-            .document_dir()
-            .map(|p| p.to_path_buf())
-            .unwrap_or_else(|| home.join("Documents"));
+fn default_thumbnail_max_height() -> u32 {
+    THUMBNAIL_MAX_HEIGHT
+}
 
-        if documents.exists() || fs::create_dir_all(&documents).is_ok() {
-            Ok(documents)
-        } else {
-            Ok(home)
+fn default_thumbnail_thread_pool_size() -> usize {
+    DEFAULT_THUMBNAIL_POOL_SIZE
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            config_version: CURRENT_CONFIG_VERSION,
+            output: OutputConfig::default(),
+            audio: AudioConfig::default(),
+            transcription: TranscriptionConfig::default(),
+            appearance: AppearanceConfig::default(),
+            always_on_top: false,
+            log_level: LogLevel::default(),
+            logging: LoggingConfig::default(),
+            service_startup_timeout_secs: default_service_startup_timeout_secs(),
+            preferred_backend: None,
+            permission_cache_ttl_secs: default_permission_cache_ttl_secs(),
+            check_update_on_startup: default_check_update_on_startup(),
+            thumbnail_max_width: default_thumbnail_max_width(),
+            thumbnail_max_height: default_thumbnail_max_height(),
+            thumbnail_quality: ThumbnailQualityConfig::default(),
+            thumbnail_thread_pool_size: default_thumbnail_thread_pool_size(),
+            highlight_style: HighlightStyle::default(),
+            last_region: None,
+            audio_exclusive_mode: false,
+            video_filter_chain: None,
+            encoder_threads: EncoderThreads::Auto,
+            keyframe_interval_secs: default_keyframe_interval_secs(),
+            dynamic_resolution_scaling: DynamicResolutionScaling::default(),
+            enable_load_shedding: default_enable_load_shedding(),
+            trim_silence: false,
+            silence_threshold_db: default_silence_threshold_db(),
+            temp_recording_dir: None,
+            copy_recording_path_to_clipboard: false,
+            open_output_dir_after_recording: false,
+            smart_format: false,
+            countdown_secs: 0,
+            show_completion_notification: default_show_completion_notification(),
+            notification_include_path: false,
+            show_recording_preview: false,
+            verify_on_finish: default_verify_on_finish(),
+            recent_recordings: Vec::new(),
+            window_follow: false,
+            force_wgc_for_games: false,
+            gpu_memory_low_threshold_mb: default_gpu_memory_low_threshold_mb(),
+            focus_before_capture: default_focus_before_capture(),
+            cloud_upload: None,
+            pip: None,
+            region_selector: RegionSelectorConfig::default(),
+            hotkeys: HotkeyConfig::default(),
         }
     }
+}
 
-    #[cfg(not(target_os = "macos"))]
-    {
-        let output_dir = user_dirs
+impl AppConfig {
+    /// Create a new config with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Put `path` first in the recent recordings, dropping an earlier entry for
+    /// the same file and anything beyond [`MAX_RECENT_RECORDINGS`].
+    pub fn add_recent_recording(&mut self, path: String) {
+        self.recent_recordings.retain(|recent| *recent != path);
+        self.recent_recordings.insert(0, path);
+        self.recent_recordings.truncate(MAX_RECENT_RECORDINGS);
+    }
+
+    /// Check the settings the config window validates before saving.
+    pub fn validate(&self) -> Result<(), String> {
+        validate_thumbnail_dimensions(self.thumbnail_max_width, self.thumbnail_max_height)?;
+        validate_thumbnail_pool_size(self.thumbnail_thread_pool_size)?;
+        self.thumbnail_quality.validate()?;
+        self.highlight_style.validate()?;
+        self.encoder_threads.validate()?;
+        validate_keyframe_interval(self.keyframe_interval_secs)?;
+        self.dynamic_resolution_scaling.validate()?;
+        omnirec_types::validate_agc_target_level(self.audio.agc_target_level_dbfs)?;
+        if let Some(pip) = &self.pip {
+            pip.validate()?;
+        }
+        self.region_selector.validate()?;
+        self.hotkeys.validate()?;
+        if self.countdown_secs > MAX_COUNTDOWN_SECS {
+            return Err(format!(
+                "Countdown must be between 0 and {} seconds",
+                MAX_COUNTDOWN_SECS
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Serialize `config` as pretty JSON for moving it to another machine.
+///
+/// The cloud secret access key is left out so the file is safe to share.
+pub fn export_config(config: &AppConfig) -> Result<String, String> {
+    let mut value =
+        serde_json::to_value(config).map_err(|e| format!("Failed to serialize: {}", e))?;
+    if let Some(credentials) = value
+        .pointer_mut("/cloud_upload/credentials")
+        .and_then(|c| c.as_object_mut())
+    {
+        credentials.remove("secret_access_key");
+    }
+    serde_json::to_string_pretty(&value).map_err(|e| format!("Failed to serialize: {}", e))
+}
+
+/// Apply exported config JSON on top of `current`.
+///
+/// The import is migrated from its own `config_version` and merged into the
+/// current settings, so fields it leaves out keep their current values.
+pub fn import_config(current: &AppConfig, contents: &str) -> Result<AppConfig, String> {
+    let imported: serde_json::Value =
+        serde_json::from_str(contents).map_err(|e| format!("Failed to parse: {}", e))?;
+    if !imported.is_object() {
+        return Err("Failed to parse: expected a JSON object".to_string());
+    }
+
+    let version = imported
+        .get("config_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    if version > CURRENT_CONFIG_VERSION {
+        return Err(format!(
+            "Config version {} is newer than supported version {}",
+            version, CURRENT_CONFIG_VERSION
+        ));
+    }
+    let imported = migrate_config(imported, version);
+
+    let mut merged =
+        serde_json::to_value(current).map_err(|e| format!("Failed to serialize: {}", e))?;
+    merge_json(&mut merged, imported);
+
+    let config: AppConfig =
+        serde_json::from_value(merged).map_err(|e| format!("Failed to parse: {}", e))?;
+    config.validate()?;
+    Ok(config)
+}
+
+/// Recursively merge `patch` into `base`: objects are merged key by key,
+/// anything else in `patch` replaces the value in `base`.
+fn merge_json(base: &mut serde_json::Value, patch: serde_json::Value) {
+    match (base, patch) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+/// Get the path to the config file.
+pub fn get_config_path() -> Result<PathBuf, String> {
+    let proj_dirs =
+        ProjectDirs::from("", "", "omnirec").ok_or("Could not determine config directory")?;
+
+    let config_dir = proj_dirs.config_dir();
+    Ok(config_dir.join("config.json"))
+}
+
+/// Path of the backup copy kept alongside a config file.
+fn backup_path(config_path: &Path) -> PathBuf {
+    with_suffix(config_path, ".backup")
+}
+
+/// Path of the temporary file used for atomic writes.
+fn temp_path(config_path: &Path) -> PathBuf {
+    with_suffix(config_path, ".tmp")
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Read and parse a config file.
+pub fn read_config_file(path: &Path) -> Result<AppConfig, String> {
+    read_config_file_migrated(path).map(|(config, _)| config)
+}
+
+/// Read and parse a config file, migrating older schemas.
+///
+/// Returns the config and whether a migration was applied.
+fn read_config_file_migrated(path: &Path) -> Result<(AppConfig, bool), String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Failed to read: {}", e))?;
+    parse_config(&contents)
+}
+
+/// Parse config JSON, running any migrations needed to reach the current schema.
+fn parse_config(contents: &str) -> Result<(AppConfig, bool), String> {
+    let value: serde_json::Value =
+        serde_json::from_str(contents).map_err(|e| format!("Failed to parse: {}", e))?;
+
+    let version = value
+        .get("config_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+
+    let (value, migrated) = if version < CURRENT_CONFIG_VERSION {
+        (migrate_config(value, version), true)
+    } else {
+        if version > CURRENT_CONFIG_VERSION {
+            warn!(
+                "[Config] Config version {} is newer than supported version {}",
+                version, CURRENT_CONFIG_VERSION
+            );
+        }
+        (value, false)
+    };
+
+    let config = serde_json::from_value(value).map_err(|e| format!("Failed to parse: {}", e))?;
+    Ok((config, migrated))
+}
+
+/// Upgrade raw config JSON from `from_version` to [`CURRENT_CONFIG_VERSION`].
+///
+/// Each arm upgrades exactly one version, so old files step through every
+/// migration in order.
+pub fn migrate_config(mut value: serde_json::Value, from_version: u32) -> serde_json::Value {
+    let mut version = from_version;
+    while version < CURRENT_CONFIG_VERSION {
+        match version {
+            // v0 -> v1: versioning introduced. Early builds could persist empty
+            // strings for unset paths/devices; normalize them to null.
+            0 => {
+                for (group, key) in [
+                    ("output", "directory"),
+                    ("audio", "source_id"),
+                    ("audio", "microphone_id"),
+                ] {
+                    if let Some(field) = value.get_mut(group).and_then(|g| g.get_mut(key)) {
+                        if field.as_str() == Some("") {
+                            *field = serde_json::Value::Null;
+                        }
+                    }
+                }
+            }
+            _ => unreachable!("no migration defined from config version {}", version),
+        }
+        version += 1;
+        debug!("[Config] Migrated config to version {}", version);
+    }
+
+    if let Some(object) = value.as_object_mut() {
+        object.insert("config_version".to_string(), version.into());
+    }
+    value
+}
+
+/// Load configuration from disk.
+/// Returns default config if file doesn't exist or is invalid.
+pub fn load_config() -> AppConfig {
+    match get_config_path() {
+        Ok(path) => load_config_with_fallback(&path),
+        Err(e) => {
+            warn!("[Config] Failed to get config path: {}", e);
+            AppConfig::default()
+        }
+    }
+}
+
+/// Load configuration from `config_path`, falling back to its `.backup` copy
+/// if the primary file is unreadable or corrupt.
+pub fn load_config_with_fallback(config_path: &Path) -> AppConfig {
+    if !config_path.exists() && !backup_path(config_path).exists() {
+        debug!("[Config] No config file found, using defaults");
+        return AppConfig::default();
+    }
+
+    match read_config_file_migrated(config_path) {
+        Ok((config, migrated)) => {
+            debug!("[Config] Loaded config from {:?}", config_path);
+            if migrated {
+                info!(
+                    "[Config] Upgraded config to version {}",
+                    CURRENT_CONFIG_VERSION
+                );
+                if let Err(e) = save_config_to_path(&config, config_path) {
+                    warn!("[Config] Failed to save migrated config: {}", e);
+                }
+            }
+            return config;
+        }
+        Err(e) => warn!("[Config] Config file {:?} unusable: {}", config_path, e),
+    }
+
+    let backup = backup_path(config_path);
+    match read_config_file(&backup) {
+        Ok(config) => {
+            warn!("[Config] Recovered config from backup {:?}", backup);
+            config
+        }
+        Err(e) => {
+            warn!("[Config] Backup config unusable: {}. Using defaults.", e);
+            AppConfig::default()
+        }
+    }
+}
+
+/// Save configuration to disk.
+/// Creates the config directory if it doesn't exist.
+pub fn save_config(config: &AppConfig) -> Result<(), String> {
+    let config_path = get_config_path()?;
+    save_config_to_path(config, &config_path)?;
+    debug!("[Config] Saved config to {:?}", config_path);
+    Ok(())
+}
+
+/// Write `config` to `config_path` without ever leaving a half-written file.
+///
+/// The previous file is copied to `<name>.backup` (if it is valid), the new
+/// contents are written and synced to `<name>.tmp`, and the temp file is then
+/// renamed over the original. All three are readable by the user only, as
+/// the config can hold cloud storage credentials.
+fn save_config_to_path(config: &AppConfig, config_path: &Path) -> Result<(), String> {
+    // Ensure config directory exists
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    // Only back up a config we can read back; otherwise a corrupt primary
+    // would overwrite the last good backup.
+    if read_config_file(config_path).is_ok() {
+        let backup = backup_path(config_path);
+        if let Err(e) = fs::copy(config_path, &backup).and_then(|_| restrict_to_user(&backup)) {
+            warn!("[Config] Failed to back up config file: {}", e);
+        }
+    }
+
+    let tmp_path = temp_path(config_path);
+    {
+        let mut file = create_private_file(&tmp_path)
+            .map_err(|e| format!("Failed to write config file: {}", e))?;
+        file.write_all(json.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|e| format!("Failed to write config file: {}", e))?;
+    }
+
+    if let Err(e) = fs::rename(&tmp_path, config_path) {
+        // Rename can fail when the temp file cannot replace the target in
+        // place; fall back to a copy, which the backup above protects.
+        debug!("[Config] Atomic rename failed ({}), copying instead", e);
+        let copied = fs::copy(&tmp_path, config_path);
+        let _ = fs::remove_file(&tmp_path);
+        copied.map_err(|e| format!("Failed to write config file: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Create or truncate `path`, readable and writable by the user only.
+fn create_private_file(path: &Path) -> std::io::Result<fs::File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options.open(path)?;
+    // The mode only applies when the file is created
+    restrict_to_user(path)?;
+    Ok(file)
+}
+
+/// Make `path` readable and writable by the user only. On Windows the
+/// config directory is already private to the user.
+fn restrict_to_user(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Get the default output directory (system Videos folder).
+pub fn get_default_output_dir() -> Result<PathBuf, String> {
+    let user_dirs = UserDirs::new().ok_or("Could not determine user directories")?;
+
+    #[cfg(target_os = "macos")]
+    {
+        let home = user_dirs.home_dir().to_path_buf();
+        let recordings = home.join("Documents").join("Recordings");
+
+        if recordings.exists() || fs::create_dir_all(&recordings).is_ok() {
+            return Ok(recordings);
+        }
+
+        let documents = user_dirs
+            .document_dir()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| home.join("Documents"));
+
+        if documents.exists() || fs::create_dir_all(&documents).is_ok() {
+            Ok(documents)
+        } else {
+            Ok(home)
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let output_dir = user_dirs
             .video_dir()
             .map(|p| p.to_path_buf())
             .unwrap_or_else(|| {
@@ -552,9 +1519,159 @@ pub fn validate_directory(path: &str) -> Result<(), String> {
     }
 }
 
+/// Validate an output directory.
+///
+/// A missing directory is accepted when `create_if_missing` is set and it
+/// could be created.
+pub fn validate_output_directory(path: &str, create_if_missing: bool) -> Result<(), String> {
+    let dir = Path::new(path);
+    if dir.exists() || !create_if_missing {
+        return validate_directory(path);
+    }
+    check_creatable(dir)
+}
+
+/// Check that the missing directory `path` could be created, by creating
+/// and removing a temporary directory in its nearest existing ancestor.
+fn check_creatable(path: &Path) -> Result<(), String> {
+    let ancestor = path
+        .ancestors()
+        .skip(1)
+        .find(|ancestor| ancestor.exists())
+        .ok_or("Directory cannot be created")?;
+    if !ancestor.is_dir() {
+        return Err(format!("{} is not a directory", ancestor.display()));
+    }
+
+    let probe = ancestor.join(format!(".omnirec_create_test_{}", std::process::id()));
+    match fs::create_dir_all(&probe) {
+        Ok(()) => {
+            let _ = fs::remove_dir(&probe);
+            Ok(())
+        }
+        Err(_) => Err(format!(
+            "Directory cannot be created: {} is not writable",
+            ancestor.display()
+        )),
+    }
+}
+
+/// Validate a temporary recording directory.
+///
+/// Besides the checks of [`validate_directory`], the directory must not be on
+/// a network filesystem (checked on Linux), since avoiding slow writes to one
+/// is the point of recording to a temporary directory.
+pub fn validate_temp_directory(path: &str) -> Result<(), String> {
+    validate_directory(path)?;
+
+    #[cfg(target_os = "linux")]
+    if is_network_filesystem(Path::new(path)) {
+        return Err("Directory is on a network filesystem; choose a local disk".to_string());
+    }
+
+    Ok(())
+}
+
+/// Whether `path` is on a network filesystem (NFS, SMB/CIFS, AFS, ...).
+#[cfg(target_os = "linux")]
+fn is_network_filesystem(path: &Path) -> bool {
+    use nix::sys::statfs::{
+        statfs, FsType, AFS_SUPER_MAGIC, CODA_SUPER_MAGIC, NCP_SUPER_MAGIC, NFS_SUPER_MAGIC,
+        SMB_SUPER_MAGIC,
+    };
+    // Not exported by nix
+    const CIFS_MAGIC_NUMBER: FsType = FsType(0xFF53_4D42_u32 as _);
+    const SMB2_MAGIC_NUMBER: FsType = FsType(0xFE53_4D42_u32 as _);
+
+    let network_types = [
+        NFS_SUPER_MAGIC,
+        SMB_SUPER_MAGIC,
+        CIFS_MAGIC_NUMBER,
+        SMB2_MAGIC_NUMBER,
+        AFS_SUPER_MAGIC,
+        CODA_SUPER_MAGIC,
+        NCP_SUPER_MAGIC,
+    ];
+    match statfs(path) {
+        Ok(stat) => network_types.contains(&stat.filesystem_type()),
+        Err(e) => {
+            warn!(
+                "Could not determine filesystem of {}: {}",
+                path.display(),
+                e
+            );
+            false
+        }
+    }
+}
+
+/// Validate thumbnail bounds (each dimension must be within 64–1920 pixels).
+pub fn validate_thumbnail_dimensions(max_width: u32, max_height: u32) -> Result<(), String> {
+    let range = THUMBNAIL_DIMENSION_MIN..=THUMBNAIL_DIMENSION_MAX;
+    if !range.contains(&max_width) {
+        return Err(format!(
+            "Thumbnail width must be between {} and {} pixels",
+            THUMBNAIL_DIMENSION_MIN, THUMBNAIL_DIMENSION_MAX
+        ));
+    }
+    if !range.contains(&max_height) {
+        return Err(format!(
+            "Thumbnail height must be between {} and {} pixels",
+            THUMBNAIL_DIMENSION_MIN, THUMBNAIL_DIMENSION_MAX
+        ));
+    }
+    Ok(())
+}
+
+/// Validate the thumbnail pool size (1–16 concurrent captures).
+pub fn validate_thumbnail_pool_size(size: usize) -> Result<(), String> {
+    if !(1..=MAX_THUMBNAIL_POOL_SIZE).contains(&size) {
+        return Err(format!(
+            "Thumbnail pool size must be between 1 and {}",
+            MAX_THUMBNAIL_POOL_SIZE
+        ));
+    }
+    Ok(())
+}
+
+/// Resolve a stored region against the currently connected monitors.
+///
+/// The region is returned unchanged if its monitor is still connected. Otherwise
+/// it is moved to the center of the primary monitor (shrunk to fit if needed).
+/// Returns `None` if there are no monitors.
+pub fn resolve_last_region(
+    region: &CaptureRegion,
+    monitors: &[MonitorInfo],
+) -> Option<CaptureRegion> {
+    if monitors.iter().any(|m| m.id == region.monitor_id) {
+        return Some(region.clone());
+    }
+
+    let primary = monitors
+        .iter()
+        .find(|m| m.is_primary)
+        .or_else(|| monitors.first())?;
+    let width = region.width.min(primary.width);
+    let height = region.height.min(primary.height);
+
+    debug!(
+        "[Config] Last region monitor {} not connected, centering on {}",
+        region.monitor_id, primary.id
+    );
+    Some(CaptureRegion {
+        monitor_id: primary.id.clone(),
+        x: ((primary.width - width) / 2) as i32,
+        y: ((primary.height - height) / 2) as i32,
+        width,
+        height,
+        aspect_ratio: region.aspect_ratio,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::temp_dir;
 
     #[test]
     fn test_default_config() {
@@ -596,6 +1713,16 @@ mod tests {
         assert!(config.source_id.is_none());
         assert!(config.microphone_id.is_none());
         assert!(config.echo_cancellation);
+        assert!(!config.audio_monitor_enabled);
+        assert_eq!(config.monitor_volume, 1.0);
+    }
+
+    #[test]
+    fn test_audio_monitor_defaults_for_older_configs() {
+        let parsed: AudioConfig = serde_json::from_str(r#"{"enabled":true}"#).unwrap();
+        assert!(!parsed.audio_monitor_enabled);
+        assert_eq!(parsed.monitor_volume, 1.0);
+        assert_eq!(parsed.agc_target_level_dbfs, -20.0);
     }
 
     #[test]
@@ -626,6 +1753,37 @@ mod tests {
         assert_eq!(parsed.source_id, Some("123".to_string()));
         assert!(parsed.microphone_id.is_none());
         assert!(parsed.echo_cancellation); // default value
+        assert_eq!(parsed.audio_bitrate_kbps, 0); // automatic
+    }
+
+    #[test]
+    fn test_region_selector_defaults() {
+        let parsed: AppConfig = serde_json::from_str(r#"{"region_selector":{}}"#).unwrap();
+        assert!(parsed.region_selector.snap_to_window);
+        assert_eq!(parsed.region_selector.snap_threshold_px, 15);
+        assert_eq!(
+            AppConfig::default().region_selector,
+            RegionSelectorConfig::default()
+        );
+
+        let config = RegionSelectorConfig {
+            snap_threshold_px: MAX_SNAP_THRESHOLD_PX + 1,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_hotkey_config_validation() {
+        assert!(HotkeyConfig::default().validate().is_ok());
+        let config = HotkeyConfig {
+            screenshot: Some("CommandOrControl+Shift+S".to_string()),
+        };
+        assert!(config.validate().is_ok());
+        let config = HotkeyConfig {
+            screenshot: Some("Shift+NotAKey".to_string()),
+        };
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -695,6 +1853,7 @@ mod tests {
         let config = TranscriptionConfig {
             enabled: true,
             model: WhisperModel::SmallEn,
+            language: WhisperLanguage::De,
             show_transcript_window: false,
         };
 
@@ -703,6 +1862,7 @@ mod tests {
 
         assert!(parsed.enabled);
         assert_eq!(parsed.model, WhisperModel::SmallEn);
+        assert_eq!(parsed.language, WhisperLanguage::De);
         assert!(!parsed.show_transcript_window);
     }
 
@@ -714,6 +1874,41 @@ mod tests {
 
         assert!(parsed.enabled);
         assert_eq!(parsed.model, WhisperModel::MediumEn); // default
+        assert_eq!(parsed.language, WhisperLanguage::En); // default
+    }
+
+    #[test]
+    fn test_effective_model_for_language() {
+        let mut config = TranscriptionConfig::default();
+        assert_eq!(config.effective_model(), WhisperModel::MediumEn);
+
+        config.language = WhisperLanguage::Auto;
+        assert_eq!(config.effective_model(), WhisperModel::Medium);
+        config.language = WhisperLanguage::Ja;
+        config.model = WhisperModel::TinyEn;
+        assert_eq!(config.effective_model(), WhisperModel::Tiny);
+        config.model = WhisperModel::LargeV3;
+        assert_eq!(config.effective_model(), WhisperModel::LargeV3);
+    }
+
+    #[test]
+    fn test_whisper_language_codes() {
+        assert_eq!(
+            WhisperLanguage::from_code("auto"),
+            Some(WhisperLanguage::Auto)
+        );
+        assert_eq!(
+            WhisperLanguage::from_code(" DE "),
+            Some(WhisperLanguage::De)
+        );
+        assert_eq!(WhisperLanguage::from_code("xx"), None);
+        assert_eq!(
+            serde_json::to_string(&WhisperLanguage::Zh).unwrap(),
+            "\"zh\""
+        );
+        for language in WhisperLanguage::all() {
+            assert_eq!(WhisperLanguage::from_code(language.code()), Some(*language));
+        }
     }
 
     #[test]
@@ -777,4 +1972,605 @@ mod tests {
         assert!(model.download_url().contains("huggingface.co"));
         assert!(model.download_url().contains("ggml-medium.en.bin"));
     }
+
+    #[test]
+    fn test_logging_config_backward_compatible() {
+        // Configs written before log rotation settings existed still load
+        let json = r#"{"log_level": "debug"}"#;
+        let parsed: AppConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.logging, LoggingConfig::default());
+
+        let json = r#"{"logging": {"max_files": 3}}"#;
+        let parsed: AppConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.logging.max_files, 3);
+        assert_eq!(parsed.logging.max_file_size_mb, 10);
+        assert_eq!(parsed.logging.max_age_days, 14);
+    }
+
+    #[test]
+    fn test_service_startup_timeout_default() {
+        assert_eq!(AppConfig::default().service_startup_timeout_secs, 15);
+
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed.service_startup_timeout_secs, 15);
+    }
+
+    #[test]
+    fn test_preferred_backend_roundtrip() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed.preferred_backend, None);
+
+        let parsed: AppConfig =
+            serde_json::from_str(r#"{"preferred_backend":"windows_graphics_capture"}"#).unwrap();
+        assert_eq!(
+            parsed.preferred_backend,
+            Some(BackendId::WindowsGraphicsCapture)
+        );
+    }
+
+    #[test]
+    fn test_check_update_on_startup_defaults_to_enabled() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert!(parsed.check_update_on_startup);
+        assert!(AppConfig::default().check_update_on_startup);
+    }
+
+    #[test]
+    fn test_permission_cache_ttl_default() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed.permission_cache_ttl_secs, 30);
+        assert_eq!(AppConfig::default().permission_cache_ttl_secs, 30);
+    }
+
+    #[test]
+    fn test_audio_exclusive_mode_defaults_to_disabled() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert!(!parsed.audio_exclusive_mode);
+        assert!(!AppConfig::default().audio_exclusive_mode);
+    }
+
+    #[test]
+    fn test_silence_trim_defaults() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert!(!parsed.trim_silence);
+        assert_eq!(parsed.silence_threshold_db, DEFAULT_SILENCE_THRESHOLD_DB);
+        assert!(SILENCE_THRESHOLD_DB_RANGE.contains(&DEFAULT_SILENCE_THRESHOLD_DB));
+    }
+
+    #[test]
+    fn test_keyframe_interval_defaults_and_validation() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(
+            parsed.keyframe_interval_secs,
+            DEFAULT_KEYFRAME_INTERVAL_SECS
+        );
+        assert!(validate_keyframe_interval(DEFAULT_KEYFRAME_INTERVAL_SECS).is_ok());
+        assert!(validate_keyframe_interval(0.1).is_ok());
+        assert!(validate_keyframe_interval(10.0).is_ok());
+        assert!(validate_keyframe_interval(0.05).is_err());
+        assert!(validate_keyframe_interval(10.5).is_err());
+        assert!(validate_keyframe_interval(f32::NAN).is_err());
+    }
+
+    #[test]
+    fn test_load_shedding_enabled_by_default() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert!(parsed.enable_load_shedding);
+        assert!(AppConfig::default().enable_load_shedding);
+    }
+
+    #[test]
+    fn test_post_recording_actions_default() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert!(!parsed.copy_recording_path_to_clipboard);
+        assert!(!parsed.open_output_dir_after_recording);
+        assert!(!parsed.smart_format);
+        assert_eq!(parsed.countdown_secs, 0);
+        assert!(parsed.show_completion_notification);
+        assert!(AppConfig::default().show_completion_notification);
+        assert!(!parsed.notification_include_path);
+        assert!(!parsed.show_recording_preview);
+        assert!(parsed.verify_on_finish);
+    }
+
+    #[test]
+    fn test_add_recent_recording() {
+        let mut config = AppConfig::default();
+        assert!(config.recent_recordings.is_empty());
+        for i in 0..12 {
+            config.add_recent_recording(format!("/videos/{}.mp4", i));
+        }
+        assert_eq!(config.recent_recordings.len(), MAX_RECENT_RECORDINGS);
+        assert_eq!(config.recent_recordings[0], "/videos/11.mp4");
+        assert_eq!(config.recent_recordings[9], "/videos/2.mp4");
+
+        config.add_recent_recording("/videos/5.mp4".to_string());
+        assert_eq!(config.recent_recordings.len(), MAX_RECENT_RECORDINGS);
+        assert_eq!(config.recent_recordings[0], "/videos/5.mp4");
+        assert_eq!(
+            config
+                .recent_recordings
+                .iter()
+                .filter(|p| *p == "/videos/5.mp4")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_cloud_upload_config_validate() {
+        let config = CloudUploadConfig {
+            enabled: true,
+            provider: CloudProvider::S3Compatible {
+                endpoint_url: "https://s3.example.com".to_string(),
+            },
+            bucket: "recordings".to_string(),
+            prefix: "omnirec/".to_string(),
+            credentials: CloudCredentials {
+                access_key_id: "AKID".to_string(),
+                secret_access_key: "hunter2".to_string(),
+                region: default_cloud_region(),
+            },
+        };
+        assert!(config.validate().is_ok());
+
+        let mut invalid = config.clone();
+        invalid.provider = CloudProvider::S3Compatible {
+            endpoint_url: "s3.example.com".to_string(),
+        };
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.bucket = " ".to_string();
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.credentials.secret_access_key.clear();
+        assert!(invalid.validate().is_err());
+
+        assert!(!format!("{:?}", config).contains("hunter2"));
+    }
+
+    #[test]
+    fn test_cloud_upload_config_roundtrip() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert!(parsed.cloud_upload.is_none());
+
+        let parsed: CloudUploadConfig = serde_json::from_str(
+            r#"{"provider":{"type":"s3_compatible","endpoint_url":"https://s3.example.com"},
+                "bucket":"b","credentials":{"access_key_id":"a","secret_access_key":"s"}}"#,
+        )
+        .unwrap();
+        assert!(!parsed.enabled);
+        assert_eq!(parsed.prefix, "");
+        assert_eq!(parsed.credentials.region, "us-east-1");
+    }
+
+    #[test]
+    fn test_validate_output_directory_with_missing_parents() {
+        let dir = temp_dir("config-output-dir");
+        let missing = dir.join("a").join("b").join("recordings");
+        let missing = missing.to_string_lossy();
+
+        assert!(validate_output_directory(&missing, true).is_ok());
+        assert!(!dir.join("a").exists(), "validation must not create it");
+        assert_eq!(
+            validate_output_directory(&missing, false),
+            Err("Directory does not exist".to_string())
+        );
+        assert!(validate_output_directory(&dir.to_string_lossy(), false).is_ok());
+
+        let file = dir.join("file");
+        fs::write(&file, b"").unwrap();
+        assert!(validate_output_directory(&file.join("sub").to_string_lossy(), true).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_output_config_defaults_for_older_configs() {
+        let parsed: OutputConfig = serde_json::from_str(r#"{"directory":"/videos"}"#).unwrap();
+        assert!(parsed.create_if_missing);
+    }
+
+    #[test]
+    fn test_validate_temp_directory() {
+        let temp = std::env::temp_dir();
+        assert!(validate_temp_directory(&temp.to_string_lossy()).is_ok());
+        assert!(validate_temp_directory(&temp.join("omnirec-missing").to_string_lossy()).is_err());
+    }
+
+    #[test]
+    fn test_video_filter_chain_roundtrip() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert!(parsed.video_filter_chain.is_none());
+
+        let config = AppConfig {
+            video_filter_chain: Some("scale=1280:720,eq=brightness=0.05".to_string()),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: AppConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.video_filter_chain, config.video_filter_chain);
+    }
+
+    #[test]
+    fn test_encoder_threads_roundtrip() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed.encoder_threads, EncoderThreads::Auto);
+
+        let config = AppConfig {
+            encoder_threads: EncoderThreads::Count(6),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: AppConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.encoder_threads, EncoderThreads::Count(6));
+    }
+
+    #[test]
+    fn test_thumbnail_dimensions_default_to_constants() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed.thumbnail_max_width, THUMBNAIL_MAX_WIDTH);
+        assert_eq!(parsed.thumbnail_max_height, THUMBNAIL_MAX_HEIGHT);
+    }
+
+    #[test]
+    fn test_validate_thumbnail_dimensions() {
+        assert!(validate_thumbnail_dimensions(320, 180).is_ok());
+        assert!(validate_thumbnail_dimensions(64, 64).is_ok());
+        assert!(validate_thumbnail_dimensions(1920, 1920).is_ok());
+        assert!(validate_thumbnail_dimensions(63, 180).is_err());
+        assert!(validate_thumbnail_dimensions(320, 1921).is_err());
+        assert!(validate_thumbnail_dimensions(0, 0).is_err());
+    }
+
+    #[test]
+    fn test_thumbnail_pool_size() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(
+            parsed.thumbnail_thread_pool_size,
+            DEFAULT_THUMBNAIL_POOL_SIZE
+        );
+        assert!(validate_thumbnail_pool_size(1).is_ok());
+        assert!(validate_thumbnail_pool_size(MAX_THUMBNAIL_POOL_SIZE).is_ok());
+        assert!(validate_thumbnail_pool_size(0).is_err());
+        assert!(validate_thumbnail_pool_size(MAX_THUMBNAIL_POOL_SIZE + 1).is_err());
+    }
+
+    #[test]
+    fn test_highlight_style_from_config() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed.highlight_style, HighlightStyle::default());
+
+        let parsed: AppConfig = serde_json::from_str(
+            r#"{"highlight_style": {"color_rgba": [255, 0, 0, 128], "animation": "pulse"}}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.highlight_style.color_rgba, [255, 0, 0, 128]);
+        assert_eq!(parsed.highlight_style.border_width, 8);
+        assert_eq!(
+            parsed.highlight_style.animation,
+            crate::capture::HighlightAnimation::Pulse
+        );
+    }
+
+    fn monitor(id: &str, is_primary: bool) -> MonitorInfo {
+        MonitorInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            is_primary,
+            scale_factor: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_last_region_roundtrip() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert!(parsed.last_region.is_none());
+
+        let config = AppConfig {
+            last_region: Some(CaptureRegion {
+                monitor_id: "DP-1".to_string(),
+                x: 10,
+                y: 20,
+                width: 640,
+                height: 480,
+                aspect_ratio: Some((4, 3)),
+            }),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: AppConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.last_region, config.last_region);
+    }
+
+    #[test]
+    fn test_resolve_last_region_checks_monitor_availability() {
+        let region = CaptureRegion {
+            monitor_id: "DP-2".to_string(),
+            x: 100,
+            y: 50,
+            width: 800,
+            height: 600,
+            aspect_ratio: None,
+        };
+
+        // Monitor still connected: region is kept as-is
+        let monitors = [monitor("DP-1", true), monitor("DP-2", false)];
+        assert_eq!(
+            resolve_last_region(&region, &monitors),
+            Some(region.clone())
+        );
+
+        // Monitor gone: centered on the primary monitor
+        let monitors = [monitor("HDMI-1", false), monitor("DP-1", true)];
+        assert_eq!(
+            resolve_last_region(&region, &monitors),
+            Some(CaptureRegion {
+                monitor_id: "DP-1".to_string(),
+                x: 560,
+                y: 240,
+                width: 800,
+                height: 600,
+                aspect_ratio: None,
+            })
+        );
+
+        // Larger than the fallback monitor: shrunk to fit
+        let huge = CaptureRegion {
+            width: 3840,
+            height: 2160,
+            ..region.clone()
+        };
+        let resolved = resolve_last_region(&huge, &monitors).unwrap();
+        assert_eq!((resolved.x, resolved.y), (0, 0));
+        assert_eq!((resolved.width, resolved.height), (1920, 1080));
+
+        // No monitors at all
+        assert_eq!(resolve_last_region(&region, &[]), None);
+    }
+
+    #[test]
+    fn test_thumbnail_quality_defaults() {
+        let parsed: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed.thumbnail_quality.window_quality, 85);
+        assert_eq!(parsed.thumbnail_quality.display_quality, 80);
+        assert_eq!(parsed.thumbnail_quality.region_quality, 75);
+
+        let partial: ThumbnailQualityConfig =
+            serde_json::from_str(r#"{"window_quality": 95}"#).unwrap();
+        assert_eq!(partial.window_quality, 95);
+        assert_eq!(partial.region_quality, 75);
+    }
+
+    #[test]
+    fn test_thumbnail_quality_validation() {
+        assert!(ThumbnailQualityConfig::default().validate().is_ok());
+
+        let mut config = ThumbnailQualityConfig {
+            region_quality: 49,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        config.region_quality = 50;
+        config.window_quality = 101;
+        assert!(config.validate().is_err());
+
+        config.window_quality = 100;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_atomic_save_round_trip_and_backup() {
+        let dir = temp_dir("config-atomic");
+        let path = dir.join("config.json");
+
+        let first = AppConfig {
+            always_on_top: true,
+            ..Default::default()
+        };
+        save_config_to_path(&first, &path).unwrap();
+        assert!(!temp_path(&path).exists());
+        assert!(!backup_path(&path).exists());
+
+        let second = AppConfig {
+            log_level: LogLevel::Debug,
+            ..Default::default()
+        };
+        save_config_to_path(&second, &path).unwrap();
+
+        assert_eq!(load_config_with_fallback(&path).log_level, LogLevel::Debug);
+        // The backup holds the previous version.
+        assert!(read_config_file(&backup_path(&path)).unwrap().always_on_top);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_interrupted_temp_write_leaves_config_intact() {
+        let dir = temp_dir("config-interrupted");
+        let path = dir.join("config.json");
+
+        let config = AppConfig {
+            always_on_top: true,
+            ..Default::default()
+        };
+        save_config_to_path(&config, &path).unwrap();
+
+        // Simulate a crash mid-write: a truncated temp file never renamed into place.
+        let json = serde_json::to_string_pretty(&AppConfig::default()).unwrap();
+        fs::write(temp_path(&path), &json[..json.len() / 2]).unwrap();
+
+        assert!(load_config_with_fallback(&path).always_on_top);
+
+        // The next save replaces the stale temp file.
+        save_config_to_path(&config, &path).unwrap();
+        assert!(!temp_path(&path).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_config_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir("config-private");
+        let path = dir.join("config.json");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        save_config_to_path(&AppConfig::default(), &path).unwrap();
+        for file in [&path, &backup_path(&path)] {
+            let mode = fs::metadata(file).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600, "{:?}", file);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_corrupt_primary_falls_back_to_backup() {
+        let dir = temp_dir("config-fallback");
+        let path = dir.join("config.json");
+
+        let config = AppConfig {
+            always_on_top: true,
+            ..Default::default()
+        };
+        save_config_to_path(&config, &path).unwrap();
+        save_config_to_path(&config, &path).unwrap();
+
+        // Truncate the primary as a non-atomic writer would on crash.
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::write(&path, &contents[..contents.len() / 3]).unwrap();
+
+        assert!(load_config_with_fallback(&path).always_on_top);
+
+        // A corrupt primary must not replace the good backup on the next save.
+        save_config_to_path(&AppConfig::default(), &path).unwrap();
+        assert!(read_config_file(&backup_path(&path)).unwrap().always_on_top);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_corrupt_primary_without_backup_uses_defaults() {
+        let dir = temp_dir("config-nobackup");
+        let path = dir.join("config.json");
+        fs::write(&path, "{ \"always_on_top\": tr").unwrap();
+
+        assert!(!load_config_with_fallback(&path).always_on_top);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_migrate_v0_to_v1() {
+        let v0 = serde_json::json!({
+            "output": { "directory": "" },
+            "audio": { "enabled": true, "source_id": "", "microphone_id": "42" },
+            "always_on_top": true
+        });
+
+        let migrated = migrate_config(v0, 0);
+        assert_eq!(migrated["config_version"], 1);
+
+        let config: AppConfig = serde_json::from_value(migrated).unwrap();
+        assert_eq!(config.config_version, 1);
+        assert!(config.output.directory.is_none());
+        assert!(config.audio.source_id.is_none());
+        assert_eq!(config.audio.microphone_id.as_deref(), Some("42"));
+        assert!(config.always_on_top);
+    }
+
+    #[test]
+    fn test_parse_config_detects_version() {
+        let (config, migrated) = parse_config(r#"{"always_on_top": true}"#).unwrap();
+        assert!(migrated);
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+
+        let current = serde_json::to_string(&AppConfig::default()).unwrap();
+        let (_, migrated) = parse_config(&current).unwrap();
+        assert!(!migrated);
+    }
+
+    #[test]
+    fn test_load_rewrites_migrated_config() {
+        let dir = temp_dir("config-migrate");
+        let path = dir.join("config.json");
+        fs::write(&path, r#"{"output": {"directory": ""}}"#).unwrap();
+
+        let config = load_config_with_fallback(&path);
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+
+        let on_disk: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(on_disk["config_version"], CURRENT_CONFIG_VERSION);
+        // The pre-migration file is kept as the backup.
+        assert!(backup_path(&path).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_import_keeps_fields_missing_from_import() {
+        let mut current = AppConfig::default();
+        current.always_on_top = true;
+        current.thumbnail_max_width = 640;
+
+        let imported = import_config(&current, r#"{"thumbnail_max_height": 360}"#).unwrap();
+        assert!(imported.always_on_top);
+        assert_eq!(imported.thumbnail_max_width, 640);
+        assert_eq!(imported.thumbnail_max_height, 360);
+        assert_eq!(imported.config_version, CURRENT_CONFIG_VERSION);
+    }
+
+    #[test]
+    fn test_import_migrates_and_validates() {
+        let mut current = AppConfig::default();
+        current.output.directory = Some("/videos".to_string());
+
+        // v0 files stored unset paths as empty strings.
+        let imported = import_config(
+            &current,
+            r#"{"config_version": 0, "output": {"directory": ""}}"#,
+        )
+        .unwrap();
+        assert!(imported.output.directory.is_none());
+
+        assert!(import_config(&current, r#"{"countdown_secs": 200}"#).is_err());
+        assert!(import_config(&current, r#"{"config_version": 999}"#).is_err());
+        assert!(import_config(&current, "[]").is_err());
+    }
+
+    #[test]
+    fn test_export_omits_secret_and_import_keeps_it() {
+        let mut current = AppConfig::default();
+        current.cloud_upload = Some(CloudUploadConfig {
+            enabled: true,
+            provider: CloudProvider::S3Compatible {
+                endpoint_url: "https://s3.example.com".to_string(),
+            },
+            bucket: "recordings".to_string(),
+            prefix: String::new(),
+            credentials: CloudCredentials {
+                access_key_id: "id".to_string(),
+                secret_access_key: "hunter2".to_string(),
+                region: "us-east-1".to_string(),
+            },
+        });
+
+        let exported = export_config(&current).unwrap();
+        assert!(!exported.contains("hunter2"));
+
+        let imported = import_config(&current, &exported).unwrap();
+        assert_eq!(imported.cloud_upload, current.cloud_upload);
+    }
 }
@@ -0,0 +1,283 @@
+//! Applying the app config to the recording subsystem, and reloading it
+//! when the config file changes on disk.
+//!
+//! [`apply_service_config`] pushes the settings the `RecordingManager`
+//! records with at startup and whenever the whole config is replaced. The
+//! manager reads them as each recording starts, so a running recording
+//! keeps the settings it started with. [`start_watcher`] watches the config
+//! file (inotify, kqueue or ReadDirectoryChangesW, via `notify`) so edits
+//! made outside the app take effect without restarting it.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{mpsc, Mutex};
+use tracing::{debug, info, warn};
+
+use crate::config::{self, AppConfig};
+use crate::{capture, hotkeys, state};
+
+/// Quiet period after the last change before the file is read, so a save
+/// made of several writes and a rename is read once, complete.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Apply the capture settings kept in process-wide flags.
+pub fn apply_capture_settings(config: &AppConfig) {
+    capture::registry::set_preferred_backend(config.preferred_backend);
+    capture::window_follow::set_enabled(config.window_follow);
+    capture::gpu_memory::set_low_threshold_mb(config.gpu_memory_low_threshold_mb);
+    #[cfg(target_os = "windows")]
+    capture::windows::fullscreen::set_force_wgc_for_games(config.force_wgc_for_games);
+    #[cfg(target_os = "linux")]
+    capture::linux::hyprland_dispatch::set_focus_before_capture(config.focus_before_capture);
+    #[cfg(target_os = "macos")]
+    capture::macos::permission::set_permission_cache_ttl(Duration::from_secs(
+        config.permission_cache_ttl_secs,
+    ));
+}
+
+/// Push the settings the `RecordingManager` records with. Audio and
+/// transcription settings can't change during a recording and are skipped
+/// with a warning.
+pub async fn apply_service_config(config: &AppConfig) {
+    // Sync audio config
+    let manager = state::get_recording_manager();
+    info!(
+        "[Config] Syncing audio config: enabled={}, source={:?}, mic={:?}, aec={}",
+        config.audio.enabled,
+        config.audio.source_id,
+        config.audio.microphone_id,
+        config.audio.echo_cancellation
+    );
+    if let Err(e) = manager
+        .set_audio_config(omnirec_types::AudioConfig {
+            enabled: config.audio.enabled,
+            source_id: config.audio.source_id.clone(),
+            microphone_id: config.audio.microphone_id.clone(),
+            echo_cancellation: config.audio.echo_cancellation,
+            agc_enabled: config.audio.agc_enabled,
+            agc_noise_gate_enabled: config.audio.agc_noise_gate_enabled,
+            agc_target_level_dbfs: config.audio.agc_target_level_dbfs,
+            audio_monitor_enabled: config.audio.audio_monitor_enabled,
+            monitor_volume: config.audio.monitor_volume,
+            audio_bitrate_kbps: config.audio.audio_bitrate_kbps,
+        })
+        .await
+    {
+        warn!("[Config] Audio config not applied: {}", e);
+    }
+
+    // Sync transcription config
+    let model_path = config.transcription.effective_model().model_path();
+    info!(
+        "[Config] Syncing transcription config: enabled={}, model={:?}",
+        config.transcription.enabled, model_path
+    );
+    if let Err(e) = manager
+        .set_transcription_config(omnirec_types::TranscriptionConfig {
+            enabled: config.transcription.enabled,
+            model_path: Some(model_path.to_string_lossy().to_string()),
+            language: Some(config.transcription.language.code().to_string()),
+        })
+        .await
+    {
+        warn!("[Config] Transcription config not applied: {}", e);
+    }
+
+    // Sync thumbnail size (fall back to defaults if hand-edited out of range)
+    let thumbnail_options = match config::validate_thumbnail_dimensions(
+        config.thumbnail_max_width,
+        config.thumbnail_max_height,
+    ) {
+        Ok(()) => capture::ThumbnailOptions {
+            max_width: config.thumbnail_max_width,
+            max_height: config.thumbnail_max_height,
+            ..Default::default()
+        },
+        Err(e) => {
+            warn!("[Config] Ignoring thumbnail config: {}", e);
+            capture::ThumbnailOptions::default()
+        }
+    };
+    manager.set_thumbnail_options(thumbnail_options).await;
+    match config.thumbnail_quality.validate() {
+        Ok(()) => {
+            manager
+                .set_thumbnail_quality(config.thumbnail_quality)
+                .await
+        }
+        Err(e) => warn!("[Config] Ignoring thumbnail quality config: {}", e),
+    }
+    match config::validate_thumbnail_pool_size(config.thumbnail_thread_pool_size) {
+        Ok(()) => {
+            manager
+                .set_thumbnail_pool_size(config.thumbnail_thread_pool_size)
+                .await
+        }
+        Err(e) => warn!("[Config] Ignoring thumbnail pool size: {}", e),
+    }
+    match config.highlight_style.validate() {
+        Ok(()) => manager.set_highlight_style(config.highlight_style).await,
+        Err(e) => warn!("[Config] Ignoring highlight style: {}", e),
+    }
+    manager
+        .set_audio_exclusive_mode(config.audio_exclusive_mode)
+        .await;
+    manager
+        .set_video_filter_chain(config.video_filter_chain.clone())
+        .await;
+    match config.encoder_threads.validate() {
+        Ok(()) => manager.set_encoder_threads(config.encoder_threads).await,
+        Err(e) => warn!("[Config] Ignoring encoder threads: {}", e),
+    }
+    match config::validate_keyframe_interval(config.keyframe_interval_secs) {
+        Ok(()) => {
+            manager
+                .set_keyframe_interval(config.keyframe_interval_secs)
+                .await
+        }
+        Err(e) => warn!("[Config] Ignoring keyframe interval: {}", e),
+    }
+    match config.dynamic_resolution_scaling.validate() {
+        Ok(()) => {
+            manager
+                .set_dynamic_resolution_scaling(config.dynamic_resolution_scaling)
+                .await
+        }
+        Err(e) => warn!("[Config] Ignoring dynamic resolution scaling: {}", e),
+    }
+    manager.set_load_shedding(config.enable_load_shedding);
+    manager
+        .set_silence_trim(config.trim_silence, config.silence_threshold_db)
+        .await;
+    manager
+        .set_temp_recording_dir(config.temp_recording_dir.clone())
+        .await;
+    manager
+        .set_output_dir(
+            config
+                .output
+                .directory
+                .clone()
+                .map(std::path::PathBuf::from),
+            config.output.create_if_missing,
+        )
+        .await;
+    manager
+        .set_copy_path_to_clipboard(config.copy_recording_path_to_clipboard)
+        .await;
+    manager
+        .set_open_output_dir(config.open_output_dir_after_recording)
+        .await;
+    manager.set_smart_format(config.smart_format).await;
+    manager.set_verify_on_finish(config.verify_on_finish).await;
+    manager
+        .set_countdown_secs(config.countdown_secs.min(config::MAX_COUNTDOWN_SECS))
+        .await;
+    manager.set_cloud_upload(config.cloud_upload.clone()).await;
+    manager.set_pip(config.pip.clone()).await;
+}
+
+/// Apply a config that replaced the current one: capture settings,
+/// recording settings and hotkeys.
+pub async fn apply_config(app: &AppHandle, config: &AppConfig) {
+    apply_capture_settings(config);
+    apply_service_config(config).await;
+    if let Err(e) = hotkeys::apply_config(app, &config.hotkeys) {
+        warn!("[Config] Failed to register hotkeys: {}", e);
+    }
+}
+
+/// Watch the config file and apply changes made to it outside the app.
+pub fn start_watcher(app: AppHandle, app_config: Arc<Mutex<AppConfig>>) {
+    let path = match config::get_config_path() {
+        Ok(path) => path,
+        Err(e) => {
+            warn!("[Config] Not watching config file: {}", e);
+            return;
+        }
+    };
+    // Watch the directory: saves rename a new file over the old one, which
+    // would end a watch on the file itself
+    let Some(dir) = path.parent().map(Path::to_path_buf) else {
+        return;
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        warn!("[Config] Not watching config file: {}", e);
+        return;
+    }
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let file_name = path.file_name().map(|name| name.to_os_string());
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        let changed = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+            && event
+                .paths
+                .iter()
+                .any(|p| p.file_name() == file_name.as_deref());
+        if changed {
+            let _ = tx.send(());
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            warn!("[Config] Not watching config file: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+        warn!("[Config] Not watching config file: {}", e);
+        return;
+    }
+    debug!("[Config] Watching {:?} for changes", path);
+
+    tauri::async_runtime::spawn(async move {
+        // Dropping the watcher stops it
+        let _watcher = watcher;
+        while rx.recv().await.is_some() {
+            loop {
+                match tokio::time::timeout(RELOAD_DEBOUNCE, rx.recv()).await {
+                    Ok(Some(())) => continue,
+                    Ok(None) => return,
+                    Err(_) => break,
+                }
+            }
+            reload(&app, &app_config, &path).await;
+        }
+    });
+}
+
+/// Re-read the config file and apply it if it differs from the current
+/// config. Our own saves land here too and are skipped as unchanged.
+async fn reload(app: &AppHandle, app_config: &Mutex<AppConfig>, path: &Path) {
+    // Unlike at startup, a broken file keeps the current settings instead of
+    // falling back to the backup or defaults
+    let loaded = match config::read_config_file(path).and_then(|loaded| {
+        loaded.validate()?;
+        Ok(loaded)
+    }) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            warn!("[Config] Ignoring changed config file: {}", e);
+            return;
+        }
+    };
+
+    {
+        let mut current = app_config.lock().await;
+        if serde_json::to_value(&*current).ok() == serde_json::to_value(&loaded).ok() {
+            return;
+        }
+        *current = loaded.clone();
+    }
+
+    info!("[Config] Config file changed, applying it");
+    apply_config(app, &loaded).await;
+    let _ = app.emit("config-reloaded", ());
+    let _ = app.emit("theme-changed", loaded.appearance.theme.as_str());
+}
@@ -0,0 +1,177 @@
+//! Crash dumps for the service on Windows.
+//!
+//! An access violation or stack overflow ends the process without a panic,
+//! so nothing reaches the log. [`install_crash_handler`] registers an
+//! unhandled exception filter that writes a minidump (stacks, threads and
+//! loaded modules) to `%LOCALAPPDATA%\OmniRec\crashes\` before the process
+//! exits. Dumps left by earlier runs are reported at startup and listed by
+//! the `get_crash_reports` command.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// A minidump written when the service crashed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrashReport {
+    /// Full path of the dump
+    pub path: String,
+    /// File name of the dump
+    pub file_name: String,
+    /// Dump size in bytes
+    pub size_bytes: u64,
+    /// When the dump was written (RFC 3339)
+    pub created_at: String,
+}
+
+/// Directory crash dumps are written to.
+pub fn crash_dir() -> PathBuf {
+    directories::BaseDirs::new()
+        .map(|dirs| dirs.data_local_dir().join("OmniRec").join("crashes"))
+        .unwrap_or_else(|| std::env::temp_dir().join("omnirec-crashes"))
+}
+
+/// List the `.dmp` files in `dir`, newest first.
+pub fn list_crash_reports(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut reports: Vec<(std::time::SystemTime, CrashReport)> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "dmp"))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            let modified = metadata.modified().ok()?;
+            let report = CrashReport {
+                path: entry.path().to_string_lossy().to_string(),
+                file_name: entry.file_name().to_string_lossy().to_string(),
+                size_bytes: metadata.len(),
+                created_at: chrono::DateTime::<chrono::Local>::from(modified).to_rfc3339(),
+            };
+            Some((modified, report))
+        })
+        .collect();
+    reports.sort_by(|a, b| b.0.cmp(&a.0));
+    reports.into_iter().map(|(_, report)| report).collect()
+}
+
+/// Log crash dumps left by previous runs.
+#[cfg(target_os = "windows")]
+pub fn report_previous_crashes() {
+    let reports = list_crash_reports(&crash_dir());
+    if let Some(latest) = reports.first() {
+        tracing::warn!(
+            "[Crash] Found {} crash dump(s) from previous runs, latest: {}",
+            reports.len(),
+            latest.path
+        );
+    }
+}
+
+/// Register the unhandled exception filter that writes crash dumps.
+#[cfg(target_os = "windows")]
+pub fn install_crash_handler() {
+    use windows::Win32::System::Diagnostics::Debug::SetUnhandledExceptionFilter;
+    use windows::Win32::System::Threading::SetThreadStackGuarantee;
+
+    let dir = crash_dir();
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        tracing::warn!(
+            "[Crash] Crash dumps disabled, cannot create {}: {}",
+            dir.display(),
+            e
+        );
+        return;
+    }
+    let _ = CRASH_DIR.set(dir);
+
+    unsafe {
+        // Leave stack for the filter when the main thread overflows its stack
+        let mut guarantee = STACK_GUARANTEE;
+        let _ = SetThreadStackGuarantee(&mut guarantee);
+        SetUnhandledExceptionFilter(Some(write_crash_dump));
+    }
+    tracing::debug!("[Crash] Crash dump handler installed");
+}
+
+/// Crash dump directory, set before the filter is registered.
+#[cfg(target_os = "windows")]
+static CRASH_DIR: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+
+/// Bytes of stack reserved for handling a stack overflow.
+#[cfg(target_os = "windows")]
+const STACK_GUARANTEE: u32 = 64 * 1024;
+
+/// Let Windows continue with its default handling (ending the process).
+#[cfg(target_os = "windows")]
+const EXCEPTION_CONTINUE_SEARCH: i32 = 0;
+
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn write_crash_dump(
+    exception: *const windows::Win32::System::Diagnostics::Debug::EXCEPTION_POINTERS,
+) -> i32 {
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::{FALSE, HANDLE};
+    use windows::Win32::System::Diagnostics::Debug::{
+        MiniDumpNormal, MiniDumpWithThreadInfo, MiniDumpWithUnloadedModules, MiniDumpWriteDump,
+        MINIDUMP_EXCEPTION_INFORMATION,
+    };
+    use windows::Win32::System::Threading::{
+        GetCurrentProcess, GetCurrentProcessId, GetCurrentThreadId,
+    };
+
+    let Some(dir) = CRASH_DIR.get() else {
+        return EXCEPTION_CONTINUE_SEARCH;
+    };
+    let path = dir.join(format!(
+        "omnirec-{}-{}.dmp",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        GetCurrentProcessId()
+    ));
+    let Ok(file) = std::fs::File::create(&path) else {
+        return EXCEPTION_CONTINUE_SEARCH;
+    };
+
+    let exception_info = MINIDUMP_EXCEPTION_INFORMATION {
+        ThreadId: GetCurrentThreadId(),
+        ExceptionPointers: exception as *mut _,
+        ClientPointers: FALSE,
+    };
+    // Stacks and the module list are in every minidump; add thread times
+    // and modules unloaded before the crash
+    let _ = MiniDumpWriteDump(
+        GetCurrentProcess(),
+        GetCurrentProcessId(),
+        HANDLE(file.as_raw_handle()),
+        MiniDumpNormal | MiniDumpWithThreadInfo | MiniDumpWithUnloadedModules,
+        Some(&exception_info as *const _),
+        None,
+        None,
+    );
+    EXCEPTION_CONTINUE_SEARCH
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_only_dumps() {
+        let dir = std::env::temp_dir().join(format!("omnirec-crashes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("omnirec-20260101-000000-1.dmp"), b"MDMP").unwrap();
+        std::fs::write(dir.join("notes.txt"), b"not a dump").unwrap();
+
+        let reports = list_crash_reports(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].file_name, "omnirec-20260101-000000-1.dmp");
+        assert_eq!(reports[0].size_bytes, 4);
+    }
+
+    #[test]
+    fn test_missing_directory_has_no_reports() {
+        assert!(list_crash_reports(Path::new("/nonexistent/omnirec-crashes")).is_empty());
+    }
+}
@@ -0,0 +1,514 @@
+//! `omnirec://` deep links for controlling recordings from other apps.
+//!
+//! Supported links:
+//! - `omnirec://record?target=display&id=DP-1&format=mp4&duration=60`
+//! - `omnirec://record?target=window&id=<handle>`
+//! - `omnirec://record?target=region&id=<display>&x=0&y=0&width=800&height=600`
+//! - `omnirec://record?target=portal`
+//! - `omnirec://record?target=workspace&id=<workspace>`
+//! - `omnirec://stop`
+//! - `omnirec://status`
+//!
+//! `format` and `duration` are optional for every `record` target; the format
+//! applies to that recording only. A `record` link starts recording only
+//! after the user confirms it in a dialog. Links are dispatched to the same
+//! handlers as IPC requests; invalid links and failures are reported with a
+//! desktop notification.
+
+use crate::ipc::handlers::handle_request;
+use crate::state::get_recording_manager;
+use omnirec_types::ipc::{Request, Response};
+use omnirec_types::{OutputFormat, RecordingState};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_notification::NotificationExt;
+use tracing::{info, warn};
+use url::Url;
+
+/// URL scheme registered for deep links.
+pub const SCHEME: &str = "omnirec";
+
+/// How long a link received at launch waits for the service to be ready.
+const STARTUP_WAIT: Duration = Duration::from_secs(30);
+
+/// What a `record` link captures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkTarget {
+    Window {
+        handle: isize,
+    },
+    Display {
+        id: String,
+    },
+    Region {
+        display: String,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+    Portal,
+    Workspace {
+        id: i32,
+    },
+}
+
+impl LinkTarget {
+    /// What is recorded, for the confirmation dialog.
+    fn describe(&self) -> String {
+        match self {
+            LinkTarget::Window { handle } => format!("window {}", handle),
+            LinkTarget::Display { id } => format!("display {}", id),
+            LinkTarget::Region {
+                display,
+                width,
+                height,
+                ..
+            } => format!("a {}x{} region of display {}", width, height, display),
+            LinkTarget::Portal => "a screen chosen in the screen sharing dialog".to_string(),
+            LinkTarget::Workspace { id } => format!("workspace {}", id),
+        }
+    }
+}
+
+/// Options of a `record` link; the same as the CLI's `record` options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordOptions {
+    /// Output format of this recording; chosen as usual when absent
+    pub format: Option<OutputFormat>,
+    /// Stop automatically after this many seconds
+    pub duration: Option<u64>,
+}
+
+/// A parsed deep link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    Record {
+        target: LinkTarget,
+        options: RecordOptions,
+    },
+    Stop,
+    Status,
+}
+
+/// Query parameters of a link, each allowed once.
+struct Params(HashMap<String, String>);
+
+impl Params {
+    fn from_url(url: &Url) -> Result<Self, String> {
+        let mut params = HashMap::new();
+        for (key, value) in url.query_pairs() {
+            if params.insert(key.to_string(), value.to_string()).is_some() {
+                return Err(format!("Parameter '{}' given more than once", key));
+            }
+        }
+        Ok(Self(params))
+    }
+
+    fn take(&mut self, key: &str) -> Option<String> {
+        self.0.remove(key)
+    }
+
+    fn require(&mut self, key: &str) -> Result<String, String> {
+        self.take(key)
+            .ok_or_else(|| format!("Missing parameter '{}'", key))
+    }
+
+    fn parse<T: std::str::FromStr>(&mut self, key: &str) -> Result<Option<T>, String> {
+        self.take(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid value '{}' for '{}'", value, key))
+            })
+            .transpose()
+    }
+
+    fn parse_required<T: std::str::FromStr>(&mut self, key: &str) -> Result<T, String> {
+        self.parse(key)?
+            .ok_or_else(|| format!("Missing parameter '{}'", key))
+    }
+
+    /// Fail if any parameter was not consumed.
+    fn finish(self) -> Result<(), String> {
+        let mut unknown: Vec<_> = self.0.into_keys().collect();
+        unknown.sort();
+        match unknown.first() {
+            Some(key) => Err(format!("Unknown parameter '{}'", key)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl DeepLink {
+    /// Parse and validate an `omnirec://` URL.
+    pub fn parse(url: &Url) -> Result<Self, String> {
+        if url.scheme() != SCHEME {
+            return Err(format!("Unsupported scheme '{}'", url.scheme()));
+        }
+        let mut params = Params::from_url(url)?;
+        let link = match url.host_str().unwrap_or_default() {
+            "record" => {
+                let target = match params.require("target")?.as_str() {
+                    "window" => LinkTarget::Window {
+                        handle: params.parse_required("id")?,
+                    },
+                    "display" => LinkTarget::Display {
+                        id: params.require("id")?,
+                    },
+                    "region" => {
+                        let display = params.require("id")?;
+                        let x = params.parse_required("x")?;
+                        let y = params.parse_required("y")?;
+                        let width = params.parse_required("width")?;
+                        let height = params.parse_required("height")?;
+                        if width == 0 || height == 0 {
+                            return Err("Region width and height must be positive".to_string());
+                        }
+                        LinkTarget::Region {
+                            display,
+                            x,
+                            y,
+                            width,
+                            height,
+                        }
+                    }
+                    "portal" => LinkTarget::Portal,
+                    "workspace" => LinkTarget::Workspace {
+                        id: params.parse_required("id")?,
+                    },
+                    other => return Err(format!("Unknown target '{}'", other)),
+                };
+                let format = params
+                    .take("format")
+                    .map(|format| {
+                        OutputFormat::parse(&format)
+                            .ok_or_else(|| format!("Unknown output format '{}'", format))
+                    })
+                    .transpose()?;
+                let duration = params.parse::<u64>("duration")?;
+                if duration == Some(0) {
+                    return Err("Duration must be positive".to_string());
+                }
+                DeepLink::Record {
+                    target,
+                    options: RecordOptions { format, duration },
+                }
+            }
+            "stop" => DeepLink::Stop,
+            "status" => DeepLink::Status,
+            other => return Err(format!("Unknown action '{}'", other)),
+        };
+        params.finish()?;
+        Ok(link)
+    }
+}
+
+/// Message of an error response.
+fn error_message(response: Response) -> String {
+    match response {
+        Response::Error { message } => message,
+        other => format!("Unexpected response: {:?}", other),
+    }
+}
+
+/// The IPC request that starts capturing `target`.
+async fn start_request(target: LinkTarget) -> Result<Request, String> {
+    Ok(match target {
+        LinkTarget::Window { handle } => Request::StartWindowCapture {
+            window_handle: handle,
+        },
+        LinkTarget::Display { id } => {
+            let monitors = match handle_request(Request::ListMonitors).await {
+                Response::Monitors { monitors } => monitors,
+                other => return Err(error_message(other)),
+            };
+            let monitor = monitors
+                .into_iter()
+                .find(|m| m.id == id)
+                .ok_or_else(|| format!("Display '{}' not found", id))?;
+            Request::StartDisplayCapture {
+                monitor_id: monitor.id,
+                width: monitor.width,
+                height: monitor.height,
+            }
+        }
+        LinkTarget::Region {
+            display,
+            x,
+            y,
+            width,
+            height,
+        } => Request::StartRegionCapture {
+            monitor_id: display,
+            x,
+            y,
+            width,
+            height,
+        },
+        LinkTarget::Portal => Request::StartPortalCapture,
+        LinkTarget::Workspace { id } => Request::StartWorkspaceCapture { workspace_id: id },
+    })
+}
+
+/// Ask the user whether to start the recording a link asked for.
+async fn confirm_recording<R: Runtime>(
+    app: &AppHandle<R>,
+    target: &LinkTarget,
+    options: &RecordOptions,
+) -> bool {
+    let mut message = format!("Another app asked OmniRec to record {}", target.describe());
+    if let Some(seconds) = options.duration {
+        message.push_str(&format!(" for {} seconds", seconds));
+    }
+    message.push_str(". Start recording?");
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(message)
+        .title("OmniRec")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Record".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+    rx.await.unwrap_or(false)
+}
+
+/// Whether recording number `started` is still going, given the current
+/// recording number and state. A later recording has a different number.
+fn still_recording(started: u64, current: u64, state: RecordingState) -> bool {
+    started == current && matches!(state, RecordingState::Recording | RecordingState::Suspended)
+}
+
+/// Carry out `link`, returning a message to show the user, if any.
+async fn run<R: Runtime>(app: &AppHandle<R>, link: DeepLink) -> Result<Option<String>, String> {
+    match link {
+        DeepLink::Record { target, options } => {
+            if !confirm_recording(app, &target, &options).await {
+                info!("[DeepLink] Recording declined");
+                return Ok(None);
+            }
+
+            let request = start_request(target).await?;
+            request
+                .validate()
+                .map_err(|e| format!("Invalid link: {}", e))?;
+
+            // Set right before the start, which takes it for this recording
+            if let Some(format) = options.format {
+                let response = handle_request(Request::SetOutputFormat {
+                    format: format.extension().to_string(),
+                })
+                .await;
+                if response.is_error() {
+                    return Err(error_message(response));
+                }
+            }
+            match handle_request(request).await {
+                Response::RecordingStarted => {}
+                other => return Err(error_message(other)),
+            }
+
+            if let Some(seconds) = options.duration {
+                let number = get_recording_manager().recording_number();
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_secs(seconds)).await;
+                    let manager = get_recording_manager();
+                    if !still_recording(
+                        number,
+                        manager.recording_number(),
+                        manager.get_state().await,
+                    ) {
+                        info!(
+                            "[DeepLink] Recording already stopped, not stopping after {}s",
+                            seconds
+                        );
+                        return;
+                    }
+                    let response = handle_request(Request::StopRecording).await;
+                    if response.is_error() {
+                        warn!(
+                            "[DeepLink] Failed to stop recording after {}s: {}",
+                            seconds,
+                            error_message(response)
+                        );
+                    }
+                });
+            }
+            Ok(None)
+        }
+        DeepLink::Stop => match handle_request(Request::StopRecording).await {
+            Response::RecordingStopped { file_path, .. } => {
+                Ok(Some(format!("Recording saved: {}", file_path)))
+            }
+            other => Err(error_message(other)),
+        },
+        DeepLink::Status => {
+            let state = match handle_request(Request::GetRecordingState).await {
+                Response::RecordingState { state } => state,
+                other => return Err(error_message(other)),
+            };
+            let elapsed = match handle_request(Request::GetElapsedTime).await {
+                Response::ElapsedTime { seconds } => seconds,
+                _ => 0,
+            };
+            Ok(Some(format!(
+                "{:?} ({}:{:02})",
+                state,
+                elapsed / 60,
+                elapsed % 60
+            )))
+        }
+    }
+}
+
+fn notify<R: Runtime>(app: &AppHandle<R>, body: &str) {
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("OmniRec")
+        .body(body)
+        .show()
+    {
+        warn!("[DeepLink] Failed to show notification: {}", e);
+    }
+}
+
+/// Handle deep links once the service is ready.
+pub async fn handle_urls<R: Runtime>(app: AppHandle<R>, urls: Vec<Url>, ready: Arc<AtomicBool>) {
+    let deadline = tokio::time::Instant::now() + STARTUP_WAIT;
+    while !ready.load(Ordering::SeqCst) {
+        if tokio::time::Instant::now() >= deadline {
+            notify(&app, "OmniRec is not ready; the link was ignored.");
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+
+    for url in urls {
+        info!("[DeepLink] Opening {}", url);
+        let result = match DeepLink::parse(&url) {
+            Ok(link) => run(&app, link).await,
+            Err(e) => Err(format!("Invalid link: {}", e)),
+        };
+        match result {
+            Ok(Some(message)) => notify(&app, &message),
+            Ok(None) => {}
+            Err(e) => {
+                warn!("[DeepLink] {}: {}", url, e);
+                notify(&app, &e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> Result<DeepLink, String> {
+        DeepLink::parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_duration_stops_only_the_linked_recording() {
+        assert!(still_recording(3, 3, RecordingState::Recording));
+        assert!(still_recording(3, 3, RecordingState::Suspended));
+        // Stopped by the user
+        assert!(!still_recording(3, 3, RecordingState::Idle));
+        assert!(!still_recording(3, 3, RecordingState::Saving));
+        // Stopped, then another recording started
+        assert!(!still_recording(3, 4, RecordingState::Recording));
+    }
+
+    #[test]
+    fn test_parse_record_display() {
+        assert_eq!(
+            parse("omnirec://record?target=display&id=DP-1&format=mp4&duration=60"),
+            Ok(DeepLink::Record {
+                target: LinkTarget::Display {
+                    id: "DP-1".to_string()
+                },
+                options: RecordOptions {
+                    format: Some(OutputFormat::Mp4),
+                    duration: Some(60),
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_record_targets() {
+        assert_eq!(
+            parse("omnirec://record?target=window&id=-42"),
+            Ok(DeepLink::Record {
+                target: LinkTarget::Window { handle: -42 },
+                options: RecordOptions {
+                    format: None,
+                    duration: None,
+                },
+            })
+        );
+        assert!(matches!(
+            parse(
+                "omnirec://record?target=region&id=DP-1&x=10&y=20&width=800&height=600&format=webm"
+            ),
+            Ok(DeepLink::Record {
+                target: LinkTarget::Region {
+                    width: 800,
+                    height: 600,
+                    ..
+                },
+                options: RecordOptions {
+                    format: Some(OutputFormat::WebM),
+                    ..
+                },
+            })
+        ));
+        assert!(matches!(
+            parse("omnirec://record?target=portal"),
+            Ok(DeepLink::Record {
+                target: LinkTarget::Portal,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse("omnirec://record?target=workspace&id=3"),
+            Ok(DeepLink::Record {
+                target: LinkTarget::Workspace { id: 3 },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_stop_and_status() {
+        assert_eq!(parse("omnirec://stop"), Ok(DeepLink::Stop));
+        assert_eq!(parse("omnirec://status"), Ok(DeepLink::Status));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_links() {
+        assert!(parse("omnirec://delete").is_err());
+        assert!(parse("https://record?target=portal").is_err());
+        assert!(parse("omnirec://record").is_err());
+        assert!(parse("omnirec://record?target=tab").is_err());
+        assert!(parse("omnirec://record?target=display").is_err());
+        assert!(parse("omnirec://record?target=window&id=abc").is_err());
+        assert!(parse("omnirec://record?target=portal&format=avi").is_err());
+        assert!(parse("omnirec://record?target=portal&duration=0").is_err());
+        assert!(parse("omnirec://record?target=portal&duration=-5").is_err());
+        assert!(parse("omnirec://record?target=portal&output=/etc/passwd").is_err());
+        assert!(parse("omnirec://record?target=portal&target=window").is_err());
+        assert!(parse("omnirec://record?target=region&id=DP-1&x=0&y=0&width=0&height=10").is_err());
+        assert!(parse("omnirec://stop?now=1").is_err());
+    }
+}
@@ -0,0 +1,254 @@
+//! Dry-run capture for `omnirec record --dry-run`.
+//!
+//! Exercises the same path as a real recording — capture backend, FFmpeg
+//! encoder, audio source lookup — without producing an output file, so users
+//! can verify their setup before recording.
+
+use crate::capture::{self, CaptureRegion, FrameReceiver, StopHandle};
+use crate::encoder::VideoEncoder;
+use crate::state::get_recording_manager;
+use omnirec_types::ipc::Request;
+use omnirec_types::{AudioConfig, AudioSource, RecordingState, APP_AUDIO_SOURCE_PREFIX};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Number of frames captured and encoded during a dry run.
+pub const DRY_RUN_FRAME_COUNT: u32 = 10;
+
+/// Maximum time to wait for all dry-run frames to arrive.
+const FRAME_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Result of a dry run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DryRunSummary {
+    /// Frames received from the capture backend
+    pub frames_captured: u32,
+    /// Whether FFmpeg accepted and encoded the frames
+    pub encoder_ok: bool,
+    /// Whether the configured audio sources are present
+    pub audio_ok: bool,
+}
+
+/// A dry run whose capture has started.
+pub struct DryRun {
+    frame_rx: FrameReceiver,
+    stop_flag: StopHandle,
+}
+
+/// Start a dry-run capture of the target described by a `Start*Capture`
+/// request. [`DryRun::finish`] then waits for its frames.
+pub async fn start(capture_request: Request) -> Result<DryRun, String> {
+    if get_recording_manager().get_state().await != RecordingState::Idle {
+        return Err("Already recording or saving".to_string());
+    }
+
+    let (frame_rx, stop_flag) = start_capture(capture_request)?;
+    Ok(DryRun {
+        frame_rx,
+        stop_flag,
+    })
+}
+
+impl DryRun {
+    /// Capture and encode the dry-run frames, then check the audio sources.
+    pub async fn finish(self) -> Result<DryRunSummary, String> {
+        let Self {
+            frame_rx,
+            stop_flag,
+        } = self;
+        let (frames_captured, encoder_ok) =
+            tokio::task::spawn_blocking(move || capture_and_encode(frame_rx, stop_flag))
+                .await
+                .map_err(|e| format!("Dry run task failed: {}", e))?;
+
+        let audio_config = get_recording_manager().get_audio_config().await;
+        let audio_ok = tokio::task::spawn_blocking(move || {
+            audio_sources_present(&audio_config, &capture::list_audio_sources())
+        })
+        .await
+        .map_err(|e| format!("Dry run task failed: {}", e))?;
+
+        let summary = DryRunSummary {
+            frames_captured,
+            encoder_ok,
+            audio_ok,
+        };
+        info!("[DryRun] {:?}", summary);
+        Ok(summary)
+    }
+}
+
+fn start_capture(request: Request) -> Result<(FrameReceiver, StopHandle), String> {
+    let backend = capture::get_capture_backend().map_err(String::from)?;
+    let started = match request {
+        Request::StartWindowCapture { window_handle } => {
+            backend.start_window_capture(window_handle)
+        }
+        Request::StartDisplayCapture {
+            monitor_id,
+            width,
+            height,
+        } => backend.start_display_capture(monitor_id, width, height),
+        Request::StartRegionCapture {
+            monitor_id,
+            x,
+            y,
+            width,
+            height,
+        } => backend.start_region_capture(CaptureRegion {
+            monitor_id,
+            x,
+            y,
+            width,
+            height,
+            aspect_ratio: None,
+        }),
+        #[cfg(target_os = "linux")]
+        Request::StartPortalCapture => capture::get_backend().start_portal_capture(),
+        #[cfg(not(target_os = "linux"))]
+        Request::StartPortalCapture => {
+            return Err("Portal capture is only available on Linux".to_string())
+        }
+        #[cfg(target_os = "linux")]
+        Request::StartWorkspaceCapture { workspace_id } => capture::get_backend()
+            .start_workspace_capture(workspace_id)
+            .map(|(frame_rx, stop_flag, _)| (frame_rx, stop_flag)),
+        #[cfg(not(target_os = "linux"))]
+        Request::StartWorkspaceCapture { .. } => {
+            return Err("Workspace capture is only available on Hyprland".to_string())
+        }
+        _ => return Err("Dry run requires a capture request".to_string()),
+    };
+    started.map_err(String::from)
+}
+
+/// Receive up to [`DRY_RUN_FRAME_COUNT`] frames and feed them to a discarding
+/// encoder. Returns the number of frames captured and whether encoding succeeded.
+fn capture_and_encode(mut frame_rx: FrameReceiver, stop_flag: StopHandle) -> (u32, bool) {
+    let deadline = Instant::now() + FRAME_TIMEOUT;
+    let mut frames_captured = 0;
+    let mut encoder: Option<VideoEncoder> = None;
+    let mut encoder_error: Option<String> = None;
+
+    while frames_captured < DRY_RUN_FRAME_COUNT && Instant::now() < deadline {
+        let frame = match frame_rx.try_recv() {
+            Ok(frame) => frame,
+            Err(tokio::sync::mpsc::error::TryRecvError::Empty) => {
+                std::thread::sleep(Duration::from_millis(5));
+                continue;
+            }
+            Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => break,
+        };
+        frames_captured += 1;
+
+        if encoder_error.is_some() {
+            continue;
+        }
+        if encoder.is_none() {
+            match VideoEncoder::new_discarding(frame.width, frame.height, frame.pixel_format)
+                .and_then(|mut enc| {
+                    enc.start(None)?;
+                    Ok(enc)
+                }) {
+                Ok(enc) => encoder = Some(enc),
+                Err(e) => {
+                    encoder_error = Some(e);
+                    continue;
+                }
+            }
+        }
+        if let Some(enc) = encoder.as_mut() {
+            if let Err(e) = enc.write_frame(&frame) {
+                encoder_error = Some(e);
+            }
+        }
+    }
+
+    stop_flag.store(true, Ordering::SeqCst);
+
+    if frames_captured < DRY_RUN_FRAME_COUNT {
+        warn!(
+            "[DryRun] Only received {} of {} frames",
+            frames_captured, DRY_RUN_FRAME_COUNT
+        );
+    }
+
+    let finished = encoder.map(VideoEncoder::finish);
+    let encoder_ok = match (encoder_error, finished) {
+        (None, Some(Ok(_))) => true,
+        (Some(e), _) | (None, Some(Err(e))) => {
+            warn!("[DryRun] Encoder check failed: {}", e);
+            false
+        }
+        (None, None) => false,
+    };
+
+    (frames_captured, encoder_ok)
+}
+
+/// Whether every configured audio source is among the available sources.
+///
+/// Disabled audio trivially passes. The macOS `"system"` placeholder is
+/// resolved by the audio engine at record time and is not checked here, nor
+/// are per-application sources, whose sink only exists while recording.
+fn audio_sources_present(config: &AudioConfig, available: &[AudioSource]) -> bool {
+    if !config.enabled {
+        return true;
+    }
+    [&config.source_id, &config.microphone_id]
+        .into_iter()
+        .flatten()
+        .filter(|id| id.as_str() != "system" && !id.starts_with(APP_AUDIO_SOURCE_PREFIX))
+        .all(|id| available.iter().any(|source| &source.id == id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use omnirec_types::AudioSourceType;
+
+    fn source(id: &str) -> AudioSource {
+        AudioSource {
+            id: id.to_string(),
+            name: id.to_string(),
+            source_type: AudioSourceType::Input,
+        }
+    }
+
+    fn config(enabled: bool, source_id: Option<&str>, microphone_id: Option<&str>) -> AudioConfig {
+        AudioConfig {
+            enabled,
+            source_id: source_id.map(String::from),
+            microphone_id: microphone_id.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_audio_sources_present() {
+        let available = [source("mic-1"), source("speakers")];
+
+        assert!(audio_sources_present(
+            &config(false, Some("gone"), None),
+            &available
+        ));
+        assert!(audio_sources_present(&config(true, None, None), &available));
+        assert!(audio_sources_present(
+            &config(true, Some("speakers"), Some("mic-1")),
+            &available
+        ));
+        assert!(audio_sources_present(
+            &config(true, Some("system"), None),
+            &available
+        ));
+        assert!(audio_sources_present(
+            &config(true, Some("app:Firefox"), Some("mic-1")),
+            &available
+        ));
+        assert!(!audio_sources_present(
+            &config(true, Some("speakers"), Some("mic-2")),
+            &available
+        ));
+    }
+}
@@ -4,6 +4,9 @@
 //! a single FFmpeg process, audio is written to a named pipe that FFmpeg reads
 //! as a second input (`-f s16le -ar 48000 -ac 1 -i <pipe_path>`).
 
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::Receiver;
+
 /// A writable named pipe for streaming audio data to FFmpeg.
 pub struct AudioPipe {
     /// The pipe path that should be passed to FFmpeg as an input.
@@ -21,22 +24,28 @@ unsafe impl Send for AudioPipe {}
 impl AudioPipe {
     /// Create a new named pipe and return the `AudioPipe`.
     ///
-    /// On Windows, creates a `\\.\pipe\omnirec_audio_<pid>` pipe.
-    /// On Unix, creates a FIFO at `/tmp/omnirec_audio_<pid>`.
+    /// On Windows, creates a `\\.\pipe\omnirec_audio_<pid>_<n>` pipe.
+    /// On Unix, creates a FIFO at `/tmp/omnirec_audio_<pid>_<n>`. `n` counts
+    /// the pipes created, as each segment of a recording has its own.
     ///
     /// The pipe is created but not yet opened for writing. Call [`open`] after
     /// FFmpeg has been spawned (FFmpeg blocks on the pipe until a writer connects).
     pub fn create() -> Result<Self, String> {
-        let pid = std::process::id();
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        let name = format!(
+            "omnirec_audio_{}_{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
 
         #[cfg(windows)]
         {
-            Self::create_windows(pid)
+            Self::create_windows(&name)
         }
 
         #[cfg(unix)]
         {
-            Self::create_unix(pid)
+            Self::create_unix(&name)
         }
     }
 
@@ -86,6 +95,111 @@ impl AudioPipe {
     }
 }
 
+/// The audio pipes of a recording split into segments, each read by its own
+/// FFmpeg process.
+///
+/// Audio goes to the current segment's pipe until the first sample of the
+/// next segment, then to the next segment's pipe.
+pub struct SegmentedPipe {
+    pipe: AudioPipe,
+    /// Pipes of later segments, with the video frame each starts at
+    next: Receiver<(AudioPipe, u64)>,
+    pending: Option<(AudioPipe, u64)>,
+    /// Samples written across all segments
+    written: u64,
+    sample_rate: u64,
+    fps: u64,
+}
+
+impl SegmentedPipe {
+    /// Write to the open `pipe`, then to the pipes received on `next`, for
+    /// video at `fps`.
+    pub fn new(pipe: AudioPipe, next: Receiver<(AudioPipe, u64)>, fps: u64) -> Self {
+        Self {
+            pipe,
+            next,
+            pending: None,
+            written: 0,
+            sample_rate: 48000,
+            fps,
+        }
+    }
+
+    /// Set the sample rate, which places segment starts in the audio.
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate as u64;
+    }
+
+    /// Write f32 mono samples as s16le.
+    pub fn write_samples(&mut self, samples: &[f32]) -> Result<(), String> {
+        self.write_split(samples.len(), |pipe, range| {
+            pipe.write_all(&f32_mono_to_s16le(&samples[range]))
+        })
+    }
+
+    /// Write `num_samples` samples of silence.
+    pub fn write_silence(&mut self, num_samples: usize) -> Result<(), String> {
+        self.write_split(num_samples, |pipe, range| pipe.write_silence(range.len()))
+    }
+
+    /// Open the pipes of segments not reached, so their FFmpeg processes
+    /// see the end of the audio instead of waiting for it.
+    pub fn close(mut self) {
+        let pending = self.pending.take().into_iter().chain(self.next.try_iter());
+        for (mut pipe, _) in pending {
+            let _ = pipe.open();
+        }
+    }
+
+    /// Write `len` samples with `write`, split where segments start.
+    fn write_split(
+        &mut self,
+        len: usize,
+        mut write: impl FnMut(&mut AudioPipe, std::ops::Range<usize>) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let mut done = 0;
+        while done < len {
+            self.switch_due()?;
+            let end = match self.next_start() {
+                Some(start) => (done + (start - self.written) as usize).min(len),
+                None => len,
+            };
+            write(&mut self.pipe, done..end)?;
+            self.written += (end - done) as u64;
+            done = end;
+        }
+        self.switch_due()
+    }
+
+    /// First sample of the next segment, once FFmpeg has started it.
+    fn next_start(&self) -> Option<u64> {
+        self.pending
+            .as_ref()
+            .map(|(_, frame)| frame * self.sample_rate / self.fps)
+    }
+
+    /// Move to the next segment's pipe once its first sample is reached.
+    fn switch_due(&mut self) -> Result<(), String> {
+        loop {
+            if self.pending.is_none() {
+                self.pending = self.next.try_recv().ok();
+            }
+            match self.next_start() {
+                Some(start) if start <= self.written => {
+                    let (mut pipe, _) = self.pending.take().expect("next_start is Some");
+                    pipe.open()?;
+                    // Audio written past the end of the previous segment is
+                    // cut off when the segments are joined; pad this one by
+                    // as much to keep it in sync
+                    pipe.write_silence((self.written - start) as usize)?;
+                    self.pipe = pipe;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+}
+
 /// Convert f32 mono samples to s16le (signed 16-bit little-endian) bytes.
 pub fn f32_mono_to_s16le(samples: &[f32]) -> Vec<u8> {
     let mut out = Vec::with_capacity(samples.len() * 2);
@@ -101,13 +215,13 @@ pub fn f32_mono_to_s16le(samples: &[f32]) -> Vec<u8> {
 
 #[cfg(windows)]
 impl AudioPipe {
-    fn create_windows(pid: u32) -> Result<Self, String> {
+    fn create_windows(name: &str) -> Result<Self, String> {
         use windows::core::HSTRING;
         use windows::Win32::Foundation::INVALID_HANDLE_VALUE;
         use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
         use windows::Win32::System::Pipes::{CreateNamedPipeW, PIPE_TYPE_BYTE, PIPE_WAIT};
 
-        let path = format!(r"\\.\pipe\omnirec_audio_{}", pid);
+        let path = format!(r"\\.\pipe\{}", name);
         let h_path = HSTRING::from(&path);
 
         // PIPE_ACCESS_OUTBOUND = 0x00000002
@@ -184,8 +298,8 @@ impl Drop for AudioPipe {
 
 #[cfg(unix)]
 impl AudioPipe {
-    fn create_unix(pid: u32) -> Result<Self, String> {
-        let path = format!("/tmp/omnirec_audio_{}", pid);
+    fn create_unix(name: &str) -> Result<Self, String> {
+        let path = format!("/tmp/{}", name);
 
         // Remove stale FIFO if it exists
         let _ = std::fs::remove_file(&path);
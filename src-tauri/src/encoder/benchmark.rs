@@ -0,0 +1,270 @@
+//! Encoder benchmark for estimating recording performance.
+//!
+//! Feeds synthetic BGRA frames through a discarding [`VideoEncoder`] as fast
+//! as FFmpeg accepts them and reports the sustained frame rate, so users can
+//! check whether their machine keeps up before starting a long recording.
+
+use super::{EncoderThreads, VideoEncoder};
+use crate::capture::types::{self, CapturedFrame, PixelFormat};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Fraction of the target frame rate the encoder must reach to pass.
+pub const BENCHMARK_PASS_RATIO: f32 = 0.95;
+
+/// Largest accepted benchmark frame size (8K UHD).
+pub const BENCHMARK_MAX_WIDTH: u32 = 7680;
+pub const BENCHMARK_MAX_HEIGHT: u32 = 4320;
+
+/// Longest accepted benchmark run, in seconds.
+pub const BENCHMARK_MAX_DURATION_SECS: u8 = 30;
+
+/// Distinct synthetic frames cycled through during a run.
+const SYNTHETIC_FRAME_COUNT: usize = 4;
+
+/// Outcome of an encoder benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    /// Frames per second the encoder sustained
+    pub achieved_fps: f32,
+    /// FFmpeg CPU usage as a share of all cores (0–100), or 0 if unavailable
+    pub cpu_percent: f32,
+    /// Whether `achieved_fps` reaches [`BENCHMARK_PASS_RATIO`] of the target
+    pub ok: bool,
+}
+
+/// Encode synthetic `width`x`height` frames for `duration_secs` on `threads`
+/// and measure the throughput against a target of `fps`.
+///
+/// Blocks for the duration of the run; call from a blocking task.
+pub fn run_benchmark(
+    width: u32,
+    height: u32,
+    fps: u32,
+    duration_secs: u8,
+    threads: EncoderThreads,
+) -> Result<BenchmarkResult, String> {
+    if width > BENCHMARK_MAX_WIDTH || height > BENCHMARK_MAX_HEIGHT {
+        return Err(format!(
+            "Benchmark size {}x{} exceeds {}x{}",
+            width, height, BENCHMARK_MAX_WIDTH, BENCHMARK_MAX_HEIGHT
+        ));
+    }
+    if fps == 0 {
+        return Err("Benchmark frame rate must be at least 1".to_string());
+    }
+    if duration_secs == 0 || duration_secs > BENCHMARK_MAX_DURATION_SECS {
+        return Err(format!(
+            "Benchmark duration must be between 1 and {} seconds",
+            BENCHMARK_MAX_DURATION_SECS
+        ));
+    }
+
+    let mut encoder = VideoEncoder::new_discarding(width, height, PixelFormat::Bgra)?;
+    encoder.set_threads(threads);
+    encoder.start(None)?;
+
+    let frames: Vec<CapturedFrame> = (0..SYNTHETIC_FRAME_COUNT)
+        .map(|index| synthetic_frame(encoder.width, encoder.height, index))
+        .collect();
+
+    let cpu_timer = ChildCpuTimer::start(encoder.process_id());
+    let duration = Duration::from_secs(duration_secs as u64);
+    let started = Instant::now();
+    let mut frames_written: u32 = 0;
+
+    while started.elapsed() < duration {
+        let frame = &frames[frames_written as usize % frames.len()];
+        encoder.write_frame(frame)?;
+        frames_written += 1;
+    }
+
+    // Frames still queued in FFmpeg count towards the elapsed time
+    encoder.finish()?;
+    let elapsed = started.elapsed();
+    let cpu_time = cpu_timer.finish();
+
+    let achieved_fps = frames_written as f32 / elapsed.as_secs_f32();
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let result = BenchmarkResult {
+        achieved_fps,
+        cpu_percent: cpu_time.map_or(0.0, |cpu| cpu_percent(cpu, elapsed, cores)),
+        ok: meets_target(achieved_fps, fps),
+    };
+
+    tracing::info!(
+        "[Benchmark] {}x{} target {} fps, threads {:?}: {:?}",
+        width,
+        height,
+        fps,
+        threads,
+        result
+    );
+    Ok(result)
+}
+
+/// Whether `achieved_fps` is within [`BENCHMARK_PASS_RATIO`] of `target_fps`.
+fn meets_target(achieved_fps: f32, target_fps: u32) -> bool {
+    achieved_fps >= target_fps as f32 * BENCHMARK_PASS_RATIO
+}
+
+/// CPU time as a percentage of the wall-clock capacity of all cores.
+fn cpu_percent(cpu_time: Duration, elapsed: Duration, cores: usize) -> f32 {
+    let capacity = elapsed.as_secs_f32() * cores.max(1) as f32;
+    if capacity <= 0.0 {
+        return 0.0;
+    }
+    (cpu_time.as_secs_f32() / capacity * 100.0).min(100.0)
+}
+
+/// A BGRA frame with a diagonal gradient and a moving block, so consecutive
+/// frames differ the way screen content with motion does.
+fn synthetic_frame(width: u32, height: u32, index: usize) -> CapturedFrame {
+    let shift = index as u32 * 16;
+    let block = (width / 8).max(1);
+    let block_x = (index as u32 * block) % width.max(1);
+
+    let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let in_block = x >= block_x && x < block_x + block && y < block;
+            let (b, g, r) = if in_block {
+                (255, 255, 255)
+            } else {
+                (
+                    ((x + shift) & 0xFF) as u8,
+                    ((y + shift) & 0xFF) as u8,
+                    ((x + y) & 0xFF) as u8,
+                )
+            };
+            data.extend_from_slice(&[b, g, r, 255]);
+        }
+    }
+
+    CapturedFrame {
+        width,
+        height,
+        data: data.into(),
+        pixel_format: PixelFormat::Bgra,
+        captured_at: SystemTime::now(),
+        timestamp_us: types::timestamp_us(Instant::now()),
+    }
+}
+
+/// Measures the CPU time used by the FFmpeg child process.
+///
+/// On Unix this is the growth of the reaped-children resource usage across
+/// the run, which is why [`finish`](Self::finish) must be called after the
+/// encoder has been waited on. On Windows the process handle is kept open so
+/// its times can be read after it exits.
+struct ChildCpuTimer {
+    #[cfg(unix)]
+    before: Option<Duration>,
+    #[cfg(windows)]
+    process: Option<windows::Win32::Foundation::HANDLE>,
+}
+
+impl ChildCpuTimer {
+    #[cfg_attr(not(windows), allow(unused_variables))]
+    fn start(pid: Option<u32>) -> Self {
+        #[cfg(unix)]
+        {
+            Self {
+                before: reaped_children_cpu_time(),
+            }
+        }
+        #[cfg(windows)]
+        {
+            use windows::Win32::System::Threading::{
+                OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+            };
+            let process = pid.and_then(|pid| unsafe {
+                OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()
+            });
+            Self { process }
+        }
+    }
+
+    fn finish(self) -> Option<Duration> {
+        #[cfg(unix)]
+        {
+            let before = self.before?;
+            reaped_children_cpu_time().map(|after| after.saturating_sub(before))
+        }
+        #[cfg(windows)]
+        {
+            use windows::Win32::Foundation::{CloseHandle, FILETIME};
+            use windows::Win32::System::Threading::GetProcessTimes;
+
+            let process = self.process?;
+            let mut creation = FILETIME::default();
+            let mut exit = FILETIME::default();
+            let mut kernel = FILETIME::default();
+            let mut user = FILETIME::default();
+            let times = unsafe {
+                GetProcessTimes(process, &mut creation, &mut exit, &mut kernel, &mut user)
+            };
+            unsafe {
+                let _ = CloseHandle(process);
+            }
+            times.ok()?;
+
+            let ticks = |t: FILETIME| ((t.dwHighDateTime as u64) << 32) | t.dwLowDateTime as u64;
+            // FILETIME counts 100 ns intervals
+            Some(Duration::from_nanos(
+                (ticks(kernel) + ticks(user)).saturating_mul(100),
+            ))
+        }
+    }
+}
+
+/// Total user and system CPU time of all reaped child processes.
+#[cfg(unix)]
+fn reaped_children_cpu_time() -> Option<Duration> {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrusage(libc::RUSAGE_CHILDREN, &mut usage) } != 0 {
+        return None;
+    }
+    let timeval = |tv: libc::timeval| {
+        Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+    };
+    Some(timeval(usage.ru_utime) + timeval(usage.ru_stime))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meets_target() {
+        assert!(meets_target(30.0, 30));
+        assert!(meets_target(28.5, 30));
+        assert!(!meets_target(28.4, 30));
+        assert!(meets_target(60.0, 30));
+    }
+
+    #[test]
+    fn test_cpu_percent_is_share_of_all_cores() {
+        let secs = Duration::from_secs;
+        assert_eq!(cpu_percent(secs(2), secs(2), 4), 25.0);
+        assert_eq!(cpu_percent(secs(8), secs(2), 4), 100.0);
+        assert_eq!(cpu_percent(secs(1), Duration::ZERO, 4), 0.0);
+    }
+
+    #[test]
+    fn test_synthetic_frames_differ() {
+        let first = synthetic_frame(64, 32, 0);
+        let second = synthetic_frame(64, 32, 1);
+        assert_eq!(first.data.len(), 64 * 32 * 4);
+        assert_ne!(first.data, second.data);
+    }
+
+    #[test]
+    fn test_run_benchmark_rejects_invalid_parameters() {
+        let threads = EncoderThreads::Auto;
+        assert!(run_benchmark(1920, 1080, 0, 5, threads).is_err());
+        assert!(run_benchmark(1920, 1080, 30, 0, threads).is_err());
+        assert!(run_benchmark(1920, 1080, 30, BENCHMARK_MAX_DURATION_SECS + 1, threads).is_err());
+        assert!(run_benchmark(BENCHMARK_MAX_WIDTH + 2, 1080, 30, 5, threads).is_err());
+    }
+}
@@ -0,0 +1,305 @@
+//! Joining recordings into one file, with a chapter at each source change.
+//!
+//! Segments are joined with FFmpeg's `concat` demuxer using stream copy, so
+//! they must share codecs and dimensions (e.g. consecutive recordings made
+//! with the same settings). Consecutive segments from the same display or
+//! window form one chapter; a new chapter starts where the source changes.
+//!
+//! The joined file never replaces an existing file, including the inputs.
+
+use super::{new_ffmpeg_command, parse_timestamp, resolve_ffmpeg_path};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// A recording to join, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentInfo {
+    /// Path of the recording
+    pub path: PathBuf,
+    /// Display or window the segment was recorded from; also the chapter title
+    pub source: String,
+    /// Length of the segment in seconds
+    pub duration_secs: f64,
+}
+
+/// Escape a value for an FFmpeg metadata file.
+fn escape_metadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Build an FFmpeg metadata file (`;FFMETADATA1`) with one chapter per run of
+/// consecutive segments from the same source.
+pub fn build_chapter_metadata(segments: &[SegmentInfo]) -> String {
+    let mut chapters: Vec<(&str, f64, f64)> = Vec::new();
+    let mut offset = 0.0;
+    for segment in segments {
+        let end = offset + segment.duration_secs.max(0.0);
+        match chapters.last_mut() {
+            Some((source, _, chapter_end)) if *source == segment.source => *chapter_end = end,
+            _ => chapters.push((&segment.source, offset, end)),
+        }
+        offset = end;
+    }
+
+    let mut metadata = String::from(";FFMETADATA1\n");
+    for (source, start, end) in chapters {
+        metadata.push_str("[CHAPTER]\nTIMEBASE=1/1000\n");
+        metadata.push_str(&format!("START={}\n", (start * 1000.0).round() as u64));
+        metadata.push_str(&format!("END={}\n", (end * 1000.0).round() as u64));
+        metadata.push_str(&format!("title={}\n", escape_metadata(source)));
+    }
+    metadata
+}
+
+/// Build a `concat` demuxer list of the segment files.
+fn build_concat_list(segments: &[SegmentInfo]) -> String {
+    segments
+        .iter()
+        .map(|segment| {
+            let path = segment.path.to_string_lossy().replace('\'', r"'\''");
+            format!("file '{}'\n", path)
+        })
+        .collect()
+}
+
+/// Read a media file's duration in seconds from FFmpeg's input summary.
+pub fn probe_duration(path: &Path) -> Result<f64, String> {
+    // FFmpeg exits with an error without an output file, but still prints
+    // the input summary
+    let output = Command::new(resolve_ffmpeg_path())
+        .args(["-hide_banner", "-i", path.to_string_lossy().as_ref()])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .find_map(|line| {
+            let rest = line.trim().strip_prefix("Duration: ")?;
+            parse_timestamp(rest.split(',').next()?)
+        })
+        .ok_or_else(|| format!("Could not read the duration of {}", path.display()))
+}
+
+/// Read a media file's video size from FFmpeg's input summary.
+pub fn probe_video_size(path: &Path) -> Result<(u32, u32), String> {
+    let output = Command::new(resolve_ffmpeg_path())
+        .args(["-hide_banner", "-i", path.to_string_lossy().as_ref()])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .find_map(parse_video_size)
+        .ok_or_else(|| format!("Could not read the video size of {}", path.display()))
+}
+
+/// Size in a `Stream #0:0: Video: ...` line of FFmpeg's input summary.
+fn parse_video_size(line: &str) -> Option<(u32, u32)> {
+    let (_, rest) = line.split_once(" Video: ")?;
+    rest.split([',', ' ']).find_map(|field| {
+        let (width, height) = field.split_once('x')?;
+        Some((width.parse().ok()?, height.parse().ok()?))
+    })
+}
+
+/// Whether `a` and `b` name the same file.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Check that joining into `output_path` replaces nothing.
+fn check_output(segments: &[SegmentInfo], output_path: &Path) -> Result<(), String> {
+    if segments
+        .iter()
+        .any(|segment| same_file(&segment.path, output_path))
+    {
+        return Err(format!(
+            "The joined file can't replace one of the recordings: {}",
+            output_path.display()
+        ));
+    }
+    if output_path.exists() {
+        return Err(format!("{} already exists", output_path.display()));
+    }
+    Ok(())
+}
+
+/// Create a new private directory for FFmpeg's input files.
+fn create_work_dir() -> std::io::Result<PathBuf> {
+    static NEXT: AtomicU32 = AtomicU32::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "omnirec-concat-{}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(&dir)?;
+    Ok(dir)
+}
+
+/// Join `segments` into `output_path` with chapter markers.
+///
+/// Fails if `output_path` exists. Blocks until FFmpeg finishes; call from a
+/// blocking task.
+pub fn concatenate(segments: &[SegmentInfo], output_path: &Path) -> Result<(), String> {
+    if segments.len() < 2 {
+        return Err("At least two recordings are needed to join".to_string());
+    }
+    check_output(segments, output_path)?;
+
+    let work_dir =
+        create_work_dir().map_err(|e| format!("Failed to write FFmpeg input files: {}", e))?;
+    let list_path = work_dir.join("concat.txt");
+    let metadata_path = work_dir.join("chapters.txt");
+    let cleanup = || {
+        let _ = std::fs::remove_dir_all(&work_dir);
+    };
+
+    std::fs::write(&list_path, build_concat_list(segments))
+        .and_then(|()| std::fs::write(&metadata_path, build_chapter_metadata(segments)))
+        .map_err(|e| {
+            cleanup();
+            format!("Failed to write FFmpeg input files: {}", e)
+        })?;
+
+    let mut command = new_ffmpeg_command();
+    command
+        .args(["-f", "concat", "-safe", "0"])
+        .args(["-i", list_path.to_string_lossy().as_ref()])
+        .args(["-i", metadata_path.to_string_lossy().as_ref()])
+        .args(["-map", "0", "-map_metadata", "1", "-map_chapters", "1"])
+        .args(["-c", "copy"])
+        // Never overwrite, even a file created since the check above
+        .args(["-n"])
+        .arg(output_path.to_string_lossy().to_string());
+
+    let inner_command = command.as_inner_mut();
+    inner_command.stdin(Stdio::null());
+    inner_command.stdout(Stdio::null());
+    inner_command.stderr(Stdio::piped());
+
+    let result = inner_command
+        .output()
+        .map_err(|e| format!("Failed to start FFmpeg for joining: {}", e));
+    cleanup();
+    let output = result?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(output_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "FFmpeg join failed: {}",
+            stderr.lines().last().unwrap_or("unknown error")
+        ));
+    }
+
+    tracing::info!(
+        "[Concat] Joined {} recordings into {}",
+        segments.len(),
+        output_path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(path: &str, source: &str, duration_secs: f64) -> SegmentInfo {
+        SegmentInfo {
+            path: PathBuf::from(path),
+            source: source.to_string(),
+            duration_secs,
+        }
+    }
+
+    #[test]
+    fn test_chapters_start_at_source_changes() {
+        let segments = [
+            segment("/rec/a.mp4", "DP-1", 10.0),
+            segment("/rec/b.mp4", "DP-1", 5.5),
+            segment("/rec/c.mp4", "HDMI-A-1", 20.0),
+            segment("/rec/d.mp4", "DP-1", 4.25),
+        ];
+        assert_eq!(
+            build_chapter_metadata(&segments),
+            ";FFMETADATA1\n\
+             [CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=15500\ntitle=DP-1\n\
+             [CHAPTER]\nTIMEBASE=1/1000\nSTART=15500\nEND=35500\ntitle=HDMI-A-1\n\
+             [CHAPTER]\nTIMEBASE=1/1000\nSTART=35500\nEND=39750\ntitle=DP-1\n"
+        );
+    }
+
+    #[test]
+    fn test_chapter_titles_are_escaped() {
+        let metadata = build_chapter_metadata(&[segment("/rec/a.mp4", "a=b; #1", 1.0)]);
+        assert!(metadata.contains("title=a\\=b\\; \\#1\n"));
+    }
+
+    #[test]
+    fn test_concat_list_quotes_paths() {
+        let list = build_concat_list(&[
+            segment("/rec/a.mp4", "DP-1", 1.0),
+            segment("/rec/it's.mp4", "DP-1", 1.0),
+        ]);
+        assert_eq!(list, "file '/rec/a.mp4'\nfile '/rec/it'\\''s.mp4'\n");
+    }
+
+    #[test]
+    fn test_parse_video_size() {
+        assert_eq!(
+            parse_video_size(
+                "  Stream #0:0[0x1](und): Video: h264 (High) (avc1 / 0x31637661), \
+                 yuv420p(tv, bt709, progressive), 1440x810 [SAR 1:1 DAR 16:9], 30 fps"
+            ),
+            Some((1440, 810))
+        );
+        assert_eq!(
+            parse_video_size("  Stream #0:1[0x2](und): Audio: aac (LC), 48000 Hz, mono"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_output_must_not_replace_a_file() {
+        let dir = std::env::temp_dir().join(format!("omnirec-concat-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = dir.join("a.mp4");
+        std::fs::write(&first, b"").unwrap();
+        let segments = [
+            segment(&first.to_string_lossy(), "DP-1", 1.0),
+            segment("/rec/b.mp4", "DP-1", 1.0),
+        ];
+
+        let err = check_output(&segments, &dir.join(".").join("a.mp4")).unwrap_err();
+        assert!(err.contains("can't replace"), "{}", err);
+
+        let existing = dir.join("joined.mp4");
+        std::fs::write(&existing, b"").unwrap();
+        assert!(check_output(&segments, &existing).is_err());
+        assert!(check_output(&segments, &dir.join("new.mp4")).is_ok());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_concatenate_needs_two_segments() {
+        let segments = [segment("/rec/a.mp4", "DP-1", 1.0)];
+        assert!(concatenate(&segments, Path::new("/rec/joined.mp4")).is_err());
+    }
+}
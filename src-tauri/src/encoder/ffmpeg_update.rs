@@ -0,0 +1,357 @@
+//! Updates for the bundled FFmpeg binary.
+//!
+//! [`ensure_ffmpeg_blocking`](super::ensure_ffmpeg_blocking) only checks that
+//! FFmpeg runs. [`check_ffmpeg_update`] compares the installed version with
+//! the release pinned in [`PINNED_RELEASES`] for this platform, and
+//! [`download_ffmpeg_update`] installs it into [`updated_ffmpeg_dir`], which
+//! [`resolve_ffmpeg_path`](super::resolve_ffmpeg_path) looks in before the
+//! bundled binary. The download is checked against the pinned SHA-256 before
+//! anything in it runs. A previously installed update is kept as
+//! `<binary>.backup` until the new one passes the same `-version` check, and
+//! put back if it doesn't.
+//!
+//! On Linux FFmpeg comes from the system package manager and is left alone.
+
+use crate::capture::error::FfmpegError;
+use omnirec_types::integrity;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// An FFmpeg build offered as an update.
+pub struct PinnedRelease {
+    /// Platform, as in [`std::env::consts::OS`]
+    pub os: &'static str,
+    /// Architecture, as in [`std::env::consts::ARCH`]
+    pub arch: &'static str,
+    /// Version as `ffmpeg -version` reports it
+    pub version: &'static str,
+    /// Archive of this exact version, not a "latest" link
+    pub url: &'static str,
+    /// SHA-256 of the archive, in hex
+    pub sha256: &'static str,
+}
+
+/// FFmpeg builds offered as updates, at most one per platform.
+///
+/// To offer a release, download its archive, check it, and add an entry with
+/// the output of `sha256sum`. Platforms without an entry report
+/// [`FfmpegUpdateStatus::NoUpdateChannel`] and can't be updated, so clients
+/// should offer no update there.
+pub const PINNED_RELEASES: &[PinnedRelease] = &[];
+
+/// Whether a newer FFmpeg is available.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum FfmpegUpdateStatus {
+    /// The installed FFmpeg is the latest release
+    UpToDate { current: String },
+    /// A newer release can be downloaded
+    UpdateAvailable { current: String, latest: String },
+    /// FFmpeg is managed by the system package manager
+    Unmanaged { current: Option<String> },
+    /// No release is pinned for this platform, so whether a newer one
+    /// exists is unknown
+    NoUpdateChannel { current: String },
+}
+
+/// Compare the installed FFmpeg with the pinned release.
+///
+/// Blocks on `ffmpeg -version`; call from a blocking task.
+pub fn check_ffmpeg_update() -> Result<FfmpegUpdateStatus, FfmpegError> {
+    let ffmpeg = super::resolve_ffmpeg_path();
+    let current = ffmpeg_version(&ffmpeg);
+    if !is_managed() {
+        return Ok(FfmpegUpdateStatus::Unmanaged { current });
+    }
+    let current = current.ok_or_else(|| {
+        FfmpegError::NotFound(format!("cannot read version of {}", ffmpeg.display()))
+    })?;
+
+    let Some(release) = pinned_release() else {
+        tracing::debug!("[FFmpeg] No release pinned for this platform");
+        return Ok(FfmpegUpdateStatus::NoUpdateChannel { current });
+    };
+    tracing::debug!(
+        "[FFmpeg] Installed version {}, pinned {}",
+        current,
+        release.version
+    );
+
+    Ok(if is_newer(release.version, &current) {
+        FfmpegUpdateStatus::UpdateAvailable {
+            current,
+            latest: release.version.to_string(),
+        }
+    } else {
+        FfmpegUpdateStatus::UpToDate { current }
+    })
+}
+
+/// Download the pinned FFmpeg release and install it into
+/// [`updated_ffmpeg_dir`]. Returns the version now installed.
+///
+/// Blocks while downloading; call from a blocking task.
+pub fn download_ffmpeg_update() -> Result<String, FfmpegError> {
+    if !is_managed() {
+        return Err(FfmpegError::DownloadFailed(
+            "FFmpeg is managed by the system package manager".to_string(),
+        ));
+    }
+    let release = pinned_release().ok_or_else(|| {
+        FfmpegError::DownloadFailed("no FFmpeg release is pinned for this platform".to_string())
+    })?;
+
+    let install_dir = updated_ffmpeg_dir();
+    let staging_dir = install_dir.with_file_name("ffmpeg-update");
+    std::fs::create_dir_all(&install_dir)?;
+    std::fs::create_dir_all(&staging_dir)?;
+
+    let ffmpeg = install_dir.join(ffmpeg_file_name());
+    let result = stage_update(&staging_dir, release).and_then(|staged| install(&staged, &ffmpeg));
+    if let Err(e) = std::fs::remove_dir_all(&staging_dir) {
+        tracing::debug!("[FFmpeg] Failed to remove {}: {}", staging_dir.display(), e);
+    }
+    result
+}
+
+/// Directory updated FFmpeg binaries are installed to, in the user's data
+/// directory so updating needs no write access to the app's install.
+pub fn updated_ffmpeg_dir() -> PathBuf {
+    directories::BaseDirs::new()
+        .map(|dirs| dirs.data_local_dir().join("OmniRec").join("ffmpeg"))
+        .unwrap_or_else(|| std::env::temp_dir().join("omnirec-ffmpeg"))
+}
+
+/// The updated FFmpeg binary, if one was installed.
+#[cfg(not(target_os = "linux"))]
+pub fn updated_ffmpeg_path() -> Option<PathBuf> {
+    let path = updated_ffmpeg_dir().join(ffmpeg_file_name());
+    path.is_file().then_some(path)
+}
+
+/// FFmpeg is only updated where it is bundled with the app.
+fn is_managed() -> bool {
+    cfg!(not(target_os = "linux"))
+}
+
+/// The release pinned for this platform, if any.
+fn pinned_release() -> Option<&'static PinnedRelease> {
+    PINNED_RELEASES.iter().find(|release| {
+        release.os == std::env::consts::OS && release.arch == std::env::consts::ARCH
+    })
+}
+
+/// Download `release` into `staging_dir`, check its checksum and unpack it,
+/// returning the path of the unpacked binary once it runs.
+fn stage_update(staging_dir: &Path, release: &PinnedRelease) -> Result<PathBuf, FfmpegError> {
+    tracing::info!(
+        "[FFmpeg] Downloading {} from {}",
+        release.version,
+        release.url
+    );
+    let archive = ffmpeg_sidecar::download::download_ffmpeg_package(release.url, staging_dir)
+        .map_err(download_failed)?;
+    verify_checksum(&archive, release.sha256)?;
+    ffmpeg_sidecar::download::unpack_ffmpeg(&archive, staging_dir).map_err(download_failed)?;
+
+    let staged = staging_dir.join(ffmpeg_file_name());
+    verify_binary(&staged)?;
+    Ok(staged)
+}
+
+/// Check that `archive` has the SHA-256 `expected`.
+fn verify_checksum(archive: &Path, expected: &str) -> Result<(), FfmpegError> {
+    let actual = integrity::sha256_file(archive)?;
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(FfmpegError::DownloadFailed(format!(
+            "checksum mismatch for {}: expected {}, got {}",
+            archive.display(),
+            expected,
+            actual
+        )))
+    }
+}
+
+/// Move `staged` to `ffmpeg`, keeping a previously installed update until the
+/// new binary is verified in place.
+fn install(staged: &Path, ffmpeg: &Path) -> Result<String, FfmpegError> {
+    let backup = ffmpeg.is_file().then(|| backup_path(ffmpeg));
+    if let Some(backup) = &backup {
+        std::fs::rename(ffmpeg, backup)?;
+    }
+
+    let installed = std::fs::rename(staged, ffmpeg)
+        .map_err(FfmpegError::from)
+        .and_then(|()| verify_binary(ffmpeg));
+    match installed {
+        Ok(version) => {
+            if let Some(backup) = &backup {
+                if let Err(e) = std::fs::remove_file(backup) {
+                    tracing::warn!("[FFmpeg] Failed to remove {}: {}", backup.display(), e);
+                }
+            }
+            tracing::info!("[FFmpeg] Updated to {}", version);
+            Ok(version)
+        }
+        Err(e) => {
+            tracing::warn!("[FFmpeg] New binary failed verification: {}", e);
+            match &backup {
+                Some(backup) => std::fs::rename(backup, ffmpeg)?,
+                // Fall back to the bundled binary
+                None => {
+                    let _ = std::fs::remove_file(ffmpeg);
+                }
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Run `path -version`, returning the version if it succeeds.
+fn verify_binary(path: &Path) -> Result<String, FfmpegError> {
+    let output = Command::new(path)
+        .arg("-version")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(FfmpegError::ExitStatus(output.status.code()));
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .and_then(parse_ffmpeg_version)
+        .ok_or_else(|| FfmpegError::NotFound(format!("{} reported no version", path.display())))
+}
+
+fn download_failed(err: impl std::fmt::Display) -> FfmpegError {
+    FfmpegError::DownloadFailed(err.to_string())
+}
+
+fn ffmpeg_version(path: &Path) -> Option<String> {
+    verify_binary(path).ok()
+}
+
+fn ffmpeg_file_name() -> &'static str {
+    if cfg!(windows) {
+        "ffmpeg.exe"
+    } else {
+        "ffmpeg"
+    }
+}
+
+fn backup_path(ffmpeg: &Path) -> PathBuf {
+    let mut path = ffmpeg.as_os_str().to_owned();
+    path.push(".backup");
+    PathBuf::from(path)
+}
+
+/// Version from the first line of `ffmpeg -version`, e.g.
+/// `ffmpeg version 7.1-essentials_build-www.gyan.dev Copyright ...`.
+pub fn parse_ffmpeg_version(line: &str) -> Option<String> {
+    let mut words = line.split_whitespace();
+    words.find(|word| *word == "version")?;
+    words.next().map(str::to_string)
+}
+
+/// Numeric components of a version such as `n7.1.1-3`, or `None` for builds
+/// without a dotted version (e.g. dated git snapshots).
+fn version_numbers(version: &str) -> Option<Vec<u32>> {
+    let version = version.trim_start_matches('n');
+    let end = version
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(version.len());
+    let numbers = version[..end]
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    (numbers.len() >= 2).then_some(numbers)
+}
+
+/// Whether `latest` is a newer release than `current`. Versions that can't
+/// be compared are never treated as newer.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    match (version_numbers(latest), version_numbers(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ffmpeg_version() {
+        assert_eq!(
+            parse_ffmpeg_version(
+                "ffmpeg version 7.1-essentials_build-www.gyan.dev Copyright (c) 2000-2024"
+            )
+            .as_deref(),
+            Some("7.1-essentials_build-www.gyan.dev")
+        );
+        assert_eq!(
+            parse_ffmpeg_version("ffmpeg version n6.1.1 Copyright").as_deref(),
+            Some("n6.1.1")
+        );
+        assert_eq!(parse_ffmpeg_version("not ffmpeg"), None);
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("7.1", "7.0.2"));
+        assert!(is_newer("7.1.1", "n7.1-essentials_build-www.gyan.dev"));
+        assert!(!is_newer("7.1", "7.1"));
+        assert!(!is_newer("7.0", "7.1"));
+        // Snapshot builds can't be compared
+        assert!(!is_newer("7.1", "2024-10-02-git-358fdf3083"));
+        assert!(!is_newer("", "7.1"));
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let archive = std::env::temp_dir().join("omnirec_test_ffmpeg_checksum.zip");
+        std::fs::write(&archive, b"abc").unwrap();
+        let sha256 = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        assert!(verify_checksum(&archive, sha256).is_ok());
+        assert!(verify_checksum(&archive, &sha256.to_uppercase()).is_ok());
+        assert!(matches!(
+            verify_checksum(&archive, &"0".repeat(64)),
+            Err(FfmpegError::DownloadFailed(_))
+        ));
+
+        std::fs::remove_file(&archive).unwrap();
+    }
+
+    #[test]
+    fn test_pinned_releases_are_well_formed() {
+        for release in PINNED_RELEASES {
+            assert_eq!(release.sha256.len(), 64, "{}", release.url);
+            assert!(release.sha256.chars().all(|c| c.is_ascii_hexdigit()));
+            assert!(release.url.starts_with("https://"), "{}", release.url);
+        }
+    }
+
+    #[test]
+    fn test_no_update_channel_is_its_own_status() {
+        let status = FfmpegUpdateStatus::NoUpdateChannel {
+            current: "7.1".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            r#"{"status":"no_update_channel","current":"7.1"}"#
+        );
+    }
+
+    #[test]
+    fn test_backup_path_appends_suffix() {
+        assert_eq!(
+            backup_path(Path::new("/opt/omnirec/ffmpeg.exe")),
+            PathBuf::from("/opt/omnirec/ffmpeg.exe.backup")
+        );
+    }
+}
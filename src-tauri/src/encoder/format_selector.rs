@@ -0,0 +1,85 @@
+//! Output format auto-selection ("smart format").
+//!
+//! When smart format is on and no output format was set, the format is
+//! chosen when the recording stops, from what was recorded: short, small
+//! clips become GIFs, everything else a video in the format that suits the
+//! platform best.
+
+use omnirec_types::OutputFormat;
+use tracing::info;
+
+/// Recordings shorter than this many seconds may become GIFs.
+pub const GIF_MAX_DURATION_SECS: u64 = 15;
+
+/// Recordings narrower than this many pixels may become GIFs.
+pub const GIF_MAX_WIDTH: u32 = 1280;
+
+/// What was recorded, as far as choosing an output format goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordTarget {
+    /// Length of the recording in seconds
+    pub duration_secs: u64,
+    /// Width of the recorded frames in pixels
+    pub width: u32,
+}
+
+/// Choose the output format for a recording of `target`.
+pub fn select_smart_format(target: &RecordTarget) -> OutputFormat {
+    let (format, reason) = select_for_platform(target, cfg!(target_os = "linux"));
+    info!(
+        "Smart format selected {:?} for {}s at {}px wide: {}",
+        format, target.duration_secs, target.width, reason
+    );
+    format
+}
+
+/// The format for `target` and why it was chosen, on Linux if `linux`.
+fn select_for_platform(target: &RecordTarget, linux: bool) -> (OutputFormat, &'static str) {
+    if target.duration_secs < GIF_MAX_DURATION_SECS && target.width < GIF_MAX_WIDTH {
+        (OutputFormat::Gif, "short clip at a small size")
+    } else if linux {
+        (OutputFormat::WebM, "longer or larger recording on Linux")
+    } else {
+        (OutputFormat::Mp4, "longer or larger recording")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(duration_secs: u64, width: u32) -> RecordTarget {
+        RecordTarget {
+            duration_secs,
+            width,
+        }
+    }
+
+    #[test]
+    fn test_short_small_clip_is_gif() {
+        for linux in [false, true] {
+            let (format, _) = select_for_platform(&target(5, 800), linux);
+            assert_eq!(format, OutputFormat::Gif);
+        }
+        let (format, _) = select_for_platform(&target(GIF_MAX_DURATION_SECS - 1, 1279), false);
+        assert_eq!(format, OutputFormat::Gif);
+    }
+
+    #[test]
+    fn test_long_or_wide_recording_is_webm_on_linux() {
+        let (format, _) = select_for_platform(&target(GIF_MAX_DURATION_SECS, 800), true);
+        assert_eq!(format, OutputFormat::WebM);
+        let (format, _) = select_for_platform(&target(5, GIF_MAX_WIDTH), true);
+        assert_eq!(format, OutputFormat::WebM);
+    }
+
+    #[test]
+    fn test_long_or_wide_recording_is_mp4_elsewhere() {
+        let (format, _) = select_for_platform(&target(600, 1920), false);
+        assert_eq!(format, OutputFormat::Mp4);
+        let (format, _) = select_for_platform(&target(5, 1920), false);
+        assert_eq!(format, OutputFormat::Mp4);
+        let (format, _) = select_for_platform(&target(GIF_MAX_DURATION_SECS, 800), false);
+        assert_eq!(format, OutputFormat::Mp4);
+    }
+}
@@ -0,0 +1,213 @@
+//! Load shedding when encoding falls behind.
+//!
+//! Frames wait in a queue until they are written to FFmpeg (see
+//! [`Backlog`]). When the queued frames cover more than [`SHED_AFTER`] of
+//! the recording, FFmpeg is not keeping up, and every other captured frame
+//! is dropped until the backlog clears. Thumbnails are encoded at a lower JPEG quality while
+//! shedding and for [`THUMBNAIL_RECOVERY`] afterwards, so they compete less
+//! with the encoder for CPU.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Frame lag at which load shedding starts.
+pub const SHED_AFTER: Duration = Duration::from_secs(5);
+
+/// Frame lag at which the backlog counts as cleared and shedding stops.
+pub const CLEARED_BELOW: Duration = Duration::from_secs(1);
+
+/// JPEG quality thumbnails are capped at while shedding.
+pub const SHEDDING_THUMBNAIL_QUALITY: u8 = 50;
+
+/// How long after shedding stops thumbnails keep the lower quality.
+pub const THUMBNAIL_RECOVERY: Duration = Duration::from_secs(30);
+
+/// Load shedding state of the current recording, shared between the
+/// encoding loop and the recording manager.
+#[derive(Debug, Default)]
+pub struct LoadShedding {
+    /// Drop frames when the encoder falls behind
+    enabled: AtomicBool,
+    /// Frames are being dropped
+    active: AtomicBool,
+    /// Frames taken from the capture channel
+    received: AtomicU64,
+    /// Frames dropped while shedding
+    dropped: AtomicU64,
+    /// Frames received since shedding started; odd ones are kept
+    shedding_received: AtomicU64,
+    /// When shedding last stopped
+    stopped_at: Mutex<Option<Instant>>,
+}
+
+impl LoadShedding {
+    /// Set whether frames are dropped when the encoder falls behind.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Clear the counters for a new recording.
+    pub fn reset(&self) {
+        self.active.store(false, Ordering::Relaxed);
+        self.received.store(0, Ordering::Relaxed);
+        self.dropped.store(0, Ordering::Relaxed);
+    }
+
+    /// Stop shedding at the end of a recording.
+    pub fn finish(&self) {
+        if self.active.swap(false, Ordering::Relaxed) {
+            *self.stopped_at.lock().unwrap() = Some(Instant::now());
+        }
+    }
+
+    /// Whether frames are being dropped.
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// Fraction of the received frames that were dropped.
+    pub fn drop_ratio(&self) -> f64 {
+        match self.received.load(Ordering::Relaxed) {
+            0 => 0.0,
+            received => self.dropped.load(Ordering::Relaxed) as f64 / received as f64,
+        }
+    }
+
+    /// Whether thumbnails should use [`SHEDDING_THUMBNAIL_QUALITY`].
+    pub fn lowers_thumbnail_quality(&self) -> bool {
+        self.is_active()
+            || self
+                .stopped_at
+                .lock()
+                .unwrap()
+                .is_some_and(|at| at.elapsed() < THUMBNAIL_RECOVERY)
+    }
+
+    /// Record a frame taken from the capture channel while FFmpeg is `lag`
+    /// behind (see [`Backlog::lag`]), and decide whether to keep it.
+    pub fn admit(&self, lag: Duration) -> bool {
+        self.received.fetch_add(1, Ordering::Relaxed);
+        let active = self.is_active();
+        if !active && self.enabled.load(Ordering::Relaxed) && lag > SHED_AFTER {
+            tracing::warn!(
+                "[Encoder] {:.1}s behind real time, dropping every other frame",
+                lag.as_secs_f64()
+            );
+            self.active.store(true, Ordering::Relaxed);
+            self.shedding_received.store(0, Ordering::Relaxed);
+        } else if active && lag < CLEARED_BELOW {
+            tracing::info!(
+                "[Encoder] Caught up, load shedding stopped ({:.0}% of frames dropped)",
+                self.drop_ratio() * 100.0
+            );
+            self.active.store(false, Ordering::Relaxed);
+            *self.stopped_at.lock().unwrap() = Some(Instant::now());
+        }
+
+        if !self.is_active() {
+            return true;
+        }
+        if self.shedding_received.fetch_add(1, Ordering::Relaxed) % 2 == 1 {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+}
+
+/// Frames queued for FFmpeg and not yet written to it.
+///
+/// Shared by the encoding loop, which counts frames as it queues them, and
+/// the stdin writer threads, which count them as FFmpeg takes them.
+#[derive(Debug, Default)]
+pub struct Backlog {
+    queued: AtomicU64,
+}
+
+impl Backlog {
+    /// Count a frame queued for FFmpeg.
+    pub fn push(&self) {
+        self.queued.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a queued frame written to FFmpeg, or one that could not be
+    /// queued.
+    pub fn pop(&self) {
+        let _ = self
+            .queued
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+    }
+
+    /// How much of a recording at `fps` is waiting to be written.
+    pub fn lag(&self, fps: u64) -> Duration {
+        Duration::from_secs_f64(self.queued.load(Ordering::Relaxed) as f64 / fps.max(1) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shedding() -> LoadShedding {
+        let shedding = LoadShedding::default();
+        shedding.set_enabled(true);
+        shedding
+    }
+
+    #[test]
+    fn test_drops_every_other_frame_while_behind() {
+        let shedding = shedding();
+        let lag = SHED_AFTER + Duration::from_secs(1);
+        let kept: Vec<bool> = (0..4).map(|_| shedding.admit(lag)).collect();
+        assert_eq!(kept, [true, false, true, false]);
+        assert!(shedding.is_active());
+        assert_eq!(shedding.drop_ratio(), 0.5);
+    }
+
+    #[test]
+    fn test_stops_once_backlog_clears() {
+        let shedding = shedding();
+        shedding.admit(SHED_AFTER + Duration::from_secs(1));
+        // Still behind, though less than the start threshold
+        shedding.admit(Duration::from_secs(2));
+        assert!(shedding.is_active());
+
+        assert!(shedding.admit(Duration::from_millis(100)));
+        assert!(!shedding.is_active());
+        assert!(shedding.lowers_thumbnail_quality());
+    }
+
+    #[test]
+    fn test_backlog_drives_shedding() {
+        let shedding = shedding();
+        let backlog = Backlog::default();
+
+        // Six seconds of frames waiting for FFmpeg at 30 fps
+        for _ in 0..180 {
+            backlog.push();
+        }
+        assert_eq!(backlog.lag(30), Duration::from_secs(6));
+        assert!(shedding.admit(backlog.lag(30)));
+        assert!(!shedding.admit(backlog.lag(30)));
+        assert!(shedding.is_active());
+
+        // FFmpeg works through all but half a second of them
+        for _ in 0..165 {
+            backlog.pop();
+        }
+        assert!(shedding.admit(backlog.lag(30)));
+        assert!(!shedding.is_active());
+    }
+
+    #[test]
+    fn test_disabled_keeps_every_frame() {
+        let shedding = LoadShedding::default();
+        for _ in 0..4 {
+            assert!(shedding.admit(SHED_AFTER * 2));
+        }
+        assert!(!shedding.is_active());
+        assert_eq!(shedding.drop_ratio(), 0.0);
+        assert!(!shedding.lowers_thumbnail_quality());
+    }
+}
@@ -8,20 +8,41 @@
 //! streams into a single MP4 file in real-time -- no post-recording mux step.
 
 pub mod audio_pipe;
-
-use crate::capture::types::CapturedFrame;
-use audio_pipe::{f32_mono_to_s16le, AudioPipe};
+pub mod benchmark;
+pub mod concat;
+pub mod ffmpeg_update;
+pub mod format_selector;
+pub mod load_shedding;
+pub mod pip;
+pub mod preview;
+pub mod scaling;
+
+use crate::capture::color_profile::ColorProfile;
+use crate::capture::error::FfmpegError;
+use crate::capture::frame_pool::FrameData;
+use crate::capture::mic_agc::MicAgc;
+use crate::capture::types::{self, CapturedFrame, PixelFormat};
+use crate::capture::webcam::WebcamCapture;
+use crate::config::DEFAULT_KEYFRAME_INTERVAL_SECS;
+use audio_pipe::{AudioPipe, SegmentedPipe};
 use chrono::Local;
 use directories::UserDirs;
 use ffmpeg_sidecar::command::FfmpegCommand;
+use load_shedding::{Backlog, LoadShedding};
+use omnirec_types::{AudioCodec, PipConfig, DEFAULT_AUDIO_BITRATE_KBPS};
+use pip::PatchedFrame;
+use scaling::{DynamicResolutionScaling, ScaleController};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{ChildStdin, Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::{broadcast, mpsc};
-use vtx_engine::EngineEvent;
+use vtx_engine::{EngineEvent, StreamingAudioData};
 
 /// Resolve the path to the FFmpeg binary.
 ///
@@ -29,7 +50,8 @@ use vtx_engine::EngineEvent;
 /// (configured in `tauri.conf.json` under `externalBin`). The sidecar is
 /// placed adjacent to the application executable with a platform-triple suffix
 /// that Tauri strips at install time, making it available as `ffmpeg` (or
-/// `ffmpeg.exe`) next to the main binary.
+/// `ffmpeg.exe`) next to the main binary. An update installed by
+/// [`ffmpeg_update::download_ffmpeg_update`] takes precedence over it.
 ///
 /// On Linux, FFmpeg is declared as a system package dependency in the
 /// deb/rpm/AUR packaging, so we use the system-installed binary from PATH.
@@ -45,7 +67,7 @@ fn resolve_ffmpeg_path() -> PathBuf {
         // ffmpeg-sidecar's built-in path resolution does exactly this: it looks
         // for an "ffmpeg" (or "ffmpeg.exe") binary next to current_exe(), which
         // is where Tauri places externalBin sidecars after installation.
-        ffmpeg_sidecar::paths::ffmpeg_path()
+        ffmpeg_update::updated_ffmpeg_path().unwrap_or_else(ffmpeg_sidecar::paths::ffmpeg_path)
     }
 }
 
@@ -117,6 +139,24 @@ pub struct VideoEncoder {
     video_stdin: Option<ChildStdin>,
     child: Option<std::process::Child>,
     output_path: PathBuf,
+    /// Discard encoded output (`-f null -`) instead of writing `output_path`.
+    discard_output: bool,
+    /// Container metadata tags (`-metadata key=value`).
+    metadata: Vec<(String, String)>,
+    /// FFmpeg video filter chain (`-vf`), applied before encoding.
+    video_filter: Option<String>,
+    /// Directory FFmpeg writes to until `finish` moves the file to `output_path`.
+    temp_dir: Option<PathBuf>,
+    /// Video encoder threads (`-threads`).
+    threads: EncoderThreads,
+    /// AAC audio bitrate in kbps (`-b:a`).
+    audio_bitrate_kbps: u32,
+    /// Seconds between keyframes (`-g`, in frames).
+    keyframe_interval_secs: f32,
+    /// Layout of the frames written to stdin (input `-pix_fmt`).
+    pixel_format: PixelFormat,
+    /// Color profile the video is tagged with (`-color_primaries` etc.).
+    color_profile: ColorProfile,
     /// Encoder width (even-aligned).
     pub width: u32,
     /// Encoder height (even-aligned).
@@ -124,15 +164,17 @@ pub struct VideoEncoder {
 }
 
 impl VideoEncoder {
-    /// Create a new encoder with the given frame dimensions and optional output path.
+    /// Create a new encoder for frames of the given dimensions and pixel
+    /// format, with an optional output path.
     pub fn new_with_options(
         width: u32,
         height: u32,
+        pixel_format: PixelFormat,
         output_path: Option<PathBuf>,
     ) -> Result<Self, String> {
         let output_path = match output_path {
             Some(p) => p,
-            None => generate_output_path()?,
+            None => generate_output_path(None, true)?,
         };
 
         // Ensure dimensions are even (required by many codecs including h264)
@@ -147,11 +189,80 @@ impl VideoEncoder {
             video_stdin: None,
             child: None,
             output_path,
+            discard_output: false,
+            metadata: Vec::new(),
+            video_filter: None,
+            temp_dir: None,
+            threads: EncoderThreads::Auto,
+            audio_bitrate_kbps: DEFAULT_AUDIO_BITRATE_KBPS,
+            keyframe_interval_secs: DEFAULT_KEYFRAME_INTERVAL_SECS,
+            pixel_format,
+            color_profile: ColorProfile::default(),
             width,
             height,
         })
     }
 
+    /// Set container metadata tags written to the output file.
+    pub fn set_metadata(&mut self, metadata: Vec<(String, String)>) {
+        self.metadata = metadata;
+    }
+
+    /// Set the FFmpeg video filter chain applied to the frames.
+    pub fn set_video_filter(&mut self, filter: Option<String>) {
+        self.video_filter = filter;
+    }
+
+    /// Set the number of threads the video encoder uses.
+    pub fn set_threads(&mut self, threads: EncoderThreads) {
+        self.threads = threads;
+    }
+
+    /// Set the time between keyframes, in seconds.
+    pub fn set_keyframe_interval(&mut self, secs: f32) {
+        self.keyframe_interval_secs = secs;
+    }
+
+    /// Set the color profile of the captured display.
+    pub fn set_color_profile(&mut self, profile: ColorProfile) {
+        self.color_profile = profile;
+    }
+
+    /// Set the AAC audio bitrate in kbps.
+    pub fn set_audio_bitrate(&mut self, kbps: u32) {
+        self.audio_bitrate_kbps = kbps;
+    }
+
+    /// Write to a file in `dir` while recording and move it to the output
+    /// path when finished.
+    pub fn set_temp_dir(&mut self, dir: Option<PathBuf>) {
+        self.temp_dir = dir;
+    }
+
+    /// Path FFmpeg writes to: the output path, or a file of the same name in
+    /// the temporary directory.
+    fn working_path(&self) -> PathBuf {
+        match (&self.temp_dir, self.output_path.file_name()) {
+            (Some(dir), Some(name)) if !self.discard_output => dir.join(name),
+            _ => self.output_path.clone(),
+        }
+    }
+
+    /// Create an encoder whose output is discarded.
+    ///
+    /// Frames go through the full encode pipeline but FFmpeg writes to its
+    /// null muxer, so no file is created. Used for dry-run checks.
+    pub fn new_discarding(
+        width: u32,
+        height: u32,
+        pixel_format: PixelFormat,
+    ) -> Result<Self, String> {
+        let mut encoder =
+            Self::new_with_options(width, height, pixel_format, Some(PathBuf::from("-")))?;
+        encoder.discard_output = true;
+        Ok(encoder)
+    }
+
     /// Start the FFmpeg encoding process.
     ///
     /// When `audio_pipe_path` is provided, FFmpeg is started with two inputs:
@@ -175,7 +286,7 @@ impl VideoEncoder {
         command
             // Input 0: raw video frames from stdin
             .args(["-f", "rawvideo"])
-            .args(["-pix_fmt", "bgra"])
+            .args(["-pix_fmt", self.pixel_format.ffmpeg_name()])
             .args(["-s", &format!("{}x{}", self.width, self.height)])
             .args(["-r", "30"]) // 30 FPS
             .args(["-i", "-"]); // Read from stdin
@@ -189,51 +300,26 @@ impl VideoEncoder {
                 .args(["-i", pipe_path]);
         }
 
-        // Output: H.264 in MP4 container
-        command.args(["-c:v", encoder]);
-
-        // Add encoder-specific options
-        match encoder {
-            "libx264" => {
-                command
-                    .args(["-preset", "ultrafast"]) // Fast encoding for real-time
-                    .args(["-crf", "23"]); // Good quality/size balance
-            }
-            "libopenh264" => {
-                // OpenH264 has limited options
-                command.args(["-b:v", "2M"]); // Target bitrate
-            }
-            "h264_vaapi" => {
-                // VAAPI needs different options
-                command.args(["-qp", "23"]); // Quality parameter
-            }
-            "h264_nvenc" | "h264_amf" => {
-                command
-                    .args(["-preset", "p1"]) // Fastest preset
-                    .args(["-rc", "vbr"])
-                    .args(["-cq", "23"]);
-            }
-            _ => {
-                // Generic options for other encoders
-                tracing::debug!("[Encoder] Using generic options for encoder: {}", encoder);
-            }
+        // User-supplied video filters
+        if let Some(filter) = &self.video_filter {
+            command.args(["-vf", filter]);
         }
 
-        command.args(["-pix_fmt", "yuv420p"]); // Compatible pixel format
-
-        // Audio encoding (if audio pipe provided)
+        self.add_output_args(&mut command, encoder, audio_pipe_path.is_some());
         if audio_pipe_path.is_some() {
             command
-                .args(["-c:a", "aac"])
-                .args(["-b:a", "192k"])
                 .args(["-map", "0:v"]) // Video from input 0 (stdin)
                 .args(["-map", "1:a"]); // Audio from input 1 (pipe)
                                         // Note: -shortest removed - video duration should determine output length
         }
 
-        command
-            .args(["-y"]) // Overwrite output
-            .arg(self.output_path.to_string_lossy().to_string());
+        if self.discard_output {
+            command.args(["-f", "null", "-"]);
+        } else {
+            command
+                .args(["-y"]) // Overwrite output
+                .arg(self.working_path().to_string_lossy().to_string());
+        }
 
         // Get the inner command and configure stdin
         let inner_command = command.as_inner_mut();
@@ -268,6 +354,58 @@ impl VideoEncoder {
         Ok(())
     }
 
+    /// Add the video and audio encoding options and metadata tags.
+    fn add_output_args(&self, command: &mut FfmpegCommand, encoder: &str, has_audio: bool) {
+        // Output: H.264 in MP4 container
+        command.args(["-c:v", encoder]);
+
+        // Add encoder-specific options
+        match encoder {
+            "libx264" => {
+                command
+                    .args(["-preset", "ultrafast"]) // Fast encoding for real-time
+                    .args(["-crf", "23"]); // Good quality/size balance
+            }
+            "libopenh264" => {
+                // OpenH264 has limited options
+                command.args(["-b:v", "2M"]); // Target bitrate
+            }
+            "h264_vaapi" => {
+                // VAAPI needs different options
+                command.args(["-qp", "23"]); // Quality parameter
+            }
+            "h264_nvenc" | "h264_amf" => {
+                command
+                    .args(["-preset", "p1"]) // Fastest preset
+                    .args(["-rc", "vbr"])
+                    .args(["-cq", "23"]);
+            }
+            _ => {
+                // Generic options for other encoders
+                tracing::debug!("[Encoder] Using generic options for encoder: {}", encoder);
+            }
+        }
+
+        command.args(["-threads", &self.threads.ffmpeg_threads().to_string()]);
+        command.args([
+            "-g",
+            &gop_size(TARGET_FPS, self.keyframe_interval_secs).to_string(),
+        ]);
+        command.args(["-pix_fmt", "yuv420p"]); // Compatible pixel format
+        command.args(self.color_profile.ffmpeg_args());
+
+        // Audio encoding (if the output has audio)
+        if has_audio {
+            command
+                .args(["-c:a", "aac"])
+                .args(["-b:a", &format!("{}k", self.audio_bitrate_kbps)]);
+        }
+
+        for (key, value) in &self.metadata {
+            command.args(["-metadata", &format!("{}={}", key, value)]);
+        }
+    }
+
     /// Write a frame to the encoder.
     pub fn write_frame(&mut self, frame: &CapturedFrame) -> Result<(), String> {
         // Handle frames that may be slightly larger than encoder dimensions
@@ -283,6 +421,15 @@ impl VideoEncoder {
             return Ok(());
         }
 
+        if frame.pixel_format != self.pixel_format {
+            tracing::debug!(
+                "Skipping frame: pixel format {:?} differs from encoder {:?}",
+                frame.pixel_format,
+                self.pixel_format
+            );
+            return Ok(());
+        }
+
         if let Some(ref mut stdin) = self.video_stdin {
             // If frame is larger than encoder dimensions, crop it
             if frame.width == self.width && frame.height == self.height {
@@ -290,20 +437,10 @@ impl VideoEncoder {
                 stdin
                     .write_all(&frame.data)
                     .map_err(|e| format!("Failed to write frame: {}", e))?;
-            } else {
-                // Need to crop - extract only the rows/columns we need
-                let src_row_bytes = (frame.width * 4) as usize;
-                let dst_row_bytes = (self.width * 4) as usize;
-
-                for y in 0..self.height as usize {
-                    let src_start = y * src_row_bytes;
-                    let src_end = src_start + dst_row_bytes;
-                    if src_end <= frame.data.len() {
-                        stdin
-                            .write_all(&frame.data[src_start..src_end])
-                            .map_err(|e| format!("Failed to write frame row: {}", e))?;
-                    }
-                }
+            } else if let Some(cropped) = frame.crop(0, 0, self.width, self.height) {
+                stdin
+                    .write_all(&cropped.data)
+                    .map_err(|e| format!("Failed to write frame: {}", e))?;
             }
         }
         Ok(())
@@ -318,6 +455,11 @@ impl VideoEncoder {
         self.video_stdin.take()
     }
 
+    /// Process ID of the running FFmpeg process, once started.
+    pub fn process_id(&self) -> Option<u32> {
+        self.child.as_ref().map(|child| child.id())
+    }
+
     /// Get the output path.
     pub fn output_path(&self) -> &PathBuf {
         &self.output_path
@@ -328,6 +470,42 @@ impl VideoEncoder {
     /// Standard MP4 is written (not fragmented), so the moov atom will be
     /// written at the end when FFmpeg exits with correct duration.
     pub fn finish(mut self) -> Result<PathBuf, String> {
+        self.wait()?;
+
+        if self.discard_output {
+            return Ok(self.output_path);
+        }
+
+        let working_path = self.working_path();
+        if working_path != self.output_path {
+            move_file(&working_path, &self.output_path)?;
+            tracing::info!(
+                "[Encoder] Moved recording from {} to {}",
+                working_path.display(),
+                self.output_path.display()
+            );
+        }
+
+        // Check file size
+        match std::fs::metadata(&self.output_path) {
+            Ok(metadata) => {
+                let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
+                tracing::info!(
+                    "[Encoder] Output file size: {:.2} MB ({:.2} KB)",
+                    size_mb,
+                    metadata.len() as f64 / 1024.0
+                );
+            }
+            Err(e) => {
+                tracing::warn!("[Encoder] Could not get file metadata: {}", e);
+            }
+        }
+
+        Ok(self.output_path)
+    }
+
+    /// Close FFmpeg's stdin and wait for it to write the file.
+    fn wait(&mut self) -> Result<(), String> {
         tracing::info!("[Encoder] Closing FFmpeg stdin and waiting for process to finish...");
 
         // Close stdin to signal end of input
@@ -348,36 +526,257 @@ impl VideoEncoder {
                 ));
             }
         }
+        Ok(())
+    }
+
+    /// Encoder for segment `index` of this recording, encoded with
+    /// `video_filter` into a file next to the recording's own.
+    fn segment(&self, index: usize, video_filter: Option<String>) -> Self {
+        Self {
+            video_stdin: None,
+            child: None,
+            output_path: self.segment_path(index),
+            discard_output: false,
+            metadata: Vec::new(),
+            video_filter,
+            temp_dir: None,
+            threads: self.threads,
+            audio_bitrate_kbps: self.audio_bitrate_kbps,
+            keyframe_interval_secs: self.keyframe_interval_secs,
+            pixel_format: self.pixel_format,
+            color_profile: self.color_profile,
+            width: self.width,
+            height: self.height,
+        }
+    }
 
-        // Check file size
-        match std::fs::metadata(&self.output_path) {
-            Ok(metadata) => {
-                let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
-                tracing::info!(
-                    "[Encoder] Output file size: {:.2} MB ({:.2} KB)",
-                    size_mb,
-                    metadata.len() as f64 / 1024.0
+    /// File segment `index` of this recording is written to.
+    fn segment_path(&self, index: usize) -> PathBuf {
+        let working_path = self.working_path();
+        let stem = working_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        let name = match working_path.extension() {
+            Some(ext) => format!("{}.part{}.{}", stem, index, ext.to_string_lossy()),
+            None => format!("{}.part{}", stem, index),
+        };
+        working_path.with_file_name(name)
+    }
+
+    /// Join the finished segment files `parts`, with the frames each holds,
+    /// into this encoder's file, at the size of the first.
+    fn join_segments(&self, parts: &[(PathBuf, u64)], has_audio: bool) -> Result<(), String> {
+        let (width, height) = concat::probe_video_size(&parts[0].0)?;
+        let frames: Vec<u64> = parts.iter().map(|(_, frames)| *frames).collect();
+        tracing::info!(
+            "[Encoder] Joining {} segments at {}x{}",
+            parts.len(),
+            width,
+            height
+        );
+
+        let mut command = new_ffmpeg_command();
+        for (path, _) in parts {
+            command.args(["-i", path.to_string_lossy().as_ref()]);
+        }
+        command.args([
+            "-filter_complex",
+            &scaling::join_filter(&frames, TARGET_FPS, width, height, has_audio),
+        ]);
+        command.args(["-map", "[v]"]);
+        if has_audio {
+            command.args(["-map", "[a]"]);
+        }
+        self.add_output_args(&mut command, detect_h264_encoder(), has_audio);
+        command
+            .args(["-y"])
+            .arg(self.working_path().to_string_lossy().to_string());
+
+        let inner_command = command.as_inner_mut();
+        inner_command.stdin(Stdio::null());
+        inner_command.stdout(Stdio::null());
+        inner_command.stderr(Stdio::piped());
+
+        let output = inner_command
+            .output()
+            .map_err(|e| format!("Failed to start FFmpeg for joining segments: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!(
+                "FFmpeg segment join failed: {}",
+                stderr.lines().last().unwrap_or("unknown error")
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Move a file, copying and deleting it when `rename` cannot cross devices.
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    match std::fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            std::fs::copy(from, to).map_err(|e| {
+                format!(
+                    "Failed to copy recording to {} (kept at {}): {}",
+                    to.display(),
+                    from.display(),
+                    e
+                )
+            })?;
+            if let Err(e) = std::fs::remove_file(from) {
+                tracing::warn!(
+                    "[Encoder] Failed to remove temporary recording {}: {}",
+                    from.display(),
+                    e
                 );
             }
-            Err(e) => {
-                tracing::warn!("[Encoder] Could not get file metadata: {}", e);
-            }
+            Ok(())
         }
+        Err(e) => Err(format!(
+            "Failed to move recording to {} (kept at {}): {}",
+            to.display(),
+            from.display(),
+            e
+        )),
+    }
+}
 
-        Ok(self.output_path)
+/// Per-recording options for [`encode_frames`].
+#[derive(Debug, Clone)]
+pub struct EncodeOptions {
+    /// Container metadata tags written to the output file.
+    pub metadata: Vec<(String, String)>,
+    /// FFmpeg video filter chain (`-vf`), if any.
+    pub video_filter: Option<String>,
+    /// Local directory to record into before moving to the output path.
+    pub temp_dir: Option<PathBuf>,
+    /// Threads the video encoder uses.
+    pub threads: EncoderThreads,
+    /// AAC audio bitrate in kbps.
+    pub audio_bitrate_kbps: u32,
+    /// Seconds between video keyframes.
+    pub keyframe_interval_secs: f32,
+    /// Lowering the resolution of later recordings when frames are dropped.
+    pub scaling: DynamicResolutionScaling,
+    /// Set while the system sleeps; the encoder holds its timeline until cleared.
+    pub suspended: Arc<AtomicBool>,
+    /// Directory recordings are saved to; the system default when `None`.
+    pub output_dir: Option<PathBuf>,
+    /// Create a missing output directory instead of failing.
+    pub create_output_dir: bool,
+    /// Webcam overlaid on the recording, if any.
+    pub pip: Option<PipConfig>,
+    /// Color profile of the captured display; sRGB when `None`.
+    pub color_profile: Option<ColorProfile>,
+    /// Set to the width of the first frame once it arrives.
+    pub frame_width: Arc<AtomicU32>,
+    /// Drops frames while the encoder falls behind.
+    pub load_shedding: Arc<LoadShedding>,
+    /// Level the audio of a microphone-only recording is normalized to, in
+    /// dBFS; `None` leaves it as captured.
+    pub mic_agc_target_dbfs: Option<f32>,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            metadata: Vec::new(),
+            video_filter: None,
+            temp_dir: None,
+            threads: EncoderThreads::Auto,
+            audio_bitrate_kbps: DEFAULT_AUDIO_BITRATE_KBPS,
+            keyframe_interval_secs: DEFAULT_KEYFRAME_INTERVAL_SECS,
+            scaling: DynamicResolutionScaling::default(),
+            suspended: Arc::new(AtomicBool::new(false)),
+            output_dir: None,
+            create_output_dir: true,
+            pip: None,
+            color_profile: None,
+            frame_width: Arc::new(AtomicU32::new(0)),
+            load_shedding: Arc::new(LoadShedding::default()),
+            mic_agc_target_dbfs: None,
+        }
     }
 }
 
-/// Generate a unique output filename in the default output directory (Videos folder).
-pub fn generate_output_path() -> Result<PathBuf, String> {
-    // Use system Videos directory or fallback to temp
-    let output_dir = get_default_output_dir()?;
+/// Number of threads FFmpeg's video encoders use.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EncoderThreads {
+    /// Let FFmpeg pick, usually one thread per core
+    #[default]
+    Auto,
+    /// Encode on one thread, leaving the other cores to capture
+    Single,
+    /// Encode on a fixed number of threads
+    Count(u8),
+}
 
-    // Ensure directory exists
-    if !output_dir.exists() {
-        std::fs::create_dir_all(&output_dir)
-            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+impl EncoderThreads {
+    /// Value for FFmpeg's `-threads` option (0 lets FFmpeg pick).
+    pub fn ffmpeg_threads(self) -> u8 {
+        match self {
+            EncoderThreads::Auto => 0,
+            EncoderThreads::Single => 1,
+            EncoderThreads::Count(count) => count,
+        }
+    }
+
+    /// Whether VP9 encoding is split into tiles encoded in parallel.
+    /// Only worth it with enough threads to work on the tiles.
+    pub fn vp9_tiling(self) -> bool {
+        matches!(self, EncoderThreads::Count(count) if count >= 4)
+    }
+
+    /// Check that a fixed thread count is at least 1.
+    pub fn validate(self) -> Result<(), String> {
+        match self {
+            EncoderThreads::Count(0) => Err("Encoder thread count must be at least 1".to_string()),
+            _ => Ok(()),
+        }
     }
+}
+
+/// Check an FFmpeg video filter chain by running it against a synthetic source.
+///
+/// Returns FFmpeg's error output if the chain is rejected.
+pub fn validate_video_filter(filter: &str) -> Result<(), String> {
+    let output = Command::new(resolve_ffmpeg_path())
+        .args(["-hide_banner", "-loglevel", "error"])
+        .args(["-f", "lavfi", "-i", "nullsrc"])
+        .args(["-vf", filter])
+        .args(["-t", "0.1", "-f", "null", "-"])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if stderr.is_empty() {
+        Err(format!(
+            "FFmpeg rejected the filter (exit code {:?})",
+            output.status.code()
+        ))
+    } else {
+        Err(stderr)
+    }
+}
+
+/// Generate a unique output filename in `output_dir`, or the default output
+/// directory (Videos folder) when `None`.
+///
+/// A missing directory is created, parents included, when
+/// `create_if_missing` is set and is an error otherwise.
+pub fn generate_output_path(
+    output_dir: Option<&Path>,
+    create_if_missing: bool,
+) -> Result<PathBuf, String> {
+    let output_dir = prepare_output_dir(output_dir, create_if_missing)?;
 
     let timestamp = Local::now().format("%Y-%m-%d_%H%M%S");
     let filename = format!("recording_{}.mp4", timestamp);
@@ -386,6 +785,46 @@ pub fn generate_output_path() -> Result<PathBuf, String> {
     Ok(output_dir.join(filename))
 }
 
+/// Generate a unique PNG screenshot filename, like [`generate_output_path`].
+pub fn generate_screenshot_path(
+    output_dir: Option<&Path>,
+    create_if_missing: bool,
+) -> Result<PathBuf, String> {
+    let output_dir = prepare_output_dir(output_dir, create_if_missing)?;
+
+    let timestamp = Local::now().format("%Y-%m-%d_%H%M%S%.3f");
+    Ok(output_dir.join(format!("screenshot_{}.png", timestamp)))
+}
+
+/// Resolve the output directory, creating it if missing and allowed.
+fn prepare_output_dir(
+    output_dir: Option<&Path>,
+    create_if_missing: bool,
+) -> Result<PathBuf, String> {
+    let output_dir = match output_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => get_default_output_dir()?,
+    };
+
+    if !output_dir.exists() {
+        if !create_if_missing {
+            return Err(format!(
+                "Output directory does not exist: {}",
+                output_dir.display()
+            ));
+        }
+        std::fs::create_dir_all(&output_dir).map_err(|e| {
+            format!(
+                "Failed to create output directory {}: {}",
+                output_dir.display(),
+                e
+            )
+        })?;
+        tracing::info!("[Encoder] Created output directory {:?}", output_dir);
+    }
+    Ok(output_dir)
+}
+
 /// Get the default output directory (system Videos folder or temp fallback).
 fn get_default_output_dir() -> Result<PathBuf, String> {
     let user_dirs = UserDirs::new().ok_or("Could not determine user directories")?;
@@ -435,6 +874,21 @@ fn get_default_output_dir() -> Result<PathBuf, String> {
 
 /// Target frame rate for output video
 const TARGET_FPS: u64 = 30;
+
+/// How far behind real time frame slots are filled, so frames that reach
+/// the encoder late still land in the slot for the time they were presented.
+const FRAME_LATENCY: Duration = Duration::from_millis(50);
+
+/// Most frames held back waiting for their slot. Only reached if a source's
+/// timestamps run ahead of the clock.
+const MAX_PENDING_FRAMES: usize = 30;
+
+/// Frames between keyframes (`-g`) for a keyframe every `interval_secs` at
+/// `fps`, at least 1.
+fn gop_size(fps: u64, interval_secs: f32) -> u64 {
+    ((fps as f64 * interval_secs as f64).round() as u64).max(1)
+}
+
 /// Compute the exact time a given frame number should be written.
 /// Uses multiplication (not accumulated addition) so there is zero
 /// truncation drift regardless of recording length.
@@ -442,6 +896,21 @@ const TARGET_FPS: u64 = 30;
 fn frame_time(start: Instant, frame_number: u64) -> Instant {
     start + Duration::from_nanos(frame_number * 1_000_000_000 / TARGET_FPS)
 }
+
+/// Take the newest of `pending` presented at or before `slot_us`, dropping
+/// the older ones it replaces.
+fn take_presented(pending: &mut VecDeque<CapturedFrame>, slot_us: u64) -> Option<CapturedFrame> {
+    let mut presented = None;
+    while pending.len() > MAX_PENDING_FRAMES
+        || pending
+            .front()
+            .is_some_and(|frame| frame.timestamp_us <= slot_us)
+    {
+        presented = pending.pop_front();
+    }
+    presented
+}
+
 /// Helper: compute the signed difference between two `SystemTime` values
 /// in seconds.  Returns `a - b` as a float (positive if a is after b).
 fn systemtime_diff_secs(a: SystemTime, b: SystemTime) -> f64 {
@@ -451,6 +920,16 @@ fn systemtime_diff_secs(a: SystemTime, b: SystemTime) -> f64 {
     }
 }
 
+/// Run captured audio through the microphone AGC, if enabled, creating it
+/// at the audio's sample rate on the first chunk.
+fn apply_agc(agc: &mut Option<MicAgc>, target_dbfs: Option<f32>, data: &mut StreamingAudioData) {
+    let Some(target_dbfs) = target_dbfs else {
+        return;
+    };
+    agc.get_or_insert_with(|| MicAgc::new(data.sample_rate, target_dbfs))
+        .process(&mut data.samples);
+}
+
 /// Unified encoding function that receives video frames and optionally muxes
 /// audio from vtx-engine's `RawAudioData` events in real-time.
 ///
@@ -458,6 +937,9 @@ fn systemtime_diff_secs(a: SystemTime, b: SystemTime) -> f64 {
 ///
 /// Both audio and video are paced to wall-clock time.  Audio uses
 /// `sample_offset` positioning with a `SystemTime`-based T=0 alignment.
+/// Each video slot shows the newest frame the OS presented by the slot's
+/// time (`CapturedFrame::timestamp_us`), [`FRAME_LATENCY`] behind real time,
+/// rather than whichever frame happened to arrive last.
 /// Video uses a dedicated stdin writer thread so the pacing loop is never
 /// blocked by FFmpeg's stdin backpressure (which occurs during H.264 codec
 /// initialization and whenever the encoder falls behind).  The pacing loop
@@ -471,6 +953,7 @@ pub fn encode_frames(
     stop_flag: Arc<AtomicBool>,
     output_path: Option<PathBuf>,
     audio_capture_start: Option<SystemTime>,
+    options: EncodeOptions,
 ) -> Result<PathBuf, String> {
     let has_audio = audio_rx.is_some();
     tracing::debug!(
@@ -483,9 +966,14 @@ pub fn encode_frames(
         tracing::debug!("[Encoder] recv() returned None - channel closed without frames");
         "No frames received".to_string()
     })?;
+    preview::offer(&first_frame);
+
+    let metrics = crate::metrics::metrics();
+    let _active = crate::metrics::ActiveRecording::start();
 
     let frame_width = first_frame.width;
     let frame_height = first_frame.height;
+    options.frame_width.store(frame_width, Ordering::Relaxed);
     let frame_data_size = first_frame.data.len();
     tracing::info!(
         "[Encoder] Got first frame: {}x{}, data size: {} bytes",
@@ -501,8 +989,41 @@ pub fn encode_frames(
         None
     };
 
+    let output_path = match output_path {
+        Some(path) => path,
+        None => generate_output_path(options.output_dir.as_deref(), options.create_output_dir)?,
+    };
+
     // Create and start the video encoder
-    let mut encoder = VideoEncoder::new_with_options(frame_width, frame_height, output_path)?;
+    let mut encoder = VideoEncoder::new_with_options(
+        frame_width,
+        frame_height,
+        first_frame.pixel_format,
+        Some(output_path),
+    )?;
+
+    // Open the webcam before FFmpeg starts, since the camera can take a
+    // while to deliver its first frame. A camera that fails to open is
+    // logged and the recording continues without the overlay.
+    let webcam = options.pip.as_ref().and_then(|pip| {
+        let width = pip::overlay_width(pip, encoder.width);
+        match WebcamCapture::start(&pip.camera_id, width) {
+            Ok(capture) => Some((capture, pip.position)),
+            Err(e) => {
+                tracing::warn!("[Encoder] Recording without webcam overlay: {}", e);
+                None
+            }
+        }
+    });
+
+    let mut scaler = ScaleController::new(options.scaling, 1.0);
+    encoder.set_metadata(options.metadata);
+    encoder.set_video_filter(options.video_filter.clone());
+    encoder.set_temp_dir(options.temp_dir);
+    encoder.set_threads(options.threads);
+    encoder.set_audio_bitrate(options.audio_bitrate_kbps);
+    encoder.set_keyframe_interval(options.keyframe_interval_secs);
+    encoder.set_color_profile(options.color_profile.unwrap_or_default());
     let pipe_path = audio_pipe.as_ref().map(|p| p.path().to_string());
     encoder.start(pipe_path.as_deref())?;
 
@@ -511,6 +1032,7 @@ pub fn encode_frames(
     // Write the first video frame BEFORE opening the audio pipe.
     // FFmpeg reads stdin (input 0) first, then opens the pipe (input 1).
     encoder.write_frame(&first_frame)?;
+    metrics.frames_encoded_total.inc();
 
     // Take stdin out of the encoder so a dedicated writer thread can own it.
     // This decouples the pacing loop from FFmpeg's stdin backpressure.
@@ -533,8 +1055,11 @@ pub fn encode_frames(
     // receipt) would make the pacing loop think it's behind by the setup
     // duration, causing it to send extra frames and making video PTS
     // gradually drift ahead of audio PTS.
-    let video_start_instant = Instant::now();
-    let video_t0_system = SystemTime::now();
+    //
+    // Slots are filled FRAME_LATENCY behind real time, so the video
+    // timeline, and the audio aligned to it, starts that much earlier.
+    let mut video_start_instant = Instant::now();
+    let video_t0_system = SystemTime::now() - FRAME_LATENCY;
 
     tracing::debug!("[Encoder] Encoder initialized, entering main loop...");
 
@@ -548,25 +1073,11 @@ pub fn encode_frames(
     //
     // After codec init, FFmpeg processes frames as fast as they arrive,
     // draining the backlog and then keeping pace with real-time.
-    let (video_data_tx, video_data_rx) = std::sync::mpsc::sync_channel::<Vec<u8>>(300); // ~10s buffer at 30fps
-    let stdin_thread = {
-        let mut stdin = video_stdin;
-        std::thread::spawn(move || {
-            let mut frames_written = 0u64;
-            while let Ok(data) = video_data_rx.recv() {
-                frames_written += 1;
-                if stdin.write_all(&data).is_err() {
-                    tracing::debug!("[StdinWriter] Write error, exiting");
-                    break;
-                }
-            }
-            tracing::info!(
-                "[StdinWriter] Channel closed, wrote {} frames total",
-                frames_written
-            );
-            drop(stdin); // Closes FFmpeg's stdin → signals EOF
-        })
-    };
+    let backlog = Arc::new(Backlog::default());
+    let (mut video_data_tx, mut stdin_thread) = spawn_stdin_writer(video_stdin, backlog.clone());
+
+    // Audio pipes of segments started after a scale change
+    let (segment_pipe_tx, segment_pipe_rx) = std::sync::mpsc::channel();
 
     // ── Audio writer thread ──
     //
@@ -574,18 +1085,29 @@ pub fn encode_frames(
     // video frame count -- if audio stopped writing, FFmpeg would starve
     // on its audio input and stop reading video, deadlocking the pipeline.
     let audio_thread = if let Some(mut rx) = audio_rx.take() {
-        let mut pipe = audio_pipe
-            .take()
-            .expect("audio_pipe must exist when audio_rx is Some");
+        let mut pipe = SegmentedPipe::new(
+            audio_pipe
+                .take()
+                .expect("audio_pipe must exist when audio_rx is Some"),
+            segment_pipe_rx,
+            TARGET_FPS,
+        );
         let audio_stop = stop_flag.clone();
+        let audio_suspended = options.suspended.clone();
         let audio_t0 = audio_capture_start.unwrap_or(video_t0_system);
         let video_t0 = video_t0_system;
+        let agc_target_dbfs = options.mic_agc_target_dbfs;
         Some(std::thread::spawn(move || -> Result<u64, String> {
+            // Only set for a microphone-only recording, so the gain never
+            // reaches system audio
+            let mut agc = None;
             let mut samples_written: u64 = 0;
             let mut sample_rate: u32 = 48000;
             let mut first_chunk = true;
             let mut initial_skip_samples: u64 = 0;
             let mut last_diag = Instant::now();
+            // Set after a suspension, so audio continues where it stopped
+            let mut resync_after_suspend = false;
 
             let t0_offset_secs = systemtime_diff_secs(video_t0, audio_t0);
 
@@ -603,12 +1125,15 @@ pub fn encode_frames(
                 if audio_stop.load(Ordering::Relaxed) {
                     loop {
                         match rx.try_recv() {
-                            Ok(EngineEvent::RawAudioData(data)) => {
-                                let pcm = f32_mono_to_s16le(&data.samples);
-                                if pipe.write_all(&pcm).is_err() {
+                            Ok(EngineEvent::RawAudioData(mut data)) => {
+                                apply_agc(&mut agc, agc_target_dbfs, &mut data);
+                                if pipe.write_samples(&data.samples).is_err() {
                                     break;
                                 }
                                 samples_written += data.samples.len() as u64;
+                                metrics
+                                    .audio_samples_total
+                                    .inc_by(data.samples.len() as u64);
                             }
                             Ok(_) => {}
                             _ => break,
@@ -618,7 +1143,19 @@ pub fn encode_frames(
                     break;
                 }
 
-                let data = match rx.try_recv() {
+                // Drop audio while the system sleeps, like the video frames
+                if audio_suspended.load(Ordering::Relaxed) {
+                    while !matches!(
+                        rx.try_recv(),
+                        Err(broadcast::error::TryRecvError::Empty
+                            | broadcast::error::TryRecvError::Closed)
+                    ) {}
+                    resync_after_suspend = true;
+                    std::thread::sleep(Duration::from_millis(10));
+                    continue;
+                }
+
+                let mut data = match rx.try_recv() {
                     Ok(EngineEvent::RawAudioData(d)) => d,
                     Ok(_) => continue,
                     Err(broadcast::error::TryRecvError::Empty) => {
@@ -638,6 +1175,7 @@ pub fn encode_frames(
                 if first_chunk {
                     first_chunk = false;
                     sample_rate = data.sample_rate;
+                    pipe.set_sample_rate(sample_rate);
 
                     initial_skip_samples = if t0_offset_secs > 0.0 {
                         (t0_offset_secs * sample_rate as f64).round() as u64
@@ -655,6 +1193,7 @@ pub fn encode_frames(
                             );
                             pipe.write_silence(pad as usize)?;
                             samples_written += pad;
+                            metrics.audio_samples_total.inc_by(pad);
                         }
                     }
 
@@ -668,6 +1207,13 @@ pub fn encode_frames(
                     );
                 }
 
+                apply_agc(&mut agc, agc_target_dbfs, &mut data);
+
+                if resync_after_suspend {
+                    resync_after_suspend = false;
+                    initial_skip_samples = data.sample_offset.saturating_sub(samples_written);
+                }
+
                 let target_pos = data.sample_offset.saturating_sub(initial_skip_samples);
                 let chunk_len = data.samples.len() as u64;
 
@@ -679,6 +1225,7 @@ pub fn encode_frames(
                     let gap = target_pos - samples_written;
                     pipe.write_silence(gap as usize)?;
                     samples_written += gap;
+                    metrics.audio_samples_total.inc_by(gap);
                 }
 
                 let skip_front = if samples_written > target_pos {
@@ -688,9 +1235,9 @@ pub fn encode_frames(
                 };
                 let usable = &data.samples[skip_front.min(data.samples.len())..];
                 if !usable.is_empty() {
-                    let pcm = f32_mono_to_s16le(usable);
-                    pipe.write_all(&pcm)?;
+                    pipe.write_samples(usable)?;
                     samples_written += usable.len() as u64;
+                    metrics.audio_samples_total.inc_by(usable.len() as u64);
                 }
 
                 let now_instant = Instant::now();
@@ -709,7 +1256,7 @@ pub fn encode_frames(
                 }
             }
 
-            drop(pipe);
+            pipe.close();
             tracing::debug!(
                 "[AudioWriter] Exiting: wrote {} samples ({:.1}s)",
                 samples_written,
@@ -736,13 +1283,29 @@ pub fn encode_frames(
 
     let enc_width = encoder.width;
     let enc_height = encoder.height;
+    let suspended = options.suspended;
+    let load_shedding = options.load_shedding;
     let mut frames_written = 1u64; // first frame already sent
     let mut last_frame = first_frame;
+    // Frames received but not yet due, oldest first
+    let mut pending_frames: VecDeque<CapturedFrame> = VecDeque::new();
     let mut next_frame_time = frame_time(video_start_instant, frames_written);
 
     let mut consecutive_empty_polls = 0u32;
     const MAX_EMPTY_POLLS: u32 = 100;
 
+    // Frames sent since `fps_window_start`, for the encode_fps metric
+    let mut fps_window_start = Instant::now();
+    let mut fps_window_frames = 0u64;
+    // Frame slots skipped in the same window, for dynamic resolution scaling
+    let mut fps_window_skipped = 0u64;
+    // Scale to start a new segment at, from the next frame sent
+    let mut rescale: Option<f32> = None;
+    // Segments before the current one, and where the current one starts
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut segment_encoder: Option<VideoEncoder> = None;
+    let mut segment_start = 0u64;
+
     loop {
         let now = Instant::now();
 
@@ -751,10 +1314,50 @@ pub fn encode_frames(
             break;
         }
 
-        // Receive latest video frame (non-blocking)
+        // While the system sleeps, mark the gap with one black frame and
+        // send nothing else. On wake, the timeline continues from where it
+        // stopped instead of catching up on the missed slots.
+        if suspended.load(Ordering::Relaxed) {
+            let black = FrameData::from(last_frame.pixel_format.black_frame(enc_width, enc_height));
+            if video_data_tx.send(black.into()).is_err() {
+                tracing::debug!("[Encoder] Stdin writer channel closed");
+                break;
+            }
+            frames_written += 1;
+            metrics.frames_encoded_total.inc();
+
+            tracing::info!("[Encoder] Suspended at frame {}", frames_written);
+            // Instant doesn't advance during sleep on Linux and macOS; it
+            // moves the timeline, the wall clock measures the sleep
+            let suspended_at = Instant::now();
+            let suspended_wall = SystemTime::now();
+            while suspended.load(Ordering::Relaxed) && !stop_flag.load(Ordering::Relaxed) {
+                // Frames captured around sleep are stale; drop them
+                pending_frames.clear();
+                while frame_rx.try_recv().is_ok() {}
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            video_start_instant += suspended_at.elapsed();
+            next_frame_time = frame_time(video_start_instant, frames_written);
+            tracing::info!(
+                "[Encoder] Resumed after {:.1}s",
+                suspended_wall
+                    .elapsed()
+                    .unwrap_or_else(|_| suspended_at.elapsed())
+                    .as_secs_f64()
+            );
+            continue;
+        }
+
+        // Receive the next video frame (non-blocking); it is shown from the
+        // slot matching its timestamp
         match frame_rx.try_recv() {
             Ok(frame) => {
-                last_frame = frame;
+                // Frames waiting for FFmpeg mean it is falling behind
+                if load_shedding.admit(backlog.lag(TARGET_FPS)) {
+                    preview::offer(&frame);
+                    pending_frames.push_back(frame);
+                }
                 consecutive_empty_polls = 0;
             }
             Err(mpsc::error::TryRecvError::Empty) => {
@@ -778,22 +1381,18 @@ pub fn encode_frames(
 
         // Send one frame per ~33.3ms slot (exact 30fps via frame_time()).
         if next_frame_time <= now {
+            let slot_us = types::timestamp_us(next_frame_time)
+                .saturating_sub(FRAME_LATENCY.as_micros() as u64);
+            if let Some(frame) = take_presented(&mut pending_frames, slot_us) {
+                last_frame = frame;
+            }
+
             // Prepare frame data (handle cropping if frame is larger than encoder)
             let frame_data = if last_frame.width == enc_width && last_frame.height == enc_height {
                 last_frame.data.clone()
-            } else if last_frame.width >= enc_width && last_frame.height >= enc_height {
-                // Crop to encoder dimensions
-                let src_row_bytes = (last_frame.width * 4) as usize;
-                let dst_row_bytes = (enc_width * 4) as usize;
-                let mut cropped = Vec::with_capacity(dst_row_bytes * enc_height as usize);
-                for y in 0..enc_height as usize {
-                    let src_start = y * src_row_bytes;
-                    let src_end = src_start + dst_row_bytes;
-                    if src_end <= last_frame.data.len() {
-                        cropped.extend_from_slice(&last_frame.data[src_start..src_end]);
-                    }
-                }
-                cropped
+            } else if let Some(cropped) = last_frame.crop(0, 0, enc_width, enc_height) {
+                // Cropped to encoder dimensions
+                cropped.data
             } else {
                 // Frame too small -- skip this slot
                 frames_written += 1;
@@ -801,12 +1400,84 @@ pub fn encode_frames(
                 continue;
             };
 
+            // Draw the webcam over copies of the rows it covers; captured
+            // frames are shared
+            let webcam_frame = webcam
+                .as_ref()
+                .and_then(|(capture, position)| Some((capture.latest_frame()?, *position)));
+            let patches = match webcam_frame {
+                Some((image, position)) => {
+                    let format = last_frame.pixel_format;
+                    pip::overlay_patches(
+                        &frame_data,
+                        enc_width,
+                        enc_height,
+                        format,
+                        &image,
+                        position,
+                    )
+                }
+                None => Vec::new(),
+            };
+            let frame_data = PatchedFrame::new(frame_data, patches);
+
+            // A new scale starts a new segment, from this frame on
+            if let Some(scale) = rescale.take() {
+                let filter = scaling::with_scale_filter(options.video_filter.clone(), scale);
+                match start_segment(&encoder, segments.len() + 1, filter, has_audio) {
+                    Ok((next, stdin, pipe)) => {
+                        // The previous segment's FFmpeg finishes its backlog
+                        // once its channel closes
+                        let (tx, thread) = spawn_stdin_writer(stdin, backlog.clone());
+                        video_data_tx = tx;
+                        segments.push(Segment {
+                            encoder: segment_encoder.replace(next),
+                            stdin_thread: std::mem::replace(&mut stdin_thread, thread),
+                            first_frame: segment_start,
+                        });
+                        segment_start = frames_written;
+                        if let Some(pipe) = pipe {
+                            let _ = segment_pipe_tx.send((pipe, frames_written));
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "[Encoder] Failed to start a segment at scale {:.2}: {}",
+                            scale,
+                            e
+                        )
+                    }
+                }
+            }
+
             // Send to the stdin writer channel (blocks if full = backpressure).
             if video_data_tx.send(frame_data).is_err() {
                 tracing::debug!("[Encoder] Stdin writer channel closed");
                 break;
             }
             frames_written += 1;
+            metrics.frames_encoded_total.inc();
+            fps_window_frames += 1;
+            let fps_window = fps_window_start.elapsed();
+            if fps_window >= Duration::from_secs(1) {
+                metrics
+                    .encode_fps
+                    .set(fps_window_frames as f64 / fps_window.as_secs_f64());
+                let previous_scale = scaler.scale();
+                if let Some(scale) =
+                    scaler.record_second(fps_window_frames + fps_window_skipped, fps_window_skipped)
+                {
+                    tracing::info!(
+                        "[Encoder] Resolution scale {:.2} -> {:.2}: starting a new segment",
+                        previous_scale,
+                        scale
+                    );
+                    rescale = Some(scale);
+                }
+                fps_window_start = Instant::now();
+                fps_window_frames = 0;
+                fps_window_skipped = 0;
+            }
             // Compute next frame time from frame count, not by adding an interval.
             // This eliminates truncation drift: frame N is always at exactly
             // N * 1_000_000_000 / 30 nanoseconds from start.
@@ -819,6 +1490,7 @@ pub fn encode_frames(
                 let target_frame = elapsed_ns * TARGET_FPS / 1_000_000_000;
                 if target_frame > frames_written {
                     let skip = target_frame - frames_written;
+                    fps_window_skipped += skip;
                     frames_written = target_frame;
                     next_frame_time = frame_time(video_start_instant, frames_written);
                     tracing::debug!("[Encoder] Pacing fell behind, skipped {} slots", skip);
@@ -833,33 +1505,30 @@ pub fn encode_frames(
         }
     }
 
+    load_shedding.finish();
+
     // Drain remaining frames from the capture channel
     tracing::info!("[Encoder] Draining remaining frames from capture channel...");
     let mut drain_count = 0u64;
     loop {
-        match frame_rx.try_recv() {
+        let next = match pending_frames.pop_front() {
+            Some(frame) => Ok(frame),
+            None => frame_rx.try_recv(),
+        };
+        match next {
             Ok(frame) => {
                 // Prepare frame data (handle cropping if frame is larger than encoder)
                 let frame_data = if frame.width == enc_width && frame.height == enc_height {
                     frame.data.clone()
-                } else if frame.width >= enc_width && frame.height >= enc_height {
-                    let src_row_bytes = (frame.width * 4) as usize;
-                    let dst_row_bytes = (enc_width * 4) as usize;
-                    let mut cropped = Vec::with_capacity(dst_row_bytes * enc_height as usize);
-                    for y in 0..enc_height as usize {
-                        let src_start = y * src_row_bytes;
-                        let src_end = src_start + dst_row_bytes;
-                        if src_end <= frame.data.len() {
-                            cropped.extend_from_slice(&frame.data[src_start..src_end]);
-                        }
-                    }
-                    cropped
+                } else if let Some(cropped) = frame.crop(0, 0, enc_width, enc_height) {
+                    cropped.data
                 } else {
                     continue;
                 };
 
-                if video_data_tx.send(frame_data).is_ok() {
+                if video_data_tx.send(frame_data.into()).is_ok() {
                     frames_written += 1;
+                    metrics.frames_encoded_total.inc();
                     drain_count += 1;
                 } else {
                     break;
@@ -877,9 +1546,18 @@ pub fn encode_frames(
     // → FFmpeg sees EOF on video input.
     tracing::info!("[Encoder] Closing video channel, waiting for stdin writer...");
     drop(video_data_tx);
-    match stdin_thread.join() {
-        Ok(_) => tracing::info!("[Encoder] Stdin writer thread finished successfully"),
-        Err(e) => tracing::warn!("[Encoder] Stdin writer thread panicked: {:?}", e),
+    if segments.is_empty() {
+        match stdin_thread.join() {
+            Ok(_) => tracing::info!("[Encoder] Stdin writer thread finished successfully"),
+            Err(e) => tracing::warn!("[Encoder] Stdin writer thread panicked: {:?}", e),
+        }
+    } else {
+        // Joined with the other segments' writers
+        segments.push(Segment {
+            encoder: segment_encoder,
+            stdin_thread,
+            first_frame: segment_start,
+        });
     }
 
     // Wait for the audio writer thread to finish and close the pipe.
@@ -911,15 +1589,182 @@ pub fn encode_frames(
         audio_samples_written,
     );
 
+    let result = if segments.is_empty() {
+        encoder.finish()
+    } else {
+        finish_segments(encoder, segments, frames_written, has_audio)
+    };
+    result.inspect(|_| metrics.recordings_total.inc())
+}
+
+/// Queues frames for a stdin writer thread, counting them in the backlog.
+struct FrameSender {
+    tx: std::sync::mpsc::SyncSender<PatchedFrame>,
+    backlog: Arc<Backlog>,
+}
+
+impl FrameSender {
+    /// Queue `frame`, blocking while the queue is full.
+    fn send(&self, frame: PatchedFrame) -> Result<(), std::sync::mpsc::SendError<PatchedFrame>> {
+        self.backlog.push();
+        self.tx.send(frame).inspect_err(|_| self.backlog.pop())
+    }
+}
+
+/// Spawn a thread writing the frames sent on the returned channel to
+/// FFmpeg's stdin, closing it when the channel closes.
+fn spawn_stdin_writer(
+    mut stdin: ChildStdin,
+    backlog: Arc<Backlog>,
+) -> (FrameSender, JoinHandle<()>) {
+    let (video_data_tx, video_data_rx) = std::sync::mpsc::sync_channel::<PatchedFrame>(300); // ~10s buffer at 30fps
+    let thread_backlog = backlog.clone();
+    let thread = std::thread::spawn(move || {
+        let mut frames_written = 0u64;
+        while let Ok(data) = video_data_rx.recv() {
+            frames_written += 1;
+            let result = data.write_to(&mut stdin);
+            thread_backlog.pop();
+            if result.is_err() {
+                tracing::debug!("[StdinWriter] Write error, exiting");
+                break;
+            }
+        }
+        // Frames left after a write error never reach FFmpeg
+        for _ in video_data_rx.try_iter() {
+            thread_backlog.pop();
+        }
+        tracing::info!(
+            "[StdinWriter] Channel closed, wrote {} frames total",
+            frames_written
+        );
+        drop(stdin); // Closes FFmpeg's stdin → signals EOF
+    });
+    let sender = FrameSender {
+        tx: video_data_tx,
+        backlog,
+    };
+    (sender, thread)
+}
+
+/// Part of a recording encoded at one scale.
+struct Segment {
+    /// FFmpeg writing the segment; `None` for the first, written by the
+    /// recording's own encoder
+    encoder: Option<VideoEncoder>,
+    stdin_thread: JoinHandle<()>,
+    /// Frame slot the segment starts at
+    first_frame: u64,
+}
+
+/// Start FFmpeg for segment `index` of `encoder`'s recording, returning it
+/// with its stdin and, with `has_audio`, the audio pipe it reads.
+fn start_segment(
+    encoder: &VideoEncoder,
+    index: usize,
+    video_filter: Option<String>,
+    has_audio: bool,
+) -> Result<(VideoEncoder, ChildStdin, Option<AudioPipe>), String> {
+    let pipe = if has_audio {
+        Some(AudioPipe::create()?)
+    } else {
+        None
+    };
+    let mut segment = encoder.segment(index, video_filter);
+    segment.start(pipe.as_ref().map(|p| p.path()))?;
+    let stdin = segment
+        .take_stdin()
+        .ok_or("Failed to take FFmpeg stdin for writer thread")?;
+    Ok((segment, stdin, pipe))
+}
+
+/// Wait for each segment's FFmpeg and join the segment files into
+/// `encoder`'s recording. Each segment lasts until the next one starts, and
+/// the last until `frames_written`.
+///
+/// If the segments can't be joined, the recording keeps the first one and
+/// the others are left next to it.
+fn finish_segments(
+    mut encoder: VideoEncoder,
+    segments: Vec<Segment>,
+    frames_written: u64,
+    has_audio: bool,
+) -> Result<PathBuf, String> {
+    let mut parts = Vec::new();
+    let ends: Vec<u64> = segments
+        .iter()
+        .skip(1)
+        .map(|segment| segment.first_frame)
+        .chain([frames_written])
+        .collect();
+    for (segment, end) in segments.into_iter().zip(ends) {
+        if segment.stdin_thread.join().is_err() {
+            tracing::warn!("[Encoder] Stdin writer thread panicked");
+        }
+        let frames = end - segment.first_frame;
+        match segment.encoder {
+            None => {
+                encoder.wait()?;
+                let path = encoder.segment_path(0);
+                std::fs::rename(encoder.working_path(), &path)
+                    .map_err(|e| format!("Failed to rename the first segment: {}", e))?;
+                parts.push((path, frames));
+            }
+            Some(mut segment) => match segment.wait() {
+                Ok(()) => parts.push((segment.output_path.clone(), frames)),
+                Err(e) => tracing::warn!(
+                    "[Encoder] Leaving out segment {}: {}",
+                    segment.output_path.display(),
+                    e
+                ),
+            },
+        }
+    }
+
+    match encoder.join_segments(&parts, has_audio) {
+        Ok(()) => {
+            for (path, _) in &parts {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        Err(e) => {
+            tracing::warn!(
+                "[Encoder] Keeping only the first segment, later ones are left next to it: {}",
+                e
+            );
+            std::fs::rename(&parts[0].0, encoder.working_path())
+                .map_err(|e| format!("Failed to restore the first segment: {}", e))?;
+        }
+    }
     encoder.finish()
 }
 
+/// The FFmpeg binary in use and the first line of its `-version` output,
+/// or `None` if it could not be run.
+pub fn probe_ffmpeg() -> (PathBuf, Option<String>) {
+    let ffmpeg = resolve_ffmpeg_path();
+    let version = Command::new(&ffmpeg)
+        .arg("-version")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .next()
+                .map(|line| line.trim().to_string())
+        });
+    (ffmpeg, version)
+}
+
 /// Ensure FFmpeg is available. Should be called once at app startup.
 ///
 /// On Windows and macOS, verifies that the bundled sidecar binary exists and is
 /// executable. On Linux, falls back to runtime auto-download if the system
 /// FFmpeg is not available (though it should be installed as a package dependency).
-pub fn ensure_ffmpeg_blocking() -> Result<(), String> {
+pub fn ensure_ffmpeg_blocking() -> Result<(), FfmpegError> {
     let ffmpeg = resolve_ffmpeg_path();
     tracing::debug!("[FFmpeg] Resolved path: {}", ffmpeg.display());
 
@@ -934,11 +1779,7 @@ pub fn ensure_ffmpeg_blocking() -> Result<(), String> {
             tracing::debug!("[FFmpeg] Binary verified OK");
             Ok(())
         }
-        Ok(status) => Err(format!(
-            "FFmpeg binary at {} exited with status: {}",
-            ffmpeg.display(),
-            status
-        )),
+        Ok(status) => Err(FfmpegError::ExitStatus(status.code())),
         Err(e) => {
             tracing::debug!("[FFmpeg] Binary not found at {}: {}", ffmpeg.display(), e);
             // On Linux, try auto-download as a last resort (system package may
@@ -947,25 +1788,217 @@ pub fn ensure_ffmpeg_blocking() -> Result<(), String> {
             {
                 tracing::debug!("[FFmpeg] Attempting auto-download as fallback...");
                 ffmpeg_sidecar::download::auto_download()
-                    .map_err(|e| format!("FFmpeg not found and auto-download failed: {}", e))
+                    .map_err(|e| FfmpegError::DownloadFailed(e.to_string()))
             }
             #[cfg(not(target_os = "linux"))]
             {
-                Err(format!(
-                    "Bundled FFmpeg not found at {}. The application may not be installed correctly.",
-                    ffmpeg.display()
-                ))
+                Err(FfmpegError::NotFound(format!(
+                    "bundled binary missing at {} ({}). The application may not be installed correctly.",
+                    ffmpeg.display(),
+                    e
+                )))
             }
         }
     }
 }
 
 use omnirec_types::OutputFormat;
-use std::path::Path;
 
-/// Transcode a source MP4 file to the specified output format.
-/// Returns the path to the transcoded file.
-pub fn transcode_video(source_path: &Path, format: OutputFormat) -> Result<PathBuf, String> {
+/// Minimum length of a pause treated as silence when trimming, in seconds.
+const SILENCE_MIN_DURATION_SECS: f64 = 0.5;
+
+/// Silence this close to the start or end of a file counts as touching it.
+const SILENCE_EDGE_TOLERANCE_SECS: f64 = 0.05;
+
+/// Transcode a source MP4 file to the specified output format, then trim
+/// leading and trailing silence if `silence_threshold_db` is set.
+/// Returns the path to the final file (the source itself for MP4).
+///
+/// The encoder settings apply to formats that are re-encoded (WebM); remuxed
+/// and animated formats ignore them.
+pub fn transcode_video(
+    source_path: &Path,
+    format: OutputFormat,
+    silence_threshold_db: Option<f32>,
+    threads: EncoderThreads,
+    audio_bitrate_kbps: u32,
+    keyframe_interval_secs: f32,
+) -> Result<PathBuf, String> {
+    let output_path = convert_format(
+        source_path,
+        format,
+        threads,
+        audio_bitrate_kbps,
+        keyframe_interval_secs,
+    )?;
+
+    if let Some(threshold_db) = silence_threshold_db {
+        // Detect on the MP4 source: formats like GIF carry no audio
+        match detect_silence_boundaries(source_path, threshold_db) {
+            (None, None) => {
+                tracing::debug!("[Trim] No leading or trailing silence found");
+            }
+            (start, end) => {
+                if let Err(e) = trim_in_place(&output_path, start, end) {
+                    tracing::warn!("[Trim] Keeping untrimmed recording: {}", e);
+                }
+            }
+        }
+    }
+
+    Ok(output_path)
+}
+
+/// Find where speech starts and ends in a recording's audio.
+///
+/// Runs FFmpeg's `silencedetect` filter with a noise floor of `threshold_db`
+/// and returns the end of leading silence and the start of trailing silence.
+/// Either is `None` when there is no such silence, and both are `None` when
+/// the file has no audio, is silent throughout, or FFmpeg fails.
+pub fn detect_silence_boundaries(path: &Path, threshold_db: f32) -> (Option<f64>, Option<f64>) {
+    let filter = format!(
+        "silencedetect=noise={}dB:d={}",
+        threshold_db, SILENCE_MIN_DURATION_SECS
+    );
+    let output = Command::new(resolve_ffmpeg_path())
+        .args(["-hide_banner", "-nostats"])
+        .args(["-i", path.to_string_lossy().as_ref()])
+        .args(["-vn", "-af", &filter])
+        .args(["-f", "null", "-"])
+        .stdin(Stdio::null())
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_silence_boundaries(&String::from_utf8_lossy(&output.stderr))
+        }
+        Ok(output) => {
+            tracing::debug!(
+                "[Trim] Silence detection failed with exit code {:?}",
+                output.status.code()
+            );
+            (None, None)
+        }
+        Err(e) => {
+            tracing::debug!("[Trim] Failed to run FFmpeg: {}", e);
+            (None, None)
+        }
+    }
+}
+
+/// Parse `silencedetect` output into speech start and end times.
+fn parse_silence_boundaries(stderr: &str) -> (Option<f64>, Option<f64>) {
+    let mut duration = None;
+    let mut silences: Vec<(f64, Option<f64>)> = Vec::new();
+
+    for line in stderr.lines() {
+        if let Some(rest) = line.trim().strip_prefix("Duration: ") {
+            duration = rest.split(',').next().and_then(parse_timestamp);
+        } else if let Some(value) = field_after(line, "silence_start: ") {
+            silences.push((value, None));
+        } else if let Some(value) = field_after(line, "silence_end: ") {
+            if let Some(last) = silences.last_mut() {
+                last.1 = Some(value);
+            }
+        }
+    }
+
+    let reaches_end = |end: Option<f64>| match (end, duration) {
+        (None, _) => true,
+        (Some(end), Some(duration)) => end >= duration - SILENCE_EDGE_TOLERANCE_SECS,
+        (Some(_), None) => false,
+    };
+
+    let (Some(&first), Some(&last)) = (silences.first(), silences.last()) else {
+        return (None, None);
+    };
+    let leading = first.0 <= SILENCE_EDGE_TOLERANCE_SECS;
+    if leading && reaches_end(first.1) {
+        // Silent throughout; leave the recording alone
+        return (None, None);
+    }
+
+    let speech_start = if leading { first.1 } else { None };
+    let speech_end = if reaches_end(last.1) && last.0 > SILENCE_EDGE_TOLERANCE_SECS {
+        Some(last.0)
+    } else {
+        None
+    };
+    (speech_start, speech_end)
+}
+
+/// The number following `key` in `line`, e.g. `12.5` in `silence_end: 12.5 | ...`.
+fn field_after(line: &str, key: &str) -> Option<f64> {
+    let (_, rest) = line.split_once(key)?;
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// Parse an FFmpeg `HH:MM:SS.ss` timestamp into seconds.
+fn parse_timestamp(value: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in value.trim().split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+/// Cut `path` to the `start`..`end` range (in seconds) with stream copy,
+/// replacing the file.
+fn trim_in_place(path: &Path, start: Option<f64>, end: Option<f64>) -> Result<(), String> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let temp_path = path.with_extension(format!("trimming.{}", extension));
+
+    let mut command = Command::new(resolve_ffmpeg_path());
+    command.args(["-hide_banner", "-loglevel", "error"]);
+    // Input seeking with stream copy starts at the keyframe before `start`
+    if let Some(start) = start {
+        command.args(["-ss", &format!("{:.3}", start)]);
+    }
+    command.args(["-i", path.to_string_lossy().as_ref()]);
+    if let Some(end) = end {
+        let length = end - start.unwrap_or(0.0);
+        command.args(["-t", &format!("{:.3}", length)]);
+    }
+    command
+        .args(["-map", "0", "-c", "copy", "-y"])
+        .arg(&temp_path)
+        .stdin(Stdio::null());
+
+    tracing::debug!(
+        "[Trim] Trimming {} to {:?}..{:?}",
+        path.display(),
+        start,
+        end
+    );
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&temp_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "FFmpeg trim failed: {}",
+            stderr.lines().last().unwrap_or("unknown error")
+        ));
+    }
+
+    std::fs::rename(&temp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp_path);
+        format!("Failed to replace recording with trimmed file: {}", e)
+    })?;
+    tracing::info!("[Trim] Trimmed silence from {}", path.display());
+    Ok(())
+}
+
+/// Convert a source MP4 file to the specified output format.
+/// Returns the path to the converted file.
+fn convert_format(
+    source_path: &Path,
+    format: OutputFormat,
+    threads: EncoderThreads,
+    audio_bitrate_kbps: u32,
+    keyframe_interval_secs: f32,
+) -> Result<PathBuf, String> {
     // Generate output path with new extension
     let output_path = source_path.with_extension(format.extension());
 
@@ -992,6 +2025,20 @@ pub fn transcode_video(source_path: &Path, format: OutputFormat) -> Result<PathB
             command.args(["-c:v", "libvpx-vp9"]);
             command.args(["-crf", "30"]);
             command.args(["-b:v", "0"]);
+            command.args(["-threads", &threads.ffmpeg_threads().to_string()]);
+            command.args([
+                "-g",
+                &gop_size(TARGET_FPS, keyframe_interval_secs).to_string(),
+            ]);
+            if threads.vp9_tiling() {
+                command.args(["-tile-columns", "2"]);
+                command.args(["-row-mt", "1"]);
+            }
+            // Audio is re-encoded with WebM's default codec, Opus
+            let opus_range = AudioCodec::Opus.bitrate_range_kbps();
+            let audio_bitrate_kbps =
+                audio_bitrate_kbps.clamp(*opus_range.start(), *opus_range.end());
+            command.args(["-b:a", &format!("{}k", audio_bitrate_kbps)]);
         }
         OutputFormat::Mkv => {
             // Remux only - copy video stream (very fast)
@@ -1069,3 +2116,157 @@ pub fn transcode_video(source_path: &Path, format: OutputFormat) -> Result<PathB
     tracing::debug!("[Transcode] Successfully created {}", output_path.display());
     Ok(output_path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::generate_test_frame;
+
+    const HEADER: &str = "Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'rec.mp4':\n  Duration: 00:00:20.00, start: 0.000000, bitrate: 512 kb/s\n";
+
+    #[test]
+    fn test_parse_silence_boundaries_leading_and_trailing() {
+        let stderr = format!(
+            "{HEADER}[silencedetect @ 0x1] silence_start: 0\n\
+             [silencedetect @ 0x1] silence_end: 2.5 | silence_duration: 2.5\n\
+             [silencedetect @ 0x1] silence_start: 8\n\
+             [silencedetect @ 0x1] silence_end: 9 | silence_duration: 1\n\
+             [silencedetect @ 0x1] silence_start: 17.25\n"
+        );
+        assert_eq!(parse_silence_boundaries(&stderr), (Some(2.5), Some(17.25)));
+    }
+
+    #[test]
+    fn test_parse_silence_boundaries_trailing_silence_ended_at_eof() {
+        let stderr = format!(
+            "{HEADER}[silencedetect @ 0x1] silence_start: 15\n\
+             [silencedetect @ 0x1] silence_end: 20 | silence_duration: 5\n"
+        );
+        assert_eq!(parse_silence_boundaries(&stderr), (None, Some(15.0)));
+    }
+
+    #[test]
+    fn test_parse_silence_boundaries_ignores_inner_pauses_and_full_silence() {
+        let inner = format!(
+            "{HEADER}[silencedetect @ 0x1] silence_start: 5\n\
+             [silencedetect @ 0x1] silence_end: 6 | silence_duration: 1\n"
+        );
+        assert_eq!(parse_silence_boundaries(&inner), (None, None));
+
+        let silent = format!("{HEADER}[silencedetect @ 0x1] silence_start: 0\n");
+        assert_eq!(parse_silence_boundaries(&silent), (None, None));
+
+        assert_eq!(parse_silence_boundaries(HEADER), (None, None));
+    }
+
+    #[test]
+    fn test_take_presented_picks_newest_due_frame() {
+        let frame = |timestamp_us| CapturedFrame {
+            timestamp_us,
+            ..generate_test_frame(1, 1, 0)
+        };
+        let mut pending: VecDeque<_> = [10_000, 40_000, 70_000].map(frame).into();
+
+        assert!(take_presented(&mut pending, 5_000).is_none());
+        let shown = take_presented(&mut pending, 45_000).unwrap();
+        assert_eq!(shown.timestamp_us, 40_000);
+        assert_eq!(pending.len(), 1);
+        assert!(take_presented(&mut pending, 60_000).is_none());
+
+        // Frames stamped far ahead are not held back forever
+        let mut pending: VecDeque<_> = (1..=MAX_PENDING_FRAMES as u64 + 2)
+            .map(|i| frame(i * 1_000_000))
+            .collect();
+        let shown = take_presented(&mut pending, 0).unwrap();
+        assert_eq!(shown.timestamp_us, 2_000_000);
+        assert_eq!(pending.len(), MAX_PENDING_FRAMES);
+    }
+
+    #[test]
+    fn test_encoder_threads_options() {
+        assert_eq!(EncoderThreads::Auto.ffmpeg_threads(), 0);
+        assert_eq!(EncoderThreads::Single.ffmpeg_threads(), 1);
+        assert_eq!(EncoderThreads::Count(12).ffmpeg_threads(), 12);
+
+        assert!(!EncoderThreads::Auto.vp9_tiling());
+        assert!(!EncoderThreads::Count(3).vp9_tiling());
+        assert!(EncoderThreads::Count(4).vp9_tiling());
+
+        assert!(EncoderThreads::Count(0).validate().is_err());
+        assert!(EncoderThreads::Count(1).validate().is_ok());
+    }
+
+    #[test]
+    fn test_gop_size() {
+        assert_eq!(gop_size(30, 2.0), 60);
+        assert_eq!(gop_size(30, 0.5), 15);
+        assert_eq!(gop_size(30, 0.1), 3);
+        // Never below one frame
+        assert_eq!(gop_size(30, 0.01), 1);
+    }
+
+    #[test]
+    fn test_encoder_threads_serde() {
+        assert_eq!(
+            serde_json::to_string(&EncoderThreads::Single).unwrap(),
+            r#""single""#
+        );
+        let parsed: EncoderThreads = serde_json::from_str(r#"{"count":8}"#).unwrap();
+        assert_eq!(parsed, EncoderThreads::Count(8));
+    }
+
+    #[test]
+    fn test_working_path_uses_temp_dir() {
+        let output = PathBuf::from("/videos/recording_1.mp4");
+        let mut encoder =
+            VideoEncoder::new_with_options(64, 64, PixelFormat::Bgra, Some(output.clone()))
+                .unwrap();
+        assert_eq!(encoder.working_path(), output);
+
+        encoder.set_temp_dir(Some(PathBuf::from("/fast")));
+        assert_eq!(
+            encoder.working_path(),
+            PathBuf::from("/fast/recording_1.mp4")
+        );
+    }
+
+    #[test]
+    fn test_generate_output_path_creates_missing_parents() {
+        let dir = std::env::temp_dir().join(format!("omnirec-output-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let output_dir = dir.join("nested").join("recordings");
+
+        let err = generate_output_path(Some(&output_dir), false).unwrap_err();
+        assert!(err.starts_with("Output directory does not exist"));
+        assert!(!dir.exists());
+
+        let path = generate_output_path(Some(&output_dir), true).unwrap();
+        assert!(output_dir.is_dir());
+        assert_eq!(path.parent(), Some(output_dir.as_path()));
+
+        let screenshot = generate_screenshot_path(Some(&output_dir), false).unwrap();
+        assert_eq!(screenshot.parent(), Some(output_dir.as_path()));
+        assert_eq!(screenshot.extension().unwrap(), "png");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_move_file() {
+        let dir = std::env::temp_dir().join(format!("omnirec-move-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let from = dir.join("a.mp4");
+        let to = dir.join("b.mp4");
+        std::fs::write(&from, b"data").unwrap();
+
+        move_file(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(std::fs::read(&to).unwrap(), b"data");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("01:02:03.50"), Some(3723.5));
+        assert_eq!(parse_timestamp("N/A"), None);
+    }
+}
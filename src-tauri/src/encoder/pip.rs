@@ -0,0 +1,379 @@
+//! Picture-in-picture webcam overlay.
+//!
+//! [`overlay_patches`] draws a webcam frame from
+//! [`WebcamCapture`](crate::capture::webcam::WebcamCapture) into a corner of
+//! a screen frame before it is sent to FFmpeg. Screen frames are shared with
+//! the preview and repeated slots, so only the rows the overlay covers are
+//! copied, and a [`PatchedFrame`] writes them in place of the originals.
+//!
+//! BGRA frames are overlaid directly; for NV12 and YUV420 frames the webcam
+//! image is converted to BT.601 limited-range YUV (FFmpeg's assumption for
+//! untagged input) and each plane is overlaid separately.
+
+use crate::capture::frame_pool::FrameData;
+use crate::capture::types::PixelFormat;
+use image::imageops;
+use image::{GrayImage, ImageBuffer, Luma, LumaA, RgbImage, Rgba};
+use omnirec_types::{PipConfig, PipPosition};
+use std::io::Write;
+use std::ops::Range;
+
+/// Gap between the overlay and the edges of the recording, as a fraction of
+/// the recording's width.
+const MARGIN_FRACTION: f32 = 0.02;
+
+/// Overlay width for a recording `frame_width` pixels wide. Even, so the
+/// overlay lines up with subsampled chroma.
+pub fn overlay_width(config: &PipConfig, frame_width: u32) -> u32 {
+    (((frame_width as f32 * config.size_fraction) as u32) & !1).max(2)
+}
+
+/// Top-left corner of a `width`x`height` overlay at `position` in a
+/// `frame_width`x`frame_height` frame. Even, like the overlay size.
+pub fn overlay_origin(
+    position: PipPosition,
+    frame_width: u32,
+    frame_height: u32,
+    width: u32,
+    height: u32,
+) -> (i64, i64) {
+    let margin = (frame_width as f32 * MARGIN_FRACTION) as i64 & !1;
+    let right = (frame_width as i64 - width as i64 - margin) & !1;
+    let bottom = (frame_height as i64 - height as i64 - margin) & !1;
+    match position {
+        PipPosition::TopLeft => (margin, margin),
+        PipPosition::TopRight => (right, margin),
+        PipPosition::BottomLeft => (margin, bottom),
+        PipPosition::BottomRight => (right, bottom),
+    }
+}
+
+/// Bytes of a frame replaced by an overlay: offsets into the frame, in
+/// order, each with the bytes that go there.
+pub type Patches = Vec<(usize, Vec<u8>)>;
+
+/// A frame on its way to FFmpeg, with any overlay drawn over it as patches.
+pub struct PatchedFrame {
+    data: FrameData,
+    patches: Patches,
+}
+
+impl PatchedFrame {
+    /// `data` with `patches` written over it.
+    pub fn new(data: FrameData, patches: Patches) -> Self {
+        Self { data, patches }
+    }
+
+    /// Write the frame, with its patches in place of the bytes they replace.
+    pub fn write_to(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let mut written = 0;
+        for (offset, bytes) in &self.patches {
+            out.write_all(&self.data[written..*offset])?;
+            out.write_all(bytes)?;
+            written = offset + bytes.len();
+        }
+        out.write_all(&self.data[written..])
+    }
+}
+
+impl From<FrameData> for PatchedFrame {
+    fn from(data: FrameData) -> Self {
+        Self::new(data, Vec::new())
+    }
+}
+
+/// Patches drawing `webcam` at `position` onto `frame`, a `width`x`height`
+/// frame laid out as `format`. Only the rows the overlay covers are copied.
+/// Frames smaller than their format's size get no patches.
+pub fn overlay_patches(
+    frame: &[u8],
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    webcam: &RgbImage,
+    position: PipPosition,
+) -> Patches {
+    if frame.len() < format.frame_size(width, height) {
+        return Vec::new();
+    }
+    let (x, y) = overlay_origin(position, width, height, webcam.width(), webcam.height());
+    let top = y.clamp(0, height as i64) as u32;
+    let bottom = (y + webcam.height() as i64).clamp(0, height as i64) as u32;
+    if top >= bottom {
+        return Vec::new();
+    }
+    // Drawn relative to the copied rows
+    let band_y = y - top as i64;
+    let luma_len = width as usize * height as usize;
+    let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+    let chroma_rows = top / 2..bottom.div_ceil(2).min(chroma_height);
+    let chroma_y = y / 2 - chroma_rows.start as i64;
+
+    match format {
+        PixelFormat::Bgra => {
+            let overlay = ImageBuffer::from_fn(webcam.width(), webcam.height(), |px, py| {
+                let [r, g, b] = webcam.get_pixel(px, py).0;
+                Rgba([b, g, r, 255])
+            });
+            let (offset, mut band) = copy_rows(frame, 0, width as usize * 4, top..bottom);
+            if let Some(mut base) =
+                ImageBuffer::<Rgba<u8>, _>::from_raw(width, bottom - top, &mut band[..])
+            {
+                imageops::overlay(&mut base, &overlay, x, band_y);
+            }
+            vec![(offset, band)]
+        }
+        PixelFormat::Nv12 => {
+            let (luma, u, v) = to_yuv420(webcam);
+            let y_patch = overlay_rows(frame, 0, width, top..bottom, &luma, x, band_y);
+
+            let uv = ImageBuffer::from_fn(u.width(), u.height(), |px, py| {
+                LumaA([u.get_pixel(px, py).0[0], v.get_pixel(px, py).0[0]])
+            });
+            let (offset, mut band) = copy_rows(
+                frame,
+                luma_len,
+                chroma_width as usize * 2,
+                chroma_rows.clone(),
+            );
+            if let Some(mut base) = ImageBuffer::<LumaA<u8>, _>::from_raw(
+                chroma_width,
+                chroma_rows.end - chroma_rows.start,
+                &mut band[..],
+            ) {
+                // `overlay` would treat V as alpha; copy instead
+                imageops::replace(&mut base, &uv, x / 2, chroma_y);
+            }
+            vec![y_patch, (offset, band)]
+        }
+        PixelFormat::Yuv420 => {
+            let (luma, u, v) = to_yuv420(webcam);
+            let chroma_len = chroma_width as usize * chroma_height as usize;
+            vec![
+                overlay_rows(frame, 0, width, top..bottom, &luma, x, band_y),
+                overlay_rows(
+                    frame,
+                    luma_len,
+                    chroma_width,
+                    chroma_rows.clone(),
+                    &u,
+                    x / 2,
+                    chroma_y,
+                ),
+                overlay_rows(
+                    frame,
+                    luma_len + chroma_len,
+                    chroma_width,
+                    chroma_rows,
+                    &v,
+                    x / 2,
+                    chroma_y,
+                ),
+            ]
+        }
+    }
+}
+
+/// Copy `rows` of a plane starting at `plane_start` in `frame`, `stride`
+/// bytes per row. Returns the copy and its offset in `frame`.
+fn copy_rows(
+    frame: &[u8],
+    plane_start: usize,
+    stride: usize,
+    rows: Range<u32>,
+) -> (usize, Vec<u8>) {
+    let start = plane_start + rows.start as usize * stride;
+    let end = plane_start + rows.end as usize * stride;
+    (start, frame[start..end].to_vec())
+}
+
+/// Copy `rows` of an 8-bit plane `width` pixels wide and overlay `top` at
+/// (`x`, `y`) relative to the first row.
+fn overlay_rows(
+    frame: &[u8],
+    plane_start: usize,
+    width: u32,
+    rows: Range<u32>,
+    top: &GrayImage,
+    x: i64,
+    y: i64,
+) -> (usize, Vec<u8>) {
+    let height = rows.end - rows.start;
+    let (offset, mut band) = copy_rows(frame, plane_start, width as usize, rows);
+    if let Some(mut base) = ImageBuffer::<Luma<u8>, _>::from_raw(width, height, &mut band[..]) {
+        imageops::overlay(&mut base, top, x, y);
+    }
+    (offset, band)
+}
+
+/// Split an RGB image into BT.601 limited-range Y, U and V planes, with
+/// chroma averaged over 2x2 blocks.
+fn to_yuv420(rgb: &RgbImage) -> (GrayImage, GrayImage, GrayImage) {
+    let (width, height) = rgb.dimensions();
+    let luma = GrayImage::from_fn(width, height, |x, y| {
+        let [r, g, b] = rgb.get_pixel(x, y).0.map(i32::from);
+        Luma([(16 + ((66 * r + 129 * g + 25 * b + 128) >> 8)) as u8])
+    });
+
+    let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+    let mut u = GrayImage::new(chroma_width, chroma_height);
+    let mut v = GrayImage::new(chroma_width, chroma_height);
+    for cy in 0..chroma_height {
+        for cx in 0..chroma_width {
+            let (mut r, mut g, mut b, mut n) = (0, 0, 0, 0);
+            for y in (cy * 2)..(cy * 2 + 2).min(height) {
+                for x in (cx * 2)..(cx * 2 + 2).min(width) {
+                    let [pr, pg, pb] = rgb.get_pixel(x, y).0.map(i32::from);
+                    (r, g, b, n) = (r + pr, g + pg, b + pb, n + 1);
+                }
+            }
+            let (r, g, b) = (r / n, g / n, b / n);
+            let cb = 128 + ((-38 * r - 74 * g + 112 * b + 128) >> 8);
+            let cr = 128 + ((112 * r - 94 * g - 18 * b + 128) >> 8);
+            u.put_pixel(cx, cy, Luma([cb as u8]));
+            v.put_pixel(cx, cy, Luma([cr as u8]));
+        }
+    }
+    (luma, u, v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    /// Draw `webcam` onto `frame` as the encoder writes it.
+    fn composite(
+        frame: &mut Vec<u8>,
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+        webcam: &RgbImage,
+        position: PipPosition,
+    ) {
+        let patches = overlay_patches(frame, width, height, format, webcam, position);
+        let mut written = Vec::new();
+        PatchedFrame::new(FrameData::from(frame.clone()), patches)
+            .write_to(&mut written)
+            .unwrap();
+        *frame = written;
+    }
+
+    fn pip(position: PipPosition) -> PipConfig {
+        PipConfig {
+            camera_id: "0".to_string(),
+            position,
+            size_fraction: 0.25,
+        }
+    }
+
+    #[test]
+    fn test_overlay_origin_corners() {
+        assert_eq!(overlay_width(&pip(PipPosition::TopLeft), 1920), 480);
+        assert_eq!(
+            overlay_origin(PipPosition::TopLeft, 1920, 1080, 480, 270),
+            (38, 38)
+        );
+        assert_eq!(
+            overlay_origin(PipPosition::TopRight, 1920, 1080, 480, 270),
+            (1402, 38)
+        );
+        assert_eq!(
+            overlay_origin(PipPosition::BottomLeft, 1920, 1080, 480, 270),
+            (38, 772)
+        );
+        assert_eq!(
+            overlay_origin(PipPosition::BottomRight, 1920, 1080, 480, 270),
+            (1402, 772)
+        );
+    }
+
+    #[test]
+    fn test_composite_bgra() {
+        let (width, height) = (100, 50);
+        let mut frame = PixelFormat::Bgra.black_frame(width, height);
+        let webcam = RgbImage::from_pixel(10, 6, Rgb([255, 0, 0]));
+        composite(
+            &mut frame,
+            width,
+            height,
+            PixelFormat::Bgra,
+            &webcam,
+            PipPosition::BottomRight,
+        );
+
+        let pixel = |x: u32, y: u32| {
+            let i = (y * width + x) as usize * 4;
+            &frame[i..i + 4]
+        };
+        // Margin is 2px, so the overlay spans x 88..98, y 42..48
+        assert_eq!(pixel(88, 42), [0, 0, 255, 255]);
+        assert_eq!(pixel(97, 47), [0, 0, 255, 255]);
+        assert_eq!(pixel(87, 42), [0, 0, 0, 255]);
+        assert_eq!(pixel(98, 48), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_patches_copy_only_overlay_rows() {
+        let (width, height) = (100, 50);
+        let frame = PixelFormat::Nv12.black_frame(width, height);
+        let webcam = RgbImage::from_pixel(10, 6, Rgb([255, 255, 255]));
+        let patches = overlay_patches(
+            &frame,
+            width,
+            height,
+            PixelFormat::Nv12,
+            &webcam,
+            PipPosition::TopLeft,
+        );
+        // Luma rows 2..8 and chroma rows 1..4
+        assert_eq!(patches.len(), 2);
+        assert_eq!((patches[0].0, patches[0].1.len()), (200, 600));
+        assert_eq!((patches[1].0, patches[1].1.len()), (5000 + 100, 300));
+    }
+
+    #[test]
+    fn test_composite_nv12() {
+        let (width, height) = (100, 50);
+        let mut frame = PixelFormat::Nv12.black_frame(width, height);
+        let webcam = RgbImage::from_pixel(10, 6, Rgb([255, 255, 255]));
+        composite(
+            &mut frame,
+            width,
+            height,
+            PixelFormat::Nv12,
+            &webcam,
+            PipPosition::TopLeft,
+        );
+
+        let luma = |x: u32, y: u32| frame[(y * width + x) as usize];
+        assert_eq!(luma(2, 2), 235);
+        assert_eq!(luma(11, 7), 235);
+        assert_eq!(luma(12, 2), 16);
+        // White has neutral chroma, like the black frame around it
+        let uv_start = (width * height) as usize;
+        assert!(frame[uv_start..].iter().all(|&c| c == 128));
+    }
+
+    #[test]
+    fn test_composite_yuv420_chroma() {
+        let (width, height) = (8, 8);
+        let mut frame = PixelFormat::Yuv420.black_frame(width, height);
+        let webcam = RgbImage::from_pixel(2, 2, Rgb([255, 0, 0]));
+        composite(
+            &mut frame,
+            width,
+            height,
+            PixelFormat::Yuv420,
+            &webcam,
+            PipPosition::TopLeft,
+        );
+
+        // No margin at this width; the overlay covers chroma sample (0, 0)
+        let (u_start, v_start) = (64, 64 + 16);
+        assert_eq!(frame[u_start], 90);
+        assert_eq!(frame[v_start], 240);
+        assert_eq!(frame[u_start + 1], 128);
+        assert_eq!(frame[v_start + 1], 128);
+    }
+}
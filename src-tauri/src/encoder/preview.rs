@@ -0,0 +1,221 @@
+//! Live preview of the recording.
+//!
+//! While a preview stream runs, the encoder passes every frame it receives
+//! to [`offer`], which sends at most `fps` frames per second to the preview
+//! thread over a channel of its own. The thread scales each frame down to
+//! fit 240x135, encodes it as JPEG and broadcasts it as
+//! [`ServiceEvent::PreviewFrame`]. A frame that arrives while the thread is
+//! still busy is dropped, so the preview never holds up the recording.
+
+use crate::capture::frame_limit::TokenBucket;
+use crate::capture::thumbnail::calculate_scaled_dimensions;
+use crate::capture::types::{CapturedFrame, PixelFormat};
+use crate::state::ServiceEvent;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::imageops;
+use image::{GrayImage, ImageBuffer, Luma, Rgb, RgbImage, Rgba};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Maximum preview width in pixels.
+pub const PREVIEW_WIDTH: u32 = 240;
+
+/// Maximum preview height in pixels.
+pub const PREVIEW_HEIGHT: u32 = 135;
+
+/// Preview frame rate of the app's preview window.
+pub const DEFAULT_PREVIEW_FPS: u8 = 10;
+
+/// JPEG quality of preview frames (0-100).
+const PREVIEW_JPEG_QUALITY: u8 = 70;
+
+/// The running preview stream.
+struct PreviewTap {
+    tx: SyncSender<CapturedFrame>,
+    bucket: TokenBucket,
+}
+
+static TAP: Mutex<Option<PreviewTap>> = Mutex::new(None);
+
+/// Start broadcasting preview frames on `events` at up to `fps` frames per
+/// second, replacing the running stream if there is one.
+pub fn start(fps: u8, events: broadcast::Sender<ServiceEvent>) -> Result<(), String> {
+    // Room for one frame: the thread encodes one while the next one waits
+    let (tx, rx) = mpsc::sync_channel::<CapturedFrame>(1);
+    std::thread::Builder::new()
+        .name("recording-preview".to_string())
+        .spawn(move || {
+            while let Ok(frame) = rx.recv() {
+                match encode_preview(&frame) {
+                    Some((data, width, height)) => {
+                        let _ = events.send(ServiceEvent::PreviewFrame {
+                            data,
+                            width,
+                            height,
+                        });
+                    }
+                    None => tracing::debug!("[Preview] Skipped a frame that could not be encoded"),
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to start preview thread: {}", e))?;
+
+    *TAP.lock().unwrap() = Some(PreviewTap {
+        tx,
+        bucket: TokenBucket::new(fps as u32),
+    });
+    Ok(())
+}
+
+/// Stop the preview stream; its thread exits once the channel is dropped.
+pub fn stop() {
+    *TAP.lock().unwrap() = None;
+}
+
+/// Pass `frame` to the preview stream if one is running and a frame is due.
+pub fn offer(frame: &CapturedFrame) {
+    let mut tap = TAP.lock().unwrap();
+    let Some(active) = tap.as_mut() else {
+        return;
+    };
+    if !active.bucket.try_acquire() {
+        return;
+    }
+    if let Err(TrySendError::Disconnected(_)) = active.tx.try_send(frame.clone()) {
+        *tap = None;
+    }
+}
+
+/// Scale `frame` down to fit the preview bounds and encode it as base64 JPEG.
+///
+/// Returns the data with the scaled width and height, or `None` if the frame
+/// data is too short for its size.
+fn encode_preview(frame: &CapturedFrame) -> Option<(String, u32, u32)> {
+    let (width, height) =
+        calculate_scaled_dimensions(frame.width, frame.height, PREVIEW_WIDTH, PREVIEW_HEIGHT);
+    let image = scale_to_rgb(frame, width, height)?;
+
+    let mut jpeg_bytes: Vec<u8> = Vec::new();
+    let mut encoder =
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg_bytes, PREVIEW_JPEG_QUALITY);
+    encoder.encode_image(&image).ok()?;
+    Some((STANDARD.encode(&jpeg_bytes), width, height))
+}
+
+/// Scale `frame` to `width`x`height` and convert it to RGB.
+///
+/// YUV frames are scaled plane by plane and converted afterwards, so only
+/// the scaled pixels go through the color conversion.
+fn scale_to_rgb(frame: &CapturedFrame, width: u32, height: u32) -> Option<RgbImage> {
+    let (w, h) = (frame.width, frame.height);
+    if frame.data.len() < frame.pixel_format.frame_size(w, h) {
+        return None;
+    }
+    let luma_len = w as usize * h as usize;
+    let (chroma_width, chroma_height) = (w.div_ceil(2), h.div_ceil(2));
+    let chroma_len = chroma_width as usize * chroma_height as usize;
+
+    match frame.pixel_format {
+        PixelFormat::Bgra => {
+            // Scaled as if it were RGBA, with the channels swapped afterwards
+            let bgra = ImageBuffer::<Rgba<u8>, _>::from_raw(w, h, &frame.data[..luma_len * 4])?;
+            let scaled = imageops::thumbnail(&bgra, width, height);
+            Some(RgbImage::from_fn(width, height, |x, y| {
+                let [b, g, r, _] = scaled.get_pixel(x, y).0;
+                Rgb([r, g, b])
+            }))
+        }
+        PixelFormat::Nv12 => {
+            let (y_plane, uv_plane) = frame.data.split_at(luma_len);
+            let uv = &uv_plane[..chroma_len * 2];
+            let u = GrayImage::from_fn(chroma_width, chroma_height, |x, y| {
+                Luma([uv[(y * chroma_width + x) as usize * 2]])
+            });
+            let v = GrayImage::from_fn(chroma_width, chroma_height, |x, y| {
+                Luma([uv[(y * chroma_width + x) as usize * 2 + 1]])
+            });
+            let luma = ImageBuffer::<Luma<u8>, _>::from_raw(w, h, y_plane)?;
+            Some(yuv_to_rgb(
+                &imageops::thumbnail(&luma, width, height),
+                &imageops::thumbnail(&u, width, height),
+                &imageops::thumbnail(&v, width, height),
+            ))
+        }
+        PixelFormat::Yuv420 => {
+            let (y_plane, chroma) = frame.data.split_at(luma_len);
+            let (u_plane, v_plane) = chroma.split_at(chroma_len);
+            let luma = ImageBuffer::<Luma<u8>, _>::from_raw(w, h, y_plane)?;
+            let u = ImageBuffer::<Luma<u8>, _>::from_raw(chroma_width, chroma_height, u_plane)?;
+            let v = ImageBuffer::<Luma<u8>, _>::from_raw(
+                chroma_width,
+                chroma_height,
+                &v_plane[..chroma_len],
+            )?;
+            Some(yuv_to_rgb(
+                &imageops::thumbnail(&luma, width, height),
+                &imageops::thumbnail(&u, width, height),
+                &imageops::thumbnail(&v, width, height),
+            ))
+        }
+    }
+}
+
+/// Combine equally sized Y, U and V planes (BT.709, limited range) into RGB.
+fn yuv_to_rgb(luma: &GrayImage, u: &GrayImage, v: &GrayImage) -> RgbImage {
+    RgbImage::from_fn(luma.width(), luma.height(), |x, y| {
+        let l = (luma.get_pixel(x, y).0[0] as f32 - 16.0) * 1.164;
+        let cb = u.get_pixel(x, y).0[0] as f32 - 128.0;
+        let cr = v.get_pixel(x, y).0[0] as f32 - 128.0;
+        let clamp = |c: f32| c.round().clamp(0.0, 255.0) as u8;
+        Rgb([
+            clamp(l + 1.793 * cr),
+            clamp(l - 0.213 * cb - 0.533 * cr),
+            clamp(l + 2.112 * cb),
+        ])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::generate_test_frame;
+
+    fn frame(pixel_format: PixelFormat, width: u32, height: u32, data: Vec<u8>) -> CapturedFrame {
+        CapturedFrame {
+            width,
+            height,
+            data: data.into(),
+            pixel_format,
+            captured_at: std::time::SystemTime::now(),
+            timestamp_us: 0,
+        }
+    }
+
+    #[test]
+    fn test_preview_fits_bounds() {
+        let (_, width, height) = encode_preview(&generate_test_frame(1920, 1080, 0)).unwrap();
+        assert_eq!((width, height), (PREVIEW_WIDTH, PREVIEW_HEIGHT));
+
+        let (_, width, height) = encode_preview(&generate_test_frame(1080, 1920, 1)).unwrap();
+        assert!(width <= PREVIEW_WIDTH && height == PREVIEW_HEIGHT);
+    }
+
+    #[test]
+    fn test_frames_convert_to_rgb() {
+        let (width, height) = (64, 36);
+        let nv12 = PixelFormat::Nv12.black_frame(width, height);
+        let image = scale_to_rgb(&frame(PixelFormat::Nv12, width, height, nv12), 32, 18).unwrap();
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0]);
+
+        let bgra: Vec<u8> = [0u8, 0, 255, 255].repeat(width as usize * height as usize);
+        let image = scale_to_rgb(&frame(PixelFormat::Bgra, width, height, bgra), 32, 18).unwrap();
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0]);
+    }
+
+    #[test]
+    fn test_short_frame_is_skipped() {
+        let short = frame(PixelFormat::Yuv420, 64, 36, vec![0; 100]);
+        assert!(encode_preview(&short).is_none());
+    }
+}
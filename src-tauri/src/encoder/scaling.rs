@@ -0,0 +1,272 @@
+//! Dynamic resolution scaling.
+//!
+//! When FFmpeg can't keep up, the pacing loop in
+//! [`encode_frames`](super::encode_frames) skips frame slots. With dynamic
+//! resolution scaling enabled, a [`ScaleController`] watches the share of
+//! skipped slots: more than 10% for 3 seconds in a row lowers the scale by
+//! one step, and 5 seconds without drops raises it again.
+//!
+//! Every recording starts at full size. FFmpeg fixes the encoded frame size
+//! when it starts, so a new scale starts a new segment: FFmpeg is restarted
+//! with a `scale` filter after any user filters, and when the recording
+//! stops the segments are joined into one file at the size of the first.
+
+use serde::{Deserialize, Serialize};
+
+/// Share of skipped frame slots above which a second counts as dropping.
+const DROP_RATE_THRESHOLD: f64 = 0.10;
+
+/// Dropping seconds in a row before the scale is lowered.
+const SECONDS_BEFORE_DOWNSCALE: u32 = 3;
+
+/// Seconds without drops in a row before the scale is raised.
+const SECONDS_BEFORE_UPSCALE: u32 = 5;
+
+/// Dynamic resolution scaling settings.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DynamicResolutionScaling {
+    /// Lower the recording resolution when frames are dropped
+    pub enabled: bool,
+    /// Smallest scale recordings are reduced to (0.1–1.0)
+    pub min_scale: f32,
+    /// Change of scale per adjustment (0.05–1.0)
+    pub step: f32,
+}
+
+impl Default for DynamicResolutionScaling {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_scale: 0.5,
+            step: 0.25,
+        }
+    }
+}
+
+impl DynamicResolutionScaling {
+    /// Check that the minimum scale and step are in range.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.1..=1.0).contains(&self.min_scale) {
+            return Err("Minimum scale must be between 0.1 and 1.0".to_string());
+        }
+        if !(0.05..=1.0).contains(&self.step) {
+            return Err("Scale step must be between 0.05 and 1.0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Video filter chain encoding at `scale`: `filter` followed by a `scale`
+/// filter shrinking its output, rounded down to even dimensions.
+pub fn with_scale_filter(filter: Option<String>, scale: f32) -> Option<String> {
+    if scale >= 1.0 {
+        return filter;
+    }
+    let scale_filter = format!("scale=trunc(iw*{0}/2)*2:trunc(ih*{0}/2)*2", scale);
+    Some(match filter {
+        Some(filter) => format!("{},{}", filter, scale_filter),
+        None => scale_filter,
+    })
+}
+
+/// `-filter_complex` graph joining segments holding `frames` frames each
+/// (inputs `0..`) into `[v]` and, with `has_audio`, `[a]`. Every segment is
+/// scaled to `width`x`height`, and its audio is cut to the length of its
+/// video so later segments stay in sync.
+pub fn join_filter(frames: &[u64], fps: u64, width: u32, height: u32, has_audio: bool) -> String {
+    let mut graph = String::new();
+    let mut inputs = String::new();
+    for (index, &frames) in frames.iter().enumerate() {
+        graph.push_str(&format!(
+            "[{0}:v]scale={1}:{2},setsar=1[v{0}];",
+            index, width, height
+        ));
+        inputs.push_str(&format!("[v{}]", index));
+        if has_audio {
+            graph.push_str(&format!(
+                "[{0}:a]atrim=end={1},asetpts=PTS-STARTPTS[a{0}];",
+                index,
+                frames as f64 / fps as f64
+            ));
+            inputs.push_str(&format!("[a{}]", index));
+        }
+    }
+    graph.push_str(&inputs);
+    if has_audio {
+        graph.push_str(&format!("concat=n={}:v=1:a=1[v][a]", frames.len()));
+    } else {
+        graph.push_str(&format!("concat=n={}:v=1:a=0[v]", frames.len()));
+    }
+    graph
+}
+
+/// Watches frame drops and picks the recording scale.
+#[derive(Debug, Clone)]
+pub struct ScaleController {
+    settings: DynamicResolutionScaling,
+    scale: f32,
+    dropping_secs: u32,
+    clean_secs: u32,
+}
+
+impl ScaleController {
+    /// Controller starting at `scale`.
+    pub fn new(settings: DynamicResolutionScaling, scale: f32) -> Self {
+        Self {
+            settings,
+            scale: scale.clamp(settings.min_scale, 1.0),
+            dropping_secs: 0,
+            clean_secs: 0,
+        }
+    }
+
+    /// Current scale.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Record one second with `slots` frame slots, `skipped` of them
+    /// skipped. Returns the new scale when it changes.
+    pub fn record_second(&mut self, slots: u64, skipped: u64) -> Option<f32> {
+        if !self.settings.enabled || slots == 0 {
+            return None;
+        }
+
+        if skipped as f64 / slots as f64 > DROP_RATE_THRESHOLD {
+            self.dropping_secs += 1;
+            self.clean_secs = 0;
+        } else if skipped == 0 {
+            self.clean_secs += 1;
+            self.dropping_secs = 0;
+        } else {
+            self.dropping_secs = 0;
+            self.clean_secs = 0;
+        }
+
+        let scale = if self.dropping_secs >= SECONDS_BEFORE_DOWNSCALE {
+            (self.scale - self.settings.step).max(self.settings.min_scale)
+        } else if self.clean_secs >= SECONDS_BEFORE_UPSCALE {
+            (self.scale + self.settings.step).min(1.0)
+        } else {
+            return None;
+        };
+        self.dropping_secs = 0;
+        self.clean_secs = 0;
+
+        if (scale - self.scale).abs() < f32::EPSILON {
+            return None;
+        }
+        self.scale = scale;
+        Some(scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> DynamicResolutionScaling {
+        DynamicResolutionScaling {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_scale_filter_goes_last() {
+        assert_eq!(with_scale_filter(None, 1.0), None);
+        assert_eq!(
+            with_scale_filter(Some("hflip".to_string()), 1.0),
+            Some("hflip".to_string())
+        );
+        assert_eq!(
+            with_scale_filter(None, 0.5),
+            Some("scale=trunc(iw*0.5/2)*2:trunc(ih*0.5/2)*2".to_string())
+        );
+        assert_eq!(
+            with_scale_filter(Some("crop=1280:720:0:0".to_string()), 0.75),
+            Some("crop=1280:720:0:0,scale=trunc(iw*0.75/2)*2:trunc(ih*0.75/2)*2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_join_filter_trims_audio_to_video() {
+        assert_eq!(
+            join_filter(&[60, 15], 30, 1920, 1080, true),
+            "[0:v]scale=1920:1080,setsar=1[v0];\
+             [0:a]atrim=end=2,asetpts=PTS-STARTPTS[a0];\
+             [1:v]scale=1920:1080,setsar=1[v1];\
+             [1:a]atrim=end=0.5,asetpts=PTS-STARTPTS[a1];\
+             [v0][a0][v1][a1]concat=n=2:v=1:a=1[v][a]"
+        );
+        assert_eq!(
+            join_filter(&[60, 15], 30, 1280, 720, false),
+            "[0:v]scale=1280:720,setsar=1[v0];\
+             [1:v]scale=1280:720,setsar=1[v1];\
+             [v0][v1]concat=n=2:v=1:a=0[v]"
+        );
+    }
+
+    #[test]
+    fn test_downscales_after_three_dropping_seconds() {
+        let mut controller = ScaleController::new(enabled(), 1.0);
+        assert_eq!(controller.record_second(30, 6), None);
+        assert_eq!(controller.record_second(30, 6), None);
+        assert_eq!(controller.record_second(30, 6), Some(0.75));
+        assert_eq!(controller.record_second(30, 6), None);
+        assert_eq!(controller.record_second(30, 6), None);
+        assert_eq!(controller.record_second(30, 6), Some(0.5));
+        // Already at the minimum
+        for _ in 0..3 {
+            assert_eq!(controller.record_second(30, 6), None);
+        }
+        assert_eq!(controller.scale(), 0.5);
+    }
+
+    #[test]
+    fn test_upscales_after_five_clean_seconds() {
+        let mut controller = ScaleController::new(enabled(), 0.5);
+        for _ in 0..4 {
+            assert_eq!(controller.record_second(30, 0), None);
+        }
+        assert_eq!(controller.record_second(30, 0), Some(0.75));
+    }
+
+    #[test]
+    fn test_light_drops_reset_both_counters() {
+        let mut controller = ScaleController::new(enabled(), 0.75);
+        controller.record_second(30, 6);
+        controller.record_second(30, 6);
+        controller.record_second(30, 1);
+        assert_eq!(controller.record_second(30, 6), None);
+        for _ in 0..4 {
+            controller.record_second(30, 0);
+        }
+        controller.record_second(30, 1);
+        assert_eq!(controller.record_second(30, 0), None);
+    }
+
+    #[test]
+    fn test_disabled_never_changes_scale() {
+        let mut controller = ScaleController::new(DynamicResolutionScaling::default(), 1.0);
+        for _ in 0..10 {
+            assert_eq!(controller.record_second(30, 30), None);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(DynamicResolutionScaling::default().validate().is_ok());
+        let settings = DynamicResolutionScaling {
+            min_scale: 0.0,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+        let settings = DynamicResolutionScaling {
+            step: 0.0,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! In-memory log of the service's recent messages.
+//!
+//! [`EventLogLayer`] keeps the last [`EVENT_LOG_CAPACITY`] `tracing` events,
+//! so the log viewer and IPC clients (`GetRecentEvents`) can show recent
+//! activity without reading log files from disk.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use omnirec_types::LogEvent;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Number of events kept.
+pub const EVENT_LOG_CAPACITY: usize = omnirec_types::security::validation::MAX_RECENT_EVENTS;
+
+/// Fixed-capacity buffer dropping its oldest item when full.
+pub struct EventRingBuffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T: Clone> EventRingBuffer<T> {
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            items: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.items.len() == self.capacity {
+            self.items.pop_front();
+        }
+        self.items.push_back(item);
+    }
+
+    /// The newest `count` items matching `filter`, oldest first.
+    pub fn recent(&self, count: usize, filter: impl Fn(&T) -> bool) -> Vec<T> {
+        let mut recent: Vec<T> = self
+            .items
+            .iter()
+            .rev()
+            .filter(|item| filter(item))
+            .take(count)
+            .cloned()
+            .collect();
+        recent.reverse();
+        recent
+    }
+}
+
+static RECENT_EVENTS: Mutex<EventRingBuffer<LogEvent>> =
+    Mutex::new(EventRingBuffer::new(EVENT_LOG_CAPACITY));
+
+/// The newest `count` logged events, oldest first. With `level`, only events
+/// at least that severe are returned.
+pub fn recent_events(count: usize, level: Option<&str>) -> Result<Vec<LogEvent>, String> {
+    let min_level = level
+        .map(|level| Level::from_str(level).map_err(|_| format!("Unknown log level: {}", level)))
+        .transpose()?;
+    let events = RECENT_EVENTS.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(events.recent(count, |event| match min_level {
+        // More severe levels compare as lower
+        Some(min_level) => Level::from_str(&event.level).is_ok_and(|level| level <= min_level),
+        None => true,
+    }))
+}
+
+/// Collects an event's or span's fields as strings.
+struct FieldVisitor<'a> {
+    message: Option<String>,
+    fields: &'a mut BTreeMap<String, String>,
+}
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+        } else {
+            self.fields
+                .insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        } else {
+            self.fields
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+}
+
+/// Fields recorded on a span, stored in its extensions.
+#[derive(Default)]
+struct SpanFields(BTreeMap<String, String>);
+
+/// A `tracing_subscriber::Layer` recording events into the in-memory log.
+pub struct EventLogLayer;
+
+impl<S> Layer<S> for EventLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = SpanFields::default();
+        attrs.record(&mut FieldVisitor {
+            message: None,
+            fields: &mut fields.0,
+        });
+        span.extensions_mut().insert(fields);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(fields) = extensions.get_mut::<SpanFields>() {
+            values.record(&mut FieldVisitor {
+                message: None,
+                fields: &mut fields.0,
+            });
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        // Span fields first, outermost span first, so the event's own
+        // fields take precedence
+        let mut fields = BTreeMap::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(span_fields) = span.extensions().get::<SpanFields>() {
+                    fields.extend(span_fields.0.clone());
+                }
+            }
+        }
+        let mut visitor = FieldVisitor {
+            message: None,
+            fields: &mut fields,
+        };
+        event.record(&mut visitor);
+        let message = visitor.message.unwrap_or_default();
+
+        let metadata = event.metadata();
+        let log_event = LogEvent {
+            timestamp: chrono::Utc::now()
+                .format("%Y-%m-%dT%H:%M:%S%.6fZ")
+                .to_string(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message,
+            fields,
+        };
+        RECENT_EVENTS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(log_event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_ring_buffer_keeps_newest() {
+        let mut buffer = EventRingBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(i);
+        }
+        assert_eq!(buffer.recent(10, |_| true), vec![2, 3, 4]);
+        assert_eq!(buffer.recent(2, |_| true), vec![3, 4]);
+        assert_eq!(buffer.recent(10, |i| i % 2 == 0), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_layer_records_events_and_span_fields() {
+        let subscriber = tracing_subscriber::registry().with(EventLogLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("recording", session = 7);
+            let _guard = span.enter();
+            tracing::warn!(frames = 42, "event-log-test dropped frames");
+            tracing::debug!("event-log-test detail");
+        });
+
+        let events: Vec<LogEvent> = recent_events(EVENT_LOG_CAPACITY, None)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.message.starts_with("event-log-test"))
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].level, "WARN");
+        assert_eq!(events[0].message, "event-log-test dropped frames");
+        assert_eq!(events[0].fields["frames"], "42");
+        assert_eq!(events[0].fields["session"], "7");
+
+        let warnings = recent_events(EVENT_LOG_CAPACITY, Some("warn")).unwrap();
+        assert!(warnings
+            .iter()
+            .any(|e| e.message == "event-log-test dropped frames"));
+        assert!(!warnings
+            .iter()
+            .any(|e| e.message == "event-log-test detail"));
+        assert!(recent_events(10, Some("verbose")).is_err());
+    }
+}
@@ -0,0 +1,344 @@
+//! Recording history log.
+//!
+//! Every saved recording is appended to `history.json` in the data directory:
+//! - Linux: `~/.local/share/omnirec/history.json`
+//! - macOS: `~/Library/Application Support/omnirec/history.json`
+//! - Windows: `%APPDATA%\omnirec\data\history.json`
+//!
+//! The log keeps the newest [`MAX_HISTORY_ENTRIES`] entries.
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Maximum number of entries kept; the oldest are dropped first.
+pub const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// Serializes reads and writes of the history file.
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// What kind of target a recording captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingSourceType {
+    Window,
+    Display,
+    Region,
+    Portal,
+    Workspace,
+}
+
+/// One saved recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingHistoryEntry {
+    /// Path of the saved file
+    pub path: String,
+    /// Output format, e.g. `mp4`
+    pub format: String,
+    /// Recording length in seconds
+    pub duration_secs: u64,
+    /// What was captured
+    pub source_type: RecordingSourceType,
+    /// Window title or monitor name, when known
+    #[serde(default)]
+    pub source_name: Option<String>,
+    /// File size in bytes when saved
+    pub file_size: u64,
+    /// When the recording was saved (RFC 3339)
+    pub created_at: String,
+}
+
+impl RecordingHistoryEntry {
+    /// Text matched by [`search_entries`]: the source name and file name.
+    fn title(&self) -> String {
+        let file_name = Path::new(&self.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match &self.source_name {
+            Some(name) => format!("{} {}", name, file_name),
+            None => file_name,
+        }
+    }
+}
+
+/// A recently saved recording, as listed in the tray's Recent Recordings menu.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RecentFile {
+    /// Path of the saved file
+    pub path: String,
+    /// File name shown in menus
+    pub name: String,
+    /// Whether the file is still on disk
+    pub exists: bool,
+}
+
+impl RecentFile {
+    /// Describe the recording at `path`, checking whether it still exists.
+    pub fn new(path: String) -> Self {
+        let name = Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+        let exists = Path::new(&path).is_file();
+        Self { path, name, exists }
+    }
+}
+
+/// Path of the history file.
+fn history_path() -> Result<PathBuf, String> {
+    let proj_dirs =
+        ProjectDirs::from("", "", "omnirec").ok_or("Could not determine data directory")?;
+    Ok(proj_dirs.data_dir().join("history.json"))
+}
+
+/// Read the history, oldest first. A missing or corrupt file reads as empty.
+fn read_history(path: &Path) -> Vec<RecordingHistoryEntry> {
+    let Ok(json) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        warn!("[History] Ignoring unreadable history {:?}: {}", path, e);
+        Vec::new()
+    })
+}
+
+/// Replace the history file via a temp file so it is never half-written.
+fn write_history(path: &Path, entries: &[RecordingHistoryEntry]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create history directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize history: {}", e))?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).map_err(|e| format!("Failed to write history: {}", e))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to replace history: {}", e))
+}
+
+fn append_to(path: &Path, entry: RecordingHistoryEntry) -> Result<(), String> {
+    let mut entries = read_history(path);
+    entries.push(entry);
+    if entries.len() > MAX_HISTORY_ENTRIES {
+        entries.drain(..entries.len() - MAX_HISTORY_ENTRIES);
+    }
+    write_history(path, &entries)
+}
+
+fn remove_from(path: &Path, file_path: &str) -> Result<Option<RecordingHistoryEntry>, String> {
+    let mut entries = read_history(path);
+    let Some(index) = entries.iter().position(|entry| entry.path == file_path) else {
+        return Ok(None);
+    };
+    let removed = entries.remove(index);
+    write_history(path, &entries)?;
+    Ok(Some(removed))
+}
+
+/// Append an entry to the history, dropping the oldest beyond the limit.
+pub fn append_entry(entry: RecordingHistoryEntry) -> Result<(), String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = history_path()?;
+    append_to(&path, entry)?;
+    debug!("[History] Appended entry to {:?}", path);
+    Ok(())
+}
+
+/// All entries, newest first.
+pub fn load_entries() -> Result<Vec<RecordingHistoryEntry>, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut entries = read_history(&history_path()?);
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Remove the entry for `file_path`, returning it if it existed.
+pub fn remove_entry(file_path: &str) -> Result<Option<RecordingHistoryEntry>, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    remove_from(&history_path()?, file_path)
+}
+
+/// Zero-based page `page` of `entries` with `per_page` entries per page.
+pub fn page_entries(
+    entries: Vec<RecordingHistoryEntry>,
+    page: u32,
+    per_page: u32,
+) -> Vec<RecordingHistoryEntry> {
+    let per_page = per_page.max(1) as usize;
+    entries
+        .into_iter()
+        .skip((page as usize).saturating_mul(per_page))
+        .take(per_page)
+        .collect()
+}
+
+/// Entries whose title fuzzily matches `query`, best match first.
+pub fn search_entries(
+    entries: Vec<RecordingHistoryEntry>,
+    query: &str,
+) -> Vec<RecordingHistoryEntry> {
+    let query = query.trim();
+    if query.is_empty() {
+        return entries;
+    }
+    let mut scored: Vec<_> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            sublime_fuzzy::best_match(query, &entry.title()).map(|m| (m.score(), entry))
+        })
+        .collect();
+    // Stable sort keeps newer entries first among equal scores
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Whether `path` is a saved recording: a file inside `output_dir`, or one
+/// listed in `entries`.
+///
+/// Paths are compared after resolving symlinks and `..`, so a path can't
+/// step out of the output directory. Paths that don't exist never match.
+pub fn is_recording_path(
+    path: &Path,
+    output_dir: Option<&Path>,
+    entries: &[RecordingHistoryEntry],
+) -> bool {
+    let Ok(path) = path.canonicalize() else {
+        return false;
+    };
+    if output_dir
+        .and_then(|dir| dir.canonicalize().ok())
+        .is_some_and(|dir| path.starts_with(dir))
+    {
+        return true;
+    }
+    entries
+        .iter()
+        .any(|entry| Path::new(&entry.path).canonicalize().ok().as_ref() == Some(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::temp_dir;
+
+    fn entry(path: &str, source_name: Option<&str>) -> RecordingHistoryEntry {
+        RecordingHistoryEntry {
+            path: path.to_string(),
+            format: "mp4".to_string(),
+            duration_secs: 10,
+            source_type: RecordingSourceType::Window,
+            source_name: source_name.map(String::from),
+            file_size: 1024,
+            created_at: "2026-01-01T00:00:00+00:00".to_string(),
+        }
+    }
+
+    fn temp_history(name: &str) -> PathBuf {
+        temp_dir(&format!("history-{}", name)).join("history.json")
+    }
+
+    #[test]
+    fn test_append_rotates_oldest_first() {
+        let path = temp_history("rotate");
+        let full: Vec<_> = (0..MAX_HISTORY_ENTRIES + 5)
+            .map(|i| entry(&format!("/rec/{}.mp4", i), None))
+            .collect();
+        write_history(&path, &full).unwrap();
+        append_to(&path, entry("/rec/last.mp4", None)).unwrap();
+
+        let entries = read_history(&path);
+        assert_eq!(entries.len(), MAX_HISTORY_ENTRIES);
+        assert_eq!(entries[0].path, "/rec/6.mp4");
+        assert_eq!(entries.last().unwrap().path, "/rec/last.mp4");
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_remove_entry() {
+        let path = temp_history("remove");
+        append_to(&path, entry("/rec/a.mp4", None)).unwrap();
+        append_to(&path, entry("/rec/b.mp4", None)).unwrap();
+
+        assert_eq!(
+            remove_from(&path, "/rec/a.mp4").unwrap().map(|e| e.path),
+            Some("/rec/a.mp4".to_string())
+        );
+        assert_eq!(remove_from(&path, "/rec/a.mp4").unwrap(), None);
+        assert_eq!(read_history(&path), vec![entry("/rec/b.mp4", None)]);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_corrupt_history_reads_as_empty() {
+        let path = temp_history("corrupt");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "not json").unwrap();
+        assert!(read_history(&path).is_empty());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_page_entries() {
+        let entries: Vec<_> = (0..5)
+            .map(|i| entry(&format!("/rec/{}.mp4", i), None))
+            .collect();
+        let paths = |page: Vec<RecordingHistoryEntry>| -> Vec<String> {
+            page.into_iter().map(|e| e.path).collect()
+        };
+
+        assert_eq!(
+            paths(page_entries(entries.clone(), 0, 2)),
+            ["/rec/0.mp4", "/rec/1.mp4"]
+        );
+        assert_eq!(paths(page_entries(entries.clone(), 2, 2)), ["/rec/4.mp4"]);
+        assert!(page_entries(entries.clone(), 3, 2).is_empty());
+        assert_eq!(page_entries(entries, 0, 0).len(), 1);
+    }
+
+    #[test]
+    fn test_search_entries() {
+        let entries = vec![
+            entry("/rec/recording_1.mp4", Some("Firefox")),
+            entry("/rec/recording_2.mp4", Some("Visual Studio Code")),
+            entry("/rec/standup.mp4", Some("Zoom Meeting")),
+        ];
+
+        let found = search_entries(entries.clone(), "vscode");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, "/rec/recording_2.mp4");
+
+        let found = search_entries(entries.clone(), "standup");
+        assert_eq!(found[0].path, "/rec/standup.mp4");
+
+        assert!(search_entries(entries.clone(), "xyzzy").is_empty());
+        assert_eq!(search_entries(entries, "  ").len(), 3);
+    }
+
+    #[test]
+    fn test_is_recording_path() {
+        let dir = temp_dir("history-recording-path");
+        let output_dir = dir.join("recordings");
+        fs::create_dir_all(&output_dir).unwrap();
+        let inside = output_dir.join("a.mp4");
+        let listed = dir.join("moved.mp4");
+        let other = dir.join("other.txt");
+        for file in [&inside, &listed, &other] {
+            fs::write(file, b"").unwrap();
+        }
+        let entries = vec![entry(listed.to_str().unwrap(), None)];
+
+        assert!(is_recording_path(&inside, Some(&output_dir), &entries));
+        assert!(is_recording_path(&listed, Some(&output_dir), &entries));
+        assert!(!is_recording_path(&other, Some(&output_dir), &entries));
+        assert!(!is_recording_path(
+            &output_dir.join("..").join("other.txt"),
+            Some(&output_dir),
+            &entries
+        ));
+        assert!(!is_recording_path(&inside, None, &[]));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,101 @@
+//! Global hotkeys.
+//!
+//! Shortcuts from [`HotkeyConfig`] are registered with the system through
+//! `tauri-plugin-global-shortcut`, so they work while OmniRec is in the
+//! background. Each shortcut triggers a [`HotkeyAction`].
+
+use std::sync::Mutex;
+
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::{debug, info, warn};
+
+use crate::config::HotkeyConfig;
+use crate::state::get_recording_manager;
+
+/// What a hotkey does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    /// Save a PNG of the source selected in the main window, or of the
+    /// primary display if none is selected.
+    TakeScreenshot,
+}
+
+/// Registered shortcuts and the actions they trigger.
+static BINDINGS: Mutex<Vec<(Shortcut, HotkeyAction)>> = Mutex::new(Vec::new());
+
+/// Parse an accelerator such as `CommandOrControl+Shift+S`.
+pub fn parse_shortcut(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .parse()
+        .map_err(|e| format!("Invalid shortcut \"{}\": {}", accelerator, e))
+}
+
+/// The global shortcut plugin, running the action bound to each pressed
+/// shortcut.
+pub fn plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            let action = BINDINGS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .find(|(bound, _)| bound == shortcut)
+                .map(|(_, action)| *action);
+            if let Some(action) = action {
+                run(app, action);
+            }
+        })
+        .build()
+}
+
+/// Register the shortcuts in `config`, replacing the ones registered before.
+pub fn apply_config(app: &AppHandle, config: &HotkeyConfig) -> Result<(), String> {
+    let wanted = [(config.screenshot.as_deref(), HotkeyAction::TakeScreenshot)];
+    let mut parsed = Vec::new();
+    for (accelerator, action) in wanted {
+        if let Some(accelerator) = accelerator {
+            parsed.push((accelerator, parse_shortcut(accelerator)?, action));
+        }
+    }
+
+    let global_shortcut = app.global_shortcut();
+    let mut bindings = BINDINGS.lock().unwrap_or_else(|e| e.into_inner());
+    for (shortcut, _) in bindings.drain(..) {
+        if let Err(e) = global_shortcut.unregister(shortcut) {
+            warn!("[Hotkeys] Failed to unregister {:?}: {}", shortcut, e);
+        }
+    }
+    for (accelerator, shortcut, action) in parsed {
+        global_shortcut
+            .register(shortcut)
+            .map_err(|e| format!("Failed to register shortcut {}: {}", accelerator, e))?;
+        info!("[Hotkeys] {} -> {:?}", accelerator, action);
+        bindings.push((shortcut, action));
+    }
+    Ok(())
+}
+
+fn run(app: &AppHandle, action: HotkeyAction) {
+    debug!("[Hotkeys] Running {:?}", action);
+    match action {
+        HotkeyAction::TakeScreenshot => {
+            // The main window knows the selected source and calls
+            // `take_screenshot` with it
+            if app.get_webview_window("main").is_some()
+                && app.emit_to("main", "screenshot-requested", ()).is_ok()
+            {
+                return;
+            }
+            tauri::async_runtime::spawn(async {
+                if let Err(e) = get_recording_manager().take_screenshot(None).await {
+                    warn!("[Hotkeys] Screenshot failed: {}", e);
+                }
+            });
+        }
+    }
+}
@@ -2,17 +2,87 @@
 //!
 //! This module contains handlers for each IPC request type.
 
-use crate::capture;
+use crate::capture::{self, ThumbnailResult};
 use crate::state::get_recording_manager;
-use omnirec_types::ipc::{Request, Response};
-use omnirec_types::{AudioConfig, CaptureRegion, OutputFormat, TranscriptionConfig};
+use omnirec_types::ipc::{format_error_chain, negotiate, Request, Response};
+use omnirec_types::{AudioConfig, CaptureRegion, OutputFormat, PipConfig, TranscriptionConfig};
+use tokio::sync::{mpsc, Mutex};
 use tracing::{debug, error, info, warn};
 
+/// Held while a capture is being started, for a recording or a dry run.
+///
+/// Requests are handled concurrently across connections, so this keeps two
+/// starts from both passing the idle check. A start that finds it held fails
+/// instead of waiting, so one client's start never holds up another client.
+static START_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Whether a request starts a capture.
+fn starts_capture(request: &Request) -> bool {
+    matches!(
+        request,
+        Request::StartWindowCapture { .. }
+            | Request::StartDisplayCapture { .. }
+            | Request::StartRegionCapture { .. }
+            | Request::StartPortalCapture
+            | Request::StartWorkspaceCapture { .. }
+            | Request::DryRunCapture { .. }
+    )
+}
+
+/// Whether a request changes recording state or settings.
+///
+/// The server runs such a request only once the connection's earlier
+/// requests are answered, and holds back its later ones until then.
+pub fn mutates_state(request: &Request) -> bool {
+    matches!(
+        request,
+        Request::StartWindowCapture { .. }
+            | Request::StartDisplayCapture { .. }
+            | Request::StartRegionCapture { .. }
+            | Request::StartPortalCapture
+            | Request::StartWorkspaceCapture { .. }
+            | Request::DryRunCapture { .. }
+            | Request::StopRecording
+            | Request::SetOutputFormat { .. }
+            | Request::SetAudioConfig { .. }
+            | Request::SetFrameRateLimit { .. }
+            | Request::SetSelection { .. }
+            | Request::StoreToken { .. }
+            | Request::RevokeApprovalToken { .. }
+            | Request::SetTranscriptionConfig { .. }
+            | Request::Shutdown
+    )
+}
+
 /// Handle an IPC request and return a response.
 pub async fn handle_request(request: Request) -> Response {
+    handle(request, None).await
+}
+
+/// Handle a start request, overlaying `pip` on the recording in place of the
+/// configured webcam.
+pub async fn handle_start_request(request: Request, pip: Option<PipConfig>) -> Response {
+    handle(request, pip).await
+}
+
+async fn handle(request: Request, pip: Option<PipConfig>) -> Response {
     debug!("Handling request: {:?}", request);
+    let start_guard = if starts_capture(&request) {
+        match START_LOCK.try_lock() {
+            Ok(guard) => Some(guard),
+            Err(_) => return Response::error("Another capture is being started"),
+        }
+    } else {
+        None
+    };
 
     match request {
+        // === Handshake ===
+        Request::Hello {
+            client_version,
+            min_protocol,
+        } => negotiate(client_version, min_protocol),
+
         // === Enumeration ===
         Request::ListWindows => {
             let windows = capture::list_windows();
@@ -34,7 +104,7 @@ pub async fn handle_request(request: Request) -> Response {
         Request::StartWindowCapture { window_handle } => {
             info!("StartWindowCapture: handle={}", window_handle);
             let manager = get_recording_manager();
-            match manager.start_window_capture(window_handle).await {
+            match manager.start_window_capture(window_handle, pip).await {
                 Ok(()) => Response::RecordingStarted,
                 Err(e) => {
                     error!("Failed to start window capture: {}", e);
@@ -53,7 +123,7 @@ pub async fn handle_request(request: Request) -> Response {
             );
             let manager = get_recording_manager();
             match manager
-                .start_display_capture(monitor_id, width, height)
+                .start_display_capture(monitor_id, width, height, pip)
                 .await
             {
                 Ok(()) => Response::RecordingStarted,
@@ -80,9 +150,10 @@ pub async fn handle_request(request: Request) -> Response {
                 y,
                 width,
                 height,
+                aspect_ratio: None,
             };
             let manager = get_recording_manager();
-            match manager.start_region_capture(region).await {
+            match manager.start_region_capture(region, pip).await {
                 Ok(()) => Response::RecordingStarted,
                 Err(e) => {
                     error!("Failed to start region capture: {}", e);
@@ -93,7 +164,7 @@ pub async fn handle_request(request: Request) -> Response {
         Request::StartPortalCapture => {
             info!("StartPortalCapture");
             let manager = get_recording_manager();
-            match manager.start_portal_capture().await {
+            match manager.start_portal_capture(pip).await {
                 Ok(()) => Response::RecordingStarted,
                 Err(e) => {
                     error!("Failed to start portal capture: {}", e);
@@ -101,6 +172,39 @@ pub async fn handle_request(request: Request) -> Response {
                 }
             }
         }
+        Request::StartWorkspaceCapture { workspace_id } => {
+            info!("StartWorkspaceCapture: {}", workspace_id);
+            let manager = get_recording_manager();
+            match manager.start_workspace_capture(workspace_id, pip).await {
+                Ok(()) => Response::RecordingStarted,
+                Err(e) => {
+                    error!("Failed to start workspace capture: {}", e);
+                    Response::error(e)
+                }
+            }
+        }
+        Request::DryRunCapture { capture } => {
+            info!("DryRunCapture: {:?}", capture);
+            let result = match crate::dry_run::start(*capture).await {
+                Ok(dry_run) => {
+                    // Once capturing, other starts need not wait for the frames
+                    drop(start_guard);
+                    dry_run.finish().await
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(summary) => Response::DryRunResult {
+                    frames_captured: summary.frames_captured,
+                    encoder_ok: summary.encoder_ok,
+                    audio_ok: summary.audio_ok,
+                },
+                Err(e) => {
+                    error!("Dry run failed: {}", e);
+                    Response::error(e)
+                }
+            }
+        }
         Request::StopRecording => {
             info!("StopRecording");
             let manager = get_recording_manager();
@@ -108,6 +212,7 @@ pub async fn handle_request(request: Request) -> Response {
                 Ok(result) => Response::RecordingStopped {
                     file_path: result.file_path.display().to_string(),
                     source_path: result.source_path.display().to_string(),
+                    copied_to_clipboard: result.copied_to_clipboard,
                 },
                 Err(e) => {
                     error!("Failed to stop recording: {}", e);
@@ -127,6 +232,11 @@ pub async fn handle_request(request: Request) -> Response {
             let seconds = manager.get_elapsed_seconds().await;
             Response::ElapsedTime { seconds }
         }
+        Request::GetCountdown => {
+            let manager = get_recording_manager();
+            let seconds = manager.get_countdown_secs().await;
+            Response::Countdown { seconds }
+        }
         Request::SubscribeEvents => {
             // TODO: Implement event subscription via streaming
             // For now, just acknowledge subscription
@@ -138,7 +248,10 @@ pub async fn handle_request(request: Request) -> Response {
             let manager = get_recording_manager();
             let format = manager.get_output_format().await;
             Response::OutputFormat {
-                format: format!("{:?}", format).to_lowercase(),
+                format: format.map_or_else(
+                    || "auto".to_string(),
+                    |format| format!("{:?}", format).to_lowercase(),
+                ),
             }
         }
         Request::SetOutputFormat { format } => {
@@ -170,8 +283,14 @@ pub async fn handle_request(request: Request) -> Response {
         } => {
             info!(
                 "SetAudioConfig: enabled={}, source={:?}, mic={:?}, aec={}, agc={}, agc_gate={}",
-                enabled, source_id, microphone_id, echo_cancellation, agc_enabled, agc_noise_gate_enabled
+                enabled,
+                source_id,
+                microphone_id,
+                echo_cancellation,
+                agc_enabled,
+                agc_noise_gate_enabled
             );
+            let manager = get_recording_manager();
             let config = AudioConfig {
                 enabled,
                 source_id,
@@ -179,44 +298,44 @@ pub async fn handle_request(request: Request) -> Response {
                 echo_cancellation,
                 agc_enabled,
                 agc_noise_gate_enabled,
+                // Monitoring is a local playback setting, not part of the request
+                ..manager.get_audio_config().await
             };
-            let manager = get_recording_manager();
             match manager.set_audio_config(config).await {
                 Ok(()) => Response::ok(),
                 Err(e) => Response::error(e),
             }
         }
+        Request::SetFrameRateLimit { max_fps } => {
+            info!("SetFrameRateLimit: {:?}", max_fps);
+            get_recording_manager().set_frame_rate_limit(max_fps).await;
+            Response::ok()
+        }
 
         // === Thumbnails ===
         Request::GetWindowThumbnail { window_handle } => {
-            use crate::capture::ThumbnailCapture;
-            let backend = capture::get_backend();
-            match backend.capture_window_thumbnail(window_handle) {
+            match window_thumbnail(window_handle).await {
                 Ok(result) => Response::Thumbnail {
                     data: result.data,
                     width: result.width,
                     height: result.height,
                 },
-                Err(e) => {
-                    warn!("Failed to capture window thumbnail: {}", e);
-                    Response::error(format!("Failed to capture thumbnail: {}", e))
-                }
+                Err(e) => Response::error(e),
             }
         }
-        Request::GetDisplayThumbnail { monitor_id } => {
-            use crate::capture::ThumbnailCapture;
-            let backend = capture::get_backend();
-            match backend.capture_display_thumbnail(&monitor_id) {
-                Ok(result) => Response::Thumbnail {
-                    data: result.data,
-                    width: result.width,
-                    height: result.height,
-                },
-                Err(e) => {
-                    warn!("Failed to capture display thumbnail: {}", e);
-                    Response::error(format!("Failed to capture thumbnail: {}", e))
-                }
-            }
+        Request::GetDisplayThumbnail { monitor_id } => match display_thumbnail(monitor_id).await {
+            Ok(result) => Response::Thumbnail {
+                data: result.data,
+                width: result.width,
+                height: result.height,
+            },
+            Err(e) => Response::error(e),
+        },
+        Request::GetThumbnailBatch { .. } => {
+            Response::error("Thumbnail batches are only sent as a streamed response")
+        }
+        Request::GetRecentEvents { .. } => {
+            Response::error("Recent events are only sent as a streamed response")
         }
         Request::GetRegionPreview {
             monitor_id,
@@ -227,15 +346,43 @@ pub async fn handle_request(request: Request) -> Response {
         } => {
             use crate::capture::ThumbnailCapture;
             let backend = capture::get_backend();
-            match backend.capture_region_preview(&monitor_id, x, y, width, height) {
+            let quality = get_recording_manager()
+                .get_thumbnail_quality()
+                .await
+                .region_quality;
+            match backend.capture_region_preview(&monitor_id, x, y, width, height, quality) {
                 Ok(result) => Response::Thumbnail {
                     data: result.data,
                     width: result.width,
                     height: result.height,
                 },
                 Err(e) => {
-                    warn!("Failed to capture region preview: {}", e);
-                    Response::error(format!("Failed to capture preview: {}", e))
+                    let message = format_error_chain(&e);
+                    warn!("Failed to capture region preview: {}", message);
+                    Response::error(format!("Failed to capture preview: {}", message))
+                }
+            }
+        }
+        Request::StartPreviewStream { fps } => {
+            match get_recording_manager().start_preview_stream(fps) {
+                Ok(()) => Response::ok(),
+                Err(e) => Response::error(e),
+            }
+        }
+        Request::StopPreviewStream => {
+            get_recording_manager().stop_preview_stream();
+            Response::ok()
+        }
+
+        // === Screenshots ===
+        Request::TakeScreenshot { target } => {
+            match get_recording_manager().take_screenshot(target).await {
+                Ok(path) => Response::ScreenshotSaved {
+                    path: path.display().to_string(),
+                },
+                Err(e) => {
+                    warn!("Failed to take screenshot: {}", e);
+                    Response::error(e)
                 }
             }
         }
@@ -247,18 +394,21 @@ pub async fn handle_request(request: Request) -> Response {
             width,
             height,
         } => {
-            capture::show_highlight(x, y, width, height);
+            let style = get_recording_manager().get_highlight_style().await;
+            capture::show_highlight(x, y, width, height, &style);
             Response::ok()
         }
         Request::ShowWindowHighlight { window_handle } => {
             // Get window geometry and show highlight
             let windows = capture::list_windows();
             if let Some(window) = windows.iter().find(|w| w.handle == window_handle) {
+                let style = get_recording_manager().get_highlight_style().await;
                 capture::show_highlight(
                     window.x,
                     window.y,
                     window.width as i32,
                     window.height as i32,
+                    &style,
                 );
                 Response::ok()
             } else {
@@ -312,7 +462,9 @@ pub async fn handle_request(request: Request) -> Response {
             #[cfg(target_os = "linux")]
             {
                 use crate::capture::linux::approval_token;
-                if let Err(e) = approval_token::write_token(&token) {
+                let (source_type, source_id) =
+                    crate::capture::linux::current_selection_source().await;
+                if let Err(e) = approval_token::write_token(&token, &source_type, &source_id) {
                     warn!("Failed to store approval token: {}", e);
                 }
             }
@@ -320,6 +472,59 @@ pub async fn handle_request(request: Request) -> Response {
             let _ = token; // Silence unused variable warning on non-Linux
             Response::TokenStored
         }
+        Request::ListApprovalTokens => {
+            #[cfg(target_os = "linux")]
+            let tokens = crate::capture::linux::approval_token::list_tokens();
+            #[cfg(not(target_os = "linux"))]
+            let tokens = Vec::new();
+            Response::ApprovalTokens { tokens }
+        }
+        Request::RevokeApprovalToken { token_id } => {
+            #[cfg(target_os = "linux")]
+            {
+                use crate::capture::linux::approval_token;
+                match approval_token::revoke_token(&token_id) {
+                    Ok(true) => Response::ok(),
+                    Ok(false) => Response::error(format!("No approval token {}", token_id)),
+                    Err(e) => Response::error(format!("Failed to revoke approval token: {}", e)),
+                }
+            }
+            #[cfg(not(target_os = "linux"))]
+            Response::error(format!("No approval token {}", token_id))
+        }
+        Request::SetSelection {
+            source_type,
+            source_id,
+            geometry,
+        } => {
+            #[cfg(target_os = "linux")]
+            {
+                use crate::capture::linux::ipc_server::{self, CaptureSelection, Geometry};
+                info!("SetSelection: type={}, id={}", source_type, source_id);
+                match crate::capture::linux::get_ipc_state() {
+                    Some(state) => {
+                        let selection = CaptureSelection {
+                            source_type,
+                            source_id,
+                            geometry: geometry.map(|g| Geometry {
+                                x: g.x,
+                                y: g.y,
+                                width: g.width,
+                                height: g.height,
+                            }),
+                        };
+                        ipc_server::set_selection(&state, selection).await;
+                        Response::ok()
+                    }
+                    None => Response::error("Picker IPC is not initialized"),
+                }
+            }
+            #[cfg(not(target_os = "linux"))]
+            {
+                let _ = (source_type, source_id, geometry);
+                Response::error("Source selection is only used by the Linux portal picker")
+            }
+        }
 
         // === Transcription ===
         Request::GetTranscriptionConfig => {
@@ -335,11 +540,13 @@ pub async fn handle_request(request: Request) -> Response {
                 "SetTranscriptionConfig: enabled={}, model_path={:?}",
                 enabled, model_path
             );
+            let manager = get_recording_manager();
+            // The request has no language; keep the one set by the app
             let config = TranscriptionConfig {
                 enabled,
                 model_path,
+                language: manager.get_transcription_config().await.language,
             };
-            let manager = get_recording_manager();
             match manager.set_transcription_config(config).await {
                 Ok(()) => Response::ok(),
                 Err(e) => Response::error(e),
@@ -366,6 +573,150 @@ pub async fn handle_request(request: Request) -> Response {
             crate::request_shutdown();
             Response::ok()
         }
+        Request::GetDiagnostics => {
+            let (ffmpeg, ffmpeg_version) =
+                tokio::task::spawn_blocking(crate::encoder::probe_ffmpeg)
+                    .await
+                    .unwrap_or_else(|_| (std::path::PathBuf::from("ffmpeg"), None));
+
+            #[cfg(target_os = "macos")]
+            let screen_recording_permission =
+                Some(crate::platform::macos::has_screen_recording_permission());
+            #[cfg(not(target_os = "macos"))]
+            let screen_recording_permission = None;
+
+            let output_directory = crate::config::load_config()
+                .output
+                .directory
+                .map(std::path::PathBuf::from)
+                .or_else(|| crate::config::get_default_output_dir().ok())
+                .map(|dir| dir.to_string_lossy().to_string());
+
+            Response::Diagnostics {
+                ffmpeg_path: ffmpeg.to_string_lossy().to_string(),
+                ffmpeg_version,
+                screen_recording_permission,
+                output_directory,
+            }
+        }
         Request::Ping => Response::Pong,
     }
 }
+
+/// Items a streamed response may buffer ahead of the client.
+const STREAM_BUFFER: usize = 4;
+
+/// Handle a request whose response is streamed (see
+/// [`Request::is_streaming`]), returning the items as they become ready.
+pub fn handle_streaming_request(request: Request) -> mpsc::Receiver<Response> {
+    debug!("Handling streaming request: {:?}", request);
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+
+    match request {
+        Request::GetThumbnailBatch {
+            window_handles,
+            monitor_ids,
+        } => {
+            info!(
+                "Capturing thumbnail batch of {} windows and {} displays",
+                window_handles.len(),
+                monitor_ids.len()
+            );
+            // The thumbnail pool bounds how many are captured at once
+            for window_handle in window_handles {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let result = window_thumbnail(window_handle).await;
+                    let _ = tx
+                        .send(batch_thumbnail(window_handle.to_string(), result))
+                        .await;
+                });
+            }
+            for monitor_id in monitor_ids {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let result = display_thumbnail(monitor_id.clone()).await;
+                    let _ = tx.send(batch_thumbnail(monitor_id, result)).await;
+                });
+            }
+        }
+        Request::GetRecentEvents { count, level } => {
+            match crate::event_log::recent_events(count as usize, level.as_deref()) {
+                Ok(events) => {
+                    tokio::spawn(async move {
+                        for event in events {
+                            if tx.send(Response::LogEvent(event)).await.is_err() {
+                                break;
+                            }
+                        }
+                    });
+                }
+                Err(e) => {
+                    let _ = tx.try_send(Response::error(e));
+                }
+            }
+        }
+        other => {
+            let _ = tx.try_send(Response::error(format!(
+                "Not a streaming request: {:?}",
+                other
+            )));
+        }
+    }
+    rx
+}
+
+fn batch_thumbnail(source_id: String, result: Result<ThumbnailResult, String>) -> Response {
+    match result {
+        Ok(result) => Response::BatchThumbnail {
+            source_id,
+            data: Some(result.data),
+            width: result.width,
+            height: result.height,
+            error: None,
+        },
+        Err(e) => Response::BatchThumbnail {
+            source_id,
+            data: None,
+            width: 0,
+            height: 0,
+            error: Some(e),
+        },
+    }
+}
+
+/// Capture a window thumbnail on the thumbnail pool.
+async fn window_thumbnail(window_handle: isize) -> Result<ThumbnailResult, String> {
+    use crate::capture::ThumbnailCapture;
+    let manager = get_recording_manager();
+    let quality = manager.get_thumbnail_quality().await.window_quality;
+    let options = manager.get_thumbnail_options().await.with_quality(quality);
+    let result = manager
+        .get_thumbnail_pool()
+        .await
+        .run(move || capture::get_backend().capture_window_thumbnail(window_handle, options))
+        .await?;
+    result.map_err(|e| {
+        let message = format_error_chain(&e);
+        warn!("Failed to capture window thumbnail: {}", message);
+        format!("Failed to capture thumbnail: {}", message)
+    })
+}
+
+/// Capture a display thumbnail on the thumbnail pool.
+async fn display_thumbnail(monitor_id: String) -> Result<ThumbnailResult, String> {
+    use crate::capture::ThumbnailCapture;
+    let manager = get_recording_manager();
+    let quality = manager.get_thumbnail_quality().await.display_quality;
+    let options = manager.get_thumbnail_options().await.with_quality(quality);
+    let result = manager
+        .get_thumbnail_pool()
+        .await
+        .run(move || capture::get_backend().capture_display_thumbnail(&monitor_id, options))
+        .await?;
+    result.map_err(|e| {
+        let message = format_error_chain(&e);
+        warn!("Failed to capture display thumbnail: {}", message);
+        format!("Failed to capture thumbnail: {}", message)
+    })
+}
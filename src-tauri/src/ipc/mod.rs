@@ -3,4 +3,5 @@
 //! This module provides the socket server for CLI communication.
 
 pub mod handlers;
-pub mod server;
\ No newline at end of file
+pub mod server;
+pub mod service_config;
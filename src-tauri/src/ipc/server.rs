@@ -1,8 +1,14 @@
 //! IPC server with secure socket setup and peer verification.
 
-use omnirec_types::ipc::{read_json, write_json, Request, Response};
+use omnirec_types::ipc::{
+    read_connection_start_with_limit, read_json_with_limit, write_json, ConnectionStart, IpcError,
+    Request, Response, StreamWriter,
+};
+use std::sync::Arc;
 use tokio::io::{AsyncRead, AsyncWrite};
-use tracing::{error, info, warn};
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
 
 #[cfg(unix)]
 use omnirec_types::ipc::get_socket_path;
@@ -11,7 +17,8 @@ use std::os::unix::fs::PermissionsExt;
 #[cfg(unix)]
 use std::path::Path;
 
-use super::handlers::handle_request;
+use super::handlers::{handle_request, handle_streaming_request, mutates_state};
+use super::service_config::ServiceConfig;
 
 /// Socket file permissions (owner read/write only)
 #[cfg(unix)]
@@ -56,66 +63,374 @@ fn secure_socket_file(socket_path: &Path) -> std::io::Result<()> {
     Ok(())
 }
 
-/// Handle a single authenticated client connection.
-async fn handle_client<S>(mut stream: S, peer_info: String)
+/// First file descriptor passed by systemd socket activation.
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: std::os::fd::RawFd = 3;
+
+/// The listening socket passed by systemd socket activation, if any.
+#[cfg(unix)]
+fn inherited_listen_fd() -> Option<std::os::fd::RawFd> {
+    parse_listen_fds(
+        std::env::var("LISTEN_PID").ok().as_deref(),
+        std::env::var("LISTEN_FDS").ok().as_deref(),
+        std::process::id(),
+    )
+}
+
+/// Read `LISTEN_PID` and `LISTEN_FDS`. The sockets are only ours when
+/// `LISTEN_PID` names this process, not a parent that left them set.
+#[cfg(unix)]
+fn parse_listen_fds(
+    listen_pid: Option<&str>,
+    listen_fds: Option<&str>,
+    pid: u32,
+) -> Option<std::os::fd::RawFd> {
+    let listen_pid: u32 = listen_pid?.trim().parse().ok()?;
+    let count: u32 = listen_fds?.trim().parse().ok()?;
+    if listen_pid != pid || count == 0 {
+        return None;
+    }
+    if count > 1 {
+        warn!("systemd passed {} sockets, using the first", count);
+    }
+    Some(SD_LISTEN_FDS_START)
+}
+
+/// Take over a listening Unix socket inherited from the parent process
+/// (systemd socket activation).
+#[cfg(unix)]
+pub fn from_inherited_fd(fd: std::os::fd::RawFd) -> std::io::Result<tokio::net::UnixListener> {
+    use std::os::fd::FromRawFd;
+
+    // Safety: the descriptor was passed to this process, which owns it from here on
+    let listener = unsafe { std::os::unix::net::UnixListener::from_raw_fd(fd) };
+    // Inherited descriptors lack close-on-exec; keep the socket out of
+    // FFmpeg and the picker
+    if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    listener.set_nonblocking(true)?;
+    tokio::net::UnixListener::from_std(listener)
+}
+
+/// Requests of one connection handled at the same time, at most.
+const MAX_IN_FLIGHT: usize = 16;
+
+/// Validate and handle one request.
+async fn respond(request: Request, peer_info: &str) -> Response {
+    if let Err(e) = request.validate() {
+        warn!("Invalid request from {}: {}", peer_info, e);
+        return Response::error(format!("Invalid request: {}", e));
+    }
+    handle_request(request).await
+}
+
+/// What a handler sends back: one response, or a streamed response.
+enum Reply {
+    Single(Response),
+    Stream(mpsc::Receiver<Response>),
+}
+
+/// Validate and handle one request of a versioned connection.
+async fn reply(request: Request, peer_info: &str) -> Reply {
+    if !request.is_streaming() {
+        return Reply::Single(respond(request, peer_info).await);
+    }
+    match request.validate() {
+        Ok(()) => Reply::Stream(handle_streaming_request(request)),
+        Err(e) => {
+            warn!("Invalid request from {}: {}", peer_info, e);
+            let (tx, rx) = mpsc::channel(1);
+            let _ = tx.try_send(Response::error(format!("Invalid request: {}", e)));
+            Reply::Stream(rx)
+        }
+    }
+}
+
+/// Handle a request in its own task, holding `turn` until it is handled.
+fn dispatch<T>(request: Request, peer_info: String, turn: T) -> JoinHandle<Reply>
+where
+    T: Send + 'static,
+{
+    tokio::spawn(async move {
+        let reply = reply(request, &peer_info).await;
+        drop(turn);
+        reply
+    })
+}
+
+/// Write a reply: one message, or the items of a stream as they arrive.
+async fn write_reply<W>(writer: &mut W, reply: Reply) -> Result<(), IpcError>
+where
+    W: AsyncWrite + Unpin,
+{
+    match reply {
+        Reply::Single(response) => write_json(writer, &response).await,
+        Reply::Stream(mut items) => {
+            let mut stream = StreamWriter::new(writer);
+            while let Some(item) = items.recv().await {
+                stream.write_item(&item).await?;
+            }
+            stream.finish().await
+        }
+    }
+}
+
+/// Write responses in request order as their handlers finish.
+///
+/// The protocol has no request IDs, so a response can't overtake the
+/// response to an earlier request; handlers still run concurrently.
+async fn write_responses<W>(
+    mut writer: W,
+    mut responses: mpsc::Receiver<JoinHandle<Reply>>,
+    peer_info: String,
+) where
+    W: AsyncWrite + Unpin,
+{
+    while let Some(handler) = responses.recv().await {
+        let reply = handler.await.unwrap_or_else(|e| {
+            error!("Request handler for {} failed: {}", peer_info, e);
+            Reply::Single(Response::error("Request handler failed"))
+        });
+        if let Err(e) = write_reply(&mut writer, reply).await {
+            error!("Error writing response to {}: {}", peer_info, e);
+            break;
+        }
+    }
+}
+
+/// Answer a message larger than the client may send. The connection is
+/// closed after this response, as the rest of the message is never read.
+fn message_too_large(peer_info: &str, size: usize, max: usize) -> Response {
+    warn!(
+        "Rejected {} byte message from {} (limit {} bytes)",
+        size, peer_info, max
+    );
+    Response::error("Message too large")
+}
+
+/// Read the protocol header and perform the `Hello` handshake.
+///
+/// Returns the response to the first request of an unversioned client, or
+/// `None` once a versioned client was welcomed. Returns an error if the
+/// connection should be closed.
+async fn open_connection<S>(
+    stream: &mut S,
+    peer_info: &str,
+    max_message_size: usize,
+) -> Result<Option<Response>, IpcError>
 where
     S: AsyncRead + AsyncWrite + Unpin,
+{
+    match read_connection_start_with_limit(stream, max_message_size).await? {
+        ConnectionStart::Versioned(version) => {
+            let response = match read_json_with_limit(stream, max_message_size).await? {
+                Request::Hello {
+                    client_version,
+                    min_protocol,
+                } => {
+                    debug!(
+                        "Client {} speaks protocol {} (header {}), needs >= {}",
+                        peer_info, client_version, version, min_protocol
+                    );
+                    handle_request(Request::Hello {
+                        client_version,
+                        min_protocol,
+                    })
+                    .await
+                }
+                _ => Response::error("Expected hello as the first request"),
+            };
+            let welcomed = matches!(response, Response::Welcome { .. });
+            write_json(stream, &response).await?;
+            if welcomed {
+                Ok(None)
+            } else {
+                warn!("Handshake with {} failed: {:?}", peer_info, response);
+                Err(IpcError::ConnectionClosed)
+            }
+        }
+        ConnectionStart::Unversioned(payload) => {
+            debug!("Client {} connected without a protocol header", peer_info);
+            let request: Request = serde_json::from_slice(&payload)
+                .map_err(|e| IpcError::ParseError(e.to_string()))?;
+            Ok(Some(respond(request, peer_info).await))
+        }
+    }
+}
+
+/// Handle a single authenticated client connection.
+///
+/// Requests are read and dispatched to their own tasks as they arrive, so a
+/// slow query doesn't hold up the ones after it. Requests that change state
+/// still take effect in the order they were sent: each waits for the
+/// connection's earlier requests, and the later ones wait for it. A writer
+/// task sends the responses back in order. Requests longer than
+/// `max_message_size` bytes close the connection.
+async fn handle_client<S>(mut stream: S, peer_info: String, max_message_size: usize)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
     info!("Client connected: {}", peer_info);
+    let _connection = crate::metrics::ConnectionGuard::open();
+
+    match open_connection(&mut stream, &peer_info, max_message_size).await {
+        Ok(Some(response)) => {
+            if let Err(e) = write_json(&mut stream, &response).await {
+                error!("Error writing response to {}: {}", peer_info, e);
+                return;
+            }
+        }
+        Ok(None) => {}
+        Err(IpcError::ConnectionClosed) => {
+            info!("Client disconnected: {}", peer_info);
+            return;
+        }
+        Err(IpcError::MessageTooLarge { size, max }) => {
+            let response = message_too_large(&peer_info, size, max);
+            let _ = write_json(&mut stream, &response).await;
+            return;
+        }
+        Err(e) => {
+            error!("Error opening connection from {}: {}", peer_info, e);
+            return;
+        }
+    }
+
+    let (mut reader, writer) = tokio::io::split(stream);
+    let (response_tx, response_rx) = mpsc::channel(MAX_IN_FLIGHT);
+    let writer_task = tokio::spawn(write_responses(writer, response_rx, peer_info.clone()));
+    // Queries share it, state changes take it alone; its queue is fair, so
+    // it is handed out in request order
+    let order = Arc::new(RwLock::new(()));
 
     loop {
         // Read request
-        let request: Request = match read_json(&mut stream).await {
+        let request: Request = match read_json_with_limit(&mut reader, max_message_size).await {
             Ok(req) => req,
-            Err(omnirec_types::ipc::IpcError::ConnectionClosed) => {
+            Err(IpcError::ConnectionClosed) => {
                 info!("Client disconnected: {}", peer_info);
                 break;
             }
+            Err(IpcError::MessageTooLarge { size, max }) => {
+                // Answered after the responses still pending, then closed
+                let response = message_too_large(&peer_info, size, max);
+                let handler = tokio::spawn(async move { Reply::Single(response) });
+                let _ = response_tx.send(handler).await;
+                break;
+            }
             Err(e) => {
                 error!("Error reading request from {}: {}", peer_info, e);
                 break;
             }
         };
 
-        // Validate request parameters
-        if let Err(e) = request.validate() {
-            warn!("Invalid request from {}: {}", peer_info, e);
-            let response = Response::error(format!("Invalid request: {}", e));
-            if let Err(e) = write_json(&mut stream, &response).await {
-                error!("Error writing response: {}", e);
-                break;
-            }
-            continue;
+        // Dispatch; waits here once MAX_IN_FLIGHT responses are pending
+        let handler_peer = peer_info.clone();
+        let handler = if mutates_state(&request) {
+            let turn = order.clone().write_owned().await;
+            dispatch(request, handler_peer, turn)
+        } else {
+            let turn = order.clone().read_owned().await;
+            dispatch(request, handler_peer, turn)
+        };
+        if response_tx.send(handler).await.is_err() {
+            // Writer stopped after a write error
+            break;
         }
+    }
 
-        // Handle request
-        let response = handle_request(request).await;
+    // Let the writer send the responses still pending
+    drop(response_tx);
+    let _ = writer_task.await;
+}
 
-        // Write response
-        if let Err(e) = write_json(&mut stream, &response).await {
-            error!("Error writing response to {}: {}", peer_info, e);
-            break;
+/// Apply the configured `SO_RCVBUF`/`SO_SNDBUF` sizes to a socket.
+#[cfg(unix)]
+fn set_socket_buffers(fd: std::os::fd::RawFd, config: &ServiceConfig) -> std::io::Result<()> {
+    for (option, size) in [
+        (libc::SO_RCVBUF, config.socket_recv_buf_size),
+        (libc::SO_SNDBUF, config.socket_send_buf_size),
+    ] {
+        if size == 0 {
+            continue;
+        }
+        let value = size as libc::c_int;
+        let result = unsafe {
+            libc::setsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                option,
+                &value as *const libc::c_int as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+        if result != 0 {
+            return Err(std::io::Error::last_os_error());
         }
     }
+    Ok(())
+}
+
+/// Receive and send buffer sizes the kernel actually uses for a socket
+/// (Linux reports double the requested size for bookkeeping).
+#[cfg(unix)]
+fn socket_buffer_sizes(fd: std::os::fd::RawFd) -> std::io::Result<(usize, usize)> {
+    let get = |option| {
+        let mut value: libc::c_int = 0;
+        let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                option,
+                &mut value as *mut libc::c_int as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        if result == 0 {
+            Ok(value as usize)
+        } else {
+            Err(std::io::Error::last_os_error())
+        }
+    };
+    Ok((get(libc::SO_RCVBUF)?, get(libc::SO_SNDBUF)?))
 }
 
 /// Run the IPC server (Unix implementation).
 #[cfg(unix)]
 pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
     use omnirec_types::security::peer_verify::verify_peer;
+    use std::os::fd::AsRawFd;
     use tokio::net::UnixListener;
 
+    let service_config = ServiceConfig::from_env();
     let socket_path = get_socket_path();
     info!("Starting IPC server at {:?}", socket_path);
 
-    // Create secure socket directory
-    create_secure_socket_dir(&socket_path)?;
+    let listener = match inherited_listen_fd() {
+        // systemd already bound the socket with the unit's permissions
+        Some(fd) => {
+            info!("Using socket passed by systemd (fd {})", fd);
+            from_inherited_fd(fd)?
+        }
+        None => {
+            // Create secure socket directory
+            create_secure_socket_dir(&socket_path)?;
+
+            // Bind socket
+            let listener = UnixListener::bind(&socket_path)?;
 
-    // Bind socket
-    let listener = UnixListener::bind(&socket_path)?;
+            // Set socket permissions AFTER binding
+            secure_socket_file(&socket_path)?;
+            listener
+        }
+    };
 
-    // Set socket permissions AFTER binding
-    secure_socket_file(&socket_path)?;
+    // Accepted connections inherit the listener's buffer sizes
+    if let Err(e) = set_socket_buffers(listener.as_raw_fd(), &service_config) {
+        warn!("Failed to set IPC socket buffer sizes: {}", e);
+    }
 
     info!("IPC server listening on {:?}", socket_path);
 
@@ -152,9 +467,19 @@ pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
                     "Authenticated peer: pid={} exe={:?}",
                     peer.pid, peer.executable
                 );
+                match socket_buffer_sizes(std_stream.as_raw_fd()) {
+                    Ok((recv, send)) => {
+                        debug!("Socket buffers: SO_RCVBUF={} SO_SNDBUF={}", recv, send)
+                    }
+                    Err(e) => debug!("Failed to read socket buffer sizes: {}", e),
+                }
                 let stream = tokio::net::UnixStream::from_std(std_stream)?;
                 let peer_info = format!("pid={}", peer.pid);
-                tokio::spawn(handle_client(stream, peer_info));
+                tokio::spawn(handle_client(
+                    stream,
+                    peer_info,
+                    service_config.max_message_size_bytes,
+                ));
             }
             Err(e) => {
                 warn!("Rejected connection: {:?}", e);
@@ -166,9 +491,13 @@ pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-/// Named pipe name for Windows IPC.
+/// Named pipe name for Windows IPC, one per session.
 #[cfg(windows)]
-const PIPE_NAME: &str = r"\\.\pipe\omnirec-service";
+fn pipe_name() -> String {
+    omnirec_types::ipc::get_socket_path()
+        .to_string_lossy()
+        .into_owned()
+}
 
 /// Create a security descriptor that only allows the current user.
 ///
@@ -275,24 +604,55 @@ fn create_security_attributes() -> Result<
 }
 
 /// Create a new named pipe server with security attributes.
+///
+/// Named pipes have no socket options; the configured buffer sizes become
+/// the pipe's input and output buffer sizes instead.
 #[cfg(windows)]
 fn create_pipe_server(
     first_instance: bool,
     sa: &mut windows::Win32::Security::SECURITY_ATTRIBUTES,
+    config: &ServiceConfig,
 ) -> Result<tokio::net::windows::named_pipe::NamedPipeServer, std::io::Error> {
     use tokio::net::windows::named_pipe::{PipeMode, ServerOptions};
-    
+
+    let mut options = ServerOptions::new();
+    options
+        .first_pipe_instance(first_instance)
+        .pipe_mode(PipeMode::Byte);
+    if config.socket_recv_buf_size > 0 {
+        options.in_buffer_size(config.socket_recv_buf_size as u32);
+    }
+    if config.socket_send_buf_size > 0 {
+        options.out_buffer_size(config.socket_send_buf_size as u32);
+    }
+
     unsafe {
-        ServerOptions::new()
-            .first_pipe_instance(first_instance)
-            .pipe_mode(PipeMode::Byte)
-            .create_with_security_attributes_raw(
-                PIPE_NAME,
-                sa as *mut _ as *mut std::ffi::c_void,
-            )
+        options
+            .create_with_security_attributes_raw(pipe_name(), sa as *mut _ as *mut std::ffi::c_void)
     }
 }
 
+/// Input and output buffer sizes of a named pipe.
+#[cfg(windows)]
+fn pipe_buffer_sizes(
+    handle: windows::Win32::Foundation::HANDLE,
+) -> windows::core::Result<(u32, u32)> {
+    use windows::Win32::System::Pipes::GetNamedPipeInfo;
+
+    let mut in_size = 0u32;
+    let mut out_size = 0u32;
+    unsafe {
+        GetNamedPipeInfo(
+            handle,
+            None,
+            Some(&mut out_size as *mut u32),
+            Some(&mut in_size as *mut u32),
+            None,
+        )?;
+    }
+    Ok((in_size, out_size))
+}
+
 /// Run the IPC server (Windows implementation).
 #[cfg(windows)]
 pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
@@ -300,14 +660,16 @@ pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
     use std::os::windows::io::AsRawHandle;
     use windows::Win32::Foundation::HANDLE;
 
-    info!("Starting IPC server at {}", PIPE_NAME);
+    let service_config = ServiceConfig::from_env();
+    let pipe_name = pipe_name();
+    info!("Starting IPC server at {}", pipe_name);
 
-    info!("IPC server listening on {}", PIPE_NAME);
+    info!("IPC server listening on {}", pipe_name);
 
     // Create the first pipe instance
     let mut server = {
         let (mut sa, _sd_bytes) = create_security_attributes()?;
-        create_pipe_server(true, &mut sa)?
+        create_pipe_server(true, &mut sa, &service_config)?
     };
 
     loop {
@@ -336,16 +698,26 @@ pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
                             "Authenticated peer: pid={} exe={:?}",
                             peer.pid, peer.executable
                         );
+                        match pipe_buffer_sizes(handle) {
+                            Ok((in_size, out_size)) => {
+                                debug!("Pipe buffers: in={} out={}", in_size, out_size)
+                            }
+                            Err(e) => debug!("Failed to read pipe buffer sizes: {}", e),
+                        }
 
                         // Take ownership of the connected pipe and create a new one for the next client
                         let connected_pipe = server;
                         server = {
                             let (mut sa, _sd_bytes) = create_security_attributes()?;
-                            create_pipe_server(false, &mut sa)?
+                            create_pipe_server(false, &mut sa, &service_config)?
                         };
 
                         let peer_info = format!("pid={}", peer.pid);
-                        tokio::spawn(handle_client(connected_pipe, peer_info));
+                        tokio::spawn(handle_client(
+                            connected_pipe,
+                            peer_info,
+                            service_config.max_message_size_bytes,
+                        ));
                     }
                     Err(e) => {
                         warn!("Rejected connection: {:?}", e);
@@ -359,7 +731,7 @@ pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
                 // Try to recreate the pipe
                 server = {
                     let (mut sa, _sd_bytes) = create_security_attributes()?;
-                    create_pipe_server(false, &mut sa)?
+                    create_pipe_server(false, &mut sa, &service_config)?
                 };
             }
             None => {
@@ -371,3 +743,101 @@ pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use omnirec_types::ipc::{read_json, write_message, MAX_MESSAGE_SIZE};
+
+    #[tokio::test]
+    async fn test_pipelined_responses_keep_request_order() {
+        let (mut client, server) = tokio::io::duplex(4096);
+        let connection = tokio::spawn(handle_client(server, "test".to_string(), MAX_MESSAGE_SIZE));
+
+        // The invalid request's error must come back between the pongs
+        write_json(&mut client, &Request::Ping).await.unwrap();
+        let invalid = Request::GetWindowThumbnail { window_handle: -1 };
+        write_json(&mut client, &invalid).await.unwrap();
+        write_json(&mut client, &Request::Ping).await.unwrap();
+
+        let first: Response = read_json(&mut client).await.unwrap();
+        let second: Response = read_json(&mut client).await.unwrap();
+        let third: Response = read_json(&mut client).await.unwrap();
+        assert!(matches!(first, Response::Pong));
+        assert!(matches!(second, Response::Error { .. }));
+        assert!(matches!(third, Response::Pong));
+
+        drop(client);
+        connection.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_streamed_response_keeps_request_order() {
+        use omnirec_types::ipc::StreamReader;
+
+        let (mut client, server) = tokio::io::duplex(4096);
+        let connection = tokio::spawn(handle_client(server, "test".to_string(), MAX_MESSAGE_SIZE));
+
+        // The first request opens an unversioned connection
+        write_json(&mut client, &Request::Ping).await.unwrap();
+        let invalid = Request::GetThumbnailBatch {
+            window_handles: vec![-1],
+            monitor_ids: Vec::new(),
+        };
+        write_json(&mut client, &invalid).await.unwrap();
+        write_json(&mut client, &Request::Ping).await.unwrap();
+
+        let pong: Response = read_json(&mut client).await.unwrap();
+        assert!(matches!(pong, Response::Pong));
+        let mut stream = StreamReader::new(&mut client);
+        let item: Response = stream.next_item().await.unwrap().unwrap();
+        assert!(matches!(item, Response::Error { .. }));
+        assert!(stream.next_item::<Response>().await.unwrap().is_none());
+        let pong: Response = read_json(&mut client).await.unwrap();
+        assert!(matches!(pong, Response::Pong));
+
+        drop(client);
+        connection.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_oversized_message_closes_connection() {
+        let (mut client, server) = tokio::io::duplex(4096);
+        let connection = tokio::spawn(handle_client(server, "test".to_string(), 1024));
+
+        write_json(&mut client, &Request::Ping).await.unwrap();
+        write_message(&mut client, &[b' '; 2048]).await.unwrap();
+
+        let pong: Response = read_json(&mut client).await.unwrap();
+        assert!(matches!(pong, Response::Pong));
+        let rejected: Response = read_json(&mut client).await.unwrap();
+        assert!(
+            matches!(rejected, Response::Error { ref message } if message == "Message too large")
+        );
+        connection.await.unwrap();
+        let closed = read_json::<_, Response>(&mut client).await;
+        assert!(matches!(closed, Err(IpcError::ConnectionClosed)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_listen_fds_for_this_process() {
+        assert_eq!(
+            parse_listen_fds(Some("42"), Some("1"), 42),
+            Some(SD_LISTEN_FDS_START)
+        );
+        assert_eq!(
+            parse_listen_fds(Some("42"), Some("2"), 42),
+            Some(SD_LISTEN_FDS_START)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_listen_fds_ignored_when_not_ours() {
+        assert_eq!(parse_listen_fds(None, None, 42), None);
+        assert_eq!(parse_listen_fds(Some("41"), Some("1"), 42), None);
+        assert_eq!(parse_listen_fds(Some("42"), Some("0"), 42), None);
+        assert_eq!(parse_listen_fds(Some("42"), Some("x"), 42), None);
+    }
+}
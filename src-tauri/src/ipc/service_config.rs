@@ -0,0 +1,140 @@
+//! Service settings read from the environment.
+//!
+//! Unlike [`AppConfig`](crate::config::AppConfig), these are tuning knobs
+//! for the IPC transport that are not shown in the UI or saved to disk.
+//! The socket path itself comes from `OMNIREC_SOCKET` or `--socket` (see
+//! [`omnirec_types::ipc::get_socket_path`]).
+
+use omnirec_types::ipc::MAX_MESSAGE_SIZE;
+use tracing::warn;
+
+/// Environment variable for the IPC socket receive buffer size in bytes.
+pub const SOCK_RCVBUF_ENV: &str = "OMNIREC_SOCK_RCVBUF";
+
+/// Environment variable for the IPC socket send buffer size in bytes.
+pub const SOCK_SNDBUF_ENV: &str = "OMNIREC_SOCK_SNDBUF";
+
+/// Environment variable for the largest request message accepted, in bytes.
+pub const MAX_MESSAGE_SIZE_ENV: &str = "OMNIREC_MAX_MESSAGE_SIZE";
+
+/// IPC transport settings. A buffer size of 0 keeps the OS default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServiceConfig {
+    /// Receive buffer size of client connections (`SO_RCVBUF`, or the named
+    /// pipe's input buffer on Windows)
+    pub socket_recv_buf_size: usize,
+    /// Send buffer size of client connections (`SO_SNDBUF`, or the named
+    /// pipe's output buffer on Windows)
+    pub socket_send_buf_size: usize,
+    /// Largest message a client may send; the connection is closed after a
+    /// larger one is announced, before its payload is read
+    pub max_message_size_bytes: usize,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self {
+            socket_recv_buf_size: 0,
+            socket_send_buf_size: 0,
+            max_message_size_bytes: MAX_MESSAGE_SIZE,
+        }
+    }
+}
+
+impl ServiceConfig {
+    /// Read the settings from `OMNIREC_SOCK_RCVBUF`, `OMNIREC_SOCK_SNDBUF`
+    /// and `OMNIREC_MAX_MESSAGE_SIZE`.
+    pub fn from_env() -> Self {
+        Self::from_vars(
+            std::env::var(SOCK_RCVBUF_ENV).ok(),
+            std::env::var(SOCK_SNDBUF_ENV).ok(),
+            std::env::var(MAX_MESSAGE_SIZE_ENV).ok(),
+        )
+    }
+
+    fn from_vars(recv: Option<String>, send: Option<String>, max_message: Option<String>) -> Self {
+        Self {
+            socket_recv_buf_size: parse_size(SOCK_RCVBUF_ENV, recv),
+            socket_send_buf_size: parse_size(SOCK_SNDBUF_ENV, send),
+            max_message_size_bytes: match parse_size(MAX_MESSAGE_SIZE_ENV, max_message) {
+                0 => MAX_MESSAGE_SIZE,
+                size => size,
+            },
+        }
+    }
+}
+
+/// The `--socket <path>` the app was started with: the IPC socket (named
+/// pipe on Windows) a spawning CLI expects instead of the session default.
+pub fn socket_path_from_args<I>(args: I) -> Option<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let value = match arg.split_once('=') {
+            Some(("--socket", value)) => value.to_string(),
+            None if arg == "--socket" => args.next()?,
+            _ => continue,
+        };
+        return Some(value).filter(|value| !value.is_empty());
+    }
+    None
+}
+
+fn parse_size(name: &str, value: Option<String>) -> usize {
+    let Some(value) = value else {
+        return 0;
+    };
+    match value.trim().parse::<u32>() {
+        Ok(size) => size as usize,
+        Err(_) => {
+            warn!("Ignoring {}={:?}: not a size in bytes", name, value);
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes_from_vars() {
+        let config = ServiceConfig::from_vars(
+            Some("262144".into()),
+            Some(" 1048576 ".into()),
+            Some("4096".into()),
+        );
+        assert_eq!(config.socket_recv_buf_size, 262144);
+        assert_eq!(config.socket_send_buf_size, 1048576);
+        assert_eq!(config.max_message_size_bytes, 4096);
+    }
+
+    #[test]
+    fn test_socket_path_from_args() {
+        let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            socket_path_from_args(args(&["--headless", "--socket", "/run/a.sock"])),
+            Some("/run/a.sock".to_string())
+        );
+        assert_eq!(
+            socket_path_from_args(args(&["--socket=/run/b.sock"])),
+            Some("/run/b.sock".to_string())
+        );
+        assert_eq!(socket_path_from_args(args(&["--headless"])), None);
+        assert_eq!(socket_path_from_args(args(&["--socket"])), None);
+    }
+
+    #[test]
+    fn test_unset_or_invalid_keeps_default() {
+        assert_eq!(
+            ServiceConfig::from_vars(None, None, None),
+            ServiceConfig::default()
+        );
+        let config =
+            ServiceConfig::from_vars(Some("1M".into()), Some("-1".into()), Some("0".into()));
+        assert_eq!(config, ServiceConfig::default());
+        assert_eq!(config.max_message_size_bytes, MAX_MESSAGE_SIZE);
+    }
+}
@@ -3,8 +3,10 @@
 //! This is the main entry point for the Tauri backend. The code is organized into
 //! several modules:
 //!
+//! - `app_state` - Shared state managed by Tauri (configuration, readiness)
 //! - `commands` - Tauri command handlers organized by functionality
 //! - `config` - Application configuration persistence
+//! - `config_sync` - Applying configuration to the recording subsystem and reloading it
 //! - `capture` - Platform-specific screen, window, region, and audio capture backends
 //! - `encoder` - FFmpeg-based video encoding and transcoding
 //! - `transcription` - Whisper.cpp voice transcription
@@ -13,20 +15,47 @@
 //! - `platform` - Minimal platform-specific functionality (e.g., macOS permission checks)
 //! - `tray` - Cross-platform system tray functionality
 
+mod api;
+mod app_state;
 mod capture;
+mod cloud;
 mod commands;
 mod config;
+mod config_sync;
+mod crash;
+mod deep_link;
+mod dry_run;
 mod encoder;
+mod event_log;
+mod history;
+mod hotkeys;
 pub mod ipc;
+mod metrics;
+mod notification;
 mod platform;
 pub mod state;
+#[cfg(test)]
+mod test_utils;
 pub mod tray;
+mod update;
+mod waveform;
+
+/// Items measured by the benchmarks in `benches/`, which can't reach the
+/// private modules.
+#[doc(hidden)]
+pub mod bench {
+    pub use crate::capture::thumbnail::{
+        bgra_to_jpeg_thumbnail, DEFAULT_JPEG_QUALITY, THUMBNAIL_MAX_HEIGHT, THUMBNAIL_MAX_WIDTH,
+    };
+    pub use crate::capture::types::{timestamp_us, PixelFormat};
+    pub use crate::capture::CapturedFrame;
+    pub use crate::encoder::VideoEncoder;
+}
 
-use config::{load_config, save_config, AppConfig, LogLevel};
+use config::{load_config, save_config, LogLevel, LoggingConfig};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::OnceLock;
-use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::{reload, EnvFilter};
@@ -53,6 +82,9 @@ pub fn is_shutdown_requested() -> bool {
     get_shutdown_flag().load(Ordering::SeqCst)
 }
 
+// Re-export application state for use in commands
+pub use app_state::{AppConfigState, AppStateBuilder, HeadlessState, ServiceReadyState};
+
 // Re-export tray types for use in commands
 pub use tray::TrayState;
 
@@ -67,7 +99,7 @@ pub use tray::GnomeTrayState;
 /// A single pre-formatted log line sent to the frontend log viewer.
 ///
 /// The line is formatted identically to what `tracing_subscriber::fmt` writes
-/// to the log file, so history (read from file) and live events render the same.
+/// to the log file, so history (recent events) and live events render the same.
 ///
 /// Format: `{timestamp}  {LEVEL} {target}: {message}\n`
 /// e.g.  `2026-03-02T00:27:33.464210Z  INFO omnirec_lib: engine started`
@@ -127,8 +159,8 @@ where
         let now = chrono::Utc::now();
         let level_str = match *event.metadata().level() {
             tracing::Level::ERROR => "ERROR",
-            tracing::Level::WARN  => " WARN",
-            tracing::Level::INFO  => " INFO",
+            tracing::Level::WARN => " WARN",
+            tracing::Level::INFO => " INFO",
             tracing::Level::DEBUG => "DEBUG",
             tracing::Level::TRACE => "TRACE",
         };
@@ -172,13 +204,152 @@ fn is_dev_mode() -> bool {
         .unwrap_or(false)
 }
 
+// =============================================================================
+// Size-capped log appender
+// =============================================================================
+
+/// Filename prefix shared by all application log files.
+const LOG_FILE_PREFIX: &str = "omnirec-app";
+
+/// A log writer that rotates daily and additionally starts a new file once
+/// the current one grows past `max_bytes`.
+///
+/// `tracing_appender` only rotates on time boundaries, so when the size limit
+/// is reached the inner appender is rebuilt with a numbered prefix
+/// (`omnirec-app.1.<date>.log`, `omnirec-app.2.<date>.log`, ...). Numbering
+/// starts over each day.
+struct SizeCappedAppender {
+    dir: std::path::PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    segment: u32,
+    written: u64,
+    /// UTC day of the current file, as the inner appender rotates on
+    date: chrono::NaiveDate,
+    inner: tracing_appender::rolling::RollingFileAppender,
+}
+
+impl SizeCappedAppender {
+    fn new(
+        dir: &std::path::Path,
+        max_bytes: u64,
+        max_files: usize,
+    ) -> Result<Self, tracing_appender::rolling::InitError> {
+        // Resume after the last full segment for today so restarts don't keep
+        // appending to an oversized file.
+        let date = chrono::Utc::now().date_naive();
+        let today = date.format("%Y-%m-%d").to_string();
+        let mut segment = 0;
+        let written = loop {
+            let path = dir.join(format!("{}.{}.log", Self::prefix(segment), today));
+            let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            if max_bytes == 0 || size < max_bytes {
+                break size;
+            }
+            segment += 1;
+        };
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            max_bytes,
+            max_files,
+            segment,
+            written,
+            date,
+            inner: Self::build(dir, segment, max_files)?,
+        })
+    }
+
+    fn prefix(segment: u32) -> String {
+        if segment == 0 {
+            LOG_FILE_PREFIX.to_string()
+        } else {
+            format!("{}.{}", LOG_FILE_PREFIX, segment)
+        }
+    }
+
+    fn build(
+        dir: &std::path::Path,
+        segment: u32,
+        max_files: usize,
+    ) -> Result<tracing_appender::rolling::RollingFileAppender, tracing_appender::rolling::InitError>
+    {
+        tracing_appender::rolling::RollingFileAppender::builder()
+            .rotation(tracing_appender::rolling::Rotation::DAILY)
+            .max_log_files(max_files.max(1))
+            .filename_prefix(Self::prefix(segment))
+            .filename_suffix("log")
+            .build(dir)
+    }
+
+    /// Switch to the next numbered segment and enforce the file count limit.
+    fn roll_over(&mut self) {
+        match Self::build(&self.dir, self.segment + 1, self.max_files) {
+            Ok(inner) => {
+                self.inner = inner;
+                self.segment += 1;
+                omnirec_types::logging::prune_logs(
+                    &self.dir,
+                    std::time::Duration::MAX,
+                    self.max_files,
+                );
+            }
+            Err(e) => {
+                // Keep writing to the current file rather than dropping lines.
+                eprintln!("Warning: Failed to roll over log file: {}", e);
+            }
+        }
+        self.written = 0;
+    }
+
+    /// Go back to the unnumbered file when the inner appender has moved on
+    /// to a new day's file.
+    fn start_day(&mut self, date: chrono::NaiveDate) {
+        self.date = date;
+        self.written = 0;
+        if self.segment == 0 {
+            return;
+        }
+        match Self::build(&self.dir, 0, self.max_files) {
+            Ok(inner) => {
+                self.inner = inner;
+                self.segment = 0;
+            }
+            Err(e) => eprintln!("Warning: Failed to start the day's log file: {}", e),
+        }
+    }
+}
+
+impl std::io::Write for SizeCappedAppender {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let today = chrono::Utc::now().date_naive();
+        if today != self.date {
+            self.start_day(today);
+        }
+        if self.max_bytes > 0 && self.written >= self.max_bytes {
+            self.roll_over();
+        }
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// Initialize the layered tracing subscriber.
 ///
+/// Old log files are pruned according to `logging` before the appender is
+/// created.
+///
 /// Returns:
 /// - A `LogState` containing the reload handle (stored in Tauri state).
 /// - An mpsc receiver for log lines (consumed by a forwarder task after app build).
 fn init_logging(
     initial_level: &LogLevel,
+    logging: &LoggingConfig,
 ) -> (LogState, tokio::sync::mpsc::Receiver<LogLinePayload>) {
     let filter_str = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new(initial_level.as_filter_str()));
@@ -202,25 +373,19 @@ fn init_logging(
     let log_path = omnirec_types::logging::app_log_path();
     let log_dir = log_path.parent().unwrap();
 
-    let file_appender = match tracing_appender::rolling::RollingFileAppender::builder()
-        .rotation(tracing_appender::rolling::Rotation::DAILY)
-        .max_log_files(5)
-        .filename_prefix("omnirec-app")
-        .filename_suffix("log")
-        .build(log_dir)
-    {
+    let max_age = std::time::Duration::from_secs(u64::from(logging.max_age_days) * 86_400);
+    let max_files = logging.max_files as usize;
+    let max_bytes = logging.max_file_size_mb.saturating_mul(1024 * 1024);
+    let pruned = omnirec_types::logging::prune_logs(log_dir, max_age, max_files);
+
+    let file_appender = match SizeCappedAppender::new(log_dir, max_bytes, max_files) {
         Ok(appender) => appender,
         Err(e) => {
             // pre-subscriber bootstrap: cannot use tracing yet
             eprintln!("Warning: Failed to create log file appender: {}", e);
             let temp_dir = std::env::temp_dir().join("omnirec-logs");
             let _ = std::fs::create_dir_all(&temp_dir);
-            tracing_appender::rolling::RollingFileAppender::builder()
-                .rotation(tracing_appender::rolling::Rotation::DAILY)
-                .max_log_files(5)
-                .filename_prefix("omnirec-app")
-                .filename_suffix("log")
-                .build(&temp_dir)
+            SizeCappedAppender::new(&temp_dir, max_bytes, max_files)
                 .expect("Failed to create temp log file appender")
         }
     };
@@ -228,9 +393,9 @@ fn init_logging(
     // Use the rolling appender directly (synchronous) instead of non_blocking.
     // Non-blocking buffers writes in a background channel; forgetting the guard
     // means that buffer is never flushed. Synchronous writes go to the OS
-    // immediately, so get_log_history can read them right away.
+    // immediately, so downloaded logs include them right away.
     let file_fmt_layer = tracing_subscriber::fmt::layer()
-        .with_writer(file_appender)
+        .with_writer(std::sync::Mutex::new(file_appender))
         .with_ansi(false);
 
     if is_dev_mode() {
@@ -244,15 +409,21 @@ fn init_logging(
             .with(file_fmt_layer)
             .with(stdout_fmt_layer)
             .with(tauri_layer)
+            .with(event_log::EventLogLayer)
             .init();
     } else {
         tracing_subscriber::registry()
             .with(filter_layer)
             .with(file_fmt_layer)
             .with(tauri_layer)
+            .with(event_log::EventLogLayer)
             .init();
     }
 
+    if pruned > 0 {
+        info!("[Logging] Removed {} expired log file(s)", pruned);
+    }
+
     let log_state = LogState {
         reload_handle: Arc::new(reload_handle),
     };
@@ -260,47 +431,6 @@ fn init_logging(
     (log_state, rx)
 }
 
-// =============================================================================
-// Application State
-// =============================================================================
-
-/// Application state wrapper.
-///
-/// This struct holds all the shared state for the application, including:
-/// - Application configuration (persisted locally)
-/// - Recording subsystem readiness flag
-/// - Headless mode flag (started with --headless)
-///
-/// The RecordingManager is a `'static` singleton accessed via
-/// `state::get_recording_manager()` — it is not stored in AppState.
-pub struct AppState {
-    /// Application configuration (UI preferences, output directory, etc.).
-    pub app_config: Arc<Mutex<AppConfig>>,
-    /// Whether the recording subsystem is initialized and ready.
-    pub service_ready: Arc<AtomicBool>,
-    /// Whether the app was launched in headless mode (--headless).
-    pub headless: bool,
-}
-
-impl AppState {
-    fn new(headless: bool) -> Self {
-        // Load configuration
-        let app_config = load_config();
-        info!("[AppState] Loaded config: {:?}", app_config);
-
-        Self {
-            app_config: Arc::new(Mutex::new(app_config)),
-            service_ready: Arc::new(AtomicBool::new(false)),
-            headless,
-        }
-    }
-
-    /// Check if the recording subsystem is ready.
-    pub fn is_service_ready(&self) -> bool {
-        self.service_ready.load(Ordering::SeqCst)
-    }
-}
-
 // =============================================================================
 // macOS Window Setup
 // =============================================================================
@@ -359,41 +489,23 @@ fn log_to_file(level: String, message: String) {
     }
 }
 
-/// Return the raw text of the current session's log file.
-///
-/// Finds the most recently modified `omnirec-app.*.log` file in the log
-/// directory — this is the file the rolling appender is currently writing to.
-/// Returns an empty string if no log file exists yet.
-#[tauri::command]
-fn get_log_history() -> String {
-    let log_dir = omnirec_types::logging::app_log_path()
+/// Resolve the directory the rolling appender writes log files to.
+fn current_log_dir() -> std::path::PathBuf {
+    omnirec_types::logging::app_log_path()
         .parent()
         .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| std::env::temp_dir().join("omnirec-logs"));
-
-    // Find the most recently modified omnirec-app.*.log file.
-    let most_recent = std::fs::read_dir(&log_dir)
-        .ok()
-        .into_iter()
-        .flatten()
-        .filter_map(|e| e.ok())
-        .filter(|e| {
-            let name = e.file_name();
-            let name = name.to_string_lossy();
-            name.starts_with("omnirec-app.") && name.ends_with(".log")
-        })
-        .filter_map(|e| {
-            let meta = e.metadata().ok()?;
-            let modified = meta.modified().ok()?;
-            Some((e.path(), modified))
-        })
-        .max_by_key(|(_, modified)| *modified)
-        .map(|(path, _)| path);
+        .unwrap_or_else(|| std::env::temp_dir().join("omnirec-logs"))
+}
 
-    match most_recent {
-        Some(path) => std::fs::read_to_string(&path).unwrap_or_default(),
-        None => String::new(),
-    }
+/// Return the most recent log messages, oldest first, from memory.
+///
+/// With `level`, only messages at least that severe are returned.
+#[tauri::command]
+fn get_recent_events(
+    count: u32,
+    level: Option<String>,
+) -> Result<Vec<omnirec_types::LogEvent>, String> {
+    event_log::recent_events(count as usize, level.as_deref())
 }
 
 /// Get the current log level from config.
@@ -405,10 +517,7 @@ fn get_log_level() -> Result<String, String> {
 
 /// Set the minimum log level at runtime and persist to config.
 #[tauri::command]
-fn set_log_level(
-    level: String,
-    state: tauri::State<'_, LogState>,
-) -> Result<(), String> {
+fn set_log_level(level: String, state: tauri::State<'_, LogState>) -> Result<(), String> {
     let log_level: LogLevel = match level.as_str() {
         "error" => LogLevel::Error,
         "warn" => LogLevel::Warn,
@@ -438,10 +547,7 @@ async fn download_logs(app_handle: tauri::AppHandle) -> Result<(), String> {
     use std::io::Write;
     use tauri_plugin_dialog::DialogExt;
 
-    let log_dir = omnirec_types::logging::app_log_path()
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| std::env::temp_dir().join("omnirec-logs"));
+    let log_dir = current_log_dir();
 
     // Collect *.log files.
     let entries: Vec<std::path::PathBuf> = match std::fs::read_dir(&log_dir) {
@@ -491,8 +597,7 @@ async fn download_logs(app_handle: tauri::AppHandle) -> Result<(), String> {
 
     match rx.await {
         Ok(Some(dest)) => {
-            std::fs::write(&dest, &zip_bytes)
-                .map_err(|e| format!("Failed to write zip: {}", e))?;
+            std::fs::write(&dest, &zip_bytes).map_err(|e| format!("Failed to write zip: {}", e))?;
         }
         Ok(None) => {
             // User cancelled — not an error.
@@ -543,6 +648,14 @@ async fn check_for_updates(app: tauri::AppHandle) -> Result<UpdateInfo, String>
     }
 }
 
+/// Check GitHub Releases for a newer version.
+///
+/// Results are cached for 24 hours; works for installs the updater cannot replace.
+#[tauri::command]
+async fn check_for_update() -> Result<update::UpdateCheckResult, String> {
+    update::check_for_update(false).await
+}
+
 /// Download and install an available update, then restart the application.
 ///
 /// Emits `update-download-progress` events during the download phase with
@@ -590,33 +703,90 @@ pub fn run() {
     // Parse --headless flag from command-line arguments
     let headless = std::env::args().any(|arg| arg == "--headless");
 
+    // A spawning CLI names its session's socket with --socket. Exporting it
+    // before any threads start also hands it to the picker.
+    if let Some(path) = ipc::service_config::socket_path_from_args(std::env::args().skip(1)) {
+        std::env::set_var(omnirec_types::ipc::SOCKET_PATH_ENV, path);
+    }
+
     // Read config before initializing logging so we can use the configured level.
     let initial_config = load_config();
 
     // Initialize layered logging subscriber with reloadable filter.
     // This must happen before any tracing calls.
-    let (log_state, log_rx) = init_logging(&initial_config.log_level);
+    let (log_state, log_rx) = init_logging(&initial_config.log_level, &initial_config.logging);
+
+    info!("[Startup] Loaded config: {:?}", initial_config);
+    config_sync::apply_capture_settings(&initial_config);
+    let app_states = AppStateBuilder::new()
+        .config(initial_config)
+        .headless(headless)
+        .build();
+
+    #[cfg(target_os = "windows")]
+    {
+        crash::report_previous_crashes();
+        crash::install_crash_handler();
+    }
+
+    // Warn about entitlements missing from the code signature
+    #[cfg(target_os = "macos")]
+    std::thread::spawn(|| platform::macos::EntitlementsValidator::new().check());
 
     if headless {
         info!("[Startup] Running in headless mode (tray only, no main window)");
     }
 
+    // Parse --listen/--api-token for the optional REST API
+    let api_options = match api::ApiOptions::from_args(
+        std::env::args().skip(1),
+        std::env::var(api::API_TOKEN_ENV).ok(),
+    ) {
+        Ok(options) => options,
+        Err(e) => {
+            error!("[Startup] REST API disabled: {}", e);
+            None
+        }
+    };
+
+    // Parse --metrics-port/--metrics-bind for the standalone Prometheus endpoint
+    let metrics_options = match metrics::MetricsOptions::from_args(
+        std::env::args().skip(1),
+        std::env::var(api::API_TOKEN_ENV).ok(),
+    ) {
+        Ok(options) => options,
+        Err(e) => {
+            error!("[Startup] Metrics server disabled: {}", e);
+            None
+        }
+    };
+
     #[cfg(target_os = "macos")]
     let mut builder = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
-        .manage(AppState::new(headless))
+        .plugin(hotkeys::plugin())
         .manage(log_state);
 
     #[cfg(not(target_os = "macos"))]
     let builder = tauri::Builder::default()
+        // Must be registered first; forwards deep links opened while running
+        .plugin(tauri_plugin_single_instance::init(|_app, argv, _cwd| {
+            debug!("[Startup] Another instance was launched with {:?}", argv);
+        }))
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
-        .manage(AppState::new(headless))
+        .plugin(hotkeys::plugin())
         .manage(log_state);
 
     // On macOS, register menu event handler at the Builder level
@@ -630,6 +800,8 @@ pub fn run() {
 
     let app = builder
         .setup(move |app| {
+            app_states.manage(app.handle());
+
             // In headless mode, hide/destroy the main window immediately and set
             // macOS activation policy to Accessory (no dock icon).
             if headless {
@@ -642,7 +814,9 @@ pub fn run() {
                 }
                 #[cfg(target_os = "macos")]
                 {
-                    let _ = app.handle().set_activation_policy(tauri::ActivationPolicy::Accessory);
+                    let _ = app
+                        .handle()
+                        .set_activation_policy(tauri::ActivationPolicy::Accessory);
                     info!("[Setup] Headless: activation policy set to Accessory");
                 }
             } else {
@@ -657,9 +831,9 @@ pub fn run() {
             // Restore always-on-top window property from config
             {
                 use tauri::Manager;
-                let app_state = app.state::<AppState>();
-                // Use a blocking read from AppState to avoid redundant config load
-                let always_on_top = app_state
+                let config_state = app.state::<AppConfigState>();
+                // Use a non-blocking read of the managed config to avoid a redundant load
+                let always_on_top = config_state
                     .app_config
                     .try_lock()
                     .ok()
@@ -678,9 +852,8 @@ pub fn run() {
 
             // ---- Initialize recording subsystem in-process ----
             use tauri::Manager;
-            let app_state = app.state::<AppState>();
-            let service_ready = app_state.service_ready.clone();
-            let app_config = app_state.app_config.clone();
+            let service_ready = app.state::<ServiceReadyState>().service_ready.clone();
+            let app_config = app.state::<AppConfigState>().app_config.clone();
 
             // Verify FFmpeg is available (bundled sidecar on Windows/macOS,
             // system package on Linux)
@@ -692,6 +865,12 @@ pub fn run() {
                 }
             });
 
+            // Check capture dependencies up front so problems surface before the
+            // first recording attempt.
+            tauri::async_runtime::spawn_blocking(|| {
+                capture::probe::run_startup_probe();
+            });
+
             // Initialize platform-specific capture backends (Linux)
             #[cfg(target_os = "linux")]
             {
@@ -703,50 +882,78 @@ pub fn run() {
 
             // Initialize RecordingManager singleton with the model from config
             {
-                let app_state = app.state::<AppState>();
-                let config = app_state.app_config.clone();
-                let model = config.blocking_lock().transcription.model.to_vtx_model();
+                let config = app.state::<AppConfigState>().app_config.clone();
+                let model = config
+                    .blocking_lock()
+                    .transcription
+                    .effective_model()
+                    .to_vtx_model();
                 state::init_recording_manager(model);
             }
-            let _manager = state::get_recording_manager();
+            let manager = state::get_recording_manager();
             info!("[Setup] RecordingManager initialized");
 
+            // Let the manager copy saved recording paths to the clipboard
+            {
+                use tauri_plugin_clipboard_manager::ClipboardExt;
+                let app_handle = app.handle().clone();
+                manager.set_clipboard_writer(Box::new(move |text| {
+                    app_handle
+                        .clipboard()
+                        .write_text(text)
+                        .map_err(|e| e.to_string())
+                }));
+            }
+
+            // Register global hotkeys
+            {
+                let hotkey_config = app_config.blocking_lock().hotkeys.clone();
+                if let Err(e) = hotkeys::apply_config(app.handle(), &hotkey_config) {
+                    warn!("[Setup] Failed to register hotkeys: {}", e);
+                }
+            }
+
+            // Watch for monitor hot-plug and resolution changes (runs on the
+            // main thread so macOS display callbacks are delivered)
+            capture::monitor_watch::start();
+            // Suspend recordings while the system sleeps
+            capture::power_watch::start();
+
+            // Apply changes made to the config file while the app runs
+            config_sync::start_watcher(app.handle().clone(), app_config.clone());
+
             // Sync local config to the RecordingManager
             {
                 let config_clone = app_config.clone();
                 let service_ready_clone = service_ready.clone();
                 tauri::async_runtime::spawn(async move {
                     let config = config_clone.lock().await;
-
-                    // Sync audio config
-                    let manager = state::get_recording_manager();
-                    info!("[Setup] Syncing audio config: enabled={}, source={:?}, mic={:?}, aec={}",
-                        config.audio.enabled,
-                        config.audio.source_id,
-                        config.audio.microphone_id,
-                        config.audio.echo_cancellation
-                    );
-                    let _ = manager.set_audio_config(omnirec_types::AudioConfig {
-                        enabled: config.audio.enabled,
-                        source_id: config.audio.source_id.clone(),
-                        microphone_id: config.audio.microphone_id.clone(),
-                        echo_cancellation: config.audio.echo_cancellation,
-                        agc_enabled: config.audio.agc_enabled,
-                        agc_noise_gate_enabled: config.audio.agc_noise_gate_enabled,
-                    }).await;
-
-                    // Sync transcription config
-                    let model_path = config.transcription.model.model_path();
-                    info!("[Setup] Syncing transcription config: enabled={}, model={:?}",
-                        config.transcription.enabled, model_path
-                    );
-                    let _ = manager.set_transcription_config(omnirec_types::TranscriptionConfig {
-                        enabled: config.transcription.enabled,
-                        model_path: Some(model_path.to_string_lossy().to_string()),
-                    }).await;
+                    let startup_timeout =
+                        std::time::Duration::from_secs(config.service_startup_timeout_secs);
+
+                    let sync = async {
+                        config_sync::apply_service_config(&config).await;
+                    };
+
+                    // Don't leave the UI waiting forever if the engine wedges
+                    // during startup; is_service_ready simply stays false.
+                    if tokio::time::timeout(startup_timeout, sync).await.is_err() {
+                        error!(
+                            "[Setup] Recording subsystem not ready after {}s, giving up",
+                            startup_timeout.as_secs()
+                        );
+                        return;
+                    }
 
                     service_ready_clone.store(true, Ordering::SeqCst);
                     info!("[Setup] Config sync complete, recording subsystem ready");
+
+                    // Tell systemd a Type=notify unit has started; does
+                    // nothing when not run by systemd
+                    #[cfg(target_os = "linux")]
+                    if let Err(e) = sd_notify::notify(false, &[sd_notify::NotifyState::Ready]) {
+                        warn!("[Setup] Failed to notify systemd: {}", e);
+                    }
                 });
             }
 
@@ -758,37 +965,85 @@ pub fn run() {
                 }
             });
 
+            // Start the REST API when requested with --listen
+            if let Some(options) = api_options.clone() {
+                tauri::async_runtime::spawn(async move {
+                    info!("[Setup] Starting REST API on {}...", options.listen);
+                    if let Err(e) = api::run_server(options).await {
+                        error!("[Setup] REST API error: {}", e);
+                    }
+                });
+            }
+
+            // Start the standalone metrics server when requested with --metrics-port
+            if let Some(options) = metrics_options {
+                tauri::async_runtime::spawn(async move {
+                    info!("[Setup] Starting metrics server on {}...", options.listen);
+                    if let Err(e) = metrics::run_server(options).await {
+                        error!("[Setup] Metrics server error: {}", e);
+                    }
+                });
+            }
+
+            // Handle omnirec:// deep links, including one the app was launched with
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                let deep_links = app.deep_link();
+                // Installed packages register the scheme; Linux and dev builds
+                // register it at runtime
+                #[cfg(any(target_os = "linux", all(windows, debug_assertions)))]
+                if let Err(e) = deep_links.register_all() {
+                    warn!("[Setup] Failed to register deep link scheme: {}", e);
+                }
+
+                let app_handle = app.handle().clone();
+                let ready = service_ready.clone();
+                deep_links.on_open_url(move |event| {
+                    tauri::async_runtime::spawn(deep_link::handle_urls(
+                        app_handle.clone(),
+                        event.urls(),
+                        ready.clone(),
+                    ));
+                });
+                if let Ok(Some(urls)) = deep_links.get_current() {
+                    tauri::async_runtime::spawn(deep_link::handle_urls(
+                        app.handle().clone(),
+                        urls,
+                        service_ready.clone(),
+                    ));
+                }
+            }
+
             // Background update check — release builds only, runs 5 seconds after startup
             #[cfg(all(desktop, not(debug_assertions)))]
             {
-                use tauri::Emitter;
-                use tauri_plugin_updater::UpdaterExt;
-                let app_handle = app.handle().clone();
-                tauri::async_runtime::spawn(async move {
-                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-                    match app_handle.updater() {
-                        Ok(updater) => match updater.check().await {
-                            Ok(Some(update)) => {
-                                let payload = serde_json::json!({
-                                    "available": true,
-                                    "version": update.version,
-                                    "date": update.date.map(|d| d.to_string()),
-                                    "notes": update.body,
-                                });
-                                let _ = app_handle.emit("update-available", payload);
+                let check_on_startup = app
+                    .state::<AppConfigState>()
+                    .app_config
+                    .try_lock()
+                    .map(|g| g.check_update_on_startup)
+                    .unwrap_or(true);
+                if check_on_startup {
+                    use tauri::Emitter;
+                    let app_handle = app.handle().clone();
+                    tauri::async_runtime::spawn(async move {
+                        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                        match update::check_for_update(false).await {
+                            Ok(result) if result.update_available => {
+                                info!("[Update] Version {} is available", result.latest);
+                                let _ = app_handle.emit("update-available", result);
                             }
-                            Ok(None) => {
-                                debug!("[Updater] App is up to date");
+                            Ok(_) => {
+                                debug!("[Update] App is up to date");
                             }
                             Err(e) => {
-                                debug!("[Updater] Background check error: {}", e);
+                                debug!("[Update] Background check error: {}", e);
                             }
-                        },
-                        Err(e) => {
-                            debug!("[Updater] Failed to get updater: {}", e);
                         }
-                    }
-                });
+                    });
+                } else {
+                    debug!("[Update] Startup update check disabled in config");
+                }
             }
 
             Ok(())
@@ -800,7 +1055,9 @@ pub fn run() {
             #[cfg(any(target_os = "windows", target_os = "macos"))]
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 if window.label() == "main" {
-                    info!("[Window] Close requested for main window - hiding (use tray Exit to quit)");
+                    info!(
+                        "[Window] Close requested for main window - hiding (use tray Exit to quit)"
+                    );
                     api.prevent_close();
                     match window.hide() {
                         Ok(()) => info!("[Window] hide() succeeded"),
@@ -816,7 +1073,10 @@ pub fn run() {
                         info!("[Window] Set activation policy to Accessory");
                     }
                 } else {
-                    info!("[Window] Close requested for window '{}' - allowing close", window.label());
+                    info!(
+                        "[Window] Close requested for window '{}' - allowing close",
+                        window.label()
+                    );
                 }
             }
 
@@ -831,24 +1091,45 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             // Capture commands
+            commands::list_available_backends,
+            commands::get_backend_probe_result,
             commands::get_windows,
             commands::get_monitors,
             commands::show_display_highlight,
             commands::show_window_highlight,
             commands::get_window_thumbnail,
             commands::get_display_thumbnail,
+            commands::get_monitor_config_version,
+            commands::list_cameras,
+            commands::take_screenshot,
             commands::get_region_preview,
             commands::check_screen_recording_permission,
             commands::open_screen_recording_settings,
+            commands::get_entitlements_status,
             // Recording commands
             commands::get_recording_state,
             commands::start_recording,
             commands::start_region_recording,
+            commands::start_workspace_recording,
             commands::start_display_recording,
             commands::start_gnome_recording,
             commands::set_tray_recording_state,
             commands::stop_recording,
+            commands::cancel_countdown,
+            commands::copy_to_clipboard,
+            commands::open_recording_file,
+            commands::verify_recording_integrity,
+            // History commands
+            commands::get_recording_history,
+            commands::search_history,
+            commands::delete_history_entry,
+            commands::get_recent_recordings,
+            commands::clear_recent_recordings,
             commands::get_elapsed_time,
+            commands::benchmark_encoder,
+            commands::check_ffmpeg_update,
+            commands::download_ffmpeg_update,
+            commands::concatenate_recordings,
             // Platform commands
             commands::get_platform,
             commands::is_hyprland,
@@ -867,15 +1148,64 @@ pub fn run() {
             commands::get_audio_config,
             commands::save_audio_config,
             commands::is_system_audio_available,
+            commands::list_running_audio_apps,
+            commands::set_audio_monitor,
+            commands::set_audio_bitrate,
+            commands::get_audio_waveform,
             // Configuration commands
             commands::get_config,
+            commands::export_config,
+            commands::import_config,
+            commands::export_config_to_file,
+            commands::import_config_from_file,
             commands::save_output_directory,
+            commands::save_create_output_dir_if_missing,
             commands::get_default_output_directory,
             commands::pick_output_directory,
+            commands::pick_temp_directory,
+            commands::save_temp_recording_dir,
+            commands::save_copy_recording_path_to_clipboard,
+            commands::save_open_output_dir_after_recording,
+            commands::save_smart_format,
+            commands::save_show_completion_notification,
+            commands::save_notification_include_path,
+            commands::save_show_recording_preview,
+            commands::save_verify_on_finish,
+            commands::save_window_follow,
+            commands::save_force_wgc_for_games,
+            commands::save_focus_before_capture,
+            commands::save_countdown_secs,
+            commands::save_cloud_config,
+            commands::test_cloud_connection,
+            commands::cancel_cloud_upload,
+            commands::save_pip_config,
+            commands::save_snap_to_window,
+            commands::save_hotkey_config,
             commands::validate_output_directory,
             commands::save_theme,
+            commands::set_preferred_backend,
+            commands::save_thumbnail_config,
+            commands::save_thumbnail_quality_config,
+            commands::save_thumbnail_pool_size,
+            commands::save_highlight_style,
+            commands::save_audio_exclusive_mode,
+            commands::validate_video_filter,
+            commands::save_video_filter_chain,
+            commands::save_encoder_config,
+            commands::save_keyframe_interval,
+            commands::save_silence_trim,
+            commands::get_last_region,
             // Service status
             commands::is_service_ready,
+            commands::check_service_health,
+            commands::get_crash_reports,
+            commands::get_gpu_memory_info,
+            commands::get_autostart_enabled,
+            commands::enable_autostart,
+            commands::disable_autostart,
+            commands::get_approval_tokens,
+            commands::revoke_approval_token,
+            commands::open_crash_report_directory,
             // Transcription commands
             commands::get_transcription_config,
             commands::save_transcription_config,
@@ -886,18 +1216,20 @@ pub fn run() {
             // Model management commands
             commands::get_model_status,
             commands::list_available_models,
+            commands::list_supported_languages,
             commands::download_model,
             commands::cancel_download,
             commands::is_download_in_progress,
             // Logging commands
             startup_log,
             log_to_file,
-            get_log_history,
+            get_recent_events,
             get_log_level,
             set_log_level,
             download_logs,
             // Update commands
             check_for_updates,
+            check_for_update,
             install_update,
         ])
         .build(tauri::generate_context!())
@@ -917,6 +1249,16 @@ pub fn run() {
     // Spawn task to forward ServiceEvent::StateChanged to all webview windows
     // as a "recording-state-changed" Tauri event.  The transcript window and
     // any other frontend listeners depend on this to start/stop polling.
+    // CaptureDisconnected is forwarded as "capture-disconnected" so the main
+    // window can show the saved file, MonitorConfigChanged as
+    // "monitor-config-changed" so display thumbnails are refreshed, Countdown
+    // as "recording-countdown" (also pulsing the tray icon), and cloud upload
+    // events as "cloud-upload-progress" / "cloud-upload-finished", recording
+    // statistics as "recording-stats", and preview frames as "preview-frame". Starting a recording opens the live
+    // preview window when enabled; returning to idle closes it.
+    // RecordingSaved adds the file to the recent recordings list (refreshing
+    // the tray submenu) and shows the completion notification; ScreenshotSaved
+    // is forwarded as "screenshot-saved" and also notified.
     {
         let app_handle = app.handle().clone();
         let mut service_rx = state::get_recording_manager().subscribe();
@@ -924,10 +1266,125 @@ pub fn run() {
             loop {
                 match service_rx.recv().await {
                     Ok(state::ServiceEvent::StateChanged(new_state)) => {
+                        match new_state {
+                            omnirec_types::RecordingState::Recording => {
+                                let show_preview =
+                                    tauri::Manager::state::<AppConfigState>(&app_handle)
+                                        .app_config
+                                        .lock()
+                                        .await
+                                        .show_recording_preview;
+                                if show_preview {
+                                    tray::open_recording_preview_window(&app_handle);
+                                }
+                            }
+                            omnirec_types::RecordingState::Idle => {
+                                tray::close_recording_preview_window(&app_handle);
+                            }
+                            _ => {}
+                        }
+                        let _ =
+                            tauri::Emitter::emit(&app_handle, "recording-state-changed", new_state);
+                    }
+                    Ok(state::ServiceEvent::RecordingResumed { .. }) => {
                         let _ = tauri::Emitter::emit(
                             &app_handle,
                             "recording-state-changed",
-                            new_state,
+                            omnirec_types::RecordingState::Recording,
+                        );
+                    }
+                    Ok(state::ServiceEvent::CaptureDisconnected { reason, path }) => {
+                        let _ = tauri::Emitter::emit(
+                            &app_handle,
+                            "capture-disconnected",
+                            serde_json::json!({ "reason": reason, "path": path }),
+                        );
+                    }
+                    Ok(state::ServiceEvent::RecordingSaved {
+                        path,
+                        duration_secs,
+                        size_bytes,
+                    }) => {
+                        {
+                            let state = tauri::Manager::state::<AppConfigState>(&app_handle);
+                            let mut config = state.app_config.lock().await;
+                            config.add_recent_recording(path.clone());
+                            if let Err(e) = save_config(&config) {
+                                warn!("Failed to save recent recordings: {}", e);
+                            }
+                        }
+                        tray::set_recording_state(&app_handle, false);
+                        notification::notify_recording_saved(
+                            &app_handle,
+                            notification::CompletionSummary {
+                                path,
+                                duration_secs,
+                                size_bytes,
+                            },
+                        )
+                        .await;
+                    }
+                    Ok(state::ServiceEvent::ScreenshotSaved { path, size_bytes }) => {
+                        let _ = tauri::Emitter::emit(&app_handle, "screenshot-saved", &path);
+                        notification::notify_screenshot_saved(&app_handle, path, size_bytes).await;
+                    }
+                    Ok(state::ServiceEvent::MonitorConfigChanged { version }) => {
+                        let _ =
+                            tauri::Emitter::emit(&app_handle, "monitor-config-changed", version);
+                    }
+                    Ok(state::ServiceEvent::GpuMemoryWarning {
+                        available_mb,
+                        threshold_mb,
+                    }) => {
+                        let _ = tauri::Emitter::emit(
+                            &app_handle,
+                            "gpu-memory-warning",
+                            serde_json::json!({
+                                "available_mb": available_mb,
+                                "threshold_mb": threshold_mb,
+                            }),
+                        );
+                    }
+                    Ok(state::ServiceEvent::Countdown { remaining }) => {
+                        tray::set_countdown_state(&app_handle, remaining);
+                        let _ = tauri::Emitter::emit(
+                            &app_handle,
+                            "recording-countdown",
+                            serde_json::json!({ "remaining": remaining }),
+                        );
+                    }
+                    Ok(state::ServiceEvent::CloudUploadProgress {
+                        percent,
+                        bytes_uploaded,
+                    }) => {
+                        let _ = tauri::Emitter::emit(
+                            &app_handle,
+                            "cloud-upload-progress",
+                            serde_json::json!({
+                                "percent": percent,
+                                "bytes_uploaded": bytes_uploaded,
+                            }),
+                        );
+                    }
+                    Ok(state::ServiceEvent::CloudUploadFinished { path, key, error }) => {
+                        let _ = tauri::Emitter::emit(
+                            &app_handle,
+                            "cloud-upload-finished",
+                            serde_json::json!({ "path": path, "key": key, "error": error }),
+                        );
+                    }
+                    Ok(state::ServiceEvent::Stats(stats)) => {
+                        let _ = tauri::Emitter::emit(&app_handle, "recording-stats", stats);
+                    }
+                    Ok(state::ServiceEvent::PreviewFrame {
+                        data,
+                        width,
+                        height,
+                    }) => {
+                        let _ = tauri::Emitter::emit(
+                            &app_handle,
+                            "preview-frame",
+                            serde_json::json!({ "data": data, "width": width, "height": height }),
                         );
                     }
                     Ok(state::ServiceEvent::Shutdown) | Err(_) => break,
@@ -944,10 +1401,10 @@ pub fn run() {
             // Only prevent exit when `code` is None (triggered by window close), not when
             // app.exit(0) is called explicitly (code is Some), so tray Exit works correctly.
             #[cfg(any(target_os = "macos", target_os = "windows"))]
-            tauri::RunEvent::ExitRequested { api, code, .. } => {
-                if code.is_none() {
-                    api.prevent_exit();
-                }
+            tauri::RunEvent::ExitRequested {
+                api, code: None, ..
+            } => {
+                api.prevent_exit();
             }
             // On macOS, when all windows are closed but app is still running,
             // clicking the dock icon should show the main window
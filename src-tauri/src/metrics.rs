@@ -0,0 +1,326 @@
+//! Prometheus metrics for the recording service.
+//!
+//! Metrics are collected in a process-wide [`Registry`] and served in the
+//! Prometheus text format at `GET /metrics`, either on the REST API
+//! (`--listen`) or on a standalone server started with `--metrics-port`.
+//! The standalone server binds to loopback unless `--metrics-bind` names
+//! another address, which then requires the API token.
+
+use crate::api::{require_token, API_TOKEN_ENV};
+use axum::http::{header, StatusCode};
+use axum::middleware;
+use axum::response::{IntoResponse, Response as HttpResponse};
+use axum::routing::get;
+use axum::Router;
+use prometheus::{Encoder, Gauge, IntCounter, IntGauge, Registry, TextEncoder};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, OnceLock};
+use tracing::{info, warn};
+
+/// Service metrics exported to Prometheus.
+pub struct Metrics {
+    registry: Registry,
+    /// Recordings saved since the service started
+    pub recordings_total: IntCounter,
+    /// 1 while a recording is being encoded
+    pub recording_active: IntGauge,
+    /// Video frames sent to the encoder
+    pub frames_encoded_total: IntCounter,
+    /// Video frames encoded per second over the last interval
+    pub encode_fps: Gauge,
+    /// Audio samples written to the encoder
+    pub audio_samples_total: IntCounter,
+    /// Open IPC client connections
+    pub ipc_connections: IntGauge,
+}
+
+impl Metrics {
+    fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new();
+        let metrics = Self {
+            recordings_total: IntCounter::new(
+                "omnirec_recordings_total",
+                "Recordings saved since the service started",
+            )?,
+            recording_active: IntGauge::new(
+                "omnirec_recording_active",
+                "1 while a recording is being encoded, otherwise 0",
+            )?,
+            frames_encoded_total: IntCounter::new(
+                "omnirec_frames_encoded_total",
+                "Video frames sent to the encoder",
+            )?,
+            encode_fps: Gauge::new("omnirec_encode_fps", "Video frames encoded per second")?,
+            audio_samples_total: IntCounter::new(
+                "omnirec_audio_samples_total",
+                "Audio samples written to the encoder",
+            )?,
+            ipc_connections: IntGauge::new(
+                "omnirec_ipc_connections",
+                "Open IPC client connections",
+            )?,
+            registry,
+        };
+        metrics
+            .registry
+            .register(Box::new(metrics.recordings_total.clone()))?;
+        metrics
+            .registry
+            .register(Box::new(metrics.recording_active.clone()))?;
+        metrics
+            .registry
+            .register(Box::new(metrics.frames_encoded_total.clone()))?;
+        metrics
+            .registry
+            .register(Box::new(metrics.encode_fps.clone()))?;
+        metrics
+            .registry
+            .register(Box::new(metrics.audio_samples_total.clone()))?;
+        metrics
+            .registry
+            .register(Box::new(metrics.ipc_connections.clone()))?;
+        Ok(metrics)
+    }
+
+    /// All metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> Result<String, String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .map_err(|e| format!("Failed to encode metrics: {}", e))?;
+        String::from_utf8(buffer).map_err(|e| format!("Failed to encode metrics: {}", e))
+    }
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+/// The process-wide metrics.
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(|| Metrics::new().expect("Metric definitions are valid"))
+}
+
+/// Decrements [`Metrics::ipc_connections`] when dropped.
+pub struct ConnectionGuard(());
+
+impl ConnectionGuard {
+    /// Count a new IPC connection until the guard is dropped.
+    pub fn open() -> Self {
+        metrics().ipc_connections.inc();
+        Self(())
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        metrics().ipc_connections.dec();
+    }
+}
+
+/// Sets [`Metrics::recording_active`] while encoding; resets it and
+/// [`Metrics::encode_fps`] when dropped.
+pub struct ActiveRecording(());
+
+impl ActiveRecording {
+    /// Mark a recording as active until the guard is dropped.
+    pub fn start() -> Self {
+        metrics().recording_active.set(1);
+        Self(())
+    }
+}
+
+impl Drop for ActiveRecording {
+    fn drop(&mut self) {
+        metrics().recording_active.set(0);
+        metrics().encode_fps.set(0.0);
+    }
+}
+
+/// Standalone metrics server settings parsed from the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsOptions {
+    /// Address the server binds to
+    pub listen: SocketAddr,
+    /// Bearer token required when `listen` is not a loopback address
+    pub token: Option<String>,
+}
+
+impl MetricsOptions {
+    /// Parse `--metrics-port`, `--metrics-bind` and `--api-token` from `args`
+    /// (without the program name), falling back to `env_token` for the
+    /// token.
+    ///
+    /// Returns `Ok(None)` when `--metrics-port` is absent.
+    pub fn from_args<I>(args: I, env_token: Option<String>) -> Result<Option<Self>, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut port = None;
+        let mut bind = None;
+        let mut token = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let slot = match name.as_str() {
+                "--metrics-port" => &mut port,
+                "--metrics-bind" => &mut bind,
+                "--api-token" => &mut token,
+                _ => continue,
+            };
+            let value = inline
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} requires a value", name))?;
+            *slot = Some(value);
+        }
+
+        let Some(port) = port else {
+            return Ok(None);
+        };
+        let port = port
+            .parse::<u16>()
+            .ok()
+            .filter(|port| *port != 0)
+            .ok_or_else(|| format!("Invalid --metrics-port '{}'", port))?;
+        let ip = match bind {
+            Some(bind) => bind
+                .parse::<IpAddr>()
+                .map_err(|e| format!("Invalid --metrics-bind address '{}': {}", bind, e))?,
+            None => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        };
+
+        let token = if ip.is_loopback() {
+            None
+        } else {
+            let token = token
+                .or(env_token)
+                .filter(|token| !token.is_empty())
+                .ok_or_else(|| {
+                    format!(
+                        "--metrics-bind {} requires an API token (--api-token or {})",
+                        ip, API_TOKEN_ENV
+                    )
+                })?;
+            Some(token)
+        };
+
+        Ok(Some(Self {
+            listen: SocketAddr::new(ip, port),
+            token,
+        }))
+    }
+}
+
+/// Router for `GET /metrics`.
+pub fn router() -> Router {
+    Router::new().route("/metrics", get(serve_metrics))
+}
+
+async fn serve_metrics() -> HttpResponse {
+    match metrics().render() {
+        Ok(text) => ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], text).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+/// Serve `GET /metrics` at `options.listen` until it fails.
+pub async fn run_server(options: MetricsOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let mut router = router();
+    if let Some(token) = options.token {
+        warn!(
+            "[Metrics] Listening on non-loopback address {}; traffic is not encrypted",
+            options.listen
+        );
+        router = router.route_layer(middleware::from_fn_with_state(
+            Arc::new(token),
+            require_token,
+        ));
+    }
+
+    let listener = tokio::net::TcpListener::bind(options.listen).await?;
+    info!(
+        "[Metrics] Serving metrics on http://{}/metrics",
+        options.listen
+    );
+    axum::serve(listener, router).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn parse(list: &[&str], env_token: Option<&str>) -> Result<Option<MetricsOptions>, String> {
+        MetricsOptions::from_args(args(list), env_token.map(str::to_string))
+    }
+
+    #[test]
+    fn test_from_args_defaults_to_loopback() {
+        assert_eq!(parse(&["--headless"], None), Ok(None));
+        assert_eq!(
+            parse(&["--metrics-port", "9100"], None),
+            Ok(Some(MetricsOptions {
+                listen: "127.0.0.1:9100".parse().unwrap(),
+                token: None,
+            }))
+        );
+        assert_eq!(
+            parse(
+                &["--headless", "--metrics-port=9200", "--metrics-bind=::1"],
+                None
+            )
+            .unwrap()
+            .map(|options| options.listen),
+            Some("[::1]:9200".parse().unwrap())
+        );
+        assert!(parse(&["--metrics-port"], None).is_err());
+        assert!(parse(&["--metrics-port", "0"], None).is_err());
+        assert!(parse(&["--metrics-port", "http"], None).is_err());
+        assert!(parse(&["--metrics-port", "9100", "--metrics-bind", "host"], None).is_err());
+    }
+
+    #[test]
+    fn test_from_args_requires_token_beyond_loopback() {
+        let exposed = ["--metrics-port", "9100", "--metrics-bind", "0.0.0.0"];
+        assert!(parse(&exposed, None).is_err());
+        assert!(parse(&exposed, Some("")).is_err());
+        assert_eq!(
+            parse(&exposed, Some("secret")),
+            Ok(Some(MetricsOptions {
+                listen: "0.0.0.0:9100".parse().unwrap(),
+                token: Some("secret".to_string()),
+            }))
+        );
+        let mut with_flag = exposed.to_vec();
+        with_flag.extend(["--api-token", "flag"]);
+        assert_eq!(
+            parse(&with_flag, Some("env"))
+                .unwrap()
+                .unwrap()
+                .token
+                .as_deref(),
+            Some("flag")
+        );
+    }
+
+    #[test]
+    fn test_render_exports_all_metrics() {
+        let text = Metrics::new().unwrap().render().unwrap();
+        for name in [
+            "omnirec_recordings_total",
+            "omnirec_recording_active",
+            "omnirec_frames_encoded_total",
+            "omnirec_encode_fps",
+            "omnirec_audio_samples_total",
+            "omnirec_ipc_connections",
+        ] {
+            assert!(text.contains(&format!("# TYPE {} ", name)), "{}", name);
+        }
+    }
+}
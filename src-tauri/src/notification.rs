@@ -0,0 +1,262 @@
+//! Desktop notification shown when a recording or screenshot has been saved.
+//!
+//! The notification names the file with its duration (recordings only) and
+//! size and offers an "Open File" action. It is sent through the freedesktop notification
+//! service over D-Bus on Linux (the protocol libnotify speaks), Notification
+//! Center on macOS and toast notifications on Windows. When notifications are
+//! not permitted or cannot be shown, the summary goes to the tray tooltip.
+
+use std::path::Path;
+use tauri::plugin::PermissionState;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tracing::{debug, warn};
+
+const TITLE: &str = "Recording saved";
+const SCREENSHOT_TITLE: &str = "Screenshot saved";
+const OPEN_ACTION_LABEL: &str = "Open File";
+
+/// A saved recording, as described in its notification.
+#[derive(Debug, Clone)]
+pub struct CompletionSummary {
+    pub path: String,
+    pub duration_secs: u64,
+    pub size_bytes: u64,
+}
+
+impl CompletionSummary {
+    /// Notification body: the file name (or full path) and its duration and size.
+    fn body(&self, include_path: bool) -> String {
+        format!(
+            "{}\n{} \u{00b7} {}",
+            file_label(&self.path, include_path),
+            format_duration(self.duration_secs),
+            format_size(self.size_bytes)
+        )
+    }
+}
+
+/// The full path, or just the file name.
+fn file_label(path: &str, include_path: bool) -> String {
+    if include_path {
+        return path.to_string();
+    }
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Format seconds as `m:ss`, or `h:mm:ss` for an hour or more.
+fn format_duration(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// Format a byte count with binary units, e.g. `12.3 MB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Whether to include the full path in notifications, or `None` if
+/// completion notifications are turned off.
+async fn include_path_setting(app: &AppHandle) -> Option<bool> {
+    let state = app.state::<crate::AppConfigState>();
+    let config = state.app_config.lock().await;
+    config
+        .show_completion_notification
+        .then_some(config.notification_include_path)
+}
+
+/// Show the completion notification for `summary` if enabled in the config.
+pub async fn notify_recording_saved(app: &AppHandle, summary: CompletionSummary) {
+    if let Some(include_path) = include_path_setting(app).await {
+        let body = summary.body(include_path);
+        notify(app, TITLE, body, summary.path);
+    }
+}
+
+/// Show the completion notification for a screenshot saved to `path` if
+/// enabled in the config.
+pub async fn notify_screenshot_saved(app: &AppHandle, path: String, size_bytes: u64) {
+    if let Some(include_path) = include_path_setting(app).await {
+        let body = format!(
+            "{}\n{}",
+            file_label(&path, include_path),
+            format_size(size_bytes)
+        );
+        notify(app, SCREENSHOT_TITLE, body, path);
+    }
+}
+
+/// Show a notification with an action opening `path`, falling back to the
+/// tray tooltip.
+fn notify(app: &AppHandle, title: &'static str, body: String, path: String) {
+    let granted = matches!(
+        app.notification().permission_state(),
+        Ok(PermissionState::Granted)
+    );
+
+    // Native notifications may block until dismissed to report the action
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let result = if granted {
+            show_native(&app, title, &body, move || open_file(path))
+        } else {
+            Err("notification permission not granted".to_string())
+        };
+        if let Err(e) = result {
+            debug!("[Notification] Falling back to tray tooltip: {}", e);
+            let tooltip = format!("OmniRec - {}: {}", title, body.replace('\n', " "));
+            crate::tray::set_tooltip(&app, &tooltip);
+        }
+    });
+}
+
+fn open_file(path: String) {
+    if let Err(e) = crate::commands::open_recording_file(path) {
+        warn!("[Notification] Failed to open file: {}", e);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn show_native(
+    _app: &AppHandle,
+    title: &str,
+    body: &str,
+    on_open: impl FnOnce() + Send + 'static,
+) -> Result<(), String> {
+    let handle = notify_rust::Notification::new()
+        .appname("OmniRec")
+        .summary(title)
+        .body(body)
+        .icon("omnirec")
+        .action("default", OPEN_ACTION_LABEL)
+        .action("open", OPEN_ACTION_LABEL)
+        .show()
+        .map_err(|e| e.to_string())?;
+    handle.wait_for_action(|action| {
+        if action == "default" || action == "open" {
+            on_open();
+        }
+    });
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn show_native(
+    app: &AppHandle,
+    title: &str,
+    body: &str,
+    on_open: impl FnOnce() + Send + 'static,
+) -> Result<(), String> {
+    use mac_notification_sys::{MainButton, Notification, NotificationResponse};
+
+    static APPLICATION: std::sync::Once = std::sync::Once::new();
+    APPLICATION.call_once(|| {
+        if let Err(e) = mac_notification_sys::set_application(&app.config().identifier) {
+            debug!("[Notification] Failed to set application: {}", e);
+        }
+    });
+
+    let response = Notification::new()
+        .title(title)
+        .message(body)
+        .main_button(MainButton::SingleAction(OPEN_ACTION_LABEL))
+        .wait_for_click(true)
+        .send()
+        .map_err(|e| e.to_string())?;
+    if matches!(
+        response,
+        NotificationResponse::ActionButton(_) | NotificationResponse::Click
+    ) {
+        on_open();
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn show_native(
+    app: &AppHandle,
+    title: &str,
+    body: &str,
+    on_open: impl FnOnce() + Send + 'static,
+) -> Result<(), String> {
+    use tauri_winrt_notification::Toast;
+
+    // Unpackaged debug builds have no registered AppUserModelID
+    let app_id = if cfg!(debug_assertions) {
+        Toast::POWERSHELL_APP_ID.to_string()
+    } else {
+        app.config().identifier.clone()
+    };
+    let mut on_open = Some(on_open);
+    Toast::new(&app_id)
+        .title(title)
+        .text1(body)
+        .add_button(OPEN_ACTION_LABEL, "open")
+        .on_activated(move |action| {
+            if action.as_deref() == Some("open") {
+                if let Some(on_open) = on_open.take() {
+                    on_open();
+                }
+            }
+            Ok(())
+        })
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(path: &str) -> CompletionSummary {
+        CompletionSummary {
+            path: path.to_string(),
+            duration_secs: 83,
+            size_bytes: 12_900_000,
+        }
+    }
+
+    #[test]
+    fn test_body_shows_file_name_duration_and_size() {
+        assert_eq!(
+            summary("/home/user/Videos/recording_2026.mp4").body(false),
+            "recording_2026.mp4\n1:23 \u{00b7} 12.3 MB"
+        );
+        assert_eq!(
+            summary("/home/user/Videos/recording_2026.mp4").body(true),
+            "/home/user/Videos/recording_2026.mp4\n1:23 \u{00b7} 12.3 MB"
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0:00");
+        assert_eq!(format_duration(59), "0:59");
+        assert_eq!(format_duration(3600), "1:00:00");
+        assert_eq!(format_duration(3725), "1:02:05");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GB");
+    }
+}
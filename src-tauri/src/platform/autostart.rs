@@ -0,0 +1,290 @@
+//! Starting OmniRec at login.
+//!
+//! The login item starts the app with `--headless`, so only the tray icon
+//! appears. Its presence is the setting: nothing is stored in the app
+//! config, and removing the entry by hand turns auto-start off.
+//!
+//! - macOS: a LaunchAgent at `~/Library/LaunchAgents/com.omnirec.app.plist`
+//! - Windows: an `OmniRec` value under
+//!   `HKCU\Software\Microsoft\Windows\CurrentVersion\Run`
+//! - Linux: `omnirec.desktop` in `~/.config/autostart/`
+
+use std::path::Path;
+
+/// Argument the login item starts the app with.
+const HEADLESS_ARG: &str = "--headless";
+
+/// Label of the macOS LaunchAgent.
+#[cfg(any(target_os = "macos", test))]
+const LAUNCH_AGENT_LABEL: &str = "com.omnirec.app";
+
+/// Name of the Windows `Run` value.
+#[cfg(target_os = "windows")]
+const RUN_VALUE_NAME: &str = "OmniRec";
+
+/// Registry key of programs started at login.
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+/// Whether OmniRec starts at login.
+pub fn is_autostart_enabled() -> bool {
+    #[cfg(target_os = "windows")]
+    return windows_run_value_exists();
+    #[cfg(not(target_os = "windows"))]
+    entry_path().is_some_and(|path| path.exists())
+}
+
+/// Start OmniRec at login, replacing an entry that points at another binary.
+pub fn enable_autostart() -> Result<(), String> {
+    let exe = executable_path()?;
+
+    #[cfg(target_os = "windows")]
+    {
+        set_windows_run_value(&exe)?;
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let path = entry_path().ok_or("Could not determine the home directory")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        std::fs::write(&path, entry_contents(&exe))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    tracing::info!("[Autostart] Enabled for {}", exe.display());
+    Ok(())
+}
+
+/// Path the login item starts.
+///
+/// An AppImage runs from a temporary mount that is gone after logout, so
+/// its entry points at the AppImage file itself, which the runtime passes
+/// in `$APPIMAGE`.
+fn executable_path() -> Result<std::path::PathBuf, String> {
+    #[cfg(target_os = "linux")]
+    if let Some(appimage) = std::env::var_os("APPIMAGE").filter(|path| !path.is_empty()) {
+        return Ok(appimage.into());
+    }
+    std::env::current_exe().map_err(|e| format!("Failed to locate the OmniRec executable: {}", e))
+}
+
+/// Stop starting OmniRec at login. Does nothing if it isn't enabled.
+pub fn disable_autostart() -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        delete_windows_run_value()?;
+    }
+    #[cfg(not(target_os = "windows"))]
+    if let Some(path) = entry_path() {
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to remove {}: {}", path.display(), e)),
+        }
+    }
+
+    tracing::info!("[Autostart] Disabled");
+    Ok(())
+}
+
+/// Path of the login item file.
+#[cfg(target_os = "macos")]
+fn entry_path() -> Option<std::path::PathBuf> {
+    directories::BaseDirs::new().map(|dirs| {
+        dirs.home_dir()
+            .join("Library")
+            .join("LaunchAgents")
+            .join(format!("{}.plist", LAUNCH_AGENT_LABEL))
+    })
+}
+
+/// Path of the login item file.
+#[cfg(target_os = "linux")]
+fn entry_path() -> Option<std::path::PathBuf> {
+    directories::BaseDirs::new()
+        .map(|dirs| dirs.config_dir().join("autostart").join("omnirec.desktop"))
+}
+
+/// Contents of the login item file for `exe`.
+#[cfg(target_os = "macos")]
+fn entry_contents(exe: &Path) -> String {
+    launch_agent_plist(exe)
+}
+
+/// Contents of the login item file for `exe`.
+#[cfg(target_os = "linux")]
+fn entry_contents(exe: &Path) -> String {
+    desktop_entry(exe)
+}
+
+/// LaunchAgent plist that runs `exe --headless` at login.
+#[cfg(any(target_os = "macos", test))]
+fn launch_agent_plist(exe: &Path) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        LAUNCH_AGENT_LABEL,
+        escape(&exe.to_string_lossy()),
+        HEADLESS_ARG
+    )
+}
+
+/// XDG autostart entry that runs `exe --headless` at login.
+#[cfg(any(target_os = "linux", test))]
+fn desktop_entry(exe: &Path) -> String {
+    // Quoted per the Desktop Entry spec. The escaping backslashes are
+    // escaped again because the whole value is a string, and `%` starts a
+    // field code
+    let mut quoted = String::from('"');
+    for c in exe.to_string_lossy().chars() {
+        match c {
+            '"' | '`' | '$' => quoted.push_str(r"\\"),
+            '\\' => quoted.push_str(r"\\\"),
+            '%' => quoted.push('%'),
+            _ => {}
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=OmniRec\n\
+         Comment=Start OmniRec in the system tray\n\
+         Exec={} {}\n\
+         Icon=omnirec\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        quoted, HEADLESS_ARG
+    )
+}
+
+/// `Run` value data: the quoted executable path and `--headless`.
+#[cfg(any(target_os = "windows", test))]
+fn run_command(exe: &Path) -> String {
+    format!("\"{}\" {}", exe.display(), HEADLESS_ARG)
+}
+
+#[cfg(target_os = "windows")]
+fn windows_run_value_exists() -> bool {
+    use windows::core::HSTRING;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ};
+
+    unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(RUN_KEY),
+            &HSTRING::from(RUN_VALUE_NAME),
+            RRF_RT_REG_SZ,
+            None,
+            None,
+            None,
+        )
+        .is_ok()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn set_windows_run_value(exe: &Path) -> Result<(), String> {
+    use windows::core::HSTRING;
+    use windows::Win32::System::Registry::{RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ};
+
+    // REG_SZ data is UTF-16 including the terminating NUL
+    let data: Vec<u16> = run_command(exe)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(RUN_KEY),
+            &HSTRING::from(RUN_VALUE_NAME),
+            REG_SZ.0,
+            Some(data.as_ptr() as *const std::ffi::c_void),
+            (data.len() * std::mem::size_of::<u16>()) as u32,
+        )
+        .ok()
+        .map_err(|e| format!("Failed to add the login item: {}", e))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn delete_windows_run_value() -> Result<(), String> {
+    use windows::core::HSTRING;
+    use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+    use windows::Win32::System::Registry::{RegDeleteKeyValueW, HKEY_CURRENT_USER};
+
+    let result = unsafe {
+        RegDeleteKeyValueW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(RUN_KEY),
+            &HSTRING::from(RUN_VALUE_NAME),
+        )
+    };
+    if result == ERROR_FILE_NOT_FOUND {
+        return Ok(());
+    }
+    result
+        .ok()
+        .map_err(|e| format!("Failed to remove the login item: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop_entry_quotes_exec() {
+        let entry = desktop_entry(Path::new("/opt/Omni Rec/omnirec-app"));
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("\nExec=\"/opt/Omni Rec/omnirec-app\" --headless\n"));
+
+        let entry = desktop_entry(Path::new("/home/a$b/omnirec-app"));
+        assert!(entry.contains("\nExec=\"/home/a\\\\$b/omnirec-app\" --headless\n"));
+
+        let entry = desktop_entry(Path::new("/home/100%/omnirec-app"));
+        assert!(entry.contains("\nExec=\"/home/100%%/omnirec-app\" --headless\n"));
+    }
+
+    #[test]
+    fn test_launch_agent_plist() {
+        let plist = launch_agent_plist(Path::new(
+            "/Applications/R&D/OmniRec.app/Contents/MacOS/omnirec",
+        ));
+        assert!(plist.contains("<string>com.omnirec.app</string>"));
+        assert!(plist
+            .contains("<string>/Applications/R&amp;D/OmniRec.app/Contents/MacOS/omnirec</string>"));
+        assert!(plist.contains("<string>--headless</string>"));
+        assert!(plist.contains("<key>RunAtLoad</key>\n    <true/>"));
+    }
+
+    #[test]
+    fn test_run_command_quotes_path() {
+        assert_eq!(
+            run_command(Path::new(r"C:\Program Files\OmniRec\omnirec-app.exe")),
+            r#""C:\Program Files\OmniRec\omnirec-app.exe" --headless"#
+        );
+    }
+}
@@ -1,18 +1,27 @@
 //! macOS-specific platform functionality.
 //!
 //! This module provides minimal macOS-specific checks that need to run in the Tauri client,
-//! such as permission checks and version detection.
+//! such as permission checks, entitlement checks and version detection.
+
+use super::EntitlementsStatus;
 
 // Core Graphics FFI for permission checks
 #[link(name = "CoreGraphics", kind = "framework")]
 extern "C" {
-    fn CGPreflightScreenCaptureAccess() -> bool;
     fn CGRequestScreenCaptureAccess() -> bool;
 }
 
 /// Check if screen recording permission is granted.
+///
+/// The result is cached by the capture backend; see
+/// [`crate::capture::macos::permission`].
 pub fn has_screen_recording_permission() -> bool {
-    unsafe { CGPreflightScreenCaptureAccess() }
+    crate::capture::macos::permission::has_screen_recording_permission()
+}
+
+/// Forget the cached permission so the next check probes again.
+pub fn invalidate_permission_cache() {
+    crate::capture::macos::permission::invalidate_permission_cache()
 }
 
 /// Trigger the permission prompt to add the app to the Screen Recording list.
@@ -53,3 +62,140 @@ fn is_macos_13_or_later() -> bool {
     // Default to false if we can't determine version
     false
 }
+
+/// Checks that the app's code signature declares the entitlements it needs.
+///
+/// The required list is every entitlement enabled in the
+/// `Entitlements.plist` the bundle is signed with.
+pub struct EntitlementsValidator {
+    required: Vec<&'static str>,
+}
+
+/// The plist `bundle.macOS.entitlements` points at.
+const ENTITLEMENTS_PLIST: &str = include_str!("../../Entitlements.plist");
+
+impl EntitlementsValidator {
+    pub fn new() -> Self {
+        Self {
+            required: parse_entitlements(ENTITLEMENTS_PLIST),
+        }
+    }
+
+    /// Read the running binary's entitlements and compare them to the
+    /// required list, logging a warning for each missing one.
+    pub fn check(&self) -> EntitlementsStatus {
+        let Some(declared) = signed_entitlements() else {
+            tracing::debug!("[Entitlements] App is not code signed, skipping check");
+            return EntitlementsStatus::default();
+        };
+        let status = self.compare(&declared);
+        for entitlement in &status.missing {
+            tracing::warn!(
+                "[Entitlements] Required entitlement not declared: {}",
+                entitlement
+            );
+        }
+        status
+    }
+
+    fn compare(&self, declared: &[String]) -> EntitlementsStatus {
+        let (present, missing): (Vec<&str>, Vec<&str>) = self
+            .required
+            .iter()
+            .partition(|e| declared.iter().any(|d| d == *e));
+        EntitlementsStatus {
+            signed: true,
+            declared: present.into_iter().map(String::from).collect(),
+            missing: missing.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+impl Default for EntitlementsValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Entitlements of the running binary's code signature, or `None` if it is
+/// not signed.
+fn signed_entitlements() -> Option<Vec<String>> {
+    use std::process::Command;
+
+    let exe = std::env::current_exe().ok()?;
+    let output = Command::new("codesign")
+        .args(["-d", "--entitlements", "-", "--xml"])
+        .arg(&exe)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        parse_entitlements(&String::from_utf8_lossy(&output.stdout))
+            .into_iter()
+            .map(String::from)
+            .collect(),
+    )
+}
+
+/// Keys set to `<true/>` in an entitlements plist.
+fn parse_entitlements(plist: &str) -> Vec<&str> {
+    let mut entitlements = Vec::new();
+    let mut rest = plist;
+    while let Some(start) = rest.find("<key>") {
+        rest = &rest[start + "<key>".len()..];
+        let Some(end) = rest.find("</key>") else {
+            break;
+        };
+        let key = &rest[..end];
+        rest = &rest[end + "</key>".len()..];
+        if rest.trim_start().starts_with("<true/>") {
+            entitlements.push(key);
+        }
+    }
+    entitlements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entitlements() {
+        let plist = "<?xml version=\"1.0\"?><plist><dict>\
+            <key>com.apple.security.app-sandbox</key><false/>\
+            <key>com.apple.security.device.audio-input</key>\n  <true/>\
+            <key>com.apple.security.device.camera</key><true/>\
+            </dict></plist>";
+        assert_eq!(
+            parse_entitlements(plist),
+            [
+                "com.apple.security.device.audio-input",
+                "com.apple.security.device.camera"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_reports_missing() {
+        let validator = EntitlementsValidator {
+            required: vec!["a", "b", "c"],
+        };
+        let status = validator.compare(&["c".to_string(), "a".to_string()]);
+        assert!(status.signed);
+        assert_eq!(status.declared, ["a", "c"]);
+        assert_eq!(status.missing, ["b"]);
+    }
+
+    #[test]
+    fn test_required_list_from_entitlements_plist() {
+        let validator = EntitlementsValidator::new();
+        assert!(validator
+            .required
+            .contains(&"com.apple.security.device.audio-input"));
+        assert!(!validator
+            .required
+            .contains(&"com.apple.security.app-sandbox"));
+    }
+}
@@ -1,7 +1,21 @@
 //! Platform-specific functionality.
 //!
-//! This module contains minimal platform-specific code for checks that must be done
-//! in the Tauri client (e.g., permission checks on macOS).
+//! This module contains minimal platform-specific code for checks and settings that
+//! must be done in the Tauri client (e.g., permission checks on macOS, starting at login).
 
+pub mod autostart;
 #[cfg(target_os = "macos")]
 pub mod macos;
+
+use serde::Serialize;
+
+/// Entitlements declared by the running app's code signature (macOS only).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EntitlementsStatus {
+    /// Whether the running binary is code signed
+    pub signed: bool,
+    /// Required entitlements the signature declares
+    pub declared: Vec<String>,
+    /// Required entitlements the signature lacks
+    pub missing: Vec<String>,
+}
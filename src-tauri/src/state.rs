@@ -1,24 +1,35 @@
 //! Recording state management for the OmniRec service.
 //!
 //! This module manages the recording lifecycle, including:
-//! - Recording state (idle, recording, saving)
+//! - Recording state (idle, recording, suspended, saving)
 //! - Output format configuration
 //! - Audio configuration
 //! - Transcription configuration (delegated to vtx-engine)
 //! - Elapsed time tracking
 //! - Event broadcasting to subscribed clients
 
-use crate::capture::{CaptureBackend, CaptureRegion, FrameReceiver, StopHandle};
-use crate::encoder::encode_frames;
+use crate::capture::color_profile::{get_display_color_profile, ColorProfile};
+use crate::capture::{
+    save_bgra_png, CaptureRegion, FrameReceiver, HighlightStyle, StopHandle, ThumbnailOptions,
+    ThumbnailPool,
+};
+use crate::config::{CloudUploadConfig, ThumbnailQualityConfig, DEFAULT_KEYFRAME_INTERVAL_SECS};
+use crate::encoder::format_selector::{select_smart_format, RecordTarget};
+use crate::encoder::load_shedding::{LoadShedding, SHEDDING_THUMBNAIL_QUALITY};
+use crate::encoder::scaling::DynamicResolutionScaling;
+use crate::encoder::{encode_frames, EncodeOptions, EncoderThreads};
+use crate::history::{RecordingHistoryEntry, RecordingSourceType};
+use omnirec_types::integrity;
+use omnirec_types::ipc::format_error_chain;
 use omnirec_types::{
-    AudioConfig, OutputFormat, RecordingState, TranscriptionConfig, TranscriptionSegment,
-    TranscriptionStatus,
+    AudioConfig, AudioSourceType, OutputFormat, PipConfig, RecordingState, ScreenshotTarget,
+    TranscriptionConfig, TranscriptionSegment, TranscriptionStatus, APP_AUDIO_SOURCE_PREFIX,
 };
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::{broadcast, Mutex, RwLock};
 use tracing::{error, info, warn};
 use vtx_engine::{AgcConfig, AudioEngine, EngineBuilder, EngineConfig, EngineEvent};
@@ -30,6 +41,96 @@ pub struct RecordingResult {
     pub source_path: PathBuf,
     /// Path to the final output file (after transcoding, if applicable)
     pub file_path: PathBuf,
+    /// Whether `file_path` was copied to the clipboard
+    pub copied_to_clipboard: bool,
+}
+
+/// Writes text to the system clipboard; installed by the Tauri layer.
+pub type ClipboardWriter = Box<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Statistics collected while setting up and running the current recording.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+pub struct RecordingStats {
+    /// Number of times the screencast portal request had to be retried (Linux).
+    pub portal_retries: u32,
+    /// Whether frames are being dropped because encoding fell behind.
+    pub load_shedding_active: bool,
+    /// Fraction of the captured frames dropped by load shedding.
+    pub dropped_frame_ratio: f64,
+}
+
+/// A stretch of a recording during which the system was asleep.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct RecordingGap {
+    /// Position in the recording where the gap is, in seconds
+    pub at_secs: u64,
+    /// How long the recording was suspended, in seconds
+    pub duration_secs: f64,
+}
+
+/// A suspension of the current recording for system sleep.
+#[derive(Debug, Clone, Copy)]
+struct Suspension {
+    /// When it started, for moving the recording timeline
+    since: Instant,
+    /// When it started by the wall clock. `Instant` doesn't advance while
+    /// the system sleeps on Linux and macOS, so only this measures the gap.
+    since_wall: SystemTime,
+    /// Elapsed recording seconds when it started
+    at_secs: u64,
+}
+
+impl Suspension {
+    fn new(at_secs: u64) -> Self {
+        Self {
+            since: Instant::now(),
+            since_wall: SystemTime::now(),
+            at_secs,
+        }
+    }
+
+    /// How long the system slept if it woke at `woke_at`.
+    ///
+    /// Never less than the monotonic time that passed, in case the wall
+    /// clock was set back meanwhile.
+    fn gap(&self, woke_at: SystemTime) -> Duration {
+        let awake = self.since.elapsed();
+        woke_at
+            .duration_since(self.since_wall)
+            .map_or(awake, |slept| slept.max(awake))
+    }
+}
+
+/// vtx-engine AGC settings for `config`, with its gain stage on if `enabled`.
+fn engine_agc_config(config: &AudioConfig, enabled: bool) -> AgcConfig {
+    let gate_threshold_db = if config.agc_noise_gate_enabled {
+        -50.0_f32 // vtx-engine default: gate active
+    } else {
+        -100.0_f32 // below noise floor: gate bypassed
+    };
+    AgcConfig {
+        enabled,
+        gate_threshold_db,
+        ..AgcConfig::default()
+    }
+}
+
+/// The format a recording will be saved in: the one that was set, or the
+/// default unless `smart` format is on, in which case it is `None` until
+/// [`select_smart_format`] chooses it when the recording stops.
+fn planned_format(set: Option<OutputFormat>, smart: bool) -> Option<OutputFormat> {
+    match set {
+        Some(format) => Some(format),
+        None if smart => None,
+        None => Some(OutputFormat::default()),
+    }
+}
+
+/// Path of the JSON file listing a recording's gaps: `<name>.gaps.json`.
+fn gaps_sidecar_path(recording: &Path) -> PathBuf {
+    let mut name = recording.file_name().unwrap_or_default().to_os_string();
+    name.push(".gaps.json");
+    recording.with_file_name(name)
 }
 
 /// Events broadcast to subscribed clients.
@@ -40,12 +141,56 @@ pub enum ServiceEvent {
     StateChanged(RecordingState),
     /// Elapsed time update (sent every second during recording)
     ElapsedTime(u64),
+    /// Statistics of the current recording (sent every second during recording)
+    Stats(RecordingStats),
     /// Transcoding started
     TranscodingStarted { format: String },
     /// Transcoding completed
     TranscodingComplete { success: bool, path: Option<String> },
     /// A transcription segment was produced
     TranscriptionSegment { timestamp_secs: f64, text: String },
+    /// Capture source was lost and could not be reconnected; the recording
+    /// has been stopped and saved to `path` if saving succeeded
+    CaptureDisconnected {
+        reason: String,
+        path: Option<String>,
+    },
+    /// A recording was saved to `path`
+    RecordingSaved {
+        path: String,
+        duration_secs: u64,
+        size_bytes: u64,
+    },
+    /// A screenshot was saved to `path`
+    ScreenshotSaved { path: String, size_bytes: u64 },
+    /// A suspended recording continued after `gap_secs` of system sleep;
+    /// the state is back to recording
+    RecordingResumed { gap_secs: f64 },
+    /// Monitors were added, removed or changed resolution
+    MonitorConfigChanged { version: u64 },
+    /// Free video memory dropped below `threshold_mb`; the capture is
+    /// throttled until it recovers
+    GpuMemoryWarning {
+        available_mb: u64,
+        threshold_mb: u64,
+    },
+    /// Seconds left before a recording starts; 0 when the countdown ended
+    /// or was cancelled
+    Countdown { remaining: u8 },
+    /// Part of a recording was uploaded to cloud storage
+    CloudUploadProgress { percent: f64, bytes_uploaded: u64 },
+    /// A cloud upload finished; `error` is set if it failed or was cancelled
+    CloudUploadFinished {
+        path: String,
+        key: Option<String>,
+        error: Option<String>,
+    },
+    /// A frame of the live recording preview, as base64 JPEG
+    PreviewFrame {
+        data: String,
+        width: u32,
+        height: u32,
+    },
     /// Service is shutting down
     Shutdown,
 }
@@ -53,11 +198,14 @@ pub enum ServiceEvent {
 /// Thin transcript writer: creates the file, writes the heading and appends segments.
 struct TranscriptWriter {
     writer: BufWriter<std::fs::File>,
+    /// Configured language code written with each segment, `None` when
+    /// it is detected (vtx-engine doesn't report the detected language)
+    lang: Option<String>,
 }
 
 impl TranscriptWriter {
     /// Create a new transcript writer at the given path.
-    fn new(path: &PathBuf) -> Result<Self, std::io::Error> {
+    fn new(path: &PathBuf, lang: Option<String>) -> Result<Self, std::io::Error> {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
@@ -65,7 +213,7 @@ impl TranscriptWriter {
         let mut writer = BufWriter::new(file);
         writeln!(writer, "# Recording Transcript\n")?;
         writer.flush()?;
-        Ok(Self { writer })
+        Ok(Self { writer, lang })
     }
 
     /// Derive transcript path from the video output path.
@@ -81,13 +229,19 @@ impl TranscriptWriter {
         parent.join(format!("{}_transcript.md", stem))
     }
 
-    /// Append a transcription segment line: `[HH:MM:SS] text`.
+    /// Append a transcription segment line: `[HH:MM:SS] [configured lang=xx] text`,
+    /// or `[HH:MM:SS] text` when the language is detected.
     fn append_segment(&mut self, timestamp_offset_ms: u64, text: &str) {
         let total_secs = timestamp_offset_ms / 1000;
         let h = total_secs / 3600;
         let m = (total_secs % 3600) / 60;
         let s = total_secs % 60;
-        let line = format!("[{:02}:{:02}:{:02}] {}\n\n", h, m, s, text);
+        let tag = self
+            .lang
+            .as_ref()
+            .map(|lang| format!("[configured lang={}] ", lang))
+            .unwrap_or_default();
+        let line = format!("[{:02}:{:02}:{:02}] {}{}\n\n", h, m, s, tag, text);
         let _ = self.writer.write_all(line.as_bytes());
         let _ = self.writer.flush();
     }
@@ -98,11 +252,30 @@ pub struct RecordingManager {
     state: RwLock<RecordingState>,
     stop_flag: Mutex<Option<StopHandle>>,
     recording_start: Mutex<Option<Instant>>,
+    /// Recordings started so far; identifies the current one
+    recording_number: AtomicU64,
     encoding_task: Mutex<Option<tokio::task::JoinHandle<Result<PathBuf, String>>>>,
-    output_format: RwLock<OutputFormat>,
+    /// Output format set by the user for the next recording only
+    output_format: RwLock<Option<OutputFormat>>,
+    /// Output format of the current recording; chosen when it stops if `None`
+    recording_format: RwLock<Option<OutputFormat>>,
+    /// Choose the format from the recording when none was set
+    smart_format: RwLock<bool>,
+    /// Width of the current recording's frames; shared with the encoding task
+    frame_width: Arc<AtomicU32>,
+    /// Frame dropping when encoding falls behind; shared with the encoding task
+    load_shedding: Arc<LoadShedding>,
     audio_config: RwLock<AudioConfig>,
     /// Transcription configuration (enabled/model path)
     transcription_config: RwLock<TranscriptionConfig>,
+    /// Output bounds for window/display thumbnails
+    thumbnail_options: RwLock<ThumbnailOptions>,
+    /// JPEG quality per thumbnail type
+    thumbnail_quality: RwLock<ThumbnailQualityConfig>,
+    /// Bounds concurrent window/display thumbnail captures
+    thumbnail_pool: RwLock<ThumbnailPool>,
+    /// Capture highlight border style
+    highlight_style: RwLock<HighlightStyle>,
     /// vtx-engine instance for audio capture and transcription.
     /// Wrapped in Arc so it can be shared with the event subscriber task.
     engine: Arc<AudioEngine>,
@@ -116,6 +289,65 @@ pub struct RecordingManager {
     /// Uses Arc<std::sync::Mutex> because the OnceLock init is synchronous and
     /// we want to avoid async in the accessor path.
     transcription_segments: std::sync::Arc<std::sync::Mutex<Vec<TranscriptionSegment>>>,
+    /// Statistics for the current (or most recent) recording.
+    stats: std::sync::Mutex<RecordingStats>,
+    /// Virtual sink routing a single application's audio, while recording
+    /// with an `app:<name>` system audio source.
+    #[cfg(target_os = "linux")]
+    app_audio: std::sync::Mutex<Option<crate::capture::linux::audio::AppAudioCapture>>,
+    /// Capture the microphone in WASAPI exclusive mode when possible
+    audio_exclusive_mode: RwLock<bool>,
+    /// FFmpeg video filter chain applied to recordings
+    video_filter_chain: RwLock<Option<String>>,
+    /// Threads FFmpeg's video encoders use
+    encoder_threads: RwLock<EncoderThreads>,
+    /// Seconds between video keyframes
+    keyframe_interval_secs: RwLock<f32>,
+    /// Lowering the resolution of later recordings when frames are dropped
+    dynamic_scaling: RwLock<DynamicResolutionScaling>,
+    /// Capture frame rate limit for the next recording only
+    frame_rate_limit: RwLock<Option<u32>>,
+    /// Noise floor (dBFS) for trimming leading/trailing silence, when enabled
+    silence_trim_threshold_db: RwLock<Option<f32>>,
+    /// Local directory recordings are written to before moving to the output
+    temp_recording_dir: RwLock<Option<PathBuf>>,
+    /// Directory recordings are saved to; the system default when `None`
+    output_dir: RwLock<Option<PathBuf>>,
+    /// Create a missing output directory instead of failing the recording
+    create_output_dir: RwLock<bool>,
+    /// Copy the saved recording's path to the clipboard when stopping
+    copy_path_to_clipboard: RwLock<bool>,
+    /// Clipboard access, once the Tauri layer has provided it
+    clipboard_writer: std::sync::OnceLock<ClipboardWriter>,
+    /// Open the output directory in the file manager when stopping
+    open_output_dir: RwLock<bool>,
+    /// Write a SHA-256 checksum file next to each saved recording
+    verify_on_finish: RwLock<bool>,
+    /// Seconds to count down before recordings started from the UI
+    countdown_secs: RwLock<u8>,
+    /// Abort handle of the countdown in progress, if any
+    countdown: std::sync::Mutex<Option<tokio::task::AbortHandle>>,
+    /// Cloud storage recordings are uploaded to, when enabled
+    cloud_upload: RwLock<Option<CloudUploadConfig>>,
+    /// Cancel flag of the upload in progress, if any
+    cloud_upload_cancel: std::sync::Mutex<Option<Arc<AtomicBool>>>,
+    /// Webcam overlaid on recordings, if any
+    pip: RwLock<Option<PipConfig>>,
+    /// Set while the system sleeps; shared with the encoding task
+    suspended: Arc<AtomicBool>,
+    /// The current suspension, if the system is asleep
+    suspension: std::sync::Mutex<Option<Suspension>>,
+    /// Suspensions of the current recording
+    gaps: std::sync::Mutex<Vec<RecordingGap>>,
+    /// What the current recording captures, for the history log
+    current_source: std::sync::Mutex<Option<(RecordingSourceType, Option<String>)>>,
+    /// Exclusive-mode microphone capture, while recording with it
+    #[cfg(target_os = "windows")]
+    exclusive_audio:
+        std::sync::Mutex<Option<crate::capture::windows::exclusive_audio::ExclusiveCapture>>,
+    /// Playback of the captured audio, while recording with monitoring enabled
+    #[cfg(target_os = "macos")]
+    audio_monitor: std::sync::Mutex<Option<crate::capture::macos::audio::AudioMonitor>>,
 }
 
 impl RecordingManager {
@@ -171,15 +403,54 @@ impl RecordingManager {
             state: RwLock::new(RecordingState::Idle),
             stop_flag: Mutex::new(None),
             recording_start: Mutex::new(None),
+            recording_number: AtomicU64::new(0),
             encoding_task: Mutex::new(None),
-            output_format: RwLock::new(OutputFormat::default()),
+            output_format: RwLock::new(None),
+            recording_format: RwLock::new(None),
+            smart_format: RwLock::new(false),
+            frame_width: Arc::new(AtomicU32::new(0)),
+            load_shedding: Arc::new(LoadShedding::default()),
             audio_config: RwLock::new(AudioConfig::default()),
             transcription_config: RwLock::new(TranscriptionConfig::default()),
+            thumbnail_options: RwLock::new(ThumbnailOptions::default()),
+            thumbnail_quality: RwLock::new(ThumbnailQualityConfig::default()),
+            thumbnail_pool: RwLock::new(ThumbnailPool::default()),
+            highlight_style: RwLock::new(HighlightStyle::default()),
             engine: Arc::new(engine),
             engine_task: Mutex::new(None),
             event_tx,
             elapsed_task: Mutex::new(None),
             transcription_segments: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            stats: std::sync::Mutex::new(RecordingStats::default()),
+            #[cfg(target_os = "linux")]
+            app_audio: std::sync::Mutex::new(None),
+            audio_exclusive_mode: RwLock::new(false),
+            video_filter_chain: RwLock::new(None),
+            encoder_threads: RwLock::new(EncoderThreads::Auto),
+            keyframe_interval_secs: RwLock::new(DEFAULT_KEYFRAME_INTERVAL_SECS),
+            dynamic_scaling: RwLock::new(DynamicResolutionScaling::default()),
+            frame_rate_limit: RwLock::new(None),
+            silence_trim_threshold_db: RwLock::new(None),
+            temp_recording_dir: RwLock::new(None),
+            output_dir: RwLock::new(None),
+            create_output_dir: RwLock::new(true),
+            copy_path_to_clipboard: RwLock::new(false),
+            clipboard_writer: std::sync::OnceLock::new(),
+            open_output_dir: RwLock::new(false),
+            verify_on_finish: RwLock::new(true),
+            countdown_secs: RwLock::new(0),
+            countdown: std::sync::Mutex::new(None),
+            cloud_upload: RwLock::new(None),
+            cloud_upload_cancel: std::sync::Mutex::new(None),
+            pip: RwLock::new(None),
+            suspended: Arc::new(AtomicBool::new(false)),
+            suspension: std::sync::Mutex::new(None),
+            gaps: std::sync::Mutex::new(Vec::new()),
+            current_source: std::sync::Mutex::new(None),
+            #[cfg(target_os = "windows")]
+            exclusive_audio: std::sync::Mutex::new(None),
+            #[cfg(target_os = "macos")]
+            audio_monitor: std::sync::Mutex::new(None),
         }
     }
 
@@ -208,29 +479,120 @@ impl RecordingManager {
         self.broadcast(ServiceEvent::StateChanged(new_state));
     }
 
+    /// Number of the current or last recording. It changes each time a
+    /// recording starts, so it tells whether a recording is still the one
+    /// seen earlier.
+    pub fn recording_number(&self) -> u64 {
+        self.recording_number.load(Ordering::Relaxed)
+    }
+
     /// Get elapsed recording time in seconds.
+    ///
+    /// Time spent suspended does not count.
     pub async fn get_elapsed_seconds(&self) -> u64 {
         let start = self.recording_start.lock().await;
         match *start {
-            Some(instant) => instant.elapsed().as_secs(),
+            Some(instant) => {
+                let now = self
+                    .suspension
+                    .lock()
+                    .unwrap()
+                    .map_or_else(Instant::now, |suspension| suspension.since);
+                now.saturating_duration_since(instant).as_secs()
+            }
             None => 0,
         }
     }
 
-    /// Get the current output format.
-    pub async fn get_output_format(&self) -> OutputFormat {
-        *self.output_format.read().await
+    /// Pause the recording because the system is about to sleep.
+    ///
+    /// The encoder marks the gap with a black frame and holds its timeline
+    /// until [`resume_recording`](Self::resume_recording); the stop flag is
+    /// left alone so capture and encoding survive the sleep.
+    pub async fn suspend_recording(&self) {
+        let mut state = self.state.write().await;
+        if *state != RecordingState::Recording {
+            return;
+        }
+        // Read before `suspension` is set, which freezes the elapsed time
+        let at_secs = match *self.recording_start.lock().await {
+            Some(start) => start.elapsed().as_secs(),
+            None => 0,
+        };
+        *self.suspension.lock().unwrap() = Some(Suspension::new(at_secs));
+        self.suspended.store(true, Ordering::Relaxed);
+        *state = RecordingState::Suspended;
+        drop(state);
+
+        info!("Recording suspended for system sleep at {}s", at_secs);
+        self.broadcast(ServiceEvent::StateChanged(RecordingState::Suspended));
+    }
+
+    /// Continue a recording suspended by [`suspend_recording`](Self::suspend_recording).
+    pub async fn resume_recording(&self) {
+        let mut state = self.state.write().await;
+        if *state != RecordingState::Suspended {
+            return;
+        }
+        let mut gap_secs = 0.0;
+        let suspension = self.suspension.lock().unwrap().take();
+        if let Some(suspension) = suspension {
+            gap_secs = suspension.gap(SystemTime::now()).as_secs_f64();
+            // Move the start forward so elapsed time skips the gap
+            if let Some(start) = self.recording_start.lock().await.as_mut() {
+                *start += suspension.since.elapsed();
+            }
+            self.gaps.lock().unwrap().push(RecordingGap {
+                at_secs: suspension.at_secs,
+                duration_secs: gap_secs,
+            });
+            info!("Recording resumed after {:.1}s of system sleep", gap_secs);
+        }
+        self.suspended.store(false, Ordering::Relaxed);
+        *state = RecordingState::Recording;
+        drop(state);
+
+        self.broadcast(ServiceEvent::RecordingResumed { gap_secs });
+    }
+
+    /// Write the suspensions of the recording to `<name>.gaps.json` next to it.
+    fn write_gaps(&self, path: &Path) {
+        let gaps = std::mem::take(&mut *self.gaps.lock().unwrap());
+        if gaps.is_empty() {
+            return;
+        }
+        let sidecar = gaps_sidecar_path(path);
+        let json = serde_json::json!({ "gaps": gaps });
+        match serde_json::to_string_pretty(&json)
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(&sidecar, json).map_err(|e| e.to_string()))
+        {
+            Ok(()) => info!("Recording gaps written to {}", sidecar.display()),
+            Err(e) => warn!("Failed to write recording gaps: {}", e),
+        }
+    }
+
+    /// Get the output format of the next recording, or `None` if smart
+    /// format will choose it when the recording stops.
+    pub async fn get_output_format(&self) -> Option<OutputFormat> {
+        planned_format(
+            *self.output_format.read().await,
+            *self.smart_format.read().await,
+        )
     }
 
-    /// Set the output format for future recordings.
+    /// Set the output format of the next recording.
+    ///
+    /// The format is used once; later recordings fall back to the default or
+    /// automatically chosen format unless one is set again.
     pub async fn set_output_format(&self, format: OutputFormat) -> Result<(), String> {
         let state = self.state.read().await;
         if *state != RecordingState::Idle {
             return Err("Cannot change format while recording".to_string());
         }
         let mut fmt = self.output_format.write().await;
-        *fmt = format;
-        info!("Output format set to {:?}", format);
+        *fmt = Some(format);
+        info!("Output format for next recording: {:?}", format);
         Ok(())
     }
 
@@ -247,16 +609,8 @@ impl RecordingManager {
         }
 
         // Hot-apply AGC config to the engine immediately (takes effect within one audio chunk).
-        let gate_threshold_db = if config.agc_noise_gate_enabled {
-            -50.0_f32 // vtx-engine default: gate active
-        } else {
-            -100.0_f32 // below noise floor: gate bypassed
-        };
-        self.engine.set_agc_config(AgcConfig {
-            enabled: config.agc_enabled,
-            gate_threshold_db,
-            ..AgcConfig::default()
-        });
+        self.engine
+            .set_agc_config(engine_agc_config(&config, config.agc_enabled));
 
         let mut cfg = self.audio_config.write().await;
         *cfg = config;
@@ -264,6 +618,46 @@ impl RecordingManager {
         Ok(())
     }
 
+    /// Enable or disable audio monitoring and set its volume.
+    ///
+    /// Unlike the rest of the audio configuration this may change while
+    /// recording: the monitor is started, stopped or re-leveled immediately.
+    pub async fn set_audio_monitor(&self, enabled: bool, volume: f32) {
+        {
+            let mut cfg = self.audio_config.write().await;
+            cfg.audio_monitor_enabled = enabled;
+            cfg.monitor_volume = volume;
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            let recording = *self.state.read().await == RecordingState::Recording;
+            let mut monitor = self.audio_monitor.lock().unwrap();
+            match monitor.as_ref() {
+                Some(running) if enabled => running.set_volume(volume),
+                Some(_) => {
+                    monitor.take();
+                }
+                None if enabled && recording && self.engine.is_capturing() => {
+                    *monitor = Some(crate::capture::macos::audio::AudioMonitor::start(
+                        self.engine.subscribe(),
+                        volume,
+                    ));
+                }
+                None => {}
+            }
+        }
+
+        info!("Audio monitor: enabled={}, volume={}", enabled, volume);
+    }
+
+    /// Set the audio bitrate in kbps (0 for automatic). Takes effect from
+    /// the next recording, so it may change while recording.
+    pub async fn set_audio_bitrate(&self, bitrate_kbps: u32) {
+        self.audio_config.write().await.audio_bitrate_kbps = bitrate_kbps;
+        info!("Audio bitrate: {} kbps", bitrate_kbps);
+    }
+
     /// Get the current transcription configuration.
     pub async fn get_transcription_config(&self) -> TranscriptionConfig {
         self.transcription_config.read().await.clone()
@@ -279,12 +673,440 @@ impl RecordingManager {
             return Err("Cannot change transcription config while recording".to_string());
         }
         let enabled = config.enabled;
+        let language = config.language.clone();
         let mut cfg = self.transcription_config.write().await;
         *cfg = config;
-        info!("Transcription configuration updated: enabled={}", enabled);
+        info!(
+            "Transcription configuration updated: enabled={}, language={:?}",
+            enabled, language
+        );
         Ok(())
     }
 
+    /// Get the current thumbnail options.
+    pub async fn get_thumbnail_options(&self) -> ThumbnailOptions {
+        *self.thumbnail_options.read().await
+    }
+
+    /// Set the thumbnail options (takes effect on the next thumbnail request).
+    pub async fn set_thumbnail_options(&self, options: ThumbnailOptions) {
+        *self.thumbnail_options.write().await = options;
+        info!(
+            "Thumbnail size updated: {}x{}",
+            options.max_width, options.max_height
+        );
+    }
+
+    /// Get the pool that thumbnail captures run on.
+    pub async fn get_thumbnail_pool(&self) -> ThumbnailPool {
+        self.thumbnail_pool.read().await.clone()
+    }
+
+    /// Resize the thumbnail pool. Captures already running keep their permits
+    /// from the previous pool.
+    pub async fn set_thumbnail_pool_size(&self, max_concurrent: usize) {
+        let mut pool = self.thumbnail_pool.write().await;
+        if pool.max_concurrent() != max_concurrent {
+            *pool = ThumbnailPool::new(max_concurrent);
+            info!("Thumbnail pool size updated: {}", pool.max_concurrent());
+        }
+    }
+
+    /// Save a full-resolution PNG of `target`, or of the primary display if
+    /// `None`, to the output directory. Returns the saved path.
+    ///
+    /// Runs on the thumbnail pool and does not affect a recording in progress.
+    pub async fn take_screenshot(
+        &self,
+        target: Option<ScreenshotTarget>,
+    ) -> Result<PathBuf, String> {
+        let target = match target {
+            Some(target) => target,
+            None => {
+                let monitors = crate::capture::list_monitors();
+                let primary = monitors
+                    .iter()
+                    .find(|m| m.is_primary)
+                    .or(monitors.first())
+                    .ok_or("No display found for the screenshot")?;
+                ScreenshotTarget::Display {
+                    monitor_id: primary.id.clone(),
+                }
+            }
+        };
+        let path = crate::encoder::generate_screenshot_path(
+            self.output_dir.read().await.as_deref(),
+            *self.create_output_dir.read().await,
+        )?;
+
+        let save_path = path.clone();
+        self.get_thumbnail_pool()
+            .await
+            .run(move || {
+                use crate::capture::ThumbnailCapture;
+                let backend = crate::capture::get_backend();
+                let screenshot = match &target {
+                    ScreenshotTarget::Window { window_handle } => {
+                        backend.capture_window_screenshot(*window_handle)
+                    }
+                    ScreenshotTarget::Display { monitor_id } => {
+                        backend.capture_display_screenshot(monitor_id)
+                    }
+                }
+                .map_err(|e| format!("Failed to capture screenshot: {}", format_error_chain(&e)))?;
+                save_bgra_png(
+                    &screenshot.data,
+                    screenshot.width,
+                    screenshot.height,
+                    &save_path,
+                )
+            })
+            .await??;
+
+        info!("Screenshot saved: {}", path.display());
+        self.broadcast(ServiceEvent::ScreenshotSaved {
+            path: path.display().to_string(),
+            size_bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+        });
+        Ok(path)
+    }
+
+    /// Get the current thumbnail quality settings, lowered while encoding
+    /// is shedding load.
+    pub async fn get_thumbnail_quality(&self) -> ThumbnailQualityConfig {
+        let quality = *self.thumbnail_quality.read().await;
+        if self.load_shedding.lowers_thumbnail_quality() {
+            quality.capped(SHEDDING_THUMBNAIL_QUALITY)
+        } else {
+            quality
+        }
+    }
+
+    /// Set the thumbnail quality settings.
+    pub async fn set_thumbnail_quality(&self, quality: ThumbnailQualityConfig) {
+        *self.thumbnail_quality.write().await = quality;
+        info!(
+            "Thumbnail quality updated: window={}, display={}, region={}",
+            quality.window_quality, quality.display_quality, quality.region_quality
+        );
+    }
+
+    /// Set whether the microphone is captured in WASAPI exclusive mode
+    /// (takes effect on the next recording; Windows only).
+    pub async fn set_audio_exclusive_mode(&self, enabled: bool) {
+        *self.audio_exclusive_mode.write().await = enabled;
+        info!("Audio exclusive mode: {}", enabled);
+    }
+
+    /// Set the FFmpeg video filter chain applied to the next recording.
+    pub async fn set_video_filter_chain(&self, filter: Option<String>) {
+        info!("Video filter chain: {:?}", filter);
+        *self.video_filter_chain.write().await = filter;
+    }
+
+    /// Get the number of threads FFmpeg's video encoders use.
+    pub async fn get_encoder_threads(&self) -> EncoderThreads {
+        *self.encoder_threads.read().await
+    }
+
+    /// Set the number of threads FFmpeg's video encoders use, from the next
+    /// recording (and its transcode) on.
+    pub async fn set_encoder_threads(&self, threads: EncoderThreads) {
+        *self.encoder_threads.write().await = threads;
+        info!("Encoder threads: {:?}", threads);
+    }
+
+    /// Set the time between video keyframes, from the next recording (and
+    /// its transcode) on.
+    pub async fn set_keyframe_interval(&self, secs: f32) {
+        *self.keyframe_interval_secs.write().await = secs;
+        info!("Keyframe interval: {} s", secs);
+    }
+
+    /// Set dynamic resolution scaling, from the next recording on.
+    pub async fn set_dynamic_resolution_scaling(&self, settings: DynamicResolutionScaling) {
+        *self.dynamic_scaling.write().await = settings;
+        info!("Dynamic resolution scaling: {:?}", settings);
+    }
+
+    /// Limit the capture frame rate of the next recording.
+    ///
+    /// The limit is used once; later recordings capture at the full rate
+    /// unless a limit is set again.
+    pub async fn set_frame_rate_limit(&self, max_fps: Option<u32>) {
+        *self.frame_rate_limit.write().await = max_fps;
+        info!("Frame rate limit for next recording: {:?}", max_fps);
+    }
+
+    /// Broadcast preview frames of the recording at up to `fps` frames per
+    /// second as [`ServiceEvent::PreviewFrame`], until
+    /// [`stop_preview_stream`](Self::stop_preview_stream).
+    pub fn start_preview_stream(&self, fps: u8) -> Result<(), String> {
+        crate::encoder::preview::start(fps, self.event_tx.clone())?;
+        info!("Preview stream started at {} fps", fps);
+        Ok(())
+    }
+
+    /// Stop broadcasting preview frames.
+    pub fn stop_preview_stream(&self) {
+        crate::encoder::preview::stop();
+        info!("Preview stream stopped");
+    }
+
+    /// Set whether leading and trailing silence is trimmed from recordings,
+    /// and the noise floor below which audio counts as silence.
+    pub async fn set_silence_trim(&self, enabled: bool, threshold_db: f32) {
+        *self.silence_trim_threshold_db.write().await = enabled.then_some(threshold_db);
+        info!(
+            "Silence trimming: enabled={}, threshold={} dB",
+            enabled, threshold_db
+        );
+    }
+
+    /// Set the directory recordings are written to while capturing.
+    pub async fn set_temp_recording_dir(&self, dir: Option<PathBuf>) {
+        info!("Temporary recording directory: {:?}", dir);
+        *self.temp_recording_dir.write().await = dir;
+    }
+
+    /// Set the directory recordings are saved to and whether it is created
+    /// when missing.
+    pub async fn set_output_dir(&self, dir: Option<PathBuf>, create_if_missing: bool) {
+        info!(
+            "Output directory: {:?} (create if missing: {})",
+            dir, create_if_missing
+        );
+        *self.output_dir.write().await = dir;
+        *self.create_output_dir.write().await = create_if_missing;
+    }
+
+    /// Set whether the saved recording's path is copied to the clipboard.
+    pub async fn set_copy_path_to_clipboard(&self, enabled: bool) {
+        info!("Copy recording path to clipboard: {}", enabled);
+        *self.copy_path_to_clipboard.write().await = enabled;
+    }
+
+    /// Set whether the output directory is opened when a recording stops.
+    pub async fn set_open_output_dir(&self, enabled: bool) {
+        info!("Open output directory after recording: {}", enabled);
+        *self.open_output_dir.write().await = enabled;
+    }
+
+    /// Set whether frames are dropped when encoding falls behind real time.
+    pub fn set_load_shedding(&self, enabled: bool) {
+        info!("Load shedding: {}", enabled);
+        self.load_shedding.set_enabled(enabled);
+    }
+
+    /// Set whether the output format is chosen from the recording when
+    /// none was set.
+    pub async fn set_smart_format(&self, enabled: bool) {
+        info!("Smart output format: {}", enabled);
+        *self.smart_format.write().await = enabled;
+    }
+
+    /// Set whether a checksum file is written for each saved recording.
+    pub async fn set_verify_on_finish(&self, enabled: bool) {
+        info!("Write recording checksums: {}", enabled);
+        *self.verify_on_finish.write().await = enabled;
+    }
+
+    /// Set the countdown before recordings started from the UI.
+    pub async fn set_countdown_secs(&self, secs: u8) {
+        info!("Recording countdown: {}s", secs);
+        *self.countdown_secs.write().await = secs;
+    }
+
+    /// Get the countdown before recordings start.
+    pub async fn get_countdown_secs(&self) -> u8 {
+        *self.countdown_secs.read().await
+    }
+
+    /// Count down the configured seconds, broadcasting
+    /// [`ServiceEvent::Countdown`] every second.
+    ///
+    /// Returns an error if the countdown was cancelled with
+    /// [`cancel_countdown`](Self::cancel_countdown).
+    pub async fn run_countdown(&self) -> Result<(), String> {
+        let secs = self.get_countdown_secs().await;
+        if secs == 0 {
+            return Ok(());
+        }
+        self.check_idle().await?;
+
+        let task = {
+            let mut slot = self.countdown.lock().unwrap();
+            if slot.is_some() {
+                return Err("A countdown is already running".to_string());
+            }
+            let task = tokio::spawn(async move {
+                let manager = get_recording_manager();
+                for remaining in (1..=secs).rev() {
+                    manager.broadcast(ServiceEvent::Countdown { remaining });
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                }
+            });
+            *slot = Some(task.abort_handle());
+            task
+        };
+
+        let result = task.await;
+        self.countdown.lock().unwrap().take();
+        self.broadcast(ServiceEvent::Countdown { remaining: 0 });
+
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) if e.is_cancelled() => {
+                info!("Recording countdown cancelled");
+                Err("Countdown cancelled".to_string())
+            }
+            Err(e) => Err(format!("Countdown failed: {}", e)),
+        }
+    }
+
+    /// Cancel the countdown in progress. Returns whether one was running.
+    pub fn cancel_countdown(&self) -> bool {
+        match self.countdown.lock().unwrap().as_ref() {
+            Some(task) => {
+                task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the cloud storage recordings are uploaded to after saving.
+    pub async fn set_cloud_upload(&self, config: Option<CloudUploadConfig>) {
+        let config = config.filter(|c| c.enabled);
+        info!(
+            "Cloud upload: {}",
+            config.as_ref().map_or("disabled", |c| c.bucket.as_str())
+        );
+        *self.cloud_upload.write().await = config;
+    }
+
+    /// Set the webcam overlaid on recordings.
+    pub async fn set_pip(&self, pip: Option<PipConfig>) {
+        info!("Webcam overlay: {:?}", pip);
+        *self.pip.write().await = pip;
+    }
+
+    /// Cancel the cloud upload in progress. Returns whether one was running.
+    pub fn cancel_cloud_upload(&self) -> bool {
+        match self.cloud_upload_cancel.lock().unwrap().as_ref() {
+            Some(cancel) => {
+                cancel.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Upload a saved recording in the background if cloud upload is enabled.
+    async fn start_cloud_upload(&self, file_path: PathBuf) {
+        let Some(config) = self.cloud_upload.read().await.clone() else {
+            return;
+        };
+        let cancel = Arc::new(AtomicBool::new(false));
+        if let Some(previous) = self
+            .cloud_upload_cancel
+            .lock()
+            .unwrap()
+            .replace(cancel.clone())
+        {
+            // Only one upload at a time; the newest recording wins
+            previous.store(true, Ordering::SeqCst);
+        }
+
+        tokio::spawn(async move {
+            let manager = get_recording_manager();
+            info!("[Cloud] Uploading {}", file_path.display());
+            let result =
+                crate::cloud::upload_file(&config, &file_path, &cancel, |uploaded, total| {
+                    manager.broadcast(ServiceEvent::CloudUploadProgress {
+                        percent: crate::cloud::upload_percent(uploaded, total),
+                        bytes_uploaded: uploaded,
+                    });
+                })
+                .await;
+
+            {
+                let mut current = manager.cloud_upload_cancel.lock().unwrap();
+                if current.as_ref().is_some_and(|c| Arc::ptr_eq(c, &cancel)) {
+                    current.take();
+                }
+            }
+
+            let (key, error) = match result {
+                Ok(key) => {
+                    info!("[Cloud] Uploaded {} as {}", file_path.display(), key);
+                    (Some(key), None)
+                }
+                Err(e) => {
+                    warn!("[Cloud] Upload of {} failed: {}", file_path.display(), e);
+                    (None, Some(e))
+                }
+            };
+            manager.broadcast(ServiceEvent::CloudUploadFinished {
+                path: file_path.display().to_string(),
+                key,
+                error,
+            });
+        });
+    }
+
+    /// Provide clipboard access. Only the first writer is kept.
+    pub fn set_clipboard_writer(&self, writer: ClipboardWriter) {
+        if self.clipboard_writer.set(writer).is_err() {
+            warn!("Clipboard writer already set");
+        }
+    }
+
+    /// Copy `path` to the clipboard if enabled. Returns whether it was copied.
+    async fn copy_path_to_clipboard(&self, path: &Path) -> bool {
+        if !*self.copy_path_to_clipboard.read().await {
+            return false;
+        }
+        let Some(writer) = self.clipboard_writer.get() else {
+            warn!("Clipboard is not available");
+            return false;
+        };
+        match writer(&path.to_string_lossy()) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("Failed to copy recording path to clipboard: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Write the checksum file for a saved recording if enabled.
+    async fn write_checksum(&self, path: &Path) {
+        if !*self.verify_on_finish.read().await {
+            return;
+        }
+        let path = path.to_path_buf();
+        match tokio::task::spawn_blocking(move || integrity::write_checksum(&path)).await {
+            Ok(Ok(digest)) => info!("Recording SHA-256: {}", digest),
+            Ok(Err(e)) => warn!("Failed to write recording checksum: {}", e),
+            Err(e) => warn!("Checksum task error: {}", e),
+        }
+    }
+
+    /// Get the capture highlight style.
+    pub async fn get_highlight_style(&self) -> HighlightStyle {
+        *self.highlight_style.read().await
+    }
+
+    /// Set the capture highlight style (takes effect on the next highlight).
+    pub async fn set_highlight_style(&self, style: HighlightStyle) {
+        *self.highlight_style.write().await = style;
+        info!(
+            "Highlight style updated: color={:?}, border_width={}, animation={:?}",
+            style.color_rgba, style.border_width, style.animation
+        );
+    }
+
     /// Get current transcription status (from vtx-engine state).
     pub async fn get_transcription_status(&self) -> TranscriptionStatus {
         let engine_status = self.engine.get_status();
@@ -325,6 +1147,15 @@ impl RecordingManager {
         segments.clear();
     }
 
+    /// Get statistics for the current (or most recent) recording.
+    pub fn get_stats(&self) -> RecordingStats {
+        RecordingStats {
+            load_shedding_active: self.load_shedding.is_active(),
+            dropped_frame_ratio: self.load_shedding.drop_ratio(),
+            ..*self.stats.lock().unwrap()
+        }
+    }
+
     /// Get a clone of the stop flag (for external stop monitoring).
     #[allow(dead_code)]
     pub async fn get_stop_flag(&self) -> Option<StopHandle> {
@@ -332,27 +1163,45 @@ impl RecordingManager {
     }
 
     /// Start recording the specified window.
-    pub async fn start_window_capture(&self, window_handle: isize) -> Result<(), String> {
+    pub async fn start_window_capture(
+        &self,
+        window_handle: isize,
+        pip: Option<PipConfig>,
+    ) -> Result<(), String> {
         self.check_idle().await?;
+        self.apply_next_recording_settings().await;
 
-        let backend = crate::capture::get_backend();
+        let backend = crate::capture::get_capture_backend().map_err(String::from)?;
         let (frame_rx, stop_flag) = backend
             .start_window_capture(window_handle)
-            .map_err(|e| e.to_string())?;
-
-        self.start_encoding(frame_rx, stop_flag).await
+            .map_err(String::from)?;
+
+        let title = crate::capture::list_windows()
+            .into_iter()
+            .find(|w| w.handle == window_handle)
+            .map(|w| w.title);
+        self.set_current_source(RecordingSourceType::Window, title);
+        self.start_encoding(frame_rx, stop_flag, Vec::new(), None, pip)
+            .await
     }
 
     /// Start recording a screen region.
-    pub async fn start_region_capture(&self, region: CaptureRegion) -> Result<(), String> {
+    pub async fn start_region_capture(
+        &self,
+        region: CaptureRegion,
+        pip: Option<PipConfig>,
+    ) -> Result<(), String> {
         self.check_idle().await?;
+        self.apply_next_recording_settings().await;
 
-        let backend = crate::capture::get_backend();
-        let (frame_rx, stop_flag) = backend
-            .start_region_capture(region)
-            .map_err(|e| e.to_string())?;
+        let backend = crate::capture::get_capture_backend().map_err(String::from)?;
+        let monitor_id = region.monitor_id.clone();
+        let (frame_rx, stop_flag) = backend.start_region_capture(region).map_err(String::from)?;
 
-        self.start_encoding(frame_rx, stop_flag).await
+        self.set_current_source(RecordingSourceType::Region, monitor_name(&monitor_id));
+        let color_profile = get_display_color_profile(&monitor_id);
+        self.start_encoding(frame_rx, stop_flag, Vec::new(), color_profile, pip)
+            .await
     }
 
     /// Start recording an entire display.
@@ -361,33 +1210,160 @@ impl RecordingManager {
         monitor_id: String,
         width: u32,
         height: u32,
+        pip: Option<PipConfig>,
     ) -> Result<(), String> {
         self.check_idle().await?;
+        self.apply_next_recording_settings().await;
 
-        let backend = crate::capture::get_backend();
+        let backend = crate::capture::get_capture_backend().map_err(String::from)?;
+        let name = monitor_name(&monitor_id);
+        let color_profile = get_display_color_profile(&monitor_id);
         let (frame_rx, stop_flag) = backend
             .start_display_capture(monitor_id, width, height)
-            .map_err(|e| e.to_string())?;
+            .map_err(String::from)?;
 
-        self.start_encoding(frame_rx, stop_flag).await
+        self.set_current_source(RecordingSourceType::Display, name);
+        self.start_encoding(frame_rx, stop_flag, Vec::new(), color_profile, pip)
+            .await
     }
 
     /// Start portal-based capture (for GNOME/KDE tray mode).
     #[cfg(target_os = "linux")]
-    pub async fn start_portal_capture(&self) -> Result<(), String> {
+    pub async fn start_portal_capture(&self, pip: Option<PipConfig>) -> Result<(), String> {
         self.check_idle().await?;
+        self.apply_next_recording_settings().await;
 
         let backend = crate::capture::get_backend();
-        let (frame_rx, stop_flag) = backend.start_portal_capture().map_err(|e| e.to_string())?;
+        let (frame_rx, stop_flag) = backend.start_portal_capture().map_err(String::from)?;
 
-        self.start_encoding(frame_rx, stop_flag).await
+        self.set_current_source(RecordingSourceType::Portal, None);
+        self.start_encoding(frame_rx, stop_flag, Vec::new(), None, pip)
+            .await
     }
 
     #[cfg(not(target_os = "linux"))]
-    pub async fn start_portal_capture(&self) -> Result<(), String> {
+    pub async fn start_portal_capture(&self, _pip: Option<PipConfig>) -> Result<(), String> {
         Err("Portal capture is only available on Linux".to_string())
     }
 
+    /// Start recording the monitor that shows a Hyprland workspace.
+    ///
+    /// The workspace name is stored in the recording's metadata.
+    #[cfg(target_os = "linux")]
+    pub async fn start_workspace_capture(
+        &self,
+        workspace_id: i32,
+        pip: Option<PipConfig>,
+    ) -> Result<(), String> {
+        self.check_idle().await?;
+        self.apply_next_recording_settings().await;
+
+        let backend = crate::capture::get_backend();
+        let (frame_rx, stop_flag, workspace_name) = backend
+            .start_workspace_capture(workspace_id)
+            .map_err(String::from)?;
+
+        let metadata = vec![(
+            "comment".to_string(),
+            format!("Hyprland workspace: {}", workspace_name),
+        )];
+        self.set_current_source(RecordingSourceType::Workspace, Some(workspace_name));
+        self.start_encoding(frame_rx, stop_flag, metadata, None, pip)
+            .await
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub async fn start_workspace_capture(
+        &self,
+        _workspace_id: i32,
+        _pip: Option<PipConfig>,
+    ) -> Result<(), String> {
+        Err("Workspace capture is only available on Hyprland".to_string())
+    }
+
+    /// Capture a short clip from one audio source and compute its levels.
+    ///
+    /// Uses the audio engine the same way a recording does, so only runs while
+    /// idle. Application audio sources only exist while recording and can't
+    /// be checked.
+    pub async fn capture_audio_waveform(
+        &self,
+        source_id: &str,
+        duration_ms: u64,
+    ) -> Result<crate::waveform::WaveformResult, String> {
+        let duration = crate::waveform::validate_duration(duration_ms)?;
+        self.check_idle().await?;
+        if source_id.starts_with(APP_AUDIO_SOURCE_PREFIX) {
+            return Err("Application audio can only be checked while recording".to_string());
+        }
+
+        // Loopback sources go in the engine's system audio slot so they are
+        // routed the same way as when recording.
+        let is_system_audio = source_id == "system"
+            || crate::capture::list_audio_sources()
+                .iter()
+                .any(|s| s.id == source_id && s.source_type == AudioSourceType::Output);
+        let (mic_device_id, system_device_id) = if is_system_audio {
+            let device_id = self
+                .resolve_system_audio_device_id(Some(source_id))
+                .ok_or_else(|| format!("Audio source not available: {}", source_id))?;
+            (None, Some(device_id))
+        } else {
+            (Some(source_id.to_string()), None)
+        };
+
+        let rx = self.engine.subscribe();
+        self.engine
+            .start_capture(mic_device_id, system_device_id)
+            .await?;
+        let collected =
+            tokio::task::spawn_blocking(move || crate::waveform::collect_samples(rx, duration))
+                .await;
+
+        // A recording started meanwhile has taken over the capture
+        if self.get_state().await == RecordingState::Idle {
+            if let Err(e) = self.engine.stop_capture().await {
+                warn!("[Waveform] Failed to stop audio capture: {}", e);
+            }
+        }
+
+        let (samples, sample_rate) =
+            collected.map_err(|e| format!("Waveform task failed: {}", e))?;
+        if samples.is_empty() {
+            return Err(format!("No audio received from {}", source_id));
+        }
+        Ok(crate::waveform::WaveformResult::from_samples(
+            &samples,
+            sample_rate,
+        ))
+    }
+
+    /// Remember what the recording being started captures.
+    fn set_current_source(&self, source_type: RecordingSourceType, name: Option<String>) {
+        *self.current_source.lock().unwrap() = Some((source_type, name));
+    }
+
+    /// Append a saved recording to the history log in the background.
+    fn record_history(&self, file_path: &Path, format: OutputFormat, duration_secs: u64) {
+        let Some((source_type, source_name)) = self.current_source.lock().unwrap().take() else {
+            return;
+        };
+        let entry = RecordingHistoryEntry {
+            path: file_path.display().to_string(),
+            format: format!("{:?}", format).to_lowercase(),
+            duration_secs,
+            source_type,
+            source_name,
+            file_size: std::fs::metadata(file_path).map_or(0, |m| m.len()),
+            created_at: chrono::Local::now().to_rfc3339(),
+        };
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = crate::history::append_entry(entry) {
+                warn!("[History] Failed to record recording: {}", e);
+            }
+        });
+    }
+
     /// Check that we're in idle state.
     async fn check_idle(&self) -> Result<(), String> {
         let state = self.state.read().await;
@@ -397,6 +1373,19 @@ impl RecordingManager {
         Ok(())
     }
 
+    /// Apply the settings given for the next recording only, before a
+    /// capture starts: the frame rate limit goes to the capture backends and
+    /// the output format to the recording.
+    async fn apply_next_recording_settings(&self) {
+        let max_fps = self.frame_rate_limit.write().await.take();
+        if let Some(fps) = max_fps {
+            info!("Limiting capture to {} fps", fps);
+        }
+        crate::capture::frame_limit::set_max_fps(max_fps);
+
+        *self.recording_format.write().await = self.output_format.write().await.take();
+    }
+
     fn resolve_system_audio_device_id(&self, source_id: Option<&str>) -> Option<String> {
         match source_id {
             None => None,
@@ -425,6 +1414,33 @@ impl RecordingManager {
                     None
                 }
             }
+            Some(id) if id.starts_with(APP_AUDIO_SOURCE_PREFIX) => {
+                let app_name = &id[APP_AUDIO_SOURCE_PREFIX.len()..];
+
+                #[cfg(target_os = "linux")]
+                {
+                    match crate::capture::linux::audio::start_app_audio_capture(app_name) {
+                        Ok(capture) => {
+                            let node_id = capture.node_id().to_string();
+                            *self.app_audio.lock().unwrap() = Some(capture);
+                            Some(node_id)
+                        }
+                        Err(e) => {
+                            warn!("[Recording] Failed to capture audio of {}: {}", app_name, e);
+                            None
+                        }
+                    }
+                }
+
+                #[cfg(not(target_os = "linux"))]
+                {
+                    warn!(
+                        "[Recording] Ignoring application audio source {} on non-Linux platform",
+                        app_name
+                    );
+                    None
+                }
+            }
             Some(id) => Some(id.to_string()),
         }
     }
@@ -434,15 +1450,55 @@ impl RecordingManager {
     /// When audio is enabled, vtx-engine's `start_capture()` is used as the sole
     /// audio source. The engine's broadcast channel delivers `RawAudioData` events
     /// to the encoding task, which writes them to a named pipe for FFmpeg to mux
-    /// in real-time.
+    /// in real-time. `metadata` is written to the output file as container tags,
+    /// and the video is tagged with the captured display's `color_profile`.
+    /// `pip` overlays a webcam on this recording in place of the configured one.
     async fn start_encoding(
         &self,
         frame_rx: FrameReceiver,
         stop_flag: StopHandle,
+        metadata: Vec<(String, String)>,
+        color_profile: Option<ColorProfile>,
+        pip: Option<PipConfig>,
     ) -> Result<(), String> {
         // Clear any previous transcription segments
         self.clear_transcription_segments();
 
+        let mut options = EncodeOptions {
+            metadata,
+            video_filter: self.video_filter_chain.read().await.clone(),
+            temp_dir: self.temp_recording_dir.read().await.clone(),
+            threads: *self.encoder_threads.read().await,
+            audio_bitrate_kbps: self.audio_config.read().await.effective_bitrate_kbps(),
+            keyframe_interval_secs: *self.keyframe_interval_secs.read().await,
+            scaling: *self.dynamic_scaling.read().await,
+            suspended: self.suspended.clone(),
+            output_dir: self.output_dir.read().await.clone(),
+            create_output_dir: *self.create_output_dir.read().await,
+            pip: match pip {
+                Some(pip) => Some(pip),
+                None => self.pip.read().await.clone(),
+            },
+            color_profile,
+            frame_width: self.frame_width.clone(),
+            load_shedding: self.load_shedding.clone(),
+            mic_agc_target_dbfs: None,
+        };
+        self.suspended.store(false, Ordering::Relaxed);
+        self.frame_width.store(0, Ordering::Relaxed);
+        self.load_shedding.reset();
+        self.gaps.lock().unwrap().clear();
+
+        // Reset per-recording statistics, keeping what capture setup reported
+        {
+            let mut stats = self.stats.lock().unwrap();
+            *stats = RecordingStats::default();
+            #[cfg(target_os = "linux")]
+            {
+                stats.portal_retries = crate::capture::linux::portal_client::last_request_retries();
+            }
+        }
+
         // Get audio config
         let audio_cfg = self.get_audio_config().await;
         let system_device_id = if audio_cfg.enabled {
@@ -458,6 +1514,11 @@ impl RecordingManager {
         let has_system_audio = system_device_id.is_some();
         let has_microphone = mic_device_id.is_some();
         let audio_enabled = has_system_audio || has_microphone;
+        // The encoder levels the microphone when nothing is mixed with it;
+        // otherwise vtx-engine's AGC does before mixing
+        if has_microphone && !has_system_audio && audio_cfg.agc_enabled {
+            options.mic_agc_target_dbfs = Some(audio_cfg.agc_target_level_dbfs);
+        }
 
         // Get transcription config
         let transcription_cfg = self.get_transcription_config().await;
@@ -489,7 +1550,10 @@ impl RecordingManager {
         // Start encoding task
         let encoding_handle = if audio_enabled {
             // Generate output path upfront so the transcript can use the same base name
-            let video_output_path = match crate::encoder::generate_output_path() {
+            let video_output_path = match crate::encoder::generate_output_path(
+                options.output_dir.as_deref(),
+                options.create_output_dir,
+            ) {
                 Ok(path) => path,
                 Err(e) => {
                     error!("Failed to generate output path: {}", e);
@@ -517,18 +1581,56 @@ impl RecordingManager {
                 system_device_id, mic_device_id, transcription_enabled
             );
 
-            // Start vtx-engine audio capture. This is the sole audio source for
-            // both recording (via RawAudioData events) and transcription.
-            if let Err(e) = self
-                .engine
-                .start_capture(mic_device_id, system_device_id)
-                .await
+            #[cfg(target_os = "windows")]
+            let exclusive_rx = self
+                .start_exclusive_audio(
+                    mic_device_id.as_deref(),
+                    has_system_audio,
+                    transcription_enabled,
+                )
+                .await;
+            #[cfg(not(target_os = "windows"))]
+            let exclusive_rx = None;
+
+            let audio_rx = match exclusive_rx {
+                Some(audio_rx) => audio_rx,
+                None => {
+                    // The encoder's gain stage replaces the engine's for this recording
+                    if options.mic_agc_target_dbfs.is_some() {
+                        self.engine
+                            .set_agc_config(engine_agc_config(&audio_cfg, false));
+                    }
+
+                    // Start vtx-engine audio capture. This is the sole audio source for
+                    // both recording (via RawAudioData events) and transcription.
+                    if let Err(e) = self
+                        .engine
+                        .start_capture(mic_device_id, system_device_id)
+                        .await
+                    {
+                        warn!(
+                            "vtx-engine audio capture failed, recording video only: {}",
+                            e
+                        );
+                        return self.start_video_only(frame_rx, stop_flag, options).await;
+                    }
+
+                    // Subscribe to engine events for the encoding task (audio data)
+                    self.engine.subscribe()
+                }
+            };
+
+            // Play the captured audio back if monitoring is enabled
+            #[cfg(target_os = "macos")]
             {
-                warn!(
-                    "vtx-engine audio capture failed, recording video only: {}",
-                    e
-                );
-                return self.start_video_only(frame_rx, stop_flag).await;
+                let audio_config = self.audio_config.read().await;
+                if audio_config.audio_monitor_enabled {
+                    *self.audio_monitor.lock().unwrap() =
+                        Some(crate::capture::macos::audio::AudioMonitor::start(
+                            self.engine.subscribe(),
+                            audio_config.monitor_volume,
+                        ));
+                }
             }
 
             // Capture the wall-clock time when audio capture started.
@@ -538,9 +1640,6 @@ impl RecordingManager {
             // carry their own SystemTime timestamps from the capture callback).
             let audio_capture_start = std::time::SystemTime::now();
 
-            // Subscribe to engine events for the encoding task (audio data)
-            let audio_rx = self.engine.subscribe();
-
             // Start transcription if enabled
             if transcription_enabled {
                 self.start_engine_recording(video_output_path.clone()).await;
@@ -557,12 +1656,13 @@ impl RecordingManager {
                     stop_flag.clone(),
                     Some(video_output_path),
                     Some(audio_capture_start),
+                    options,
                 )
             })
         } else {
             info!("Starting video-only recording");
             tokio::task::spawn_blocking(move || {
-                encode_frames(frame_rx, None, stop_flag.clone(), None, None)
+                encode_frames(frame_rx, None, stop_flag.clone(), None, None, options)
             })
         };
 
@@ -576,6 +1676,7 @@ impl RecordingManager {
             let mut start = self.recording_start.lock().await;
             *start = Some(Instant::now());
         }
+        self.recording_number.fetch_add(1, Ordering::Relaxed);
 
         // Update state
         self.set_state(RecordingState::Recording).await;
@@ -587,14 +1688,55 @@ impl RecordingManager {
         Ok(())
     }
 
+    /// Start WASAPI exclusive-mode microphone capture, if enabled.
+    ///
+    /// Exclusive capture bypasses vtx-engine, so it is only used when a
+    /// microphone is recorded on its own: loopback capture of system audio is
+    /// shared-mode only, and transcription needs the engine. Returns `None`
+    /// when the engine should capture instead.
+    #[cfg(target_os = "windows")]
+    async fn start_exclusive_audio(
+        &self,
+        mic_device_id: Option<&str>,
+        has_system_audio: bool,
+        transcription_enabled: bool,
+    ) -> Option<broadcast::Receiver<EngineEvent>> {
+        if !*self.audio_exclusive_mode.read().await {
+            return None;
+        }
+        let mic_device_id = mic_device_id?;
+        if has_system_audio || transcription_enabled {
+            info!(
+                "[Recording] Exclusive mode needs a microphone without system audio or transcription, using shared mode"
+            );
+            return None;
+        }
+
+        match crate::capture::windows::exclusive_audio::start_exclusive_capture(mic_device_id) {
+            Ok(capture) => {
+                let audio_rx = capture.subscribe();
+                *self.exclusive_audio.lock().unwrap() = Some(capture);
+                Some(audio_rx)
+            }
+            Err(e) => {
+                warn!(
+                    "[Recording] Exclusive capture failed, using shared mode: {}",
+                    e
+                );
+                None
+            }
+        }
+    }
+
     /// Start video-only recording (fallback when audio capture fails).
     async fn start_video_only(
         &self,
         frame_rx: FrameReceiver,
         stop_flag: StopHandle,
+        options: EncodeOptions,
     ) -> Result<(), String> {
         let encoding_handle = tokio::task::spawn_blocking(move || {
-            encode_frames(frame_rx, None, stop_flag.clone(), None, None)
+            encode_frames(frame_rx, None, stop_flag.clone(), None, None, options)
         });
 
         {
@@ -606,6 +1748,7 @@ impl RecordingManager {
             let mut start = self.recording_start.lock().await;
             *start = Some(Instant::now());
         }
+        self.recording_number.fetch_add(1, Ordering::Relaxed);
 
         self.set_state(RecordingState::Recording).await;
         self.start_elapsed_broadcast().await;
@@ -623,6 +1766,15 @@ impl RecordingManager {
         let event_tx = self.event_tx.clone();
         let segments_storage = self.transcription_segments.clone();
         let session_start = std::time::Instant::now();
+        let lang = Some(
+            self.transcription_config
+                .read()
+                .await
+                .language
+                .clone()
+                .unwrap_or_else(|| "en".to_string()),
+        )
+        .filter(|lang| lang != "auto");
 
         // Subscribe to engine events BEFORE starting recording so we don't miss any events.
         let mut engine_rx = engine.subscribe();
@@ -641,7 +1793,7 @@ impl RecordingManager {
         // Spawn the engine event subscriber task.
         let handle = tokio::spawn(async move {
             // Create the transcript file.
-            let mut writer = match TranscriptWriter::new(&transcript_path) {
+            let mut writer = match TranscriptWriter::new(&transcript_path, lang) {
                 Ok(w) => Some(w),
                 Err(e) => {
                     error!(
@@ -749,18 +1901,20 @@ impl RecordingManager {
     /// Start broadcasting elapsed time updates.
     async fn start_elapsed_broadcast(&self) {
         let event_tx = self.event_tx.clone();
-        let recording_start = *self.recording_start.lock().await;
         let stop_flag = self.stop_flag.lock().await.clone();
 
-        if let (Some(start), Some(flag)) = (recording_start, stop_flag) {
+        if let Some(flag) = stop_flag {
             let handle = tokio::spawn(async move {
                 loop {
                     tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
                     if flag.load(Ordering::Relaxed) {
                         break;
                     }
-                    let elapsed = start.elapsed().as_secs();
+                    // Read through the manager, which leaves out suspensions
+                    let manager = get_recording_manager();
+                    let elapsed = manager.get_elapsed_seconds().await;
                     let _ = event_tx.send(ServiceEvent::ElapsedTime(elapsed));
+                    let _ = event_tx.send(ServiceEvent::Stats(manager.get_stats()));
                 }
             });
 
@@ -771,16 +1925,38 @@ impl RecordingManager {
 
     /// Stop the current recording and save the file.
     pub async fn stop_recording(&self) -> Result<RecordingResult, String> {
+        // Checked and set at once, so only one of several concurrent stops
+        // goes on to save the recording
         {
-            let state = self.state.read().await;
-            if *state != RecordingState::Recording {
+            let mut state = self.state.write().await;
+            if !matches!(
+                *state,
+                RecordingState::Recording | RecordingState::Suspended
+            ) {
                 return Err("Not currently recording".to_string());
             }
+            *state = RecordingState::Saving;
+        }
+        self.broadcast(ServiceEvent::StateChanged(RecordingState::Saving));
+
+        let duration_secs = self.get_elapsed_seconds().await;
+        let format = planned_format(
+            self.recording_format.write().await.take(),
+            *self.smart_format.read().await,
+        )
+        .unwrap_or_else(|| {
+            select_smart_format(&RecordTarget {
+                duration_secs,
+                width: self.frame_width.load(Ordering::Relaxed),
+            })
+        });
+        // A recording stopped while suspended ends at the suspension
+        if let Some(suspension) = self.suspension.lock().unwrap().take() {
+            self.gaps.lock().unwrap().push(RecordingGap {
+                at_secs: suspension.at_secs,
+                duration_secs: suspension.gap(SystemTime::now()).as_secs_f64(),
+            });
         }
-
-        let format = self.get_output_format().await;
-
-        self.set_state(RecordingState::Saving).await;
 
         // Stop elapsed time broadcasting
         {
@@ -797,6 +1973,15 @@ impl RecordingManager {
                 stop_flag.store(true, Ordering::Relaxed);
             }
         }
+        // The frame rate limit was for this recording only
+        crate::capture::frame_limit::set_max_fps(None);
+
+        // Stop audio playback right away rather than after finalizing, so the
+        // monitor cannot feed back into a microphone still being captured
+        #[cfg(target_os = "macos")]
+        {
+            self.audio_monitor.lock().unwrap().take();
+        }
 
         // Stop vtx-engine recording — this submits the accumulated audio buffer
         // for transcription. TranscriptionSegment events will follow asynchronously.
@@ -827,13 +2012,24 @@ impl RecordingManager {
             }
         };
 
-        // Transcode if needed
+        // Transcode and trim silence if needed
+        let silence_threshold_db = *self.silence_trim_threshold_db.read().await;
+        let threads = *self.encoder_threads.read().await;
+        let audio_bitrate_kbps = self.audio_config.read().await.effective_bitrate_kbps();
+        let keyframe_interval_secs = *self.keyframe_interval_secs.read().await;
         let file_path = if format != OutputFormat::Mp4 {
             self.broadcast(ServiceEvent::TranscodingStarted {
                 format: format!("{:?}", format),
             });
 
-            match crate::encoder::transcode_video(&source_path, format) {
+            match crate::encoder::transcode_video(
+                &source_path,
+                format,
+                silence_threshold_db,
+                threads,
+                audio_bitrate_kbps,
+                keyframe_interval_secs,
+            ) {
                 Ok(transcoded_path) => {
                     self.broadcast(ServiceEvent::TranscodingComplete {
                         success: true,
@@ -850,23 +2046,60 @@ impl RecordingManager {
                     source_path.clone()
                 }
             }
+        } else if silence_threshold_db.is_some() {
+            crate::encoder::transcode_video(
+                &source_path,
+                format,
+                silence_threshold_db,
+                threads,
+                audio_bitrate_kbps,
+                keyframe_interval_secs,
+            )
+            .unwrap_or_else(|_| source_path.clone())
         } else {
             source_path.clone()
         };
 
+        // Checksum the final file, so transcoding and trimming are covered
+        self.write_checksum(&file_path).await;
+        self.write_gaps(&file_path);
+
+        self.record_history(&file_path, format, duration_secs);
+
         // Clean up and reset to idle
         self.cleanup().await;
 
         info!("Recording saved: {}", file_path.display());
 
+        let copied_to_clipboard = self.copy_path_to_clipboard(&file_path).await;
+
+        if *self.open_output_dir.read().await {
+            if let Some(dir) = file_path.parent() {
+                if let Err(e) = tauri_plugin_opener::open_path(dir, None::<&str>) {
+                    warn!("Failed to open output directory: {}", e);
+                }
+            }
+        }
+
+        self.broadcast(ServiceEvent::RecordingSaved {
+            path: file_path.display().to_string(),
+            duration_secs,
+            size_bytes: std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0),
+        });
+
+        self.start_cloud_upload(file_path.clone()).await;
+
         Ok(RecordingResult {
             source_path,
             file_path,
+            copied_to_clipboard,
         })
     }
 
     /// Clean up internal state and reset to idle.
     async fn cleanup(&self) {
+        self.current_source.lock().unwrap().take();
+
         // Restore PTT mode so the next recording session starts from a known state.
         self.engine.set_ptt_mode(true);
 
@@ -877,6 +2110,30 @@ impl RecordingManager {
                 warn!("[Transcription] Engine stop_capture error: {}", e);
             }
         }
+        // Turn the engine's AGC back on if the encoder's replaced it
+        {
+            let audio_config = self.audio_config.read().await;
+            self.engine
+                .set_agc_config(engine_agc_config(&audio_config, audio_config.agc_enabled));
+        }
+
+        // Remove the per-application audio sink, if any
+        #[cfg(target_os = "linux")]
+        {
+            self.app_audio.lock().unwrap().take();
+        }
+
+        // Release the exclusively opened microphone, if any
+        #[cfg(target_os = "windows")]
+        {
+            self.exclusive_audio.lock().unwrap().take();
+        }
+
+        // Stop audio monitoring, if still running
+        #[cfg(target_os = "macos")]
+        {
+            self.audio_monitor.lock().unwrap().take();
+        }
 
         // Wait for the engine event subscriber task to finish (ensures transcript is fully written).
         {
@@ -894,9 +2151,47 @@ impl RecordingManager {
             let mut start = self.recording_start.lock().await;
             *start = None;
         }
+        self.suspension.lock().unwrap().take();
+        self.suspended.store(false, Ordering::Relaxed);
         self.set_state(RecordingState::Idle).await;
     }
 
+    /// Stop the recording after its capture source was lost for good.
+    ///
+    /// Called by capture backends once reconnection attempts are exhausted.
+    pub async fn handle_capture_disconnected(&self, reason: String) {
+        warn!("[Recording] Capture disconnected: {}", reason);
+        let path = if matches!(
+            self.get_state().await,
+            RecordingState::Recording | RecordingState::Suspended
+        ) {
+            match self.stop_recording().await {
+                Ok(result) => Some(result.file_path.display().to_string()),
+                Err(e) => {
+                    error!("[Recording] Failed to stop after disconnect: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        self.broadcast(ServiceEvent::CaptureDisconnected { reason, path });
+    }
+
+    /// Broadcast that the monitor configuration changed.
+    pub fn notify_monitor_config_changed(&self, version: u64) {
+        self.broadcast(ServiceEvent::MonitorConfigChanged { version });
+    }
+
+    /// Broadcast that video memory is running low during a capture.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub fn notify_gpu_memory_low(&self, available_mb: u64, threshold_mb: u64) {
+        self.broadcast(ServiceEvent::GpuMemoryWarning {
+            available_mb,
+            threshold_mb,
+        });
+    }
+
     /// Broadcast shutdown event to all subscribers.
     pub fn shutdown(&self) {
         if self.engine.is_recording() {
@@ -907,6 +2202,14 @@ impl RecordingManager {
     }
 }
 
+/// Display name of a monitor, for the history log.
+fn monitor_name(monitor_id: &str) -> Option<String> {
+    crate::capture::list_monitors()
+        .into_iter()
+        .find(|m| m.id == monitor_id)
+        .map(|m| m.name)
+}
+
 impl Default for RecordingManager {
     fn default() -> Self {
         Self::new(vtx_engine::WhisperModel::MediumEn)
@@ -939,3 +2242,44 @@ pub fn get_recording_manager() -> &'static RecordingManager {
         RecordingManager::new(model)
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_planned_format() {
+        assert_eq!(
+            planned_format(Some(OutputFormat::Gif), true),
+            Some(OutputFormat::Gif)
+        );
+        assert_eq!(planned_format(None, false), Some(OutputFormat::default()));
+        assert_eq!(planned_format(None, true), None);
+    }
+
+    #[test]
+    fn test_suspension_gap_uses_wall_clock() {
+        // Instant stops during sleep: an hour asleep shows up only on the
+        // wall clock
+        let suspension = Suspension {
+            since: Instant::now(),
+            since_wall: SystemTime::UNIX_EPOCH + Duration::from_secs(1_000),
+            at_secs: 42,
+        };
+        let woke_at = SystemTime::UNIX_EPOCH + Duration::from_secs(4_600);
+        let gap = suspension.gap(woke_at);
+        assert!(gap >= Duration::from_secs(3_600));
+        assert!(gap < Duration::from_secs(3_601));
+    }
+
+    #[test]
+    fn test_suspension_gap_with_clock_set_back() {
+        let suspension = Suspension {
+            since: Instant::now() - Duration::from_secs(5),
+            since_wall: SystemTime::UNIX_EPOCH + Duration::from_secs(1_000),
+            at_secs: 0,
+        };
+        let gap = suspension.gap(SystemTime::UNIX_EPOCH + Duration::from_secs(500));
+        assert!(gap >= Duration::from_secs(5));
+    }
+}
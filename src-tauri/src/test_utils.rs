@@ -0,0 +1,121 @@
+//! Helpers shared by the unit tests.
+//!
+//! [`generate_test_frame`] stands in for a capture backend: its frames are
+//! deterministic, differ from one index to the next, and have detail in
+//! every direction, so scaling, cropping and encoding have something to act
+//! on. [`temp_dir`] gives each test its own scratch directory.
+
+use crate::capture::types::{timestamp_us, PixelFormat};
+use crate::capture::CapturedFrame;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime};
+
+/// Degrees the hue moves with each frame index.
+const HUE_STEP: u32 = 36;
+
+/// A BGRA frame with a gradient: the hue runs around the color wheel from
+/// left to right, starting where `frame_index` puts it, and the brightness
+/// falls from top to bottom.
+pub fn generate_test_frame(width: u32, height: u32, frame_index: u32) -> CapturedFrame {
+    let mut data = Vec::with_capacity(PixelFormat::Bgra.frame_size(width, height));
+    for y in 0..height {
+        let value = 255 - (y * 128 / height.max(1)) as u8;
+        for x in 0..width {
+            let hue = (x * 360 / width.max(1) + frame_index * HUE_STEP) % 360;
+            let [r, g, b] = hue_to_rgb(hue, value);
+            data.extend_from_slice(&[b, g, r, 255]);
+        }
+    }
+    CapturedFrame {
+        width,
+        height,
+        data: data.into(),
+        pixel_format: PixelFormat::Bgra,
+        captured_at: SystemTime::now(),
+        timestamp_us: timestamp_us(Instant::now()),
+    }
+}
+
+/// Fully saturated color of `hue` (degrees) at brightness `value`.
+fn hue_to_rgb(hue: u32, value: u8) -> [u8; 3] {
+    let value = value as u32;
+    let rising = (value * (hue % 60) / 60) as u8;
+    let falling = value as u8 - rising;
+    let value = value as u8;
+    match hue / 60 {
+        0 => [value, rising, 0],
+        1 => [falling, value, 0],
+        2 => [0, value, rising],
+        3 => [0, falling, value],
+        4 => [rising, 0, value],
+        _ => [value, 0, falling],
+    }
+}
+
+/// Assert that two frames have the same size and format, and that no byte
+/// of their data differs by more than `max_delta`.
+#[track_caller]
+pub fn assert_frame_equals(a: &CapturedFrame, b: &CapturedFrame, max_delta: u8) {
+    assert_eq!(
+        (a.width, a.height, a.pixel_format),
+        (b.width, b.height, b.pixel_format),
+        "frames differ in size or format"
+    );
+    assert_eq!(a.data.len(), b.data.len(), "frames differ in data length");
+    if let Some((offset, (x, y))) = a
+        .data
+        .iter()
+        .zip(b.data.iter())
+        .enumerate()
+        .find(|(_, (x, y))| x.abs_diff(**y) > max_delta)
+    {
+        panic!(
+            "frames differ at byte {}: {} vs {} (max delta {})",
+            offset, x, y, max_delta
+        );
+    }
+}
+
+/// An empty directory under the system temp dir, named after `name` and the
+/// process id so parallel test runs don't share it.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("omnirec-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_are_deterministic_and_distinct() {
+        let frame = generate_test_frame(64, 36, 3);
+        assert_eq!(frame.data.len(), PixelFormat::Bgra.frame_size(64, 36));
+        assert_frame_equals(&frame, &generate_test_frame(64, 36, 3), 0);
+        assert_ne!(*frame.data, *generate_test_frame(64, 36, 4).data);
+    }
+
+    #[test]
+    fn test_hue_wheel() {
+        assert_eq!(hue_to_rgb(0, 255), [255, 0, 0]);
+        assert_eq!(hue_to_rgb(120, 255), [0, 255, 0]);
+        assert_eq!(hue_to_rgb(240, 255), [0, 0, 255]);
+        assert_eq!(hue_to_rgb(60, 200), [200, 200, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "frames differ at byte")]
+    fn test_assert_frame_equals_tolerance() {
+        let a = generate_test_frame(8, 8, 0);
+        let mut data = a.data.to_vec();
+        data[5] = data[5].wrapping_add(3);
+        let b = CapturedFrame {
+            data: data.into(),
+            ..a.clone()
+        };
+        assert_frame_equals(&a, &b, 3);
+        assert_frame_equals(&a, &b, 2);
+    }
+}
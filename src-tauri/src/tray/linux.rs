@@ -212,9 +212,9 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     // Track recording state
     let is_recording = Arc::new(AtomicBool::new(false));
 
-    // Get current always-on-top state from AppState (avoids redundant config load)
+    // Get current always-on-top state from the managed config (avoids redundant config load)
     let initial_always_on_top = app
-        .try_state::<crate::AppState>()
+        .try_state::<crate::AppConfigState>()
         .and_then(|s| s.app_config.try_lock().ok().map(|g| g.always_on_top))
         .unwrap_or(false);
 
@@ -248,6 +248,7 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         initial_always_on_top,
         None::<&str>,
     )?;
+    let recent_submenu = super::build_recent_submenu(app)?;
     let configuration = MenuItem::with_id(
         app,
         menu_ids::CONFIGURATION,
@@ -274,6 +275,7 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
             &stop_item,
             &transcription_item,
             &always_on_top_item,
+            &recent_submenu,
             &configuration,
             &logs,
             &about,
@@ -304,6 +306,9 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
                 tracing::debug!("[Tray] Always on Top clicked");
                 super::toggle_always_on_top(app);
             }
+            id if id.starts_with(menu_ids::RECENT_PREFIX) => {
+                super::open_recent_recording(id);
+            }
             id if id == menu_ids::CONFIGURATION => {
                 tracing::debug!("[Tray] Configuration clicked - opening config window");
                 super::open_config_window(app);
@@ -341,6 +346,7 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         tray: std::sync::Mutex::new(tray),
         is_recording,
         always_on_top_item: std::sync::Mutex::new(Some(always_on_top_item)),
+        recent_submenu: std::sync::Mutex::new(Some(recent_submenu)),
     });
 
     // Hide main window on portal-mode desktops (start with tray only)
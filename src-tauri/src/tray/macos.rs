@@ -141,9 +141,9 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     // Track recording state
     let is_recording = Arc::new(AtomicBool::new(false));
 
-    // Get current always-on-top state from AppState (avoids redundant config load)
+    // Get current always-on-top state from the managed config (avoids redundant config load)
     let initial_always_on_top = app
-        .try_state::<crate::AppState>()
+        .try_state::<crate::AppConfigState>()
         .and_then(|s| s.app_config.try_lock().ok().map(|g| g.always_on_top))
         .unwrap_or(false);
 
@@ -178,6 +178,7 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         initial_always_on_top,
         None::<&str>,
     )?;
+    let recent_submenu = super::build_recent_submenu(app)?;
     let configuration = MenuItem::with_id(
         app,
         menu_ids::CONFIGURATION,
@@ -204,6 +205,7 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
             &stop_item,
             &transcription_item,
             &always_on_top_item,
+            &recent_submenu,
             &configuration,
             &logs,
             &about,
@@ -228,6 +230,7 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         tray: std::sync::Mutex::new(tray),
         is_recording,
         always_on_top_item: std::sync::Mutex::new(Some(always_on_top_item)),
+        recent_submenu: std::sync::Mutex::new(Some(recent_submenu)),
     });
 
     tracing::debug!("[Tray] macOS menu bar setup complete");
@@ -309,6 +312,8 @@ pub fn handle_menu_event(app: &tauri::AppHandle, event: &tauri::menu::MenuEvent)
     } else if id == menu_ids::ALWAYS_ON_TOP {
         tracing::debug!("[Tray] Always on Top clicked");
         super::toggle_always_on_top(app);
+    } else if id.starts_with(menu_ids::RECENT_PREFIX) {
+        super::open_recent_recording(id);
     } else if id == menu_ids::CONFIGURATION {
         tracing::debug!("[Tray] Configuration clicked - opening config window");
         super::open_config_window(app);
@@ -398,6 +403,40 @@ pub fn set_recording_state(app: &tauri::AppHandle, recording: bool) {
     }
 }
 
+/// Show the countdown next to the menu bar icon and pulse the icon.
+pub fn set_countdown_state(app: &tauri::AppHandle, remaining: u8) {
+    if let Some(tray_state) = app.try_state::<TrayState>() {
+        if let Ok(tray) = tray_state.tray.lock() {
+            let title = (remaining > 0).then(|| remaining.to_string());
+            if let Err(e) = tray.set_title(title.as_deref()) {
+                tracing::debug!("[Tray] Failed to update title: {:?}", e);
+            }
+        }
+    }
+
+    if remaining == 0 {
+        set_recording_state(app, false);
+        return;
+    }
+
+    if let Some(tray_state) = app.try_state::<TrayState>() {
+        if let Ok(tray) = tray_state.tray.lock() {
+            let pulse = remaining % 2 == 1;
+            let icon = if pulse {
+                load_recording_tray_icon_from_handle(app)
+            } else {
+                load_normal_tray_icon_from_handle(app)
+            };
+            if let Err(e) = tray.set_icon(Some(icon)) {
+                tracing::debug!("[Tray] Failed to update icon: {:?}", e);
+            }
+            if let Err(e) = tray.set_icon_as_template(!pulse) {
+                tracing::debug!("[Tray] Failed to set icon_as_template: {:?}", e);
+            }
+        }
+    }
+}
+
 /// Load normal icon using AppHandle (for runtime updates).
 fn load_normal_tray_icon_from_handle(app: &tauri::AppHandle) -> Image<'static> {
     load_tray_icon_from_paths(app.path().resource_dir().ok(), icon_names::TEMPLATE_2X)
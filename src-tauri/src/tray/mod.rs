@@ -29,6 +29,10 @@ pub mod menu_ids {
     pub const STOP: &str = "stop";
     pub const TRANSCRIPTION: &str = "transcription";
     pub const ALWAYS_ON_TOP: &str = "always_on_top";
+    pub const RECENT_RECORDINGS: &str = "recent_recordings";
+    /// Prefix of Recent Recordings entries; the rest of the ID is the file path.
+    pub const RECENT_PREFIX: &str = "recent:";
+    pub const NO_RECENT_RECORDINGS: &str = "no_recent_recordings";
     pub const CONFIGURATION: &str = "configuration";
     pub const LOGS: &str = "logs";
     pub const ABOUT: &str = "about";
@@ -42,6 +46,8 @@ pub mod menu_labels {
     pub const STOP: &str = "Stop Recording";
     pub const TRANSCRIPTION: &str = "Transcription";
     pub const ALWAYS_ON_TOP: &str = "Always on Top";
+    pub const RECENT_RECORDINGS: &str = "Recent Recordings";
+    pub const NO_RECENT_RECORDINGS: &str = "No Recent Recordings";
     pub const CONFIGURATION: &str = "Configuration";
     pub const LOGS: &str = "Logs";
     pub const ABOUT: &str = "About";
@@ -82,9 +88,11 @@ pub mod icon_names {
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
-use tauri::menu::CheckMenuItem;
+use tauri::menu::{CheckMenuItem, MenuItem, Submenu};
 use tauri::tray::TrayIcon;
 
+use crate::history::RecentFile;
+
 // =============================================================================
 // Shared Window Helpers
 // =============================================================================
@@ -103,13 +111,14 @@ pub fn open_config_window(app: &tauri::AppHandle) {
         return;
     }
 
-    let builder = WebviewWindowBuilder::new(app, "config", WebviewUrl::App("src/config.html".into()))
-        .title("OmniRec Settings")
-        .inner_size(450.0, 550.0)
-        .resizable(false)
-        .maximizable(false)
-        .decorations(false)
-        .shadow(true);
+    let builder =
+        WebviewWindowBuilder::new(app, "config", WebviewUrl::App("src/config.html".into()))
+            .title("OmniRec Settings")
+            .inner_size(450.0, 550.0)
+            .resizable(false)
+            .maximizable(false)
+            .decorations(false)
+            .shadow(true);
     #[cfg(target_os = "macos")]
     let builder = builder.transparent(true).hidden_title(true);
     #[cfg(not(target_os = "macos"))]
@@ -193,6 +202,65 @@ pub fn open_log_viewer_window(app: &tauri::AppHandle) {
         Err(e) => tracing::error!("[Tray] Failed to create logs window: {:?}", e),
     }
 }
+
+/// Start the preview stream and show the live recording preview window.
+pub fn open_recording_preview_window(app: &tauri::AppHandle) {
+    use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
+
+    let fps = crate::encoder::preview::DEFAULT_PREVIEW_FPS;
+    if let Err(e) = crate::state::get_recording_manager().start_preview_stream(fps) {
+        tracing::error!("[Tray] Failed to start preview stream: {}", e);
+        return;
+    }
+
+    if let Some(w) = app.get_webview_window("recording-preview") {
+        let _ = w.show();
+        return;
+    }
+
+    let width = crate::encoder::preview::PREVIEW_WIDTH as f64;
+    let height = crate::encoder::preview::PREVIEW_HEIGHT as f64;
+    let builder = WebviewWindowBuilder::new(
+        app,
+        "recording-preview",
+        WebviewUrl::App("src/recording-preview.html".into()),
+    )
+    .title("Recording Preview")
+    .inner_size(width, height)
+    .resizable(false)
+    .maximizable(false)
+    .minimizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .focused(false)
+    // Keep the preview out of display recordings (Windows and macOS)
+    .content_protected(true);
+    #[cfg(target_os = "macos")]
+    let builder = builder.transparent(true).hidden_title(true);
+    #[cfg(not(target_os = "macos"))]
+    let builder = builder.transparent(false);
+
+    match builder.build() {
+        Ok(window) => {
+            crate::configure_macos_window(&window);
+            tracing::debug!("[Tray] Recording preview window created");
+        }
+        Err(e) => tracing::error!("[Tray] Failed to create recording preview window: {:?}", e),
+    }
+}
+
+/// Close the live recording preview window and stop its preview stream.
+///
+/// A stream started over IPC without the window keeps running.
+pub fn close_recording_preview_window(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    if let Some(w) = app.get_webview_window("recording-preview") {
+        crate::state::get_recording_manager().stop_preview_stream();
+        let _ = w.close();
+    }
+}
 
 /// System tray state.
 ///
@@ -206,6 +274,107 @@ pub struct TrayState {
 
     /// The "Always on Top" check menu item handle (for toggling the checkmark).
     pub always_on_top_item: std::sync::Mutex<Option<CheckMenuItem<tauri::Wry>>>,
+
+    /// The "Recent Recordings" submenu (rebuilt after each recording).
+    pub recent_submenu: std::sync::Mutex<Option<Submenu<tauri::Wry>>>,
+}
+
+// =============================================================================
+// Recent Recordings
+// =============================================================================
+
+/// Number of recent recordings listed in the tray menu.
+const RECENT_MENU_ENTRIES: usize = 5;
+
+/// Recent recordings to list in the tray menu, newest first.
+fn recent_recordings<M: tauri::Manager<tauri::Wry>>(manager: &M) -> Vec<RecentFile> {
+    let paths = manager
+        .try_state::<crate::AppConfigState>()
+        .and_then(|s| {
+            s.app_config
+                .try_lock()
+                .ok()
+                .map(|g| g.recent_recordings.clone())
+        })
+        .unwrap_or_else(|| crate::config::load_config().recent_recordings);
+    paths
+        .into_iter()
+        .take(RECENT_MENU_ENTRIES)
+        .map(RecentFile::new)
+        .collect()
+}
+
+/// Fill the Recent Recordings submenu. Files no longer on disk are disabled.
+fn append_recent_items<M: tauri::Manager<tauri::Wry>>(
+    manager: &M,
+    submenu: &Submenu<tauri::Wry>,
+) -> tauri::Result<()> {
+    let recent = recent_recordings(manager);
+    if recent.is_empty() {
+        submenu.append(&MenuItem::with_id(
+            manager,
+            menu_ids::NO_RECENT_RECORDINGS,
+            menu_labels::NO_RECENT_RECORDINGS,
+            false,
+            None::<&str>,
+        )?)?;
+    }
+    for file in recent {
+        submenu.append(&MenuItem::with_id(
+            manager,
+            format!("{}{}", menu_ids::RECENT_PREFIX, file.path),
+            &file.name,
+            file.exists,
+            None::<&str>,
+        )?)?;
+    }
+    Ok(())
+}
+
+/// Build the Recent Recordings submenu for the tray menu.
+pub fn build_recent_submenu(app: &tauri::App) -> tauri::Result<Submenu<tauri::Wry>> {
+    let submenu = Submenu::with_id(
+        app,
+        menu_ids::RECENT_RECORDINGS,
+        menu_labels::RECENT_RECORDINGS,
+        true,
+    )?;
+    append_recent_items(app, &submenu)?;
+    Ok(submenu)
+}
+
+/// Rebuild the Recent Recordings submenu from the config.
+pub fn refresh_recent_recordings(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    let Some(tray_state) = app.try_state::<TrayState>() else {
+        return;
+    };
+    let Ok(guard) = tray_state.recent_submenu.lock() else {
+        return;
+    };
+    let Some(submenu) = guard.as_ref() else {
+        return;
+    };
+
+    let result = (|| {
+        while submenu.remove_at(0)?.is_some() {}
+        append_recent_items(app, submenu)
+    })();
+    if let Err(e) = result {
+        tracing::error!("[Tray] Failed to rebuild recent recordings: {:?}", e);
+    }
+}
+
+/// Open a recording picked from the Recent Recordings submenu.
+pub fn open_recent_recording(id: &str) {
+    let Some(path) = id.strip_prefix(menu_ids::RECENT_PREFIX) else {
+        return;
+    };
+    tracing::debug!("[Tray] Recent recording clicked: {}", path);
+    if let Err(e) = crate::commands::open_recording_file(path.to_string()) {
+        tracing::error!("[Tray] Failed to open recent recording: {}", e);
+    }
 }
 
 /// Toggle the always-on-top window property and persist the new state.
@@ -231,8 +400,8 @@ pub fn toggle_always_on_top(app: &tauri::AppHandle) {
         }
     }
 
-    // Sync the in-memory AppState config so other code sees the update
-    if let Some(state) = app.try_state::<crate::AppState>() {
+    // Sync the in-memory config so other code sees the update
+    if let Some(state) = app.try_state::<crate::AppConfigState>() {
         let config_clone = config.clone();
         let state_config = state.app_config.clone();
         tauri::async_runtime::spawn(async move {
@@ -298,24 +467,69 @@ pub fn set_tray_visible(app: &tauri::AppHandle, visible: bool) {
     macos::set_tray_visible(app, visible)
 }
 
+/// Set the tray icon tooltip.
+///
+/// Used as a fallback where desktop notifications are unavailable.
+pub fn set_tooltip(app: &tauri::AppHandle, tooltip: &str) {
+    use tauri::Manager;
+
+    if let Some(tray_state) = app.try_state::<TrayState>() {
+        if let Ok(tray) = tray_state.tray.lock() {
+            if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+                tracing::debug!("[Tray] Failed to update tooltip: {:?}", e);
+            }
+        }
+    }
+}
+
 /// Update the tray icon to reflect recording state.
 ///
 /// When recording, the icon changes to a red indicator.
 /// When idle, the normal application icon is shown.
-/// This also updates the menu item enabled states.
+/// This also updates the menu item enabled states, and rebuilds the Recent
+/// Recordings submenu when recording stops.
 #[cfg(target_os = "windows")]
 pub fn set_recording_state(app: &tauri::AppHandle, recording: bool) {
-    windows::set_recording_state(app, recording)
+    windows::set_recording_state(app, recording);
+    if !recording {
+        refresh_recent_recordings(app);
+    }
 }
 
 #[cfg(target_os = "macos")]
 pub fn set_recording_state(app: &tauri::AppHandle, recording: bool) {
-    macos::set_recording_state(app, recording)
+    macos::set_recording_state(app, recording);
+    if !recording {
+        refresh_recent_recordings(app);
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn set_recording_state(app: &tauri::AppHandle, recording: bool) {
+    // The icon is left alone on Linux - GNOME's system indicator is used
+    // during recording
+    if !recording {
+        refresh_recent_recordings(app);
+    }
+}
+
+/// Pulse the tray icon during a pre-recording countdown.
+///
+/// Alternates between the recording and normal icons each second and shows
+/// the seconds left in the tooltip. `remaining == 0` restores the idle icon.
+#[cfg(target_os = "windows")]
+pub fn set_countdown_state(app: &tauri::AppHandle, remaining: u8) {
+    windows::set_countdown_state(app, remaining)
+}
+
+#[cfg(target_os = "macos")]
+pub fn set_countdown_state(app: &tauri::AppHandle, remaining: u8) {
+    macos::set_countdown_state(app, remaining)
 }
 
 #[cfg(target_os = "linux")]
-pub fn set_recording_state(_app: &tauri::AppHandle, _recording: bool) {
-    // No-op on Linux - GNOME's system indicator is used during recording
+pub fn set_countdown_state(_app: &tauri::AppHandle, _remaining: u8) {
+    // No-op on Linux - the tray icon is not updated while recording either
 }
 
 /// Check if running in portal mode.
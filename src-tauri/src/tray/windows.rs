@@ -201,9 +201,9 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     // Track recording state
     let is_recording = Arc::new(AtomicBool::new(false));
 
-    // Get current always-on-top state from AppState (avoids redundant config load)
+    // Get current always-on-top state from the managed config (avoids redundant config load)
     let initial_always_on_top = app
-        .try_state::<crate::AppState>()
+        .try_state::<crate::AppConfigState>()
         .and_then(|s| s.app_config.try_lock().ok().map(|g| g.always_on_top))
         .unwrap_or(false);
 
@@ -231,6 +231,7 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         initial_always_on_top,
         None::<&str>,
     )?;
+    let recent_submenu = super::build_recent_submenu(app)?;
     let configuration = MenuItem::with_id(
         app,
         menu_ids::CONFIGURATION,
@@ -257,6 +258,7 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
             &stop_item,
             &transcription_item,
             &always_on_top_item,
+            &recent_submenu,
             &configuration,
             &logs,
             &about,
@@ -295,6 +297,9 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
                 tracing::debug!("[Tray] Always on Top clicked");
                 super::toggle_always_on_top(app);
             }
+            id if id.starts_with(menu_ids::RECENT_PREFIX) => {
+                super::open_recent_recording(id);
+            }
             id if id == menu_ids::CONFIGURATION => {
                 tracing::debug!("[Tray] Configuration clicked - opening config window");
                 super::open_config_window(app);
@@ -332,6 +337,7 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         tray: std::sync::Mutex::new(tray),
         is_recording,
         always_on_top_item: std::sync::Mutex::new(Some(always_on_top_item)),
+        recent_submenu: std::sync::Mutex::new(Some(recent_submenu)),
     });
 
     tracing::debug!("[Tray] Windows tray setup complete");
@@ -400,6 +406,31 @@ pub fn set_recording_state(app: &tauri::AppHandle, recording: bool) {
     }
 }
 
+/// Pulse the tray icon during a pre-recording countdown.
+pub fn set_countdown_state(app: &tauri::AppHandle, remaining: u8) {
+    if remaining == 0 {
+        set_recording_state(app, false);
+        return;
+    }
+
+    if let Some(tray_state) = app.try_state::<TrayState>() {
+        if let Ok(tray) = tray_state.tray.lock() {
+            let icon = if remaining % 2 == 1 {
+                load_recording_tray_icon_from_handle(app)
+            } else {
+                load_normal_tray_icon_from_handle(app)
+            };
+            if let Err(e) = tray.set_icon(Some(icon)) {
+                tracing::debug!("[Tray] Failed to update icon: {:?}", e);
+            }
+            let tooltip = format!("OmniRec - Recording in {}...", remaining);
+            if let Err(e) = tray.set_tooltip(Some(&tooltip)) {
+                tracing::debug!("[Tray] Failed to update tooltip: {:?}", e);
+            }
+        }
+    }
+}
+
 /// Load normal icon using AppHandle (for runtime updates).
 fn load_normal_tray_icon_from_handle(app: &tauri::AppHandle) -> Image<'static> {
     load_tray_icon_from_paths(app.path().resource_dir().ok(), icon_names::COLOR_32)
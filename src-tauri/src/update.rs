@@ -0,0 +1,247 @@
+//! Release update check via the GitHub Releases API.
+//!
+//! Complements the signed Tauri updater: this only reports whether a newer
+//! release exists, so it also works for package-managed installs that cannot
+//! self-update. Results are cached for 24 hours to stay well inside GitHub's
+//! unauthenticated rate limit.
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// GitHub API endpoint for the latest published release.
+const RELEASES_URL: &str = "https://api.github.com/repos/omnirec/omnirec/releases/latest";
+
+/// How long a cached check result stays valid.
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Cache file name (in the platform cache directory).
+const CACHE_FILE_NAME: &str = "update-check.json";
+
+/// Request timeout for the GitHub API call.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Result of an update check.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateCheckResult {
+    /// Version of the running application
+    pub current: String,
+    /// Latest published release version
+    pub latest: String,
+    /// Whether `latest` is newer than `current`
+    pub update_available: bool,
+    /// Release notes (Markdown) of the latest release
+    pub release_notes: String,
+    /// Release page URL
+    pub download_url: String,
+}
+
+/// Subset of the GitHub release payload we care about.
+#[derive(Debug, Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    html_url: String,
+}
+
+/// On-disk cache entry.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedCheck {
+    /// Unix timestamp (seconds) of the check
+    checked_at: u64,
+    result: UpdateCheckResult,
+}
+
+impl CachedCheck {
+    /// Whether the entry is still valid at `now` for the running version.
+    fn is_fresh(&self, now: u64, current: &str) -> bool {
+        // A version change (e.g. after updating) invalidates the cache.
+        self.result.current == current
+            && now >= self.checked_at
+            && now - self.checked_at < CACHE_TTL.as_secs()
+    }
+}
+
+/// Check whether a newer release is available.
+///
+/// Uses the cached result if it is less than 24 hours old, unless `force` is set.
+pub async fn check_for_update(force: bool) -> Result<UpdateCheckResult, String> {
+    let current = env!("CARGO_PKG_VERSION");
+    let now = unix_now();
+
+    if !force {
+        if let Some(cached) = read_cache() {
+            if cached.is_fresh(now, current) {
+                debug!("[Update] Using cached update check result");
+                return Ok(cached.result);
+            }
+        }
+    }
+
+    let release = fetch_latest_release().await?;
+    let result = build_result(current, release);
+
+    write_cache(&CachedCheck {
+        checked_at: now,
+        result: result.clone(),
+    });
+
+    Ok(result)
+}
+
+async fn fetch_latest_release() -> Result<GitHubRelease, String> {
+    // GitHub rejects API requests without a User-Agent.
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("OmniRec/", env!("CARGO_PKG_VERSION")))
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| format!("Update check request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Update check failed: HTTP {}",
+            response.status().as_u16()
+        ));
+    }
+
+    response
+        .json::<GitHubRelease>()
+        .await
+        .map_err(|e| format!("Invalid release metadata: {}", e))
+}
+
+fn build_result(current: &str, release: GitHubRelease) -> UpdateCheckResult {
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+    UpdateCheckResult {
+        current: current.to_string(),
+        update_available: is_newer(current, &latest),
+        latest,
+        release_notes: release.body.unwrap_or_default(),
+        download_url: release.html_url,
+    }
+}
+
+/// Whether `latest` is a newer semantic version than `current`.
+///
+/// Unparseable versions are never considered newer.
+fn is_newer(current: &str, latest: &str) -> bool {
+    let parse = |v: &str| semver::Version::parse(v.trim_start_matches('v')).ok();
+    match (parse(current), parse(latest)) {
+        (Some(current), Some(latest)) => latest > current,
+        _ => false,
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn cache_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "omnirec").map(|dirs| dirs.cache_dir().join(CACHE_FILE_NAME))
+}
+
+fn read_cache() -> Option<CachedCheck> {
+    let contents = fs::read_to_string(cache_path()?).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn write_cache(entry: &CachedCheck) {
+    let Some(path) = cache_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    match serde_json::to_string_pretty(entry) {
+        Ok(json) => {
+            if let Err(e) = fs::write(&path, json) {
+                warn!("[Update] Failed to write update cache: {}", e);
+            }
+        }
+        Err(e) => warn!("[Update] Failed to serialize update cache: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(current: &str) -> UpdateCheckResult {
+        UpdateCheckResult {
+            current: current.to_string(),
+            latest: "1.0.0".to_string(),
+            update_available: true,
+            release_notes: String::new(),
+            download_url: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.2.1", "0.3.0"));
+        assert!(is_newer("0.2.1", "v0.2.2"));
+        assert!(!is_newer("0.2.1", "0.2.1"));
+        assert!(!is_newer("0.3.0", "0.2.9"));
+        assert!(!is_newer("0.2.1", "nightly"));
+        // Pre-releases sort before the final release.
+        assert!(is_newer("1.0.0-beta.1", "1.0.0"));
+    }
+
+    #[test]
+    fn test_build_result_from_release_payload() {
+        let release: GitHubRelease = serde_json::from_str(
+            r#"{
+                "tag_name": "v0.3.0",
+                "body": "Bug fixes",
+                "html_url": "https://github.com/omnirec/omnirec/releases/tag/v0.3.0",
+                "draft": false
+            }"#,
+        )
+        .unwrap();
+
+        let result = build_result("0.2.1", release);
+        assert_eq!(result.latest, "0.3.0");
+        assert!(result.update_available);
+        assert_eq!(result.release_notes, "Bug fixes");
+        assert!(result.download_url.ends_with("/v0.3.0"));
+    }
+
+    #[test]
+    fn test_missing_release_notes_default_to_empty() {
+        let release: GitHubRelease =
+            serde_json::from_str(r#"{"tag_name": "0.2.1", "body": null, "html_url": "x"}"#)
+                .unwrap();
+        let result = build_result("0.2.1", release);
+        assert!(!result.update_available);
+        assert_eq!(result.release_notes, "");
+    }
+
+    #[test]
+    fn test_cache_freshness() {
+        let entry = CachedCheck {
+            checked_at: 1_000_000,
+            result: result("0.2.1"),
+        };
+        assert!(entry.is_fresh(1_000_000, "0.2.1"));
+        assert!(entry.is_fresh(1_000_000 + CACHE_TTL.as_secs() - 1, "0.2.1"));
+        assert!(!entry.is_fresh(1_000_000 + CACHE_TTL.as_secs(), "0.2.1"));
+        // Clock went backwards
+        assert!(!entry.is_fresh(999_999, "0.2.1"));
+        // App was updated since the check
+        assert!(!entry.is_fresh(1_000_000, "0.3.0"));
+    }
+}
@@ -0,0 +1,145 @@
+//! Audio level check for the audio settings panel.
+//!
+//! Captures a short clip from one audio source through the audio engine and
+//! reduces it to per-block RMS levels, so users can verify their levels
+//! before starting a recording.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+use vtx_engine::EngineEvent;
+
+/// Longest accepted capture, in milliseconds.
+pub const WAVEFORM_MAX_DURATION_MS: u64 = 3000;
+
+/// Length of the block each level is computed over, in milliseconds.
+pub const WAVEFORM_BLOCK_MS: u64 = 50;
+
+/// Number of levels returned; blocks not covered by the capture are zero.
+pub const WAVEFORM_BAR_COUNT: usize = (WAVEFORM_MAX_DURATION_MS / WAVEFORM_BLOCK_MS) as usize;
+
+/// Poll interval while waiting for audio.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Audio levels of a short capture.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WaveformResult {
+    /// RMS level of each 50 ms block relative to full scale (0.0–1.0)
+    pub levels: Vec<f32>,
+    /// Loudest block level
+    pub peak: f32,
+}
+
+impl WaveformResult {
+    /// Build the result for mono `samples` captured at `sample_rate`.
+    pub fn from_samples(samples: &[f32], sample_rate: u32) -> Self {
+        let levels = block_rms_levels(samples, sample_rate);
+        let peak = levels.iter().copied().fold(0.0, f32::max);
+        Self { levels, peak }
+    }
+}
+
+/// Check a requested capture duration.
+pub fn validate_duration(duration_ms: u64) -> Result<Duration, String> {
+    if duration_ms == 0 || duration_ms > WAVEFORM_MAX_DURATION_MS {
+        return Err(format!(
+            "Waveform duration must be between 1 and {} ms",
+            WAVEFORM_MAX_DURATION_MS
+        ));
+    }
+    Ok(Duration::from_millis(duration_ms))
+}
+
+/// Collect `RawAudioData` samples from the engine for `duration`.
+///
+/// Blocks for the duration; call from a blocking task. Returns the mono
+/// samples and their sample rate (0 if no audio arrived).
+pub fn collect_samples(
+    mut rx: broadcast::Receiver<EngineEvent>,
+    duration: Duration,
+) -> (Vec<f32>, u32) {
+    let deadline = Instant::now() + duration;
+    let mut samples = Vec::new();
+    let mut sample_rate = 0;
+
+    while Instant::now() < deadline {
+        match rx.try_recv() {
+            Ok(EngineEvent::RawAudioData(data)) => {
+                sample_rate = data.sample_rate;
+                samples.extend_from_slice(&data.samples);
+            }
+            Ok(_) => {}
+            Err(TryRecvError::Empty) => std::thread::sleep(POLL_INTERVAL),
+            Err(TryRecvError::Lagged(n)) => {
+                tracing::warn!("[Waveform] Lagged: {} events skipped", n);
+            }
+            Err(TryRecvError::Closed) => break,
+        }
+    }
+
+    (samples, sample_rate)
+}
+
+/// RMS level of each [`WAVEFORM_BLOCK_MS`] block, padded with zeros to
+/// [`WAVEFORM_BAR_COUNT`] values and clamped to 0.0–1.0.
+fn block_rms_levels(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let block_len = (sample_rate as u64 * WAVEFORM_BLOCK_MS / 1000) as usize;
+    let mut levels = vec![0.0; WAVEFORM_BAR_COUNT];
+    if block_len == 0 {
+        return levels;
+    }
+
+    for (level, block) in levels.iter_mut().zip(samples.chunks(block_len)) {
+        let mean_square = block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32;
+        *level = mean_square.sqrt().min(1.0);
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_rms_levels() {
+        // 48 kHz: 2400 samples per block
+        let mut samples = vec![0.5; 2400];
+        samples.extend(std::iter::repeat_n([1.0, -1.0], 1200).flatten());
+        samples.extend(vec![0.0; 100]);
+
+        let levels = block_rms_levels(&samples, 48_000);
+        assert_eq!(levels.len(), WAVEFORM_BAR_COUNT);
+        assert!((levels[0] - 0.5).abs() < 1e-4);
+        assert!((levels[1] - 1.0).abs() < 1e-4);
+        assert_eq!(levels[2], 0.0);
+        assert!(levels[3..].iter().all(|level| *level == 0.0));
+    }
+
+    #[test]
+    fn test_block_rms_levels_ignores_excess_and_missing_audio() {
+        let samples = vec![0.25; 48_000 * 4];
+        let levels = block_rms_levels(&samples, 48_000);
+        assert_eq!(levels.len(), WAVEFORM_BAR_COUNT);
+        assert!(levels.iter().all(|level| (level - 0.25).abs() < 1e-4));
+
+        assert_eq!(block_rms_levels(&[], 0), vec![0.0; WAVEFORM_BAR_COUNT]);
+    }
+
+    #[test]
+    fn test_waveform_result_peak() {
+        let mut samples = vec![0.1; 2400];
+        samples.extend(vec![0.8; 2400]);
+        let result = WaveformResult::from_samples(&samples, 48_000);
+        assert!((result.peak - 0.8).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_validate_duration() {
+        assert!(validate_duration(0).is_err());
+        assert!(validate_duration(WAVEFORM_MAX_DURATION_MS + 1).is_err());
+        assert_eq!(
+            validate_duration(WAVEFORM_MAX_DURATION_MS).unwrap(),
+            Duration::from_millis(3000)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! End-to-end recording through the service: IPC server, recording manager,
+//! encoder and output file, with the synthetic capture backend in place of
+//! the screen.
+//!
+//! Needs FFmpeg and ffprobe on PATH. Run with:
+//!
+//! ```text
+//! cargo test -p omnirec --features integration-tests --test integration -- --ignored
+//! ```
+
+#![cfg(all(unix, feature = "integration-tests"))]
+
+use omnirec_lib::ipc::server::run_server;
+use omnirec_lib::state::get_recording_manager;
+use omnirec_types::ipc::{read_json, write_json, Request, Response, SOCKET_PATH_ENV};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+use tokio::net::UnixStream;
+
+/// Size of the synthetic backend's frames.
+const WIDTH: u32 = 1280;
+const HEIGHT: u32 = 720;
+
+/// Connect to the service once its socket is up.
+async fn connect(socket: &Path) -> UnixStream {
+    for _ in 0..50 {
+        if let Ok(stream) = UnixStream::connect(socket).await {
+            return stream;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("IPC server did not start at {:?}", socket);
+}
+
+async fn send(stream: &mut UnixStream, request: Request) -> Response {
+    write_json(stream, &request).await.unwrap();
+    read_json(stream).await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs FFmpeg and ffprobe on PATH"]
+async fn test_recording_writes_playable_file() {
+    let dir = std::env::temp_dir().join(format!("omnirec-integration-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let socket = dir.join("service.sock");
+    std::env::set_var(SOCKET_PATH_ENV, &socket);
+    get_recording_manager()
+        .set_output_dir(Some(dir.join("recordings")), true)
+        .await;
+    tokio::spawn(async {
+        if let Err(e) = run_server().await {
+            panic!("IPC server failed: {}", e);
+        }
+    });
+
+    let mut client = connect(&socket).await;
+    let started = send(
+        &mut client,
+        Request::StartDisplayCapture {
+            monitor_id: "synthetic".to_string(),
+            width: WIDTH,
+            height: HEIGHT,
+        },
+    )
+    .await;
+    assert!(
+        matches!(started, Response::RecordingStarted),
+        "{:?}",
+        started
+    );
+
+    // The backend sends its 30 frames over one second
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let file_path = match send(&mut client, Request::StopRecording).await {
+        Response::RecordingStopped { file_path, .. } => file_path,
+        other => panic!("Unexpected response to stop: {:?}", other),
+    };
+    let size = std::fs::metadata(&file_path).unwrap().len();
+    assert!(size > 0, "{} is empty", file_path);
+
+    let probe = Command::new("ffprobe")
+        .args(["-v", "error", "-show_streams", "-select_streams", "v:0"])
+        .arg(&file_path)
+        .output()
+        .expect("ffprobe not found");
+    assert!(
+        probe.status.success(),
+        "ffprobe failed: {}",
+        String::from_utf8_lossy(&probe.stderr)
+    );
+    let streams = String::from_utf8_lossy(&probe.stdout);
+    assert!(streams.contains("codec_type=video"), "{}", streams);
+    assert!(streams.contains(&format!("width={}", WIDTH)), "{}", streams);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
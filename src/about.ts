@@ -19,6 +19,15 @@ interface UpdateInfo {
   notes?: string;
 }
 
+/** Payload of the startup `update-available` event (GitHub release check). */
+interface UpdateCheckResult {
+  current: string;
+  latest: string;
+  update_available: boolean;
+  release_notes: string;
+  download_url: string;
+}
+
 interface DownloadProgressPayload {
   chunkLength: number;
   contentLength: number | null;
@@ -179,8 +188,12 @@ window.addEventListener("DOMContentLoaded", async () => {
   });
 
   // Background update-available event (from startup check)
-  await listen<UpdateInfo>("update-available", (event) => {
-    displayUpdateAvailable(event.payload);
+  await listen<UpdateCheckResult>("update-available", (event) => {
+    displayUpdateAvailable({
+      available: event.payload.update_available,
+      version: event.payload.latest,
+      notes: event.payload.release_notes,
+    });
   });
 
   // Tray "Check for Updates" item triggers a check
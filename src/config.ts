@@ -12,6 +12,15 @@ interface AudioSource {
   source_type: "input" | "output";
 }
 
+interface AudioAppInfo {
+  name: string;
+  binary?: string;
+  stream_count: number;
+}
+
+// Prefix of source IDs that select a single application's audio (Linux)
+const APP_AUDIO_SOURCE_PREFIX = "app:";
+
 interface AudioConfig {
   enabled: boolean;
   source_id: string | null;
@@ -19,14 +28,32 @@ interface AudioConfig {
   echo_cancellation: boolean;
   agc_enabled: boolean;
   agc_noise_gate_enabled: boolean;
+  agc_target_level_dbfs: number;
+  audio_monitor_enabled: boolean;
+  monitor_volume: number;
+  audio_bitrate_kbps: number;
 }
 
 interface TranscriptionConfig {
   enabled: boolean;
   model: string;
+  language: string;
   show_transcript_window: boolean;
 }
 
+interface ApprovalTokenInfo {
+  token_id: string;
+  source_type: string;
+  source_id: string;
+  created_at: string;
+  last_used_at: string | null;
+}
+
+interface LanguageInfo {
+  code: string;
+  display_name: string;
+}
+
 interface ModelInfo {
   id: string;
   display_name: string;
@@ -58,9 +85,12 @@ interface DownloadProgress {
 
 type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
 
+type EncoderThreads = "auto" | "single" | { count: number };
+
 interface AppConfig {
   output: {
     directory: string | null;
+    create_if_missing: boolean;
   };
   audio: {
     enabled: boolean;
@@ -69,18 +99,109 @@ interface AppConfig {
     echo_cancellation: boolean;
     agc_enabled: boolean;
     agc_noise_gate_enabled: boolean;
+    agc_target_level_dbfs: number;
   };
+  audio_exclusive_mode: boolean;
+  video_filter_chain: string | null;
+  encoder_threads: EncoderThreads;
+  keyframe_interval_secs: number;
+  trim_silence: boolean;
+  silence_threshold_db: number;
+  temp_recording_dir: string | null;
+  copy_recording_path_to_clipboard: boolean;
+  open_output_dir_after_recording: boolean;
+  smart_format: boolean;
+  show_completion_notification: boolean;
+  notification_include_path: boolean;
+  show_recording_preview: boolean;
+  verify_on_finish: boolean;
+  window_follow: boolean;
+  force_wgc_for_games: boolean;
+  focus_before_capture: boolean;
+  snap_to_window: boolean;
+  hotkeys: {
+    screenshot: string | null;
+  };
+  countdown_secs: number;
+  cloud_upload: CloudUploadConfig | null;
+  pip: PipConfig | null;
   appearance: {
     theme: ThemeMode;
   };
   log_level: LogLevel;
 }
 
+type PipPosition = "top_left" | "top_right" | "bottom_left" | "bottom_right";
+
+interface PipConfig {
+  camera_id: string;
+  position: PipPosition;
+  size_fraction: number;
+}
+
+interface CameraInfo {
+  id: string;
+  name: string;
+}
+
+interface CloudUploadConfig {
+  enabled: boolean;
+  provider: { type: "s3_compatible"; endpoint_url: string };
+  bucket: string;
+  prefix: string;
+  credentials: {
+    access_key_id: string;
+    secret_access_key: string;
+    region: string;
+  };
+}
+
 // DOM elements
 let closeBtn: HTMLButtonElement | null;
 let outputDirInput: HTMLInputElement | null;
 let browseOutputDirBtn: HTMLButtonElement | null;
 let outputDirErrorEl: HTMLElement | null;
+let tempDirInput: HTMLInputElement | null;
+let browseTempDirBtn: HTMLButtonElement | null;
+let tempDirErrorEl: HTMLElement | null;
+let copyPathCheckbox: HTMLInputElement | null;
+let openOutputDirCheckbox: HTMLInputElement | null;
+let smartFormatCheckbox: HTMLInputElement | null;
+let createOutputDirCheckbox: HTMLInputElement | null;
+let completionNotificationCheckbox: HTMLInputElement | null;
+let notificationPathCheckbox: HTMLInputElement | null;
+let recordingPreviewCheckbox: HTMLInputElement | null;
+let verifyOnFinishCheckbox: HTMLInputElement | null;
+let autostartCheckbox: HTMLInputElement | null;
+let windowFollowCheckbox: HTMLInputElement | null;
+let windowFollowConfigItem: HTMLElement | null;
+let forceWgcCheckbox: HTMLInputElement | null;
+let forceWgcConfigItem: HTMLElement | null;
+let focusBeforeCaptureCheckbox: HTMLInputElement | null;
+let focusBeforeCaptureConfigItem: HTMLElement | null;
+let snapToWindowCheckbox: HTMLInputElement | null;
+let countdownInput: HTMLInputElement | null;
+let pipCameraSelect: HTMLSelectElement | null;
+let pipPositionSelect: HTMLSelectElement | null;
+let pipSizeSelect: HTMLSelectElement | null;
+let refreshCamerasBtn: HTMLButtonElement | null;
+let cloudEnabledCheckbox: HTMLInputElement | null;
+let cloudEndpointInput: HTMLInputElement | null;
+let cloudRegionInput: HTMLInputElement | null;
+let cloudBucketInput: HTMLInputElement | null;
+let cloudPrefixInput: HTMLInputElement | null;
+let cloudAccessKeyInput: HTMLInputElement | null;
+let cloudSecretKeyInput: HTMLInputElement | null;
+let cloudErrorEl: HTMLElement | null;
+let cloudTestBtn: HTMLButtonElement | null;
+let cloudTestResultEl: HTMLElement | null;
+let videoFilterInput: HTMLInputElement | null;
+let videoFilterErrorEl: HTMLElement | null;
+let screenshotHotkeyInput: HTMLInputElement | null;
+let screenshotHotkeyErrorEl: HTMLElement | null;
+let trimSilenceCheckbox: HTMLInputElement | null;
+let silenceThresholdSelect: HTMLSelectElement | null;
+let silenceThresholdConfigItem: HTMLElement | null;
 let audioSourceSelect: HTMLSelectElement | null;
 let micSourceSelect: HTMLSelectElement | null;
 let aecCheckbox: HTMLInputElement | null;
@@ -89,6 +210,15 @@ let agcCheckbox: HTMLInputElement | null;
 let agcConfigItem: HTMLElement | null;
 let agcNoiseGateCheckbox: HTMLInputElement | null;
 let agcNoiseGateConfigItem: HTMLElement | null;
+let agcTargetSelect: HTMLSelectElement | null;
+let agcTargetConfigItem: HTMLElement | null;
+let exclusiveModeCheckbox: HTMLInputElement | null;
+let exclusiveModeConfigItem: HTMLElement | null;
+let audioMonitorCheckbox: HTMLInputElement | null;
+let audioMonitorConfigItem: HTMLElement | null;
+let monitorVolumeSelect: HTMLSelectElement | null;
+let monitorVolumeConfigItem: HTMLElement | null;
+let audioBitrateSelect: HTMLSelectElement | null;
 let refreshAudioBtn: HTMLButtonElement | null;
 let themeSelect: HTMLSelectElement | null;
 let macosSystemAudioCheckbox: HTMLInputElement | null;
@@ -106,9 +236,21 @@ let modelCancelBtn: HTMLButtonElement | null;
 let modelProgressContainer: HTMLElement | null;
 let modelProgressFill: HTMLElement | null;
 let modelProgressText: HTMLElement | null;
+let languageConfigItem: HTMLElement | null;
+let languageSelect: HTMLSelectElement | null;
 let showTranscriptConfigItem: HTMLElement | null;
 let showTranscriptCheckbox: HTMLInputElement | null;
 let logLevelSelect: HTMLSelectElement | null;
+let encoderThreadsSelect: HTMLSelectElement | null;
+let keyframeIntervalInput: HTMLInputElement | null;
+let benchmarkBtn: HTMLButtonElement | null;
+let audioLevelBtn: HTMLButtonElement | null;
+let audioLevelResultEl: HTMLElement | null;
+let audioWaveformEl: HTMLElement | null;
+let benchmarkResultEl: HTMLElement | null;
+let exportConfigBtn: HTMLButtonElement | null;
+let importConfigBtn: HTMLButtonElement | null;
+let configTransferResultEl: HTMLElement | null;
 
 // State
 let defaultOutputDir = "";
@@ -132,6 +274,57 @@ window.addEventListener("DOMContentLoaded", async () => {
   outputDirInput = document.querySelector("#output-dir-input");
   browseOutputDirBtn = document.querySelector("#browse-output-dir-btn");
   outputDirErrorEl = document.querySelector("#output-dir-error");
+  tempDirInput = document.querySelector("#temp-dir-input");
+  browseTempDirBtn = document.querySelector("#browse-temp-dir-btn");
+  tempDirErrorEl = document.querySelector("#temp-dir-error");
+  copyPathCheckbox = document.querySelector("#copy-path-checkbox");
+  openOutputDirCheckbox = document.querySelector("#open-output-dir-checkbox");
+  smartFormatCheckbox = document.querySelector("#smart-format-checkbox");
+  createOutputDirCheckbox = document.querySelector("#create-output-dir-checkbox");
+  completionNotificationCheckbox = document.querySelector("#completion-notification-checkbox");
+  notificationPathCheckbox = document.querySelector("#notification-path-checkbox");
+  recordingPreviewCheckbox = document.querySelector("#recording-preview-checkbox");
+  verifyOnFinishCheckbox = document.querySelector("#verify-on-finish-checkbox");
+  autostartCheckbox = document.querySelector("#autostart-checkbox");
+  windowFollowCheckbox = document.querySelector("#window-follow-checkbox");
+  windowFollowConfigItem = document.querySelector("#window-follow-config-item");
+  windowFollowConfigItem?.classList.toggle("hidden", currentPlatform === "linux");
+  forceWgcCheckbox = document.querySelector("#force-wgc-checkbox");
+  forceWgcConfigItem = document.querySelector("#force-wgc-config-item");
+  forceWgcConfigItem?.classList.toggle("hidden", currentPlatform !== "windows");
+  focusBeforeCaptureCheckbox = document.querySelector("#focus-before-capture-checkbox");
+  focusBeforeCaptureConfigItem = document.querySelector("#focus-before-capture-config-item");
+  focusBeforeCaptureConfigItem?.classList.toggle("hidden", currentPlatform !== "linux");
+  snapToWindowCheckbox = document.querySelector("#snap-to-window-checkbox");
+  document
+    .querySelector("#snap-to-window-config-item")
+    ?.classList.toggle("hidden", currentPlatform !== "linux");
+  // The consent picker and its approvals only exist on Linux
+  document
+    .querySelector("#approvals-config-group")
+    ?.classList.toggle("hidden", currentPlatform !== "linux");
+  countdownInput = document.querySelector("#countdown-input");
+  pipCameraSelect = document.querySelector("#pip-camera-select");
+  pipPositionSelect = document.querySelector("#pip-position-select");
+  pipSizeSelect = document.querySelector("#pip-size-select");
+  refreshCamerasBtn = document.querySelector("#refresh-cameras-btn");
+  cloudEnabledCheckbox = document.querySelector("#cloud-enabled-checkbox");
+  cloudEndpointInput = document.querySelector("#cloud-endpoint-input");
+  cloudRegionInput = document.querySelector("#cloud-region-input");
+  cloudBucketInput = document.querySelector("#cloud-bucket-input");
+  cloudPrefixInput = document.querySelector("#cloud-prefix-input");
+  cloudAccessKeyInput = document.querySelector("#cloud-access-key-input");
+  cloudSecretKeyInput = document.querySelector("#cloud-secret-key-input");
+  cloudErrorEl = document.querySelector("#cloud-error");
+  cloudTestBtn = document.querySelector("#cloud-test-btn");
+  cloudTestResultEl = document.querySelector("#cloud-test-result");
+  videoFilterInput = document.querySelector("#video-filter-input");
+  videoFilterErrorEl = document.querySelector("#video-filter-error");
+  screenshotHotkeyInput = document.querySelector("#screenshot-hotkey-input");
+  screenshotHotkeyErrorEl = document.querySelector("#screenshot-hotkey-error");
+  trimSilenceCheckbox = document.querySelector("#trim-silence-checkbox");
+  silenceThresholdSelect = document.querySelector("#silence-threshold-select");
+  silenceThresholdConfigItem = document.querySelector("#silence-threshold-config-item");
   audioSourceSelect = document.querySelector("#audio-source-select");
   micSourceSelect = document.querySelector("#mic-source-select");
   aecCheckbox = document.querySelector("#aec-checkbox");
@@ -140,6 +333,15 @@ window.addEventListener("DOMContentLoaded", async () => {
   agcConfigItem = document.querySelector("#agc-config-item");
   agcNoiseGateCheckbox = document.querySelector("#agc-noise-gate-checkbox");
   agcNoiseGateConfigItem = document.querySelector("#agc-noise-gate-config-item");
+  agcTargetSelect = document.querySelector("#agc-target-select");
+  agcTargetConfigItem = document.querySelector("#agc-target-config-item");
+  exclusiveModeCheckbox = document.querySelector("#exclusive-mode-checkbox");
+  exclusiveModeConfigItem = document.querySelector("#exclusive-mode-config-item");
+  audioMonitorCheckbox = document.querySelector("#audio-monitor-checkbox");
+  audioMonitorConfigItem = document.querySelector("#audio-monitor-config-item");
+  monitorVolumeSelect = document.querySelector("#monitor-volume-select");
+  monitorVolumeConfigItem = document.querySelector("#monitor-volume-config-item");
+  audioBitrateSelect = document.querySelector("#audio-bitrate-select");
   refreshAudioBtn = document.querySelector("#refresh-audio-btn");
   themeSelect = document.querySelector("#theme-select");
   macosSystemAudioCheckbox = document.querySelector("#macos-system-audio-checkbox");
@@ -157,9 +359,21 @@ window.addEventListener("DOMContentLoaded", async () => {
   modelProgressContainer = document.querySelector("#model-progress-container");
   modelProgressFill = document.querySelector("#model-progress-fill");
   modelProgressText = document.querySelector("#model-progress-text");
+  languageConfigItem = document.querySelector("#language-config-item");
+  languageSelect = document.querySelector("#language-select");
   showTranscriptConfigItem = document.querySelector("#show-transcript-config-item");
   showTranscriptCheckbox = document.querySelector("#show-transcript-checkbox");
   logLevelSelect = document.querySelector("#log-level-select");
+  encoderThreadsSelect = document.querySelector("#encoder-threads-select");
+  keyframeIntervalInput = document.querySelector("#keyframe-interval-input");
+  benchmarkBtn = document.querySelector("#benchmark-btn");
+  audioLevelBtn = document.querySelector("#audio-level-btn");
+  audioLevelResultEl = document.querySelector("#audio-level-result");
+  audioWaveformEl = document.querySelector("#audio-waveform");
+  benchmarkResultEl = document.querySelector("#benchmark-result");
+  exportConfigBtn = document.querySelector("#export-config-btn");
+  importConfigBtn = document.querySelector("#import-config-btn");
+  configTransferResultEl = document.querySelector("#config-transfer-result");
 
   // Close button handler
   closeBtn?.addEventListener("click", () => {
@@ -169,12 +383,53 @@ window.addEventListener("DOMContentLoaded", async () => {
   // Config event handlers
   outputDirInput?.addEventListener("input", handleOutputDirInput);
   outputDirInput?.addEventListener("blur", handleOutputDirBlur);
+  tempDirInput?.addEventListener("change", saveTempDirectory);
+  browseTempDirBtn?.addEventListener("click", handleBrowseTempDir);
+  copyPathCheckbox?.addEventListener("change", handleCopyPathChange);
+  openOutputDirCheckbox?.addEventListener("change", handleOpenOutputDirChange);
+  smartFormatCheckbox?.addEventListener("change", handleSmartFormatChange);
+  createOutputDirCheckbox?.addEventListener("change", handleCreateOutputDirChange);
+  completionNotificationCheckbox?.addEventListener("change", handleCompletionNotificationChange);
+  notificationPathCheckbox?.addEventListener("change", handleNotificationPathChange);
+  recordingPreviewCheckbox?.addEventListener("change", handleRecordingPreviewChange);
+  verifyOnFinishCheckbox?.addEventListener("change", handleVerifyOnFinishChange);
+  autostartCheckbox?.addEventListener("change", handleAutostartChange);
+  windowFollowCheckbox?.addEventListener("change", handleWindowFollowChange);
+  forceWgcCheckbox?.addEventListener("change", handleForceWgcChange);
+  focusBeforeCaptureCheckbox?.addEventListener("change", handleFocusBeforeCaptureChange);
+  snapToWindowCheckbox?.addEventListener("change", handleSnapToWindowChange);
+  countdownInput?.addEventListener("change", handleCountdownChange);
+  pipCameraSelect?.addEventListener("change", handlePipConfigChange);
+  pipPositionSelect?.addEventListener("change", handlePipConfigChange);
+  pipSizeSelect?.addEventListener("change", handlePipConfigChange);
+  refreshCamerasBtn?.addEventListener("click", () => loadCameras());
+  for (const input of [
+    cloudEnabledCheckbox,
+    cloudEndpointInput,
+    cloudRegionInput,
+    cloudBucketInput,
+    cloudPrefixInput,
+    cloudAccessKeyInput,
+    cloudSecretKeyInput,
+  ]) {
+    input?.addEventListener("change", handleCloudConfigChange);
+  }
+  cloudTestBtn?.addEventListener("click", handleCloudTestClick);
+  videoFilterInput?.addEventListener("change", handleVideoFilterChange);
+  screenshotHotkeyInput?.addEventListener("change", handleScreenshotHotkeyChange);
+  trimSilenceCheckbox?.addEventListener("change", handleSilenceTrimChange);
+  silenceThresholdSelect?.addEventListener("change", handleSilenceTrimChange);
   browseOutputDirBtn?.addEventListener("click", handleBrowseOutputDir);
   audioSourceSelect?.addEventListener("change", handleAudioConfigChange);
   micSourceSelect?.addEventListener("change", handleAudioConfigChange);
   aecCheckbox?.addEventListener("change", handleAudioConfigChange);
   agcCheckbox?.addEventListener("change", handleAudioConfigChange);
   agcNoiseGateCheckbox?.addEventListener("change", handleAudioConfigChange);
+  agcTargetSelect?.addEventListener("change", handleAudioConfigChange);
+  exclusiveModeCheckbox?.addEventListener("change", handleExclusiveModeChange);
+  audioMonitorCheckbox?.addEventListener("change", handleAudioMonitorChange);
+  monitorVolumeSelect?.addEventListener("change", handleAudioMonitorChange);
+  audioBitrateSelect?.addEventListener("change", handleAudioBitrateChange);
   refreshAudioBtn?.addEventListener("click", loadAudioSources);
   themeSelect?.addEventListener("change", handleThemeChange);
   macosSystemAudioCheckbox?.addEventListener("change", handleMacosSystemAudioChange);
@@ -182,16 +437,33 @@ window.addEventListener("DOMContentLoaded", async () => {
   modelSelect?.addEventListener("change", handleModelChange);
   modelDownloadBtn?.addEventListener("click", handleModelDownload);
   modelCancelBtn?.addEventListener("click", handleModelCancel);
+  languageSelect?.addEventListener("change", handleLanguageChange);
   showTranscriptCheckbox?.addEventListener("change", handleShowTranscriptChange);
   logLevelSelect?.addEventListener("change", handleLogLevelChange);
+  encoderThreadsSelect?.addEventListener("change", handleEncoderThreadsChange);
+  keyframeIntervalInput?.addEventListener("change", handleKeyframeIntervalChange);
+  benchmarkBtn?.addEventListener("click", handleBenchmarkClick);
+  exportConfigBtn?.addEventListener("click", handleExportConfigClick);
+  importConfigBtn?.addEventListener("click", handleImportConfigClick);
+  audioLevelBtn?.addEventListener("click", handleAudioLevelClick);
 
   // Listen for model download progress events
   listen<DownloadProgress>("model-download-progress", (event) => {
     handleDownloadProgress(event.payload);
   });
 
+  // Show settings changed by editing the config file
+  listen("config-reloaded", () => {
+    console.log("[Config] Config file changed, reloading");
+    loadConfig();
+  });
+
   // Load config
   loadConfig();
+  loadAutostartState();
+  if (currentPlatform === "linux") {
+    loadApprovalTokens();
+  }
 });
 
 // =============================================================================
@@ -208,6 +480,85 @@ async function loadConfig(): Promise<void> {
       outputDirInput.value = config.output.directory || "";
     }
 
+    if (createOutputDirCheckbox) {
+      createOutputDirCheckbox.checked = config.output.create_if_missing;
+    }
+
+    if (tempDirInput) {
+      tempDirInput.value = config.temp_recording_dir || "";
+    }
+
+    if (copyPathCheckbox) {
+      copyPathCheckbox.checked = config.copy_recording_path_to_clipboard;
+    }
+
+    if (openOutputDirCheckbox) {
+      openOutputDirCheckbox.checked = config.open_output_dir_after_recording;
+    }
+
+    if (smartFormatCheckbox) {
+      smartFormatCheckbox.checked = config.smart_format;
+    }
+
+    if (completionNotificationCheckbox) {
+      completionNotificationCheckbox.checked = config.show_completion_notification;
+    }
+
+    if (notificationPathCheckbox) {
+      notificationPathCheckbox.checked = config.notification_include_path;
+    }
+
+    if (recordingPreviewCheckbox) {
+      recordingPreviewCheckbox.checked = config.show_recording_preview;
+    }
+
+    if (verifyOnFinishCheckbox) {
+      verifyOnFinishCheckbox.checked = config.verify_on_finish;
+    }
+
+    if (windowFollowCheckbox) {
+      windowFollowCheckbox.checked = config.window_follow;
+    }
+
+    if (forceWgcCheckbox) {
+      forceWgcCheckbox.checked = config.force_wgc_for_games;
+    }
+
+    if (focusBeforeCaptureCheckbox) {
+      focusBeforeCaptureCheckbox.checked = config.focus_before_capture;
+    }
+
+    if (snapToWindowCheckbox) {
+      snapToWindowCheckbox.checked = config.snap_to_window;
+    }
+
+    if (countdownInput) {
+      countdownInput.value = String(config.countdown_secs);
+    }
+
+    loadCloudConfig(config.cloud_upload);
+    loadPipConfig(config.pip);
+
+    if (videoFilterInput) {
+      videoFilterInput.value = config.video_filter_chain || "";
+    }
+
+    if (screenshotHotkeyInput) {
+      screenshotHotkeyInput.value = config.hotkeys.screenshot || "";
+    }
+
+    if (trimSilenceCheckbox) {
+      trimSilenceCheckbox.checked = config.trim_silence;
+    }
+
+    if (silenceThresholdSelect) {
+      silenceThresholdSelect.value = String(config.silence_threshold_db);
+      if (silenceThresholdSelect.value === "") {
+        silenceThresholdSelect.value = "-40";
+      }
+    }
+    updateSilenceThresholdVisibility();
+
     const themeMode = config.appearance?.theme || "auto";
     initTheme(themeMode);
 
@@ -220,6 +571,26 @@ async function loadConfig(): Promise<void> {
       logLevelSelect.value = logLevel;
     }
 
+    if (encoderThreadsSelect) {
+      encoderThreadsSelect.value = encoderThreadsValue(config.encoder_threads ?? "auto");
+      if (encoderThreadsSelect.value === "") {
+        // A custom count from the config file: show it as an extra option
+        const option = document.createElement("option");
+        option.value = encoderThreadsValue(config.encoder_threads);
+        option.textContent = option.value === "1" ? "1 thread" : `${option.value} threads`;
+        encoderThreadsSelect.append(option);
+        encoderThreadsSelect.value = option.value;
+      }
+    }
+
+    if (keyframeIntervalInput) {
+      keyframeIntervalInput.value = String(config.keyframe_interval_secs ?? 2);
+    }
+
+    if (exclusiveModeCheckbox) {
+      exclusiveModeCheckbox.checked = config.audio_exclusive_mode ?? false;
+    }
+
     console.log("[Config] Loaded config, default dir:", defaultOutputDir, ", theme:", themeMode, ", log level:", logLevel);
 
     await loadAudioSources();
@@ -287,6 +658,444 @@ function clearOutputDirError(): void {
   outputDirInput?.classList.remove("has-error");
 }
 
+async function saveTempDirectory(): Promise<void> {
+  if (!tempDirInput) return;
+  const directory = tempDirInput.value.trim();
+  try {
+    await invoke("save_temp_recording_dir", { directory: directory || null });
+    setTempDirError(null);
+    console.log("[Config] Saved temporary directory:", directory || "(none)");
+  } catch (error) {
+    setTempDirError(String(error));
+    console.error("[Config] Failed to save temporary directory:", error);
+  }
+}
+
+async function handleBrowseTempDir(): Promise<void> {
+  try {
+    const selectedPath = await invoke<string | null>("pick_temp_directory");
+    if (selectedPath && tempDirInput) {
+      tempDirInput.value = selectedPath;
+      await saveTempDirectory();
+    }
+  } catch (error) {
+    console.error("[Config] Failed to pick temporary directory:", error);
+  }
+}
+
+function setTempDirError(message: string | null): void {
+  if (tempDirErrorEl) {
+    tempDirErrorEl.textContent = message ?? "";
+    tempDirErrorEl.classList.toggle("hidden", message === null);
+  }
+  tempDirInput?.classList.toggle("has-error", message !== null);
+}
+
+async function handleCopyPathChange(): Promise<void> {
+  if (!copyPathCheckbox) return;
+  const enabled = copyPathCheckbox.checked;
+
+  try {
+    await invoke("save_copy_recording_path_to_clipboard", { enabled });
+    console.log("[Config] Saved copy path to clipboard:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save copy path to clipboard:", error);
+  }
+}
+
+async function handleCreateOutputDirChange(): Promise<void> {
+  if (!createOutputDirCheckbox) return;
+  const enabled = createOutputDirCheckbox.checked;
+
+  try {
+    await invoke("save_create_output_dir_if_missing", { enabled });
+    console.log("[Config] Saved create output directory if missing:", enabled);
+    // The saved directory may now be accepted or rejected
+    await saveOutputDirectory();
+  } catch (error) {
+    console.error("[Config] Failed to save create output directory if missing:", error);
+  }
+}
+
+async function handleOpenOutputDirChange(): Promise<void> {
+  if (!openOutputDirCheckbox) return;
+  const enabled = openOutputDirCheckbox.checked;
+
+  try {
+    await invoke("save_open_output_dir_after_recording", { enabled });
+    console.log("[Config] Saved open output directory after recording:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save open output directory after recording:", error);
+  }
+}
+
+async function handleSmartFormatChange(): Promise<void> {
+  if (!smartFormatCheckbox) return;
+  const enabled = smartFormatCheckbox.checked;
+
+  try {
+    await invoke("save_smart_format", { enabled });
+    console.log("[Config] Saved smart format:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save smart format:", error);
+  }
+}
+
+async function handleCompletionNotificationChange(): Promise<void> {
+  if (!completionNotificationCheckbox) return;
+  const enabled = completionNotificationCheckbox.checked;
+
+  try {
+    await invoke("save_show_completion_notification", { enabled });
+    console.log("[Config] Saved completion notification:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save completion notification:", error);
+  }
+}
+
+async function handleNotificationPathChange(): Promise<void> {
+  if (!notificationPathCheckbox) return;
+  const enabled = notificationPathCheckbox.checked;
+
+  try {
+    await invoke("save_notification_include_path", { enabled });
+    console.log("[Config] Saved notification include path:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save notification include path:", error);
+  }
+}
+
+async function handleRecordingPreviewChange(): Promise<void> {
+  if (!recordingPreviewCheckbox) return;
+  const enabled = recordingPreviewCheckbox.checked;
+
+  try {
+    await invoke("save_show_recording_preview", { enabled });
+    console.log("[Config] Saved recording preview:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save recording preview:", error);
+  }
+}
+
+async function handleVerifyOnFinishChange(): Promise<void> {
+  if (!verifyOnFinishCheckbox) return;
+  const enabled = verifyOnFinishCheckbox.checked;
+
+  try {
+    await invoke("save_verify_on_finish", { enabled });
+    console.log("[Config] Saved verify on finish:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save verify on finish:", error);
+  }
+}
+
+// The login item itself is the setting, so it's read from the OS rather
+// than the app config
+async function loadAutostartState(): Promise<void> {
+  if (!autostartCheckbox) return;
+  try {
+    autostartCheckbox.checked = await invoke<boolean>("get_autostart_enabled");
+  } catch (error) {
+    console.error("[Config] Failed to read autostart state:", error);
+  }
+}
+
+async function handleAutostartChange(): Promise<void> {
+  if (!autostartCheckbox) return;
+  const enabled = autostartCheckbox.checked;
+
+  try {
+    await invoke(enabled ? "enable_autostart" : "disable_autostart");
+    console.log("[Config] Saved autostart:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save autostart:", error);
+  }
+  await loadAutostartState();
+}
+
+async function handleWindowFollowChange(): Promise<void> {
+  if (!windowFollowCheckbox) return;
+  const enabled = windowFollowCheckbox.checked;
+
+  try {
+    await invoke("save_window_follow", { enabled });
+    console.log("[Config] Saved window follow:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save window follow:", error);
+  }
+}
+
+async function handleForceWgcChange(): Promise<void> {
+  if (!forceWgcCheckbox) return;
+  const enabled = forceWgcCheckbox.checked;
+
+  try {
+    await invoke("save_force_wgc_for_games", { enabled });
+    console.log("[Config] Saved force WGC for games:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save force WGC for games:", error);
+  }
+}
+
+async function handleFocusBeforeCaptureChange(): Promise<void> {
+  if (!focusBeforeCaptureCheckbox) return;
+  const enabled = focusBeforeCaptureCheckbox.checked;
+
+  try {
+    await invoke("save_focus_before_capture", { enabled });
+    console.log("[Config] Saved focus before capture:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save focus before capture:", error);
+  }
+}
+
+async function handleSnapToWindowChange(): Promise<void> {
+  if (!snapToWindowCheckbox) return;
+  const enabled = snapToWindowCheckbox.checked;
+
+  try {
+    await invoke("save_snap_to_window", { enabled });
+    console.log("[Config] Saved snap to window:", enabled);
+  } catch (error) {
+    console.error("[Config] Failed to save snap to window:", error);
+  }
+}
+
+async function handleCountdownChange(): Promise<void> {
+  if (!countdownInput) return;
+  const secs = Math.min(10, Math.max(0, Math.round(Number(countdownInput.value) || 0)));
+  countdownInput.value = String(secs);
+
+  try {
+    await invoke("save_countdown_secs", { secs });
+    console.log("[Config] Saved countdown:", secs);
+  } catch (error) {
+    console.error("[Config] Failed to save countdown:", error);
+  }
+}
+
+function loadPipConfig(pip: PipConfig | null): void {
+  if (pip) {
+    if (pipPositionSelect) pipPositionSelect.value = pip.position;
+    if (pipSizeSelect) {
+      pipSizeSelect.value = String(pip.size_fraction);
+      if (pipSizeSelect.value === "") {
+        pipSizeSelect.value = "0.25";
+      }
+    }
+  }
+  updatePipVisibility(pip !== null);
+  loadCameras(pip?.camera_id ?? null);
+}
+
+/** Fill the camera list, selecting `selectedId` (the current selection if undefined). */
+async function loadCameras(selectedId: string | null = pipCameraSelect?.value || null): Promise<void> {
+  if (!pipCameraSelect) return;
+
+  let cameras: CameraInfo[] = [];
+  try {
+    cameras = await invoke<CameraInfo[]>("list_cameras");
+  } catch (error) {
+    console.error("[Config] Failed to list cameras:", error);
+  }
+
+  pipCameraSelect.innerHTML = '<option value="">None (no webcam)</option>';
+  for (const camera of cameras) {
+    const option = document.createElement("option");
+    option.value = camera.id;
+    option.textContent = camera.name;
+    pipCameraSelect.appendChild(option);
+  }
+  // Keep a configured camera that is unplugged right now
+  if (selectedId && !cameras.some(camera => camera.id === selectedId)) {
+    const option = document.createElement("option");
+    option.value = selectedId;
+    option.textContent = `Camera ${selectedId} (not connected)`;
+    pipCameraSelect.appendChild(option);
+  }
+  pipCameraSelect.value = selectedId ?? "";
+}
+
+function updatePipVisibility(enabled: boolean): void {
+  document.querySelector("#pip-position-config-item")?.classList.toggle("hidden", !enabled);
+  document.querySelector("#pip-size-config-item")?.classList.toggle("hidden", !enabled);
+}
+
+async function handlePipConfigChange(): Promise<void> {
+  const cameraId = pipCameraSelect?.value ?? "";
+  const pip: PipConfig | null = cameraId
+    ? {
+        camera_id: cameraId,
+        position: (pipPositionSelect?.value ?? "bottom_right") as PipPosition,
+        size_fraction: Number(pipSizeSelect?.value ?? 0.25),
+      }
+    : null;
+  updatePipVisibility(pip !== null);
+
+  try {
+    await invoke("save_pip_config", { pip });
+    console.log("[Config] Saved webcam overlay:", pip);
+  } catch (error) {
+    console.error("[Config] Failed to save webcam overlay:", error);
+  }
+}
+
+function loadCloudConfig(cloud: CloudUploadConfig | null): void {
+  if (cloudEnabledCheckbox) cloudEnabledCheckbox.checked = cloud?.enabled ?? false;
+  if (cloudEndpointInput) cloudEndpointInput.value = cloud?.provider.endpoint_url ?? "";
+  if (cloudRegionInput) cloudRegionInput.value = cloud?.credentials.region ?? "";
+  if (cloudBucketInput) cloudBucketInput.value = cloud?.bucket ?? "";
+  if (cloudPrefixInput) cloudPrefixInput.value = cloud?.prefix ?? "";
+  if (cloudAccessKeyInput) cloudAccessKeyInput.value = cloud?.credentials.access_key_id ?? "";
+  // The saved secret is never sent to the frontend
+  if (cloudSecretKeyInput) cloudSecretKeyInput.value = "";
+}
+
+function readCloudConfig(): CloudUploadConfig {
+  return {
+    enabled: cloudEnabledCheckbox?.checked ?? false,
+    provider: {
+      type: "s3_compatible",
+      endpoint_url: cloudEndpointInput?.value.trim() ?? "",
+    },
+    bucket: cloudBucketInput?.value.trim() ?? "",
+    prefix: cloudPrefixInput?.value.trim() ?? "",
+    credentials: {
+      access_key_id: cloudAccessKeyInput?.value.trim() ?? "",
+      secret_access_key: cloudSecretKeyInput?.value ?? "",
+      region: cloudRegionInput?.value.trim() || "us-east-1",
+    },
+  };
+}
+
+async function handleCloudConfigChange(): Promise<void> {
+  try {
+    await invoke("save_cloud_config", { cloud: readCloudConfig() });
+    cloudErrorEl?.classList.add("hidden");
+    console.log("[Config] Saved cloud upload settings");
+  } catch (error) {
+    if (cloudErrorEl) {
+      cloudErrorEl.textContent = String(error);
+      cloudErrorEl.classList.remove("hidden");
+    }
+    console.error("[Config] Failed to save cloud upload settings:", error);
+  }
+}
+
+async function handleCloudTestClick(): Promise<void> {
+  if (!cloudTestBtn || !cloudTestResultEl) return;
+
+  cloudTestBtn.disabled = true;
+  cloudTestResultEl.classList.remove("config-item__hint--warning");
+  cloudTestResultEl.textContent = "Connecting...";
+
+  try {
+    await invoke("test_cloud_connection", { cloud: readCloudConfig() });
+    cloudTestResultEl.textContent = "Connected";
+  } catch (error) {
+    cloudTestResultEl.textContent = String(error);
+    cloudTestResultEl.classList.add("config-item__hint--warning");
+  } finally {
+    cloudTestBtn.disabled = false;
+  }
+}
+
+function updateSilenceThresholdVisibility(): void {
+  const enabled = trimSilenceCheckbox?.checked ?? false;
+  silenceThresholdConfigItem?.classList.toggle("hidden", !enabled);
+}
+
+async function handleSilenceTrimChange(): Promise<void> {
+  if (!trimSilenceCheckbox || !silenceThresholdSelect) return;
+  const enabled = trimSilenceCheckbox.checked;
+  const thresholdDb = Number(silenceThresholdSelect.value);
+
+  updateSilenceThresholdVisibility();
+
+  try {
+    await invoke("save_silence_trim", { enabled, thresholdDb });
+    console.log("[Config] Saved silence trimming: enabled=", enabled, ", threshold=", thresholdDb);
+  } catch (error) {
+    console.error("[Config] Failed to save silence trimming:", error);
+  }
+}
+
+interface FilterValidationResult {
+  valid: boolean;
+  error: string | null;
+}
+
+async function handleVideoFilterChange(): Promise<void> {
+  if (!videoFilterInput) return;
+  const filter = videoFilterInput.value.trim();
+
+  try {
+    if (filter) {
+      const result = await invoke<FilterValidationResult>("validate_video_filter", { filter });
+      if (!result.valid) {
+        showVideoFilterError(result.error ?? "Invalid filter chain");
+        return;
+      }
+    }
+    await invoke("save_video_filter_chain", { filter: filter || null });
+    clearVideoFilterError();
+    console.log("[Config] Saved video filter chain:", filter || "(none)");
+  } catch (error) {
+    showVideoFilterError(String(error));
+    console.error("[Config] Failed to save video filter chain:", error);
+  }
+}
+
+function showVideoFilterError(message: string): void {
+  if (videoFilterErrorEl) {
+    videoFilterErrorEl.textContent = message;
+    videoFilterErrorEl.classList.remove("hidden");
+  }
+  videoFilterInput?.classList.add("has-error");
+}
+
+function clearVideoFilterError(): void {
+  if (videoFilterErrorEl) {
+    videoFilterErrorEl.textContent = "";
+    videoFilterErrorEl.classList.add("hidden");
+  }
+  videoFilterInput?.classList.remove("has-error");
+}
+
+// =============================================================================
+// Hotkey Configuration Functions
+// =============================================================================
+
+async function handleScreenshotHotkeyChange(): Promise<void> {
+  if (!screenshotHotkeyInput) return;
+  const screenshot = screenshotHotkeyInput.value.trim();
+
+  try {
+    await invoke("save_hotkey_config", { hotkeys: { screenshot: screenshot || null } });
+    clearScreenshotHotkeyError();
+    console.log("[Config] Saved screenshot hotkey:", screenshot || "(none)");
+  } catch (error) {
+    showScreenshotHotkeyError(String(error));
+    console.error("[Config] Failed to save screenshot hotkey:", error);
+  }
+}
+
+function showScreenshotHotkeyError(message: string): void {
+  if (screenshotHotkeyErrorEl) {
+    screenshotHotkeyErrorEl.textContent = message;
+    screenshotHotkeyErrorEl.classList.remove("hidden");
+  }
+  screenshotHotkeyInput?.classList.add("has-error");
+}
+
+function clearScreenshotHotkeyError(): void {
+  if (screenshotHotkeyErrorEl) {
+    screenshotHotkeyErrorEl.textContent = "";
+    screenshotHotkeyErrorEl.classList.add("hidden");
+  }
+  screenshotHotkeyInput?.classList.remove("has-error");
+}
+
 // =============================================================================
 // Audio Configuration Functions
 // =============================================================================
@@ -338,6 +1147,10 @@ async function loadAudioSources(): Promise<void> {
         audioSourceSelect.appendChild(option);
       }
 
+      if (currentPlatform === "linux") {
+        await appendAudioAppOptions(audioSourceSelect, audioConfig.source_id);
+      }
+
       if (audioConfig.source_id && audioConfig.source_id !== "system") {
         audioSourceSelect.value = audioConfig.source_id;
         if (audioSourceSelect.value !== audioConfig.source_id) {
@@ -373,7 +1186,33 @@ async function loadAudioSources(): Promise<void> {
       agcNoiseGateCheckbox.checked = audioConfig.agc_noise_gate_enabled;
     }
 
+    if (agcTargetSelect) {
+      agcTargetSelect.value = String(audioConfig.agc_target_level_dbfs ?? -20);
+      if (agcTargetSelect.value === "") {
+        agcTargetSelect.value = "-20";
+      }
+    }
+
+    if (audioMonitorCheckbox) {
+      audioMonitorCheckbox.checked = audioConfig.audio_monitor_enabled;
+    }
+
+    if (monitorVolumeSelect) {
+      monitorVolumeSelect.value = String(audioConfig.monitor_volume);
+      if (monitorVolumeSelect.value === "") {
+        monitorVolumeSelect.value = "1";
+      }
+    }
+
+    if (audioBitrateSelect) {
+      audioBitrateSelect.value = String(audioConfig.audio_bitrate_kbps ?? 0);
+      if (audioBitrateSelect.value === "") {
+        audioBitrateSelect.value = "0";
+      }
+    }
+
     updateAecVisibility();
+    updateAudioMonitorVisibility();
     updateAgcVisibility();
     updateTranscriptionVisibility();
     await loadTranscriptionConfig();
@@ -386,6 +1225,31 @@ async function loadAudioSources(): Promise<void> {
   }
 }
 
+// Add an "Applications" group of per-application sources. The saved app is
+// kept selectable even if it is not playing audio right now.
+async function appendAudioAppOptions(select: HTMLSelectElement, savedSourceId: string | null): Promise<void> {
+  const apps = await invoke<AudioAppInfo[]>("list_running_audio_apps");
+  const savedApp = savedSourceId?.startsWith(APP_AUDIO_SOURCE_PREFIX)
+    ? savedSourceId.slice(APP_AUDIO_SOURCE_PREFIX.length)
+    : null;
+
+  const entries = apps.map(app => ({ name: app.name, label: app.name }));
+  if (savedApp && !apps.some(app => app.name === savedApp)) {
+    entries.push({ name: savedApp, label: `${savedApp} (not playing)` });
+  }
+  if (entries.length === 0) return;
+
+  const group = document.createElement("optgroup");
+  group.label = "Applications";
+  for (const entry of entries) {
+    const option = document.createElement("option");
+    option.value = APP_AUDIO_SOURCE_PREFIX + entry.name;
+    option.textContent = entry.label;
+    group.appendChild(option);
+  }
+  select.appendChild(group);
+}
+
 function updateAecVisibility(): void {
   if (!aecConfigItem || !micSourceSelect) return;
   const hasMic = micSourceSelect.value !== "";
@@ -398,6 +1262,54 @@ function updateAgcVisibility(): void {
   const agcOn = agcCheckbox?.checked ?? false;
   agcConfigItem?.classList.toggle("hidden", !hasMic);
   agcNoiseGateConfigItem?.classList.toggle("hidden", !(hasMic && agcOn));
+  agcTargetConfigItem?.classList.toggle("hidden", !(hasMic && agcOn));
+  exclusiveModeConfigItem?.classList.toggle("hidden", !(hasMic && currentPlatform === "windows"));
+}
+
+function updateAudioMonitorVisibility(): void {
+  const isMacos = currentPlatform === "macos";
+  const monitorOn = audioMonitorCheckbox?.checked ?? false;
+  audioMonitorConfigItem?.classList.toggle("hidden", !isMacos);
+  monitorVolumeConfigItem?.classList.toggle("hidden", !(isMacos && monitorOn));
+}
+
+async function handleAudioMonitorChange(): Promise<void> {
+  if (!audioMonitorCheckbox || !monitorVolumeSelect) return;
+  const enabled = audioMonitorCheckbox.checked;
+  const volume = Number(monitorVolumeSelect.value);
+
+  updateAudioMonitorVisibility();
+
+  try {
+    await invoke("set_audio_monitor", { enabled, volume });
+    console.log("[Audio] Saved monitor: enabled=", enabled, ", volume=", volume);
+  } catch (error) {
+    console.error("[Audio] Failed to save audio monitor:", error);
+  }
+}
+
+async function handleAudioBitrateChange(): Promise<void> {
+  if (!audioBitrateSelect) return;
+  const bitrateKbps = Number(audioBitrateSelect.value);
+
+  try {
+    await invoke("set_audio_bitrate", { bitrateKbps });
+    console.log("[Audio] Saved bitrate:", bitrateKbps);
+  } catch (error) {
+    console.error("[Audio] Failed to save audio bitrate:", error);
+  }
+}
+
+async function handleExclusiveModeChange(): Promise<void> {
+  if (!exclusiveModeCheckbox) return;
+  const enabled = exclusiveModeCheckbox.checked;
+
+  try {
+    await invoke("save_audio_exclusive_mode", { enabled });
+    console.log("[Audio] Saved exclusive mode:", enabled);
+  } catch (error) {
+    console.error("[Audio] Failed to save exclusive mode:", error);
+  }
 }
 
 async function handleAudioConfigChange(): Promise<void> {
@@ -408,6 +1320,7 @@ async function handleAudioConfigChange(): Promise<void> {
   const echoCancellation = aecCheckbox.checked;
   const agcEnabled = agcCheckbox?.checked ?? false;
   const agcNoiseGateEnabled = agcNoiseGateCheckbox?.checked ?? true;
+  const agcTargetLevelDbfs = Number(agcTargetSelect?.value ?? -20);
 
   updateAecVisibility();
   updateAgcVisibility();
@@ -421,10 +1334,12 @@ async function handleAudioConfigChange(): Promise<void> {
       echoCancellation,
       agcEnabled,
       agcNoiseGateEnabled,
+      agcTargetLevelDbfs,
     });
     console.log("[Audio] Saved config: system=", sourceId || "(none)",
       ", mic=", microphoneId || "(none)", ", aec=", echoCancellation,
-      ", agc=", agcEnabled, ", agc_gate=", agcNoiseGateEnabled);
+      ", agc=", agcEnabled, ", agc_gate=", agcNoiseGateEnabled,
+      ", agc_target=", agcTargetLevelDbfs);
   } catch (error) {
     console.error("[Audio] Failed to save audio config:", error);
   }
@@ -466,6 +1381,10 @@ function updateTranscriptionVisibility(): void {
     modelConfigItem.classList.toggle("hidden", !showModelConfig);
   }
 
+  if (languageConfigItem) {
+    languageConfigItem.classList.toggle("hidden", !showModelConfig);
+  }
+
   if (showTranscriptConfigItem) {
     showTranscriptConfigItem.classList.toggle("hidden", !showModelConfig);
   }
@@ -506,10 +1425,15 @@ async function loadTranscriptionConfig(): Promise<void> {
       modelSelect.value = config.model;
     }
 
+    await loadSupportedLanguages();
+    if (languageSelect && config.language) {
+      languageSelect.value = config.language;
+    }
+
     await updateModelStatus();
     updateTranscriptionVisibility();
 
-    console.log("[Transcription] Loaded config: enabled=", config.enabled, "model=", config.model, "show_transcript_window=", config.show_transcript_window);
+    console.log("[Transcription] Loaded config: enabled=", config.enabled, "model=", config.model, "language=", config.language, "show_transcript_window=", config.show_transcript_window);
   } catch (error) {
     console.error("[Transcription] Failed to load config:", error);
   }
@@ -553,10 +1477,52 @@ async function loadAvailableModels(): Promise<void> {
   }
 }
 
+async function loadSupportedLanguages(): Promise<void> {
+  if (!languageSelect) return;
+
+  try {
+    const languages = await invoke<LanguageInfo[]>("list_supported_languages");
+    languageSelect.innerHTML = "";
+    for (const language of languages) {
+      const option = document.createElement("option");
+      option.value = language.code;
+      option.textContent = language.display_name;
+      languageSelect.appendChild(option);
+    }
+  } catch (error) {
+    console.error("[Transcription] Failed to load languages:", error);
+  }
+}
+
+// Model loaded for the selected model and language: English-only models
+// ("medium-en") are swapped for the multilingual one ("medium") unless the
+// language is English
+function effectiveModelId(): string {
+  const model = modelSelect?.value ?? "";
+  const language = languageSelect?.value ?? "en";
+  return language !== "en" && model.endsWith("-en") ? model.slice(0, -3) : model;
+}
+
+async function handleLanguageChange(): Promise<void> {
+  if (!languageSelect) return;
+  const language = languageSelect.value;
+  await updateModelStatus();
+
+  try {
+    await invoke("save_transcription_config", {
+      enabled: transcriptionCheckbox?.checked ?? false,
+      language,
+    });
+    console.log("[Transcription] Saved language:", language);
+  } catch (error) {
+    console.error("[Transcription] Failed to save language:", error);
+  }
+}
+
 async function updateModelStatus(): Promise<void> {
   if (!modelSelect || !modelStatus || !modelDownloadBtn || !modelCancelBtn) return;
 
-  const selectedModel = modelSelect.value;
+  const selectedModel = effectiveModelId();
   if (!selectedModel) {
     if (modelInfo) modelInfo.textContent = "";
     modelStatus.textContent = "";
@@ -620,7 +1586,7 @@ async function handleModelChange(): Promise<void> {
 
 async function handleModelDownload(): Promise<void> {
   if (!modelSelect) return;
-  const selectedModel = modelSelect.value;
+  const selectedModel = effectiveModelId();
   if (!selectedModel) return;
 
   console.log("[Model] Starting download:", selectedModel);
@@ -768,3 +1734,255 @@ async function handleLogLevelChange(): Promise<void> {
     console.error("[Config] Failed to set log level:", error);
   }
 }
+
+/** Select value for an encoder thread setting: "auto", "single" or the count. */
+function encoderThreadsValue(threads: EncoderThreads): string {
+  return typeof threads === "string" ? threads : String(threads.count);
+}
+
+async function handleEncoderThreadsChange(): Promise<void> {
+  if (!encoderThreadsSelect) return;
+
+  const value = encoderThreadsSelect.value;
+  const encoderThreads: EncoderThreads =
+    value === "auto" || value === "single" ? value : { count: Number(value) };
+
+  try {
+    await invoke("save_encoder_config", { encoderThreads });
+    console.log("[Config] Saved encoder threads:", encoderThreads);
+  } catch (error) {
+    console.error("[Config] Failed to save encoder threads:", error);
+  }
+}
+
+async function handleKeyframeIntervalChange(): Promise<void> {
+  if (!keyframeIntervalInput) return;
+  const value = Number(keyframeIntervalInput.value) || 2;
+  const secs = Math.min(10, Math.max(0.1, Math.round(value * 10) / 10));
+  keyframeIntervalInput.value = String(secs);
+
+  try {
+    await invoke("save_keyframe_interval", { secs });
+    console.log("[Config] Saved keyframe interval:", secs);
+  } catch (error) {
+    console.error("[Config] Failed to save keyframe interval:", error);
+  }
+}
+
+// =============================================================================
+// Audio Level Check
+// =============================================================================
+
+interface WaveformResult {
+  levels: number[];
+  peak: number;
+}
+
+const WAVEFORM_DURATION_MS = 3000;
+// RMS levels are small for typical speech; scale bars so -20 dBFS fills them
+const WAVEFORM_DISPLAY_GAIN = 10;
+const WAVEFORM_QUIET_PEAK = 0.01;
+const WAVEFORM_CLIPPING_PEAK = 0.7;
+
+// The source to check: the microphone if one is selected, else system audio
+function selectedLevelCheckSource(): string | null {
+  if (micSourceSelect?.value) return micSourceSelect.value;
+  if (currentPlatform === "macos") {
+    return macosSystemAudioCheckbox?.checked ? "system" : null;
+  }
+  return audioSourceSelect?.value || null;
+}
+
+async function handleAudioLevelClick(): Promise<void> {
+  if (!audioLevelBtn || !audioLevelResultEl || !audioWaveformEl) return;
+
+  const sourceId = selectedLevelCheckSource();
+  if (!sourceId) {
+    audioLevelResultEl.textContent = "Select a microphone or system audio source first";
+    audioLevelResultEl.classList.add("config-item__hint--warning");
+    return;
+  }
+
+  audioLevelBtn.disabled = true;
+  audioLevelResultEl.classList.remove("config-item__hint--warning");
+  audioLevelResultEl.textContent = "Listening...";
+
+  try {
+    const result = await invoke<WaveformResult>("get_audio_waveform", {
+      sourceId,
+      durationMs: WAVEFORM_DURATION_MS,
+    });
+    renderWaveform(result.levels);
+    if (result.peak < WAVEFORM_QUIET_PEAK) {
+      audioLevelResultEl.textContent = "Very quiet — check the source and its volume";
+    } else if (result.peak > WAVEFORM_CLIPPING_PEAK) {
+      audioLevelResultEl.textContent = "Very loud — the recording may distort";
+    } else {
+      audioLevelResultEl.textContent = "Levels look good";
+    }
+    audioLevelResultEl.classList.toggle(
+      "config-item__hint--warning",
+      result.peak < WAVEFORM_QUIET_PEAK || result.peak > WAVEFORM_CLIPPING_PEAK
+    );
+  } catch (error) {
+    audioWaveformEl.classList.add("hidden");
+    audioLevelResultEl.textContent = `Level check failed: ${error}`;
+    audioLevelResultEl.classList.add("config-item__hint--warning");
+    console.error("[Config] Level check failed:", error);
+  } finally {
+    audioLevelBtn.disabled = false;
+  }
+}
+
+function renderWaveform(levels: number[]): void {
+  if (!audioWaveformEl) return;
+  audioWaveformEl.innerHTML = "";
+  for (const level of levels) {
+    const bar = document.createElement("div");
+    bar.className = "audio-waveform__bar";
+    bar.style.height = `${Math.min(level * WAVEFORM_DISPLAY_GAIN, 1) * 100}%`;
+    if (level > WAVEFORM_CLIPPING_PEAK) {
+      bar.classList.add("audio-waveform__bar--clipping");
+    }
+    audioWaveformEl.appendChild(bar);
+  }
+  audioWaveformEl.classList.remove("hidden");
+}
+
+// =============================================================================
+// Performance Benchmark
+// =============================================================================
+
+interface BenchmarkResult {
+  achieved_fps: number;
+  cpu_percent: number;
+  ok: boolean;
+}
+
+const BENCHMARK_WIDTH = 1920;
+const BENCHMARK_HEIGHT = 1080;
+const BENCHMARK_FPS = 30;
+const BENCHMARK_DURATION_SECS = 5;
+
+// =============================================================================
+// Screen Sharing Approvals
+// =============================================================================
+
+function formatApprovalTime(timestamp: string | null): string {
+  return timestamp ? new Date(timestamp).toLocaleString() : "Never";
+}
+
+async function loadApprovalTokens(): Promise<void> {
+  const table = document.querySelector<HTMLTableElement>("#approvals-table");
+  const body = document.querySelector<HTMLElement>("#approvals-table-body");
+  const empty = document.querySelector<HTMLElement>("#approvals-empty");
+  if (!table || !body || !empty) return;
+
+  try {
+    const tokens = await invoke<ApprovalTokenInfo[]>("get_approval_tokens");
+    body.innerHTML = "";
+    for (const token of tokens) {
+      const row = document.createElement("tr");
+      const source =
+        token.source_type === "unknown" ? "Any source" : `${token.source_type}: ${token.source_id}`;
+      for (const text of [
+        source,
+        formatApprovalTime(token.created_at),
+        formatApprovalTime(token.last_used_at),
+      ]) {
+        const cell = document.createElement("td");
+        cell.textContent = text;
+        row.appendChild(cell);
+      }
+
+      const actionCell = document.createElement("td");
+      const revokeBtn = document.createElement("button");
+      revokeBtn.type = "button";
+      revokeBtn.className = "config-item__action-btn config-item__action-btn--cancel";
+      revokeBtn.textContent = "Revoke";
+      revokeBtn.addEventListener("click", () => handleRevokeApproval(token.token_id));
+      actionCell.appendChild(revokeBtn);
+      row.appendChild(actionCell);
+
+      body.appendChild(row);
+    }
+    table.classList.toggle("hidden", tokens.length === 0);
+    empty.classList.toggle("hidden", tokens.length > 0);
+  } catch (error) {
+    console.error("[Approvals] Failed to load approval tokens:", error);
+  }
+}
+
+async function handleRevokeApproval(tokenId: string): Promise<void> {
+  try {
+    await invoke("revoke_approval_token", { tokenId });
+    console.log("[Approvals] Revoked", tokenId);
+  } catch (error) {
+    console.error("[Approvals] Failed to revoke approval token:", error);
+  }
+  await loadApprovalTokens();
+}
+
+async function handleBenchmarkClick(): Promise<void> {
+  if (!benchmarkBtn || !benchmarkResultEl) return;
+
+  benchmarkBtn.disabled = true;
+  benchmarkResultEl.classList.remove("config-item__hint--warning");
+  benchmarkResultEl.textContent = `Testing for ${BENCHMARK_DURATION_SECS} seconds...`;
+
+  try {
+    const result = await invoke<BenchmarkResult>("benchmark_encoder", {
+      width: BENCHMARK_WIDTH,
+      height: BENCHMARK_HEIGHT,
+      fps: BENCHMARK_FPS,
+      durationSecs: BENCHMARK_DURATION_SECS,
+    });
+    const summary = `${result.achieved_fps.toFixed(1)} fps at 1080p, ${Math.round(result.cpu_percent)}% CPU`;
+    benchmarkResultEl.textContent = result.ok
+      ? `${summary} — ready for 30 fps recording`
+      : `${summary} — recordings may drop frames`;
+    benchmarkResultEl.classList.toggle("config-item__hint--warning", !result.ok);
+    console.log("[Benchmark] Result:", result);
+  } catch (error) {
+    benchmarkResultEl.textContent = `Test failed: ${error}`;
+    benchmarkResultEl.classList.add("config-item__hint--warning");
+    console.error("[Benchmark] Failed:", error);
+  } finally {
+    benchmarkBtn.disabled = false;
+  }
+}
+
+async function handleExportConfigClick(): Promise<void> {
+  if (!configTransferResultEl) return;
+
+  configTransferResultEl.classList.remove("config-item__hint--warning");
+  try {
+    const path = await invoke<string | null>("export_config_to_file");
+    if (path) {
+      configTransferResultEl.textContent = `Exported to ${path}`;
+      console.log("[Config] Exported config to", path);
+    }
+  } catch (error) {
+    configTransferResultEl.textContent = `Export failed: ${error}`;
+    configTransferResultEl.classList.add("config-item__hint--warning");
+    console.error("[Config] Failed to export config:", error);
+  }
+}
+
+async function handleImportConfigClick(): Promise<void> {
+  if (!configTransferResultEl) return;
+
+  configTransferResultEl.classList.remove("config-item__hint--warning");
+  try {
+    const imported = await invoke<boolean>("import_config_from_file");
+    if (imported) {
+      await loadConfig();
+      configTransferResultEl.textContent = "Imported — restart OmniRec to apply every setting";
+      console.log("[Config] Imported config");
+    }
+  } catch (error) {
+    configTransferResultEl.textContent = `Import failed: ${error}`;
+    configTransferResultEl.classList.add("config-item__hint--warning");
+    console.error("[Config] Failed to import config:", error);
+  }
+}
@@ -12,6 +12,18 @@ interface LogLinePayload {
   line: string;
 }
 
+/** A recent log message kept in memory by the backend (see event_log.rs). */
+interface LogEvent {
+  timestamp: string;
+  level: string;
+  target: string;
+  message: string;
+  fields?: Record<string, string>;
+}
+
+/** How many recent messages to show when the window opens. */
+const HISTORY_COUNT = 1000;
+
 // ─── State ────────────────────────────────────────────────────────────────────
 
 let isPinned = true;
@@ -29,9 +41,10 @@ let downloadBtn: HTMLButtonElement;
 
 // ─── Log line rendering ───────────────────────────────────────────────────────
 //
-// Both history (from file) and live events (from TauriLogLayer) arrive as the
-// same pre-formatted string. We parse each line into its parts and render them
-// as separate styled spans so each part can be colored independently.
+// Both history (recent events, formatted by formatEventLine) and live events
+// (from TauriLogLayer) arrive as the same pre-formatted string. We parse each
+// line into its parts and render them as separate styled spans so each part
+// can be colored independently.
 //
 // Line format produced by tracing_subscriber::fmt (and mirrored by TauriLogLayer):
 //   2026-03-02T00:27:33.464210Z  INFO omnirec_lib: message text here
@@ -181,22 +194,31 @@ async function handleDownload() {
   downloadBtn.textContent = "Download Logs";
 }
 
-// ─── Seed historical log lines from the current session file ──────────────────
+// ─── Seed recent log lines from the backend's in-memory log ───────────────────
+
+/** Format an event as the log file would, with its fields as `key=value`. */
+function formatEventLine(event: LogEvent): string {
+  const level = event.level.padStart(5);
+  const fields = Object.entries(event.fields ?? {})
+    .map(([key, value]) => ` ${key}=${value}`)
+    .join("");
+  return `${event.timestamp} ${level} ${event.target}: ${event.message}${fields}`;
+}
 
 async function seedHistory() {
-  let raw: string;
+  let events: LogEvent[];
   try {
-    raw = await invoke<string>("get_log_history");
+    events = await invoke<LogEvent[]>("get_recent_events", { count: HISTORY_COUNT });
   } catch {
     return; // Non-fatal: just show live lines going forward
   }
 
-  if (!raw) return;
+  if (events.length === 0) return;
 
   const fragment = document.createDocumentFragment();
   let count = 0;
-  for (const line of raw.split("\n")) {
-    const el = buildRawRow(line);
+  for (const event of events) {
+    const el = buildRawRow(formatEventLine(event));
     if (el) {
       fragment.appendChild(el);
       count++;
@@ -251,7 +273,7 @@ document.addEventListener("DOMContentLoaded", async () => {
   scrollToBottomBtn.addEventListener("click", () => scrollToBottom());
   downloadBtn.addEventListener("click", () => void handleDownload());
 
-  // 1. Seed with the current session log file contents
+  // 1. Seed with the most recent messages
   await seedHistory();
 
   // 2. Subscribe to live "log-line" events from the backend
@@ -10,6 +10,8 @@ interface WindowInfo {
   handle: number;
   title: string;
   process_name: string;
+  workspace_id?: number;
+  workspace_name?: string;
 }
 
 interface MonitorInfo {
@@ -30,11 +32,12 @@ interface CaptureRegion {
   y: number;
   width: number;
   height: number;
+  aspect_ratio?: [number, number] | null;
 }
 
 type CaptureMode = "window" | "region" | "display";
 type ViewMode = CaptureMode;
-type RecordingState = "idle" | "recording" | "saving";
+type RecordingState = "idle" | "recording" | "suspended" | "saving";
 
 interface AudioConfig {
   enabled: boolean;
@@ -43,6 +46,7 @@ interface AudioConfig {
   echo_cancellation: boolean;
   agc_enabled: boolean;
   agc_noise_gate_enabled: boolean;
+  agc_target_level_dbfs: number;
 }
 
 interface TranscriptionConfig {
@@ -84,6 +88,7 @@ interface RecordingResult {
   file_path: string | null;
   source_path: string | null;
   error: string | null;
+  copied_to_clipboard: boolean;
 }
 
 interface ThumbnailResponse {
@@ -125,6 +130,8 @@ let displaySelectionEl: HTMLElement | null;
 let displayListEl: HTMLElement | null;
 let recordBtn: HTMLButtonElement | null;
 let selectRegionBtn: HTMLButtonElement | null;
+let aspectLockCheckbox: HTMLInputElement | null;
+let aspectRatioSelect: HTMLSelectElement | null;
 let modeWindowBtn: HTMLButtonElement | null;
 let modeRegionBtn: HTMLButtonElement | null;
 let modeDisplayBtn: HTMLButtonElement | null;
@@ -135,6 +142,9 @@ let resultDismissTimeout: number | null = null;
 let resultEl: HTMLElement | null;
 let resultPathEl: HTMLElement | null;
 let openFolderBtn: HTMLButtonElement | null;
+let copyPathBtn: HTMLButtonElement | null;
+let openFileBtn: HTMLButtonElement | null;
+let cancelUploadBtn: HTMLButtonElement | null;
 let appVersionEl: HTMLElement | null;
 let permissionNoticeEl: HTMLElement | null;
 let captureUiEl: HTMLElement | null;
@@ -154,12 +164,15 @@ let desktopEnvironment: DesktopEnvironment = "unknown";
 // State
 let captureMode: CaptureMode = "window";
 let selectedWindow: WindowInfo | null = null;
+let selectedWorkspaceId: number | null = null;
 let selectedRegion: CaptureRegion | null = null;
 let selectedDisplay: MonitorInfo | null = null;
 let regionSelectorWindow: WebviewWindow | null = null;
 let currentState: RecordingState = "idle";
+let countdownRemaining = 0;
 let timerInterval: number | null = null;
 let recordingStartTime: number = 0;
+let suspendedAt: number | null = null;
 // Theme state
 let currentThemeMode: ThemeMode = "auto";
 let systemThemeMediaQuery: MediaQueryList | null = null;
@@ -183,6 +196,66 @@ interface SelectorGeometry {
 }
 let storedSelectorGeometry: SelectorGeometry | null = null;
 
+// Region of the last successful region recording (persisted in config, monitor-relative)
+type LastRegion = Omit<CaptureRegion, "monitor_name">;
+let lastRegionAvailable = false;
+
+// Load the last recorded region as logical selector geometry, if there is one
+async function loadLastRegionGeometry(monitors: MonitorInfo[]): Promise<SelectorGeometry | null> {
+  try {
+    const region = await invoke<LastRegion | null>("get_last_region");
+    lastRegionAvailable = region !== null;
+    const monitor = region && monitors.find(m => m.id === region.monitor_id);
+    if (!region || !monitor) return null;
+
+    if (region.aspect_ratio) {
+      setLockedAspectRatio(region.aspect_ratio);
+    }
+
+    // Monitor coordinates are physical, convert to logical for WebviewWindow
+    const scale = monitor.scale_factor;
+    return {
+      x: Math.round((monitor.x + region.x) / scale),
+      y: Math.round((monitor.y + region.y) / scale),
+      width: Math.round(region.width / scale),
+      height: Math.round(region.height / scale),
+    };
+  } catch (e) {
+    console.warn("Failed to load last region:", e);
+    return null;
+  }
+}
+
+// Aspect ratio (width, height) the region selector is locked to, or null
+function lockedAspectRatio(): [number, number] | null {
+  if (!aspectLockCheckbox?.checked || !aspectRatioSelect) return null;
+  const [width, height] = aspectRatioSelect.value.split(":").map(Number);
+  return width > 0 && height > 0 ? [width, height] : null;
+}
+
+function setLockedAspectRatio(ratio: [number, number]): void {
+  if (!aspectLockCheckbox || !aspectRatioSelect) return;
+  const value = `${ratio[0]}:${ratio[1]}`;
+  if (!Array.from(aspectRatioSelect.options).some(o => o.value === value)) return;
+  aspectRatioSelect.value = value;
+  aspectLockCheckbox.checked = true;
+  aspectRatioSelect.disabled = false;
+}
+
+// Apply the aspect ratio lock to an open region selector
+async function handleAspectRatioChange(): Promise<void> {
+  if (aspectLockCheckbox && aspectRatioSelect) {
+    aspectRatioSelect.disabled = !aspectLockCheckbox.checked;
+  }
+  await regionSelectorWindow?.emit("region-aspect-ratio", lockedAspectRatio());
+}
+
+// Reopen the region selector at the last recorded region
+async function resetToLastRegion(): Promise<void> {
+  storedSelectorGeometry = null;
+  await openRegionSelector();
+}
+
 // Close region selector and store its geometry for later restoration
 async function closeRegionSelector(): Promise<void> {
   if (!regionSelectorWindow) return;
@@ -233,6 +306,8 @@ window.addEventListener("DOMContentLoaded", () => {
   recordBtn = document.querySelector("#record-btn");
 
   selectRegionBtn = document.querySelector("#select-region-btn");
+  aspectLockCheckbox = document.querySelector("#aspect-lock-checkbox");
+  aspectRatioSelect = document.querySelector("#aspect-ratio-select");
   modeWindowBtn = document.querySelector("#mode-window-btn");
   modeRegionBtn = document.querySelector("#mode-region-btn");
   modeDisplayBtn = document.querySelector("#mode-display-btn");
@@ -241,6 +316,9 @@ window.addEventListener("DOMContentLoaded", () => {
   resultEl = document.querySelector("#result");
   resultPathEl = document.querySelector("#result-path");
   openFolderBtn = document.querySelector("#open-folder-btn");
+  copyPathBtn = document.querySelector("#copy-path-btn");
+  openFileBtn = document.querySelector("#open-file-btn");
+  cancelUploadBtn = document.querySelector("#cancel-upload-btn");
   appVersionEl = document.querySelector("#app-version");
   permissionNoticeEl = document.querySelector("#permission-notice");
   captureUiEl = document.querySelector("#capture-ui");
@@ -284,15 +362,21 @@ window.addEventListener("DOMContentLoaded", () => {
   });
   statusOverlayEl?.addEventListener("click", dismissStatus);
   resultEl?.addEventListener("click", (e) => {
-    // Don't dismiss if clicking the Open Folder button
-    if (e.target !== openFolderBtn) {
+    // Don't dismiss if clicking one of the action buttons
+    const actions = [openFolderBtn, copyPathBtn, openFileBtn, cancelUploadBtn];
+    if (!actions.includes(e.target as HTMLButtonElement)) {
       dismissResult();
     }
   });
   openSettingsBtn?.addEventListener("click", handleOpenSettings);
   recordBtn?.addEventListener("click", handleRecordClick);
   openFolderBtn?.addEventListener("click", handleOpenFolder);
+  copyPathBtn?.addEventListener("click", handleCopyPath);
+  openFileBtn?.addEventListener("click", handleOpenFile);
+  cancelUploadBtn?.addEventListener("click", handleCancelUpload);
   selectRegionBtn?.addEventListener("click", openRegionSelector);
+  aspectLockCheckbox?.addEventListener("change", handleAspectRatioChange);
+  aspectRatioSelect?.addEventListener("change", handleAspectRatioChange);
   modeWindowBtn?.addEventListener("click", () => setViewMode("window"));
   modeRegionBtn?.addEventListener("click", () => setViewMode("region"));
   modeDisplayBtn?.addEventListener("click", () => setViewMode("display"));
@@ -357,6 +441,13 @@ window.addEventListener("DOMContentLoaded", () => {
     handleTrayShowTranscription();
   });
 
+  // Listen for the screenshot hotkey; the service doesn't know which source
+  // is selected here, so the screenshot is taken from this window
+  listen("screenshot-requested", () => {
+    console.log("[Hotkey] Screenshot requested");
+    takeScreenshot();
+  });
+
   // Listen for external recording stop (e.g., user clicked GNOME's recording indicator)
   listen("recording-stream-stopped", () => {
     console.log("[Stream] Recording stream stopped externally");
@@ -365,6 +456,79 @@ window.addEventListener("DOMContentLoaded", () => {
     }
   });
 
+  // Listen for capture loss the service could not recover from; the service
+  // has already stopped and saved the recording
+  listen<{ reason: string; path: string | null }>("capture-disconnected", async (event) => {
+    console.log("[Stream] Capture disconnected:", event.payload.reason);
+    stopTimer();
+    if (event.payload.path) {
+      showResult(event.payload.path);
+    }
+    setStatus(`Recording stopped: ${event.payload.reason}`, true);
+    if (regionSelectorWindow) {
+      await regionSelectorWindow.emit("recording-stopped");
+    }
+    currentState = "idle";
+    updateRecordButton();
+    disableSelection(false);
+  });
+
+  // System sleep: the service pauses the recording and resumes it on wake.
+  // The button keeps its stop action; the timer skips the time asleep
+  listen<RecordingState>("recording-state-changed", (event) => {
+    if (currentState !== "recording") return;
+    if (event.payload === "suspended") {
+      suspendedAt = Date.now();
+      setStatus("Recording paused while the system sleeps");
+    } else if (event.payload === "recording" && suspendedAt !== null) {
+      recordingStartTime += Date.now() - suspendedAt;
+      suspendedAt = null;
+      setStatus("Recording...");
+    }
+  });
+
+  // Pre-recording countdown (when enabled in settings); the record button
+  // cancels it until it reaches 0
+  listen<{ remaining: number }>("recording-countdown", (event) => {
+    countdownRemaining = event.payload.remaining;
+    if (countdownRemaining > 0) {
+      setStatus(`Recording starts in ${countdownRemaining}...`);
+    }
+    updateRecordButton();
+  });
+
+  // Cloud upload of the saved recording (when enabled in settings)
+  listen<{ percent: number; bytes_uploaded: number }>("cloud-upload-progress", (event) => {
+    cancelUploadBtn?.classList.remove("hidden");
+    setStatus(`Uploading... ${Math.floor(event.payload.percent)}%`);
+  });
+  listen<{ path: string; key: string | null; error: string | null }>(
+    "cloud-upload-finished",
+    (event) => {
+      cancelUploadBtn?.classList.add("hidden");
+      if (event.payload.error) {
+        setStatus(`Upload failed: ${event.payload.error}`, true);
+      } else {
+        setStatus(`Uploaded to ${event.payload.key}`);
+      }
+    }
+  );
+
+  // Monitors were added, removed or changed resolution: reconcile the display
+  // list and recapture every visible display thumbnail
+  listen<number>("monitor-config-changed", async (event) => {
+    console.log("[Displays] Monitor configuration changed, version", event.payload);
+    if (captureMode !== "display") return;
+    await reconcileDisplayList();
+    for (const id of visibleItems) {
+      if (id.startsWith("display:")) {
+        lastRefreshed.delete(id);
+        pendingCapture.add(id);
+      }
+    }
+    drainPendingCaptures().catch(err => console.error("Drain error:", err));
+  });
+
   // Page Visibility API - pause/resume all refresh when app window hides/shows
   document.addEventListener("visibilitychange", () => {
     appWindowVisible = !document.hidden;
@@ -479,7 +643,8 @@ function setViewMode(mode: ViewMode): void {
   // Clear window selection when switching away from window mode
   if (mode !== "window") {
     selectedWindow = null;
-    document.querySelectorAll(".window-item.selected").forEach((el) => {
+    selectedWorkspaceId = null;
+    document.querySelectorAll(".window-item.selected, .workspace-group.selected").forEach((el) => {
       el.classList.remove("selected");
     });
     stopWindowThumbnailRefresh();
@@ -553,6 +718,7 @@ function updateRegionDisplay(): void {
             <div class="region-overlay__monitor">${selectedRegion.monitor_name}</div>
           </div>
           ${!selectorActive ? '<button id="select-region-btn" type="button">Change Region</button>' : ''}
+          ${!selectorActive && lastRegionAvailable ? '<button id="last-region-btn" type="button">Reset to Last Region</button>' : ''}
         </div>
       </div>
     `;
@@ -560,6 +726,7 @@ function updateRegionDisplay(): void {
     // Re-attach event listener since we replaced the DOM
     if (!selectorActive) {
       document.querySelector("#select-region-btn")?.addEventListener("click", openRegionSelector);
+      document.querySelector("#last-region-btn")?.addEventListener("click", resetToLastRegion);
     }
     
     // Load region preview (throttled)
@@ -568,9 +735,11 @@ function updateRegionDisplay(): void {
     regionDisplayEl.classList.remove("has-selection");
     regionDisplayEl.classList.remove("selector-active");
     regionPreviewData = null;
-    regionDisplayEl.innerHTML = '<button id="select-region-btn" type="button">Select Region</button>';
-    // Re-attach event listener since we replaced the DOM
+    regionDisplayEl.innerHTML = '<button id="select-region-btn" type="button">Select Region</button>'
+      + (lastRegionAvailable ? '<button id="last-region-btn" type="button">Reset to Last Region</button>' : '');
+    // Re-attach event listeners since we replaced the DOM
     document.querySelector("#select-region-btn")?.addEventListener("click", openRegionSelector);
+    document.querySelector("#last-region-btn")?.addEventListener("click", resetToLastRegion);
   }
 }
 
@@ -666,14 +835,17 @@ async function openRegionSelector(): Promise<void> {
     let startY: number;
     let startWidth: number;
     let startHeight: number;
-    
-    if (storedSelectorGeometry) {
+
+    // Prefer this session's geometry, then the region of the last recording
+    const restoredGeometry = storedSelectorGeometry ?? await loadLastRegionGeometry(monitors);
+
+    if (restoredGeometry) {
       // Restore previous position and size (already in logical coordinates)
-      console.log("Restoring selector geometry (logical):", storedSelectorGeometry);
-      startX = storedSelectorGeometry.x;
-      startY = storedSelectorGeometry.y;
-      startWidth = storedSelectorGeometry.width;
-      startHeight = storedSelectorGeometry.height;
+      console.log("Restoring selector geometry (logical):", restoredGeometry);
+      startX = restoredGeometry.x;
+      startY = restoredGeometry.y;
+      startWidth = restoredGeometry.width;
+      startHeight = restoredGeometry.height;
     } else {
       console.log("No stored geometry, using defaults");
       // Default selection size and position (centered on primary monitor)
@@ -693,9 +865,11 @@ async function openRegionSelector(): Promise<void> {
 
     // Determine the URL based on environment
     const isDev = window.location.hostname === "localhost";
-    const overlayUrl = isDev
+    const aspectRatio = lockedAspectRatio();
+    const overlayQuery = aspectRatio ? `?aspect=${aspectRatio[0]}:${aspectRatio[1]}` : "";
+    const overlayUrl = (isDev
       ? "http://localhost:1420/src/selection-overlay.html"
-      : "src/selection-overlay.html";
+      : "src/selection-overlay.html") + overlayQuery;
 
 
 
@@ -741,13 +915,15 @@ async function openRegionSelector(): Promise<void> {
     
     // If we have stored geometry, move the window to that position (Hyprland only)
     // Wayland doesn't respect window position hints, so we use Hyprland IPC
-    if (storedSelectorGeometry) {
+    if (restoredGeometry) {
       try {
         await invoke("move_region_selector", {
-          x: storedSelectorGeometry.x,
-          y: storedSelectorGeometry.y,
-          width: storedSelectorGeometry.width,
-          height: storedSelectorGeometry.height,
+          x: restoredGeometry.x,
+          y: restoredGeometry.y,
+          width: restoredGeometry.width,
+          height: restoredGeometry.height,
+          lockAspectRatio: aspectRatio !== null,
+          aspectRatio: aspectRatio ?? [0, 0],
         });
       } catch (e) {
         console.warn("Failed to move region selector:", e);
@@ -782,6 +958,7 @@ async function loadWindows(): Promise<void> {
 
   windowListEl.innerHTML = '<p class="loading">Loading windows...</p>';
   selectedWindow = null;
+  selectedWorkspaceId = null;
   updateRecordButton();
 
   // Stop any existing refresh intervals
@@ -806,7 +983,7 @@ async function loadWindows(): Promise<void> {
     windowListEl.innerHTML = "";
     for (const win of windows) {
       const item = createWindowItem(win);
-      windowListEl.appendChild(item);
+      windowItemContainer(win).appendChild(item);
     }
 
     // Thumbnails are loaded by IntersectionObserver as items enter the viewport.
@@ -897,6 +1074,7 @@ async function reconcileWindowList(): Promise<void> {
           selectedWindow = null;
           updateRecordButton();
         }
+        removeEmptyWorkspaceGroups();
       } else {
         existingHandles.add(handle);
         // Update metadata in place (title, process name)
@@ -925,7 +1103,7 @@ async function reconcileWindowList(): Promise<void> {
     for (const win of windows) {
       if (!existingHandles.has(win.handle)) {
         const item = createWindowItem(win);
-        windowListEl.appendChild(item);
+        windowItemContainer(win).appendChild(item);
       }
     }
   } catch (error) {
@@ -1085,6 +1263,52 @@ function tickWindowThumbnails(): void {
 }
 
 // Create a window list item element
+// Parent element for a window item: its workspace group on Hyprland, otherwise the list itself
+function windowItemContainer(win: WindowInfo): HTMLElement {
+  if (win.workspace_id === undefined) return windowListEl!;
+
+  const selector = `.workspace-group[data-workspace-id="${win.workspace_id}"] .workspace-group__items`;
+  const existing = windowListEl!.querySelector<HTMLElement>(selector);
+  if (existing) return existing;
+
+  const group = createWorkspaceGroup(win.workspace_id, win.workspace_name || String(win.workspace_id));
+  windowListEl!.appendChild(group);
+  return group.querySelector<HTMLElement>(".workspace-group__items")!;
+}
+
+// Create a workspace group with a "Record whole workspace" option
+function createWorkspaceGroup(workspaceId: number, workspaceName: string): HTMLElement {
+  const group = document.createElement("div");
+  group.className = "workspace-group";
+  group.dataset.workspaceId = String(workspaceId);
+
+  group.innerHTML = `
+    <div class="workspace-group__header">
+      <span class="workspace-group__title">Workspace ${escapeHtml(workspaceName)}</span>
+      <button type="button" class="workspace-group__record">Record whole workspace</button>
+    </div>
+    <div class="workspace-group__items"></div>
+  `;
+
+  group.querySelector(".workspace-group__record")?.addEventListener("click", () => {
+    selectWorkspace(workspaceId, group);
+  });
+
+  return group;
+}
+
+// Remove workspace groups whose windows have all closed
+function removeEmptyWorkspaceGroups(): void {
+  windowListEl?.querySelectorAll<HTMLElement>(".workspace-group").forEach((group) => {
+    if (group.querySelector(".window-item")) return;
+    if (selectedWorkspaceId === Number(group.dataset.workspaceId)) {
+      selectedWorkspaceId = null;
+      updateRecordButton();
+    }
+    group.remove();
+  });
+}
+
 function createWindowItem(win: WindowInfo): HTMLElement {
   const item = document.createElement("div");
   item.className = "window-item";
@@ -1298,19 +1522,34 @@ function selectWindow(win: WindowInfo, element: HTMLElement): void {
   if (currentState !== "idle") return;
 
   // Remove selection from previous
-  document.querySelectorAll(".window-item.selected").forEach((el) => {
+  document.querySelectorAll(".window-item.selected, .workspace-group.selected").forEach((el) => {
     el.classList.remove("selected");
   });
 
   // Select new
   element.classList.add("selected");
   selectedWindow = win;
+  selectedWorkspaceId = null;
   updateRecordButton();
 
   // Show highlight overlay on the selected window
   showWindowHighlight(win);
 }
 
+// Select a whole Hyprland workspace for recording
+function selectWorkspace(workspaceId: number, group: HTMLElement): void {
+  if (currentState !== "idle") return;
+
+  document.querySelectorAll(".window-item.selected, .workspace-group.selected").forEach((el) => {
+    el.classList.remove("selected");
+  });
+
+  group.classList.add("selected");
+  selectedWindow = null;
+  selectedWorkspaceId = workspaceId;
+  updateRecordButton();
+}
+
 // Show a brief highlight border on a window to help identify it
 async function showWindowHighlight(win: WindowInfo): Promise<void> {
   try {
@@ -1324,7 +1563,9 @@ async function showWindowHighlight(win: WindowInfo): Promise<void> {
 
 // Handle record button click
 async function handleRecordClick(): Promise<void> {
-  if (currentState === "idle") {
+  if (countdownRemaining > 0) {
+    await invoke("cancel_countdown");
+  } else if (currentState === "idle") {
     await startRecording();
   } else if (currentState === "recording") {
     await stopRecording();
@@ -1333,7 +1574,7 @@ async function handleRecordClick(): Promise<void> {
 
 // Start recording
 async function startRecording(): Promise<void> {
-  if (captureMode === "window" && !selectedWindow) {
+  if (captureMode === "window" && !selectedWindow && selectedWorkspaceId === null) {
     setStatus("Please select a window first", true);
     return;
   }
@@ -1352,13 +1593,12 @@ async function startRecording(): Promise<void> {
   const transcriptionEnabled = transcriptionQuickCheckbox?.checked ?? false;
   if (transcriptionEnabled) {
     try {
-      const txConfig = await invoke<TranscriptionConfig>("get_transcription_config");
-      if (txConfig.model) {
-        const status = await invoke<ModelStatus>("get_model_status", { model: txConfig.model });
-        if (!status.exists) {
-          setStatus(`Transcription model "${status.display_name}" not downloaded. Please download it first or disable transcription.`, true);
-          return;
-        }
+      // Without a model, the status is of the model loaded for the
+      // configured language
+      const status = await invoke<ModelStatus>("get_model_status");
+      if (!status.exists) {
+        setStatus(`Transcription model "${status.display_name}" not downloaded. Please download it first or disable transcription.`, true);
+        return;
       }
     } catch (error) {
       console.error("[Recording] Failed to check model status:", error);
@@ -1370,7 +1610,9 @@ async function startRecording(): Promise<void> {
   disableSelection(true);
 
   try {
-    if (captureMode === "window" && selectedWindow) {
+    if (captureMode === "window" && selectedWorkspaceId !== null) {
+      await invoke("start_workspace_recording", { workspaceId: selectedWorkspaceId });
+    } else if (captureMode === "window" && selectedWindow) {
       await invoke("start_recording", { windowHandle: selectedWindow.handle });
     } else if (captureMode === "region" && selectedRegion) {
       console.log("Starting region recording with:", selectedRegion);
@@ -1386,7 +1628,9 @@ async function startRecording(): Promise<void> {
         y: Math.round(selectedRegion.y),
         width: Math.round(selectedRegion.width),
         height: Math.round(selectedRegion.height),
+        aspectRatio: lockedAspectRatio(),
       });
+      lastRegionAvailable = true;
     } else if (captureMode === "display" && selectedDisplay) {
       console.log("Starting display recording with:", selectedDisplay);
       await invoke("start_display_recording", {
@@ -1415,7 +1659,14 @@ async function startRecording(): Promise<void> {
       }
     }
   } catch (error) {
-    setStatus(`Failed to start recording: ${error}`, true);
+    if (error === "Countdown cancelled") {
+      setStatus("Recording cancelled");
+      if (regionSelectorWindow) {
+        await regionSelectorWindow.emit("recording-stopped");
+      }
+    } else {
+      setStatus(`Failed to start recording: ${error}`, true);
+    }
     disableSelection(false);
   }
 }
@@ -1432,7 +1683,11 @@ async function stopRecording(): Promise<void> {
 
     if (result.success && result.file_path) {
       showResult(result.file_path);
-      setStatus("Recording saved successfully!");
+      setStatus(
+        result.copied_to_clipboard
+          ? "Recording saved, path copied to clipboard"
+          : "Recording saved successfully!"
+      );
       
       // Close region selector after successful recording (preserves region state)
       if (captureMode === "region" && regionSelectorWindow) {
@@ -1458,6 +1713,29 @@ async function stopRecording(): Promise<void> {
   disableSelection(false);
 }
 
+// =============================================================================
+// Screenshots
+// =============================================================================
+
+// Save a screenshot of the selected window or display, or of the primary
+// display when neither is selected
+async function takeScreenshot(): Promise<void> {
+  let target = null;
+  if (captureMode === "window" && selectedWindow) {
+    target = { source: "window", window_handle: selectedWindow.handle };
+  } else if (captureMode === "display" && selectedDisplay) {
+    target = { source: "display", monitor_id: selectedDisplay.id };
+  }
+
+  try {
+    const path = await invoke<string>("take_screenshot", { target });
+    console.log("[Screenshot] Saved:", path);
+  } catch (error) {
+    console.error("[Screenshot] Failed:", error);
+    setStatus(`Screenshot failed: ${error}`, true);
+  }
+}
+
 // =============================================================================
 // GNOME Tray Recording Functions
 // =============================================================================
@@ -1552,9 +1830,14 @@ function updateRecordButton(): void {
 
   switch (currentState) {
     case "idle":
+      if (countdownRemaining > 0) {
+        recordBtn.textContent = `Cancel (${countdownRemaining})`;
+        recordBtn.disabled = false;
+        break;
+      }
       recordBtn.textContent = "Record";
       if (captureMode === "window") {
-        recordBtn.disabled = !selectedWindow;
+        recordBtn.disabled = !selectedWindow && selectedWorkspaceId === null;
       } else if (captureMode === "region") {
         recordBtn.disabled = !selectedRegion;
       } else if (captureMode === "display") {
@@ -1577,6 +1860,7 @@ function updateRecordButton(): void {
 // Timer functions
 function startTimer(): void {
   recordingStartTime = Date.now();
+  suspendedAt = null;
   updateTimerDisplay();
   timerInterval = window.setInterval(updateTimerDisplay, 1000);
   timerEl?.classList.add("recording");
@@ -1621,6 +1905,10 @@ function disableSelection(disabled: boolean): void {
     modeAboutBtn.disabled = disabled;
   }
 
+  document.querySelectorAll<HTMLButtonElement>(".workspace-group__record").forEach((btn) => {
+    btn.disabled = disabled;
+  });
+
   document.querySelectorAll(".window-item").forEach((el) => {
     if (disabled) {
       el.classList.add("disabled");
@@ -1699,6 +1987,40 @@ async function handleOpenFolder(): Promise<void> {
   }
 }
 
+// Handle open file button
+async function handleOpenFile(): Promise<void> {
+  const path = resultEl?.dataset.path;
+  if (!path) return;
+
+  try {
+    await invoke("open_recording_file", { path });
+  } catch (error) {
+    setStatus(`Failed to open recording: ${error}`, true);
+  }
+}
+
+// Handle cancel upload button
+async function handleCancelUpload(): Promise<void> {
+  try {
+    await invoke<boolean>("cancel_cloud_upload");
+  } catch (error) {
+    console.error("Failed to cancel upload:", error);
+  }
+}
+
+// Handle copy path button
+async function handleCopyPath(): Promise<void> {
+  const path = resultEl?.dataset.path;
+  if (!path) return;
+
+  try {
+    await invoke("copy_to_clipboard", { text: path });
+    setStatus("Path copied to clipboard");
+  } catch (error) {
+    setStatus(`Failed to copy path: ${error}`, true);
+  }
+}
+
 // Set status message - shows as overlay that auto-dismisses
 function setStatus(message: string, isError = false): void {
   // Log the message to console
@@ -1761,6 +2083,10 @@ async function loadConfig(): Promise<void> {
     // Load transcription state for quick toggle
     await loadTranscriptionQuickToggle();
 
+    // Offer "Reset to Last Region" if a region was recorded in a previous session
+    lastRegionAvailable = (await invoke<LastRegion | null>("get_last_region")) !== null;
+    updateRegionDisplay();
+
     console.log("[Config] Loaded config, theme:", themeMode);
   } catch (error) {
     console.error("[Config] Failed to load config:", error);
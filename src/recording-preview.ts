@@ -0,0 +1,24 @@
+import { listen } from "@tauri-apps/api/event";
+import { applyPlatformWindowClass } from "./lib/window";
+
+// Payload of the "preview-frame" event
+interface PreviewFrame {
+  data: string;
+  width: number;
+  height: number;
+}
+
+let previewImage: HTMLImageElement | null;
+
+window.addEventListener("DOMContentLoaded", async () => {
+  await applyPlatformWindowClass();
+
+  previewImage = document.querySelector("#preview-image");
+
+  // Frames arrive as base64 JPEG at the preview frame rate
+  await listen<PreviewFrame>("preview-frame", (event) => {
+    if (!previewImage) return;
+    previewImage.src = `data:image/jpeg;base64,${event.payload.data}`;
+    previewImage.classList.remove("hidden");
+  });
+});
@@ -1,4 +1,4 @@
-import { getCurrentWindow, Window } from "@tauri-apps/api/window";
+import { getCurrentWindow, PhysicalSize, Window } from "@tauri-apps/api/window";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 
@@ -33,6 +33,10 @@ let monitors: MonitorInfo[] = [];
 let currentWindow: Awaited<ReturnType<typeof getCurrentWindow>>;
 let emitTimeout: number | null = null;
 let isHyprland = false;
+// Aspect ratio (width, height) the recording area is locked to, if any
+let aspectRatio: [number, number] | null = parseAspectRatio(
+  new URLSearchParams(window.location.search).get("aspect"),
+);
 
 // DOM elements
 let dragAreaEl: HTMLElement;
@@ -104,9 +108,15 @@ window.addEventListener("DOMContentLoaded", async () => {
     e.preventDefault();
     await currentWindow.startDragging();
     // Update after drag completes
+    await snapToWindows();
     emitRegionUpdate();
   });
 
+  // The backend lined the selector up with a window
+  listen<{ snapped_to: number }>("region-snapped", (event) => {
+    console.log("Snapped to window", event.payload.snapped_to);
+  });
+
   // Close on Escape
   document.addEventListener("keydown", (e) => {
     if (e.key === "Escape") {
@@ -134,6 +144,13 @@ window.addEventListener("DOMContentLoaded", async () => {
     document.body.classList.remove("recording");
   });
 
+  // The main window changes the aspect ratio lock
+  listen<[number, number] | null>("region-aspect-ratio", (event) => {
+    console.log("Aspect ratio lock:", event.payload);
+    aspectRatio = event.payload;
+    emitRegionUpdate();
+  });
+
   // Initial update
   emitRegionUpdate();
 });
@@ -158,7 +175,9 @@ async function emitRegionUpdate(): Promise<void> {
   let windowHeight: number;
   
   try {
-    // Get position from Hyprland (returns physical pixels)
+    // Get position from Hyprland (returns physical pixels). On Windows the
+    // command returns logical pixels, so use Tauri's physical geometry there.
+    if (!isHyprland) throw new Error("Not on Hyprland");
     const [x, y, w, h] = await invoke<[number, number, number, number]>("get_region_selector_position");
     windowX = x;
     windowY = y;
@@ -166,7 +185,7 @@ async function emitRegionUpdate(): Promise<void> {
     windowHeight = h;
     console.log("Position from Hyprland:", windowX, windowY, windowWidth, "x", windowHeight);
   } catch (e) {
-    console.error("Failed to get position from Hyprland:", e);
+    if (isHyprland) console.error("Failed to get position from Hyprland:", e);
     // Fallback to Tauri (Windows/macOS)
     // Tauri returns PhysicalPosition/PhysicalSize - use physical coordinates directly
     // to match the monitor coordinate system (which is also in physical coordinates)
@@ -216,6 +235,11 @@ async function emitRegionUpdate(): Promise<void> {
   const recordY = windowY + physicalBorderOffset;
   const recordWidth = windowWidth - (physicalBorderOffset * 2);
   const recordHeight = windowHeight - (physicalBorderOffset * 2);
+
+  // Resizing to the locked ratio triggers another update
+  if (await applyAspectRatio(windowX, windowY, windowWidth, windowHeight, recordWidth, recordHeight)) {
+    return;
+  }
   
   console.log("=== REGION CALCULATION (physical coords) ===");
   console.log("Window position:", windowX, windowY);
@@ -271,6 +295,71 @@ async function emitRegionUpdate(): Promise<void> {
   }
 }
 
+// Let the backend line the selector up with nearby window edges. Only
+// Hyprland needs this: it is where the backend positions the selector.
+async function snapToWindows(): Promise<void> {
+  if (!isHyprland) return;
+  try {
+    const [x, y, width, height] = await invoke<[number, number, number, number]>("get_region_selector_position");
+    await invoke("move_region_selector", {
+      x,
+      y,
+      width,
+      height,
+      lockAspectRatio: aspectRatio !== null,
+      aspectRatio: aspectRatio ?? [0, 0],
+    });
+  } catch (e) {
+    console.warn("Failed to snap region selector:", e);
+  }
+}
+
+function parseAspectRatio(value: string | null): [number, number] | null {
+  const [width, height] = (value ?? "").split(":").map(Number);
+  return width > 0 && height > 0 ? [width, height] : null;
+}
+
+// Recording area size with the locked aspect ratio: the larger dimension is
+// kept and the smaller one derived from the ratio (as constrain_to_aspect)
+function constrainToAspect(width: number, height: number, ratio: [number, number]): [number, number] {
+  if (width >= height) {
+    return [width, Math.max(1, Math.round((width * ratio[1]) / ratio[0]))];
+  }
+  return [Math.max(1, Math.round((height * ratio[0]) / ratio[1])), height];
+}
+
+// Resize the selector so the recording area has the locked aspect ratio.
+// Returns true if the window was resized.
+async function applyAspectRatio(
+  windowX: number,
+  windowY: number,
+  windowWidth: number,
+  windowHeight: number,
+  recordWidth: number,
+  recordHeight: number,
+): Promise<boolean> {
+  if (!aspectRatio) return false;
+  const [width, height] = constrainToAspect(recordWidth, recordHeight, aspectRatio);
+  if (width === recordWidth && height === recordHeight) return false;
+
+  console.log("Constraining to aspect ratio", aspectRatio, ":", width, "x", height);
+  if (isHyprland) {
+    await invoke("move_region_selector", {
+      x: windowX,
+      y: windowY,
+      width: windowWidth,
+      height: windowHeight,
+      lockAspectRatio: true,
+      aspectRatio,
+    });
+  } else {
+    await currentWindow.setSize(
+      new PhysicalSize(windowWidth + width - recordWidth, windowHeight + height - recordHeight),
+    );
+  }
+  return true;
+}
+
 function findMonitorAt(x: number, y: number): MonitorInfo | null {
   for (const monitor of monitors) {
     if (
@@ -291,7 +380,8 @@ async function closeOverlay(): Promise<void> {
     // Send current geometry with the close event so main window can store it
     // WebviewWindow creation expects logical coordinates, so convert from physical
     try {
-      // Try Hyprland IPC first (works on Wayland), fallback to Tauri
+      // Try Hyprland IPC first (works on Wayland) or the monitor's logical
+      // coordinates (Windows per-monitor DPI), fallback to Tauri
       let x: number, y: number, width: number, height: number;
       try {
         const [hx, hy, hw, hh] = await invoke<[number, number, number, number]>("get_region_selector_position");
@@ -0,0 +1,5 @@
+const COMMANDS: &[&str] = &["is_connected"];
+
+fn main() {
+    tauri_plugin::Builder::new(COMMANDS).build();
+}
@@ -0,0 +1,65 @@
+// JavaScript bindings for tauri-plugin-omnirec-events.
+//
+// Usage:
+//   import { omnirec } from "tauri-plugin-omnirec-events-api";
+//   const unlisten = await omnirec.events.on("recording-started", () => { ... });
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+/** Prefix of the Tauri events emitted by the plugin. */
+const EVENT_PREFIX = "omnirec-events://";
+
+/** A recording started. */
+export interface RecordingStartedPayload {}
+
+/** The recording was stopped and saved. */
+export interface RecordingStoppedPayload {}
+
+/** The recording was paused because the system is going to sleep. */
+export interface RecordingSuspendedPayload {}
+
+export interface RecordingResumedPayload {
+  /** How long the system slept, in seconds */
+  gap_seconds: number;
+}
+
+/** Sent periodically while recording. */
+export interface StatsUpdatePayload {
+  elapsed_seconds: number;
+}
+
+export interface ErrorPayload {
+  message: string;
+}
+
+/** Events OmniRec sends, by name. */
+export interface OmniRecEventMap {
+  "recording-started": RecordingStartedPayload;
+  "recording-stopped": RecordingStoppedPayload;
+  "recording-suspended": RecordingSuspendedPayload;
+  "recording-resumed": RecordingResumedPayload;
+  "stats-update": StatsUpdatePayload;
+  error: ErrorPayload;
+}
+
+export type OmniRecEventName = keyof OmniRecEventMap;
+
+export const events = {
+  /** Call `callback` for each `event`; resolves to a function that stops listening. */
+  on<E extends OmniRecEventName>(
+    event: E,
+    callback: (payload: OmniRecEventMap[E]) => void,
+  ): Promise<UnlistenFn> {
+    return listen<OmniRecEventMap[E]>(`${EVENT_PREFIX}${event}`, (e) => callback(e.payload));
+  },
+};
+
+/** Whether the plugin is connected to OmniRec's event stream. */
+export function isConnected(): Promise<boolean> {
+  return invoke<boolean>("plugin:omnirec-events|is_connected");
+}
+
+export const omnirec = { events, isConnected };
+
+export default omnirec;
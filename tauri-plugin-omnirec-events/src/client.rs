@@ -0,0 +1,97 @@
+//! WebSocket client for the service's `GET /ws` event stream.
+//!
+//! Stays connected for the life of the app: when the connection can't be
+//! made or drops, it is retried with exponential backoff.
+
+use crate::models::OmniRecEvent;
+use crate::EVENT_PREFIX;
+use futures_util::StreamExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Delay before the first reconnect attempt.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest delay between reconnect attempts.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Where and how to connect.
+#[derive(Debug, Clone)]
+pub(crate) struct ClientOptions {
+    /// `ws://` URL of the event stream
+    pub url: String,
+    /// API bearer token
+    pub token: Option<String>,
+}
+
+/// Forward events to `app` until the app exits, reconnecting as needed.
+pub(crate) async fn run<R: Runtime>(
+    app: AppHandle<R>,
+    options: ClientOptions,
+    connected: Arc<AtomicBool>,
+) {
+    let mut delay = INITIAL_RETRY_DELAY;
+    loop {
+        match forward_events(&app, &options, &connected).await {
+            Ok(()) => {
+                tracing::info!("[OmniRecEvents] Event stream closed");
+                delay = INITIAL_RETRY_DELAY;
+            }
+            Err(e) => tracing::debug!("[OmniRecEvents] Event stream unavailable: {}", e),
+        }
+        connected.store(false, Ordering::Relaxed);
+
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RETRY_DELAY);
+    }
+}
+
+/// Connect once and forward events until the stream ends.
+async fn forward_events<R: Runtime>(
+    app: &AppHandle<R>,
+    options: &ClientOptions,
+    connected: &AtomicBool,
+) -> Result<(), String> {
+    let mut request = options
+        .url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| format!("Invalid URL '{}': {}", options.url, e))?;
+    if let Some(token) = &options.token {
+        let value = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|e| format!("Invalid API token: {}", e))?;
+        request.headers_mut().insert(AUTHORIZATION, value);
+    }
+
+    let (mut stream, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(|e| format!("Failed to connect to {}: {}", options.url, e))?;
+    tracing::info!("[OmniRecEvents] Connected to {}", options.url);
+    connected.store(true, Ordering::Relaxed);
+
+    while let Some(message) = stream.next().await {
+        let text = match message {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => break,
+            Ok(_) => continue,
+            Err(e) => return Err(format!("Event stream failed: {}", e)),
+        };
+        let event = match serde_json::from_str::<OmniRecEvent>(&text) {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!("[OmniRecEvents] Ignoring malformed event: {}", e);
+                continue;
+            }
+        };
+        if let Some(name) = event.name() {
+            let _ = app.emit(&format!("{}{}", EVENT_PREFIX, name), event.payload());
+        }
+    }
+    Ok(())
+}
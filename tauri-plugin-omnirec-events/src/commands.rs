@@ -0,0 +1,10 @@
+//! Plugin commands.
+
+use crate::OmniRecEvents;
+use tauri::State;
+
+/// Whether the plugin is connected to OmniRec's event stream.
+#[tauri::command]
+pub(crate) fn is_connected(state: State<'_, OmniRecEvents>) -> bool {
+    state.is_connected()
+}
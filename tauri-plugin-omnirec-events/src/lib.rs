@@ -0,0 +1,117 @@
+//! Tauri plugin delivering OmniRec recording events to other apps.
+//!
+//! Connects to the `GET /ws` event stream of an OmniRec started with
+//! `--listen` and re-emits each event to the app's windows as
+//! `omnirec-events://<name>` (e.g. `omnirec-events://recording-started`).
+//! The `tauri-plugin-omnirec-events-api` package wraps this as
+//! `omnirec.events.on('recording-started', callback)`.
+//!
+//! ```ignore
+//! tauri::Builder::default().plugin(
+//!     tauri_plugin_omnirec_events::Builder::new()
+//!         .url("ws://127.0.0.1:7890/ws")
+//!         .build(),
+//! )
+//! ```
+//!
+//! The URL and token can also be set under `plugins.omnirec-events` in
+//! `tauri.conf.json`. Without a token, `OMNIREC_API_TOKEN` is used.
+
+mod client;
+mod commands;
+mod models;
+
+pub use models::{
+    ErrorPayload, OmniRecEvent, RecordingResumedPayload, RecordingStartedPayload,
+    RecordingStoppedPayload, RecordingSuspendedPayload, StatsUpdatePayload,
+};
+
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::plugin::{Builder as PluginBuilder, TauriPlugin};
+use tauri::{Manager, Runtime};
+
+/// Prefix of the Tauri events the plugin emits.
+pub const EVENT_PREFIX: &str = "omnirec-events://";
+
+/// Environment variable holding the API bearer token, as for OmniRec itself.
+pub const API_TOKEN_ENV: &str = "OMNIREC_API_TOKEN";
+
+/// Settings under `plugins.omnirec-events` in `tauri.conf.json`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Config {
+    /// `ws://` URL of OmniRec's event stream, e.g. `ws://127.0.0.1:7890/ws`
+    pub url: Option<String>,
+    /// API bearer token
+    pub token: Option<String>,
+}
+
+/// Connection state, managed by the app.
+#[derive(Debug, Default)]
+pub struct OmniRecEvents {
+    connected: Arc<AtomicBool>,
+}
+
+impl OmniRecEvents {
+    /// Whether the event stream is connected.
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+}
+
+/// Builder for the plugin. Settings given here override `tauri.conf.json`.
+#[derive(Debug, Default)]
+pub struct Builder {
+    url: Option<String>,
+    token: Option<String>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `ws://` URL of OmniRec's event stream.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// API bearer token.
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R, Option<Config>> {
+        PluginBuilder::<R, Option<Config>>::new("omnirec-events")
+            .invoke_handler(tauri::generate_handler![commands::is_connected])
+            .setup(move |app, api| {
+                let config = api.config().clone().unwrap_or_default();
+                let state = OmniRecEvents::default();
+                let connected = state.connected.clone();
+                app.manage(state);
+
+                let Some(url) = self.url.or(config.url) else {
+                    tracing::warn!("[OmniRecEvents] No event stream URL configured");
+                    return Ok(());
+                };
+                let token = self
+                    .token
+                    .or(config.token)
+                    .or_else(|| std::env::var(API_TOKEN_ENV).ok())
+                    .filter(|token| !token.is_empty());
+
+                let options = client::ClientOptions { url, token };
+                tauri::async_runtime::spawn(client::run(app.clone(), options, connected));
+                Ok(())
+            })
+            .build()
+    }
+}
+
+/// The plugin, configured from `tauri.conf.json`.
+pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
+    Builder::new().build()
+}
@@ -0,0 +1,126 @@
+//! Events received from OmniRec's `GET /ws` stream.
+//!
+//! These mirror the service's `ServerEvent` JSON messages, e.g.
+//! `{"type":"stats_update","elapsed_seconds":12}`.
+
+use serde::{Deserialize, Serialize};
+
+/// Payload of `recording-started`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct RecordingStartedPayload {}
+
+/// Payload of `recording-stopped`: the recording was stopped and saved.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct RecordingStoppedPayload {}
+
+/// Payload of `recording-suspended`: the system is going to sleep.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct RecordingSuspendedPayload {}
+
+/// Payload of `recording-resumed`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingResumedPayload {
+    /// How long the system slept, in seconds
+    pub gap_seconds: f64,
+}
+
+/// Payload of `stats-update`, sent periodically while recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsUpdatePayload {
+    pub elapsed_seconds: u64,
+}
+
+/// Payload of `error`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorPayload {
+    pub message: String,
+}
+
+/// An event from OmniRec.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OmniRecEvent {
+    RecordingStarted(RecordingStartedPayload),
+    RecordingStopped(RecordingStoppedPayload),
+    RecordingSuspended(RecordingSuspendedPayload),
+    RecordingResumed(RecordingResumedPayload),
+    StatsUpdate(StatsUpdatePayload),
+    Error(ErrorPayload),
+    /// An event added in a newer OmniRec
+    #[serde(other)]
+    Unknown,
+}
+
+impl OmniRecEvent {
+    /// Name the event is delivered under in JavaScript, e.g.
+    /// `recording-started`; `None` for unknown events.
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self {
+            OmniRecEvent::RecordingStarted(_) => "recording-started",
+            OmniRecEvent::RecordingStopped(_) => "recording-stopped",
+            OmniRecEvent::RecordingSuspended(_) => "recording-suspended",
+            OmniRecEvent::RecordingResumed(_) => "recording-resumed",
+            OmniRecEvent::StatsUpdate(_) => "stats-update",
+            OmniRecEvent::Error(_) => "error",
+            OmniRecEvent::Unknown => return None,
+        })
+    }
+
+    /// The event's payload as JSON.
+    pub fn payload(&self) -> serde_json::Value {
+        let payload = match self {
+            OmniRecEvent::RecordingStarted(p) => serde_json::to_value(p),
+            OmniRecEvent::RecordingStopped(p) => serde_json::to_value(p),
+            OmniRecEvent::RecordingSuspended(p) => serde_json::to_value(p),
+            OmniRecEvent::RecordingResumed(p) => serde_json::to_value(p),
+            OmniRecEvent::StatsUpdate(p) => serde_json::to_value(p),
+            OmniRecEvent::Error(p) => serde_json::to_value(p),
+            OmniRecEvent::Unknown => Ok(serde_json::Value::Null),
+        };
+        payload.unwrap_or(serde_json::Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> OmniRecEvent {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_parse_server_events() {
+        assert_eq!(
+            parse(r#"{"type":"recording_started"}"#),
+            OmniRecEvent::RecordingStarted(RecordingStartedPayload {})
+        );
+        assert_eq!(
+            parse(r#"{"type":"stats_update","elapsed_seconds":12}"#),
+            OmniRecEvent::StatsUpdate(StatsUpdatePayload {
+                elapsed_seconds: 12
+            })
+        );
+        assert_eq!(
+            parse(r#"{"type":"recording_resumed","gap_seconds":42.0}"#).payload(),
+            serde_json::json!({ "gap_seconds": 42.0 })
+        );
+        assert_eq!(parse(r#"{"type":"something_new"}"#), OmniRecEvent::Unknown);
+    }
+
+    #[test]
+    fn test_event_names() {
+        assert_eq!(
+            parse(r#"{"type":"recording_stopped"}"#).name(),
+            Some("recording-stopped")
+        );
+        assert_eq!(
+            parse(r#"{"type":"error","message":"Transcoding failed"}"#).name(),
+            Some("error")
+        );
+        assert_eq!(OmniRecEvent::Unknown.name(), None);
+    }
+}
@@ -20,6 +20,7 @@ export default defineConfig(async () => ({
         about: resolve(__dirname, "src/about.html"),
         config: resolve(__dirname, "src/config.html"),
         "transcript-view": resolve(__dirname, "src/transcript-view.html"),
+        "recording-preview": resolve(__dirname, "src/recording-preview.html"),
       },
     },
   },